@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use compiler__file_system::{FileSystem, RealFileSystem};
+use compiler__reports::{
+    CompilerFailure, CompilerFailureKind, DiagnosticPhase, RenderedDiagnostic,
+};
+use compiler__source::{compare_paths, path_to_key};
+
+use crate::{analyze_target_with_workspace_root, sort_file_diagnostics};
+
+const FINGERPRINT_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FINGERPRINT_PRIME: u64 = 0x0100_0000_01b3;
+
+// Diagnostics checked in as known, named by the workspace's `lint.baseline`
+// setting. Analysis leaves them out of the diagnostics it reports, so that a
+// workspace can adopt a new rule and fix what it already reports over time.
+// The phase stands in for a diagnostic code until diagnostics have codes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticBaseline {
+    pub diagnostics: Vec<DiagnosticBaselineEntry>,
+}
+
+// The fingerprint hashes the workspace-relative path, message, and spanned
+// source text, so an entry keeps matching when edits move its diagnostic. The
+// message is repeated for whoever reviews the file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticBaselineEntry {
+    pub phase: DiagnosticPhase,
+    pub path: String,
+    pub fingerprint: String,
+    pub message: String,
+}
+
+// Rewrites the workspace's baseline to list every diagnostic the workspace
+// currently has, whichever package of it `path` names, and returns how many
+// it lists.
+pub fn regenerate_diagnostic_baseline(
+    path: &str,
+    workspace_root_override: Option<&str>,
+) -> Result<usize, CompilerFailure> {
+    let mut analyzed_target = analyze_target_with_workspace_root(path, workspace_root_override)?;
+    if analyzed_target.absolute_target_path != analyzed_target.workspace_root {
+        let workspace_root = analyzed_target.workspace_root.display().to_string();
+        analyzed_target =
+            analyze_target_with_workspace_root(&workspace_root, Some(&workspace_root))?;
+    }
+    let Some(baseline_path) = &analyzed_target.workspace.settings().diagnostic_baseline else {
+        return Err(CompilerFailure {
+            kind: CompilerFailureKind::DiagnosticBaselineNotConfigured,
+            message: "workspace does not set 'lint.baseline'".to_string(),
+            path: Some(path.to_string()),
+            details: Vec::new(),
+        });
+    };
+
+    let mut diagnostics_by_file = analyzed_target.all_diagnostics_by_file.clone();
+    for (file_path, baselined_diagnostics) in &analyzed_target.baselined_diagnostics_by_file {
+        diagnostics_by_file
+            .entry(file_path.clone())
+            .or_default()
+            .extend(baselined_diagnostics.iter().cloned());
+    }
+    let mut diagnostics_by_file = diagnostics_by_file.into_iter().collect::<Vec<_>>();
+    diagnostics_by_file.sort_by(|(left, _), (right, _)| compare_paths(left, right));
+    let mut baseline = DiagnosticBaseline::default();
+    for (file_path, mut diagnostics) in diagnostics_by_file {
+        sort_file_diagnostics(&mut diagnostics);
+        let source_text = source_text_for(&analyzed_target.source_by_path, &diagnostics);
+        baseline.diagnostics.extend(
+            diagnostics
+                .iter()
+                .map(|diagnostic| baseline_entry(&file_path, source_text, diagnostic)),
+        );
+    }
+
+    let absolute_baseline_path = analyzed_target.workspace_root.join(baseline_path);
+    let mut contents =
+        serde_json::to_string_pretty(&baseline).expect("diagnostic baseline should serialize");
+    contents.push('\n');
+    absolute_baseline_path
+        .parent()
+        .map_or(Ok(()), |parent| RealFileSystem.create_directory_all(parent))
+        .and_then(|()| RealFileSystem.write(&absolute_baseline_path, contents.as_bytes()))
+        .map_err(|error| CompilerFailure {
+            kind: CompilerFailureKind::WriteSource,
+            message: error.to_string(),
+            path: Some(path_to_key(&absolute_baseline_path)),
+            details: Vec::new(),
+        })?;
+    Ok(baseline.diagnostics.len())
+}
+
+// A baseline that does not exist yet lists no diagnostics, so that the
+// setting can be added before the baseline is first generated.
+pub(crate) fn read_diagnostic_baseline(
+    file_system: &dyn FileSystem,
+    workspace_root: &Path,
+    baseline_path: &Path,
+) -> Result<DiagnosticBaseline, CompilerFailure> {
+    let absolute_baseline_path = workspace_root.join(baseline_path);
+    let read_failure = |message: String| CompilerFailure {
+        kind: CompilerFailureKind::ReadSource,
+        message,
+        path: Some(path_to_key(baseline_path)),
+        details: Vec::new(),
+    };
+    match file_system.read_to_string(&absolute_baseline_path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|error| read_failure(format!("invalid diagnostic baseline: {error}"))),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(DiagnosticBaseline::default()),
+        Err(error) => Err(read_failure(error.to_string())),
+    }
+}
+
+// Moves the diagnostics the baseline lists out of every file's diagnostics
+// and the reported diagnostics, and returns them by file. An entry matches
+// one diagnostic, so a file that reports a listed diagnostic more often than
+// the baseline lists it still reports the surplus.
+pub(crate) fn apply_diagnostic_baseline(
+    baseline: &DiagnosticBaseline,
+    source_by_path: &BTreeMap<String, String>,
+    all_diagnostics_by_file: &mut BTreeMap<PathBuf, Vec<RenderedDiagnostic>>,
+    rendered_diagnostics: &mut Vec<RenderedDiagnostic>,
+) -> BTreeMap<PathBuf, Vec<RenderedDiagnostic>> {
+    let mut unmatched_count_by_key = BTreeMap::<(DiagnosticPhase, String), usize>::new();
+    for entry in &baseline.diagnostics {
+        *unmatched_count_by_key
+            .entry((entry.phase, entry.fingerprint.clone()))
+            .or_default() += 1;
+    }
+    let mut baselined_diagnostics_by_file = BTreeMap::new();
+    let mut baselined_count_by_identity = BTreeMap::<DiagnosticIdentity, usize>::new();
+    for (file_path, diagnostics) in all_diagnostics_by_file.iter_mut() {
+        let source_text = source_text_for(source_by_path, diagnostics);
+        let (baselined_diagnostics, remaining_diagnostics) = std::mem::take(diagnostics)
+            .into_iter()
+            .partition::<Vec<_>, _>(|diagnostic| {
+                let key = (
+                    diagnostic.phase,
+                    diagnostic_fingerprint(file_path, source_text, diagnostic),
+                );
+                match unmatched_count_by_key.get_mut(&key) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        true
+                    }
+                    _ => false,
+                }
+            });
+        *diagnostics = remaining_diagnostics;
+        if baselined_diagnostics.is_empty() {
+            continue;
+        }
+        for diagnostic in &baselined_diagnostics {
+            *baselined_count_by_identity
+                .entry(diagnostic_identity(diagnostic))
+                .or_default() += 1;
+        }
+        baselined_diagnostics_by_file.insert(file_path.clone(), baselined_diagnostics);
+    }
+    all_diagnostics_by_file.retain(|_, diagnostics| !diagnostics.is_empty());
+    // Reported diagnostics are copies of diagnostics of their files.
+    rendered_diagnostics.retain(|diagnostic| {
+        match baselined_count_by_identity.get_mut(&diagnostic_identity(diagnostic)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        }
+    });
+    baselined_diagnostics_by_file
+}
+
+type DiagnosticIdentity = (DiagnosticPhase, String, String, usize, usize);
+
+fn diagnostic_identity(diagnostic: &RenderedDiagnostic) -> DiagnosticIdentity {
+    (
+        diagnostic.phase,
+        diagnostic.path.clone(),
+        diagnostic.message.clone(),
+        diagnostic.span.start,
+        diagnostic.span.end,
+    )
+}
+
+fn baseline_entry(
+    file_path: &Path,
+    source_text: &str,
+    diagnostic: &RenderedDiagnostic,
+) -> DiagnosticBaselineEntry {
+    DiagnosticBaselineEntry {
+        phase: diagnostic.phase,
+        path: path_to_key(file_path),
+        fingerprint: diagnostic_fingerprint(file_path, source_text, diagnostic),
+        message: diagnostic.message.clone(),
+    }
+}
+
+// FNV-1a over fields separated by a byte that text never contains.
+fn diagnostic_fingerprint(
+    file_path: &Path,
+    source_text: &str,
+    diagnostic: &RenderedDiagnostic,
+) -> String {
+    let spanned_text = source_text
+        .get(diagnostic.span.start..diagnostic.span.end)
+        .unwrap_or_default();
+    let mut hash = FINGERPRINT_OFFSET_BASIS;
+    for field in [
+        path_to_key(file_path).as_str(),
+        &diagnostic.message,
+        spanned_text,
+    ] {
+        for byte in field.bytes().chain([0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FINGERPRINT_PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+// Every diagnostic of a file is rendered with the same path.
+fn source_text_for<'a>(
+    source_by_path: &'a BTreeMap<String, String>,
+    file_diagnostics: &[RenderedDiagnostic],
+) -> &'a str {
+    file_diagnostics
+        .first()
+        .and_then(|diagnostic| source_by_path.get(&diagnostic.path))
+        .map_or("", String::as_str)
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use compiler__cancellation::CancellationToken;
+use compiler__reports::{CompilerFailure, DiagnosticPhase, RenderedDiagnostic};
+
+use crate::{
+    AnalysisOptions, AnalyzedTarget, QueryDatabase,
+    analyze_target_with_workspace_root_and_overrides,
+};
+
+// The diagnostics a change introduces and resolves relative to its baseline,
+// each in the order its analysis reported them.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticChanges {
+    pub introduced_diagnostics: Vec<RenderedDiagnostic>,
+    pub resolved_diagnostics: Vec<RenderedDiagnostic>,
+}
+
+// Edits move the diagnostics that follow them, so diagnostics are matched by
+// their phase, path, message, and the source text they span rather than by
+// position. Diagnostics that match more than once pair up in reported order,
+// and only the surplus on either side counts as introduced or resolved.
+#[must_use]
+pub fn diff_analyzed_diagnostics(
+    baseline: &AnalyzedTarget,
+    changed: &AnalyzedTarget,
+) -> DiagnosticChanges {
+    DiagnosticChanges {
+        introduced_diagnostics: unmatched_diagnostics(changed, baseline),
+        resolved_diagnostics: unmatched_diagnostics(baseline, changed),
+    }
+}
+
+// Analyzes the target as it is on disk and again with the source overrides
+// applied, sharing work between the two analyses, and reports the
+// diagnostics that the overrides introduce and resolve.
+pub fn analyze_target_diagnostic_changes(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    source_override_by_workspace_relative_path: &BTreeMap<String, String>,
+    analysis_options: &AnalysisOptions,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<DiagnosticChanges, CompilerFailure> {
+    let mut query_database = QueryDatabase::new();
+    let baseline = analyze_target_with_workspace_root_and_overrides(
+        path,
+        workspace_root_override,
+        &BTreeMap::new(),
+        analysis_options,
+        None,
+        cancellation_token,
+        &mut query_database,
+    )?;
+    let changed = analyze_target_with_workspace_root_and_overrides(
+        path,
+        workspace_root_override,
+        source_override_by_workspace_relative_path,
+        analysis_options,
+        None,
+        cancellation_token,
+        &mut query_database,
+    )?;
+    Ok(diff_analyzed_diagnostics(&baseline, &changed))
+}
+
+fn unmatched_diagnostics(
+    analyzed_target: &AnalyzedTarget,
+    other_analyzed_target: &AnalyzedTarget,
+) -> Vec<RenderedDiagnostic> {
+    let mut unmatched_count_by_key = BTreeMap::<DiagnosticKey<'_>, usize>::new();
+    for diagnostic in &other_analyzed_target.diagnostics {
+        *unmatched_count_by_key
+            .entry(diagnostic_key(other_analyzed_target, diagnostic))
+            .or_default() += 1;
+    }
+    analyzed_target
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            match unmatched_count_by_key.get_mut(&diagnostic_key(analyzed_target, diagnostic)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+type DiagnosticKey<'a> = (DiagnosticPhase, &'a str, &'a str, &'a str);
+
+fn diagnostic_key<'a>(
+    analyzed_target: &'a AnalyzedTarget,
+    diagnostic: &'a RenderedDiagnostic,
+) -> DiagnosticKey<'a> {
+    let spanned_text = analyzed_target
+        .source_by_path
+        .get(&diagnostic.path)
+        .and_then(|source| source.get(diagnostic.span.start..diagnostic.span.end))
+        .unwrap_or_default();
+    (
+        diagnostic.phase,
+        &diagnostic.path,
+        &diagnostic.message,
+        spanned_text,
+    )
+}
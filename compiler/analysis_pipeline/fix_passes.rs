@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use compiler__file_system::{FileSystem, RealFileSystem};
+use compiler__reports::{CompilerFailure, CompilerFailureDetail, CompilerFailureKind};
+use compiler__source::path_to_key;
+use compiler__source_formatting::FormattingVerificationError;
+
+use crate::{
+    AnalysisOptions, QueryDatabase, SkippedSafeAutofix,
+    analyze_target_with_workspace_root_and_overrides,
+};
+
+// What `fix_target` applied. Edit counts are summed over the passes that
+// changed each file, and the fixpoint is reached when a pass finds nothing
+// left to fix before the pass limit runs out. Skipped fixes are retried by the
+// next pass, so only those of the last pass are left unapplied, as are the
+// files whose formatting failed verification in the last pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixSummary {
+    pub applied_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub skipped_safe_autofixes_by_workspace_relative_path:
+        BTreeMap<String, Vec<SkippedSafeAutofix>>,
+    pub unverified_formatting_by_workspace_relative_path:
+        BTreeMap<String, FormattingVerificationError>,
+    pub pass_count: usize,
+    pub reached_fixpoint: bool,
+}
+
+pub fn fix_target(path: &str, max_iterations: usize) -> Result<FixSummary, CompilerFailure> {
+    fix_target_with_workspace_root(path, None, max_iterations)
+}
+
+// Applying safe autofixes can unlock more of them, as when one fix was dropped
+// for overlapping another or a rename no longer collides, so each pass
+// analyzes the sources the previous passes fixed. Files are only written once
+// the passes finish, so a failed pass writes nothing. Writes are not atomic
+// across files, so a failed write lists the files already written.
+pub fn fix_target_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    max_iterations: usize,
+) -> Result<FixSummary, CompilerFailure> {
+    let mut query_database = QueryDatabase::new();
+    let mut source_override_by_workspace_relative_path = BTreeMap::new();
+    let mut summary = FixSummary::default();
+    let mut workspace_root = None;
+    while summary.pass_count < max_iterations {
+        let analyzed_target = analyze_target_with_workspace_root_and_overrides(
+            path,
+            workspace_root_override,
+            &source_override_by_workspace_relative_path,
+            &AnalysisOptions::default(),
+            None,
+            None,
+            &mut query_database,
+        )?;
+        workspace_root = Some(analyzed_target.workspace_root);
+        summary.skipped_safe_autofixes_by_workspace_relative_path =
+            analyzed_target.skipped_safe_autofixes_by_workspace_relative_path;
+        summary.unverified_formatting_by_workspace_relative_path =
+            analyzed_target.unverified_formatting_by_workspace_relative_path;
+        if analyzed_target
+            .canonical_source_override_by_workspace_relative_path
+            .is_empty()
+        {
+            summary.reached_fixpoint = true;
+            break;
+        }
+        summary.pass_count += 1;
+        for (workspace_relative_path, edit_count) in
+            analyzed_target.safe_autofix_edit_count_by_workspace_relative_path
+        {
+            *summary
+                .applied_edit_count_by_workspace_relative_path
+                .entry(workspace_relative_path)
+                .or_default() += edit_count;
+        }
+        source_override_by_workspace_relative_path
+            .extend(analyzed_target.canonical_source_override_by_workspace_relative_path);
+    }
+
+    if let Some(workspace_root) = workspace_root {
+        let mut written_workspace_relative_paths: Vec<&str> = Vec::new();
+        for (workspace_relative_path, source_text) in &source_override_by_workspace_relative_path {
+            let absolute_path = workspace_root.join(workspace_relative_path);
+            RealFileSystem
+                .write(&absolute_path, source_text.as_bytes())
+                .map_err(|error| CompilerFailure {
+                    kind: CompilerFailureKind::WriteSource,
+                    message: error.to_string(),
+                    path: Some(path_to_key(&absolute_path)),
+                    details: written_workspace_relative_paths
+                        .iter()
+                        .map(|written_workspace_relative_path| CompilerFailureDetail {
+                            message: "fixes were already written to this file".to_string(),
+                            path: Some(written_workspace_relative_path.to_string()),
+                        })
+                        .collect(),
+                })?;
+            written_workspace_relative_paths.push(workspace_relative_path);
+        }
+    }
+    Ok(summary)
+}
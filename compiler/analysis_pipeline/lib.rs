@@ -1,47 +1,147 @@
+mod diagnostic_baseline;
+mod diagnostic_changes;
+mod fix_passes;
+mod query_database;
+
 use std::collections::{BTreeMap, BTreeSet};
-use std::fs;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
-use compiler__diagnostics::{FileScopedDiagnostic, PhaseDiagnostic};
+use compiler__cancellation::CancellationToken;
+use compiler__diagnostics::{DiagnosticSeverity, FileScopedDiagnostic, PhaseDiagnostic};
 use compiler__file_role_rules as file_role_rules;
-use compiler__fix_edits::{TextEdit, apply_text_edits, merge_text_edits};
+use compiler__file_system::{FileSystem, FileSystemEntryKind, OverlayFileSystem, RealFileSystem};
+use compiler__fix_edits::{TextEditGroup, apply_text_edits, merge_text_edit_groups};
+use compiler__maintainability_rules as maintainability_rules;
+use compiler__package_graph::PackageDependencyRule;
 use compiler__package_symbols::{
     PackageSymbolFileInput, ResolvedImportBindingSummary, ResolvedImportSummary,
     build_typed_public_symbol_table,
 };
 use compiler__packages::PackageId;
-use compiler__parsing::parse_file;
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
+use compiler__refactorings::{Refactoring, inline_refactorings};
 use compiler__reports::{
     CompilerFailure, CompilerFailureDetail, CompilerFailureKind, DiagnosticPhase,
-    RenderedDiagnostic,
+    RenderedDiagnostic, RenderedDiagnosticSeverity, RenderedRelatedSpan, RenderedSpan,
+    RenderedSuggestedFix, RenderedTextEdit,
 };
 use compiler__resolution as resolution;
 use compiler__safe_autofix::SafeAutofix;
-use compiler__semantic_lowering::lower_parsed_file;
 use compiler__semantic_program::SemanticFile;
-use compiler__source::{FileRole, compare_paths, path_to_key};
-use compiler__source_formatting::formatting_text_edits;
+use compiler__semantic_types::{ForbiddenBuiltin, HostFunctionContract};
+use compiler__source::{FileRole, LanguageVersion, LineIndex, compare_paths, path_to_key};
+use compiler__source_formatting::{FormattingVerificationError, verified_formatting_text_edits};
+use compiler__syntax::{SyntaxDeclaration, SyntaxPackageFieldValue, SyntaxParsedFile};
 use compiler__syntax_rules as syntax_rules;
-use compiler__type_analysis as type_analysis;
-use compiler__type_annotated_program::TypeResolvedDeclarations;
+use compiler__type_annotated_program::{
+    TypeAnnotatedDeclarationSignatures, TypeResolvedDeclarations,
+};
 use compiler__visibility::ResolvedImport;
-use compiler__workspace::{Workspace, discover_workspace};
+use compiler__workspace::{
+    DiscoveredPackage, PackageMetadata, Workspace, WorkspaceSettings, discover_workspace,
+};
+
+pub use diagnostic_baseline::{
+    DiagnosticBaseline, DiagnosticBaselineEntry, regenerate_diagnostic_baseline,
+};
+pub use diagnostic_changes::{
+    DiagnosticChanges, analyze_target_diagnostic_changes, diff_analyzed_diagnostics,
+};
+pub use fix_passes::{FixSummary, fix_target, fix_target_with_workspace_root};
+pub use query_database::QueryDatabase;
+
+use diagnostic_baseline::{apply_diagnostic_baseline, read_diagnostic_baseline};
 
 const WORKSPACE_MARKER_FILENAME: &str = "COPPICE_WORKSPACE";
+const DEFAULT_SIMILAR_DIAGNOSTIC_LIMIT_PER_FILE: NonZeroUsize = NonZeroUsize::new(3).unwrap();
+const DEFAULT_DIAGNOSTIC_LIMIT_PER_FILE: NonZeroUsize = NonZeroUsize::new(50).unwrap();
+
+// A diagnostic limit of None reports every diagnostic. Host function contracts
+// let programs analyzed for an embedding host call the functions it provides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnalysisOptions {
+    pub similar_diagnostic_limit_per_file: Option<NonZeroUsize>,
+    pub diagnostic_limit_per_file: Option<NonZeroUsize>,
+    pub host_function_contracts: Vec<HostFunctionContract>,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            similar_diagnostic_limit_per_file: Some(DEFAULT_SIMILAR_DIAGNOSTIC_LIMIT_PER_FILE),
+            diagnostic_limit_per_file: Some(DEFAULT_DIAGNOSTIC_LIMIT_PER_FILE),
+            host_function_contracts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalysisPhase {
+    Parsing,
+    SyntaxRules,
+    Resolution,
+    SemanticLowering,
+    TypeAnalysis,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnalysisProgress {
+    pub phase: AnalysisPhase,
+    pub completed_file_count: usize,
+    pub file_count: usize,
+    pub completed_package_count: usize,
+    pub package_count: usize,
+}
+
+// Every phase reports once before each file and once at its end, so observers
+// that redraw should throttle on their own. Files skipped because an earlier
+// phase failed still count as completed, and every phase ends at its totals.
+pub trait ProgressObserver {
+    fn analysis_progressed(&self, progress: AnalysisProgress);
+}
+
+// Identifies a safe autofix until diagnostics have codes: the phase that
+// emitted it and the rule within that phase that proposed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SafeAutofixProvenance {
+    pub phase: DiagnosticPhase,
+    pub rule: &'static str,
+}
+
+// A safe autofix that was not applied, either because its edits overlap those
+// of a fix that was or because they would drop a comment or move the code it
+// is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SkippedSafeAutofix {
+    pub provenance: SafeAutofixProvenance,
+    pub reason: SafeAutofixSkipReason,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SafeAutofixSkipReason {
+    ConflictsWith(SafeAutofixProvenance),
+    DisturbsComment,
+}
 
 pub struct AnalyzedTargetSummary {
     pub diagnostics: Vec<RenderedDiagnostic>,
     pub source_by_path: BTreeMap<String, String>,
     pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub refactorings_by_path: BTreeMap<String, Vec<Refactoring>>,
 }
 
 pub struct AnalyzedTarget {
     pub diagnostics: Vec<RenderedDiagnostic>,
     pub all_diagnostics_by_file: BTreeMap<PathBuf, Vec<RenderedDiagnostic>>,
+    pub baselined_diagnostics_by_file: BTreeMap<PathBuf, Vec<RenderedDiagnostic>>,
     pub source_by_path: BTreeMap<String, String>,
     pub source_by_workspace_relative_path_in_scope: BTreeMap<String, String>,
     pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub skipped_safe_autofixes_by_workspace_relative_path:
+        BTreeMap<String, Vec<SkippedSafeAutofix>>,
+    pub unverified_formatting_by_workspace_relative_path:
+        BTreeMap<String, FormattingVerificationError>,
     pub canonical_source_override_by_workspace_relative_path: BTreeMap<String, String>,
     pub workspace_root: PathBuf,
     pub workspace: Workspace,
@@ -51,13 +151,15 @@ pub struct AnalyzedTarget {
     pub file_role_by_path: BTreeMap<PathBuf, FileRole>,
     pub resolved_imports: Vec<ResolvedImport>,
     pub resolved_declarations_by_path: BTreeMap<PathBuf, TypeResolvedDeclarations>,
+    pub declaration_signatures_by_path: BTreeMap<PathBuf, TypeAnnotatedDeclarationSignatures>,
+    pub refactorings_by_path: BTreeMap<String, Vec<Refactoring>>,
 }
 
 struct ParsedUnit {
     package_id: PackageId,
     package_path: String,
     path: PathBuf,
-    parsed: compiler__syntax::SyntaxParsedFile,
+    parsed: SyntaxParsedFile,
     phase_state: FilePhaseState,
 }
 
@@ -69,6 +171,68 @@ struct FilePhaseState {
     semantic_lowering: PhaseStatus,
 }
 
+// Reached before each file of each phase and at the end of each phase, where
+// progress is reported and cancellation is checked. Files are visited package
+// by package in workspace order, so the number of completed packages only
+// depends on how many files are completed.
+struct PhaseCheckpoints<'a> {
+    progress_observer: Option<&'a dyn ProgressObserver>,
+    cancellation_token: Option<&'a CancellationToken>,
+    completed_package_count_by_completed_file_count: Vec<usize>,
+}
+
+impl<'a> PhaseCheckpoints<'a> {
+    fn new(
+        progress_observer: Option<&'a dyn ProgressObserver>,
+        cancellation_token: Option<&'a CancellationToken>,
+        packages: &[&DiscoveredPackage],
+    ) -> Self {
+        let mut phase_checkpoints = Self {
+            progress_observer,
+            cancellation_token,
+            completed_package_count_by_completed_file_count: vec![0],
+        };
+        for package in packages {
+            phase_checkpoints.add_package(package);
+        }
+        phase_checkpoints
+    }
+
+    // Packages found while following imports are added during parsing, so the
+    // totals reported for that phase grow until the closure is complete.
+    fn add_package(&mut self, package: &DiscoveredPackage) {
+        let counts = &mut self.completed_package_count_by_completed_file_count;
+        let package_index = counts[counts.len() - 1];
+        counts.extend(std::iter::repeat_n(
+            package_index,
+            package.source_files.len(),
+        ));
+        counts.push(package_index + 1);
+    }
+
+    fn reach(
+        &self,
+        phase: AnalysisPhase,
+        completed_file_count: usize,
+    ) -> Result<(), CompilerFailure> {
+        if let Some(cancellation_token) = self.cancellation_token {
+            cancellation_token.check()?;
+        }
+        let Some(progress_observer) = self.progress_observer else {
+            return Ok(());
+        };
+        let counts = &self.completed_package_count_by_completed_file_count;
+        progress_observer.analysis_progressed(AnalysisProgress {
+            phase,
+            completed_file_count,
+            file_count: counts.len() - 1,
+            completed_package_count: counts[completed_file_count],
+            package_count: counts[counts.len() - 1],
+        });
+        Ok(())
+    }
+}
+
 impl FilePhaseState {
     fn can_run_syntax_checks(&self) -> bool {
         matches!(self.parsing, PhaseStatus::Ok)
@@ -102,12 +266,17 @@ pub fn analyze_target_summary_with_workspace_root(
         path,
         workspace_root_override,
         &source_override_by_workspace_relative_path,
+        &AnalysisOptions::default(),
+        None,
+        None,
+        &mut QueryDatabase::new(),
     )?;
     Ok(AnalyzedTargetSummary {
         diagnostics: analyzed_target.diagnostics,
         source_by_path: analyzed_target.source_by_path,
         safe_autofix_edit_count_by_workspace_relative_path: analyzed_target
             .safe_autofix_edit_count_by_workspace_relative_path,
+        refactorings_by_path: analyzed_target.refactorings_by_path,
     })
 }
 
@@ -120,6 +289,10 @@ pub fn analyze_target_with_workspace_root(
         path,
         workspace_root_override,
         &source_override_by_workspace_relative_path,
+        &AnalysisOptions::default(),
+        None,
+        None,
+        &mut QueryDatabase::new(),
     )
 }
 
@@ -127,17 +300,56 @@ pub fn analyze_target_summary_with_workspace_root_and_overrides(
     path: &str,
     workspace_root_override: Option<&str>,
     source_override_by_workspace_relative_path: &BTreeMap<String, String>,
+    analysis_options: &AnalysisOptions,
+    progress_observer: Option<&dyn ProgressObserver>,
+    cancellation_token: Option<&CancellationToken>,
+    query_database: &mut QueryDatabase,
 ) -> Result<AnalyzedTargetSummary, CompilerFailure> {
     let analyzed_target = analyze_target_with_workspace_root_and_overrides(
         path,
         workspace_root_override,
         source_override_by_workspace_relative_path,
+        analysis_options,
+        progress_observer,
+        cancellation_token,
+        query_database,
+    )?;
+    Ok(AnalyzedTargetSummary {
+        diagnostics: analyzed_target.diagnostics,
+        source_by_path: analyzed_target.source_by_path,
+        safe_autofix_edit_count_by_workspace_relative_path: analyzed_target
+            .safe_autofix_edit_count_by_workspace_relative_path,
+        refactorings_by_path: analyzed_target.refactorings_by_path,
+    })
+}
+
+// Analyzes a workspace that exists only in `file_system`, for hosts without a
+// disk or working directory such as the in-browser playground. `path` is
+// resolved against `workspace_root`, which must be absolute.
+pub fn analyze_target_summary_in_file_system(
+    file_system: &dyn FileSystem,
+    workspace_root: &str,
+    path: &str,
+    analysis_options: &AnalysisOptions,
+) -> Result<AnalyzedTargetSummary, CompilerFailure> {
+    let source_override_by_workspace_relative_path = BTreeMap::new();
+    let analyzed_target = analyze_target_in_file_system(
+        file_system,
+        Path::new(workspace_root),
+        path,
+        Some(workspace_root),
+        &source_override_by_workspace_relative_path,
+        analysis_options,
+        None,
+        None,
+        &mut QueryDatabase::new(),
     )?;
     Ok(AnalyzedTargetSummary {
         diagnostics: analyzed_target.diagnostics,
         source_by_path: analyzed_target.source_by_path,
         safe_autofix_edit_count_by_workspace_relative_path: analyzed_target
             .safe_autofix_edit_count_by_workspace_relative_path,
+        refactorings_by_path: analyzed_target.refactorings_by_path,
     })
 }
 
@@ -145,31 +357,101 @@ pub fn analyze_target_with_workspace_root_and_overrides(
     path: &str,
     workspace_root_override: Option<&str>,
     source_override_by_workspace_relative_path: &BTreeMap<String, String>,
+    analysis_options: &AnalysisOptions,
+    progress_observer: Option<&dyn ProgressObserver>,
+    cancellation_token: Option<&CancellationToken>,
+    query_database: &mut QueryDatabase,
 ) -> Result<AnalyzedTarget, CompilerFailure> {
-    let workspace_root = resolve_workspace_root(path, workspace_root_override)?;
     let current_directory = std::env::current_dir().map_err(|error| CompilerFailure {
         kind: CompilerFailureKind::ReadSource,
         message: error.to_string(),
         path: Some(".".to_string()),
         details: Vec::new(),
     })?;
+    analyze_target_in_file_system(
+        &RealFileSystem,
+        &current_directory,
+        path,
+        workspace_root_override,
+        source_override_by_workspace_relative_path,
+        analysis_options,
+        progress_observer,
+        cancellation_token,
+        query_database,
+    )
+}
+
+fn analyze_target_in_file_system(
+    file_system: &dyn FileSystem,
+    current_directory: &Path,
+    path: &str,
+    workspace_root_override: Option<&str>,
+    source_override_by_workspace_relative_path: &BTreeMap<String, String>,
+    analysis_options: &AnalysisOptions,
+    progress_observer: Option<&dyn ProgressObserver>,
+    cancellation_token: Option<&CancellationToken>,
+    query_database: &mut QueryDatabase,
+) -> Result<AnalyzedTarget, CompilerFailure> {
+    cancellation_token.map_or(Ok(()), CancellationToken::check)?;
+    let resolved_workspace_root = resolve_workspace_root(
+        file_system,
+        current_directory,
+        path,
+        workspace_root_override,
+    )?;
+    // Paths are compared against the canonical root, so that a root reached
+    // through a symlink or spelled in a different case on a case-insensitive
+    // file system still contains the target and its overrides.
+    let workspace_root = file_system
+        .canonicalize(&resolved_workspace_root)
+        .map_err(|error| CompilerFailure {
+            kind: CompilerFailureKind::InvalidWorkspaceRoot,
+            message: format!("invalid workspace root: {error}"),
+            path: Some(path_to_key(&resolved_workspace_root)),
+            details: Vec::new(),
+        })?;
+    // Overrides may be keyed by workspace-relative or absolute path; joining
+    // keeps absolute keys as they are.
+    let mut overlay_file_system = OverlayFileSystem::new(file_system);
+    for (override_path, override_source) in source_override_by_workspace_relative_path {
+        overlay_file_system.insert_file(
+            rebase_onto_workspace_root(
+                &resolved_workspace_root.join(override_path),
+                &resolved_workspace_root,
+                &workspace_root,
+            ),
+            override_source.clone(),
+        );
+    }
+    let file_system = &overlay_file_system;
 
     let target_path = PathBuf::from(path);
     let absolute_target_path = if target_path.is_absolute() {
         target_path
     } else if workspace_root_override.is_some() {
-        workspace_root.join(&target_path)
+        resolved_workspace_root.join(&target_path)
     } else {
         current_directory.join(&target_path)
     };
-    let metadata = fs::metadata(&absolute_target_path).map_err(|error| CompilerFailure {
+    let absolute_target_path = rebase_onto_workspace_root(
+        &absolute_target_path,
+        &resolved_workspace_root,
+        &workspace_root,
+    );
+    let read_target_failure = |error: std::io::Error| CompilerFailure {
         kind: CompilerFailureKind::ReadSource,
         message: error.to_string(),
         path: Some(path.to_string()),
         details: Vec::new(),
-    })?;
-    let target_is_file = metadata.is_file();
-    if !metadata.is_file() && !metadata.is_dir() {
+    };
+    let target_entry_kind = file_system
+        .entry_kind(&absolute_target_path)
+        .map_err(read_target_failure)?;
+    let absolute_target_path = file_system
+        .canonicalize(&absolute_target_path)
+        .map_err(read_target_failure)?;
+    let target_is_file = target_entry_kind == FileSystemEntryKind::File;
+    if target_entry_kind == FileSystemEntryKind::Other {
         return Err(CompilerFailure {
             kind: CompilerFailureKind::InvalidAnalysisTarget,
             message: "expected a file or directory path".to_string(),
@@ -186,8 +468,8 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         });
     }
 
-    if metadata.is_file()
-        && find_owning_package_root(&workspace_root, &absolute_target_path).is_none()
+    if target_is_file
+        && find_owning_package_root(file_system, &workspace_root, &absolute_target_path).is_none()
     {
         return Err(CompilerFailure {
             kind: CompilerFailureKind::PackageNotFound,
@@ -196,20 +478,32 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             details: Vec::new(),
         });
     }
-    let workspace = discover_workspace(&workspace_root).map_err(|errors| CompilerFailure {
-        kind: CompilerFailureKind::WorkspaceDiscoveryFailed,
-        message: "workspace discovery failed".to_string(),
-        path: Some(path.to_string()),
-        details: errors
-            .into_iter()
-            .map(|error| CompilerFailureDetail {
-                message: error.message,
-                path: error.path.map(|path| path.display().to_string()),
-            })
-            .collect(),
-    })?;
+    let mut workspace =
+        discover_workspace(file_system, &workspace_root).map_err(|errors| CompilerFailure {
+            kind: CompilerFailureKind::WorkspaceDiscoveryFailed,
+            message: "workspace discovery failed".to_string(),
+            path: Some(path.to_string()),
+            details: errors
+                .into_iter()
+                .map(|error| CompilerFailureDetail {
+                    message: error.message,
+                    path: error.path.map(|path| path.display().to_string()),
+                })
+                .collect(),
+        })?;
+    if let Ok(relative_target_path) = absolute_target_path.strip_prefix(&workspace_root)
+        && !relative_target_path.as_os_str().is_empty()
+        && workspace.settings().ignores_path(relative_target_path)
+    {
+        return Err(CompilerFailure {
+            kind: CompilerFailureKind::PackageNotFound,
+            message: "target is ignored by the workspace configuration".to_string(),
+            path: Some(path.to_string()),
+            details: Vec::new(),
+        });
+    }
     if workspace.packages().is_empty()
-        && metadata.is_dir()
+        && target_entry_kind == FileSystemEntryKind::Directory
         && absolute_target_path == workspace_root
     {
         return Err(CompilerFailure {
@@ -219,24 +513,53 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             details: Vec::new(),
         });
     }
+    let diagnostic_baseline = match &workspace.settings().diagnostic_baseline {
+        Some(baseline_path) => {
+            read_diagnostic_baseline(file_system, &workspace_root, baseline_path)?
+        }
+        None => DiagnosticBaseline::default(),
+    };
     let scoped_package_paths = scoped_package_paths_for_target(
+        file_system,
         &workspace,
         &workspace_root,
         &absolute_target_path,
-        &metadata,
+        target_entry_kind,
     )?;
     let scope_is_workspace = scoped_package_paths.is_none();
+    // A scoped target only needs the packages its scope imports, directly or
+    // transitively, so parsing starts from the scoped packages and follows
+    // imports. Packages outside that closure cannot affect the scope's
+    // diagnostics and are never read.
+    let mut packages_to_parse = workspace
+        .packages()
+        .iter()
+        .filter(|package| {
+            scope_is_workspace
+                || scoped_package_paths
+                    .as_ref()
+                    .is_some_and(|scoped| scoped.contains(&package.package_path))
+        })
+        .collect::<Vec<_>>();
+    let mut analyzed_package_paths = packages_to_parse
+        .iter()
+        .map(|package| package.package_path.clone())
+        .collect::<BTreeSet<_>>();
+    let mut phase_checkpoints =
+        PhaseCheckpoints::new(progress_observer, cancellation_token, &packages_to_parse);
 
     let mut rendered_diagnostics = Vec::new();
     let mut all_diagnostics_by_file = BTreeMap::<PathBuf, Vec<RenderedDiagnostic>>::new();
     let mut source_by_path = BTreeMap::new();
     let mut source_by_workspace_relative_path_in_scope = BTreeMap::new();
-    let mut safe_autofix_edits_by_workspace_relative_path =
-        BTreeMap::<String, Vec<TextEdit>>::new();
+    let mut safe_autofixes_by_workspace_relative_path =
+        BTreeMap::<String, Vec<TextEditGroup<SafeAutofixProvenance>>>::new();
     let mut parsed_units = Vec::new();
     let mut package_path_by_file = BTreeMap::new();
     let mut file_role_by_path = BTreeMap::new();
-    for package in workspace.packages() {
+    let mut next_package_index = 0;
+    while let Some(package) = packages_to_parse.get(next_package_index).copied() {
+        next_package_index += 1;
         let package_in_scope = scope_is_workspace
             || scoped_package_paths
                 .as_ref()
@@ -252,32 +575,28 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         file_entries.sort_by(|left, right| compare_paths(&left.0, &right.0));
 
         for (relative_path, role) in file_entries {
+            phase_checkpoints.reach(AnalysisPhase::Parsing, parsed_units.len())?;
             let absolute_path = workspace_root.join(&relative_path);
             package_path_by_file.insert(relative_path.clone(), package.package_path.clone());
             file_role_by_path.insert(relative_path.clone(), role);
             let workspace_relative_key = path_to_key(&relative_path);
-            let source = if let Some(override_source) =
-                source_override_by_workspace_relative_path.get(&workspace_relative_key)
-            {
-                override_source.clone()
-            } else if let Some(override_source) =
-                source_override_by_workspace_relative_path.get(&path_to_key(&absolute_path))
-            {
-                override_source.clone()
-            } else {
-                fs::read_to_string(&absolute_path).map_err(|error| CompilerFailure {
+            let source = file_system
+                .read_to_string(&absolute_path)
+                .map_err(|error| CompilerFailure {
                     kind: CompilerFailureKind::ReadSource,
                     message: error.to_string(),
                     path: Some(display_path(&absolute_path)),
                     details: Vec::new(),
-                })?
-            };
+                })?;
             let rendered_path = display_path(&absolute_path);
-            let parse_result = parse_file(&source, role);
+            query_database.set_source(&relative_path, role, &source);
+            let parse_result = query_database.parse(&relative_path);
+            let line_index = LineIndex::new(&source);
             for diagnostic in &parse_result.diagnostics {
                 let rendered_diagnostic = render_diagnostic(
                     DiagnosticPhase::Parsing,
                     rendered_path.clone(),
+                    &line_index,
                     diagnostic.clone(),
                 );
                 push_rendered_diagnostic(
@@ -299,6 +618,14 @@ pub fn analyze_target_with_workspace_root_and_overrides(
                 safe_autofixes: parse_safe_autofixes,
                 status: parsing_status,
             } = parse_result;
+            for imported_package_path in imported_workspace_package_paths(&parsed_file) {
+                if let Some(imported_package) = workspace.package_by_path(imported_package_path)
+                    && analyzed_package_paths.insert(imported_package.package_path.clone())
+                {
+                    phase_checkpoints.add_package(imported_package);
+                    packages_to_parse.push(imported_package);
+                }
+            }
             parsed_units.push(ParsedUnit {
                 package_id: package.id,
                 package_path: package.package_path.clone(),
@@ -313,22 +640,36 @@ pub fn analyze_target_with_workspace_root_and_overrides(
                 },
             });
             if package_in_scope {
-                append_safe_autofix_edits_for_file(
-                    &mut safe_autofix_edits_by_workspace_relative_path,
+                append_safe_autofixes_for_file(
+                    &mut safe_autofixes_by_workspace_relative_path,
                     &workspace_relative_key,
+                    DiagnosticPhase::Parsing,
                     &parse_safe_autofixes,
                 );
             }
         }
     }
 
-    for parsed_unit in &mut parsed_units {
+    phase_checkpoints.reach(AnalysisPhase::Parsing, parsed_units.len())?;
+    // Later phases visit files in workspace package order regardless of the
+    // order in which imports discovered their packages.
+    parsed_units.sort_by_key(|parsed_unit| parsed_unit.package_id);
+    packages_to_parse.sort_by_key(|package| package.id);
+    let phase_checkpoints =
+        PhaseCheckpoints::new(progress_observer, cancellation_token, &packages_to_parse);
+
+    for (completed_file_count, parsed_unit) in parsed_units.iter_mut().enumerate() {
+        phase_checkpoints.reach(AnalysisPhase::SyntaxRules, completed_file_count)?;
         if !parsed_unit.phase_state.can_run_syntax_checks() {
             continue;
         }
-        let syntax_rules_result = syntax_rules::check_file(&parsed_unit.parsed);
+        let source_path = display_path(&workspace_root.join(&parsed_unit.path));
+        let source_text = source_by_path.get(&source_path).map_or("", String::as_str);
+        let line_index = LineIndex::new(source_text);
+        let syntax_rules_result = syntax_rules::check_file(&parsed_unit.parsed, source_text);
         parsed_unit.phase_state.syntax_rules = syntax_rules_result.status;
-        let file_role_rules_result = file_role_rules::check_file(&parsed_unit.parsed);
+        let file_role_rules_result =
+            file_role_rules::check_file(&parsed_unit.parsed, &parsed_unit.path, source_text);
         parsed_unit.phase_state.file_role_rules = file_role_rules_result.status;
 
         let parsed_unit_in_scope = is_parsed_unit_in_scope(
@@ -339,7 +680,8 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         for diagnostic in &syntax_rules_result.diagnostics {
             let rendered_diagnostic = render_diagnostic(
                 DiagnosticPhase::SyntaxRules,
-                display_path(&workspace_root.join(&parsed_unit.path)),
+                source_path.clone(),
+                &line_index,
                 diagnostic.clone(),
             );
             push_rendered_diagnostic(
@@ -353,7 +695,8 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         for diagnostic in &file_role_rules_result.diagnostics {
             let rendered_diagnostic = render_diagnostic(
                 DiagnosticPhase::FileRoleRules,
-                display_path(&workspace_root.join(&parsed_unit.path)),
+                source_path.clone(),
+                &line_index,
                 diagnostic.clone(),
             );
             push_rendered_diagnostic(
@@ -365,19 +708,33 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             );
         }
         if parsed_unit_in_scope {
-            append_safe_autofix_edits_for_file(
-                &mut safe_autofix_edits_by_workspace_relative_path,
+            append_safe_autofixes_for_file(
+                &mut safe_autofixes_by_workspace_relative_path,
                 &path_to_key(&parsed_unit.path),
+                DiagnosticPhase::SyntaxRules,
                 &syntax_rules_result.safe_autofixes,
             );
-            append_safe_autofix_edits_for_file(
-                &mut safe_autofix_edits_by_workspace_relative_path,
+            append_safe_autofixes_for_file(
+                &mut safe_autofixes_by_workspace_relative_path,
                 &path_to_key(&parsed_unit.path),
+                DiagnosticPhase::FileRoleRules,
                 &file_role_rules_result.safe_autofixes,
             );
         }
     }
+    phase_checkpoints.reach(AnalysisPhase::SyntaxRules, parsed_units.len())?;
+    for parsed_unit in &parsed_units {
+        if parsed_unit.parsed.role == FileRole::PackageManifest
+            && parsed_unit.phase_state.can_run_resolution()
+        {
+            workspace.set_package_metadata(
+                &parsed_unit.package_path,
+                package_metadata_from_manifest(&parsed_unit.parsed),
+            );
+        }
+    }
 
+    phase_checkpoints.reach(AnalysisPhase::Resolution, 0)?;
     let resolution_files: Vec<resolution::ResolutionFile<'_>> = parsed_units
         .iter()
         .filter(|unit| unit.phase_state.can_run_resolution())
@@ -387,7 +744,15 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             parsed: &unit.parsed,
         })
         .collect();
-    let resolution_result = resolution::resolve_files(&resolution_files);
+    let dependency_rules = workspace
+        .dependency_rules()
+        .iter()
+        .map(|rule| PackageDependencyRule {
+            source_package_pattern: rule.source_package_pattern.clone(),
+            target_package_pattern: rule.target_package_pattern.clone(),
+        })
+        .collect::<Vec<_>>();
+    let resolution_result = resolution::resolve_files(&resolution_files, &dependency_rules);
     let resolved_imports = resolution_result.value.resolved_imports;
     for (path, status) in &resolution_result.status_by_file {
         if let Some(parsed_unit) = parsed_units.iter_mut().find(|unit| &unit.path == path) {
@@ -406,9 +771,12 @@ pub fn analyze_target_with_workspace_root_and_overrides(
                 scope_is_workspace,
                 scoped_package_paths.as_ref(),
             );
+            let source_path = display_path(&workspace_root.join(&path));
+            let source_text = source_by_path.get(&source_path).map_or("", String::as_str);
             let rendered_diagnostic = render_diagnostic(
                 DiagnosticPhase::Resolution,
-                display_path(&workspace_root.join(&path)),
+                source_path.clone(),
+                &LineIndex::new(source_text),
                 PhaseDiagnostic::new(message, span),
             );
             push_rendered_diagnostic(
@@ -421,13 +789,16 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         }
     }
 
+    phase_checkpoints.reach(AnalysisPhase::Resolution, parsed_units.len())?;
+
     let package_id_by_path = collect_package_ids_by_path(&workspace);
     let mut semantic_file_by_path: BTreeMap<PathBuf, SemanticFile> = BTreeMap::new();
-    for parsed_unit in &mut parsed_units {
+    for (completed_file_count, parsed_unit) in parsed_units.iter_mut().enumerate() {
+        phase_checkpoints.reach(AnalysisPhase::SemanticLowering, completed_file_count)?;
         if !parsed_unit.phase_state.can_run_semantic_lowering() {
             continue;
         }
-        let lowering_result = lower_parsed_file(&parsed_unit.parsed);
+        let lowering_result = query_database.semantics(&parsed_unit.path);
         let PhaseOutput {
             value,
             diagnostics,
@@ -440,10 +811,14 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             scope_is_workspace,
             scoped_package_paths.as_ref(),
         );
+        let source_path = display_path(&workspace_root.join(&parsed_unit.path));
+        let source_text = source_by_path.get(&source_path).map_or("", String::as_str);
+        let line_index = LineIndex::new(source_text);
         for diagnostic in diagnostics {
             let rendered_diagnostic = render_diagnostic(
                 DiagnosticPhase::SemanticLowering,
-                display_path(&workspace_root.join(&parsed_unit.path)),
+                source_path.clone(),
+                &line_index,
                 diagnostic,
             );
             push_rendered_diagnostic(
@@ -455,16 +830,34 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             );
         }
         if matches!(parsed_unit.phase_state.semantic_lowering, PhaseStatus::Ok) {
+            let maintainability_result = maintainability_rules::check_file(&value, source_text);
+            for diagnostic in maintainability_result.diagnostics {
+                let rendered_diagnostic = render_diagnostic(
+                    DiagnosticPhase::MaintainabilityRules,
+                    source_path.clone(),
+                    &line_index,
+                    diagnostic,
+                );
+                push_rendered_diagnostic(
+                    &mut rendered_diagnostics,
+                    &mut all_diagnostics_by_file,
+                    &parsed_unit.path,
+                    rendered_diagnostic,
+                    parsed_unit_in_scope,
+                );
+            }
             semantic_file_by_path.insert(parsed_unit.path.clone(), value);
         }
         if parsed_unit_in_scope {
-            append_safe_autofix_edits_for_file(
-                &mut safe_autofix_edits_by_workspace_relative_path,
+            append_safe_autofixes_for_file(
+                &mut safe_autofixes_by_workspace_relative_path,
                 &path_to_key(&parsed_unit.path),
+                DiagnosticPhase::SemanticLowering,
                 &safe_autofixes,
             );
         }
     }
+    phase_checkpoints.reach(AnalysisPhase::SemanticLowering, parsed_units.len())?;
     let package_symbol_file_inputs: Vec<PackageSymbolFileInput<'_>> = parsed_units
         .iter()
         .filter_map(|unit| {
@@ -479,13 +872,22 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         .collect();
     let typecheck_resolved_imports =
         build_typecheck_resolved_imports(&resolved_imports, &package_id_by_path);
-    let typed_public_symbol_table =
-        build_typed_public_symbol_table(&package_symbol_file_inputs, &typecheck_resolved_imports);
+    let mut typed_public_symbol_table =
+        build_typed_public_symbol_table(&package_symbol_file_inputs);
     let imported_bindings_by_file =
         typed_public_symbol_table.imported_bindings_by_file(&typecheck_resolved_imports);
+    let mut imported_exports_revisions_by_file = imported_exports_revisions_by_file(
+        query_database,
+        &workspace,
+        &analyzed_package_paths,
+        &typecheck_resolved_imports,
+    );
     let mut resolved_declarations_by_path = BTreeMap::new();
+    let mut declaration_signatures_by_path = BTreeMap::new();
+    let mut refactorings_by_path = BTreeMap::new();
 
-    for parsed_unit in &parsed_units {
+    for (completed_file_count, parsed_unit) in parsed_units.iter().enumerate() {
+        phase_checkpoints.reach(AnalysisPhase::TypeAnalysis, completed_file_count)?;
         if !parsed_unit.phase_state.can_run_type_analysis() {
             continue;
         }
@@ -502,22 +904,50 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         };
         let source_path = display_path(&workspace_root.join(&parsed_unit.path));
         let source_text = source_by_path.get(&source_path).map_or("", String::as_str);
-        let type_analysis_result = type_analysis::check_package_unit(
+        let type_analysis_result = query_database.typecheck(
+            &parsed_unit.path,
             parsed_unit.package_id,
             &parsed_unit.package_path,
-            source_text,
             semantic_file,
             imported_bindings,
+            imported_exports_revisions_by_file
+                .remove(&parsed_unit.path)
+                .unwrap_or_default(),
+            &analysis_options.host_function_contracts,
+            &forbidden_builtins_for_package(workspace.settings(), &parsed_unit.package_path),
+            workspace
+                .package_by_path(&parsed_unit.package_path)
+                .and_then(|package| package.metadata.language_version)
+                .unwrap_or_default(),
+        );
+        declaration_signatures_by_path.insert(
+            parsed_unit.path.clone(),
+            type_analysis_result.value.declaration_signatures,
         );
-        if let Ok(resolved_declarations) = type_analysis_result.value {
+        if let Ok(resolved_declarations) = type_analysis_result.value.resolved_declarations {
+            if parsed_unit_in_scope {
+                let refactorings =
+                    inline_refactorings(source_text, &resolved_declarations.inline_candidates);
+                if !refactorings.is_empty() {
+                    refactorings_by_path.insert(source_path.clone(), refactorings);
+                }
+            }
             resolved_declarations_by_path.insert(parsed_unit.path.clone(), resolved_declarations);
         }
+        let line_index = LineIndex::new(source_text);
         for diagnostic in &type_analysis_result.diagnostics {
-            let rendered_diagnostic = render_diagnostic(
+            let mut rendered_diagnostic = render_diagnostic(
                 DiagnosticPhase::TypeAnalysis,
-                display_path(&workspace_root.join(&parsed_unit.path)),
+                source_path.clone(),
+                &line_index,
                 diagnostic.clone(),
             );
+            place_related_spans_in_other_files(
+                &mut rendered_diagnostic,
+                diagnostic,
+                &workspace_root,
+                &source_by_path,
+            );
             push_rendered_diagnostic(
                 &mut rendered_diagnostics,
                 &mut all_diagnostics_by_file,
@@ -527,32 +957,59 @@ pub fn analyze_target_with_workspace_root_and_overrides(
             );
         }
         if parsed_unit_in_scope {
-            append_safe_autofix_edits_for_file(
-                &mut safe_autofix_edits_by_workspace_relative_path,
+            append_safe_autofixes_for_file(
+                &mut safe_autofixes_by_workspace_relative_path,
                 &path_to_key(&parsed_unit.path),
+                DiagnosticPhase::TypeAnalysis,
                 &type_analysis_result.safe_autofixes,
             );
         }
     }
+    phase_checkpoints.reach(AnalysisPhase::TypeAnalysis, parsed_units.len())?;
 
-    sort_rendered_diagnostics(&mut rendered_diagnostics);
+    let package_path_by_rendered_path = package_path_by_file
+        .iter()
+        .map(|(path, package_path)| {
+            (
+                display_path(&workspace_root.join(path)),
+                package_path.clone(),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    let baselined_diagnostics_by_file = apply_diagnostic_baseline(
+        &diagnostic_baseline,
+        &source_by_path,
+        &mut all_diagnostics_by_file,
+        &mut rendered_diagnostics,
+    );
+    rank_rendered_diagnostics(&mut rendered_diagnostics, &package_path_by_rendered_path);
+    let rendered_diagnostics = limit_rendered_diagnostics(&rendered_diagnostics, analysis_options);
     for diagnostics in all_diagnostics_by_file.values_mut() {
-        sort_rendered_diagnostics(diagnostics);
+        sort_file_diagnostics(diagnostics);
     }
-    let (
-        safe_autofix_edit_count_by_workspace_relative_path,
-        canonical_source_override_by_workspace_relative_path,
-    ) = compute_safe_autofix_outputs(
+    let SafeAutofixOutputs {
+        edit_counts: safe_autofix_edit_count_by_workspace_relative_path,
+        skipped_safe_autofixes: skipped_safe_autofixes_by_workspace_relative_path,
+        unverified_formatting: unverified_formatting_by_workspace_relative_path,
+        canonical_source_overrides: canonical_source_override_by_workspace_relative_path,
+    } = compute_safe_autofix_outputs(
         &source_by_workspace_relative_path_in_scope,
-        &safe_autofix_edits_by_workspace_relative_path,
+        &parsed_units
+            .iter()
+            .map(|parsed_unit| (path_to_key(&parsed_unit.path), &parsed_unit.parsed))
+            .collect(),
+        &safe_autofixes_by_workspace_relative_path,
     );
 
     Ok(AnalyzedTarget {
         diagnostics: rendered_diagnostics,
         all_diagnostics_by_file,
+        baselined_diagnostics_by_file,
         source_by_path,
         source_by_workspace_relative_path_in_scope,
         safe_autofix_edit_count_by_workspace_relative_path,
+        skipped_safe_autofixes_by_workspace_relative_path,
+        unverified_formatting_by_workspace_relative_path,
         canonical_source_override_by_workspace_relative_path,
         workspace_root,
         workspace,
@@ -562,14 +1019,87 @@ pub fn analyze_target_with_workspace_root_and_overrides(
         file_role_by_path,
         resolved_imports,
         resolved_declarations_by_path,
+        declaration_signatures_by_path,
+        refactorings_by_path,
     })
 }
 
+// Each forbidden builtin names the marker rule that forbids it, so that its
+// diagnostic points at the policy as well as the call.
+fn forbidden_builtins_for_package(
+    settings: &WorkspaceSettings,
+    package_path: &str,
+) -> Vec<ForbiddenBuiltin> {
+    settings
+        .forbidden_builtin_rules_for_package(package_path)
+        .into_iter()
+        .map(|rule| ForbiddenBuiltin {
+            name: rule.builtin_name.clone(),
+            policy_source: format!(
+                "'forbid {} in {}' on line {} of {WORKSPACE_MARKER_FILENAME}",
+                rule.builtin_name, rule.package_pattern, rule.line_number
+            ),
+        })
+        .collect()
+}
+
+// Reads the manifest's `package` declaration, whose fields file role rules
+// have already validated.
+fn package_metadata_from_manifest(parsed_manifest: &SyntaxParsedFile) -> PackageMetadata {
+    let mut metadata = PackageMetadata::default();
+    for declaration in parsed_manifest.top_level_declarations() {
+        let SyntaxDeclaration::Package(package_declaration) = declaration else {
+            continue;
+        };
+        for field in &package_declaration.fields {
+            match (field.name.as_str(), &field.value) {
+                ("name", SyntaxPackageFieldValue::String { value, .. }) => {
+                    metadata.name = Some(value.clone());
+                }
+                ("version", SyntaxPackageFieldValue::String { value, .. }) => {
+                    metadata.version = Some(value.clone());
+                }
+                ("description", SyntaxPackageFieldValue::String { value, .. }) => {
+                    metadata.description = Some(value.clone());
+                }
+                ("entrypoints", SyntaxPackageFieldValue::List { items, .. }) => {
+                    metadata.entrypoints =
+                        Some(items.iter().map(|(value, _)| value.clone()).collect());
+                }
+                ("language_version", SyntaxPackageFieldValue::String { value, .. }) => {
+                    metadata.language_version = LanguageVersion::from_name(value);
+                }
+                _ => {}
+            }
+        }
+    }
+    metadata
+}
+
+// Each map is keyed by workspace-relative path. A file whose formatting failed
+// verification keeps its fixes but is left unformatted.
+struct SafeAutofixOutputs {
+    edit_counts: BTreeMap<String, usize>,
+    skipped_safe_autofixes: BTreeMap<String, Vec<SkippedSafeAutofix>>,
+    unverified_formatting: BTreeMap<String, FormattingVerificationError>,
+    canonical_source_overrides: BTreeMap<String, String>,
+}
+
+// Each file's phase fixes are merged in phase order, so a fix from an earlier
+// phase wins over a later one it overlaps, once fixes that would disturb a
+// comment are set aside. The formatter then runs on the fixed text, so its
+// edits never conflict, and is only trusted after its own verification.
 fn compute_safe_autofix_outputs(
     source_by_workspace_relative_path: &BTreeMap<String, String>,
-    safe_autofix_edits_by_workspace_relative_path: &BTreeMap<String, Vec<TextEdit>>,
-) -> (BTreeMap<String, usize>, BTreeMap<String, String>) {
+    parsed_file_by_workspace_relative_path: &BTreeMap<String, &SyntaxParsedFile>,
+    safe_autofixes_by_workspace_relative_path: &BTreeMap<
+        String,
+        Vec<TextEditGroup<SafeAutofixProvenance>>,
+    >,
+) -> SafeAutofixOutputs {
     let mut safe_autofix_edit_count_by_workspace_relative_path = BTreeMap::new();
+    let mut skipped_safe_autofixes_by_workspace_relative_path = BTreeMap::new();
+    let mut unverified_formatting_by_workspace_relative_path = BTreeMap::new();
     let mut canonical_source_override_by_workspace_relative_path = BTreeMap::new();
 
     for (workspace_relative_path, source_text) in source_by_workspace_relative_path {
@@ -578,12 +1108,39 @@ fn compute_safe_autofix_outputs(
         }
         let mut canonical_source_text = source_text.clone();
         let mut safe_autofix_edit_count = 0usize;
-        if let Some(candidate_phase_safe_autofix_edits) =
-            safe_autofix_edits_by_workspace_relative_path.get(workspace_relative_path)
+        if let Some(candidate_phase_safe_autofixes) =
+            safe_autofixes_by_workspace_relative_path.get(workspace_relative_path)
         {
-            let merged_phase_safe_autofix_edits =
-                merge_text_edits(candidate_phase_safe_autofix_edits);
+            let parsed_file = parsed_file_by_workspace_relative_path
+                .get(workspace_relative_path)
+                .copied();
+            let (comment_safe_autofixes, comment_disturbing_autofixes): (Vec<_>, Vec<_>) =
+                candidate_phase_safe_autofixes
+                    .iter()
+                    .cloned()
+                    .partition(|safe_autofix| !disturbs_comments(parsed_file, safe_autofix));
+            let merged_phase_safe_autofix_edits = merge_text_edit_groups(&comment_safe_autofixes);
             safe_autofix_edit_count += merged_phase_safe_autofix_edits.accepted_text_edits.len();
+            let skipped_safe_autofixes = comment_disturbing_autofixes
+                .iter()
+                .map(|safe_autofix| SkippedSafeAutofix {
+                    provenance: safe_autofix.provenance,
+                    reason: SafeAutofixSkipReason::DisturbsComment,
+                })
+                .chain(
+                    merged_phase_safe_autofix_edits
+                        .conflicts
+                        .iter()
+                        .map(|conflict| SkippedSafeAutofix {
+                            provenance: conflict.skipped,
+                            reason: SafeAutofixSkipReason::ConflictsWith(conflict.conflicting),
+                        }),
+                )
+                .collect::<Vec<_>>();
+            if !skipped_safe_autofixes.is_empty() {
+                skipped_safe_autofixes_by_workspace_relative_path
+                    .insert(workspace_relative_path.clone(), skipped_safe_autofixes);
+            }
             if !merged_phase_safe_autofix_edits
                 .accepted_text_edits
                 .is_empty()
@@ -596,7 +1153,14 @@ fn compute_safe_autofix_outputs(
             }
         }
 
-        let formatter_text_edits = formatting_text_edits(&canonical_source_text);
+        let formatter_text_edits = match verified_formatting_text_edits(&canonical_source_text) {
+            Ok(formatter_text_edits) => formatter_text_edits,
+            Err(verification_error) => {
+                unverified_formatting_by_workspace_relative_path
+                    .insert(workspace_relative_path.clone(), verification_error);
+                Vec::new()
+            }
+        };
         if !formatter_text_edits.is_empty()
             && let Ok(formatted_text) =
                 apply_text_edits(&canonical_source_text, &formatter_text_edits)
@@ -617,46 +1181,65 @@ fn compute_safe_autofix_outputs(
             .insert(workspace_relative_path.clone(), canonical_source_text);
     }
 
-    (
-        safe_autofix_edit_count_by_workspace_relative_path,
-        canonical_source_override_by_workspace_relative_path,
-    )
+    SafeAutofixOutputs {
+        edit_counts: safe_autofix_edit_count_by_workspace_relative_path,
+        skipped_safe_autofixes: skipped_safe_autofixes_by_workspace_relative_path,
+        unverified_formatting: unverified_formatting_by_workspace_relative_path,
+        canonical_source_overrides: canonical_source_override_by_workspace_relative_path,
+    }
+}
+
+fn disturbs_comments(
+    parsed_file: Option<&SyntaxParsedFile>,
+    safe_autofix: &TextEditGroup<SafeAutofixProvenance>,
+) -> bool {
+    parsed_file.is_some_and(|parsed_file| {
+        safe_autofix.text_edits.iter().any(|text_edit| {
+            parsed_file
+                .edit_disturbs_comments(text_edit.start_byte_offset, text_edit.end_byte_offset)
+        })
+    })
 }
 
-fn append_safe_autofix_edits_for_file(
-    safe_autofix_edits_by_workspace_relative_path: &mut BTreeMap<String, Vec<TextEdit>>,
+fn append_safe_autofixes_for_file(
+    safe_autofixes_by_workspace_relative_path: &mut BTreeMap<
+        String,
+        Vec<TextEditGroup<SafeAutofixProvenance>>,
+    >,
     workspace_relative_path: &str,
+    phase: DiagnosticPhase,
     safe_autofixes: &[SafeAutofix],
 ) {
-    let file_safe_autofix_edits = safe_autofix_edits_by_workspace_relative_path
+    let file_safe_autofixes = safe_autofixes_by_workspace_relative_path
         .entry(workspace_relative_path.to_string())
         .or_default();
     for safe_autofix in safe_autofixes {
-        file_safe_autofix_edits.extend(safe_autofix.text_edits.iter().cloned());
+        file_safe_autofixes.push(TextEditGroup {
+            provenance: SafeAutofixProvenance {
+                phase,
+                rule: safe_autofix.rule,
+            },
+            text_edits: safe_autofix.text_edits.clone(),
+        });
     }
 }
 
 fn resolve_workspace_root(
+    file_system: &dyn FileSystem,
+    current_directory: &Path,
     path: &str,
     workspace_root_override: Option<&str>,
 ) -> Result<PathBuf, CompilerFailure> {
-    let current_directory = std::env::current_dir().map_err(|error| CompilerFailure {
-        kind: CompilerFailureKind::ReadSource,
-        message: error.to_string(),
-        path: Some(".".to_string()),
-        details: Vec::new(),
-    })?;
-
     if let Some(root_override) = workspace_root_override {
-        let workspace_root =
-            absolute_path_from_current_directory(&current_directory, root_override);
-        ensure_valid_workspace_root_directory(&workspace_root, root_override)?;
+        let workspace_root = absolute_path_from_current_directory(current_directory, root_override);
+        ensure_valid_workspace_root_directory(file_system, &workspace_root, root_override)?;
         return Ok(workspace_root);
     }
 
-    let absolute_target_path = absolute_path_from_current_directory(&current_directory, path);
-    let search_start_path = marker_search_start_path(&absolute_target_path);
-    let Some(workspace_root) = find_workspace_root_from_marker(&search_start_path) else {
+    let absolute_target_path = absolute_path_from_current_directory(current_directory, path);
+    let search_start_path = marker_search_start_path(file_system, &absolute_target_path);
+    let Some(workspace_root) = find_workspace_root_from_marker(file_system, &search_start_path)
+    else {
         return Err(CompilerFailure {
             kind: CompilerFailureKind::WorkspaceRootMissingManifest,
             message: format!(
@@ -666,10 +1249,25 @@ fn resolve_workspace_root(
             details: Vec::new(),
         });
     };
-    ensure_valid_workspace_root_directory(&workspace_root, &path_to_key(&workspace_root))?;
+    ensure_valid_workspace_root_directory(
+        file_system,
+        &workspace_root,
+        &path_to_key(&workspace_root),
+    )?;
     Ok(workspace_root)
 }
 
+fn rebase_onto_workspace_root(
+    path: &Path,
+    resolved_workspace_root: &Path,
+    canonical_workspace_root: &Path,
+) -> PathBuf {
+    path.strip_prefix(resolved_workspace_root).map_or_else(
+        |_| path.to_path_buf(),
+        |relative_path| canonical_workspace_root.join(relative_path),
+    )
+}
+
 fn absolute_path_from_current_directory(current_directory: &Path, raw_path: &str) -> PathBuf {
     let parsed_path = PathBuf::from(raw_path);
     if parsed_path.is_absolute() {
@@ -680,17 +1278,20 @@ fn absolute_path_from_current_directory(current_directory: &Path, raw_path: &str
 }
 
 fn ensure_valid_workspace_root_directory(
+    file_system: &dyn FileSystem,
     workspace_root: &Path,
     workspace_root_display: &str,
 ) -> Result<(), CompilerFailure> {
-    let workspace_root_metadata =
-        fs::metadata(workspace_root).map_err(|error| CompilerFailure {
-            kind: CompilerFailureKind::InvalidWorkspaceRoot,
-            message: format!("invalid workspace root: {error}"),
-            path: Some(workspace_root_display.to_string()),
-            details: Vec::new(),
-        })?;
-    if !workspace_root_metadata.is_dir() {
+    let workspace_root_entry_kind =
+        file_system
+            .entry_kind(workspace_root)
+            .map_err(|error| CompilerFailure {
+                kind: CompilerFailureKind::InvalidWorkspaceRoot,
+                message: format!("invalid workspace root: {error}"),
+                path: Some(workspace_root_display.to_string()),
+                details: Vec::new(),
+            })?;
+    if workspace_root_entry_kind != FileSystemEntryKind::Directory {
         return Err(CompilerFailure {
             kind: CompilerFailureKind::WorkspaceRootNotDirectory,
             message: "workspace root must be a directory".to_string(),
@@ -701,11 +1302,8 @@ fn ensure_valid_workspace_root_directory(
     Ok(())
 }
 
-fn marker_search_start_path(absolute_target_path: &Path) -> PathBuf {
-    if fs::metadata(absolute_target_path)
-        .map(|metadata| metadata.is_file())
-        .unwrap_or(false)
-    {
+fn marker_search_start_path(file_system: &dyn FileSystem, absolute_target_path: &Path) -> PathBuf {
+    if file_system.is_file(absolute_target_path) {
         return absolute_target_path
             .parent()
             .map_or_else(|| absolute_target_path.to_path_buf(), Path::to_path_buf);
@@ -713,10 +1311,13 @@ fn marker_search_start_path(absolute_target_path: &Path) -> PathBuf {
     absolute_target_path.to_path_buf()
 }
 
-fn find_workspace_root_from_marker(search_start_path: &Path) -> Option<PathBuf> {
+fn find_workspace_root_from_marker(
+    file_system: &dyn FileSystem,
+    search_start_path: &Path,
+) -> Option<PathBuf> {
     let mut current_path = search_start_path.to_path_buf();
     loop {
-        if current_path.join(WORKSPACE_MARKER_FILENAME).is_file() {
+        if file_system.is_file(&current_path.join(WORKSPACE_MARKER_FILENAME)) {
             return Some(current_path);
         }
         let parent = current_path.parent()?.to_path_buf();
@@ -732,6 +1333,63 @@ fn collect_package_ids_by_path(workspace: &Workspace) -> BTreeMap<String, Packag
     package_id_by_path
 }
 
+// The exports revision of every package each file imports, which is what the
+// file's memoized type analysis depends on besides the file itself.
+fn imported_exports_revisions_by_file(
+    query_database: &mut QueryDatabase,
+    workspace: &Workspace,
+    analyzed_package_paths: &BTreeSet<String>,
+    typecheck_resolved_imports: &[ResolvedImportSummary],
+) -> BTreeMap<PathBuf, Vec<(PackageId, u64)>> {
+    let mut exports_revision_by_package_id = BTreeMap::new();
+    for package in workspace.packages() {
+        if !analyzed_package_paths.contains(&package.package_path) {
+            continue;
+        }
+        let mut source_paths = vec![package.manifest_path.as_path()];
+        source_paths.extend(
+            package
+                .source_files
+                .iter()
+                .filter(|source_file| source_file.role == FileRole::Library)
+                .map(|source_file| source_file.workspace_relative_path.as_path()),
+        );
+        exports_revision_by_package_id.insert(
+            package.id,
+            query_database.exports(&package.package_path, &source_paths),
+        );
+    }
+
+    let mut imported_exports_revisions_by_file = BTreeMap::<PathBuf, Vec<_>>::new();
+    for resolved_import in typecheck_resolved_imports {
+        if let Some(exports_revision) =
+            exports_revision_by_package_id.get(&resolved_import.target_package_id)
+        {
+            imported_exports_revisions_by_file
+                .entry(resolved_import.source_path.clone())
+                .or_default()
+                .push((resolved_import.target_package_id, *exports_revision));
+        }
+    }
+    imported_exports_revisions_by_file
+}
+
+fn imported_workspace_package_paths(parsed_file: &SyntaxParsedFile) -> Vec<&str> {
+    parsed_file
+        .top_level_declarations()
+        .filter_map(|declaration| match declaration {
+            SyntaxDeclaration::Import(import_declaration) => {
+                if import_declaration.package_path == "workspace" {
+                    Some("")
+                } else {
+                    import_declaration.package_path.strip_prefix("workspace/")
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 fn is_parsed_unit_in_scope(
     parsed_unit: &ParsedUnit,
     scope_is_workspace: bool,
@@ -757,7 +1415,7 @@ fn build_typecheck_resolved_imports(
             .map(|binding| ResolvedImportBindingSummary {
                 imported_name: binding.imported_name.clone(),
                 local_name: binding.local_name.clone(),
-                span: binding.span.clone(),
+                span: binding.span,
             })
             .collect();
         typecheck_resolved_imports.push(ResolvedImportSummary {
@@ -771,19 +1429,20 @@ fn build_typecheck_resolved_imports(
 }
 
 fn scoped_package_paths_for_target(
+    file_system: &dyn FileSystem,
     workspace: &Workspace,
     workspace_root: &Path,
     absolute_target_path: &Path,
-    target_metadata: &fs::Metadata,
+    target_entry_kind: FileSystemEntryKind,
 ) -> Result<Option<BTreeSet<String>>, CompilerFailure> {
     if absolute_target_path == workspace_root {
         return Ok(None);
     }
 
-    let owning_package_root = if target_metadata.is_file() {
-        find_owning_package_root(workspace_root, absolute_target_path)
+    let owning_package_root = if target_entry_kind == FileSystemEntryKind::File {
+        find_owning_package_root(file_system, workspace_root, absolute_target_path)
     } else {
-        find_owning_package_root_for_directory(workspace_root, absolute_target_path)
+        find_owning_package_root_for_directory(file_system, workspace_root, absolute_target_path)
     };
     let Some(owning_package_root) = owning_package_root else {
         return Err(CompilerFailure {
@@ -802,9 +1461,14 @@ fn scoped_package_paths_for_target(
             details: Vec::new(),
         })?;
     if workspace.package_by_path(&owning_package_path).is_none() {
+        let message = if workspace.settings().includes_package(&owning_package_path) {
+            "target is not inside a package (missing PACKAGE.copp)"
+        } else {
+            "target package is excluded by the workspace configuration"
+        };
         return Err(CompilerFailure {
             kind: CompilerFailureKind::PackageNotFound,
-            message: "target is not inside a package (missing PACKAGE.copp)".to_string(),
+            message: message.to_string(),
             path: Some(path_to_key(absolute_target_path)),
             details: Vec::new(),
         });
@@ -816,12 +1480,13 @@ fn scoped_package_paths_for_target(
 }
 
 fn find_owning_package_root_for_directory(
+    file_system: &dyn FileSystem,
     workspace_root: &Path,
     target_directory: &Path,
 ) -> Option<PathBuf> {
     let mut directory = target_directory.to_path_buf();
     loop {
-        if directory.join("PACKAGE.copp").is_file() {
+        if file_system.is_file(&directory.join("PACKAGE.copp")) {
             return Some(directory);
         }
         if directory == workspace_root {
@@ -843,10 +1508,14 @@ fn relative_package_path(workspace_root: &Path, package_root: &Path) -> Option<S
     Some(key)
 }
 
-fn find_owning_package_root(workspace_root: &Path, target_path: &Path) -> Option<PathBuf> {
+fn find_owning_package_root(
+    file_system: &dyn FileSystem,
+    workspace_root: &Path,
+    target_path: &Path,
+) -> Option<PathBuf> {
     let mut directory = target_path.parent()?.to_path_buf();
     loop {
-        if directory.join("PACKAGE.copp").is_file() {
+        if file_system.is_file(&directory.join("PACKAGE.copp")) {
             return Some(directory);
         }
         if directory == workspace_root {
@@ -866,13 +1535,69 @@ fn find_owning_package_root(workspace_root: &Path, target_path: &Path) -> Option
 fn render_diagnostic(
     phase: DiagnosticPhase,
     path: String,
+    line_index: &LineIndex,
     diagnostic: PhaseDiagnostic,
 ) -> RenderedDiagnostic {
     RenderedDiagnostic {
         phase,
+        severity: match diagnostic.severity {
+            DiagnosticSeverity::Error => RenderedDiagnosticSeverity::Error,
+            DiagnosticSeverity::Warning => RenderedDiagnosticSeverity::Warning,
+        },
         path,
         message: diagnostic.message,
-        span: diagnostic.span,
+        span: RenderedSpan::new(diagnostic.span, line_index),
+        related_spans: diagnostic
+            .related_spans
+            .into_iter()
+            .map(|related_span| RenderedRelatedSpan {
+                message: related_span.message,
+                path: related_span.path.as_deref().map(path_to_key),
+                span: RenderedSpan::new(related_span.span, line_index),
+            })
+            .collect(),
+        suggested_fixes: diagnostic
+            .suggested_fixes
+            .into_iter()
+            .map(|suggested_fix| RenderedSuggestedFix {
+                label: suggested_fix.label,
+                text_edits: suggested_fix
+                    .edits
+                    .into_iter()
+                    .map(|edit| RenderedTextEdit {
+                        span: RenderedSpan::new(edit.span, line_index),
+                        replacement_text: edit.replacement_text,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+// `render_diagnostic` places every related span in the diagnostic's own file.
+// Related spans in another file, such as a declaration in an imported package,
+// are placed again using the line index of that file.
+fn place_related_spans_in_other_files(
+    rendered_diagnostic: &mut RenderedDiagnostic,
+    diagnostic: &PhaseDiagnostic,
+    workspace_root: &Path,
+    source_by_path: &BTreeMap<String, String>,
+) {
+    for (rendered_related_span, related_span) in rendered_diagnostic
+        .related_spans
+        .iter_mut()
+        .zip(&diagnostic.related_spans)
+    {
+        let Some(related_path) = &related_span.path else {
+            continue;
+        };
+        let related_source_path = display_path(&workspace_root.join(related_path));
+        let related_source_text = source_by_path
+            .get(&related_source_path)
+            .map_or("", String::as_str);
+        rendered_related_span.span =
+            RenderedSpan::new(related_span.span, &LineIndex::new(related_source_text));
+        rendered_related_span.path = Some(related_source_path);
     }
 }
 
@@ -892,17 +1617,182 @@ fn push_rendered_diagnostic(
         .push(rendered_diagnostic);
 }
 
-fn sort_rendered_diagnostics(diagnostics: &mut [RenderedDiagnostic]) {
+// Diagnostics from earlier phases are root causes of later ones, because a
+// phase only runs over output its predecessors accepted. Packages and files are
+// ordered by their earliest phase, and each file lists its diagnostics by phase
+// before position.
+fn rank_rendered_diagnostics(
+    diagnostics: &mut [RenderedDiagnostic],
+    package_path_by_rendered_path: &BTreeMap<String, String>,
+) {
+    let package_path_for = |diagnostic: &RenderedDiagnostic| {
+        package_path_by_rendered_path
+            .get(&diagnostic.path)
+            .map_or("", String::as_str)
+            .to_string()
+    };
+    let mut earliest_phase_by_path = BTreeMap::<String, DiagnosticPhase>::new();
+    let mut earliest_phase_by_package_path = BTreeMap::<String, DiagnosticPhase>::new();
+    for diagnostic in diagnostics.iter() {
+        for (key, earliest_phase_by_key) in [
+            (diagnostic.path.clone(), &mut earliest_phase_by_path),
+            (
+                package_path_for(diagnostic),
+                &mut earliest_phase_by_package_path,
+            ),
+        ] {
+            earliest_phase_by_key
+                .entry(key)
+                .and_modify(|earliest_phase| {
+                    *earliest_phase = (*earliest_phase).min(diagnostic.phase);
+                })
+                .or_insert(diagnostic.phase);
+        }
+    }
+    diagnostics.sort_by_cached_key(|diagnostic| {
+        let package_path = package_path_for(diagnostic);
+        (
+            earliest_phase_by_package_path[&package_path],
+            package_path,
+            earliest_phase_by_path[&diagnostic.path],
+            diagnostic.path.clone(),
+        )
+    });
+    for file_diagnostics in diagnostics.chunk_by_mut(|left, right| left.path == right.path) {
+        sort_file_diagnostics(file_diagnostics);
+    }
+}
+
+fn sort_file_diagnostics(diagnostics: &mut [RenderedDiagnostic]) {
     diagnostics.sort_by(|left, right| {
-        left.path
-            .cmp(&right.path)
+        left.phase
+            .cmp(&right.phase)
             .then(left.span.line.cmp(&right.span.line))
             .then(left.span.column.cmp(&right.span.column))
             .then(left.message.cmp(&right.message))
-            .then(left.phase.cmp(&right.phase))
     });
 }
 
+// Only the reported diagnostics are limited; `all_diagnostics_by_file` keeps
+// every diagnostic so callers can still tell which files failed. Diagnostics
+// must already be grouped by file.
+#[must_use]
+pub fn limit_rendered_diagnostics(
+    diagnostics: &[RenderedDiagnostic],
+    analysis_options: &AnalysisOptions,
+) -> Vec<RenderedDiagnostic> {
+    let mut limited_diagnostics = Vec::new();
+    for file_diagnostics in diagnostics.chunk_by(|left, right| left.path == right.path) {
+        let mut file_diagnostics = file_diagnostics.to_vec();
+        file_diagnostics.dedup_by(|right, left| {
+            left.phase == right.phase
+                && left.message == right.message
+                && left.span.start == right.span.start
+                && left.span.end == right.span.end
+        });
+        if let Some(similar_limit) = analysis_options.similar_diagnostic_limit_per_file {
+            file_diagnostics = collapse_similar_diagnostics(file_diagnostics, similar_limit);
+        }
+        if let Some(diagnostic_limit) = analysis_options.diagnostic_limit_per_file {
+            truncate_file_diagnostics(&mut file_diagnostics, diagnostic_limit);
+        }
+        limited_diagnostics.extend(file_diagnostics);
+    }
+    limited_diagnostics
+}
+
+// Diagnostics are similar when they share a phase, severity, and message, which
+// is how a single root cause such as a missing type usually cascades through a
+// file.
+fn collapse_similar_diagnostics(
+    file_diagnostics: Vec<RenderedDiagnostic>,
+    similar_limit: NonZeroUsize,
+) -> Vec<RenderedDiagnostic> {
+    let similar_limit = similar_limit.get();
+    let mut kept_diagnostics: Vec<RenderedDiagnostic> = Vec::new();
+    let mut kept_indices_by_similarity = BTreeMap::<_, Vec<usize>>::new();
+    let mut suppressed_spans_by_last_kept_index = BTreeMap::<usize, Vec<RenderedSpan>>::new();
+    for diagnostic in file_diagnostics {
+        let kept_indices = kept_indices_by_similarity
+            .entry((
+                diagnostic.phase,
+                diagnostic.is_error(),
+                diagnostic.message.clone(),
+            ))
+            .or_default();
+        if kept_indices.len() < similar_limit {
+            kept_indices.push(kept_diagnostics.len());
+            kept_diagnostics.push(diagnostic);
+            continue;
+        }
+        let last_kept_index = kept_indices[similar_limit - 1];
+        suppressed_spans_by_last_kept_index
+            .entry(last_kept_index)
+            .or_default()
+            .push(diagnostic.span);
+    }
+    for (last_kept_index, suppressed_spans) in suppressed_spans_by_last_kept_index {
+        let last_kept_diagnostic = &mut kept_diagnostics[last_kept_index];
+        let message = format!(
+            "{} more similar {} suppressed",
+            suppressed_spans.len(),
+            severity_noun(last_kept_diagnostic.severity, suppressed_spans.len())
+        );
+        last_kept_diagnostic
+            .related_spans
+            .push(RenderedRelatedSpan {
+                message,
+                path: None,
+                span: suppressed_spans[0],
+            });
+    }
+    kept_diagnostics
+}
+
+// Errors are kept ahead of warnings, so warnings early in a file never push
+// its errors out of the report.
+fn truncate_file_diagnostics(file_diagnostics: &mut Vec<RenderedDiagnostic>, limit: NonZeroUsize) {
+    let limit = limit.get();
+    if file_diagnostics.len() <= limit {
+        return;
+    }
+    file_diagnostics.sort_by_key(|diagnostic| !diagnostic.is_error());
+    let suppressed_diagnostics = file_diagnostics.split_off(limit);
+    let suppressed_error_count = suppressed_diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .count();
+    let suppressed_warning_count = suppressed_diagnostics.len() - suppressed_error_count;
+    let suppressed_counts = [
+        (RenderedDiagnosticSeverity::Error, suppressed_error_count),
+        (
+            RenderedDiagnosticSeverity::Warning,
+            suppressed_warning_count,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(severity, count)| format!("{count} more {}", severity_noun(severity, count)))
+    .collect::<Vec<_>>()
+    .join(" and ");
+    file_diagnostics[limit - 1]
+        .related_spans
+        .push(RenderedRelatedSpan {
+            message: format!("{suppressed_counts} in this file suppressed"),
+            path: None,
+            span: suppressed_diagnostics[0].span,
+        });
+}
+
+fn severity_noun(severity: RenderedDiagnosticSeverity, count: usize) -> &'static str {
+    match (severity, count) {
+        (RenderedDiagnosticSeverity::Error, 1) => "error",
+        (RenderedDiagnosticSeverity::Error, _) => "errors",
+        (RenderedDiagnosticSeverity::Warning, 1) => "warning",
+        (RenderedDiagnosticSeverity::Warning, _) => "warnings",
+    }
+}
+
 fn display_path(path: &Path) -> String {
     let absolute_path = if path.is_absolute() {
         path.to_path_buf()
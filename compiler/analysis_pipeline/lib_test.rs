@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::num::NonZeroUsize;
+
+use compiler__analysis_pipeline::{
+    AnalysisOptions, AnalyzedTarget, DiagnosticChanges, SafeAutofixProvenance,
+    SafeAutofixSkipReason, SkippedSafeAutofix, analyze_target_diagnostic_changes,
+    analyze_target_summary_in_file_system, analyze_target_with_workspace_root,
+    fix_target_with_workspace_root, limit_rendered_diagnostics, regenerate_diagnostic_baseline,
+};
+use compiler__file_system::InMemoryFileSystem;
+use compiler__interned_names::interned_name_count;
+use compiler__reports::{CompilerFailureKind, DiagnosticPhase, RenderedDiagnostic};
+use tests__test_workspace::TestWorkspace;
+
+fn test_workspace(files: &[(&str, &str)]) -> TestWorkspace {
+    TestWorkspace::new("coppice_pipeline_test", files)
+}
+
+fn diagnostic_changes(workspace: &TestWorkspace, overrides: &[(&str, &str)]) -> DiagnosticChanges {
+    let root = workspace.path().display().to_string();
+    let source_override_by_workspace_relative_path = overrides
+        .iter()
+        .map(|(path, source)| ((*path).to_string(), (*source).to_string()))
+        .collect::<BTreeMap<_, _>>();
+    analyze_target_diagnostic_changes(
+        &root,
+        Some(&root),
+        &source_override_by_workspace_relative_path,
+        &AnalysisOptions::default(),
+        None,
+    )
+    .expect("analysis should run")
+}
+
+fn analyze(workspace: &TestWorkspace) -> AnalyzedTarget {
+    let root = workspace.path().display().to_string();
+    analyze_target_with_workspace_root(&root, Some(&root)).expect("analysis should run")
+}
+
+fn messages(diagnostics: &[RenderedDiagnostic]) -> Vec<&str> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect()
+}
+
+const BASELINE_SOURCE: &str = "\
+function first() -> int64 {
+    return missing_first
+}
+";
+
+#[test]
+fn diagnostics_moved_by_an_edit_are_neither_introduced_nor_resolved() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", BASELINE_SOURCE)]);
+
+    let changes = diagnostic_changes(
+        &workspace,
+        &[(
+            "lib.copp",
+            "\
+function second() -> int64 {
+    return missing_second
+}
+
+function first() -> int64 {
+    return missing_first
+}
+",
+        )],
+    );
+
+    assert_eq!(
+        messages(&changes.introduced_diagnostics),
+        ["unknown name 'missing_second'"]
+    );
+    assert!(changes.resolved_diagnostics.is_empty());
+}
+
+#[test]
+fn diagnostics_fixed_by_an_edit_are_resolved() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", BASELINE_SOURCE)]);
+
+    let changes = diagnostic_changes(
+        &workspace,
+        &[(
+            "lib.copp",
+            "\
+function first() -> int64 {
+    return 1
+}
+",
+        )],
+    );
+
+    assert!(changes.introduced_diagnostics.is_empty());
+    assert_eq!(
+        messages(&changes.resolved_diagnostics),
+        ["unknown name 'missing_first'"]
+    );
+}
+
+#[test]
+fn baselined_diagnostics_are_not_reported_after_they_move() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", BASELINE_SOURCE)]);
+    workspace.write_file("COPPICE_WORKSPACE", "lint.baseline = lint/baseline.json\n");
+    let root = workspace.path().display().to_string();
+
+    let diagnostic_count =
+        regenerate_diagnostic_baseline(&root, Some(&root)).expect("baseline should regenerate");
+    workspace.write_file(
+        "lib.copp",
+        "\
+function second() -> int64 {
+    return missing_second
+}
+
+function first() -> int64 {
+    return missing_first
+}
+",
+    );
+    let analyzed_target = analyze(&workspace);
+
+    assert_eq!(diagnostic_count, 1);
+    assert_eq!(
+        messages(&analyzed_target.diagnostics),
+        ["unknown name 'missing_second'"]
+    );
+    let baselined_diagnostics = analyzed_target
+        .baselined_diagnostics_by_file
+        .values()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages(&baselined_diagnostics),
+        ["unknown name 'missing_first'"]
+    );
+}
+
+#[test]
+fn diagnostic_limit_keeps_errors_ahead_of_earlier_warnings() {
+    let workspace = test_workspace(&[
+        ("COPPICE_WORKSPACE", ""),
+        ("PACKAGE.copp", ""),
+        (
+            "lib.copp",
+            "@deprecated(\"use current\")\nfunction legacy() -> int64 {\n    return 1\n}\n\nfunction total() -> string {\n    first: string := legacy()\n    second: string := legacy()\n    return first + second\n}\n",
+        ),
+    ]);
+    let analyzed_target = analyze(&workspace);
+    assert_eq!(analyzed_target.diagnostics.len(), 5);
+    assert!(!analyzed_target.diagnostics[0].is_error());
+
+    let limited_diagnostics = limit_rendered_diagnostics(
+        &analyzed_target.diagnostics,
+        &AnalysisOptions {
+            similar_diagnostic_limit_per_file: None,
+            diagnostic_limit_per_file: NonZeroUsize::new(3),
+            host_function_contracts: Vec::new(),
+        },
+    );
+    assert_eq!(
+        messages(&limited_diagnostics),
+        [
+            "type mismatch: expected string, got int64",
+            "type mismatch: expected string, got int64",
+            "return type mismatch: expected string, got int64",
+        ]
+    );
+    let suppressed_note = limited_diagnostics[2]
+        .related_spans
+        .last()
+        .expect("the last kept diagnostic should note the suppressed ones");
+    assert_eq!(
+        suppressed_note.message,
+        "2 more warnings in this file suppressed"
+    );
+}
+
+#[test]
+fn regenerating_a_baseline_requires_the_workspace_setting() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", BASELINE_SOURCE)]);
+    let root = workspace.path().display().to_string();
+
+    let error = regenerate_diagnostic_baseline(&root, Some(&root))
+        .expect_err("baseline should not regenerate");
+
+    assert!(matches!(
+        error.kind,
+        CompilerFailureKind::DiagnosticBaselineNotConfigured
+    ));
+}
+
+const OVERLAPPING_FIXES_SOURCE: &str = "\
+function isCount(value: int64) -> boolean {
+    current_value := value
+    return current_value matches int64
+}
+";
+
+#[test]
+fn conflicting_safe_autofixes_are_skipped_whole_and_reported() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", OVERLAPPING_FIXES_SOURCE)]);
+
+    let analyzed_target = analyze(&workspace);
+
+    assert_eq!(
+        analyzed_target.skipped_safe_autofixes_by_workspace_relative_path,
+        BTreeMap::from([(
+            "lib.copp".to_string(),
+            vec![SkippedSafeAutofix {
+                provenance: SafeAutofixProvenance {
+                    phase: DiagnosticPhase::TypeAnalysis,
+                    rule: "naming_convention",
+                },
+                reason: SafeAutofixSkipReason::ConflictsWith(SafeAutofixProvenance {
+                    phase: DiagnosticPhase::TypeAnalysis,
+                    rule: "always_true_matches",
+                }),
+            }],
+        )])
+    );
+    assert_eq!(
+        analyzed_target.canonical_source_override_by_workspace_relative_path["lib.copp"],
+        "function isCount(value: int64) -> boolean {\n    current_value := value\n    return true\n}\n"
+    );
+}
+
+#[test]
+fn safe_autofixes_that_would_move_a_comment_are_skipped() {
+    let workspace = test_workspace(&[
+        ("PACKAGE.copp", ""),
+        ("alpha/PACKAGE.copp", "exports { alphaValue }\n"),
+        (
+            "alpha/lib.copp",
+            "visible function alphaValue() -> int64 {\n    return 1\n}\n",
+        ),
+        ("zeta/PACKAGE.copp", "exports { zetaValue }\n"),
+        (
+            "zeta/lib.copp",
+            "visible function zetaValue() -> int64 {\n    return 2\n}\n",
+        ),
+        (
+            "lib.copp",
+            "\
+import workspace/zeta { zetaValue }
+import workspace/alpha { alphaValue } // for total
+
+function total() -> int64 {
+    current_total := alphaValue() + zetaValue() // kept beside its binding
+    return current_total
+}
+",
+        ),
+    ]);
+
+    let analyzed_target = analyze(&workspace);
+
+    assert_eq!(
+        analyzed_target.skipped_safe_autofixes_by_workspace_relative_path,
+        BTreeMap::from([(
+            "lib.copp".to_string(),
+            vec![SkippedSafeAutofix {
+                provenance: SafeAutofixProvenance {
+                    phase: DiagnosticPhase::SyntaxRules,
+                    rule: "import_order",
+                },
+                reason: SafeAutofixSkipReason::DisturbsComment,
+            }],
+        )])
+    );
+    assert!(
+        analyzed_target.canonical_source_override_by_workspace_relative_path["lib.copp"]
+            .contains("currentTotal := alphaValue() + zetaValue() // kept beside its binding")
+    );
+}
+
+#[test]
+fn fixing_repeats_until_no_safe_autofixes_remain() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", OVERLAPPING_FIXES_SOURCE)]);
+    let root = workspace.path().display().to_string();
+
+    let summary = fix_target_with_workspace_root(&root, Some(&root), 5).expect("fix should run");
+
+    assert_eq!(summary.pass_count, 2);
+    assert!(summary.reached_fixpoint);
+    assert!(
+        summary
+            .skipped_safe_autofixes_by_workspace_relative_path
+            .is_empty()
+    );
+    assert_eq!(
+        summary.applied_edit_count_by_workspace_relative_path,
+        BTreeMap::from([("lib.copp".to_string(), 2)])
+    );
+    assert_eq!(
+        fs::read_to_string(workspace.path().join("lib.copp")).expect("source should be read"),
+        "function isCount(value: int64) -> boolean {\n    currentValue := value\n    return true\n}\n"
+    );
+}
+
+#[test]
+fn fixing_stops_at_the_pass_limit() {
+    let workspace = test_workspace(&[("PACKAGE.copp", ""), ("lib.copp", OVERLAPPING_FIXES_SOURCE)]);
+    let root = workspace.path().display().to_string();
+
+    let summary = fix_target_with_workspace_root(&root, Some(&root), 1).expect("fix should run");
+
+    assert_eq!(summary.pass_count, 1);
+    assert!(!summary.reached_fixpoint);
+    assert_eq!(
+        summary.skipped_safe_autofixes_by_workspace_relative_path["lib.copp"].len(),
+        1
+    );
+    assert!(
+        analyze(&workspace)
+            .safe_autofix_edit_count_by_workspace_relative_path
+            .contains_key("lib.copp")
+    );
+}
+
+#[test]
+fn targets_are_analyzed_from_an_in_memory_file_system() {
+    let mut file_system = InMemoryFileSystem::new();
+    file_system.insert_file("/workspace/COPPICE_WORKSPACE", "");
+    file_system.insert_file("/workspace/app/PACKAGE.copp", "");
+    file_system.insert_file(
+        "/workspace/app/main.bin.copp",
+        "function main() -> nil {\n    print(missing)\n    return\n}\n",
+    );
+
+    let summary = analyze_target_summary_in_file_system(
+        &file_system,
+        "/workspace",
+        "app/main.bin.copp",
+        &AnalysisOptions::default(),
+    )
+    .expect("analysis should run");
+
+    assert_eq!(messages(&summary.diagnostics), ["unknown name 'missing'"]);
+    assert_eq!(summary.diagnostics[0].path, "/workspace/app/main.bin.copp");
+    assert_eq!(
+        summary.source_by_path["/workspace/app/main.bin.copp"],
+        "function main() -> nil {\n    print(missing)\n    return\n}\n"
+    );
+}
+
+// A language server analyzes a new version of the source after every edit, so
+// most names it interns are soon unused.
+#[test]
+fn repeated_analyses_do_not_grow_the_name_interner() {
+    let analyze_with_function_named = |function_name: &str| {
+        let mut file_system = InMemoryFileSystem::new();
+        file_system.insert_file("/workspace/COPPICE_WORKSPACE", "");
+        file_system.insert_file("/workspace/app/PACKAGE.copp", "");
+        file_system.insert_file(
+            "/workspace/app/lib.copp",
+            format!("function {function_name}() -> int64 {{\n    return 1\n}}\n"),
+        );
+        analyze_target_summary_in_file_system(
+            &file_system,
+            "/workspace",
+            "app/lib.copp",
+            &AnalysisOptions::default(),
+        )
+        .expect("analysis should run");
+    };
+
+    analyze_with_function_named("first");
+    let interned_name_count_after_first_analysis = interned_name_count();
+    let analysis_count = 1000;
+    for index in 0..analysis_count {
+        analyze_with_function_named(&format!("edited{index}"));
+    }
+    assert!(
+        interned_name_count() < interned_name_count_after_first_analysis + analysis_count,
+        "{} names interned after {analysis_count} analyses, {} after the first",
+        interned_name_count(),
+        interned_name_count_after_first_analysis
+    );
+}
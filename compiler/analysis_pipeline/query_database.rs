@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use compiler__packages::PackageId;
+use compiler__parsing::parse_file;
+use compiler__phase_results::PhaseOutput;
+use compiler__semantic_lowering::lower_parsed_file;
+use compiler__semantic_program::SemanticFile;
+use compiler__semantic_types::{ForbiddenBuiltin, HostFunctionContract, ImportedBinding};
+use compiler__source::{FileRole, LanguageVersion};
+use compiler__syntax::SyntaxParsedFile;
+use compiler__type_analysis as type_analysis;
+use compiler__type_analysis::TypeCheckedPackageUnit;
+
+type TypeAnalysisOutput = PhaseOutput<TypeCheckedPackageUnit>;
+
+// Memoizes per-file and per-package analysis queries across analyses, so that
+// analyzing again after an edit only recomputes what the edit can affect.
+// Source text is the only input, and every change to it is stamped with a new
+// revision. Each derived query records the revisions it read and is
+// recomputed once any of them changes:
+//
+// - `parse(file)` and `semantics(file)` read the file's source;
+// - `exports(package)` reads the sources of the package's manifest and
+//   library files, which are all that its public symbols depend on;
+// - `typecheck(file)` reads `semantics(file)` and `exports` of every package
+//   the file imports, along with the package identities and host functions
+//   that its result embeds, the builtins its package may not call, and the
+//   language version its package declares.
+//
+// Revisions only grow, so a revision recorded for one input never matches a
+// different input seen later, even across workspaces.
+#[derive(Default)]
+pub struct QueryDatabase {
+    revision: u64,
+    source_by_path: BTreeMap<PathBuf, SourceInput>,
+    parse_memo_by_path: BTreeMap<PathBuf, Memo<PhaseOutput<SyntaxParsedFile>>>,
+    semantics_memo_by_path: BTreeMap<PathBuf, Memo<PhaseOutput<SemanticFile>>>,
+    exports_memo_by_package_path: BTreeMap<String, ExportsMemo>,
+    typecheck_memo_by_path: BTreeMap<PathBuf, TypecheckMemo>,
+}
+
+struct SourceInput {
+    role: FileRole,
+    text: String,
+    changed_at: u64,
+}
+
+struct Memo<T> {
+    source_revision: u64,
+    value: T,
+}
+
+struct ExportsMemo {
+    source_revisions: Vec<(PathBuf, u64)>,
+    changed_at: u64,
+}
+
+#[derive(PartialEq)]
+struct TypecheckInputs {
+    package_id: PackageId,
+    package_path: String,
+    source_revision: u64,
+    imported_exports_revisions: Vec<(PackageId, u64)>,
+    host_function_contracts: Vec<HostFunctionContract>,
+    forbidden_builtins: Vec<ForbiddenBuiltin>,
+    language_version: LanguageVersion,
+}
+
+struct TypecheckMemo {
+    inputs: TypecheckInputs,
+    output: TypeAnalysisOutput,
+}
+
+impl QueryDatabase {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Setting the text a file already has keeps its revision, so the queries
+    // that read it stay valid.
+    pub(crate) fn set_source(&mut self, path: &Path, role: FileRole, text: &str) {
+        if let Some(source) = self.source_by_path.get(path)
+            && source.role == role
+            && source.text == text
+        {
+            return;
+        }
+        self.revision += 1;
+        self.source_by_path.insert(
+            path.to_path_buf(),
+            SourceInput {
+                role,
+                text: text.to_string(),
+                changed_at: self.revision,
+            },
+        );
+    }
+
+    pub(crate) fn parse(&mut self, path: &Path) -> PhaseOutput<SyntaxParsedFile> {
+        self.parsed(path).clone()
+    }
+
+    pub(crate) fn semantics(&mut self, path: &Path) -> PhaseOutput<SemanticFile> {
+        let source_revision = self.source(path).changed_at;
+        if let Some(memo) = self.semantics_memo_by_path.get(path)
+            && memo.source_revision == source_revision
+        {
+            return memo.value.clone();
+        }
+        let output = lower_parsed_file(&self.parsed(path).value);
+        self.semantics_memo_by_path.insert(
+            path.to_path_buf(),
+            Memo {
+                source_revision,
+                value: output.clone(),
+            },
+        );
+        output
+    }
+
+    // Returns the revision at which the public symbols of the package last
+    // changed, given the manifest and library files it currently has.
+    pub(crate) fn exports(&mut self, package_path: &str, source_paths: &[&Path]) -> u64 {
+        let source_revisions = source_paths
+            .iter()
+            .map(|path| (path.to_path_buf(), self.source(path).changed_at))
+            .collect::<Vec<_>>();
+        if let Some(memo) = self.exports_memo_by_package_path.get(package_path)
+            && memo.source_revisions == source_revisions
+        {
+            return memo.changed_at;
+        }
+        self.revision += 1;
+        self.exports_memo_by_package_path.insert(
+            package_path.to_string(),
+            ExportsMemo {
+                source_revisions,
+                changed_at: self.revision,
+            },
+        );
+        self.revision
+    }
+
+    // `imported_bindings` must be derived from the exports recorded in
+    // `imported_exports_revisions`; they are not compared themselves.
+    pub(crate) fn typecheck(
+        &mut self,
+        path: &Path,
+        package_id: PackageId,
+        package_path: &str,
+        semantic_file: &SemanticFile,
+        imported_bindings: &[ImportedBinding],
+        imported_exports_revisions: Vec<(PackageId, u64)>,
+        host_function_contracts: &[HostFunctionContract],
+        forbidden_builtins: &[ForbiddenBuiltin],
+        language_version: LanguageVersion,
+    ) -> TypeAnalysisOutput {
+        let inputs = TypecheckInputs {
+            package_id,
+            package_path: package_path.to_string(),
+            source_revision: self.source(path).changed_at,
+            imported_exports_revisions,
+            host_function_contracts: host_function_contracts.to_vec(),
+            forbidden_builtins: forbidden_builtins.to_vec(),
+            language_version,
+        };
+        if let Some(memo) = self.typecheck_memo_by_path.get(path)
+            && memo.inputs == inputs
+        {
+            return memo.output.clone();
+        }
+        let output = type_analysis::check_package_unit(
+            package_id,
+            package_path,
+            &self.source(path).text,
+            semantic_file,
+            imported_bindings,
+            host_function_contracts,
+            forbidden_builtins,
+            language_version,
+        );
+        self.typecheck_memo_by_path.insert(
+            path.to_path_buf(),
+            TypecheckMemo {
+                inputs,
+                output: output.clone(),
+            },
+        );
+        output
+    }
+
+    fn parsed(&mut self, path: &Path) -> &PhaseOutput<SyntaxParsedFile> {
+        let source = self
+            .source_by_path
+            .get(path)
+            .expect("source must be set before it is queried");
+        let is_current = self
+            .parse_memo_by_path
+            .get(path)
+            .is_some_and(|memo| memo.source_revision == source.changed_at);
+        if !is_current {
+            self.parse_memo_by_path.insert(
+                path.to_path_buf(),
+                Memo {
+                    source_revision: source.changed_at,
+                    value: parse_file(&source.text, source.role),
+                },
+            );
+        }
+        &self.parse_memo_by_path[path].value
+    }
+
+    fn source(&self, path: &Path) -> &SourceInput {
+        self.source_by_path
+            .get(path)
+            .expect("source must be set before it is queried")
+    }
+}
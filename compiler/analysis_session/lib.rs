@@ -2,13 +2,21 @@ use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use compiler__analysis_pipeline::{
-    AnalyzedTargetSummary, analyze_target_summary_with_workspace_root_and_overrides,
+    AnalysisOptions, QueryDatabase, analyze_target_summary_with_workspace_root_and_overrides,
 };
 use compiler__reports::CompilerFailure;
 
+pub use compiler__analysis_pipeline::{
+    AnalysisPhase, AnalysisProgress, AnalyzedTargetSummary, ProgressObserver,
+};
+pub use compiler__cancellation::CancellationToken;
+
+// Analyses share one query database, so reanalyzing after an edit reuses the
+// results of every query the edit cannot affect.
 pub struct AnalysisSession {
     workspace_root: Option<String>,
     source_override_by_path: BTreeMap<String, String>,
+    query_database: QueryDatabase,
 }
 
 impl AnalysisSession {
@@ -17,6 +25,7 @@ impl AnalysisSession {
         Self {
             workspace_root: workspace_root.map(|root| normalize_workspace_root(&root)),
             source_override_by_path: BTreeMap::new(),
+            query_database: QueryDatabase::new(),
         }
     }
 
@@ -38,11 +47,20 @@ impl AnalysisSession {
         self.source_override_by_path.remove(path);
     }
 
-    pub fn analyze_target(&self, path: &str) -> Result<AnalyzedTargetSummary, CompilerFailure> {
+    pub fn analyze_target(
+        &mut self,
+        path: &str,
+        progress_observer: Option<&dyn ProgressObserver>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<AnalyzedTargetSummary, CompilerFailure> {
         analyze_target_summary_with_workspace_root_and_overrides(
             path,
             self.workspace_root.as_deref(),
             &self.source_override_by_path,
+            &AnalysisOptions::default(),
+            progress_observer,
+            cancellation_token,
+            &mut self.query_database,
         )
     }
 }
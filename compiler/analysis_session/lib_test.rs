@@ -0,0 +1,45 @@
+use compiler__analysis_session::AnalysisSession;
+use tests__test_workspace::TestWorkspace;
+
+fn diagnostic_messages(session: &mut AnalysisSession, path: &str) -> Vec<String> {
+    session
+        .analyze_target(path, None, None)
+        .expect("analysis should run")
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+#[test]
+fn reanalysis_follows_edits_to_imported_packages() {
+    let workspace = TestWorkspace::new(
+        "coppice_session_test",
+        &[
+            ("lib/PACKAGE.copp", "exports { value }\n"),
+            (
+                "lib/values.copp",
+                "visible function value() -> int64 {\n    return 1\n}\n",
+            ),
+            ("app/PACKAGE.copp", ""),
+            (
+                "app/doubling.copp",
+                "import workspace/lib { value }\n\nfunction doubled() -> int64 {\n    return value() * 2\n}\n",
+            ),
+        ],
+    );
+    let mut session = AnalysisSession::new(Some(workspace.path().display().to_string()));
+    let target_path = workspace.file_path("app/doubling.copp");
+    let edited_path = workspace.file_path("lib/values.copp");
+
+    assert!(diagnostic_messages(&mut session, &target_path).is_empty());
+
+    session.open_or_update_document(
+        &edited_path,
+        "visible function value() -> string {\n    return \"one\"\n}\n".to_string(),
+    );
+    assert!(!diagnostic_messages(&mut session, &target_path).is_empty());
+
+    session.close_document(&edited_path);
+    assert!(diagnostic_messages(&mut session, &target_path).is_empty());
+}
@@ -21,7 +21,7 @@ pub fn check_bindings(
                                 "duplicate imported name '{}'; use an alias",
                                 binding.local_name
                             ),
-                            binding.span.clone(),
+                            binding.span,
                         ),
                     });
                     continue;
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use compiler__reports::{CompilerFailure, CompilerFailureKind};
+
+// Cancelling is visible to every clone, so a token can be handed to the work
+// and kept by whoever decides the work is no longer wanted, for example an
+// editor connection that has received newer text. Work only notices at its
+// next check, between files or backend passes.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), CompilerFailure> {
+        if self.is_cancelled() {
+            return Err(cancelled_failure());
+        }
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn cancelled_failure() -> CompilerFailure {
+    CompilerFailure {
+        kind: CompilerFailureKind::Cancelled,
+        message: "cancelled".to_string(),
+        path: None,
+        details: Vec::new(),
+    }
+}
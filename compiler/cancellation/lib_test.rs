@@ -0,0 +1,27 @@
+use compiler__cancellation::CancellationToken;
+use compiler__reports::CompilerFailureKind;
+
+#[test]
+fn cancelling_a_clone_cancels_the_original() {
+    let token = CancellationToken::new();
+    let clone = token.clone();
+    assert!(token.check().is_ok());
+
+    clone.cancel();
+
+    assert!(token.is_cancelled());
+    let failure = token
+        .check()
+        .expect_err("cancelled token should fail the check");
+    assert!(matches!(failure.kind, CompilerFailureKind::Cancelled));
+}
+
+#[test]
+fn new_tokens_are_independent() {
+    let first_token = CancellationToken::new();
+    let second_token = CancellationToken::new();
+
+    first_token.cancel();
+
+    assert!(!second_token.is_cancelled());
+}
@@ -1,15 +1,29 @@
-use std::{fs, process};
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::{env, io, process};
 
 use clap::{Parser, Subcommand};
 use serde::Serialize;
 
-use compiler__analysis_pipeline::analyze_target_with_workspace_root;
-use compiler__driver::{build_target_with_workspace_root, run_target_with_workspace_root};
+use compiler__analysis_pipeline::{
+    SafeAutofixSkipReason, analyze_target_with_workspace_root, fix_target_with_workspace_root,
+    regenerate_diagnostic_baseline,
+};
+use compiler__driver::{
+    BuildAnalysisResult, BuildReport, ConformanceReport, ReproducibilityCheck, TestFunctionKind,
+    build_target_with_workspace_root, load_target_with_workspace_root,
+    run_target_with_workspace_root, test_target_with_workspace_root,
+    why_not_conforming_with_workspace_root,
+};
+use compiler__executable_program::disassemble_program;
 use compiler__lsp::run_lsp_stdio;
+use compiler__package_graph::{package_import_graph, render_package_import_graph_dot};
 use compiler__reports::{
     CompilerAnalysisJsonOutput, CompilerAnalysisSafeFix, CompilerFailure, CompilerFailureKind,
-    RenderedDiagnostic, ReportFormat,
+    DiagnosticTextOptions, GraphFormat, RenderedDiagnostic, ReportFormat, render_diagnostics_text,
 };
+use compiler__runtime_interface::{ExecutionContext, ExecutionPolicy, OverflowMode};
+use compiler__source_formatting::FormattingVerificationError;
 
 #[derive(Parser)]
 #[command(version)]
@@ -31,9 +45,25 @@ enum Command {
         output_dir: Option<String>,
         #[arg(long)]
         strict: bool,
+        #[arg(long)]
+        check_reproducible: bool,
+        #[arg(long)]
+        build_report: bool,
+        #[arg(long, default_value_t = 0)]
+        context_lines: usize,
     },
     Fix {
         path: Option<String>,
+        #[arg(long, default_value_t = 10)]
+        max_iterations: usize,
+    },
+    Baseline {
+        path: Option<String>,
+    },
+    Graph {
+        path: Option<String>,
+        #[arg(long, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
     },
     Run {
         path: String,
@@ -41,6 +71,35 @@ enum Command {
         output_dir: Option<String>,
         #[arg(long)]
         strict: bool,
+        /// Deny every runtime capability. The executable reads the policy from
+        /// its environment, where COPPICE_DENY_STANDARD_OUTPUT=1 denies
+        /// printing, so running it directly without that variable is not
+        /// sandboxed.
+        #[arg(long)]
+        sandbox: bool,
+        #[arg(long)]
+        random_seed: Option<i64>,
+        #[arg(long)]
+        fixed_now: Option<i64>,
+        #[arg(long)]
+        abort_on_overflow: bool,
+    },
+    Test {
+        path: Option<String>,
+        #[arg(long)]
+        strict: bool,
+        #[arg(long)]
+        bench: bool,
+    },
+    Disassemble {
+        path: String,
+        #[arg(long)]
+        strict: bool,
+    },
+    Conformance {
+        path: String,
+        struct_name: String,
+        interface_name: String,
     },
     Lsp {
         #[arg(long)]
@@ -57,24 +116,75 @@ fn main() {
             format,
             output_dir,
             strict,
+            check_reproducible,
+            build_report,
+            context_lines,
         } => {
             let path = path.unwrap_or_else(|| ".".to_string());
-            run_build(&path, workspace_root, format, strict, output_dir.as_deref());
+            run_build(
+                &path,
+                workspace_root,
+                format,
+                strict,
+                if check_reproducible {
+                    ReproducibilityCheck::BuildTwiceAndCompare
+                } else {
+                    ReproducibilityCheck::Disabled
+                },
+                if build_report {
+                    BuildReportDisplay::Shown
+                } else {
+                    BuildReportDisplay::Hidden
+                },
+                context_lines,
+                output_dir.as_deref(),
+            );
         }
-        Command::Fix { path } => {
+        Command::Fix {
+            path,
+            max_iterations,
+        } => {
+            let path = path.unwrap_or_else(|| ".".to_string());
+            run_fix(&path, workspace_root, max_iterations);
+        }
+        Command::Baseline { path } => {
+            let path = path.unwrap_or_else(|| ".".to_string());
+            run_baseline(&path, workspace_root);
+        }
+        Command::Graph { path, format } => {
             let path = path.unwrap_or_else(|| ".".to_string());
-            run_fix(&path, workspace_root);
+            run_graph(&path, workspace_root, format);
         }
         Command::Run {
             path,
             output_dir,
             strict,
+            sandbox,
+            random_seed,
+            fixed_now,
+            abort_on_overflow,
         } => {
+            let execution_context = ExecutionContext {
+                execution_policy: if sandbox {
+                    ExecutionPolicy::sandboxed()
+                } else {
+                    ExecutionPolicy::default()
+                },
+                random_seed,
+                fixed_now_unix_seconds: fixed_now,
+                overflow_mode: if abort_on_overflow {
+                    OverflowMode::Abort
+                } else {
+                    OverflowMode::Wrap
+                },
+                output_sink: None,
+            };
             let run_result = run_target_with_workspace_root(
                 &path,
                 workspace_root,
                 output_dir.as_deref(),
                 strict,
+                &execution_context,
             );
             let has_pending_safe_autofixes = !run_result
                 .safe_autofix_edit_count_by_workspace_relative_path
@@ -83,25 +193,48 @@ fn main() {
                 render_safe_fix_warning();
             }
             match run_result.run {
-                Ok(exit_code) => {
-                    if exit_code != 0 {
-                        process::exit(exit_code);
+                Ok(run_outcome) => {
+                    if run_outcome.exit_code != 0 {
+                        process::exit(run_outcome.exit_code);
                     }
                 }
                 Err(error) => {
-                    render_compiler_failure_text(&path, &error);
+                    render_build_failure_text(
+                        &path,
+                        &error,
+                        run_result.analysis_result.as_ref(),
+                        0,
+                    );
                     process::exit(1);
                 }
             }
         }
+        Command::Test {
+            path,
+            strict,
+            bench,
+        } => {
+            let path = path.unwrap_or_else(|| ".".to_string());
+            run_test(&path, workspace_root, strict, bench);
+        }
+        Command::Disassemble { path, strict } => {
+            run_disassemble(&path, workspace_root, strict);
+        }
+        Command::Conformance {
+            path,
+            struct_name,
+            interface_name,
+        } => {
+            run_conformance(&path, &struct_name, &interface_name, workspace_root);
+        }
         Command::Lsp { stdio } => {
             run_lsp(workspace_root, stdio);
         }
     }
 }
 
-fn run_fix(path: &str, workspace_root: Option<&str>) {
-    let analyzed_target = match analyze_target_with_workspace_root(path, workspace_root) {
+fn run_fix(path: &str, workspace_root: Option<&str>, max_iterations: usize) {
+    let fix_summary = match fix_target_with_workspace_root(path, workspace_root, max_iterations) {
         Ok(value) => value,
         Err(error) => {
             render_compiler_failure_text(path, &error);
@@ -109,40 +242,236 @@ fn run_fix(path: &str, workspace_root: Option<&str>) {
         }
     };
 
-    let mut updated_file_count = 0usize;
-    for (workspace_relative_path, canonical_source_text) in
-        &analyzed_target.canonical_source_override_by_workspace_relative_path
+    let updated_file_count = fix_summary
+        .applied_edit_count_by_workspace_relative_path
+        .len();
+    if updated_file_count == 0 {
+        println!("no fixes applied");
+    } else {
+        println!("applied fixes to {updated_file_count} files");
+    }
+    for (workspace_relative_path, skipped_safe_autofixes) in
+        &fix_summary.skipped_safe_autofixes_by_workspace_relative_path
     {
-        let absolute_path = analyzed_target.workspace_root.join(workspace_relative_path);
-        if let Err(error) = fs::write(&absolute_path, canonical_source_text) {
-            let compiler_failure = CompilerFailure {
-                kind: CompilerFailureKind::WriteSource,
-                message: error.to_string(),
-                path: Some(absolute_path.display().to_string()),
-                details: Vec::new(),
+        for skipped_safe_autofix in skipped_safe_autofixes {
+            let reason = match skipped_safe_autofix.reason {
+                SafeAutofixSkipReason::ConflictsWith(conflicting_provenance) => format!(
+                    "overlaps {} fix from {}",
+                    conflicting_provenance.rule,
+                    conflicting_provenance.phase.as_str()
+                ),
+                SafeAutofixSkipReason::DisturbsComment => {
+                    "would drop or move a comment".to_string()
+                }
             };
-            render_compiler_failure_text(path, &compiler_failure);
+            println!(
+                "skipped {} fix from {} in {workspace_relative_path}: {reason}",
+                skipped_safe_autofix.provenance.rule,
+                skipped_safe_autofix.provenance.phase.as_str(),
+            );
+        }
+    }
+    for (workspace_relative_path, verification_error) in
+        &fix_summary.unverified_formatting_by_workspace_relative_path
+    {
+        let reason = match verification_error {
+            FormattingVerificationError::NotIdempotent => {
+                "formatting its output would change it again".to_string()
+            }
+            FormattingVerificationError::LineContentChanged { line_number } => {
+                format!("would change the text of line {line_number}")
+            }
+        };
+        println!("skipped formatting {workspace_relative_path}: {reason}");
+    }
+    if !fix_summary.reached_fixpoint {
+        println!(
+            "stopped after {} passes with fixes remaining",
+            fix_summary.pass_count
+        );
+    }
+}
+
+fn run_baseline(path: &str, workspace_root: Option<&str>) {
+    match regenerate_diagnostic_baseline(path, workspace_root) {
+        Ok(diagnostic_count) => println!("baseline lists {diagnostic_count} diagnostics"),
+        Err(error) => {
+            render_compiler_failure_text(path, &error);
             process::exit(1);
         }
-        updated_file_count += 1;
     }
+}
 
-    if updated_file_count == 0 {
-        println!("no fixes applied");
-    } else {
-        println!("applied fixes to {updated_file_count} files");
+fn run_graph(path: &str, workspace_root: Option<&str>, graph_format: GraphFormat) {
+    let analyzed_target = match analyze_target_with_workspace_root(path, workspace_root) {
+        Ok(value) => value,
+        Err(error) => {
+            render_compiler_failure_text(path, &error);
+            process::exit(1);
+        }
+    };
+
+    let graph = package_import_graph(
+        analyzed_target.package_path_by_file.values(),
+        &analyzed_target.resolved_imports,
+    );
+    match graph_format {
+        GraphFormat::Dot => print!("{}", render_package_import_graph_dot(&graph)),
+        GraphFormat::Json => {
+            let mut bytes = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+            let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+            graph.serialize(&mut serializer).unwrap();
+            println!("{}", String::from_utf8(bytes).unwrap());
+        }
+    }
+}
+
+// Benches report how long they took; tests only whether they passed, so that
+// the output of a passing run does not change between runs.
+fn run_test(path: &str, workspace_root: Option<&str>, strict: bool, include_benches: bool) {
+    let test_result =
+        test_target_with_workspace_root(path, workspace_root, strict, include_benches);
+    let has_pending_safe_autofixes = !test_result
+        .safe_autofix_edit_count_by_workspace_relative_path
+        .is_empty();
+    if !strict && has_pending_safe_autofixes {
+        render_safe_fix_warning();
+    }
+    let file_outcomes = match test_result.tests {
+        Ok(value) => value,
+        Err(error) => {
+            render_compiler_failure_text(path, &error);
+            process::exit(1);
+        }
+    };
+    let mut passed_count = 0;
+    let mut failed_count = 0;
+    for file_outcome in &file_outcomes {
+        println!("{}", file_outcome.path);
+        for function_outcome in &file_outcome.function_outcomes {
+            let name = &function_outcome.function.name;
+            if let Some(failure) = &function_outcome.failure {
+                failed_count += 1;
+                println!("  FAIL {name}");
+                println!("       {failure}");
+            } else if function_outcome.function.kind == TestFunctionKind::Bench {
+                println!(
+                    "  bench {name} ({}ms)",
+                    function_outcome.elapsed.as_millis()
+                );
+            } else {
+                passed_count += 1;
+                println!("  ok   {name}");
+            }
+        }
+        println!();
+    }
+    println!("{passed_count} passed, {failed_count} failed");
+    if failed_count > 0 {
+        process::exit(1);
+    }
+}
+
+// Prints the executable program that a build of the binary entrypoint would
+// hand to code generation, before any optimization.
+fn run_disassemble(path: &str, workspace_root: Option<&str>, strict: bool) {
+    let load_result = load_target_with_workspace_root(path, workspace_root, strict, &[]);
+    if !strict
+        && !load_result
+            .safe_autofix_edit_count_by_workspace_relative_path
+            .is_empty()
+    {
+        render_safe_fix_warning();
+    }
+    match load_result.program {
+        Ok(program) => print!("{}", disassemble_program(&program)),
+        Err(error) => {
+            render_compiler_failure_text(path, &error);
+            process::exit(1);
+        }
+    }
+}
+
+// Exits nonzero unless the struct conforms, so that the command can guard an
+// interface a struct is meant to satisfy before it declares so.
+fn run_conformance(
+    path: &str,
+    struct_name: &str,
+    interface_name: &str,
+    workspace_root: Option<&str>,
+) {
+    match why_not_conforming_with_workspace_root(path, workspace_root, struct_name, interface_name)
+    {
+        Ok(report) => {
+            print!("{}", conformance_report_text(&report));
+            if !report.conforms() {
+                process::exit(1);
+            }
+        }
+        Err(error) => {
+            render_compiler_failure_text(path, &error);
+            process::exit(1);
+        }
     }
 }
 
+fn conformance_report_text(report: &ConformanceReport) -> String {
+    if report.conforms() {
+        let declaration_note = if report.declares_implementation {
+            ""
+        } else {
+            ", but does not declare that it implements it"
+        };
+        return format!(
+            "struct '{}' conforms to interface '{}'{declaration_note}\n",
+            report.struct_name, report.interface_name
+        );
+    }
+    let mut text = format!(
+        "struct '{}' does not conform to interface '{}'\n",
+        report.struct_name, report.interface_name
+    );
+    for method_problem in &report.method_problems {
+        let Some(found_signature) = &method_problem.found_signature else {
+            writeln!(
+                text,
+                "  missing method {}",
+                method_problem.expected_signature
+            )
+            .unwrap();
+            continue;
+        };
+        writeln!(
+            text,
+            "  method '{}' expected {}, found {}",
+            method_problem.method_name, method_problem.expected_signature, found_signature
+        )
+        .unwrap();
+        for difference in &method_problem.differences {
+            writeln!(text, "    {difference}").unwrap();
+        }
+    }
+    text
+}
+
 fn run_build(
     path: &str,
     workspace_root: Option<&str>,
     report_format: ReportFormat,
     strict: bool,
+    reproducibility_check: ReproducibilityCheck,
+    build_report_display: BuildReportDisplay,
+    context_line_count: usize,
     output_directory: Option<&str>,
 ) {
-    let build_result =
-        build_target_with_workspace_root(path, workspace_root, output_directory, strict);
+    let build_result = build_target_with_workspace_root(
+        path,
+        workspace_root,
+        output_directory,
+        strict,
+        reproducibility_check,
+    );
     let safe_autofixes_by_path = safe_fix_summaries_from_edit_counts(
         &build_result.safe_autofix_edit_count_by_workspace_relative_path,
     );
@@ -155,8 +484,11 @@ fn run_build(
         Ok(()) => {
             if let Some(analysis_result) = build_result.analysis_result {
                 let has_diagnostics = !analysis_result.diagnostics.is_empty();
-                let strict_policy_failure =
-                    strict && has_pending_safe_autofixes && !has_diagnostics;
+                let has_errors = analysis_result
+                    .diagnostics
+                    .iter()
+                    .any(RenderedDiagnostic::is_error);
+                let strict_policy_failure = strict && has_pending_safe_autofixes && !has_errors;
                 let strict_policy_error = strict_policy_failure.then(|| CompilerFailure {
                     kind: CompilerFailureKind::BuildFailed,
                     message: "build failed due to pending safe autofixes".to_string(),
@@ -176,10 +508,21 @@ fn run_build(
                 match report_format {
                     ReportFormat::Text => {
                         if has_diagnostics {
-                            render_diagnostics_text(
-                                &analysis_result.diagnostics,
-                                &analysis_result.source_by_path,
+                            let options = DiagnosticTextOptions {
+                                color: stderr_supports_color(),
+                                context_line_count,
+                            };
+                            eprint!(
+                                "{}",
+                                render_diagnostics_text(
+                                    &analysis_result.diagnostics,
+                                    &analysis_result.source_by_path,
+                                    options,
+                                )
                             );
+                        }
+                        if has_errors {
+                            // The diagnostics above already explain the failure.
                         } else if let Some(error) = &strict_policy_error {
                             render_compiler_failure_text(path, error);
                         } else if let Some(success_message) = build_result.success_message {
@@ -188,7 +531,7 @@ fn run_build(
                     }
                     ReportFormat::Json => {
                         let output = CompilerAnalysisJsonOutput {
-                            ok: !has_diagnostics && !strict_policy_failure,
+                            ok: !has_errors && !strict_policy_failure,
                             diagnostics: analysis_result.diagnostics,
                             safe_fixes: safe_autofixes_by_path,
                             error: strict_policy_error,
@@ -196,7 +539,7 @@ fn run_build(
                         print_json_output_to_stderr(&output);
                     }
                 }
-                if has_diagnostics || strict_policy_failure {
+                if has_errors || strict_policy_failure {
                     process::exit(1);
                 }
                 return;
@@ -214,16 +557,29 @@ fn run_build(
                     print_json_output_to_stderr(&output);
                 }
             }
+            if build_report_display == BuildReportDisplay::Shown
+                && let Some(build_report) = &build_result.build_report
+            {
+                print!("{}", build_report_text(build_report));
+            }
         }
         Err(error) => {
             match report_format {
                 ReportFormat::Text => {
-                    render_compiler_failure_text(path, &error);
+                    render_build_failure_text(
+                        path,
+                        &error,
+                        build_result.analysis_result.as_ref(),
+                        context_line_count,
+                    );
                 }
                 ReportFormat::Json => {
                     let output = CompilerAnalysisJsonOutput {
                         ok: false,
-                        diagnostics: Vec::new(),
+                        diagnostics: build_result
+                            .analysis_result
+                            .map(|analysis_result| analysis_result.diagnostics)
+                            .unwrap_or_default(),
                         safe_fixes: safe_autofixes_by_path,
                         error: Some(error),
                     };
@@ -235,6 +591,71 @@ fn run_build(
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BuildReportDisplay {
+    Hidden,
+    Shown,
+}
+
+// Lists what the backend compiled and how long each of its passes took. Only
+// binary entrypoints produce a report.
+fn build_report_text(build_report: &BuildReport) -> String {
+    let mut text = String::new();
+    writeln!(text, "functions: {}", build_report.function_count).unwrap();
+    writeln!(
+        text,
+        "executable size: {} bytes",
+        build_report.executable_size_bytes
+    )
+    .unwrap();
+    writeln!(text, "statements:").unwrap();
+    for function_statistics in &build_report.function_statistics {
+        writeln!(
+            text,
+            "    {}: {}",
+            function_display_name(
+                function_statistics.package_path.as_str(),
+                &function_statistics.symbol_name
+            ),
+            function_statistics.statement_count
+        )
+        .unwrap();
+    }
+    writeln!(text, "generic instantiations:").unwrap();
+    for generic_instantiation_count in &build_report.generic_instantiation_counts {
+        let callable_reference = &generic_instantiation_count.callable_reference;
+        writeln!(
+            text,
+            "    {}: {}",
+            function_display_name(
+                callable_reference.package_path.as_str(),
+                callable_reference.symbol_name.as_str()
+            ),
+            generic_instantiation_count.instantiation_count
+        )
+        .unwrap();
+    }
+    writeln!(text, "pass timings:").unwrap();
+    for pass_timing in &build_report.pass_timings {
+        writeln!(
+            text,
+            "    {}: {:.3} ms",
+            pass_timing.pass_name,
+            pass_timing.duration.as_secs_f64() * 1000.0
+        )
+        .unwrap();
+    }
+    text
+}
+
+fn function_display_name(package_path: &str, symbol_name: &str) -> String {
+    if package_path.is_empty() {
+        symbol_name.to_string()
+    } else {
+        format!("{package_path}::{symbol_name}")
+    }
+}
+
 fn print_json_output_to_stderr<T: Serialize>(output: &T) {
     let mut bytes = Vec::new();
     let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
@@ -271,28 +692,34 @@ fn run_lsp(workspace_root: Option<&str>, stdio: bool) {
     }
 }
 
-fn render_diagnostics_text(
-    diagnostics: &[RenderedDiagnostic],
-    source_by_path: &std::collections::BTreeMap<String, String>,
+fn stderr_supports_color() -> bool {
+    env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal()
+}
+
+// A build rejected by diagnostics is explained by the diagnostics themselves,
+// rendered against their source like a successful build's warnings.
+fn render_build_failure_text(
+    path: &str,
+    error: &CompilerFailure,
+    analysis_result: Option<&BuildAnalysisResult>,
+    context_line_count: usize,
 ) {
-    for diagnostic in diagnostics {
-        let source = source_by_path
-            .get(&diagnostic.path)
-            .map_or("", String::as_str);
-        let line = diagnostic.span.line;
-        let column = diagnostic.span.column;
-        let line_text = source.lines().nth(line - 1).unwrap_or("");
-        eprintln!(
-            "{path}:{line}:{column}: error: {message}",
-            path = diagnostic.path,
-            message = diagnostic.message
-        );
-        eprintln!("  {line_text}");
-        if !line_text.is_empty() {
-            let caret = " ".repeat(column.saturating_sub(1));
-            eprintln!("  {caret}^");
-        }
-    }
+    let Some(analysis_result) = analysis_result else {
+        render_compiler_failure_text(path, error);
+        return;
+    };
+    let options = DiagnosticTextOptions {
+        color: stderr_supports_color(),
+        context_line_count,
+    };
+    eprint!(
+        "{}",
+        render_diagnostics_text(
+            &analysis_result.diagnostics,
+            &analysis_result.source_by_path,
+            options,
+        )
+    );
 }
 
 fn render_compiler_failure_text(path: &str, error: &CompilerFailure) {
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use compiler__executable_program::{
+    ExecutableCallTarget, ExecutableExpression, ExecutableProgram, ExecutableStatement,
+    ExecutableTypeReference, ExecutableVisitor, visit_expression_children, visit_program,
+    visit_statement_children, visit_statements,
+};
+use compiler__interned_names::PackagePath;
+use compiler__program_references::CallableReference;
+
+#[derive(Clone, Debug)]
+pub struct BuildReport {
+    pub function_count: usize,
+    pub function_statistics: Vec<FunctionBuildStatistics>,
+    pub generic_instantiation_counts: Vec<GenericInstantiationCount>,
+    pub pass_timings: Vec<PassTiming>,
+    pub executable_size_bytes: u64,
+}
+
+// Methods are reported alongside functions with a `Struct.method` symbol name.
+#[derive(Clone, Debug)]
+pub struct FunctionBuildStatistics {
+    pub package_path: PackagePath,
+    pub symbol_name: String,
+    pub statement_count: usize,
+}
+
+// Counts distinct type argument lists a generic function is called with.
+#[derive(Clone, Debug)]
+pub struct GenericInstantiationCount {
+    pub callable_reference: CallableReference,
+    pub instantiation_count: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct PassTiming {
+    pub pass_name: &'static str,
+    pub duration: Duration,
+}
+
+#[derive(Default)]
+pub(crate) struct PassTimer {
+    pass_timings: Vec<PassTiming>,
+}
+
+impl PassTimer {
+    pub(crate) fn time<T>(&mut self, pass_name: &'static str, pass: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = pass();
+        self.pass_timings.push(PassTiming {
+            pass_name,
+            duration: start.elapsed(),
+        });
+        result
+    }
+}
+
+pub(crate) fn build_report(
+    program: &ExecutableProgram,
+    pass_timer: PassTimer,
+    executable_size_bytes: u64,
+) -> BuildReport {
+    let mut function_statistics = Vec::new();
+    for function_declaration in &program.function_declarations {
+        function_statistics.push(FunctionBuildStatistics {
+            package_path: function_declaration.callable_reference.package_path.clone(),
+            symbol_name: function_declaration
+                .callable_reference
+                .symbol_name
+                .to_string(),
+            statement_count: statement_count(&function_declaration.statements),
+        });
+    }
+    for struct_declaration in &program.struct_declarations {
+        for method in &struct_declaration.methods {
+            function_statistics.push(FunctionBuildStatistics {
+                package_path: struct_declaration.struct_reference.package_path.clone(),
+                symbol_name: format!(
+                    "{}.{}",
+                    struct_declaration.struct_reference.symbol_name, method.name
+                ),
+                statement_count: statement_count(&method.statements),
+            });
+        }
+    }
+
+    let mut generic_instantiation_collector = GenericInstantiationCollector::default();
+    visit_program(&mut generic_instantiation_collector, program);
+
+    BuildReport {
+        function_count: program.function_declarations.len(),
+        function_statistics,
+        generic_instantiation_counts: generic_instantiation_collector
+            .instantiations_by_callable
+            .into_iter()
+            .map(
+                |(callable_reference, instantiations)| GenericInstantiationCount {
+                    callable_reference,
+                    instantiation_count: instantiations.len(),
+                },
+            )
+            .collect(),
+        pass_timings: pass_timer.pass_timings,
+        executable_size_bytes,
+    }
+}
+
+#[derive(Default)]
+struct GenericInstantiationCollector {
+    instantiations_by_callable: BTreeMap<CallableReference, Vec<Vec<ExecutableTypeReference>>>,
+}
+
+impl ExecutableVisitor for GenericInstantiationCollector {
+    fn visit_expression(&mut self, expression: &ExecutableExpression) {
+        visit_expression_children(self, expression);
+        let ExecutableExpression::Call {
+            call_target: Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }),
+            type_arguments,
+            ..
+        } = expression
+        else {
+            return;
+        };
+        if type_arguments.is_empty() {
+            return;
+        }
+        let instantiations = self
+            .instantiations_by_callable
+            .entry(callable_reference.clone())
+            .or_default();
+        if !instantiations.contains(type_arguments) {
+            instantiations.push(type_arguments.clone());
+        }
+    }
+}
+
+// Nested statements count individually, so an `if` with two statements in
+// its body counts as three.
+#[derive(Default)]
+struct StatementCounter {
+    statement_count: usize,
+}
+
+impl ExecutableVisitor for StatementCounter {
+    fn visit_statement(&mut self, statement: &ExecutableStatement) {
+        self.statement_count += 1;
+        visit_statement_children(self, statement);
+    }
+}
+
+fn statement_count(statements: &[ExecutableStatement]) -> usize {
+    let mut statement_counter = StatementCounter::default();
+    visit_statements(&mut statement_counter, statements);
+    statement_counter.statement_count
+}
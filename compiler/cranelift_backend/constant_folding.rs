@@ -0,0 +1,117 @@
+use compiler__executable_program::{
+    ExecutableBinaryOperator, ExecutableExpression, ExecutableFold, ExecutableProgram,
+    ExecutableUnaryOperator, fold_expression_children, fold_program,
+};
+
+struct ConstantFolder;
+
+// Operands are folded before their parent, so nested constant expressions
+// collapse in a single pass.
+impl ExecutableFold for ConstantFolder {
+    fn fold_expression(&mut self, expression: ExecutableExpression) -> ExecutableExpression {
+        let expression = fold_expression_children(self, expression);
+        folded_expression(&expression).unwrap_or(expression)
+    }
+}
+
+pub(crate) fn fold_constants(program: ExecutableProgram) -> ExecutableProgram {
+    fold_program(&mut ConstantFolder, program)
+}
+
+fn folded_expression(expression: &ExecutableExpression) -> Option<ExecutableExpression> {
+    match expression {
+        ExecutableExpression::Unary {
+            operator,
+            expression,
+            ..
+        } => match (operator, expression.as_ref()) {
+            (ExecutableUnaryOperator::Not, ExecutableExpression::BooleanLiteral { value }) => {
+                Some(ExecutableExpression::BooleanLiteral { value: !value })
+            }
+            (ExecutableUnaryOperator::Negate, ExecutableExpression::IntegerLiteral { value }) => {
+                Some(ExecutableExpression::IntegerLiteral {
+                    value: value.checked_neg()?,
+                })
+            }
+            _ => None,
+        },
+        ExecutableExpression::Binary {
+            operator,
+            left,
+            right,
+            ..
+        } => match (left.as_ref(), right.as_ref()) {
+            (
+                ExecutableExpression::IntegerLiteral { value: left },
+                ExecutableExpression::IntegerLiteral { value: right },
+            ) => folded_integer_binary_expression(*operator, *left, *right),
+            (
+                ExecutableExpression::BooleanLiteral { value: left },
+                ExecutableExpression::BooleanLiteral { value: right },
+            ) => folded_boolean_binary_expression(*operator, *left, *right),
+            (
+                ExecutableExpression::StringLiteral { value: left },
+                ExecutableExpression::StringLiteral { value: right },
+            ) if matches!(operator, ExecutableBinaryOperator::Add)
+                && !left.contains('\0')
+                && !right.contains('\0') =>
+            {
+                Some(ExecutableExpression::StringLiteral {
+                    value: format!("{left}{right}"),
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Shift amounts are masked to the low six bits as Cranelift does. Arithmetic
+// that overflows or divides by zero is left unfolded, since whether it wraps or
+// aborts is only known when the program runs.
+fn folded_integer_binary_expression(
+    operator: ExecutableBinaryOperator,
+    left: i64,
+    right: i64,
+) -> Option<ExecutableExpression> {
+    let integer = |value| Some(ExecutableExpression::IntegerLiteral { value });
+    let boolean = |value| Some(ExecutableExpression::BooleanLiteral { value });
+    match operator {
+        ExecutableBinaryOperator::Add => integer(left.checked_add(right)?),
+        ExecutableBinaryOperator::Subtract => integer(left.checked_sub(right)?),
+        ExecutableBinaryOperator::Multiply => integer(left.checked_mul(right)?),
+        ExecutableBinaryOperator::Divide => integer(left.checked_div(right)?),
+        ExecutableBinaryOperator::Modulo => integer(left.checked_rem(right)?),
+        ExecutableBinaryOperator::BitwiseAnd => integer(left & right),
+        ExecutableBinaryOperator::BitwiseOr => integer(left | right),
+        ExecutableBinaryOperator::BitwiseXor => integer(left ^ right),
+        ExecutableBinaryOperator::ShiftLeft => integer(left.wrapping_shl(shift_amount(right))),
+        ExecutableBinaryOperator::ShiftRight => integer(left.wrapping_shr(shift_amount(right))),
+        ExecutableBinaryOperator::EqualEqual => boolean(left == right),
+        ExecutableBinaryOperator::NotEqual => boolean(left != right),
+        ExecutableBinaryOperator::LessThan => boolean(left < right),
+        ExecutableBinaryOperator::LessThanOrEqual => boolean(left <= right),
+        ExecutableBinaryOperator::GreaterThan => boolean(left > right),
+        ExecutableBinaryOperator::GreaterThanOrEqual => boolean(left >= right),
+        ExecutableBinaryOperator::And | ExecutableBinaryOperator::Or => None,
+    }
+}
+
+fn shift_amount(right: i64) -> u32 {
+    u32::try_from(right & 63).unwrap_or_default()
+}
+
+fn folded_boolean_binary_expression(
+    operator: ExecutableBinaryOperator,
+    left: bool,
+    right: bool,
+) -> Option<ExecutableExpression> {
+    let value = match operator {
+        ExecutableBinaryOperator::And => left && right,
+        ExecutableBinaryOperator::Or => left || right,
+        ExecutableBinaryOperator::EqualEqual => left == right,
+        ExecutableBinaryOperator::NotEqual => left != right,
+        _ => return None,
+    };
+    Some(ExecutableExpression::BooleanLiteral { value })
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use compiler__executable_program::{
+    ExecutableCallTarget, ExecutableExpression, ExecutableFold, ExecutableProgram,
+    ExecutableStatement, ExecutableVisitor, fold_each_statement, fold_program,
+    visit_expression_children, visit_statements,
+};
+use compiler__program_references::CallableReference;
+
+struct DeadStatementRemover;
+
+impl ExecutableFold for DeadStatementRemover {
+    fn fold_statements(
+        &mut self,
+        statements: Vec<ExecutableStatement>,
+    ) -> Vec<ExecutableStatement> {
+        let mut statements = fold_each_statement(self, statements);
+        remove_dead_statements(&mut statements);
+        statements
+    }
+}
+
+#[derive(Default)]
+struct CallableReferenceCollector {
+    callable_references: Vec<CallableReference>,
+}
+
+impl ExecutableVisitor for CallableReferenceCollector {
+    fn visit_expression(&mut self, expression: &ExecutableExpression) {
+        visit_expression_children(self, expression);
+        match expression {
+            ExecutableExpression::Identifier {
+                callable_reference: Some(callable_reference),
+                ..
+            }
+            | ExecutableExpression::Call {
+                call_target: Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }),
+                ..
+            } => self.callable_references.push(callable_reference.clone()),
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn eliminate_dead_code(program: ExecutableProgram) -> ExecutableProgram {
+    let mut program = fold_program(&mut DeadStatementRemover, program);
+    remove_unreachable_functions(&mut program);
+    program
+}
+
+// Branches are never flattened into the enclosing block because their
+// bindings are scoped to the branch.
+fn remove_dead_statements(statements: &mut Vec<ExecutableStatement>) {
+    if let Some(terminator_index) = statements.iter().position(|statement| {
+        matches!(
+            statement,
+            ExecutableStatement::Return { .. }
+                | ExecutableStatement::Break
+                | ExecutableStatement::Continue
+        )
+    }) {
+        statements.truncate(terminator_index + 1);
+    }
+
+    let mut retained_statements = Vec::new();
+    for statement in statements.drain(..) {
+        match statement {
+            ExecutableStatement::If {
+                condition: ExecutableExpression::BooleanLiteral { value: true },
+                then_statements,
+                else_statements: Some(_),
+            } => retained_statements.push(ExecutableStatement::If {
+                condition: ExecutableExpression::BooleanLiteral { value: true },
+                then_statements,
+                else_statements: None,
+            }),
+            ExecutableStatement::If {
+                condition: ExecutableExpression::BooleanLiteral { value: false },
+                else_statements,
+                ..
+            } => {
+                if let Some(else_statements) = else_statements {
+                    retained_statements.push(ExecutableStatement::If {
+                        condition: ExecutableExpression::BooleanLiteral { value: true },
+                        then_statements: else_statements,
+                        else_statements: None,
+                    });
+                }
+            }
+            ExecutableStatement::For {
+                condition: Some(ExecutableExpression::BooleanLiteral { value: false }),
+                ..
+            } => {}
+            statement => retained_statements.push(statement),
+        }
+    }
+    *statements = retained_statements;
+}
+
+// Methods and constant initializers are always kept, so they act as roots
+// alongside the entrypoint.
+fn remove_unreachable_functions(program: &mut ExecutableProgram) {
+    let mut root_collector = CallableReferenceCollector::default();
+    for constant_declaration in &program.constant_declarations {
+        root_collector.visit_expression(&constant_declaration.initializer);
+    }
+    for struct_declaration in &program.struct_declarations {
+        for method in &struct_declaration.methods {
+            visit_statements(&mut root_collector, &method.statements);
+        }
+    }
+    let mut pending_callable_references = root_collector.callable_references;
+    pending_callable_references.push(program.entrypoint_callable_reference.clone());
+
+    let mut referenced_callable_references_by_function = BTreeMap::new();
+    for function_declaration in &program.function_declarations {
+        let mut collector = CallableReferenceCollector::default();
+        visit_statements(&mut collector, &function_declaration.statements);
+        referenced_callable_references_by_function.insert(
+            function_declaration.callable_reference.clone(),
+            collector.callable_references,
+        );
+    }
+
+    let mut reachable_callable_references = BTreeSet::new();
+    while let Some(callable_reference) = pending_callable_references.pop() {
+        if !reachable_callable_references.insert(callable_reference.clone()) {
+            continue;
+        }
+        if let Some(referenced_callable_references) =
+            referenced_callable_references_by_function.get(&callable_reference)
+        {
+            pending_callable_references.extend(referenced_callable_references.iter().cloned());
+        }
+    }
+
+    program
+        .function_declarations
+        .retain(|function_declaration| {
+            reachable_callable_references.contains(&function_declaration.callable_reference)
+        });
+}
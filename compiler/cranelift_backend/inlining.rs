@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use compiler__executable_program::{
+    ExecutableCallTarget, ExecutableExpression, ExecutableFold, ExecutableFunctionDeclaration,
+    ExecutableProgram, ExecutableStatement, ExecutableTypeReference, fold_expression_children,
+    fold_program,
+};
+use compiler__program_references::CallableReference;
+
+struct InlineCandidate {
+    parameter_type_by_name: BTreeMap<String, ExecutableTypeReference>,
+    parameter_names: Vec<String>,
+    body: ExecutableExpression,
+}
+
+struct Inliner {
+    inline_candidate_by_callable_reference: BTreeMap<CallableReference, InlineCandidate>,
+}
+
+impl ExecutableFold for Inliner {
+    fn fold_expression(&mut self, expression: ExecutableExpression) -> ExecutableExpression {
+        let expression = fold_expression_children(self, expression);
+        self.inlined_call(&expression).unwrap_or(expression)
+    }
+}
+
+impl Inliner {
+    fn inlined_call(&self, expression: &ExecutableExpression) -> Option<ExecutableExpression> {
+        let ExecutableExpression::Call {
+            call_target: Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }),
+            arguments,
+            type_arguments,
+            ..
+        } = expression
+        else {
+            return None;
+        };
+        if !type_arguments.is_empty() {
+            return None;
+        }
+        let inline_candidate = self
+            .inline_candidate_by_callable_reference
+            .get(callable_reference)?;
+        inlined_call_body(inline_candidate, arguments)
+    }
+}
+
+pub(crate) fn inline_small_functions(
+    program: ExecutableProgram,
+    body_size_limit: usize,
+) -> ExecutableProgram {
+    let inline_candidate_by_callable_reference = program
+        .function_declarations
+        .iter()
+        .filter_map(|function_declaration| {
+            inline_candidate(function_declaration, body_size_limit)
+                .map(|candidate| (function_declaration.callable_reference.clone(), candidate))
+        })
+        .collect::<BTreeMap<_, _>>();
+    if inline_candidate_by_callable_reference.is_empty() {
+        return program;
+    }
+    fold_program(
+        &mut Inliner {
+            inline_candidate_by_callable_reference,
+        },
+        program,
+    )
+}
+
+// Only leaf functions of the form `return <expression>` over scalar, immutable
+// parameters are inlined. Their bodies cannot mutate state, call other
+// functions, or refer to names other than their own parameters, so
+// substituting side-effect-free arguments preserves evaluation semantics.
+fn inline_candidate(
+    function_declaration: &ExecutableFunctionDeclaration,
+    body_size_limit: usize,
+) -> Option<InlineCandidate> {
+    if !function_declaration.type_parameter_names.is_empty()
+        || !is_scalar_type(&function_declaration.return_type)
+    {
+        return None;
+    }
+    let [ExecutableStatement::Return { value }] = function_declaration.statements.as_slice() else {
+        return None;
+    };
+    let mut parameter_type_by_name = BTreeMap::new();
+    for parameter in &function_declaration.parameters {
+        if parameter.mutable || !is_scalar_type(&parameter.type_reference) {
+            return None;
+        }
+        parameter_type_by_name.insert(parameter.name.clone(), parameter.type_reference.clone());
+    }
+    let body_size = leaf_expression_size(value, &parameter_type_by_name)?;
+    if body_size > body_size_limit {
+        return None;
+    }
+    Some(InlineCandidate {
+        parameter_names: function_declaration
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.clone())
+            .collect(),
+        parameter_type_by_name,
+        body: value.clone(),
+    })
+}
+
+fn is_scalar_type(type_reference: &ExecutableTypeReference) -> bool {
+    matches!(
+        type_reference,
+        ExecutableTypeReference::Int64
+            | ExecutableTypeReference::Boolean
+            | ExecutableTypeReference::String
+    )
+}
+
+fn leaf_expression_size(
+    expression: &ExecutableExpression,
+    parameter_type_by_name: &BTreeMap<String, ExecutableTypeReference>,
+) -> Option<usize> {
+    match expression {
+        ExecutableExpression::IntegerLiteral { .. }
+        | ExecutableExpression::BooleanLiteral { .. }
+        | ExecutableExpression::StringLiteral { .. } => Some(1),
+        ExecutableExpression::Identifier {
+            name,
+            constant_reference: None,
+            callable_reference: None,
+            type_reference,
+        } if parameter_type_by_name.get(name) == Some(type_reference) => Some(1),
+        ExecutableExpression::Unary { expression, .. } => {
+            Some(leaf_expression_size(expression, parameter_type_by_name)? + 1)
+        }
+        ExecutableExpression::Binary { left, right, .. } => Some(
+            leaf_expression_size(left, parameter_type_by_name)?
+                + leaf_expression_size(right, parameter_type_by_name)?
+                + 1,
+        ),
+        _ => None,
+    }
+}
+
+// Arguments are substituted directly into the body, so they must be free of
+// side effects and already have the exact parameter type: literals and plain
+// identifiers can be duplicated, dropped, or reordered without observable
+// difference.
+fn inlined_call_body(
+    inline_candidate: &InlineCandidate,
+    arguments: &[ExecutableExpression],
+) -> Option<ExecutableExpression> {
+    if arguments.len() != inline_candidate.parameter_names.len() {
+        return None;
+    }
+    let mut argument_by_parameter_name = BTreeMap::new();
+    for (parameter_name, argument) in inline_candidate.parameter_names.iter().zip(arguments) {
+        let parameter_type = inline_candidate
+            .parameter_type_by_name
+            .get(parameter_name)?;
+        let argument_matches_parameter_type = match argument {
+            ExecutableExpression::IntegerLiteral { .. } => {
+                *parameter_type == ExecutableTypeReference::Int64
+            }
+            ExecutableExpression::BooleanLiteral { .. } => {
+                *parameter_type == ExecutableTypeReference::Boolean
+            }
+            ExecutableExpression::StringLiteral { .. } => {
+                *parameter_type == ExecutableTypeReference::String
+            }
+            ExecutableExpression::Identifier { type_reference, .. } => {
+                type_reference == parameter_type
+            }
+            _ => false,
+        };
+        if !argument_matches_parameter_type {
+            return None;
+        }
+        argument_by_parameter_name.insert(parameter_name.as_str(), argument);
+    }
+    let mut inlined_body = inline_candidate.body.clone();
+    substitute_parameters(&mut inlined_body, &argument_by_parameter_name);
+    Some(inlined_body)
+}
+
+fn substitute_parameters(
+    expression: &mut ExecutableExpression,
+    argument_by_parameter_name: &BTreeMap<&str, &ExecutableExpression>,
+) {
+    match expression {
+        ExecutableExpression::Identifier { name, .. } => {
+            if let Some(argument) = argument_by_parameter_name.get(name.as_str()) {
+                *expression = (*argument).clone();
+            }
+        }
+        ExecutableExpression::Unary { expression, .. } => {
+            substitute_parameters(expression, argument_by_parameter_name);
+        }
+        ExecutableExpression::Binary { left, right, .. } => {
+            substitute_parameters(left, argument_by_parameter_name);
+            substitute_parameters(right, argument_by_parameter_name);
+        }
+        _ => {}
+    }
+}
@@ -1,73 +1,369 @@
-use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
 
-use compiler__executable_program::ExecutableProgram;
-use compiler__reports::{CompilerFailure, CompilerFailureKind};
+use compiler__cancellation::CancellationToken;
+use compiler__executable_program::{ExecutableProgram, validate_program};
+use compiler__file_system::FileSystem;
+use compiler__reports::{CompilerFailure, CompilerFailureDetail, CompilerFailureKind};
+use compiler__runtime_interface::{
+    ABORT_EXIT_CODE, ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE, ExecutionContext,
+    FIXED_NOW_ENVIRONMENT_VARIABLE, OverflowMode, RANDOM_SEED_ENVIRONMENT_VARIABLE,
+    RuntimeCapability, runtime_contract_lines,
+};
 
+mod build_report;
 mod builtin_conversion;
+mod constant_folding;
+mod dead_code_elimination;
+mod inlining;
 mod linker_bridge;
 mod object_emission;
+mod optimization;
 mod runtime_interface_emission;
 
+use build_report::{PassTimer, build_report};
 use linker_bridge::link_executable;
 use object_emission::{emit_object_bytes, ensure_program_supported};
+use optimization::optimize_program;
+
+pub use build_report::{
+    BuildReport, FunctionBuildStatistics, GenericInstantiationCount, PassTiming,
+};
+pub use optimization::{OptimizationLevel, OptimizationOptions};
 
 pub struct BuiltCraneliftProgram {
     pub binary_path: PathBuf,
+    pub report: BuildReport,
 }
 
 pub struct BuildArtifactIdentity {
     pub executable_stem: String,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReproducibilityCheck {
+    Disabled,
+    BuildTwiceAndCompare,
+}
+
+const REPRODUCIBILITY_CHECK_DIRECTORY_NAME: &str = ".reproducibility_check";
+
+// Starts the runtime contract record embedded in every executable. The record
+// continues with one contract line per line and ends at a NUL byte.
+pub(crate) const RUNTIME_CONTRACT_RECORD_HEADER: &str = "coppice runtime contracts\n";
+
+// The linker runs as a separate process and reads the object file from
+// `build_directory` on disk, so `file_system` must write through to the disk
+// there. Cancellation is checked between passes and between emitted functions;
+// a running linker is not interrupted.
 pub fn build_program(
+    file_system: &dyn FileSystem,
     program: &ExecutableProgram,
     build_directory: &Path,
     artifact_identity: &BuildArtifactIdentity,
+    optimization_options: OptimizationOptions,
+    reproducibility_check: ReproducibilityCheck,
+    cancellation_token: Option<&CancellationToken>,
 ) -> Result<BuiltCraneliftProgram, CompilerFailure> {
-    fs::create_dir_all(build_directory).map_err(|error| {
-        build_failed(
-            format!("failed to create build output directory: {error}"),
-            Some(build_directory),
-        )
-    })?;
-
+    let violations = validate_program(program);
+    if !violations.is_empty() {
+        return Err(CompilerFailure {
+            kind: CompilerFailureKind::BuildFailed,
+            message: format!(
+                "program failed validation with {} violation(s)",
+                violations.len()
+            ),
+            path: None,
+            details: violations
+                .into_iter()
+                .map(|message| CompilerFailureDetail {
+                    message,
+                    path: None,
+                })
+                .collect(),
+        });
+    }
     ensure_program_supported(program)?;
 
-    let executable_path = build_directory.join(&artifact_identity.executable_stem);
-    let object_path = build_directory.join(format!("{}.o", artifact_identity.executable_stem));
+    let mut pass_timer = PassTimer::default();
+    let program = optimize_program(program, optimization_options, &mut pass_timer);
+    cancellation_token.map_or(Ok(()), CancellationToken::check)?;
 
-    let object_bytes = emit_object_bytes(program)?;
-    fs::write(&object_path, object_bytes).map_err(|error| {
-        build_failed(
-            format!("failed to write object file: {error}"),
-            Some(&object_path),
-        )
+    let executable_path = emit_executable(
+        file_system,
+        &program,
+        optimization_options.level,
+        build_directory,
+        &artifact_identity.executable_stem,
+        &mut pass_timer,
+        cancellation_token,
+    )?;
+
+    if reproducibility_check == ReproducibilityCheck::BuildTwiceAndCompare {
+        // The second build uses the same file name in a sibling directory so
+        // that any output-name-dependent bytes would still compare equal.
+        let check_directory = build_directory.join(REPRODUCIBILITY_CHECK_DIRECTORY_NAME);
+        let check_executable_path = emit_executable(
+            file_system,
+            &program,
+            optimization_options.level,
+            &check_directory,
+            &artifact_identity.executable_stem,
+            &mut PassTimer::default(),
+            cancellation_token,
+        )?;
+        let outputs_match = read_build_output(file_system, &executable_path)?
+            == read_build_output(file_system, &check_executable_path)?;
+        file_system
+            .remove_directory_all(&check_directory)
+            .map_err(|error| {
+                build_failed(
+                    format!("failed to remove reproducibility check directory: {error}"),
+                    Some(&check_directory),
+                )
+            })?;
+        if !outputs_match {
+            return Err(build_failed(
+                "build output is not reproducible: two builds of the same program produced \
+                 different executables"
+                    .to_string(),
+                Some(&executable_path),
+            ));
+        }
+    }
+
+    let executable_size_bytes = file_system
+        .file_size_bytes(&executable_path)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to read build output metadata: {error}"),
+                Some(&executable_path),
+            )
+        })?;
+    Ok(BuiltCraneliftProgram {
+        binary_path: executable_path,
+        report: build_report(&program, pass_timer, executable_size_bytes),
+    })
+}
+
+fn emit_executable(
+    file_system: &dyn FileSystem,
+    program: &ExecutableProgram,
+    optimization_level: OptimizationLevel,
+    build_directory: &Path,
+    executable_stem: &str,
+    pass_timer: &mut PassTimer,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<PathBuf, CompilerFailure> {
+    file_system
+        .create_directory_all(build_directory)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to create build output directory: {error}"),
+                Some(build_directory),
+            )
+        })?;
+
+    let executable_file_name = executable_stem.to_string();
+    let object_file_name = format!("{executable_stem}.o");
+    let object_path = build_directory.join(&object_file_name);
+
+    let object_bytes = pass_timer.time("object_emission", || {
+        emit_object_bytes(program, optimization_level, cancellation_token)
     })?;
+    file_system
+        .write(&object_path, &object_bytes)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to write object file: {error}"),
+                Some(&object_path),
+            )
+        })?;
 
-    link_executable(&object_path, &executable_path)?;
+    cancellation_token.map_or(Ok(()), CancellationToken::check)?;
+    pass_timer.time("linking", || {
+        link_executable(build_directory, &object_file_name, &executable_file_name)
+    })?;
 
-    fs::remove_file(&object_path).map_err(|error| {
+    file_system.remove_file(&object_path).map_err(|error| {
         build_failed(
             format!("failed to remove intermediate object file: {error}"),
             Some(&object_path),
         )
     })?;
 
-    Ok(BuiltCraneliftProgram {
-        binary_path: executable_path,
-    })
+    Ok(build_directory.join(executable_file_name))
+}
+
+fn read_build_output(
+    file_system: &dyn FileSystem,
+    path: &Path,
+) -> Result<Vec<u8>, CompilerFailure> {
+    file_system
+        .read(path)
+        .map_err(|error| build_failed(format!("failed to read build output: {error}"), Some(path)))
+}
+
+// An aborting program writes nothing to stderr but its abort message, so the
+// message is recovered from stderr when the program exits with the abort
+// status. Standard output is only counted when it is captured into an output
+// sink; otherwise it goes straight to the host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub exit_code: i32,
+    pub abort_message: Option<String>,
+    pub standard_output_byte_count: Option<usize>,
+    pub standard_error_byte_count: usize,
+    pub elapsed: Duration,
 }
 
-pub fn run_program(binary_path: &Path) -> Result<i32, CompilerFailure> {
-    let status = Command::new(binary_path).status().map_err(|error| {
+// Builtins that need a capability check the policy when they are called, so a
+// denied capability aborts the program only if it is actually used. The
+// executable runs as a separate process, so `file_system` must read through to
+// the disk at `binary_path`.
+pub fn run_program(
+    file_system: &dyn FileSystem,
+    binary_path: &Path,
+    execution_context: &ExecutionContext,
+) -> Result<RunOutcome, CompilerFailure> {
+    ensure_runtime_contracts_match(file_system, binary_path)?;
+    let mut command = Command::new(binary_path);
+    for capability in RuntimeCapability::ALL {
+        if execution_context.execution_policy.permits(*capability) {
+            command.env_remove(capability.denial_environment_variable());
+        } else {
+            command.env(capability.denial_environment_variable(), "1");
+        }
+    }
+    for (environment_variable, value) in [
+        (
+            RANDOM_SEED_ENVIRONMENT_VARIABLE,
+            execution_context.random_seed,
+        ),
+        (
+            FIXED_NOW_ENVIRONMENT_VARIABLE,
+            execution_context.fixed_now_unix_seconds,
+        ),
+    ] {
+        match value {
+            Some(value) => command.env(environment_variable, value.to_string()),
+            None => command.env_remove(environment_variable),
+        };
+    }
+    match execution_context.overflow_mode {
+        OverflowMode::Wrap => command.env_remove(ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE),
+        OverflowMode::Abort => command.env(ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE, "1"),
+    };
+    command.stderr(Stdio::piped());
+    if execution_context.output_sink.is_some() {
+        command.stdout(Stdio::piped());
+    }
+    let execution_failed = |error: io::Error| {
         run_failed(
             format!("failed to execute binary: {error}"),
             Some(binary_path),
         )
+    };
+    let started = Instant::now();
+    let output = command
+        .spawn()
+        .and_then(Child::wait_with_output)
+        .map_err(execution_failed)?;
+    let elapsed = started.elapsed();
+    match &execution_context.output_sink {
+        Some(output_sink) => {
+            output_sink.write_standard_output(&output.stdout);
+            output_sink.write_standard_error(&output.stderr);
+        }
+        None => io::stderr()
+            .write_all(&output.stderr)
+            .map_err(execution_failed)?,
+    }
+
+    let exit_code = output.status.code().unwrap_or(ABORT_EXIT_CODE);
+    let standard_error = String::from_utf8_lossy(&output.stderr);
+    let abort_message =
+        (output.status.code() == Some(ABORT_EXIT_CODE) && !standard_error.is_empty()).then(|| {
+            standard_error
+                .strip_suffix('\n')
+                .unwrap_or(&standard_error)
+                .to_string()
+        });
+    Ok(RunOutcome {
+        exit_code,
+        abort_message,
+        standard_output_byte_count: execution_context
+            .output_sink
+            .as_ref()
+            .map(|_| output.stdout.len()),
+        standard_error_byte_count: output.stderr.len(),
+        elapsed,
+    })
+}
+
+// An executable built by a toolchain whose runtime contracts differ from this
+// one may call builtins with the wrong arguments or misread their results, so
+// it must be rebuilt before it runs.
+fn ensure_runtime_contracts_match(
+    file_system: &dyn FileSystem,
+    binary_path: &Path,
+) -> Result<(), CompilerFailure> {
+    let binary_bytes = file_system.read(binary_path).map_err(|error| {
+        run_failed(format!("failed to read binary: {error}"), Some(binary_path))
     })?;
-    Ok(status.code().unwrap_or(1))
+    let header = RUNTIME_CONTRACT_RECORD_HEADER.as_bytes();
+    let Some(record) = binary_bytes
+        .windows(header.len())
+        .position(|window| window == header)
+        .map(|header_start| &binary_bytes[header_start + header.len()..])
+        .map(|record| record.split(|byte| *byte == 0).next().unwrap_or(record))
+    else {
+        return Err(run_failed(
+            "binary does not record the runtime contracts it was built against; rebuild it \
+             with this toolchain"
+                .to_string(),
+            Some(binary_path),
+        ));
+    };
+    let recorded_lines = String::from_utf8_lossy(record)
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let current_lines = runtime_contract_lines();
+    let mut details = Vec::new();
+    for recorded_line in &recorded_lines {
+        if !current_lines.contains(recorded_line) {
+            details.push(format!(
+                "built against '{recorded_line}', which this toolchain does not provide"
+            ));
+        }
+    }
+    for current_line in &current_lines {
+        if !recorded_lines.contains(current_line) {
+            details.push(format!(
+                "this toolchain provides '{current_line}', which the binary was not built against"
+            ));
+        }
+    }
+    if details.is_empty() {
+        return Ok(());
+    }
+    Err(CompilerFailure {
+        kind: CompilerFailureKind::RunFailed,
+        message: "binary was built against different runtime contracts than this toolchain \
+                  provides; rebuild it with this toolchain"
+            .to_string(),
+        path: Some(binary_path.display().to_string()),
+        details: details
+            .into_iter()
+            .map(|message| CompilerFailureDetail {
+                message,
+                path: None,
+            })
+            .collect(),
+    })
 }
 
 pub(crate) fn build_failed(message: String, path: Option<&Path>) -> CompilerFailure {
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{self, Path, PathBuf};
 use std::process::Command;
 
 use compiler__reports::CompilerFailure;
@@ -6,10 +6,15 @@ use runfiles::Runfiles;
 
 use crate::build_failed;
 
+// The linker runs inside the build directory with relative file names so no
+// absolute paths reach the output, and with timestamp sources pinned so that
+// identical inputs link to identical bytes.
 pub(crate) fn link_executable(
-    object_path: &Path,
-    executable_path: &Path,
+    build_directory: &Path,
+    object_file_name: &str,
+    executable_file_name: &str,
 ) -> Result<(), CompilerFailure> {
+    let executable_path = &build_directory.join(executable_file_name);
     let runfiles = Runfiles::create().map_err(|error| {
         build_failed(
             format!("failed to initialize runfiles for linker wrapper: {error}"),
@@ -36,11 +41,27 @@ pub(crate) fn link_executable(
         )
     })?;
 
+    // Runfiles locations may be relative to this process, which would no longer
+    // resolve once the linker runs inside the build directory.
+    let absolute = |path: PathBuf| {
+        path::absolute(&path).map_err(|error| {
+            build_failed(
+                format!("failed to resolve linker wrapper path: {error}"),
+                Some(executable_path),
+            )
+        })
+    };
+    let linker_wrapper = absolute(linker_wrapper)?;
+    let runfiles_dir = absolute(runfiles_dir)?;
+
     let output = Command::new(linker_wrapper)
-        .arg(object_path)
+        .current_dir(build_directory)
+        .arg(object_file_name)
         .arg("-o")
-        .arg(executable_path)
+        .arg(executable_file_name)
         .env("RUNFILES_DIR", runfiles_dir)
+        .env("SOURCE_DATE_EPOCH", "0")
+        .env("ZERO_AR_DATE", "1")
         .output()
         .map_err(|error| {
             build_failed(
@@ -1,23 +1,33 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use crate::RUNTIME_CONTRACT_RECORD_HEADER;
 use crate::build_failed;
 use crate::builtin_conversion::convert_int64_to_string;
+use crate::optimization::OptimizationLevel;
 use crate::runtime_interface_emission::{
-    ExternalRuntimeFunctions, declare_runtime_interface_functions,
+    ExternalRuntimeFunctions, declare_runtime_interface_functions, define_random_state,
 };
+use compiler__cancellation::CancellationToken;
 use compiler__executable_program::{
     ExecutableAssignTarget, ExecutableBinaryOperator, ExecutableCallTarget,
-    ExecutableCallableReference, ExecutableConstantDeclaration, ExecutableConstantReference,
-    ExecutableEnumVariantReference, ExecutableExpression, ExecutableFunctionDeclaration,
-    ExecutableInterfaceDeclaration, ExecutableInterfaceReference, ExecutableMatchArm,
-    ExecutableMatchPattern, ExecutableMethodDeclaration, ExecutableNominalTypeReference,
-    ExecutableProgram, ExecutableStatement, ExecutableStructDeclaration, ExecutableStructReference,
+    ExecutableConstantDeclaration, ExecutableExpression, ExecutableFunctionDeclaration,
+    ExecutableInterfaceDeclaration, ExecutableLayoutTable, ExecutableMatchArm,
+    ExecutableMatchLiteral, ExecutableMatchPattern, ExecutableMethodDeclaration, ExecutableProgram,
+    ExecutableStatement, ExecutableStructDeclaration, ExecutableStructLayout,
     ExecutableTypeReference, ExecutableUnaryOperator,
 };
+use compiler__program_references::{
+    CallableReference, ConstantReference, EnumVariantReference, InterfaceReference,
+    NominalTypeReference, StructReference,
+};
 use compiler__reports::CompilerFailure;
 use compiler__runtime_interface::{
-    ABORT_FUNCTION_CONTRACT, ASSERT_FUNCTION_CONTRACT, PRINT_FUNCTION_CONTRACT,
+    ABORT_EXIT_CODE, ABORT_FUNCTION_CONTRACT, ARITHMETIC_OVERFLOW_MESSAGE,
+    ASSERT_FUNCTION_CONTRACT, FIXED_NOW_ENVIRONMENT_VARIABLE, INTEGER_DIVISION_BY_ZERO_MESSAGE,
+    INTEGER_REMAINDER_BY_ZERO_MESSAGE, NOW_FUNCTION_CONTRACT, PRINT_FUNCTION_CONTRACT,
+    RANDOM_INT_FUNCTION_CONTRACT, RANDOM_SEED_ENVIRONMENT_VARIABLE, RuntimeCapability,
+    UNREACHABLE_FUNCTION_CONTRACT, arithmetic_abort_message, runtime_contract_lines,
 };
 use cranelift_codegen::ir::condcodes::IntCC;
 use cranelift_codegen::ir::{
@@ -26,7 +36,7 @@ use cranelift_codegen::ir::{
 use cranelift_codegen::isa;
 use cranelift_codegen::settings::{self, Configurable};
 use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
-use cranelift_module::{FuncId, Linkage, Module, default_libcall_names};
+use cranelift_module::{DataDescription, DataId, FuncId, Linkage, Module, default_libcall_names};
 use cranelift_native as native_isa;
 use cranelift_object::{ObjectBuilder, ObjectModule};
 
@@ -36,8 +46,7 @@ struct FunctionRecord {
     parameter_types: Vec<ExecutableTypeReference>,
     return_type: ExecutableTypeReference,
     type_parameter_names: Vec<String>,
-    type_parameter_constraint_interface_reference_by_name:
-        BTreeMap<String, ExecutableInterfaceReference>,
+    type_parameter_constraint_interface_reference_by_name: BTreeMap<String, InterfaceReference>,
 }
 
 #[derive(Clone)]
@@ -49,7 +58,7 @@ struct MethodRecord {
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct MethodKey {
-    struct_reference: ExecutableStructReference,
+    struct_reference: StructReference,
     method_name: String,
 }
 
@@ -80,23 +89,28 @@ struct FunctionCompilationContext {
 
 #[derive(Clone)]
 struct TypeParameterWitness {
-    interface_reference: ExecutableInterfaceReference,
+    interface_reference: InterfaceReference,
     witness_table_pointer: Value,
 }
 
 pub(crate) struct CompilationState<'program> {
     module: ObjectModule,
-    function_record_by_callable_reference: BTreeMap<ExecutableCallableReference, FunctionRecord>,
+    function_record_by_callable_reference: BTreeMap<CallableReference, FunctionRecord>,
     method_record_by_key: BTreeMap<MethodKey, MethodRecord>,
     interface_declaration_by_reference:
-        BTreeMap<ExecutableInterfaceReference, &'program ExecutableInterfaceDeclaration>,
+        BTreeMap<InterfaceReference, &'program ExecutableInterfaceDeclaration>,
     constant_declaration_by_reference:
-        BTreeMap<ExecutableConstantReference, &'program ExecutableConstantDeclaration>,
+        BTreeMap<ConstantReference, &'program ExecutableConstantDeclaration>,
     struct_declaration_by_reference:
-        BTreeMap<ExecutableStructReference, &'program ExecutableStructDeclaration>,
+        BTreeMap<StructReference, &'program ExecutableStructDeclaration>,
+    struct_layout_by_reference: BTreeMap<StructReference, &'program ExecutableStructLayout>,
     external_runtime_functions: ExternalRuntimeFunctions,
+    random_state: DataId,
 }
 
+const OPTIMIZATION_LEVEL_SYMBOL_NAME: &str = "coppice_optimization_level";
+const RUNTIME_CONTRACT_SYMBOL_NAME: &str = "coppice_runtime_contract";
+
 const UNION_BOX_TAG_OFFSET: i32 = 0;
 const UNION_BOX_PAYLOAD_OFFSET: i32 = 8;
 const UNION_BOX_SIZE_BYTES: i64 = 16;
@@ -155,6 +169,64 @@ pub(crate) fn ensure_program_supported(program: &ExecutableProgram) -> Result<()
         }
     }
 
+    ensure_layout_table_supported(&program.layout_table, &program.struct_declarations)
+}
+
+fn ensure_layout_table_supported(
+    layout_table: &ExecutableLayoutTable,
+    struct_declarations: &[ExecutableStructDeclaration],
+) -> Result<(), CompilerFailure> {
+    let slot_size_bytes = types::I64.bytes();
+    let layout_mismatch =
+        |description: &str| build_failed(format!("unsupported {description} layout"), None);
+
+    let list_layout = &layout_table.list_layout;
+    if i64::from(list_layout.header_size_bytes) != LIST_HEADER_SIZE_BYTES
+        || i32::try_from(list_layout.length_offset_bytes) != Ok(LIST_LENGTH_OFFSET)
+        || i32::try_from(list_layout.data_pointer_offset_bytes) != Ok(LIST_DATA_POINTER_OFFSET)
+        || list_layout.element_stride_bytes != slot_size_bytes
+    {
+        return Err(layout_mismatch("list"));
+    }
+
+    let union_layout = &layout_table.union_layout;
+    if i64::from(union_layout.size_bytes) != UNION_BOX_SIZE_BYTES
+        || i32::try_from(union_layout.tag_offset_bytes) != Ok(UNION_BOX_TAG_OFFSET)
+        || i32::try_from(union_layout.payload_offset_bytes) != Ok(UNION_BOX_PAYLOAD_OFFSET)
+    {
+        return Err(layout_mismatch("union"));
+    }
+
+    if layout_table.enum_layout.size_bytes != slot_size_bytes {
+        return Err(layout_mismatch("enum"));
+    }
+
+    for struct_declaration in struct_declarations {
+        let struct_reference = &struct_declaration.struct_reference;
+        let description = format!(
+            "struct '{}::{}'",
+            struct_reference.package_path, struct_reference.symbol_name
+        );
+        let struct_layout = layout_table
+            .struct_layouts
+            .iter()
+            .find(|struct_layout| struct_layout.struct_reference == *struct_reference)
+            .ok_or_else(|| layout_mismatch(&description))?;
+        if struct_layout.fields.len() != struct_declaration.fields.len()
+            || i32::try_from(struct_layout.size_bytes).is_err()
+        {
+            return Err(layout_mismatch(&description));
+        }
+        for (field_layout, field) in struct_layout.fields.iter().zip(&struct_declaration.fields) {
+            if field_layout.name != field.name
+                || field_layout.size_bytes != slot_size_bytes
+                || field_layout.offset_bytes + field_layout.size_bytes > struct_layout.size_bytes
+            {
+                return Err(layout_mismatch(&description));
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -253,18 +325,43 @@ fn ensure_expression_supported(expression: &ExecutableExpression) -> Result<(),
             }
             Ok(())
         }
+        ExecutableExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            ensure_expression_supported(condition)?;
+            ensure_expression_supported(then_branch)?;
+            ensure_expression_supported(else_branch)
+        }
+        ExecutableExpression::Block { statements, value } => {
+            for statement in statements {
+                ensure_statement_supported(statement)?;
+            }
+            ensure_expression_supported(value)
+        }
         ExecutableExpression::Match { target, arms } => {
             ensure_expression_supported(target)?;
             for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    ensure_expression_supported(guard)?;
+                }
                 ensure_expression_supported(&arm.value)?;
             }
             Ok(())
         }
-        ExecutableExpression::Matches { value, .. } => ensure_expression_supported(value),
+        ExecutableExpression::EnumVariantName { value, .. }
+        | ExecutableExpression::EnumVariantFromString { value, .. }
+        | ExecutableExpression::Matches { value, .. } => ensure_expression_supported(value),
     }
 }
 
-pub(crate) fn emit_object_bytes(program: &ExecutableProgram) -> Result<Vec<u8>, CompilerFailure> {
+pub(crate) fn emit_object_bytes(
+    program: &ExecutableProgram,
+    optimization_level: OptimizationLevel,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<Vec<u8>, CompilerFailure> {
     let isa = create_native_isa()?;
     let object_builder =
         ObjectBuilder::new(isa, "coppice", default_libcall_names()).map_err(|error| {
@@ -276,6 +373,7 @@ pub(crate) fn emit_object_bytes(program: &ExecutableProgram) -> Result<Vec<u8>,
     let mut module = ObjectModule::new(object_builder);
 
     let external_runtime_functions = declare_runtime_interface_functions(&mut module)?;
+    let random_state = define_random_state(&mut module)?;
     let function_record_by_callable_reference =
         declare_program_functions(&mut module, &program.function_declarations)?;
     let method_record_by_key = declare_struct_methods(&mut module, &program.struct_declarations)?;
@@ -289,6 +387,12 @@ pub(crate) fn emit_object_bytes(program: &ExecutableProgram) -> Result<Vec<u8>,
         .iter()
         .map(|declaration| (declaration.struct_reference.clone(), declaration))
         .collect();
+    let struct_layout_by_reference = program
+        .layout_table
+        .struct_layouts
+        .iter()
+        .map(|layout| (layout.struct_reference.clone(), layout))
+        .collect();
     let interface_declaration_by_reference = program
         .interface_declarations
         .iter()
@@ -302,19 +406,25 @@ pub(crate) fn emit_object_bytes(program: &ExecutableProgram) -> Result<Vec<u8>,
         interface_declaration_by_reference,
         constant_declaration_by_reference,
         struct_declaration_by_reference,
+        struct_layout_by_reference,
         external_runtime_functions,
+        random_state,
     };
 
     for function_declaration in &program.function_declarations {
+        cancellation_token.map_or(Ok(()), CancellationToken::check)?;
         define_program_function(&mut state, function_declaration)?;
     }
     for struct_declaration in &program.struct_declarations {
         for method_declaration in &struct_declaration.methods {
+            cancellation_token.map_or(Ok(()), CancellationToken::check)?;
             define_struct_method(&mut state, struct_declaration, method_declaration)?;
         }
     }
 
     define_process_entrypoint(&mut state, &program.entrypoint_callable_reference)?;
+    define_optimization_level_record(&mut state.module, optimization_level)?;
+    define_runtime_contract_record(&mut state.module)?;
 
     let product = state.module.finish();
     product
@@ -322,6 +432,68 @@ pub(crate) fn emit_object_bytes(program: &ExecutableProgram) -> Result<Vec<u8>,
         .map_err(|error| build_failed(format!("failed to emit object bytes: {error}"), None))
 }
 
+// Records the runtime contracts the object was compiled against, which
+// `run_program` checks before running the linked executable.
+fn define_runtime_contract_record(module: &mut ObjectModule) -> Result<(), CompilerFailure> {
+    let data_id = module
+        .declare_data(RUNTIME_CONTRACT_SYMBOL_NAME, Linkage::Export, false, false)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to declare runtime contract record: {error}"),
+                None,
+            )
+        })?;
+    let mut data_description = DataDescription::new();
+    let mut contents = format!(
+        "{RUNTIME_CONTRACT_RECORD_HEADER}{}",
+        runtime_contract_lines().join("\n")
+    )
+    .into_bytes();
+    contents.push(0);
+    data_description.define(contents.into_boxed_slice());
+    module
+        .define_data(data_id, &data_description)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to define runtime contract record: {error}"),
+                None,
+            )
+        })
+}
+
+// Records the optimization level in the emitted object so a binary can be
+// traced back to the options it was built with.
+fn define_optimization_level_record(
+    module: &mut ObjectModule,
+    optimization_level: OptimizationLevel,
+) -> Result<(), CompilerFailure> {
+    let data_id = module
+        .declare_data(
+            OPTIMIZATION_LEVEL_SYMBOL_NAME,
+            Linkage::Export,
+            false,
+            false,
+        )
+        .map_err(|error| {
+            build_failed(
+                format!("failed to declare optimization level record: {error}"),
+                None,
+            )
+        })?;
+    let mut data_description = DataDescription::new();
+    let mut contents = optimization_level.name().as_bytes().to_vec();
+    contents.push(0);
+    data_description.define(contents.into_boxed_slice());
+    module
+        .define_data(data_id, &data_description)
+        .map_err(|error| {
+            build_failed(
+                format!("failed to define optimization level record: {error}"),
+                None,
+            )
+        })
+}
+
 fn create_native_isa() -> Result<Arc<dyn isa::TargetIsa>, CompilerFailure> {
     let mut flag_builder = settings::builder();
     flag_builder.set("opt_level", "speed").map_err(|error| {
@@ -346,7 +518,7 @@ fn create_native_isa() -> Result<Arc<dyn isa::TargetIsa>, CompilerFailure> {
 fn declare_program_functions(
     module: &mut ObjectModule,
     function_declarations: &[ExecutableFunctionDeclaration],
-) -> Result<BTreeMap<ExecutableCallableReference, FunctionRecord>, CompilerFailure> {
+) -> Result<BTreeMap<CallableReference, FunctionRecord>, CompilerFailure> {
     let mut function_record_by_callable_reference = BTreeMap::new();
 
     for function_declaration in function_declarations {
@@ -463,7 +635,7 @@ fn build_signature_for_method(
     let mut signature = module.make_signature();
     let self_type_reference = if struct_declaration.type_parameter_names.is_empty() {
         ExecutableTypeReference::NominalType {
-            nominal_type_reference: Some(ExecutableNominalTypeReference {
+            nominal_type_reference: Some(NominalTypeReference {
                 package_path: struct_declaration.struct_reference.package_path.clone(),
                 symbol_name: struct_declaration.struct_reference.symbol_name.clone(),
             }),
@@ -471,7 +643,7 @@ fn build_signature_for_method(
         }
     } else {
         ExecutableTypeReference::NominalTypeApplication {
-            base_nominal_type_reference: Some(ExecutableNominalTypeReference {
+            base_nominal_type_reference: Some(NominalTypeReference {
                 package_path: struct_declaration.struct_reference.package_path.clone(),
                 symbol_name: struct_declaration.struct_reference.symbol_name.clone(),
             }),
@@ -690,7 +862,7 @@ fn define_struct_method(
 
         let self_type_reference = if struct_declaration.type_parameter_names.is_empty() {
             ExecutableTypeReference::NominalType {
-                nominal_type_reference: Some(ExecutableNominalTypeReference {
+                nominal_type_reference: Some(NominalTypeReference {
                     package_path: struct_declaration.struct_reference.package_path.clone(),
                     symbol_name: struct_declaration.struct_reference.symbol_name.clone(),
                 }),
@@ -698,7 +870,7 @@ fn define_struct_method(
             }
         } else {
             ExecutableTypeReference::NominalTypeApplication {
-                base_nominal_type_reference: Some(ExecutableNominalTypeReference {
+                base_nominal_type_reference: Some(NominalTypeReference {
                     package_path: struct_declaration.struct_reference.package_path.clone(),
                     symbol_name: struct_declaration.struct_reference.symbol_name.clone(),
                 }),
@@ -773,7 +945,7 @@ fn define_struct_method(
 
 fn define_process_entrypoint(
     state: &mut CompilationState<'_>,
-    entrypoint_callable_reference: &ExecutableCallableReference,
+    entrypoint_callable_reference: &CallableReference,
 ) -> Result<(), CompilerFailure> {
     let entrypoint_id = state
         .function_record_by_callable_reference
@@ -809,6 +981,18 @@ fn define_process_entrypoint(
         function_builder.switch_to_block(entry_block);
         function_builder.seal_block(entry_block);
 
+        let random_seed = emit_environment_integer_or_time(
+            state,
+            &mut function_builder,
+            RANDOM_SEED_ENVIRONMENT_VARIABLE,
+        )?;
+        crate::runtime_interface_emission::emit_random_state_seed(
+            &mut state.module,
+            &mut function_builder,
+            state.random_state,
+            random_seed,
+        );
+
         let entrypoint = state
             .module
             .declare_func_in_func(entrypoint_id, function_builder.func);
@@ -1280,12 +1464,17 @@ fn compile_expression(
             type_reference,
             fields,
         ),
-        ExecutableExpression::FieldAccess { target, field } => compile_field_access_expression(
+        ExecutableExpression::FieldAccess {
+            target,
+            field,
+            type_reference,
+        } => compile_field_access_expression(
             state,
             function_builder,
             compilation_context,
             target,
             field,
+            type_reference.as_ref(),
         ),
         ExecutableExpression::IndexAccess { target, index } => compile_index_access_expression(
             state,
@@ -1297,6 +1486,7 @@ fn compile_expression(
         ExecutableExpression::Unary {
             operator,
             expression,
+            operation_description,
         } => {
             let operand =
                 compile_expression(state, function_builder, compilation_context, expression)?;
@@ -1332,8 +1522,16 @@ fn compile_expression(
                             None,
                         ));
                     }
+                    let zero = function_builder.ins().iconst(types::I64, 0);
                     Ok(TypedValue {
-                        value: Some(function_builder.ins().ineg(operand_value)),
+                        value: Some(compile_int64_arithmetic(
+                            state,
+                            function_builder,
+                            ExecutableBinaryOperator::Subtract,
+                            zero,
+                            operand_value,
+                            operation_description.as_deref(),
+                        )?),
                         type_reference: ExecutableTypeReference::Int64,
                         terminates: false,
                     })
@@ -1344,6 +1542,7 @@ fn compile_expression(
             operator,
             left,
             right,
+            operation_description,
         } => compile_binary_expression(
             state,
             function_builder,
@@ -1351,6 +1550,7 @@ fn compile_expression(
             *operator,
             left,
             right,
+            operation_description.as_deref(),
         ),
         ExecutableExpression::Call {
             callee,
@@ -1377,9 +1577,51 @@ fn compile_expression(
             value,
             type_reference,
         ),
+        ExecutableExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            type_reference,
+        } => compile_if_expression(
+            state,
+            function_builder,
+            compilation_context,
+            condition,
+            [then_branch, else_branch],
+            type_reference,
+        ),
+        ExecutableExpression::Block { statements, value } => compile_block_expression(
+            state,
+            function_builder,
+            compilation_context,
+            statements,
+            value,
+        ),
         ExecutableExpression::Match { target, arms } => {
             compile_match_expression(state, function_builder, compilation_context, target, arms)
         }
+        ExecutableExpression::EnumVariantName {
+            value,
+            enum_variant_references,
+        } => compile_enum_variant_name_expression(
+            state,
+            function_builder,
+            compilation_context,
+            value,
+            enum_variant_references,
+        ),
+        ExecutableExpression::EnumVariantFromString {
+            value,
+            enum_variant_references,
+            type_reference,
+        } => compile_enum_variant_from_string_expression(
+            state,
+            function_builder,
+            compilation_context,
+            value,
+            enum_variant_references,
+            type_reference,
+        ),
     }
 }
 
@@ -1390,6 +1632,7 @@ fn compile_binary_expression(
     operator: ExecutableBinaryOperator,
     left: &ExecutableExpression,
     right: &ExecutableExpression,
+    operation_description: Option<&str>,
 ) -> Result<TypedValue, CompilerFailure> {
     let left_typed_value = compile_expression(state, function_builder, compilation_context, left)?;
     if left_typed_value.terminates {
@@ -1421,7 +1664,14 @@ fn compile_binary_expression(
             ) {
                 (ExecutableTypeReference::Int64, ExecutableTypeReference::Int64) => {
                     Ok(TypedValue {
-                        value: Some(function_builder.ins().iadd(left_value, right_value)),
+                        value: Some(compile_int64_arithmetic(
+                            state,
+                            function_builder,
+                            operator,
+                            left_value,
+                            right_value,
+                            operation_description,
+                        )?),
                         type_reference: ExecutableTypeReference::Int64,
                         terminates: false,
                     })
@@ -1445,6 +1695,11 @@ fn compile_binary_expression(
         | ExecutableBinaryOperator::Multiply
         | ExecutableBinaryOperator::Divide
         | ExecutableBinaryOperator::Modulo
+        | ExecutableBinaryOperator::BitwiseAnd
+        | ExecutableBinaryOperator::BitwiseOr
+        | ExecutableBinaryOperator::BitwiseXor
+        | ExecutableBinaryOperator::ShiftLeft
+        | ExecutableBinaryOperator::ShiftRight
         | ExecutableBinaryOperator::LessThan
         | ExecutableBinaryOperator::LessThanOrEqual
         | ExecutableBinaryOperator::GreaterThan
@@ -1465,30 +1720,50 @@ fn compile_binary_expression(
                 || right_typed_value.type_reference != ExecutableTypeReference::Int64
             {
                 return Err(build_failed(
-                    "arithmetic and ordered comparison operators require int64 operands"
+                    "arithmetic, bitwise, and ordered comparison operators require int64 operands"
                         .to_string(),
                     None,
                 ));
             }
 
             match operator {
-                ExecutableBinaryOperator::Subtract => Ok(TypedValue {
-                    value: Some(function_builder.ins().isub(left_value, right_value)),
+                ExecutableBinaryOperator::Subtract
+                | ExecutableBinaryOperator::Multiply
+                | ExecutableBinaryOperator::Divide
+                | ExecutableBinaryOperator::Modulo => Ok(TypedValue {
+                    value: Some(compile_int64_arithmetic(
+                        state,
+                        function_builder,
+                        operator,
+                        left_value,
+                        right_value,
+                        operation_description,
+                    )?),
+                    type_reference: ExecutableTypeReference::Int64,
+                    terminates: false,
+                }),
+                ExecutableBinaryOperator::BitwiseAnd => Ok(TypedValue {
+                    value: Some(function_builder.ins().band(left_value, right_value)),
+                    type_reference: ExecutableTypeReference::Int64,
+                    terminates: false,
+                }),
+                ExecutableBinaryOperator::BitwiseOr => Ok(TypedValue {
+                    value: Some(function_builder.ins().bor(left_value, right_value)),
                     type_reference: ExecutableTypeReference::Int64,
                     terminates: false,
                 }),
-                ExecutableBinaryOperator::Multiply => Ok(TypedValue {
-                    value: Some(function_builder.ins().imul(left_value, right_value)),
+                ExecutableBinaryOperator::BitwiseXor => Ok(TypedValue {
+                    value: Some(function_builder.ins().bxor(left_value, right_value)),
                     type_reference: ExecutableTypeReference::Int64,
                     terminates: false,
                 }),
-                ExecutableBinaryOperator::Divide => Ok(TypedValue {
-                    value: Some(function_builder.ins().sdiv(left_value, right_value)),
+                ExecutableBinaryOperator::ShiftLeft => Ok(TypedValue {
+                    value: Some(function_builder.ins().ishl(left_value, right_value)),
                     type_reference: ExecutableTypeReference::Int64,
                     terminates: false,
                 }),
-                ExecutableBinaryOperator::Modulo => Ok(TypedValue {
-                    value: Some(function_builder.ins().srem(left_value, right_value)),
+                ExecutableBinaryOperator::ShiftRight => Ok(TypedValue {
+                    value: Some(function_builder.ins().sshr(left_value, right_value)),
                     type_reference: ExecutableTypeReference::Int64,
                     terminates: false,
                 }),
@@ -1620,6 +1895,12 @@ fn compile_binary_expression(
                     let equal_as_i8 = function_builder.ins().select(equal_condition, one, zero);
                     function_builder.ins().icmp(IntCC::Equal, equal_as_i8, zero)
                 }
+            } else if matches!(comparable_type_reference, ExecutableTypeReference::String) {
+                let comparison =
+                    emit_string_comparison(state, function_builder, left_value, right_value);
+                function_builder
+                    .ins()
+                    .icmp_imm(condition_code, comparison, 0)
             } else {
                 function_builder
                     .ins()
@@ -1720,141 +2001,600 @@ fn concatenate_strings(
     destination_pointer
 }
 
-fn compile_call_expression(
+// `assert_equals` compares the rendered forms of its arguments, so values
+// are equal exactly when the interpreter would also report them as equal.
+fn compile_assert_equals_call(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
     compilation_context: &mut FunctionCompilationContext,
-    callee: &ExecutableExpression,
-    call_target: Option<&ExecutableCallTarget>,
     arguments: &[ExecutableExpression],
     type_arguments: &[ExecutableTypeReference],
 ) -> Result<TypedValue, CompilerFailure> {
-    if let Some(call_target) = call_target {
-        return match call_target {
-            ExecutableCallTarget::BuiltinFunction { function_name } => {
-                if !type_arguments.is_empty() {
-                    return Err(build_failed(
-                        format!("builtin function '{function_name}' does not take type arguments"),
-                        None,
-                    ));
-                }
-                if function_name == PRINT_FUNCTION_CONTRACT.language_name {
-                    if arguments.len() != 1 {
-                        return Err(build_failed(
-                            "print(...) requires exactly one argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let argument = compile_expression(
-                        state,
-                        function_builder,
-                        compilation_context,
-                        &arguments[0],
-                    )?;
-                    if argument.terminates {
-                        return Ok(argument);
-                    }
-                    if argument.type_reference != ExecutableTypeReference::String {
-                        return Err(build_failed(
-                            "print(...) requires string argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let pointer = argument.value.ok_or_else(|| {
-                        build_failed("print argument produced no runtime value".to_string(), None)
-                    })?;
-                    emit_write_string_with_newline(state, function_builder, 1, pointer)?;
-                    return Ok(TypedValue {
-                        value: None,
-                        type_reference: ExecutableTypeReference::Nil,
-                        terminates: false,
-                    });
-                }
+    let (
+        [
+            left,
+            right,
+            ExecutableExpression::StringLiteral { value: description },
+        ],
+        [compared_type],
+    ) = (arguments, type_arguments)
+    else {
+        return Err(build_failed(
+            "assert_equals(...) requires two typed values and their description".to_string(),
+            None,
+        ));
+    };
+    let mut rendered_values = Vec::with_capacity(2);
+    for argument in [left, right] {
+        let argument = compile_expression(state, function_builder, compilation_context, argument)?;
+        if argument.terminates {
+            return Ok(argument);
+        }
+        let value = runtime_value_for_expected_type(
+            state,
+            function_builder,
+            argument.value,
+            &argument.type_reference,
+            compared_type,
+        )?;
+        rendered_values.push(emit_rendered_value(
+            state,
+            function_builder,
+            value,
+            compared_type,
+            &mut Vec::new(),
+        )?);
+    }
+    let (left_rendered, right_rendered) = (rendered_values[0], rendered_values[1]);
 
-                if function_name == ABORT_FUNCTION_CONTRACT.language_name {
-                    if arguments.len() != 1 {
-                        return Err(build_failed(
-                            "abort(...) requires exactly one argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let argument = compile_expression(
-                        state,
-                        function_builder,
-                        compilation_context,
-                        &arguments[0],
-                    )?;
-                    if argument.terminates {
-                        return Ok(argument);
-                    }
-                    if argument.type_reference != ExecutableTypeReference::String {
-                        return Err(build_failed(
-                            "abort(...) requires string argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let pointer = argument.value.ok_or_else(|| {
-                        build_failed("abort argument produced no runtime value".to_string(), None)
-                    })?;
-                    emit_write_string_with_newline(state, function_builder, 2, pointer)?;
-                    emit_exit_call(state, function_builder, 1);
-                    return Ok(TypedValue {
-                        value: None,
-                        type_reference: ExecutableTypeReference::Never,
-                        terminates: true,
-                    });
-                }
-                if function_name == ASSERT_FUNCTION_CONTRACT.language_name {
-                    if arguments.len() != 1 {
-                        return Err(build_failed(
-                            "assert(...) requires exactly one argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let argument = compile_expression(
-                        state,
-                        function_builder,
-                        compilation_context,
-                        &arguments[0],
-                    )?;
-                    if argument.terminates {
-                        return Ok(argument);
-                    }
-                    if argument.type_reference != ExecutableTypeReference::Boolean {
-                        return Err(build_failed(
-                            "assert(...) requires boolean argument".to_string(),
-                            None,
-                        ));
-                    }
-                    let condition_value = argument.value.ok_or_else(|| {
-                        build_failed(
-                            "assert argument produced no runtime value".to_string(),
-                            None,
-                        )
-                    })?;
-                    let zero = function_builder.ins().iconst(types::I8, 0);
-                    let condition_is_true =
-                        function_builder
-                            .ins()
-                            .icmp(IntCC::NotEqual, condition_value, zero);
+    let strcmp = state.module.declare_func_in_func(
+        state.external_runtime_functions.strcmp,
+        function_builder.func,
+    );
+    let comparison_call = function_builder
+        .ins()
+        .call(strcmp, &[left_rendered, right_rendered]);
+    let comparison = function_builder.inst_results(comparison_call)[0];
+    let renders_equal = function_builder.ins().icmp_imm(IntCC::Equal, comparison, 0);
 
-                    let pass_block = function_builder.create_block();
-                    let fail_block = function_builder.create_block();
-                    let merge_block = function_builder.create_block();
-                    function_builder.ins().brif(
-                        condition_is_true,
-                        pass_block,
-                        &[],
-                        fail_block,
-                        &[],
-                    );
+    let fail_block = function_builder.create_block();
+    let merge_block = function_builder.create_block();
+    function_builder
+        .ins()
+        .brif(renders_equal, merge_block, &[], fail_block, &[]);
+    function_builder.seal_block(fail_block);
 
-                    function_builder.switch_to_block(fail_block);
-                    let message_pointer =
-                        intern_string_literal(state, function_builder, "assertion failed")?;
-                    emit_write_string_with_newline(state, function_builder, 2, message_pointer)?;
-                    emit_exit_call(state, function_builder, 1);
-                    function_builder.seal_block(fail_block);
+    function_builder.switch_to_block(fail_block);
+    let message_prefix = intern_string_literal(
+        state,
+        function_builder,
+        &format!("assertion failed: {description}: left is "),
+    )?;
+    let right_label = intern_string_literal(state, function_builder, ", right is ")?;
+    let mut message = concatenate_strings(state, function_builder, message_prefix, left_rendered);
+    message = concatenate_strings(state, function_builder, message, right_label);
+    message = concatenate_strings(state, function_builder, message, right_rendered);
+    emit_write_string_with_newline(state, function_builder, 2, message)?;
+    emit_exit_call(state, function_builder, ABORT_EXIT_CODE);
+
+    function_builder.switch_to_block(merge_block);
+    function_builder.seal_block(merge_block);
+    Ok(TypedValue {
+        value: None,
+        type_reference: ExecutableTypeReference::Nil,
+        terminates: false,
+    })
+}
+
+fn compile_debug_string_call(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    arguments: &[ExecutableExpression],
+    type_arguments: &[ExecutableTypeReference],
+) -> Result<TypedValue, CompilerFailure> {
+    let ([argument], [rendered_type]) = (arguments, type_arguments) else {
+        return Err(build_failed(
+            "debug_string(...) requires exactly one typed argument".to_string(),
+            None,
+        ));
+    };
+    let argument = compile_expression(state, function_builder, compilation_context, argument)?;
+    if argument.terminates {
+        return Ok(argument);
+    }
+    let value = runtime_value_for_expected_type(
+        state,
+        function_builder,
+        argument.value,
+        &argument.type_reference,
+        rendered_type,
+    )?;
+    let rendered = emit_rendered_value(
+        state,
+        function_builder,
+        value,
+        rendered_type,
+        &mut Vec::new(),
+    )?;
+    Ok(TypedValue {
+        value: Some(rendered),
+        type_reference: ExecutableTypeReference::String,
+        terminates: false,
+    })
+}
+
+// Renders a value the way the interpreter does for `debug_string` and
+// `assert_equals`. Struct types already being rendered are tracked so that
+// recursive struct types, whose values could be cyclic, are rejected at build
+// time.
+fn emit_rendered_value(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    value: Option<Value>,
+    type_reference: &ExecutableTypeReference,
+    rendering_struct_references: &mut Vec<StructReference>,
+) -> Result<Value, CompilerFailure> {
+    let unrenderable = || {
+        build_failed(
+            format!(
+                "cannot render values of type {}",
+                type_reference_display(type_reference)
+            ),
+            None,
+        )
+    };
+    match type_reference {
+        ExecutableTypeReference::Nil | ExecutableTypeReference::Never => {
+            return intern_string_literal(state, function_builder, "nil");
+        }
+        ExecutableTypeReference::NominalType { name, .. } if name.contains('.') => {
+            return intern_string_literal(state, function_builder, name);
+        }
+        ExecutableTypeReference::TypeParameter { .. }
+        | ExecutableTypeReference::Function { .. } => return Err(unrenderable()),
+        _ => {}
+    }
+    let value = value.ok_or_else(|| {
+        build_failed(
+            "rendered argument produced no runtime value".to_string(),
+            None,
+        )
+    })?;
+    match type_reference {
+        ExecutableTypeReference::Int64 => convert_int64_to_string(state, function_builder, value),
+        ExecutableTypeReference::Boolean => {
+            let true_pointer = intern_string_literal(state, function_builder, "true")?;
+            let false_pointer = intern_string_literal(state, function_builder, "false")?;
+            Ok(function_builder
+                .ins()
+                .select(value, true_pointer, false_pointer))
+        }
+        ExecutableTypeReference::String => {
+            let quote_pointer = intern_string_literal(state, function_builder, "\"")?;
+            let opened = concatenate_strings(state, function_builder, quote_pointer, value);
+            Ok(concatenate_strings(
+                state,
+                function_builder,
+                opened,
+                quote_pointer,
+            ))
+        }
+        ExecutableTypeReference::List { element_type } => emit_rendered_list_value(
+            state,
+            function_builder,
+            value,
+            element_type,
+            rendering_struct_references,
+        ),
+        ExecutableTypeReference::Union { members } => {
+            let struct_member_count = members
+                .iter()
+                .filter(|member| {
+                    union_type_tag_for_type_reference(member)
+                        .is_ok_and(|tag| tag == UNION_TAG_STRUCT)
+                })
+                .count();
+            if struct_member_count > 1 {
+                return Err(unrenderable());
+            }
+            emit_rendered_union_value(
+                state,
+                function_builder,
+                value,
+                members,
+                rendering_struct_references,
+            )
+        }
+        _ => {
+            let (struct_declaration, type_substitutions_by_type_parameter_name) =
+                resolve_struct_type_details(state, type_reference).map_err(|_| unrenderable())?;
+            if rendering_struct_references.contains(&struct_declaration.struct_reference) {
+                return Err(unrenderable());
+            }
+            rendering_struct_references.push(struct_declaration.struct_reference.clone());
+            let struct_layout =
+                struct_layout_for_reference(state, &struct_declaration.struct_reference)?;
+            let mut rendered = intern_string_literal(
+                state,
+                function_builder,
+                &format!("{} {{", struct_declaration.name),
+            )?;
+            for (index, field) in struct_declaration.fields.iter().enumerate() {
+                let separator = if index == 0 { " " } else { ", " };
+                let field_label = intern_string_literal(
+                    state,
+                    function_builder,
+                    &format!("{separator}{}: ", field.name),
+                )?;
+                rendered = concatenate_strings(state, function_builder, rendered, field_label);
+                let field_type = substitute_type_reference(
+                    &field.type_reference,
+                    &type_substitutions_by_type_parameter_name,
+                );
+                let field_offset = struct_field_offset_bytes(struct_layout, &field.name)?;
+                let stored_field =
+                    function_builder
+                        .ins()
+                        .load(types::I64, MemFlags::new(), value, field_offset);
+                let field_value =
+                    runtime_value_from_i64_storage(function_builder, stored_field, &field_type);
+                let rendered_field = emit_rendered_value(
+                    state,
+                    function_builder,
+                    Some(field_value),
+                    &field_type,
+                    rendering_struct_references,
+                )?;
+                rendered = concatenate_strings(state, function_builder, rendered, rendered_field);
+            }
+            rendering_struct_references.pop();
+            let closing = if struct_declaration.fields.is_empty() {
+                "}"
+            } else {
+                " }"
+            };
+            let closing_pointer = intern_string_literal(state, function_builder, closing)?;
+            Ok(concatenate_strings(
+                state,
+                function_builder,
+                rendered,
+                closing_pointer,
+            ))
+        }
+    }
+}
+
+fn emit_rendered_list_value(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    list_pointer: Value,
+    element_type: &ExecutableTypeReference,
+    rendering_struct_references: &mut Vec<StructReference>,
+) -> Result<Value, CompilerFailure> {
+    let list_length = function_builder.ins().load(
+        types::I64,
+        MemFlags::new(),
+        list_pointer,
+        LIST_LENGTH_OFFSET,
+    );
+    let list_data_pointer = function_builder.ins().load(
+        types::I64,
+        MemFlags::new(),
+        list_pointer,
+        LIST_DATA_POINTER_OFFSET,
+    );
+    let opening_pointer = intern_string_literal(state, function_builder, "[")?;
+    let separator_pointer = intern_string_literal(state, function_builder, ", ")?;
+    let closing_pointer = intern_string_literal(state, function_builder, "]")?;
+
+    let index_variable = function_builder.declare_var(types::I64);
+    let initial_index = function_builder.ins().iconst(types::I64, 0);
+    function_builder.def_var(index_variable, initial_index);
+    let rendered_variable = function_builder.declare_var(types::I64);
+    function_builder.def_var(rendered_variable, opening_pointer);
+
+    let loop_header_block = function_builder.create_block();
+    let separator_block = function_builder.create_block();
+    let element_block = function_builder.create_block();
+    let exit_block = function_builder.create_block();
+    function_builder.ins().jump(loop_header_block, &[]);
+
+    function_builder.switch_to_block(loop_header_block);
+    let index = function_builder.use_var(index_variable);
+    let index_in_range = function_builder
+        .ins()
+        .icmp(IntCC::SignedLessThan, index, list_length);
+    function_builder
+        .ins()
+        .brif(index_in_range, separator_block, &[], exit_block, &[]);
+    function_builder.seal_block(separator_block);
+
+    function_builder.switch_to_block(separator_block);
+    let index = function_builder.use_var(index_variable);
+    let rendered = function_builder.use_var(rendered_variable);
+    let is_first_element = function_builder.ins().icmp_imm(IntCC::Equal, index, 0);
+    let separated = concatenate_strings(state, function_builder, rendered, separator_pointer);
+    let rendered = function_builder
+        .ins()
+        .select(is_first_element, rendered, separated);
+    function_builder.def_var(rendered_variable, rendered);
+    function_builder.ins().jump(element_block, &[]);
+    function_builder.seal_block(element_block);
+
+    function_builder.switch_to_block(element_block);
+    let index = function_builder.use_var(index_variable);
+    let element_offset = function_builder.ins().imul_imm(index, 8);
+    let element_pointer = function_builder
+        .ins()
+        .iadd(list_data_pointer, element_offset);
+    let stored_element =
+        function_builder
+            .ins()
+            .load(types::I64, MemFlags::new(), element_pointer, 0);
+    let element_value =
+        runtime_value_from_i64_storage(function_builder, stored_element, element_type);
+    let rendered_element = emit_rendered_value(
+        state,
+        function_builder,
+        Some(element_value),
+        element_type,
+        rendering_struct_references,
+    )?;
+    let rendered = function_builder.use_var(rendered_variable);
+    let rendered = concatenate_strings(state, function_builder, rendered, rendered_element);
+    function_builder.def_var(rendered_variable, rendered);
+    let index = function_builder.use_var(index_variable);
+    let next_index = function_builder.ins().iadd_imm(index, 1);
+    function_builder.def_var(index_variable, next_index);
+    function_builder.ins().jump(loop_header_block, &[]);
+    function_builder.seal_block(loop_header_block);
+
+    function_builder.switch_to_block(exit_block);
+    function_builder.seal_block(exit_block);
+    let rendered = function_builder.use_var(rendered_variable);
+    Ok(concatenate_strings(
+        state,
+        function_builder,
+        rendered,
+        closing_pointer,
+    ))
+}
+
+fn emit_rendered_union_value(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    union_box_pointer: Value,
+    members: &[ExecutableTypeReference],
+    rendering_struct_references: &mut Vec<StructReference>,
+) -> Result<Value, CompilerFailure> {
+    let merge_block = function_builder.create_block();
+    function_builder.append_block_param(merge_block, types::I64);
+    for member in members {
+        let member_matches =
+            emit_union_match_condition(function_builder, union_box_pointer, member)?;
+        let member_block = function_builder.create_block();
+        let next_block = function_builder.create_block();
+        function_builder
+            .ins()
+            .brif(member_matches, member_block, &[], next_block, &[]);
+        function_builder.seal_block(member_block);
+        function_builder.seal_block(next_block);
+
+        function_builder.switch_to_block(member_block);
+        let payload = extract_union_payload_for_type(function_builder, union_box_pointer, member);
+        let rendered_member = emit_rendered_value(
+            state,
+            function_builder,
+            payload,
+            member,
+            rendering_struct_references,
+        )?;
+        function_builder
+            .ins()
+            .jump(merge_block, &[BlockArg::Value(rendered_member)]);
+
+        function_builder.switch_to_block(next_block);
+    }
+    function_builder.ins().trap(TrapCode::user(2).unwrap());
+    function_builder.seal_block(merge_block);
+
+    function_builder.switch_to_block(merge_block);
+    Ok(function_builder.block_params(merge_block)[0])
+}
+
+fn compile_call_expression(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    callee: &ExecutableExpression,
+    call_target: Option<&ExecutableCallTarget>,
+    arguments: &[ExecutableExpression],
+    type_arguments: &[ExecutableTypeReference],
+) -> Result<TypedValue, CompilerFailure> {
+    if let Some(call_target) = call_target {
+        return match call_target {
+            ExecutableCallTarget::BuiltinFunction { function_name } => {
+                if function_name == "assert_equals" {
+                    return compile_assert_equals_call(
+                        state,
+                        function_builder,
+                        compilation_context,
+                        arguments,
+                        type_arguments,
+                    );
+                }
+                if function_name == "debug_string" {
+                    return compile_debug_string_call(
+                        state,
+                        function_builder,
+                        compilation_context,
+                        arguments,
+                        type_arguments,
+                    );
+                }
+                // `min` and `max` are generic, so the int64 builtins check their
+                // argument types instead of rejecting type arguments.
+                if let Some(int64_result) = compile_int64_builtin_call(
+                    state,
+                    function_builder,
+                    compilation_context,
+                    function_name,
+                    arguments,
+                )? {
+                    return Ok(int64_result);
+                }
+
+                if !type_arguments.is_empty() {
+                    return Err(build_failed(
+                        format!("builtin function '{function_name}' does not take type arguments"),
+                        None,
+                    ));
+                }
+                if function_name == PRINT_FUNCTION_CONTRACT.language_name {
+                    if arguments.len() != 1 {
+                        return Err(build_failed(
+                            "print(...) requires exactly one argument".to_string(),
+                            None,
+                        ));
+                    }
+                    let argument = compile_expression(
+                        state,
+                        function_builder,
+                        compilation_context,
+                        &arguments[0],
+                    )?;
+                    if argument.terminates {
+                        return Ok(argument);
+                    }
+                    if argument.type_reference != ExecutableTypeReference::String {
+                        return Err(build_failed(
+                            "print(...) requires string argument".to_string(),
+                            None,
+                        ));
+                    }
+                    let pointer = argument.value.ok_or_else(|| {
+                        build_failed("print argument produced no runtime value".to_string(), None)
+                    })?;
+                    if let Some(capability) = PRINT_FUNCTION_CONTRACT.required_capability {
+                        emit_capability_check(state, function_builder, capability)?;
+                    }
+                    emit_write_string_with_newline(state, function_builder, 1, pointer)?;
+                    return Ok(TypedValue {
+                        value: None,
+                        type_reference: ExecutableTypeReference::Nil,
+                        terminates: false,
+                    });
+                }
+
+                if function_name == ABORT_FUNCTION_CONTRACT.language_name {
+                    if arguments.len() != 1 {
+                        return Err(build_failed(
+                            "abort(...) requires exactly one argument".to_string(),
+                            None,
+                        ));
+                    }
+                    let argument = compile_expression(
+                        state,
+                        function_builder,
+                        compilation_context,
+                        &arguments[0],
+                    )?;
+                    if argument.terminates {
+                        return Ok(argument);
+                    }
+                    if argument.type_reference != ExecutableTypeReference::String {
+                        return Err(build_failed(
+                            "abort(...) requires string argument".to_string(),
+                            None,
+                        ));
+                    }
+                    let pointer = argument.value.ok_or_else(|| {
+                        build_failed("abort argument produced no runtime value".to_string(), None)
+                    })?;
+                    emit_write_string_with_newline(state, function_builder, 2, pointer)?;
+                    emit_exit_call(state, function_builder, ABORT_EXIT_CODE);
+                    return Ok(TypedValue {
+                        value: None,
+                        type_reference: ExecutableTypeReference::Never,
+                        terminates: true,
+                    });
+                }
+                if function_name == UNREACHABLE_FUNCTION_CONTRACT.language_name {
+                    if !arguments.is_empty() {
+                        return Err(build_failed(
+                            "unreachable() does not take arguments".to_string(),
+                            None,
+                        ));
+                    }
+                    let message_pointer =
+                        intern_string_literal(state, function_builder, "entered unreachable code")?;
+                    emit_write_string_with_newline(state, function_builder, 2, message_pointer)?;
+                    emit_exit_call(state, function_builder, ABORT_EXIT_CODE);
+                    return Ok(TypedValue {
+                        value: None,
+                        type_reference: ExecutableTypeReference::Never,
+                        terminates: true,
+                    });
+                }
+                if function_name == ASSERT_FUNCTION_CONTRACT.language_name {
+                    let [
+                        _,
+                        ExecutableExpression::StringLiteral { value: description },
+                    ] = arguments
+                    else {
+                        return Err(build_failed(
+                            "assert(...) requires a condition and its description".to_string(),
+                            None,
+                        ));
+                    };
+                    let argument = compile_expression(
+                        state,
+                        function_builder,
+                        compilation_context,
+                        &arguments[0],
+                    )?;
+                    if argument.terminates {
+                        return Ok(argument);
+                    }
+                    if argument.type_reference != ExecutableTypeReference::Boolean {
+                        return Err(build_failed(
+                            "assert(...) requires boolean argument".to_string(),
+                            None,
+                        ));
+                    }
+                    let condition_value = argument.value.ok_or_else(|| {
+                        build_failed(
+                            "assert argument produced no runtime value".to_string(),
+                            None,
+                        )
+                    })?;
+                    let zero = function_builder.ins().iconst(types::I8, 0);
+                    let condition_is_true =
+                        function_builder
+                            .ins()
+                            .icmp(IntCC::NotEqual, condition_value, zero);
+
+                    let pass_block = function_builder.create_block();
+                    let fail_block = function_builder.create_block();
+                    let merge_block = function_builder.create_block();
+                    function_builder.ins().brif(
+                        condition_is_true,
+                        pass_block,
+                        &[],
+                        fail_block,
+                        &[],
+                    );
+
+                    function_builder.switch_to_block(fail_block);
+                    let message_pointer = intern_string_literal(
+                        state,
+                        function_builder,
+                        &format!("assertion failed: {description}"),
+                    )?;
+                    emit_write_string_with_newline(state, function_builder, 2, message_pointer)?;
+                    emit_exit_call(state, function_builder, ABORT_EXIT_CODE);
+                    function_builder.seal_block(fail_block);
 
                     function_builder.switch_to_block(pass_block);
                     function_builder.ins().jump(merge_block, &[]);
@@ -1869,6 +2609,63 @@ fn compile_call_expression(
                     });
                 }
 
+                if function_name == NOW_FUNCTION_CONTRACT.language_name {
+                    if !arguments.is_empty() {
+                        return Err(build_failed(
+                            "now() does not take arguments".to_string(),
+                            None,
+                        ));
+                    }
+                    let now = emit_environment_integer_or_time(
+                        state,
+                        function_builder,
+                        FIXED_NOW_ENVIRONMENT_VARIABLE,
+                    )?;
+                    return Ok(TypedValue {
+                        value: Some(now),
+                        type_reference: ExecutableTypeReference::Int64,
+                        terminates: false,
+                    });
+                }
+                if function_name == RANDOM_INT_FUNCTION_CONTRACT.language_name {
+                    if arguments.len() != 2 {
+                        return Err(build_failed(
+                            "random_int(...) requires exactly two arguments".to_string(),
+                            None,
+                        ));
+                    }
+                    let mut bounds = Vec::with_capacity(2);
+                    for argument in arguments {
+                        let argument = compile_expression(
+                            state,
+                            function_builder,
+                            compilation_context,
+                            argument,
+                        )?;
+                        if argument.terminates {
+                            return Ok(argument);
+                        }
+                        if argument.type_reference != ExecutableTypeReference::Int64 {
+                            return Err(build_failed(
+                                "random_int(...) requires int64 arguments".to_string(),
+                                None,
+                            ));
+                        }
+                        bounds.push(argument.value.ok_or_else(|| {
+                            build_failed(
+                                "random_int argument produced no runtime value".to_string(),
+                                None,
+                            )
+                        })?);
+                    }
+                    let value = emit_random_int(state, function_builder, bounds[0], bounds[1])?;
+                    return Ok(TypedValue {
+                        value: Some(value),
+                        type_reference: ExecutableTypeReference::Int64,
+                        terminates: false,
+                    });
+                }
+
                 if let Some(conversion_result) = compile_builtin_conversion_call(
                     state,
                     function_builder,
@@ -2220,6 +3017,238 @@ fn compile_function_value_call_expression(
     }
 }
 
+fn compile_int64_builtin_call(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    function_name: &str,
+    arguments: &[ExecutableExpression],
+) -> Result<Option<TypedValue>, CompilerFailure> {
+    let parameter_count = match function_name {
+        "abs" => 1,
+        "min" | "max" | "pow" | "checked_add" | "checked_sub" | "checked_mul"
+        | "saturating_add" | "saturating_sub" | "saturating_mul" => 2,
+        _ => return Ok(None),
+    };
+    if arguments.len() != parameter_count {
+        return Err(build_failed(
+            format!("{function_name}(...) requires exactly {parameter_count} argument(s)"),
+            None,
+        ));
+    }
+    let mut values = Vec::with_capacity(arguments.len());
+    for argument in arguments {
+        let argument = compile_expression(state, function_builder, compilation_context, argument)?;
+        if argument.terminates {
+            return Ok(Some(argument));
+        }
+        if argument.type_reference != ExecutableTypeReference::Int64 {
+            return Err(build_failed(
+                format!("{function_name}(...) requires int64 arguments"),
+                None,
+            ));
+        }
+        values.push(argument.value.ok_or_else(|| {
+            build_failed(
+                format!("{function_name} argument produced no runtime value"),
+                None,
+            )
+        })?);
+    }
+    let value = match function_name {
+        "abs" => function_builder.ins().iabs(values[0]),
+        "min" => function_builder.ins().smin(values[0], values[1]),
+        "max" => function_builder.ins().smax(values[0], values[1]),
+        "pow" => emit_wrapping_pow(state, function_builder, values[0], values[1])?,
+        _ => {
+            return compile_overflow_arithmetic(
+                state,
+                function_builder,
+                function_name,
+                values[0],
+                values[1],
+            )
+            .map(Some);
+        }
+    };
+    Ok(Some(TypedValue {
+        value: Some(value),
+        type_reference: ExecutableTypeReference::Int64,
+        terminates: false,
+    }))
+}
+
+// `checked_*` builtins produce nil when the operation overflows, and
+// `saturating_*` builtins produce the int64 bound the exact result lies past.
+// `+`, `-`, and `*` wrap unless the run aborts on overflow, in which case an
+// operation the program describes aborts with its description. Division and
+// remainder by zero always abort; `i64::MIN / -1` overflows to `i64::MIN`, and
+// the divisor is replaced so that the instruction does not trap.
+fn compile_int64_arithmetic(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    operator: ExecutableBinaryOperator,
+    left: Value,
+    right: Value,
+    operation_description: Option<&str>,
+) -> Result<Value, CompilerFailure> {
+    let overflow_message =
+        arithmetic_abort_message(ARITHMETIC_OVERFLOW_MESSAGE, operation_description);
+    let (result, overflowed) = match operator {
+        ExecutableBinaryOperator::Add if operation_description.is_some() => {
+            function_builder.ins().sadd_overflow(left, right)
+        }
+        ExecutableBinaryOperator::Subtract if operation_description.is_some() => {
+            function_builder.ins().ssub_overflow(left, right)
+        }
+        ExecutableBinaryOperator::Multiply if operation_description.is_some() => {
+            function_builder.ins().smul_overflow(left, right)
+        }
+        ExecutableBinaryOperator::Add => return Ok(function_builder.ins().iadd(left, right)),
+        ExecutableBinaryOperator::Subtract => return Ok(function_builder.ins().isub(left, right)),
+        ExecutableBinaryOperator::Multiply => return Ok(function_builder.ins().imul(left, right)),
+        ExecutableBinaryOperator::Divide | ExecutableBinaryOperator::Modulo => {
+            let zero_message = arithmetic_abort_message(
+                if matches!(operator, ExecutableBinaryOperator::Divide) {
+                    INTEGER_DIVISION_BY_ZERO_MESSAGE
+                } else {
+                    INTEGER_REMAINDER_BY_ZERO_MESSAGE
+                },
+                operation_description,
+            );
+            let is_zero = function_builder.ins().icmp_imm(IntCC::Equal, right, 0);
+            crate::runtime_interface_emission::emit_abort_when(
+                &mut state.module,
+                &state.external_runtime_functions,
+                function_builder,
+                is_zero,
+                &zero_message,
+            )?;
+            let left_is_minimum = function_builder
+                .ins()
+                .icmp_imm(IntCC::Equal, left, i64::MIN);
+            let right_is_negative_one = function_builder.ins().icmp_imm(IntCC::Equal, right, -1);
+            let overflowed = function_builder
+                .ins()
+                .band(left_is_minimum, right_is_negative_one);
+            let one = function_builder.ins().iconst(types::I64, 1);
+            let divisor = function_builder.ins().select(overflowed, one, right);
+            if matches!(operator, ExecutableBinaryOperator::Modulo) {
+                return Ok(function_builder.ins().srem(left, divisor));
+            }
+            let quotient = function_builder.ins().sdiv(left, divisor);
+            if operation_description.is_none() {
+                return Ok(quotient);
+            }
+            (quotient, overflowed)
+        }
+        _ => {
+            return Err(build_failed(
+                "operator is not an int64 arithmetic operator".to_string(),
+                None,
+            ));
+        }
+    };
+    crate::runtime_interface_emission::emit_overflow_check(
+        &mut state.module,
+        &state.external_runtime_functions,
+        function_builder,
+        overflowed,
+        &overflow_message,
+    )?;
+    Ok(result)
+}
+
+fn compile_overflow_arithmetic(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    function_name: &str,
+    left: Value,
+    right: Value,
+) -> Result<TypedValue, CompilerFailure> {
+    let Some((mode, operation)) = function_name.split_once('_') else {
+        return Err(build_failed(
+            format!("unknown builtin function '{function_name}'"),
+            None,
+        ));
+    };
+    let (wrapped, overflowed) = match operation {
+        "add" => function_builder.ins().sadd_overflow(left, right),
+        "sub" => function_builder.ins().ssub_overflow(left, right),
+        _ => function_builder.ins().smul_overflow(left, right),
+    };
+    if mode == "saturating" {
+        // The exact result is below the minimum when it is negative: for
+        // addition when `right` is negative, for subtraction when `right` is
+        // positive, and for multiplication when the operand signs differ.
+        let is_negative = match operation {
+            "add" => function_builder
+                .ins()
+                .icmp_imm(IntCC::SignedLessThan, right, 0),
+            "sub" => function_builder
+                .ins()
+                .icmp_imm(IntCC::SignedGreaterThan, right, 0),
+            _ => {
+                let signs = function_builder.ins().bxor(left, right);
+                function_builder
+                    .ins()
+                    .icmp_imm(IntCC::SignedLessThan, signs, 0)
+            }
+        };
+        let minimum = function_builder.ins().iconst(types::I64, i64::MIN);
+        let maximum = function_builder.ins().iconst(types::I64, i64::MAX);
+        let bound = function_builder.ins().select(is_negative, minimum, maximum);
+        return Ok(TypedValue {
+            value: Some(function_builder.ins().select(overflowed, bound, wrapped)),
+            type_reference: ExecutableTypeReference::Int64,
+            terminates: false,
+        });
+    }
+
+    let overflow_block = function_builder.create_block();
+    let result_block = function_builder.create_block();
+    let merge_block = function_builder.create_block();
+    function_builder.append_block_param(merge_block, types::I64);
+    function_builder
+        .ins()
+        .brif(overflowed, overflow_block, &[], result_block, &[]);
+    function_builder.seal_block(overflow_block);
+    function_builder.seal_block(result_block);
+
+    function_builder.switch_to_block(overflow_block);
+    let nil_payload = function_builder.ins().iconst(types::I64, 0);
+    let nil_box_pointer = box_union_value(
+        state,
+        function_builder,
+        nil_payload,
+        &ExecutableTypeReference::Nil,
+    )?;
+    function_builder
+        .ins()
+        .jump(merge_block, &[BlockArg::Value(nil_box_pointer)]);
+
+    function_builder.switch_to_block(result_block);
+    let result_box_pointer = box_union_value(
+        state,
+        function_builder,
+        wrapped,
+        &ExecutableTypeReference::Int64,
+    )?;
+    function_builder
+        .ins()
+        .jump(merge_block, &[BlockArg::Value(result_box_pointer)]);
+    function_builder.seal_block(merge_block);
+
+    function_builder.switch_to_block(merge_block);
+    Ok(TypedValue {
+        value: Some(function_builder.block_params(merge_block)[0]),
+        type_reference: ExecutableTypeReference::Union {
+            members: vec![ExecutableTypeReference::Int64, ExecutableTypeReference::Nil],
+        },
+        terminates: false,
+    })
+}
+
 fn compile_builtin_conversion_call(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
@@ -2384,7 +3413,7 @@ fn compile_index_access_expression(
     function_builder.seal_block(invalid_index_block);
 
     function_builder.switch_to_block(invalid_index_block);
-    function_builder.ins().trap(TrapCode::user(3).unwrap());
+    emit_list_index_out_of_bounds_abort(state, function_builder, index_value, list_length)?;
 
     function_builder.switch_to_block(store_block);
     let element_offset = function_builder.ins().imul_imm(index_value, 8);
@@ -2525,7 +3554,7 @@ fn compile_index_assign_statement(
     function_builder.seal_block(invalid_index_block);
 
     function_builder.switch_to_block(invalid_index_block);
-    function_builder.ins().trap(TrapCode::user(3).unwrap());
+    emit_list_index_out_of_bounds_abort(state, function_builder, index_value, list_length)?;
 
     function_builder.switch_to_block(store_block);
     let element_offset = function_builder.ins().imul_imm(index_value, 8);
@@ -2542,7 +3571,7 @@ fn compile_struct_literal_expression(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
     compilation_context: &mut FunctionCompilationContext,
-    struct_reference: &ExecutableStructReference,
+    struct_reference: &StructReference,
     type_reference: &ExecutableTypeReference,
     fields: &[compiler__executable_program::ExecutableStructLiteralField],
 ) -> Result<TypedValue, CompilerFailure> {
@@ -2562,19 +3591,12 @@ fn compile_struct_literal_expression(
     let type_substitutions_by_type_parameter_name =
         type_substitutions_for_struct_type(struct_declaration, type_reference)?;
 
-    let allocated_pointer = allocate_heap_bytes(
-        state,
-        function_builder,
-        i64::try_from(struct_declaration.fields.len() * 8).map_err(|_| {
-            build_failed(
-                "struct literal size exceeds supported allocation range".to_string(),
-                None,
-            )
-        })?,
-    )?;
+    let struct_layout = struct_layout_for_reference(state, struct_reference)?;
+    let allocated_pointer =
+        allocate_heap_bytes(state, function_builder, i64::from(struct_layout.size_bytes))?;
     let mem_flags = MemFlags::new();
 
-    for (field_index, declared_field) in struct_declaration.fields.iter().enumerate() {
+    for declared_field in &struct_declaration.fields {
         let provided_field = fields
             .iter()
             .find(|field| field.name == declared_field.name)
@@ -2625,12 +3647,7 @@ fn compile_struct_literal_expression(
             mem_flags,
             stored_value,
             allocated_pointer,
-            i32::try_from(field_index * 8).map_err(|_| {
-                build_failed(
-                    "struct field offset exceeds supported range".to_string(),
-                    None,
-                )
-            })?,
+            struct_field_offset_bytes(struct_layout, &declared_field.name)?,
         );
     }
 
@@ -2736,6 +3753,7 @@ fn compile_field_access_expression(
     compilation_context: &mut FunctionCompilationContext,
     target: &ExecutableExpression,
     field_name: &str,
+    resolved_type_reference: Option<&ExecutableTypeReference>,
 ) -> Result<TypedValue, CompilerFailure> {
     let compiled_target = compile_expression(state, function_builder, compilation_context, target)?;
     if compiled_target.terminates {
@@ -2768,11 +3786,10 @@ fn compile_field_access_expression(
     }
     let (struct_declaration, type_substitutions_by_type_parameter_name) =
         resolve_struct_type_details(state, &compiled_target.type_reference)?;
-    let (field_index, declared_field) = struct_declaration
+    let declared_field = struct_declaration
         .fields
         .iter()
-        .enumerate()
-        .find(|(_, field)| field.name == field_name)
+        .find(|field| field.name == field_name)
         .ok_or_else(|| {
             build_failed(
                 format!("unknown field '{}.{}'", struct_declaration.name, field_name),
@@ -2788,18 +3805,33 @@ fn compile_field_access_expression(
                 None,
             )
         })?,
-        i32::try_from(field_index * 8)
-            .map_err(|_| build_failed("field offset exceeds supported range".to_string(), None))?,
+        struct_field_offset_bytes(
+            struct_layout_for_reference(state, &struct_declaration.struct_reference)?,
+            field_name,
+        )?,
     );
     let field_type = substitute_type_reference(
         &declared_field.type_reference,
         &type_substitutions_by_type_parameter_name,
     );
     let loaded_value = runtime_value_from_i64_storage(function_builder, loaded_i64, &field_type);
-
+    let Some(resolved_type_reference) = resolved_type_reference else {
+        return Ok(TypedValue {
+            value: Some(loaded_value),
+            type_reference: field_type,
+            terminates: false,
+        });
+    };
+    let lowered_value = runtime_value_for_expected_type(
+        state,
+        function_builder,
+        Some(loaded_value),
+        &field_type,
+        resolved_type_reference,
+    )?;
     Ok(TypedValue {
-        value: Some(loaded_value),
-        type_reference: field_type,
+        value: lowered_value,
+        type_reference: resolved_type_reference.clone(),
         terminates: false,
     })
 }
@@ -2814,6 +3846,7 @@ fn compile_method_call_expression(
     let ExecutableExpression::FieldAccess {
         target,
         field: method_name,
+        ..
     } = callee
     else {
         return Err(build_failed(
@@ -3167,60 +4200,223 @@ fn compile_interface_method_call_through_vtable(
         let lowered_argument = runtime_value_for_expected_type(
             state,
             function_builder,
-            compiled_argument.value,
-            &compiled_argument.type_reference,
-            &parameter.type_reference,
+            compiled_argument.value,
+            &compiled_argument.type_reference,
+            &parameter.type_reference,
+        )?;
+        call_values.push(lowered_argument.ok_or_else(|| {
+            build_failed(
+                "interface method argument produced no runtime value".to_string(),
+                None,
+            )
+        })?);
+    }
+
+    let mut call_signature = state.module.make_signature();
+    call_signature.params.push(AbiParam::new(types::I64));
+    for parameter in &method_declaration.parameters {
+        call_signature
+            .params
+            .push(AbiParam::new(cranelift_type_for(&parameter.type_reference)));
+    }
+    if !matches!(
+        method_declaration.return_type,
+        ExecutableTypeReference::Nil | ExecutableTypeReference::Never
+    ) {
+        call_signature
+            .returns
+            .push(AbiParam::new(cranelift_type_for(
+                &method_declaration.return_type,
+            )));
+    }
+    let signature_reference = function_builder.import_signature(call_signature);
+    let call =
+        function_builder
+            .ins()
+            .call_indirect(signature_reference, function_pointer, &call_values);
+
+    if matches!(
+        method_declaration.return_type,
+        ExecutableTypeReference::Nil | ExecutableTypeReference::Never
+    ) {
+        Ok(TypedValue {
+            value: None,
+            type_reference: method_declaration.return_type.clone(),
+            terminates: matches!(
+                method_declaration.return_type,
+                ExecutableTypeReference::Never
+            ),
+        })
+    } else {
+        Ok(TypedValue {
+            value: Some(function_builder.inst_results(call)[0]),
+            type_reference: method_declaration.return_type.clone(),
+            terminates: false,
+        })
+    }
+}
+
+// A value whose type is a single variant is known to be that variant; any
+// other enum value is a union box, whose payload identifies its variant.
+fn compile_enum_variant_name_expression(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    value_expression: &ExecutableExpression,
+    enum_variant_references: &[EnumVariantReference],
+) -> Result<TypedValue, CompilerFailure> {
+    let value = compile_expression(
+        state,
+        function_builder,
+        compilation_context,
+        value_expression,
+    )?;
+    if value.terminates {
+        return Ok(value);
+    }
+    if let ExecutableTypeReference::NominalType { name, .. } = &value.type_reference {
+        let (_, variant_name) = split_enum_variant_type_name(name)?;
+        return Ok(TypedValue {
+            value: Some(intern_string_literal(
+                state,
+                function_builder,
+                variant_name,
+            )?),
+            type_reference: ExecutableTypeReference::String,
+            terminates: false,
+        });
+    }
+    let union_box_pointer = value.value.ok_or_else(|| {
+        build_failed(
+            "variant_name operand produced no runtime value".to_string(),
+            None,
+        )
+    })?;
+
+    let merge_block = function_builder.create_block();
+    function_builder.append_block_param(merge_block, types::I64);
+    for enum_variant_reference in enum_variant_references {
+        let variant_matches = emit_union_match_condition(
+            function_builder,
+            union_box_pointer,
+            &enum_variant_type_reference(enum_variant_reference),
+        )?;
+        let variant_block = function_builder.create_block();
+        let next_block = function_builder.create_block();
+        function_builder
+            .ins()
+            .brif(variant_matches, variant_block, &[], next_block, &[]);
+        function_builder.seal_block(variant_block);
+        function_builder.seal_block(next_block);
+
+        function_builder.switch_to_block(variant_block);
+        let variant_name = intern_string_literal(
+            state,
+            function_builder,
+            &enum_variant_reference.variant_name,
+        )?;
+        function_builder
+            .ins()
+            .jump(merge_block, &[BlockArg::Value(variant_name)]);
+
+        function_builder.switch_to_block(next_block);
+    }
+    function_builder.ins().trap(TrapCode::user(2).unwrap());
+    function_builder.seal_block(merge_block);
+
+    function_builder.switch_to_block(merge_block);
+    Ok(TypedValue {
+        value: Some(function_builder.block_params(merge_block)[0]),
+        type_reference: ExecutableTypeReference::String,
+        terminates: false,
+    })
+}
+
+// Compares the string with each variant name in turn and boxes the first
+// variant it names, or nil when it names none.
+fn compile_enum_variant_from_string_expression(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    value_expression: &ExecutableExpression,
+    enum_variant_references: &[EnumVariantReference],
+    type_reference: &ExecutableTypeReference,
+) -> Result<TypedValue, CompilerFailure> {
+    let value = compile_expression(
+        state,
+        function_builder,
+        compilation_context,
+        value_expression,
+    )?;
+    if value.terminates {
+        return Ok(value);
+    }
+    let string_pointer = value.value.ok_or_else(|| {
+        build_failed(
+            "from_string operand produced no runtime value".to_string(),
+            None,
+        )
+    })?;
+    let strcmp = state.module.declare_func_in_func(
+        state.external_runtime_functions.strcmp,
+        function_builder.func,
+    );
+
+    let merge_block = function_builder.create_block();
+    function_builder.append_block_param(merge_block, types::I64);
+    for enum_variant_reference in enum_variant_references {
+        let variant_name = intern_string_literal(
+            state,
+            function_builder,
+            &enum_variant_reference.variant_name,
         )?;
-        call_values.push(lowered_argument.ok_or_else(|| {
-            build_failed(
-                "interface method argument produced no runtime value".to_string(),
-                None,
-            )
-        })?);
-    }
+        let comparison_call = function_builder
+            .ins()
+            .call(strcmp, &[string_pointer, variant_name]);
+        let comparison = function_builder.inst_results(comparison_call)[0];
+        let names_variant = function_builder.ins().icmp_imm(IntCC::Equal, comparison, 0);
+        let variant_block = function_builder.create_block();
+        let next_block = function_builder.create_block();
+        function_builder
+            .ins()
+            .brif(names_variant, variant_block, &[], next_block, &[]);
+        function_builder.seal_block(variant_block);
+        function_builder.seal_block(next_block);
 
-    let mut call_signature = state.module.make_signature();
-    call_signature.params.push(AbiParam::new(types::I64));
-    for parameter in &method_declaration.parameters {
-        call_signature
-            .params
-            .push(AbiParam::new(cranelift_type_for(&parameter.type_reference)));
-    }
-    if !matches!(
-        method_declaration.return_type,
-        ExecutableTypeReference::Nil | ExecutableTypeReference::Never
-    ) {
-        call_signature
-            .returns
-            .push(AbiParam::new(cranelift_type_for(
-                &method_declaration.return_type,
-            )));
-    }
-    let signature_reference = function_builder.import_signature(call_signature);
-    let call =
+        function_builder.switch_to_block(variant_block);
+        let variant_tag = function_builder
+            .ins()
+            .iconst(types::I64, enum_variant_tag(enum_variant_reference));
+        let variant_box_pointer = box_union_value(
+            state,
+            function_builder,
+            variant_tag,
+            &enum_variant_type_reference(enum_variant_reference),
+        )?;
         function_builder
             .ins()
-            .call_indirect(signature_reference, function_pointer, &call_values);
+            .jump(merge_block, &[BlockArg::Value(variant_box_pointer)]);
 
-    if matches!(
-        method_declaration.return_type,
-        ExecutableTypeReference::Nil | ExecutableTypeReference::Never
-    ) {
-        Ok(TypedValue {
-            value: None,
-            type_reference: method_declaration.return_type.clone(),
-            terminates: matches!(
-                method_declaration.return_type,
-                ExecutableTypeReference::Never
-            ),
-        })
-    } else {
-        Ok(TypedValue {
-            value: Some(function_builder.inst_results(call)[0]),
-            type_reference: method_declaration.return_type.clone(),
-            terminates: false,
-        })
+        function_builder.switch_to_block(next_block);
     }
+    let nil_payload = function_builder.ins().iconst(types::I64, 0);
+    let nil_box_pointer = box_union_value(
+        state,
+        function_builder,
+        nil_payload,
+        &ExecutableTypeReference::Nil,
+    )?;
+    function_builder
+        .ins()
+        .jump(merge_block, &[BlockArg::Value(nil_box_pointer)]);
+    function_builder.seal_block(merge_block);
+
+    function_builder.switch_to_block(merge_block);
+    Ok(TypedValue {
+        value: Some(function_builder.block_params(merge_block)[0]),
+        type_reference: type_reference.clone(),
+        terminates: false,
+    })
 }
 
 fn compile_matches_expression(
@@ -3261,6 +4457,119 @@ fn compile_matches_expression(
     })
 }
 
+// Each branch is converted to the joined result type before it jumps to the
+// merge block, so a branch whose type is a member of a union result is boxed.
+fn compile_if_expression(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &mut FunctionCompilationContext,
+    condition: &ExecutableExpression,
+    branches: [&ExecutableExpression; 2],
+    type_reference: &ExecutableTypeReference,
+) -> Result<TypedValue, CompilerFailure> {
+    let condition_typed_value =
+        compile_expression(state, function_builder, compilation_context, condition)?;
+    if condition_typed_value.terminates {
+        return Ok(condition_typed_value);
+    }
+    if condition_typed_value.type_reference != ExecutableTypeReference::Boolean {
+        return Err(build_failed(
+            "if condition must be boolean".to_string(),
+            None,
+        ));
+    }
+    let condition_value = condition_typed_value
+        .value
+        .ok_or_else(|| build_failed("if condition produced no runtime value".to_string(), None))?;
+    let zero = function_builder.ins().iconst(types::I8, 0);
+    let condition_is_true = function_builder
+        .ins()
+        .icmp(IntCC::NotEqual, condition_value, zero);
+
+    let then_block = function_builder.create_block();
+    let else_block = function_builder.create_block();
+    let merge_block = function_builder.create_block();
+    function_builder
+        .ins()
+        .brif(condition_is_true, then_block, &[], else_block, &[]);
+
+    let mut merged_result_variable: Option<Variable> = None;
+    let mut all_branches_terminate = true;
+    for (branch_block, branch) in [then_block, else_block].into_iter().zip(branches) {
+        function_builder.switch_to_block(branch_block);
+        let branch_value =
+            compile_expression(state, function_builder, compilation_context, branch)?;
+        if branch_value.terminates {
+            function_builder.seal_block(branch_block);
+            continue;
+        }
+        all_branches_terminate = false;
+        let runtime_value = runtime_value_for_expected_type(
+            state,
+            function_builder,
+            branch_value.value,
+            &branch_value.type_reference,
+            type_reference,
+        )?;
+        if let Some(runtime_value) = runtime_value {
+            let variable = *merged_result_variable.get_or_insert_with(|| {
+                function_builder.declare_var(function_builder.func.dfg.value_type(runtime_value))
+            });
+            function_builder.def_var(variable, runtime_value);
+        }
+        function_builder.ins().jump(merge_block, &[]);
+        function_builder.seal_block(branch_block);
+    }
+
+    if all_branches_terminate {
+        return Ok(TypedValue {
+            value: None,
+            type_reference: ExecutableTypeReference::Never,
+            terminates: true,
+        });
+    }
+    function_builder.switch_to_block(merge_block);
+    function_builder.seal_block(merge_block);
+    Ok(TypedValue {
+        value: merged_result_variable.map(|variable| function_builder.use_var(variable)),
+        type_reference: type_reference.clone(),
+        terminates: false,
+    })
+}
+
+// Bindings made by the statements stay local to the block; assignments to
+// enclosing locals still reach them because the variables are shared.
+fn compile_block_expression(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    compilation_context: &FunctionCompilationContext,
+    statements: &[ExecutableStatement],
+    value: &ExecutableExpression,
+) -> Result<TypedValue, CompilerFailure> {
+    let mut block_context = FunctionCompilationContext {
+        local_value_by_name: compilation_context.local_value_by_name.clone(),
+        type_parameter_witness_by_name: compilation_context.type_parameter_witness_by_name.clone(),
+        loop_context: None,
+    };
+    // Type analysis rejects `return` inside a block expression, so the
+    // return type passed here is never consulted.
+    let terminated = compile_statements(
+        state,
+        function_builder,
+        &mut block_context,
+        statements,
+        &ExecutableTypeReference::Never,
+    )?;
+    if terminated {
+        return Ok(TypedValue {
+            value: None,
+            type_reference: ExecutableTypeReference::Never,
+            terminates: true,
+        });
+    }
+    compile_expression(state, function_builder, &mut block_context, value)
+}
+
 fn compile_match_expression(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
@@ -3297,7 +4606,7 @@ fn compile_match_expression(
         let arm_block = function_builder.create_block();
         let next_block = function_builder.create_block();
 
-        let condition = emit_match_arm_condition(function_builder, &target, &arm.pattern)?;
+        let condition = emit_match_arm_condition(state, function_builder, &target, &arm.pattern)?;
         let zero = function_builder.ins().iconst(types::I8, 0);
         let condition_is_true = function_builder
             .ins()
@@ -3324,9 +4633,35 @@ fn compile_match_expression(
             &target,
             &arm.pattern,
         )?;
+        // A guard that does not hold falls through to the next arm, like a
+        // pattern that does not match.
+        let mut body_block = arm_block;
+        if let Some(guard) = &arm.guard {
+            let guard_value = compile_expression(state, function_builder, &mut arm_context, guard)?;
+            if guard_value.terminates {
+                function_builder.seal_block(arm_block);
+                function_builder.switch_to_block(next_block);
+                continue;
+            }
+            let guard_runtime_value = guard_value.value.ok_or_else(|| {
+                build_failed("match guard produced no runtime value".to_string(), None)
+            })?;
+            let guard_is_true =
+                function_builder
+                    .ins()
+                    .icmp_imm(IntCC::NotEqual, guard_runtime_value, 0);
+            body_block = function_builder.create_block();
+            function_builder
+                .ins()
+                .brif(guard_is_true, body_block, &[], next_block, &[]);
+            function_builder.seal_block(arm_block);
+            function_builder.switch_to_block(body_block);
+        }
         let arm_value = compile_expression(state, function_builder, &mut arm_context, &arm.value)?;
         if arm_value.terminates {
-            return Ok(arm_value);
+            function_builder.seal_block(body_block);
+            function_builder.switch_to_block(next_block);
+            continue;
         }
         let Some(arm_runtime_value) = arm_value.value else {
             return Err(build_failed(
@@ -3351,7 +4686,7 @@ fn compile_match_expression(
             merged_result_variable.expect("merged result variable must exist");
         function_builder.def_var(merged_result_variable, arm_runtime_value);
         function_builder.ins().jump(merge_block, &[]);
-        function_builder.seal_block(arm_block);
+        function_builder.seal_block(body_block);
 
         function_builder.switch_to_block(next_block);
     }
@@ -3359,12 +4694,13 @@ fn compile_match_expression(
     function_builder.ins().trap(TrapCode::user(2).unwrap());
     function_builder.seal_block(pending_block);
 
-    let merged_type_reference = merged_type_reference.ok_or_else(|| {
-        build_failed(
-            "match expression did not produce a merged type".to_string(),
-            None,
-        )
-    })?;
+    let Some(merged_type_reference) = merged_type_reference else {
+        return Ok(TypedValue {
+            value: None,
+            type_reference: ExecutableTypeReference::Never,
+            terminates: true,
+        });
+    };
     function_builder.switch_to_block(merge_block);
     function_builder.seal_block(merge_block);
     let merged_result_variable = merged_result_variable.ok_or_else(|| {
@@ -3383,6 +4719,7 @@ fn compile_match_expression(
 }
 
 fn emit_match_arm_condition(
+    state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
     target: &TypedValue,
     pattern: &ExecutableMatchPattern,
@@ -3390,6 +4727,9 @@ fn emit_match_arm_condition(
     let pattern_type_reference = match pattern {
         ExecutableMatchPattern::Type { type_reference }
         | ExecutableMatchPattern::Binding { type_reference, .. } => type_reference,
+        ExecutableMatchPattern::Literal { value } => {
+            return emit_match_literal_condition(state, function_builder, target, value);
+        }
     };
     if matches!(target.type_reference, ExecutableTypeReference::Union { .. }) {
         let union_box_pointer = target.value.ok_or_else(|| {
@@ -3403,6 +4743,95 @@ fn emit_match_arm_condition(
     }
 }
 
+fn emit_match_literal_condition(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    target: &TypedValue,
+    literal: &ExecutableMatchLiteral,
+) -> Result<Value, CompilerFailure> {
+    let literal_type_reference = literal.type_reference();
+    let literal_value = match literal {
+        ExecutableMatchLiteral::Integer(value) => function_builder.ins().iconst(types::I64, *value),
+        ExecutableMatchLiteral::Boolean(value) => {
+            function_builder.ins().iconst(types::I8, i64::from(*value))
+        }
+        ExecutableMatchLiteral::String(value) => {
+            intern_string_literal(state, function_builder, value)?
+        }
+    };
+    let target_value = target
+        .value
+        .ok_or_else(|| build_failed("match target produced no runtime value".to_string(), None))?;
+    let one = function_builder.ins().iconst(types::I8, 1);
+    let zero = function_builder.ins().iconst(types::I8, 0);
+
+    if matches!(target.type_reference, ExecutableTypeReference::Union { .. }) {
+        let tag_matches =
+            emit_union_match_condition(function_builder, target_value, &literal_type_reference)?;
+        let payload =
+            extract_union_payload_for_type(function_builder, target_value, &literal_type_reference)
+                .ok_or_else(|| build_failed("literal match payload missing".to_string(), None))?;
+        // The payload of another member is not a string, so compare the
+        // literal against itself rather than handing it to strcmp.
+        let compared_payload = function_builder
+            .ins()
+            .select(tag_matches, payload, literal_value);
+        let payload_matches = emit_scalar_equality(
+            state,
+            function_builder,
+            compared_payload,
+            literal_value,
+            &literal_type_reference,
+        );
+        let payload_matches_i8 = function_builder.ins().select(payload_matches, one, zero);
+        return Ok(function_builder.ins().band(tag_matches, payload_matches_i8));
+    }
+    if target.type_reference != literal_type_reference {
+        return Ok(zero);
+    }
+    let value_matches = emit_scalar_equality(
+        state,
+        function_builder,
+        target_value,
+        literal_value,
+        &literal_type_reference,
+    );
+    Ok(function_builder.ins().select(value_matches, one, zero))
+}
+
+// Strings compare by contents; every other scalar compares by its bits.
+fn emit_scalar_equality(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    left_value: Value,
+    right_value: Value,
+    type_reference: &ExecutableTypeReference,
+) -> Value {
+    if matches!(type_reference, ExecutableTypeReference::String) {
+        let comparison = emit_string_comparison(state, function_builder, left_value, right_value);
+        return function_builder.ins().icmp_imm(IntCC::Equal, comparison, 0);
+    }
+    function_builder
+        .ins()
+        .icmp(IntCC::Equal, left_value, right_value)
+}
+
+fn emit_string_comparison(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    left_value: Value,
+    right_value: Value,
+) -> Value {
+    let strcmp = state.module.declare_func_in_func(
+        state.external_runtime_functions.strcmp,
+        function_builder.func,
+    );
+    let comparison_call = function_builder
+        .ins()
+        .call(strcmp, &[left_value, right_value]);
+    function_builder.inst_results(comparison_call)[0]
+}
+
 fn bind_match_pattern_local(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
@@ -3589,7 +5018,7 @@ fn emit_union_match_condition(
     let (enum_name, variant_name) = split_enum_variant_type_name(name)?;
     let expected_enum_payload = function_builder.ins().iconst(
         types::I64,
-        enum_variant_tag(&ExecutableEnumVariantReference {
+        enum_variant_tag(&EnumVariantReference {
             enum_name: enum_name.to_string(),
             variant_name: variant_name.to_string(),
         }),
@@ -3750,6 +5179,47 @@ fn instantiate_generic_signature(
     ))
 }
 
+fn struct_layout_for_reference<'program>(
+    state: &CompilationState<'program>,
+    struct_reference: &StructReference,
+) -> Result<&'program ExecutableStructLayout, CompilerFailure> {
+    state
+        .struct_layout_by_reference
+        .get(struct_reference)
+        .copied()
+        .ok_or_else(|| {
+            build_failed(
+                format!(
+                    "missing layout for struct '{}::{}'",
+                    struct_reference.package_path, struct_reference.symbol_name
+                ),
+                None,
+            )
+        })
+}
+
+fn struct_field_offset_bytes(
+    struct_layout: &ExecutableStructLayout,
+    field_name: &str,
+) -> Result<i32, CompilerFailure> {
+    let field_layout = struct_layout
+        .fields
+        .iter()
+        .find(|field_layout| field_layout.name == field_name)
+        .ok_or_else(|| {
+            build_failed(
+                format!("missing layout for struct field '{field_name}'"),
+                None,
+            )
+        })?;
+    i32::try_from(field_layout.offset_bytes).map_err(|_| {
+        build_failed(
+            "struct field offset exceeds supported range".to_string(),
+            None,
+        )
+    })
+}
+
 fn resolve_struct_type_details<'program>(
     state: &CompilationState<'program>,
     type_reference: &ExecutableTypeReference,
@@ -3860,7 +5330,7 @@ fn resolve_interface_declaration_by_type_reference<'program>(
     };
     state
         .interface_declaration_by_reference
-        .get(&ExecutableInterfaceReference {
+        .get(&InterfaceReference {
             package_path: interface_reference.package_path.clone(),
             symbol_name: interface_reference.symbol_name.clone(),
         })
@@ -3878,7 +5348,7 @@ fn resolve_interface_declaration_by_type_reference<'program>(
 
 fn resolve_interface_declaration_by_reference<'program>(
     state: &CompilationState<'program>,
-    interface_reference: &ExecutableInterfaceReference,
+    interface_reference: &InterfaceReference,
 ) -> Result<&'program ExecutableInterfaceDeclaration, CompilerFailure> {
     state
         .interface_declaration_by_reference
@@ -3998,7 +5468,7 @@ fn build_witness_table_for_constraint(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
     type_argument: &ExecutableTypeReference,
-    interface_reference: &ExecutableInterfaceReference,
+    interface_reference: &InterfaceReference,
 ) -> Result<Value, CompilerFailure> {
     let interface_declaration =
         resolve_interface_declaration_by_reference(state, interface_reference)?;
@@ -4193,7 +5663,19 @@ fn runtime_value_from_i64_storage(
     }
 }
 
-fn enum_variant_tag(enum_variant_reference: &ExecutableEnumVariantReference) -> i64 {
+fn enum_variant_type_reference(
+    enum_variant_reference: &EnumVariantReference,
+) -> ExecutableTypeReference {
+    ExecutableTypeReference::NominalType {
+        nominal_type_reference: None,
+        name: format!(
+            "{}.{}",
+            enum_variant_reference.enum_name, enum_variant_reference.variant_name
+        ),
+    }
+}
+
+fn enum_variant_tag(enum_variant_reference: &EnumVariantReference) -> i64 {
     // Stable deterministic tag from enum+variant identity.
     let identity = format!(
         "{}::{}",
@@ -4248,6 +5730,86 @@ fn emit_exit_call(
     );
 }
 
+// Aborts with the message the interpreter reports for the same index.
+fn emit_list_index_out_of_bounds_abort(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    index_value: Value,
+    list_length: Value,
+) -> Result<(), CompilerFailure> {
+    let prefix_pointer = intern_string_literal(state, function_builder, "list index ")?;
+    let index_pointer = convert_int64_to_string(state, function_builder, index_value)?;
+    let separator_pointer =
+        intern_string_literal(state, function_builder, " out of bounds for length ")?;
+    let length_pointer = convert_int64_to_string(state, function_builder, list_length)?;
+    let message_pointer =
+        concatenate_strings(state, function_builder, prefix_pointer, index_pointer);
+    let message_pointer =
+        concatenate_strings(state, function_builder, message_pointer, separator_pointer);
+    let message_pointer =
+        concatenate_strings(state, function_builder, message_pointer, length_pointer);
+    emit_write_string_with_newline(state, function_builder, 2, message_pointer)?;
+    emit_exit_call(state, function_builder, ABORT_EXIT_CODE);
+    Ok(())
+}
+
+fn emit_environment_integer_or_time(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    environment_variable: &str,
+) -> Result<Value, CompilerFailure> {
+    crate::runtime_interface_emission::emit_environment_integer_or_time(
+        &mut state.module,
+        &state.external_runtime_functions,
+        function_builder,
+        environment_variable,
+    )
+}
+
+fn emit_random_int(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    minimum: Value,
+    maximum: Value,
+) -> Result<Value, CompilerFailure> {
+    crate::runtime_interface_emission::emit_random_int(
+        &mut state.module,
+        &state.external_runtime_functions,
+        function_builder,
+        state.random_state,
+        minimum,
+        maximum,
+    )
+}
+
+fn emit_wrapping_pow(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    base: Value,
+    exponent: Value,
+) -> Result<Value, CompilerFailure> {
+    crate::runtime_interface_emission::emit_wrapping_pow(
+        &mut state.module,
+        &state.external_runtime_functions,
+        function_builder,
+        base,
+        exponent,
+    )
+}
+
+fn emit_capability_check(
+    state: &mut CompilationState<'_>,
+    function_builder: &mut FunctionBuilder<'_>,
+    capability: RuntimeCapability,
+) -> Result<(), CompilerFailure> {
+    crate::runtime_interface_emission::emit_capability_check(
+        &mut state.module,
+        &state.external_runtime_functions,
+        function_builder,
+        capability,
+    )
+}
+
 fn intern_string_literal(
     state: &mut CompilationState<'_>,
     function_builder: &mut FunctionBuilder<'_>,
@@ -4275,7 +5837,7 @@ fn declare_local_variable(
     }
 }
 
-fn lowered_function_symbol_name(callable_reference: &ExecutableCallableReference) -> String {
+fn lowered_function_symbol_name(callable_reference: &CallableReference) -> String {
     if callable_reference.package_path.is_empty() {
         return format!("coppice_{}", callable_reference.symbol_name);
     }
@@ -4283,16 +5845,14 @@ fn lowered_function_symbol_name(callable_reference: &ExecutableCallableReference
         "coppice_{}_{}",
         callable_reference
             .package_path
+            .as_str()
             .replace(['/', '\\'], "_")
             .replace("::", "_"),
         callable_reference.symbol_name
     )
 }
 
-fn lowered_method_symbol_name(
-    struct_reference: &ExecutableStructReference,
-    method_name: &str,
-) -> String {
+fn lowered_method_symbol_name(struct_reference: &StructReference, method_name: &str) -> String {
     let package_prefix = if struct_reference.package_path.is_empty() {
         String::new()
     } else {
@@ -4300,6 +5860,7 @@ fn lowered_method_symbol_name(
             "{}_",
             struct_reference
                 .package_path
+                .as_str()
                 .replace(['/', '\\'], "_")
                 .replace("::", "_")
         )
@@ -0,0 +1,83 @@
+use compiler__executable_program::ExecutableProgram;
+
+use crate::build_report::PassTimer;
+use crate::constant_folding::fold_constants;
+use crate::dead_code_elimination::eliminate_dead_code;
+use crate::inlining::inline_small_functions;
+
+const DEFAULT_INLINE_BODY_SIZE_LIMIT: usize = 16;
+const AGGRESSIVE_INLINE_BODY_SIZE_LIMIT: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptimizationLevel {
+    None,
+    Default,
+    Aggressive,
+}
+
+impl OptimizationLevel {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            OptimizationLevel::None => "none",
+            OptimizationLevel::Default => "default",
+            OptimizationLevel::Aggressive => "aggressive",
+        }
+    }
+}
+
+// Generic code uses a uniform value representation, so there is no
+// monomorphization pass to toggle here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptimizationOptions {
+    pub level: OptimizationLevel,
+}
+
+impl OptimizationOptions {
+    fn constant_folding_enabled(self) -> bool {
+        self.level != OptimizationLevel::None
+    }
+
+    fn dead_code_elimination_enabled(self) -> bool {
+        self.level != OptimizationLevel::None
+    }
+
+    fn inline_body_size_limit(self) -> Option<usize> {
+        match self.level {
+            OptimizationLevel::None => None,
+            OptimizationLevel::Default => Some(DEFAULT_INLINE_BODY_SIZE_LIMIT),
+            OptimizationLevel::Aggressive => Some(AGGRESSIVE_INLINE_BODY_SIZE_LIMIT),
+        }
+    }
+}
+
+impl Default for OptimizationOptions {
+    fn default() -> Self {
+        Self {
+            level: OptimizationLevel::Default,
+        }
+    }
+}
+
+pub(crate) fn optimize_program(
+    program: &ExecutableProgram,
+    optimization_options: OptimizationOptions,
+    pass_timer: &mut PassTimer,
+) -> ExecutableProgram {
+    let mut optimized_program = program.clone();
+    if let Some(inline_body_size_limit) = optimization_options.inline_body_size_limit() {
+        optimized_program = pass_timer.time("inlining", || {
+            inline_small_functions(optimized_program, inline_body_size_limit)
+        });
+    }
+    if optimization_options.constant_folding_enabled() {
+        optimized_program =
+            pass_timer.time("constant_folding", || fold_constants(optimized_program));
+    }
+    if optimization_options.dead_code_elimination_enabled() {
+        optimized_program = pass_timer.time("dead_code_elimination", || {
+            eliminate_dead_code(optimized_program)
+        });
+    }
+    optimized_program
+}
@@ -1,7 +1,12 @@
 use compiler__reports::CompilerFailure;
-use cranelift_codegen::ir::{AbiParam, InstBuilder, TrapCode, Value, types};
+use compiler__runtime_interface::{
+    ABORT_EXIT_CODE, ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE, POW_NEGATIVE_EXPONENT_MESSAGE,
+    RANDOM_INT_EMPTY_RANGE_MESSAGE, RuntimeCapability,
+};
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::{AbiParam, BlockArg, InstBuilder, TrapCode, Value, types};
 use cranelift_frontend::FunctionBuilder;
-use cranelift_module::{FuncId, Module};
+use cranelift_module::{DataDescription, DataId, FuncId, Module};
 use cranelift_object::ObjectModule;
 
 use crate::build_failed;
@@ -10,9 +15,13 @@ use crate::build_failed;
 pub(crate) struct ExternalRuntimeFunctions {
     pub write: FuncId,
     pub strlen: FuncId,
+    pub strcmp: FuncId,
     pub exit: FuncId,
     pub malloc: FuncId,
     pub memcpy: FuncId,
+    pub getenv: FuncId,
+    pub atoll: FuncId,
+    pub time: FuncId,
 }
 
 pub(crate) fn declare_runtime_interface_functions(
@@ -38,6 +47,18 @@ pub(crate) fn declare_runtime_interface_functions(
         )
         .map_err(|error| build_failed(format!("failed to declare 'strlen': {error}"), None))?;
 
+    let mut strcmp_signature = module.make_signature();
+    strcmp_signature.params.push(AbiParam::new(types::I64));
+    strcmp_signature.params.push(AbiParam::new(types::I64));
+    strcmp_signature.returns.push(AbiParam::new(types::I32));
+    let strcmp = module
+        .declare_function(
+            "strcmp",
+            cranelift_module::Linkage::Import,
+            &strcmp_signature,
+        )
+        .map_err(|error| build_failed(format!("failed to declare 'strcmp': {error}"), None))?;
+
     let mut exit_signature = module.make_signature();
     exit_signature.params.push(AbiParam::new(types::I32));
     let exit = module
@@ -68,12 +89,41 @@ pub(crate) fn declare_runtime_interface_functions(
         )
         .map_err(|error| build_failed(format!("failed to declare 'memcpy': {error}"), None))?;
 
+    let mut getenv_signature = module.make_signature();
+    getenv_signature.params.push(AbiParam::new(types::I64));
+    getenv_signature.returns.push(AbiParam::new(types::I64));
+    let getenv = module
+        .declare_function(
+            "getenv",
+            cranelift_module::Linkage::Import,
+            &getenv_signature,
+        )
+        .map_err(|error| build_failed(format!("failed to declare 'getenv': {error}"), None))?;
+
+    let mut atoll_signature = module.make_signature();
+    atoll_signature.params.push(AbiParam::new(types::I64));
+    atoll_signature.returns.push(AbiParam::new(types::I64));
+    let atoll = module
+        .declare_function("atoll", cranelift_module::Linkage::Import, &atoll_signature)
+        .map_err(|error| build_failed(format!("failed to declare 'atoll': {error}"), None))?;
+
+    let mut time_signature = module.make_signature();
+    time_signature.params.push(AbiParam::new(types::I64));
+    time_signature.returns.push(AbiParam::new(types::I64));
+    let time = module
+        .declare_function("time", cranelift_module::Linkage::Import, &time_signature)
+        .map_err(|error| build_failed(format!("failed to declare 'time': {error}"), None))?;
+
     Ok(ExternalRuntimeFunctions {
         write,
         strlen,
+        strcmp,
         exit,
         malloc,
         memcpy,
+        getenv,
+        atoll,
+        time,
     })
 }
 
@@ -141,6 +191,408 @@ pub(crate) fn emit_exit_call(
     function_builder.ins().trap(TrapCode::user(1).unwrap());
 }
 
+// Aborts with the capability's denial message when the execution policy that
+// `run_program` passes through the environment denies it.
+pub(crate) fn emit_capability_check(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    capability: RuntimeCapability,
+) -> Result<(), CompilerFailure> {
+    emit_abort_when_environment_variable_set(
+        module,
+        external_runtime_functions,
+        function_builder,
+        capability.denial_environment_variable(),
+        &capability.denial_message(),
+    )
+}
+
+// An int64 operation whose result did not fit aborts with `message` when
+// `run_program` asked for aborting on overflow, and otherwise keeps the wrapped
+// result.
+pub(crate) fn emit_overflow_check(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    overflowed: Value,
+    message: &str,
+) -> Result<(), CompilerFailure> {
+    let overflow_block = function_builder.create_block();
+    let continue_block = function_builder.create_block();
+    function_builder
+        .ins()
+        .brif(overflowed, overflow_block, &[], continue_block, &[]);
+
+    function_builder.switch_to_block(overflow_block);
+    function_builder.seal_block(overflow_block);
+    emit_abort_when_environment_variable_set(
+        module,
+        external_runtime_functions,
+        function_builder,
+        ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE,
+        message,
+    )?;
+    function_builder.ins().jump(continue_block, &[]);
+
+    function_builder.switch_to_block(continue_block);
+    function_builder.seal_block(continue_block);
+    Ok(())
+}
+
+// Aborts with `message` when `condition` holds.
+pub(crate) fn emit_abort_when(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    condition: Value,
+    message: &str,
+) -> Result<(), CompilerFailure> {
+    let abort_block = function_builder.create_block();
+    let continue_block = function_builder.create_block();
+    function_builder
+        .ins()
+        .brif(condition, abort_block, &[], continue_block, &[]);
+
+    function_builder.switch_to_block(abort_block);
+    let message_pointer = intern_string_literal(
+        module,
+        external_runtime_functions,
+        function_builder,
+        message,
+    )?;
+    emit_write_string_with_newline(
+        module,
+        external_runtime_functions,
+        function_builder,
+        2,
+        message_pointer,
+    )?;
+    emit_exit_call(
+        module,
+        external_runtime_functions,
+        function_builder,
+        ABORT_EXIT_CODE,
+    );
+    function_builder.seal_block(abort_block);
+
+    function_builder.switch_to_block(continue_block);
+    function_builder.seal_block(continue_block);
+    Ok(())
+}
+
+fn emit_abort_when_environment_variable_set(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    variable_name: &str,
+    message: &str,
+) -> Result<(), CompilerFailure> {
+    let variable_name_pointer = intern_string_literal(
+        module,
+        external_runtime_functions,
+        function_builder,
+        variable_name,
+    )?;
+    let getenv =
+        module.declare_func_in_func(external_runtime_functions.getenv, function_builder.func);
+    let getenv_call = function_builder
+        .ins()
+        .call(getenv, &[variable_name_pointer]);
+    let variable_value_pointer = function_builder.inst_results(getenv_call)[0];
+    let is_set = function_builder
+        .ins()
+        .icmp_imm(IntCC::NotEqual, variable_value_pointer, 0);
+    emit_abort_when(
+        module,
+        external_runtime_functions,
+        function_builder,
+        is_set,
+        message,
+    )
+}
+
+// The SplitMix64 state behind `random_int`, seeded by the process entrypoint.
+pub(crate) fn define_random_state(module: &mut ObjectModule) -> Result<DataId, CompilerFailure> {
+    let data_id = module
+        .declare_data(
+            "coppice_random_state",
+            cranelift_module::Linkage::Local,
+            true,
+            false,
+        )
+        .map_err(|error| build_failed(format!("failed to declare random state: {error}"), None))?;
+    let mut data_description = DataDescription::new();
+    data_description.define_zeroinit(8);
+    module
+        .define_data(data_id, &data_description)
+        .map_err(|error| build_failed(format!("failed to define random state: {error}"), None))?;
+    Ok(data_id)
+}
+
+// Reads an integer from `environment_variable`, falling back to the current
+// Unix time in seconds when it is unset.
+pub(crate) fn emit_environment_integer_or_time(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    environment_variable: &str,
+) -> Result<Value, CompilerFailure> {
+    let variable_name_pointer = intern_string_literal(
+        module,
+        external_runtime_functions,
+        function_builder,
+        environment_variable,
+    )?;
+    let getenv =
+        module.declare_func_in_func(external_runtime_functions.getenv, function_builder.func);
+    let getenv_call = function_builder
+        .ins()
+        .call(getenv, &[variable_name_pointer]);
+    let variable_value_pointer = function_builder.inst_results(getenv_call)[0];
+    let is_set = function_builder
+        .ins()
+        .icmp_imm(IntCC::NotEqual, variable_value_pointer, 0);
+
+    let set_block = function_builder.create_block();
+    let unset_block = function_builder.create_block();
+    let merge_block = function_builder.create_block();
+    function_builder.append_block_param(merge_block, types::I64);
+    function_builder
+        .ins()
+        .brif(is_set, set_block, &[], unset_block, &[]);
+
+    function_builder.switch_to_block(set_block);
+    let atoll =
+        module.declare_func_in_func(external_runtime_functions.atoll, function_builder.func);
+    let atoll_call = function_builder
+        .ins()
+        .call(atoll, &[variable_value_pointer]);
+    let parsed_value = function_builder.inst_results(atoll_call)[0];
+    function_builder
+        .ins()
+        .jump(merge_block, &[BlockArg::Value(parsed_value)]);
+    function_builder.seal_block(set_block);
+
+    function_builder.switch_to_block(unset_block);
+    let time = module.declare_func_in_func(external_runtime_functions.time, function_builder.func);
+    let null_pointer = function_builder.ins().iconst(types::I64, 0);
+    let time_call = function_builder.ins().call(time, &[null_pointer]);
+    let current_time = function_builder.inst_results(time_call)[0];
+    function_builder
+        .ins()
+        .jump(merge_block, &[BlockArg::Value(current_time)]);
+    function_builder.seal_block(unset_block);
+
+    function_builder.switch_to_block(merge_block);
+    function_builder.seal_block(merge_block);
+    Ok(function_builder.block_params(merge_block)[0])
+}
+
+pub(crate) fn emit_random_state_seed(
+    module: &mut ObjectModule,
+    function_builder: &mut FunctionBuilder<'_>,
+    random_state: DataId,
+    seed: Value,
+) {
+    let random_state = module.declare_data_in_func(random_state, function_builder.func);
+    let random_state_pointer = function_builder
+        .ins()
+        .symbol_value(types::I64, random_state);
+    function_builder.ins().store(
+        cranelift_codegen::ir::MemFlags::new(),
+        seed,
+        random_state_pointer,
+        0,
+    );
+}
+
+// Mirrors `next_random_value` and `random_int_in_range` from the runtime
+// interface, aborting when the range is empty.
+pub(crate) fn emit_random_int(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    random_state: DataId,
+    minimum: Value,
+    maximum: Value,
+) -> Result<Value, CompilerFailure> {
+    let range_is_empty = function_builder
+        .ins()
+        .icmp(IntCC::SignedLessThan, maximum, minimum);
+    let empty_range_block = function_builder.create_block();
+    let draw_block = function_builder.create_block();
+    function_builder
+        .ins()
+        .brif(range_is_empty, empty_range_block, &[], draw_block, &[]);
+
+    function_builder.switch_to_block(empty_range_block);
+    let message_pointer = intern_string_literal(
+        module,
+        external_runtime_functions,
+        function_builder,
+        RANDOM_INT_EMPTY_RANGE_MESSAGE,
+    )?;
+    emit_write_string_with_newline(
+        module,
+        external_runtime_functions,
+        function_builder,
+        2,
+        message_pointer,
+    )?;
+    emit_exit_call(
+        module,
+        external_runtime_functions,
+        function_builder,
+        ABORT_EXIT_CODE,
+    );
+    function_builder.seal_block(empty_range_block);
+
+    function_builder.switch_to_block(draw_block);
+    function_builder.seal_block(draw_block);
+    let random_state = module.declare_data_in_func(random_state, function_builder.func);
+    let random_state_pointer = function_builder
+        .ins()
+        .symbol_value(types::I64, random_state);
+    let state = function_builder.ins().load(
+        types::I64,
+        cranelift_codegen::ir::MemFlags::new(),
+        random_state_pointer,
+        0,
+    );
+    let state = function_builder
+        .ins()
+        .iadd_imm(state, 0x9E37_79B9_7F4A_7C15_u64.cast_signed());
+    function_builder.ins().store(
+        cranelift_codegen::ir::MemFlags::new(),
+        state,
+        random_state_pointer,
+        0,
+    );
+    let mut value = state;
+    for (shift, multiplier) in [(30, 0xBF58_476D_1CE4_E5B9_u64), (27, 0x94D0_49BB_1331_11EB)] {
+        let shifted = function_builder.ins().ushr_imm(value, shift);
+        let mixed = function_builder.ins().bxor(value, shifted);
+        value = function_builder
+            .ins()
+            .imul_imm(mixed, multiplier.cast_signed());
+    }
+    let shifted = function_builder.ins().ushr_imm(value, 31);
+    let random_value = function_builder.ins().bxor(value, shifted);
+
+    // A range covering every int64 has a size that wraps to zero; it takes
+    // the random value as is instead of dividing by zero.
+    let range_span = function_builder.ins().isub(maximum, minimum);
+    let range_size = function_builder.ins().iadd_imm(range_span, 1);
+    let range_is_full = function_builder.ins().icmp_imm(IntCC::Equal, range_size, 0);
+    let one = function_builder.ins().iconst(types::I64, 1);
+    let divisor = function_builder
+        .ins()
+        .select(range_is_full, one, range_size);
+    let offset = function_builder.ins().urem(random_value, divisor);
+    let ranged_value = function_builder.ins().iadd(minimum, offset);
+    Ok(function_builder
+        .ins()
+        .select(range_is_full, random_value, ranged_value))
+}
+
+// Mirrors `wrapping_pow` from the runtime interface, aborting when the
+// exponent is negative.
+pub(crate) fn emit_wrapping_pow(
+    module: &mut ObjectModule,
+    external_runtime_functions: &ExternalRuntimeFunctions,
+    function_builder: &mut FunctionBuilder<'_>,
+    base: Value,
+    exponent: Value,
+) -> Result<Value, CompilerFailure> {
+    let exponent_is_negative = function_builder
+        .ins()
+        .icmp_imm(IntCC::SignedLessThan, exponent, 0);
+    let negative_exponent_block = function_builder.create_block();
+    let loop_header_block = function_builder.create_block();
+    let loop_body_block = function_builder.create_block();
+    let exit_block = function_builder.create_block();
+    for _ in 0..3 {
+        function_builder.append_block_param(loop_header_block, types::I64);
+    }
+    function_builder.append_block_param(exit_block, types::I64);
+    let one = function_builder.ins().iconst(types::I64, 1);
+    function_builder.ins().brif(
+        exponent_is_negative,
+        negative_exponent_block,
+        &[],
+        loop_header_block,
+        &[
+            BlockArg::Value(one),
+            BlockArg::Value(base),
+            BlockArg::Value(exponent),
+        ],
+    );
+
+    function_builder.switch_to_block(negative_exponent_block);
+    let message_pointer = intern_string_literal(
+        module,
+        external_runtime_functions,
+        function_builder,
+        POW_NEGATIVE_EXPONENT_MESSAGE,
+    )?;
+    emit_write_string_with_newline(
+        module,
+        external_runtime_functions,
+        function_builder,
+        2,
+        message_pointer,
+    )?;
+    emit_exit_call(
+        module,
+        external_runtime_functions,
+        function_builder,
+        ABORT_EXIT_CODE,
+    );
+    function_builder.seal_block(negative_exponent_block);
+
+    function_builder.switch_to_block(loop_header_block);
+    let header_parameters = function_builder.block_params(loop_header_block).to_vec();
+    let (result, squared_base, remaining_exponent) = (
+        header_parameters[0],
+        header_parameters[1],
+        header_parameters[2],
+    );
+    let exponent_is_done = function_builder
+        .ins()
+        .icmp_imm(IntCC::Equal, remaining_exponent, 0);
+    function_builder.ins().brif(
+        exponent_is_done,
+        exit_block,
+        &[BlockArg::Value(result)],
+        loop_body_block,
+        &[],
+    );
+
+    function_builder.switch_to_block(loop_body_block);
+    function_builder.seal_block(loop_body_block);
+    let low_bit = function_builder.ins().band_imm(remaining_exponent, 1);
+    let multiplied_result = function_builder.ins().imul(result, squared_base);
+    let next_result = function_builder
+        .ins()
+        .select(low_bit, multiplied_result, result);
+    let next_base = function_builder.ins().imul(squared_base, squared_base);
+    let next_exponent = function_builder.ins().ushr_imm(remaining_exponent, 1);
+    function_builder.ins().jump(
+        loop_header_block,
+        &[
+            BlockArg::Value(next_result),
+            BlockArg::Value(next_base),
+            BlockArg::Value(next_exponent),
+        ],
+    );
+    function_builder.seal_block(loop_header_block);
+
+    function_builder.switch_to_block(exit_block);
+    function_builder.seal_block(exit_block);
+    Ok(function_builder.block_params(exit_block)[0])
+}
+
 pub(crate) fn intern_string_literal(
     module: &mut ObjectModule,
     external_runtime_functions: &ExternalRuntimeFunctions,
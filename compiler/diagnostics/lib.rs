@@ -4,17 +4,99 @@ use compiler__source::Span;
 
 #[derive(Clone, Debug)]
 pub struct PhaseDiagnostic {
+    pub severity: DiagnosticSeverity,
     pub message: String,
     pub span: Span,
+    pub related_spans: Vec<RelatedSpan>,
+    pub suggested_fixes: Vec<SuggestedFix>,
+}
+
+// Warnings are reported like errors but never fail a build or keep later
+// phases from running.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    #[default]
+    Error,
+    Warning,
+}
+
+// `path` is the workspace-relative path of the file the span is in, when that
+// is not the file of the diagnostic itself.
+#[derive(Clone, Debug)]
+pub struct RelatedSpan {
+    pub message: String,
+    pub span: Span,
+    pub path: Option<PathBuf>,
+}
+
+// Unlike safe autofixes, suggested fixes may change program meaning, so they
+// are only offered to the user and never applied automatically.
+#[derive(Clone, Debug)]
+pub struct SuggestedFix {
+    pub label: String,
+    pub edits: Vec<SuggestedEdit>,
+}
+
+// An empty span (start == end) inserts `replacement_text` at that offset.
+#[derive(Clone, Debug)]
+pub struct SuggestedEdit {
+    pub span: Span,
+    pub replacement_text: String,
 }
 
 impl PhaseDiagnostic {
     pub fn new(message: impl Into<String>, span: Span) -> Self {
         Self {
+            severity: DiagnosticSeverity::Error,
             message: message.into(),
             span,
+            related_spans: Vec::new(),
+            suggested_fixes: Vec::new(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            ..Self::new(message, span)
         }
     }
+
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+
+    #[must_use]
+    pub fn with_related_span(mut self, message: impl Into<String>, span: Span) -> Self {
+        self.related_spans.push(RelatedSpan {
+            message: message.into(),
+            span,
+            path: None,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_related_span_in_file(
+        mut self,
+        message: impl Into<String>,
+        path: PathBuf,
+        span: Span,
+    ) -> Self {
+        self.related_spans.push(RelatedSpan {
+            message: message.into(),
+            span,
+            path: Some(path),
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_suggested_fix(mut self, suggested_fix: SuggestedFix) -> Self {
+        self.suggested_fixes.push(suggested_fix);
+        self
+    }
 }
 
 pub struct FileScopedDiagnostic {
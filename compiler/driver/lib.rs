@@ -2,23 +2,48 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 use compiler__analysis_pipeline::{
-    analyze_target_with_workspace_root, analyze_target_with_workspace_root_and_overrides,
+    AnalysisOptions, AnalyzedTarget, QueryDatabase, analyze_target_with_workspace_root,
+    analyze_target_with_workspace_root_and_overrides, limit_rendered_diagnostics,
 };
 use compiler__autofix_policy::{
     AutofixPolicyMode, AutofixPolicyOutcome, evaluate_autofix_policy,
     summarize_pending_safe_autofixes,
 };
-use compiler__cranelift_backend::{BuildArtifactIdentity, build_program, run_program};
+use compiler__cranelift_backend::{
+    BuildArtifactIdentity, OptimizationOptions, RunOutcome, build_program, run_program,
+};
+use compiler__diagnostics::PhaseDiagnostic;
 use compiler__executable_lowering::lower_resolved_declarations_build_unit;
+use compiler__executable_program::ExecutableProgram;
+use compiler__file_system::RealFileSystem;
 use compiler__phase_results::PhaseStatus;
 use compiler__reports::{
     CompilerFailure, CompilerFailureDetail, CompilerFailureKind, RenderedDiagnostic,
 };
-use compiler__source::{FileRole, path_to_key};
+use compiler__runtime_interface::ExecutionContext;
+use compiler__semantic_types::HostFunctionContract;
+use compiler__source::{FileRole, LineIndex, path_to_key};
+use compiler__type_annotated_program::{TypeResolvedDeclarations, why_not_conforming};
 use compiler__visibility::ResolvedImport;
 
+mod test_runner;
+
+pub use compiler__cranelift_backend::{
+    BuildReport, FunctionBuildStatistics, GenericInstantiationCount, PassTiming,
+    ReproducibilityCheck,
+};
+pub use compiler__type_annotated_program::{ConformanceReport, MethodConformanceProblem};
+pub use test_runner::{
+    DiscoverTestsResult, DiscoveredTestFile, DiscoveredTestFunction, TestFileOutcome,
+    TestFunctionKind, TestFunctionOutcome, TestTargetResult, discover_tests_with_workspace_root,
+    test_target_with_workspace_root,
+};
+
+// Builds of binary entrypoints also report what the backend compiled and how
+// long each pass took, for tracking compile cost over time.
 pub struct BuildTargetResult {
     pub executable_path: Option<String>,
+    pub build_report: Option<BuildReport>,
     pub success_message: Option<String>,
     pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
     pub analysis_result: Option<BuildAnalysisResult>,
@@ -30,32 +55,245 @@ pub struct BuildAnalysisResult {
     pub source_by_path: BTreeMap<String, String>,
 }
 
+pub struct LoadTargetResult {
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub program: Result<ExecutableProgram, CompilerFailure>,
+}
+
+struct LowerTargetResult {
+    safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    lowered: Result<LoweredTarget, CompilerFailure>,
+}
+
+enum LoweredTarget {
+    // `warnings` holds the warnings of the packages the entrypoint reaches, if
+    // there are any.
+    BinaryEntrypoint {
+        program: Box<ExecutableProgram>,
+        artifact_identity: BuildArtifactIdentity,
+        workspace_root: PathBuf,
+        build_output_directory: PathBuf,
+        warnings: Option<BuildAnalysisResult>,
+    },
+    AnalysisOnly(BuildAnalysisResult),
+    // Errors in the analyzed files keep a binary entrypoint from being lowered.
+    RejectedByDiagnostics(BuildAnalysisResult),
+}
+
 #[must_use]
 pub fn build_target_with_workspace_root(
     path: &str,
     workspace_root_override: Option<&str>,
     output_directory_override: Option<&str>,
     strict: bool,
+    reproducibility_check: ReproducibilityCheck,
 ) -> BuildTargetResult {
-    let mut analyzed_target =
-        match analyze_target_with_workspace_root(path, workspace_root_override) {
-            Ok(value) => value,
+    let LowerTargetResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        lowered,
+    } = lower_target_with_workspace_root(
+        path,
+        workspace_root_override,
+        strict,
+        &AnalysisOptions::default(),
+    );
+    let (program, artifact_identity, workspace_root, build_output_directory, warnings) =
+        match lowered {
+            Ok(LoweredTarget::BinaryEntrypoint {
+                program,
+                artifact_identity,
+                workspace_root,
+                build_output_directory,
+                warnings,
+            }) => (
+                program,
+                artifact_identity,
+                workspace_root,
+                build_output_directory,
+                warnings,
+            ),
+            Ok(LoweredTarget::AnalysisOnly(analysis_result)) => {
+                return BuildTargetResult {
+                executable_path: None,
+                build_report: None,
+                success_message: Some(
+                    "analysis succeeded; package/library/test artifact generation is not implemented yet"
+                        .to_string(),
+                ),
+                safe_autofix_edit_count_by_workspace_relative_path,
+                analysis_result: Some(analysis_result),
+                build: Ok(()),
+            };
+            }
+            Ok(LoweredTarget::RejectedByDiagnostics(analysis_result)) => {
+                return BuildTargetResult {
+                    executable_path: None,
+                    build_report: None,
+                    success_message: None,
+                    safe_autofix_edit_count_by_workspace_relative_path,
+                    build: Err(build_failed_from_rendered_diagnostics(
+                        &analysis_result.diagnostics,
+                    )),
+                    analysis_result: Some(analysis_result),
+                };
+            }
             Err(error) => {
                 return BuildTargetResult {
                     executable_path: None,
+                    build_report: None,
                     success_message: None,
-                    safe_autofix_edit_count_by_workspace_relative_path: BTreeMap::new(),
+                    safe_autofix_edit_count_by_workspace_relative_path,
                     analysis_result: None,
                     build: Err(error),
                 };
             }
         };
 
+    let build_directory = if let Some(output_directory) = output_directory_override {
+        let parsed_output_directory = PathBuf::from(output_directory);
+        if parsed_output_directory.is_absolute() {
+            parsed_output_directory
+        } else {
+            workspace_root.join(parsed_output_directory)
+        }
+    } else {
+        workspace_root.join(build_output_directory)
+    };
+    let built_program = match build_program(
+        &RealFileSystem,
+        &program,
+        &build_directory,
+        &artifact_identity,
+        OptimizationOptions::default(),
+        reproducibility_check,
+        None,
+    ) {
+        Ok(value) => value,
+        Err(error) => {
+            return BuildTargetResult {
+                executable_path: None,
+                build_report: None,
+                success_message: None,
+                safe_autofix_edit_count_by_workspace_relative_path,
+                analysis_result: None,
+                build: Err(error),
+            };
+        }
+    };
+
+    BuildTargetResult {
+        executable_path: Some(display_path(&built_program.binary_path)),
+        build_report: Some(built_program.report),
+        success_message: None,
+        safe_autofix_edit_count_by_workspace_relative_path,
+        analysis_result: warnings,
+        build: Ok(()),
+    }
+}
+
+// Analyzes and lowers a binary entrypoint without emitting an executable, for
+// hosts that execute the program in-process. Failures are reported the same
+// way a build reports them. Calls to the host's functions are checked against
+// `host_function_contracts`.
+#[must_use]
+pub fn load_target_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    strict: bool,
+    host_function_contracts: &[HostFunctionContract],
+) -> LoadTargetResult {
+    let analysis_options = AnalysisOptions {
+        host_function_contracts: host_function_contracts.to_vec(),
+        ..AnalysisOptions::default()
+    };
+    let LowerTargetResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        lowered,
+    } = lower_target_with_workspace_root(path, workspace_root_override, strict, &analysis_options);
+    let program = match lowered {
+        Ok(LoweredTarget::BinaryEntrypoint { program, .. }) => Ok(*program),
+        Ok(LoweredTarget::AnalysisOnly(_)) => Err(CompilerFailure {
+            kind: CompilerFailureKind::BuildFailed,
+            message: "load target must be a .bin.copp file".to_string(),
+            path: None,
+            details: Vec::new(),
+        }),
+        Ok(LoweredTarget::RejectedByDiagnostics(analysis_result)) => Err(
+            build_failed_from_rendered_diagnostics(&analysis_result.diagnostics),
+        ),
+        Err(error) => Err(error),
+    };
+    LoadTargetResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        program,
+    }
+}
+
+// Compares a struct's methods against an interface's among the declarations
+// analysis resolved for the target, so that a target with errors, such as a
+// declared implementation missing a method, is still explained.
+pub fn why_not_conforming_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    struct_name: &str,
+    interface_name: &str,
+) -> Result<ConformanceReport, CompilerFailure> {
+    let analyzed_target = analyze_target_with_workspace_root(path, workspace_root_override)?;
+    why_not_conforming(
+        analyzed_target.declaration_signatures_by_path.values(),
+        struct_name,
+        interface_name,
+    )
+    .map_err(|message| CompilerFailure {
+        kind: CompilerFailureKind::DeclarationNotFound,
+        message,
+        path: None,
+        details: Vec::new(),
+    })
+}
+
+struct AnalyzeTargetForBuildResult {
+    safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    analyzed_target: Result<AnalyzedTarget, CompilerFailure>,
+}
+
+// Analyzes the target the way builds see it: strict mode fails on pending safe
+// autofixes, and otherwise the target is reanalyzed with them applied.
+fn analyze_target_for_build(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    strict: bool,
+    analysis_options: &AnalysisOptions,
+) -> AnalyzeTargetForBuildResult {
+    // Reanalysis with safe autofixes applied reuses the queries of every file
+    // the autofixes leave unchanged.
+    let mut query_database = QueryDatabase::new();
+    let mut analyzed_target = match analyze_target_with_workspace_root_and_overrides(
+        path,
+        workspace_root_override,
+        &BTreeMap::new(),
+        analysis_options,
+        None,
+        None,
+        &mut query_database,
+    ) {
+        Ok(value) => value,
+        Err(error) => {
+            return AnalyzeTargetForBuildResult {
+                safe_autofix_edit_count_by_workspace_relative_path: BTreeMap::new(),
+                analyzed_target: Err(error),
+            };
+        }
+    };
+
     let safe_autofix_edit_count_by_workspace_relative_path = analyzed_target
         .safe_autofix_edit_count_by_workspace_relative_path
         .clone();
-    let autofix_policy_outcome =
-        evaluate_safe_autofix_policy(strict, &safe_autofix_edit_count_by_workspace_relative_path);
+    // The workspace's `lint.strict` setting makes every build strict.
+    let autofix_policy_outcome = evaluate_safe_autofix_policy(
+        strict || analyzed_target.workspace.settings().strict,
+        &safe_autofix_edit_count_by_workspace_relative_path,
+    );
 
     if matches!(
         autofix_policy_outcome,
@@ -64,12 +302,9 @@ pub fn build_target_with_workspace_root(
         let build_failure = build_failed_from_pending_safe_autofixes(
             &safe_autofix_edit_count_by_workspace_relative_path,
         );
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+        return AnalyzeTargetForBuildResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(build_failure),
+            analyzed_target: Err(build_failure),
         };
     }
 
@@ -81,19 +316,45 @@ pub fn build_target_with_workspace_root(
             path,
             workspace_root_override,
             &analyzed_target.canonical_source_override_by_workspace_relative_path,
+            analysis_options,
+            None,
+            None,
+            &mut query_database,
         ) {
             Ok(value) => value,
             Err(error) => {
-                return BuildTargetResult {
-                    executable_path: None,
-                    success_message: None,
+                return AnalyzeTargetForBuildResult {
                     safe_autofix_edit_count_by_workspace_relative_path,
-                    analysis_result: None,
-                    build: Err(error),
+                    analyzed_target: Err(error),
                 };
             }
         };
     }
+    AnalyzeTargetForBuildResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        analyzed_target: Ok(analyzed_target),
+    }
+}
+
+fn lower_target_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    strict: bool,
+    analysis_options: &AnalysisOptions,
+) -> LowerTargetResult {
+    let AnalyzeTargetForBuildResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        analyzed_target,
+    } = analyze_target_for_build(path, workspace_root_override, strict, analysis_options);
+    let analyzed_target = match analyzed_target {
+        Ok(value) => value,
+        Err(error) => {
+            return LowerTargetResult {
+                safe_autofix_edit_count_by_workspace_relative_path,
+                lowered: Err(error),
+            };
+        }
+    };
     let binary_entrypoint = if analyzed_target.target_is_file
         && FileRole::from_path(&analyzed_target.absolute_target_path)
             == Some(FileRole::BinaryEntrypoint)
@@ -103,41 +364,34 @@ pub fn build_target_with_workspace_root(
             &analyzed_target.absolute_target_path,
         )
     } else {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: Some(
-                "analysis succeeded; package/library/test artifact generation is not implemented yet"
-                    .to_string(),
-            ),
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: Some(BuildAnalysisResult {
+            lowered: Ok(LoweredTarget::AnalysisOnly(BuildAnalysisResult {
                 diagnostics: analyzed_target.diagnostics,
                 source_by_path: analyzed_target.source_by_path,
-            }),
-            build: Ok(()),
+            })),
         };
     };
-    if !analyzed_target.diagnostics.is_empty() {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+    if analyzed_target
+        .diagnostics
+        .iter()
+        .any(RenderedDiagnostic::is_error)
+    {
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(build_failed_from_rendered_diagnostics(
-                &analyzed_target.diagnostics,
-            )),
+            lowered: Ok(LoweredTarget::RejectedByDiagnostics(BuildAnalysisResult {
+                diagnostics: analyzed_target.diagnostics,
+                source_by_path: analyzed_target.source_by_path,
+            })),
         };
     }
     let Some(binary_entrypoint_resolved_declarations) = analyzed_target
         .resolved_declarations_by_path
         .get(&binary_entrypoint)
     else {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(CompilerFailure {
+            lowered: Err(CompilerFailure {
                 kind: CompilerFailureKind::BuildFailed,
                 message: "missing resolved declarations for binary entrypoint".to_string(),
                 path: Some(path_to_key(&binary_entrypoint)),
@@ -148,12 +402,9 @@ pub fn build_target_with_workspace_root(
     let Some(binary_entrypoint_package_path) =
         analyzed_target.package_path_by_file.get(&binary_entrypoint)
     else {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(CompilerFailure {
+            lowered: Err(CompilerFailure {
                 kind: CompilerFailureKind::BuildFailed,
                 message: "missing package ownership for binary entrypoint".to_string(),
                 path: Some(path_to_key(&binary_entrypoint)),
@@ -165,128 +416,102 @@ pub fn build_target_with_workspace_root(
         binary_entrypoint_package_path,
         &analyzed_target.resolved_imports,
     );
-    let mut reachable_diagnostics = Vec::new();
-    for (file_path, file_diagnostics) in &analyzed_target.all_diagnostics_by_file {
-        let Some(package_path) = analyzed_target.package_path_by_file.get(file_path) else {
-            continue;
-        };
-        if !reachable_package_paths.contains(package_path) {
-            continue;
-        }
-        reachable_diagnostics.extend(file_diagnostics.iter().cloned());
-    }
-    sort_rendered_diagnostics(&mut reachable_diagnostics);
-    if !reachable_diagnostics.is_empty() {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+    let reachable_diagnostics = reachable_diagnostics(&analyzed_target, &reachable_package_paths);
+    if reachable_diagnostics
+        .iter()
+        .any(RenderedDiagnostic::is_error)
+    {
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(build_failed_from_rendered_diagnostics(
-                &reachable_diagnostics,
-            )),
+            lowered: Ok(LoweredTarget::RejectedByDiagnostics(BuildAnalysisResult {
+                diagnostics: limit_rendered_diagnostics(&reachable_diagnostics, analysis_options),
+                source_by_path: analyzed_target.source_by_path,
+            })),
         };
     }
-    let dependency_library_resolved_declarations = analyzed_target
-        .resolved_declarations_by_path
-        .iter()
-        .filter_map(|(file_path, resolved_declarations)| {
-            if file_path == &binary_entrypoint {
-                return None;
-            }
-            if analyzed_target.file_role_by_path.get(file_path) != Some(&FileRole::Library) {
-                return None;
-            }
-            let file_package_path = analyzed_target.package_path_by_file.get(file_path)?;
-            if !reachable_package_paths.contains(file_package_path) {
-                return None;
-            }
-            Some(resolved_declarations)
-        })
-        .collect::<Vec<_>>();
-    let executable_lowering_result = lower_resolved_declarations_build_unit(
-        binary_entrypoint_resolved_declarations,
-        &dependency_library_resolved_declarations,
-    );
-    if !matches!(executable_lowering_result.status, PhaseStatus::Ok) {
-        return BuildTargetResult {
-            executable_path: None,
-            success_message: None,
+    let package_metadata = analyzed_target
+        .workspace
+        .package_by_path(binary_entrypoint_package_path)
+        .map(|package| {
+            let package_relative_entrypoint = binary_entrypoint
+                .strip_prefix(&package.root_directory)
+                .map_or_else(|_| path_to_key(&binary_entrypoint), path_to_key);
+            (package_relative_entrypoint, &package.metadata)
+        });
+    if let Some((package_relative_entrypoint, package_metadata)) = &package_metadata
+        && let Some(entrypoints) = &package_metadata.entrypoints
+        && !entrypoints.contains(package_relative_entrypoint)
+    {
+        return LowerTargetResult {
             safe_autofix_edit_count_by_workspace_relative_path,
-            analysis_result: None,
-            build: Err(CompilerFailure {
+            lowered: Err(CompilerFailure {
                 kind: CompilerFailureKind::BuildFailed,
-                message: "build mode does not support this program yet".to_string(),
+                message: format!(
+                    "binary entrypoint '{package_relative_entrypoint}' is not listed in the entrypoints of its package"
+                ),
                 path: Some(path_to_key(&binary_entrypoint)),
-                details: executable_lowering_result
-                    .diagnostics
-                    .into_iter()
-                    .map(|diagnostic| CompilerFailureDetail {
-                        message: format!(
-                            "{} (line {}, column {})",
-                            diagnostic.message, diagnostic.span.line, diagnostic.span.column
-                        ),
-                        path: Some(path_to_key(&binary_entrypoint)),
-                    })
-                    .collect(),
+                details: Vec::new(),
             }),
         };
     }
-
-    let build_directory = if let Some(output_directory) = output_directory_override {
-        let parsed_output_directory = PathBuf::from(output_directory);
-        if parsed_output_directory.is_absolute() {
-            parsed_output_directory
-        } else {
-            analyzed_target.workspace_root.join(parsed_output_directory)
-        }
-    } else {
-        analyzed_target
-            .workspace_root
-            .join(".coppice")
-            .join("build")
-    };
-    let executable_stem = match executable_stem_for_binary_entrypoint(&binary_entrypoint) {
-        Ok(value) => value,
-        Err(error) => {
-            return BuildTargetResult {
-                executable_path: None,
-                success_message: None,
-                safe_autofix_edit_count_by_workspace_relative_path,
-                analysis_result: None,
-                build: Err(error),
-            };
-        }
-    };
-    let built_program = match build_program(
-        &executable_lowering_result.value,
-        &build_directory,
-        &BuildArtifactIdentity { executable_stem },
+    let executable_stem = match executable_stem_for_binary_entrypoint(
+        &binary_entrypoint,
+        package_metadata.and_then(|(_, package_metadata)| package_metadata.name.as_deref()),
     ) {
         Ok(value) => value,
         Err(error) => {
-            return BuildTargetResult {
-                executable_path: None,
-                success_message: None,
+            return LowerTargetResult {
                 safe_autofix_edit_count_by_workspace_relative_path,
-                analysis_result: None,
-                build: Err(error),
+                lowered: Err(error),
             };
         }
     };
+    let dependency_library_resolved_declarations =
+        dependency_library_resolved_declarations(&analyzed_target, &reachable_package_paths);
+    let executable_lowering_result = lower_resolved_declarations_build_unit(
+        binary_entrypoint_resolved_declarations,
+        &dependency_library_resolved_declarations,
+    );
+    if !matches!(executable_lowering_result.status, PhaseStatus::Ok) {
+        let entrypoint_source = analyzed_target
+            .source_by_path
+            .get(&display_path(&analyzed_target.absolute_target_path))
+            .map_or("", String::as_str);
+        return LowerTargetResult {
+            safe_autofix_edit_count_by_workspace_relative_path,
+            lowered: Err(unsupported_program_failure(
+                "build mode does not support this program yet",
+                &executable_lowering_result.diagnostics,
+                &binary_entrypoint,
+                entrypoint_source,
+            )),
+        };
+    }
 
-    BuildTargetResult {
-        executable_path: Some(display_path(&built_program.binary_path)),
-        success_message: None,
+    LowerTargetResult {
         safe_autofix_edit_count_by_workspace_relative_path,
-        analysis_result: None,
-        build: Ok(()),
+        lowered: Ok(LoweredTarget::BinaryEntrypoint {
+            program: Box::new(executable_lowering_result.value),
+            artifact_identity: BuildArtifactIdentity { executable_stem },
+            build_output_directory: analyzed_target
+                .workspace
+                .settings()
+                .build_output_directory
+                .clone(),
+            workspace_root: analyzed_target.workspace_root,
+            warnings: (!reachable_diagnostics.is_empty()).then(|| BuildAnalysisResult {
+                diagnostics: limit_rendered_diagnostics(&reachable_diagnostics, analysis_options),
+                source_by_path: analyzed_target.source_by_path,
+            }),
+        }),
     }
 }
 
+// `analysis_result` holds the diagnostics that failed the build, if any.
 pub struct RunTargetResult {
     pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
-    pub run: Result<i32, CompilerFailure>,
+    pub analysis_result: Option<BuildAnalysisResult>,
+    pub run: Result<RunOutcome, CompilerFailure>,
 }
 
 #[must_use]
@@ -295,40 +520,51 @@ pub fn run_target_with_workspace_root(
     workspace_root_override: Option<&str>,
     output_directory_override: Option<&str>,
     strict: bool,
+    execution_context: &ExecutionContext,
 ) -> RunTargetResult {
     let build_result = build_target_with_workspace_root(
         path,
         workspace_root_override,
         output_directory_override,
         strict,
+        ReproducibilityCheck::Disabled,
     );
     let BuildTargetResult {
         executable_path,
+        build_report: _build_report,
         success_message: _success_message,
         safe_autofix_edit_count_by_workspace_relative_path,
-        analysis_result: _analysis_result,
+        analysis_result,
         build,
     } = build_result;
 
-    let run = if let Err(error) = build {
-        Err(error)
-    } else {
-        let Some(executable_path) = executable_path else {
-            return RunTargetResult {
-                safe_autofix_edit_count_by_workspace_relative_path,
-                run: Err(CompilerFailure {
-                    kind: CompilerFailureKind::RunFailed,
-                    message: "build/run target must be a .bin.copp file".to_string(),
-                    path: None,
-                    details: Vec::new(),
-                }),
-            };
+    if let Err(error) = build {
+        return RunTargetResult {
+            safe_autofix_edit_count_by_workspace_relative_path,
+            analysis_result,
+            run: Err(error),
+        };
+    }
+    let Some(executable_path) = executable_path else {
+        return RunTargetResult {
+            safe_autofix_edit_count_by_workspace_relative_path,
+            analysis_result: None,
+            run: Err(CompilerFailure {
+                kind: CompilerFailureKind::RunFailed,
+                message: "build/run target must be a .bin.copp file".to_string(),
+                path: None,
+                details: Vec::new(),
+            }),
         };
-        run_program(Path::new(&executable_path))
     };
     RunTargetResult {
         safe_autofix_edit_count_by_workspace_relative_path,
-        run,
+        analysis_result: None,
+        run: run_program(
+            &RealFileSystem,
+            Path::new(&executable_path),
+            execution_context,
+        ),
     }
 }
 
@@ -379,28 +615,107 @@ fn package_dependency_closure(
     visited_package_paths
 }
 
+fn reachable_diagnostics(
+    analyzed_target: &AnalyzedTarget,
+    reachable_package_paths: &BTreeSet<String>,
+) -> Vec<RenderedDiagnostic> {
+    let mut reachable_diagnostics = Vec::new();
+    for (file_path, file_diagnostics) in &analyzed_target.all_diagnostics_by_file {
+        let Some(package_path) = analyzed_target.package_path_by_file.get(file_path) else {
+            continue;
+        };
+        if !reachable_package_paths.contains(package_path) {
+            continue;
+        }
+        reachable_diagnostics.extend(file_diagnostics.iter().cloned());
+    }
+    sort_rendered_diagnostics(&mut reachable_diagnostics);
+    reachable_diagnostics
+}
+
+// The library files of the reachable packages, which an entrypoint is lowered
+// together with.
+fn dependency_library_resolved_declarations<'a>(
+    analyzed_target: &'a AnalyzedTarget,
+    reachable_package_paths: &BTreeSet<String>,
+) -> Vec<&'a TypeResolvedDeclarations> {
+    analyzed_target
+        .resolved_declarations_by_path
+        .iter()
+        .filter_map(|(file_path, resolved_declarations)| {
+            if analyzed_target.file_role_by_path.get(file_path) != Some(&FileRole::Library) {
+                return None;
+            }
+            let file_package_path = analyzed_target.package_path_by_file.get(file_path)?;
+            if !reachable_package_paths.contains(file_package_path) {
+                return None;
+            }
+            Some(resolved_declarations)
+        })
+        .collect()
+}
+
 fn path_to_relative_workspace_path(workspace_root: &Path, absolute_path: &Path) -> PathBuf {
     absolute_path
         .strip_prefix(workspace_root)
         .map_or_else(|_| absolute_path.to_path_buf(), Path::to_path_buf)
 }
 
+// Related spans follow their diagnostic as notes, so a note counting the
+// diagnostics a per-file cap suppressed is never dropped from the failure.
 fn build_failed_from_rendered_diagnostics(diagnostics: &[RenderedDiagnostic]) -> CompilerFailure {
+    let mut details = Vec::new();
+    for diagnostic in diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+    {
+        details.push(CompilerFailureDetail {
+            message: format!(
+                "{} ({}:{}:{})",
+                diagnostic.message, diagnostic.path, diagnostic.span.line, diagnostic.span.column
+            ),
+            path: Some(diagnostic.path.clone()),
+        });
+        for related_span in &diagnostic.related_spans {
+            let path = related_span.path.as_ref().unwrap_or(&diagnostic.path);
+            details.push(CompilerFailureDetail {
+                message: format!(
+                    "note: {} ({}:{}:{})",
+                    related_span.message, path, related_span.span.line, related_span.span.column
+                ),
+                path: Some(path.clone()),
+            });
+        }
+    }
     CompilerFailure {
         kind: CompilerFailureKind::BuildFailed,
         message: "build failed due to diagnostics".to_string(),
         path: None,
-        details: diagnostics
+        details,
+    }
+}
+
+// Executable lowering reports the constructs it cannot lower yet against the
+// entrypoint file.
+fn unsupported_program_failure(
+    message: &str,
+    executable_lowering_diagnostics: &[PhaseDiagnostic],
+    entrypoint_path: &Path,
+    entrypoint_source: &str,
+) -> CompilerFailure {
+    let line_index = LineIndex::new(entrypoint_source);
+    CompilerFailure {
+        kind: CompilerFailureKind::BuildFailed,
+        message: message.to_string(),
+        path: Some(path_to_key(entrypoint_path)),
+        details: executable_lowering_diagnostics
             .iter()
-            .map(|diagnostic| CompilerFailureDetail {
-                message: format!(
-                    "{} ({}:{}:{})",
-                    diagnostic.message,
-                    diagnostic.path,
-                    diagnostic.span.line,
-                    diagnostic.span.column
-                ),
-                path: Some(diagnostic.path.clone()),
+            .map(|diagnostic| {
+                let (line, column) = line_index.line_column(diagnostic.span.anchor);
+                CompilerFailureDetail {
+                    message: format!("{} (line {line}, column {column})", diagnostic.message),
+                    path: Some(path_to_key(entrypoint_path)),
+                }
             })
             .collect(),
     }
@@ -425,8 +740,11 @@ fn build_failed_from_pending_safe_autofixes(
     }
 }
 
+// A package that declares a name builds its `main.bin.copp` entrypoint into an
+// executable of that name.
 fn executable_stem_for_binary_entrypoint(
     binary_entrypoint: &Path,
+    package_name: Option<&str>,
 ) -> Result<String, CompilerFailure> {
     let file_name = binary_entrypoint
         .file_name()
@@ -454,6 +772,11 @@ fn executable_stem_for_binary_entrypoint(
             details: Vec::new(),
         });
     }
+    if executable_stem == "main"
+        && let Some(package_name) = package_name
+    {
+        return Ok(package_name.to_string());
+    }
     Ok(executable_stem.to_string())
 }
 
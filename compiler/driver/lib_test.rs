@@ -0,0 +1,44 @@
+use compiler__driver::{ReproducibilityCheck, build_target_with_workspace_root};
+use tests__test_workspace::TestWorkspace;
+
+#[test]
+fn binary_build_reports_functions_generic_instantiations_and_pass_timings() {
+    let workspace = TestWorkspace::new(
+        "driver_build_report_test",
+        &[
+            ("PACKAGE.copp", ""),
+            (
+                "main.bin.copp",
+                "function identity[T](value: T) -> T {\n    return value\n}\n\nfunction main() -> nil {\n    print(string(identity[int64](7)))\n    print(identity[string](\"ok\"))\n    print(string(identity[int64](8)))\n    return\n}\n",
+            ),
+        ],
+    );
+    let workspace_root = workspace.path().display().to_string();
+    let output_directory = workspace.file_path("out");
+
+    let build_result = build_target_with_workspace_root(
+        &workspace.file_path("main.bin.copp"),
+        Some(&workspace_root),
+        Some(&output_directory),
+        false,
+        ReproducibilityCheck::Disabled,
+    );
+
+    assert!(build_result.build.is_ok());
+    let build_report = build_result
+        .build_report
+        .expect("binary builds should report what was compiled");
+    assert_eq!(build_report.function_count, 2);
+    assert_eq!(build_report.generic_instantiation_counts.len(), 1);
+    let identity_instantiations = &build_report.generic_instantiation_counts[0];
+    assert_eq!(
+        identity_instantiations
+            .callable_reference
+            .symbol_name
+            .as_str(),
+        "identity"
+    );
+    assert_eq!(identity_instantiations.instantiation_count, 2);
+    assert!(!build_report.pass_timings.is_empty());
+    assert!(build_report.executable_size_bytes > 0);
+}
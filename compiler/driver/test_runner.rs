@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use compiler__analysis_pipeline::{AnalysisOptions, AnalyzedTarget};
+use compiler__executable_lowering::lower_resolved_declarations_test_unit;
+use compiler__interpreter::Interpreter;
+use compiler__phase_results::PhaseStatus;
+use compiler__reports::{CompilerFailure, RenderedDiagnostic};
+use compiler__source::{FileRole, KnownAttribute, LineIndex, path_to_key};
+
+use crate::{
+    AnalyzeTargetForBuildResult, analyze_target_for_build, build_failed_from_rendered_diagnostics,
+    dependency_library_resolved_declarations, display_path, package_dependency_closure,
+    path_to_relative_workspace_path, reachable_diagnostics, unsupported_program_failure,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestFunctionKind {
+    Test,
+    Bench,
+}
+
+// A function marked `@test` or `@bench`. `line` is where its name is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredTestFunction {
+    pub name: String,
+    pub kind: TestFunctionKind,
+    pub line: usize,
+}
+
+// `path` is relative to the workspace root. Functions are in declaration order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredTestFile {
+    pub path: String,
+    pub functions: Vec<DiscoveredTestFunction>,
+}
+
+pub struct DiscoverTestsResult {
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub test_files: Result<Vec<DiscoveredTestFile>, CompilerFailure>,
+}
+
+// `failure` is the message the function stopped with, such as a failed
+// assertion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestFunctionOutcome {
+    pub function: DiscoveredTestFunction,
+    pub elapsed: Duration,
+    pub failure: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestFileOutcome {
+    pub path: String,
+    pub function_outcomes: Vec<TestFunctionOutcome>,
+}
+
+pub struct TestTargetResult {
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+    pub tests: Result<Vec<TestFileOutcome>, CompilerFailure>,
+}
+
+// Lists the `@test` and `@bench` functions of the test files under the target,
+// without running them. Test files that declare none are left out. The target
+// must analyze without errors.
+#[must_use]
+pub fn discover_tests_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    strict: bool,
+) -> DiscoverTestsResult {
+    let AnalyzeTargetForBuildResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        analyzed_target,
+    } = analyze_target_for_build(
+        path,
+        workspace_root_override,
+        strict,
+        &AnalysisOptions::default(),
+    );
+    let test_files = analyzed_target.and_then(|analyzed_target| {
+        discovered_test_files(&analyzed_target).map(|test_files| {
+            test_files
+                .into_iter()
+                .map(|(_, test_file)| test_file)
+                .collect()
+        })
+    });
+    DiscoverTestsResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        test_files,
+    }
+}
+
+// Runs the `@test` functions of the test files under the target, and their
+// `@bench` functions too when `include_benches` is set. Each test file is
+// lowered with the library files of the packages it reaches and interpreted in
+// process; a function fails when it stops with a runtime error, such as a
+// failed assertion, and the remaining functions still run.
+#[must_use]
+pub fn test_target_with_workspace_root(
+    path: &str,
+    workspace_root_override: Option<&str>,
+    strict: bool,
+    include_benches: bool,
+) -> TestTargetResult {
+    let AnalyzeTargetForBuildResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        analyzed_target,
+    } = analyze_target_for_build(
+        path,
+        workspace_root_override,
+        strict,
+        &AnalysisOptions::default(),
+    );
+    let tests = analyzed_target.and_then(|analyzed_target| {
+        let mut file_outcomes = Vec::new();
+        for (file_path, test_file) in discovered_test_files(&analyzed_target)? {
+            let functions = test_file
+                .functions
+                .into_iter()
+                .filter(|function| include_benches || function.kind == TestFunctionKind::Test)
+                .collect::<Vec<_>>();
+            if functions.is_empty() {
+                continue;
+            }
+            file_outcomes.push(TestFileOutcome {
+                function_outcomes: run_test_file(&analyzed_target, &file_path, functions)?,
+                path: test_file.path,
+            });
+        }
+        Ok(file_outcomes)
+    });
+    TestTargetResult {
+        safe_autofix_edit_count_by_workspace_relative_path,
+        tests,
+    }
+}
+
+fn discovered_test_files(
+    analyzed_target: &AnalyzedTarget,
+) -> Result<Vec<(PathBuf, DiscoveredTestFile)>, CompilerFailure> {
+    if analyzed_target
+        .diagnostics
+        .iter()
+        .any(RenderedDiagnostic::is_error)
+    {
+        return Err(build_failed_from_rendered_diagnostics(
+            &analyzed_target.diagnostics,
+        ));
+    }
+    let target_path = path_to_relative_workspace_path(
+        analyzed_target.workspace.root_directory(),
+        &analyzed_target.absolute_target_path,
+    );
+    let mut test_files = Vec::new();
+    for (file_path, resolved_declarations) in &analyzed_target.resolved_declarations_by_path {
+        if analyzed_target.file_role_by_path.get(file_path) != Some(&FileRole::Test)
+            || !file_path.starts_with(&target_path)
+        {
+            continue;
+        }
+        let line_index = LineIndex::new(source_text(analyzed_target, file_path));
+        let functions = resolved_declarations
+            .function_declarations
+            .iter()
+            .filter_map(|function_declaration| {
+                let kind = match function_declaration.test_attribute()? {
+                    KnownAttribute::Bench => TestFunctionKind::Bench,
+                    _ => TestFunctionKind::Test,
+                };
+                Some(DiscoveredTestFunction {
+                    name: function_declaration.name.clone(),
+                    kind,
+                    line: line_index.line(function_declaration.span.start),
+                })
+            })
+            .collect::<Vec<_>>();
+        if functions.is_empty() {
+            continue;
+        }
+        test_files.push((
+            file_path.clone(),
+            DiscoveredTestFile {
+                path: path_to_key(file_path),
+                functions,
+            },
+        ));
+    }
+    Ok(test_files)
+}
+
+fn run_test_file(
+    analyzed_target: &AnalyzedTarget,
+    file_path: &Path,
+    functions: Vec<DiscoveredTestFunction>,
+) -> Result<Vec<TestFunctionOutcome>, CompilerFailure> {
+    let reachable_package_paths = analyzed_target
+        .package_path_by_file
+        .get(file_path)
+        .map(|package_path| {
+            package_dependency_closure(package_path, &analyzed_target.resolved_imports)
+        })
+        .unwrap_or_default();
+    let reachable_diagnostics = reachable_diagnostics(analyzed_target, &reachable_package_paths);
+    if reachable_diagnostics
+        .iter()
+        .any(RenderedDiagnostic::is_error)
+    {
+        return Err(build_failed_from_rendered_diagnostics(
+            &reachable_diagnostics,
+        ));
+    }
+    let executable_lowering_result = lower_resolved_declarations_test_unit(
+        &analyzed_target.resolved_declarations_by_path[file_path],
+        &dependency_library_resolved_declarations(analyzed_target, &reachable_package_paths),
+    );
+    if !matches!(executable_lowering_result.status, PhaseStatus::Ok) {
+        return Err(unsupported_program_failure(
+            "test mode does not support this program yet",
+            &executable_lowering_result.diagnostics,
+            file_path,
+            source_text(analyzed_target, file_path),
+        ));
+    }
+    let mut interpreter = Interpreter::new(executable_lowering_result.value)?;
+    Ok(functions
+        .into_iter()
+        .map(|function| {
+            let start = Instant::now();
+            let result = interpreter.call_function(&function.name, &[]);
+            TestFunctionOutcome {
+                elapsed: start.elapsed(),
+                failure: result.err().map(|failure| failure.message),
+                function,
+            }
+        })
+        .collect())
+}
+
+fn source_text<'a>(analyzed_target: &'a AnalyzedTarget, file_path: &Path) -> &'a str {
+    analyzed_target
+        .source_by_path
+        .get(&display_path(
+            &analyzed_target.workspace_root.join(file_path),
+        ))
+        .map_or("", String::as_str)
+}
@@ -0,0 +1,55 @@
+use compiler__executable_program::{
+    ExecutableEnumLayout, ExecutableFieldLayout, ExecutableLayoutTable, ExecutableListLayout,
+    ExecutableStructDeclaration, ExecutableStructLayout, ExecutableUnionLayout,
+};
+
+// Every runtime value occupies one 8-byte slot: scalars are widened to 64 bits
+// and aggregates (structs, lists, unions, interface values) are heap pointers.
+const VALUE_SLOT_SIZE_BYTES: u32 = 8;
+
+#[must_use]
+pub fn compute_layout_table(
+    struct_declarations: &[ExecutableStructDeclaration],
+) -> ExecutableLayoutTable {
+    ExecutableLayoutTable {
+        struct_layouts: struct_declarations
+            .iter()
+            .map(compute_struct_layout)
+            .collect(),
+        enum_layout: ExecutableEnumLayout {
+            size_bytes: VALUE_SLOT_SIZE_BYTES,
+        },
+        union_layout: ExecutableUnionLayout {
+            size_bytes: 2 * VALUE_SLOT_SIZE_BYTES,
+            tag_offset_bytes: 0,
+            payload_offset_bytes: VALUE_SLOT_SIZE_BYTES,
+        },
+        list_layout: ExecutableListLayout {
+            header_size_bytes: 2 * VALUE_SLOT_SIZE_BYTES,
+            length_offset_bytes: 0,
+            data_pointer_offset_bytes: VALUE_SLOT_SIZE_BYTES,
+            element_stride_bytes: VALUE_SLOT_SIZE_BYTES,
+        },
+    }
+}
+
+fn compute_struct_layout(
+    struct_declaration: &ExecutableStructDeclaration,
+) -> ExecutableStructLayout {
+    let mut offset_bytes = 0;
+    let mut fields = Vec::new();
+    for field in &struct_declaration.fields {
+        fields.push(ExecutableFieldLayout {
+            name: field.name.clone(),
+            offset_bytes,
+            size_bytes: VALUE_SLOT_SIZE_BYTES,
+        });
+        offset_bytes += VALUE_SLOT_SIZE_BYTES;
+    }
+    ExecutableStructLayout {
+        struct_reference: struct_declaration.struct_reference.clone(),
+        size_bytes: offset_bytes,
+        alignment_bytes: VALUE_SLOT_SIZE_BYTES,
+        fields,
+    }
+}
@@ -1,26 +1,29 @@
 use std::collections::BTreeMap;
 
 use compiler__diagnostics::PhaseDiagnostic;
+use compiler__executable_layout::compute_layout_table;
 use compiler__executable_program::{
     ExecutableAssignTarget, ExecutableBinaryOperator, ExecutableCallTarget,
-    ExecutableCallableReference, ExecutableConstantDeclaration, ExecutableConstantReference,
-    ExecutableEnumVariantReference, ExecutableExpression, ExecutableFunctionDeclaration,
-    ExecutableInterfaceDeclaration, ExecutableInterfaceMethodDeclaration,
-    ExecutableInterfaceReference, ExecutableMatchArm, ExecutableMatchPattern,
-    ExecutableMethodDeclaration, ExecutableNominalTypeReference, ExecutableParameterDeclaration,
+    ExecutableConstantDeclaration, ExecutableExpression, ExecutableFold,
+    ExecutableFunctionDeclaration, ExecutableInterfaceDeclaration,
+    ExecutableInterfaceMethodDeclaration, ExecutableMatchArm, ExecutableMatchLiteral,
+    ExecutableMatchPattern, ExecutableMethodDeclaration, ExecutableParameterDeclaration,
     ExecutableProgram, ExecutableStatement, ExecutableStructDeclaration,
-    ExecutableStructFieldDeclaration, ExecutableStructLiteralField, ExecutableStructReference,
-    ExecutableTypeReference, ExecutableUnaryOperator,
+    ExecutableStructFieldDeclaration, ExecutableStructLiteralField, ExecutableTypeReference,
+    ExecutableUnaryOperator, fold_each_statement,
 };
+use compiler__interned_names::{PackagePath, SymbolName};
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
+use compiler__program_references::{CallableReference, EnumVariantReference};
+use compiler__runtime_interface::{ABORT_FUNCTION_CONTRACT, RANGE_STEP_ZERO_MESSAGE};
 use compiler__source::Span;
 use compiler__type_annotated_program::{
     TypeAnnotatedAssignTarget, TypeAnnotatedBinaryOperator, TypeAnnotatedCallTarget,
     TypeAnnotatedConstantDeclaration, TypeAnnotatedExpression, TypeAnnotatedFunctionDeclaration,
-    TypeAnnotatedInterfaceDeclaration, TypeAnnotatedMatchArm, TypeAnnotatedMatchPattern,
-    TypeAnnotatedMethodDeclaration, TypeAnnotatedResolvedTypeArgument, TypeAnnotatedStatement,
-    TypeAnnotatedStructDeclaration, TypeAnnotatedTypeName, TypeAnnotatedUnaryOperator,
-    TypeResolvedDeclarations,
+    TypeAnnotatedInterfaceDeclaration, TypeAnnotatedMatchArm, TypeAnnotatedMatchLiteral,
+    TypeAnnotatedMatchPattern, TypeAnnotatedMethodDeclaration, TypeAnnotatedResolvedTypeArgument,
+    TypeAnnotatedStatement, TypeAnnotatedStructDeclaration, TypeAnnotatedTypeName,
+    TypeAnnotatedUnaryOperator, TypeResolvedDeclarations,
 };
 
 #[must_use]
@@ -41,61 +44,70 @@ pub fn lower_resolved_declarations_build_unit(
         binary_entrypoint_resolved_declarations,
         &mut diagnostics,
     );
+    lower_build_unit(
+        entrypoint_callable_reference,
+        diagnostics,
+        binary_entrypoint_resolved_declarations,
+        dependency_library_resolved_declarations,
+    )
+}
 
-    let mut all_struct_declarations = Vec::new();
-    let mut all_interface_declarations = Vec::new();
-    let mut all_constant_declarations = Vec::new();
-    let mut all_function_declarations = Vec::new();
-    all_struct_declarations.extend(
-        binary_entrypoint_resolved_declarations
-            .struct_declarations
-            .iter()
-            .cloned(),
-    );
-    all_interface_declarations.extend(
-        binary_entrypoint_resolved_declarations
-            .interface_declarations
-            .iter()
-            .cloned(),
-    );
-    all_constant_declarations.extend(
-        binary_entrypoint_resolved_declarations
-            .constant_declarations
-            .iter()
-            .cloned(),
-    );
-    all_function_declarations.extend(
-        binary_entrypoint_resolved_declarations
-            .function_declarations
-            .iter()
-            .cloned(),
-    );
-    for dependency_resolved_declarations in dependency_library_resolved_declarations {
-        all_struct_declarations.extend(
-            dependency_resolved_declarations
-                .struct_declarations
-                .iter()
-                .cloned(),
-        );
-        all_interface_declarations.extend(
-            dependency_resolved_declarations
-                .interface_declarations
-                .iter()
-                .cloned(),
-        );
-        all_constant_declarations.extend(
-            dependency_resolved_declarations
-                .constant_declarations
-                .iter()
-                .cloned(),
-        );
-        all_function_declarations.extend(
-            dependency_resolved_declarations
-                .function_declarations
-                .iter()
-                .cloned(),
-        );
+// Lowers a test file for the test runner, which calls each of its `@test` and
+// `@bench` functions in turn. File role rules have already checked that they
+// take no parameters and return nil; the first of them stands in as the
+// program's entrypoint.
+#[must_use]
+pub fn lower_resolved_declarations_test_unit(
+    test_file_resolved_declarations: &TypeResolvedDeclarations,
+    dependency_library_resolved_declarations: &[&TypeResolvedDeclarations],
+) -> PhaseOutput<ExecutableProgram> {
+    let mut diagnostics = Vec::new();
+    let entrypoint_callable_reference = test_file_resolved_declarations
+        .function_declarations
+        .iter()
+        .find(|function_declaration| function_declaration.test_attribute().is_some())
+        .map(|function_declaration| function_declaration.callable_reference.clone());
+    if entrypoint_callable_reference.is_none() {
+        diagnostics.push(PhaseDiagnostic::new(
+            "test file declares no '@test' or '@bench' functions",
+            fallback_span(),
+        ));
     }
+    lower_build_unit(
+        entrypoint_callable_reference,
+        diagnostics,
+        test_file_resolved_declarations,
+        dependency_library_resolved_declarations,
+    )
+}
+
+fn lower_build_unit(
+    entrypoint_callable_reference: Option<CallableReference>,
+    mut diagnostics: Vec<PhaseDiagnostic>,
+    entrypoint_resolved_declarations: &TypeResolvedDeclarations,
+    dependency_library_resolved_declarations: &[&TypeResolvedDeclarations],
+) -> PhaseOutput<ExecutableProgram> {
+    // The entrypoint and its dependencies are lowered from borrowed
+    // declarations; only the executable program is newly allocated.
+    let all_resolved_declarations = std::iter::once(entrypoint_resolved_declarations)
+        .chain(dependency_library_resolved_declarations.iter().copied())
+        .collect::<Vec<_>>();
+    let all_struct_declarations = all_resolved_declarations
+        .iter()
+        .flat_map(|resolved_declarations| &resolved_declarations.struct_declarations)
+        .collect::<Vec<_>>();
+    let all_interface_declarations = all_resolved_declarations
+        .iter()
+        .flat_map(|resolved_declarations| &resolved_declarations.interface_declarations)
+        .collect::<Vec<_>>();
+    let all_constant_declarations = all_resolved_declarations
+        .iter()
+        .flat_map(|resolved_declarations| &resolved_declarations.constant_declarations)
+        .collect::<Vec<_>>();
+    let all_function_declarations = all_resolved_declarations
+        .iter()
+        .flat_map(|resolved_declarations| &resolved_declarations.function_declarations)
+        .collect::<Vec<_>>();
 
     let constant_declarations =
         lower_constant_declarations(&all_constant_declarations, &mut diagnostics);
@@ -103,6 +115,7 @@ pub fn lower_resolved_declarations_build_unit(
     let struct_declarations = lower_struct_declarations(&all_struct_declarations, &mut diagnostics);
     let function_declarations =
         lower_function_declarations(&all_function_declarations, &mut diagnostics);
+    let layout_table = compute_layout_table(&struct_declarations);
 
     let status = if diagnostics.is_empty() {
         PhaseStatus::Ok
@@ -111,9 +124,9 @@ pub fn lower_resolved_declarations_build_unit(
     };
 
     let entrypoint_callable_reference =
-        entrypoint_callable_reference.unwrap_or_else(|| ExecutableCallableReference {
-            package_path: String::new(),
-            symbol_name: "main".to_string(),
+        entrypoint_callable_reference.unwrap_or_else(|| CallableReference {
+            package_path: PackagePath::new(""),
+            symbol_name: SymbolName::new("main"),
         });
 
     PhaseOutput {
@@ -123,6 +136,7 @@ pub fn lower_resolved_declarations_build_unit(
             interface_declarations,
             struct_declarations,
             function_declarations,
+            layout_table,
         },
         diagnostics,
         safe_autofixes: Vec::new(),
@@ -131,7 +145,7 @@ pub fn lower_resolved_declarations_build_unit(
 }
 
 fn lower_constant_declarations(
-    constant_declarations: &[TypeAnnotatedConstantDeclaration],
+    constant_declarations: &[&TypeAnnotatedConstantDeclaration],
     diagnostics: &mut Vec<PhaseDiagnostic>,
 ) -> Vec<ExecutableConstantDeclaration> {
     let mut lowered = Vec::new();
@@ -140,10 +154,7 @@ fn lower_constant_declarations(
             lower_type_reference_to_type_reference(&constant_declaration.type_reference, &[]);
         lowered.push(ExecutableConstantDeclaration {
             name: constant_declaration.name.clone(),
-            constant_reference: ExecutableConstantReference {
-                package_path: constant_declaration.constant_reference.package_path.clone(),
-                symbol_name: constant_declaration.constant_reference.symbol_name.clone(),
-            },
+            constant_reference: constant_declaration.constant_reference.clone(),
             type_reference,
             initializer: lower_expression(&constant_declaration.initializer, &[], diagnostics),
         });
@@ -152,7 +163,7 @@ fn lower_constant_declarations(
 }
 
 fn lower_function_declarations(
-    function_declarations: &[TypeAnnotatedFunctionDeclaration],
+    function_declarations: &[&TypeAnnotatedFunctionDeclaration],
     diagnostics: &mut Vec<PhaseDiagnostic>,
 ) -> Vec<ExecutableFunctionDeclaration> {
     let mut lowered = Vec::new();
@@ -171,10 +182,7 @@ fn lower_function_declarations(
             };
             type_parameter_constraint_interface_reference_by_name.insert(
                 type_parameter.name.clone(),
-                ExecutableInterfaceReference {
-                    package_path: constraint_interface_reference.package_path.clone(),
-                    symbol_name: constraint_interface_reference.symbol_name.clone(),
-                },
+                constraint_interface_reference.clone(),
             );
         }
         let executable_parameters = function_declaration
@@ -195,26 +203,24 @@ fn lower_function_declarations(
         );
         lowered.push(ExecutableFunctionDeclaration {
             name: function_declaration.name.clone(),
-            callable_reference: ExecutableCallableReference {
-                package_path: function_declaration.callable_reference.package_path.clone(),
-                symbol_name: function_declaration.callable_reference.symbol_name.clone(),
-            },
+            callable_reference: function_declaration.callable_reference.clone(),
             type_parameter_names: type_parameter_names.clone(),
             type_parameter_constraint_interface_reference_by_name,
-            parameters: executable_parameters,
-            return_type,
-            statements: lower_statements(
+            statements: lower_function_body(
                 &function_declaration.statements,
+                &return_type,
                 &type_parameter_names,
                 diagnostics,
             ),
+            parameters: executable_parameters,
+            return_type,
         });
     }
     lowered
 }
 
 fn lower_struct_declarations(
-    struct_declarations: &[TypeAnnotatedStructDeclaration],
+    struct_declarations: &[&TypeAnnotatedStructDeclaration],
     diagnostics: &mut Vec<PhaseDiagnostic>,
 ) -> Vec<ExecutableStructDeclaration> {
     let mut lowered = Vec::new();
@@ -235,22 +241,11 @@ fn lower_struct_declarations(
                 ),
             })
             .collect();
-        let implemented_interfaces = struct_declaration
-            .implemented_interfaces
-            .iter()
-            .map(|implemented_interface| ExecutableInterfaceReference {
-                package_path: implemented_interface.package_path.clone(),
-                symbol_name: implemented_interface.symbol_name.clone(),
-            })
-            .collect::<Vec<_>>();
         lowered.push(ExecutableStructDeclaration {
             name: struct_declaration.name.clone(),
-            struct_reference: ExecutableStructReference {
-                package_path: struct_declaration.struct_reference.package_path.clone(),
-                symbol_name: struct_declaration.struct_reference.symbol_name.clone(),
-            },
+            struct_reference: struct_declaration.struct_reference.clone(),
             type_parameter_names: type_parameter_names.clone(),
-            implemented_interfaces,
+            implemented_interfaces: struct_declaration.implemented_interfaces.clone(),
             fields: executable_fields,
             methods: lower_method_declarations(
                 &struct_declaration.methods,
@@ -263,7 +258,7 @@ fn lower_struct_declarations(
 }
 
 fn lower_interface_declarations(
-    interface_declarations: &[TypeAnnotatedInterfaceDeclaration],
+    interface_declarations: &[&TypeAnnotatedInterfaceDeclaration],
 ) -> Vec<ExecutableInterfaceDeclaration> {
     let mut lowered = Vec::new();
     for interface_declaration in interface_declarations {
@@ -293,16 +288,7 @@ fn lower_interface_declarations(
             .collect();
         lowered.push(ExecutableInterfaceDeclaration {
             name: interface_declaration.name.clone(),
-            interface_reference: ExecutableInterfaceReference {
-                package_path: interface_declaration
-                    .interface_reference
-                    .package_path
-                    .clone(),
-                symbol_name: interface_declaration
-                    .interface_reference
-                    .symbol_name
-                    .clone(),
-            },
+            interface_reference: interface_declaration.interface_reference.clone(),
             methods,
         });
     }
@@ -335,13 +321,14 @@ fn lower_method_declarations(
         lowered.push(ExecutableMethodDeclaration {
             name: method_declaration.name.clone(),
             self_mutable: method_declaration.self_mutable,
-            parameters: executable_parameters,
-            return_type,
-            statements: lower_statements(
+            statements: lower_function_body(
                 &method_declaration.statements,
+                &return_type,
                 enclosing_type_parameter_names,
                 diagnostics,
             ),
+            parameters: executable_parameters,
+            return_type,
         });
     }
     lowered
@@ -350,13 +337,13 @@ fn lower_method_declarations(
 fn validate_main_signature_from_resolved_declarations(
     resolved_declarations: &TypeResolvedDeclarations,
     diagnostics: &mut Vec<PhaseDiagnostic>,
-) -> Option<ExecutableCallableReference> {
+) -> Option<CallableReference> {
     let fallback_span_for_diagnostic = resolved_declarations
         .function_declarations
         .iter()
         .find(|function_declaration| function_declaration.name == "main")
         .map_or_else(fallback_span, |main_function_declaration| {
-            main_function_declaration.span.clone()
+            main_function_declaration.span
         });
     let Some(main_declaration) = resolved_declarations
         .function_declarations
@@ -372,13 +359,13 @@ fn validate_main_signature_from_resolved_declarations(
     if !main_declaration.type_parameters.is_empty() {
         diagnostics.push(PhaseDiagnostic::new(
             "build mode currently supports only non-generic main()",
-            fallback_span_for_diagnostic.clone(),
+            fallback_span_for_diagnostic,
         ));
     }
     if !main_declaration.parameters.is_empty() {
         diagnostics.push(PhaseDiagnostic::new(
             "build mode currently supports only parameterless main()",
-            fallback_span_for_diagnostic.clone(),
+            fallback_span_for_diagnostic,
         ));
     }
     if !matches!(
@@ -391,10 +378,101 @@ fn validate_main_signature_from_resolved_declarations(
         ));
     }
 
-    Some(ExecutableCallableReference {
-        package_path: main_declaration.callable_reference.package_path.clone(),
-        symbol_name: main_declaration.callable_reference.symbol_name.clone(),
-    })
+    Some(main_declaration.callable_reference.clone())
+}
+
+// Deferred expressions become an explicit cleanup list that runs, most recent
+// first, at every return that follows them. Type analysis only allows `defer`
+// among the top-level statements of a body, so the list is complete for each
+// statement as it is lowered.
+fn lower_function_body(
+    statements: &[TypeAnnotatedStatement],
+    return_type: &ExecutableTypeReference,
+    type_parameter_names: &[String],
+    diagnostics: &mut Vec<PhaseDiagnostic>,
+) -> Vec<ExecutableStatement> {
+    let mut cleanup = DeferredCleanup {
+        deferred_expressions: Vec::new(),
+        return_type,
+        result_count: 0,
+    };
+    let mut lowered_statements = Vec::new();
+    for statement in statements {
+        if let TypeAnnotatedStatement::Defer { value, .. } = statement {
+            cleanup.deferred_expressions.insert(
+                0,
+                lower_expression(value, type_parameter_names, diagnostics),
+            );
+            continue;
+        }
+        let mut lowered_statement = Vec::new();
+        lower_statement(
+            statement,
+            type_parameter_names,
+            diagnostics,
+            &mut lowered_statement,
+        );
+        lowered_statements.extend(cleanup.fold_statements(lowered_statement));
+    }
+    lowered_statements
+}
+
+struct DeferredCleanup<'a> {
+    deferred_expressions: Vec<ExecutableExpression>,
+    return_type: &'a ExecutableTypeReference,
+    result_count: usize,
+}
+
+impl ExecutableFold for DeferredCleanup<'_> {
+    fn fold_statements(
+        &mut self,
+        statements: Vec<ExecutableStatement>,
+    ) -> Vec<ExecutableStatement> {
+        let mut folded_statements = Vec::new();
+        for statement in fold_each_statement(self, statements) {
+            match statement {
+                ExecutableStatement::Return { value } if !self.deferred_expressions.is_empty() => {
+                    self.push_cleanup_and_return(value, &mut folded_statements);
+                }
+                statement => folded_statements.push(statement),
+            }
+        }
+        folded_statements
+    }
+}
+
+impl DeferredCleanup<'_> {
+    // The returned value is computed before the cleanup runs, so a non-nil
+    // value is held in a hidden local, which user code cannot name.
+    fn push_cleanup_and_return(
+        &mut self,
+        value: ExecutableExpression,
+        statements: &mut Vec<ExecutableStatement>,
+    ) {
+        let returned_value = if matches!(value, ExecutableExpression::NilLiteral) {
+            value
+        } else if matches!(self.return_type, ExecutableTypeReference::Nil) {
+            statements.push(ExecutableStatement::Expression { expression: value });
+            ExecutableExpression::NilLiteral
+        } else {
+            let result_name = format!("$result.{}", self.result_count);
+            self.result_count += 1;
+            statements.push(ExecutableStatement::Binding {
+                name: result_name.clone(),
+                mutable: false,
+                initializer: value,
+            });
+            hidden_local(&result_name, self.return_type.clone())
+        };
+        statements.extend(self.deferred_expressions.iter().map(|expression| {
+            ExecutableStatement::Expression {
+                expression: expression.clone(),
+            }
+        }));
+        statements.push(ExecutableStatement::Return {
+            value: returned_value,
+        });
+    }
 }
 
 fn lower_statements(
@@ -402,18 +480,25 @@ fn lower_statements(
     type_parameter_names: &[String],
     diagnostics: &mut Vec<PhaseDiagnostic>,
 ) -> Vec<ExecutableStatement> {
-    statements
-        .iter()
-        .map(|statement| lower_statement(statement, type_parameter_names, diagnostics))
-        .collect()
+    let mut lowered_statements = Vec::new();
+    for statement in statements {
+        lower_statement(
+            statement,
+            type_parameter_names,
+            diagnostics,
+            &mut lowered_statements,
+        );
+    }
+    lowered_statements
 }
 
 fn lower_statement(
     statement: &TypeAnnotatedStatement,
     type_parameter_names: &[String],
     diagnostics: &mut Vec<PhaseDiagnostic>,
-) -> ExecutableStatement {
-    match statement {
+    lowered_statements: &mut Vec<ExecutableStatement>,
+) {
+    let lowered_statement = match statement {
         TypeAnnotatedStatement::Binding {
             name,
             mutable,
@@ -457,8 +542,40 @@ fn lower_statement(
                 .map(|expression| lower_expression(expression, type_parameter_names, diagnostics)),
             body_statements: lower_statements(body_statements, type_parameter_names, diagnostics),
         },
+        TypeAnnotatedStatement::ForRange {
+            name,
+            start,
+            end,
+            step,
+            body_statements,
+            span,
+        } => {
+            let range_loop = RangeLoop {
+                name,
+                start: lower_expression(start, type_parameter_names, diagnostics),
+                end: lower_expression(end, type_parameter_names, diagnostics),
+                step: step
+                    .as_ref()
+                    .map(|step| lower_expression(step, type_parameter_names, diagnostics)),
+                body_statements: lower_statements(
+                    body_statements,
+                    type_parameter_names,
+                    diagnostics,
+                ),
+                span: *span,
+            };
+            lowered_statements.extend(range_loop.into_statements());
+            return;
+        }
         TypeAnnotatedStatement::Break { .. } => ExecutableStatement::Break,
         TypeAnnotatedStatement::Continue { .. } => ExecutableStatement::Continue,
+        TypeAnnotatedStatement::Defer { span, .. } => {
+            diagnostics.push(PhaseDiagnostic::new(
+                "build mode requires defer at the top level of a function body",
+                *span,
+            ));
+            return;
+        }
         TypeAnnotatedStatement::Expression { value, .. } => {
             let executable_expression = lower_expression(value, type_parameter_names, diagnostics);
             ExecutableStatement::Expression {
@@ -471,6 +588,208 @@ fn lower_statement(
                 value: executable_expression,
             }
         }
+    };
+    lowered_statements.push(lowered_statement);
+}
+
+struct RangeLoop<'a> {
+    name: &'a str,
+    start: ExecutableExpression,
+    end: ExecutableExpression,
+    step: Option<ExecutableExpression>,
+    body_statements: Vec<ExecutableStatement>,
+    span: Span,
+}
+
+impl RangeLoop<'_> {
+    // A range loop becomes a conditional loop over hidden locals, which user
+    // code cannot name. The counter advances before the body runs so that
+    // `continue` does not skip it, and it advances with wrapping addition: a
+    // wrapped counter moves against the step, which ends the loop instead of
+    // aborting or revisiting values. A step that is zero when the loop starts
+    // aborts, like division by zero; type analysis rejects a literal zero.
+    fn into_statements(self) -> Vec<ExecutableStatement> {
+        let counter_name = self.hidden_name("counter");
+        let end_name = self.hidden_name("end");
+        let continues_name = self.hidden_name("continues");
+        let mut statements = vec![
+            ExecutableStatement::Binding {
+                name: counter_name.clone(),
+                mutable: true,
+                initializer: self.start.clone(),
+            },
+            ExecutableStatement::Binding {
+                name: end_name.clone(),
+                mutable: false,
+                initializer: self.end.clone(),
+            },
+        ];
+        let step = match &self.step {
+            None => ExecutableExpression::IntegerLiteral { value: 1 },
+            Some(step @ ExecutableExpression::IntegerLiteral { .. }) => step.clone(),
+            Some(step) => {
+                let step_name = self.hidden_name("step");
+                statements.push(ExecutableStatement::Binding {
+                    name: step_name.clone(),
+                    mutable: false,
+                    initializer: step.clone(),
+                });
+                statements.push(ExecutableStatement::If {
+                    condition: binary(
+                        ExecutableBinaryOperator::EqualEqual,
+                        int64_local(&step_name),
+                        ExecutableExpression::IntegerLiteral { value: 0 },
+                    ),
+                    then_statements: vec![ExecutableStatement::Expression {
+                        expression: abort_call(RANGE_STEP_ZERO_MESSAGE),
+                    }],
+                    else_statements: None,
+                });
+                int64_local(&step_name)
+            }
+        };
+        statements.push(ExecutableStatement::Binding {
+            name: continues_name.clone(),
+            mutable: true,
+            initializer: range_continues(&counter_name, None, &end_name, &step),
+        });
+        let mut body_statements = vec![
+            ExecutableStatement::Binding {
+                name: self.name.to_string(),
+                mutable: false,
+                initializer: int64_local(&counter_name),
+            },
+            ExecutableStatement::Assign {
+                target: ExecutableAssignTarget::Name {
+                    name: counter_name.clone(),
+                },
+                value: binary(
+                    ExecutableBinaryOperator::Add,
+                    int64_local(&counter_name),
+                    step.clone(),
+                ),
+            },
+            ExecutableStatement::Assign {
+                target: ExecutableAssignTarget::Name {
+                    name: continues_name.clone(),
+                },
+                value: range_continues(&counter_name, Some(self.name), &end_name, &step),
+            },
+        ];
+        body_statements.extend(self.body_statements);
+        statements.push(ExecutableStatement::For {
+            condition: Some(hidden_local(
+                &continues_name,
+                ExecutableTypeReference::Boolean,
+            )),
+            body_statements,
+        });
+        statements
+    }
+
+    // The span start keeps the locals of nested and sibling loops apart.
+    fn hidden_name(&self, role: &str) -> String {
+        format!("${}.{role}@{}", self.name, self.span.start)
+    }
+}
+
+// Whether the counter is still short of the end in the direction of the step,
+// and, after an advance, still past the previous value.
+fn range_continues(
+    counter_name: &str,
+    previous_name: Option<&str>,
+    end_name: &str,
+    step: &ExecutableExpression,
+) -> ExecutableExpression {
+    let direction_continues = |toward: ExecutableBinaryOperator, away: ExecutableBinaryOperator| {
+        let short_of_end = binary(toward, int64_local(counter_name), int64_local(end_name));
+        match previous_name {
+            Some(previous_name) => binary(
+                ExecutableBinaryOperator::And,
+                binary(away, int64_local(counter_name), int64_local(previous_name)),
+                short_of_end,
+            ),
+            None => short_of_end,
+        }
+    };
+    let ascending = direction_continues(
+        ExecutableBinaryOperator::LessThan,
+        ExecutableBinaryOperator::GreaterThan,
+    );
+    let descending = direction_continues(
+        ExecutableBinaryOperator::GreaterThan,
+        ExecutableBinaryOperator::LessThan,
+    );
+    match step {
+        ExecutableExpression::IntegerLiteral { value } if *value > 0 => ascending,
+        ExecutableExpression::IntegerLiteral { .. } => descending,
+        step => binary(
+            ExecutableBinaryOperator::Or,
+            binary(
+                ExecutableBinaryOperator::And,
+                binary(
+                    ExecutableBinaryOperator::GreaterThan,
+                    step.clone(),
+                    ExecutableExpression::IntegerLiteral { value: 0 },
+                ),
+                ascending,
+            ),
+            binary(
+                ExecutableBinaryOperator::And,
+                binary(
+                    ExecutableBinaryOperator::LessThan,
+                    step.clone(),
+                    ExecutableExpression::IntegerLiteral { value: 0 },
+                ),
+                descending,
+            ),
+        ),
+    }
+}
+
+fn abort_call(message: &str) -> ExecutableExpression {
+    let function_name = ABORT_FUNCTION_CONTRACT.language_name;
+    ExecutableExpression::Call {
+        callee: Box::new(hidden_local(
+            function_name,
+            ExecutableTypeReference::Function {
+                parameter_types: vec![ExecutableTypeReference::String],
+                return_type: Box::new(ExecutableTypeReference::Never),
+            },
+        )),
+        call_target: Some(ExecutableCallTarget::BuiltinFunction {
+            function_name: function_name.to_string(),
+        }),
+        arguments: vec![ExecutableExpression::StringLiteral {
+            value: message.to_string(),
+        }],
+        type_arguments: Vec::new(),
+    }
+}
+
+fn hidden_local(name: &str, type_reference: ExecutableTypeReference) -> ExecutableExpression {
+    ExecutableExpression::Identifier {
+        name: name.to_string(),
+        constant_reference: None,
+        callable_reference: None,
+        type_reference,
+    }
+}
+
+fn int64_local(name: &str) -> ExecutableExpression {
+    hidden_local(name, ExecutableTypeReference::Int64)
+}
+
+fn binary(
+    operator: ExecutableBinaryOperator,
+    left: ExecutableExpression,
+    right: ExecutableExpression,
+) -> ExecutableExpression {
+    ExecutableExpression::Binary {
+        operator,
+        left: Box::new(left),
+        right: Box::new(right),
+        operation_description: None,
     }
 }
 
@@ -516,7 +835,7 @@ fn lower_expression(
             if elements.is_empty() {
                 diagnostics.push(PhaseDiagnostic::new(
                     "build mode does not support empty list literals yet",
-                    span.clone(),
+                    *span,
                 ));
                 return ExecutableExpression::NilLiteral;
             }
@@ -540,18 +859,8 @@ fn lower_expression(
             ..
         } => ExecutableExpression::Identifier {
             name: name.clone(),
-            constant_reference: constant_reference.as_ref().map(|constant_reference| {
-                ExecutableConstantReference {
-                    package_path: constant_reference.package_path.clone(),
-                    symbol_name: constant_reference.symbol_name.clone(),
-                }
-            }),
-            callable_reference: callable_reference.as_ref().map(|callable_reference| {
-                ExecutableCallableReference {
-                    package_path: callable_reference.package_path.clone(),
-                    symbol_name: callable_reference.symbol_name.clone(),
-                }
-            }),
+            constant_reference: constant_reference.clone(),
+            callable_reference: callable_reference.clone(),
             type_reference: lower_type_reference_to_type_reference(
                 type_reference,
                 type_parameter_names,
@@ -561,29 +870,43 @@ fn lower_expression(
             enum_variant_reference,
             ..
         } => ExecutableExpression::EnumVariantLiteral {
-            enum_variant_reference: ExecutableEnumVariantReference {
-                enum_name: enum_variant_reference.enum_name.clone(),
-                variant_name: enum_variant_reference.variant_name.clone(),
-            },
-            type_reference: ExecutableTypeReference::NominalType {
-                nominal_type_reference: None,
-                name: format!(
-                    "{}.{}",
-                    enum_variant_reference.enum_name, enum_variant_reference.variant_name
-                ),
+            enum_variant_reference: enum_variant_reference.clone(),
+            type_reference: enum_variant_type_reference(enum_variant_reference),
+        },
+        TypeAnnotatedExpression::EnumVariantName {
+            value,
+            enum_variant_references,
+            ..
+        } => ExecutableExpression::EnumVariantName {
+            value: Box::new(lower_expression(value, type_parameter_names, diagnostics)),
+            enum_variant_references: enum_variant_references.clone(),
+        },
+        TypeAnnotatedExpression::EnumVariantFromString {
+            value,
+            enum_variant_references,
+            ..
+        } => ExecutableExpression::EnumVariantFromString {
+            value: Box::new(lower_expression(value, type_parameter_names, diagnostics)),
+            enum_variant_references: enum_variant_references.clone(),
+            type_reference: ExecutableTypeReference::Union {
+                members: enum_variant_references
+                    .iter()
+                    .map(enum_variant_type_reference)
+                    .chain([ExecutableTypeReference::Nil])
+                    .collect(),
             },
         },
         TypeAnnotatedExpression::StructLiteral {
             type_name,
             struct_reference,
             fields,
+            resolved_type_arguments,
             span,
-            ..
         } => {
             let Some(struct_reference) = struct_reference else {
                 diagnostics.push(PhaseDiagnostic::new(
                     "build mode requires resolved struct reference metadata for struct literals",
-                    span.clone(),
+                    *span,
                 ));
                 return ExecutableExpression::NilLiteral;
             };
@@ -595,6 +918,27 @@ fn lower_expression(
             ) else {
                 return ExecutableExpression::NilLiteral;
             };
+            let type_reference = match type_reference {
+                ExecutableTypeReference::NominalType {
+                    nominal_type_reference,
+                    name,
+                } if !resolved_type_arguments.is_empty() => {
+                    ExecutableTypeReference::NominalTypeApplication {
+                        base_nominal_type_reference: nominal_type_reference,
+                        base_name: name,
+                        arguments: resolved_type_arguments
+                            .iter()
+                            .map(|type_reference| {
+                                lower_type_reference_to_type_reference(
+                                    type_reference,
+                                    type_parameter_names,
+                                )
+                            })
+                            .collect(),
+                    }
+                }
+                type_reference => type_reference,
+            };
             let executable_fields = fields
                 .iter()
                 .map(|field| ExecutableStructLiteralField {
@@ -603,20 +947,23 @@ fn lower_expression(
                 })
                 .collect();
             ExecutableExpression::StructLiteral {
-                struct_reference: ExecutableStructReference {
-                    package_path: struct_reference.package_path.clone(),
-                    symbol_name: struct_reference.symbol_name.clone(),
-                },
+                struct_reference: struct_reference.clone(),
                 type_reference,
                 fields: executable_fields,
             }
         }
-        TypeAnnotatedExpression::FieldAccess { target, field, .. } => {
-            ExecutableExpression::FieldAccess {
-                target: Box::new(lower_expression(target, type_parameter_names, diagnostics)),
-                field: field.clone(),
-            }
-        }
+        TypeAnnotatedExpression::FieldAccess {
+            target,
+            field,
+            type_reference,
+            ..
+        } => ExecutableExpression::FieldAccess {
+            target: Box::new(lower_expression(target, type_parameter_names, diagnostics)),
+            field: field.clone(),
+            type_reference: type_reference.as_ref().map(|type_reference| {
+                lower_type_reference_to_type_reference(type_reference, type_parameter_names)
+            }),
+        },
         TypeAnnotatedExpression::IndexAccess { target, index, .. } => {
             ExecutableExpression::IndexAccess {
                 target: Box::new(lower_expression(target, type_parameter_names, diagnostics)),
@@ -626,6 +973,7 @@ fn lower_expression(
         TypeAnnotatedExpression::Unary {
             operator,
             expression,
+            operation_description,
             ..
         } => ExecutableExpression::Unary {
             operator: match operator {
@@ -637,11 +985,13 @@ fn lower_expression(
                 type_parameter_names,
                 diagnostics,
             )),
+            operation_description: operation_description.clone(),
         },
         TypeAnnotatedExpression::Binary {
             operator,
             left,
             right,
+            operation_description,
             ..
         } => ExecutableExpression::Binary {
             operator: match operator {
@@ -650,6 +1000,11 @@ fn lower_expression(
                 TypeAnnotatedBinaryOperator::Multiply => ExecutableBinaryOperator::Multiply,
                 TypeAnnotatedBinaryOperator::Divide => ExecutableBinaryOperator::Divide,
                 TypeAnnotatedBinaryOperator::Modulo => ExecutableBinaryOperator::Modulo,
+                TypeAnnotatedBinaryOperator::BitwiseAnd => ExecutableBinaryOperator::BitwiseAnd,
+                TypeAnnotatedBinaryOperator::BitwiseOr => ExecutableBinaryOperator::BitwiseOr,
+                TypeAnnotatedBinaryOperator::BitwiseXor => ExecutableBinaryOperator::BitwiseXor,
+                TypeAnnotatedBinaryOperator::ShiftLeft => ExecutableBinaryOperator::ShiftLeft,
+                TypeAnnotatedBinaryOperator::ShiftRight => ExecutableBinaryOperator::ShiftRight,
                 TypeAnnotatedBinaryOperator::EqualEqual => ExecutableBinaryOperator::EqualEqual,
                 TypeAnnotatedBinaryOperator::NotEqual => ExecutableBinaryOperator::NotEqual,
                 TypeAnnotatedBinaryOperator::LessThan => ExecutableBinaryOperator::LessThan,
@@ -665,6 +1020,7 @@ fn lower_expression(
             },
             left: Box::new(lower_expression(left, type_parameter_names, diagnostics)),
             right: Box::new(lower_expression(right, type_parameter_names, diagnostics)),
+            operation_description: operation_description.clone(),
         },
         TypeAnnotatedExpression::Call {
             callee,
@@ -686,25 +1042,75 @@ fn lower_expression(
                 .collect();
             ExecutableExpression::Call {
                 callee: Box::new(lower_expression(callee, type_parameter_names, diagnostics)),
-                call_target: call_target.as_ref().map(|call_target| match call_target {
-                    TypeAnnotatedCallTarget::BuiltinFunction { function_name } => {
-                        ExecutableCallTarget::BuiltinFunction {
-                            function_name: function_name.clone(),
+                // Method calls dispatch through the callee field access.
+                call_target: call_target
+                    .as_ref()
+                    .and_then(|call_target| match call_target {
+                        TypeAnnotatedCallTarget::BuiltinFunction { function_name } => {
+                            Some(ExecutableCallTarget::BuiltinFunction {
+                                function_name: function_name.clone(),
+                            })
                         }
-                    }
-                    TypeAnnotatedCallTarget::UserDefinedFunction { callable_reference } => {
-                        ExecutableCallTarget::UserDefinedFunction {
-                            callable_reference: ExecutableCallableReference {
-                                package_path: callable_reference.package_path.clone(),
-                                symbol_name: callable_reference.symbol_name.clone(),
-                            },
+                        TypeAnnotatedCallTarget::UserDefinedFunction { callable_reference } => {
+                            Some(ExecutableCallTarget::UserDefinedFunction {
+                                callable_reference: callable_reference.clone(),
+                            })
                         }
-                    }
-                }),
+                        TypeAnnotatedCallTarget::UserDefinedMethod { .. } => None,
+                    }),
                 arguments: lowered_arguments,
                 type_arguments: lowered_type_arguments,
             }
         }
+        TypeAnnotatedExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            type_reference,
+            span,
+        } => {
+            let Some(type_reference) = type_reference else {
+                diagnostics.push(PhaseDiagnostic::new(
+                    "build mode requires a resolved result type for if expressions",
+                    *span,
+                ));
+                return ExecutableExpression::NilLiteral;
+            };
+            ExecutableExpression::If {
+                condition: Box::new(lower_expression(
+                    condition,
+                    type_parameter_names,
+                    diagnostics,
+                )),
+                then_branch: Box::new(lower_expression(
+                    then_branch,
+                    type_parameter_names,
+                    diagnostics,
+                )),
+                else_branch: Box::new(lower_expression(
+                    else_branch,
+                    type_parameter_names,
+                    diagnostics,
+                )),
+                type_reference: lower_type_reference_to_type_reference(
+                    type_reference,
+                    type_parameter_names,
+                ),
+            }
+        }
+        // A block that only holds its value needs no scope of its own.
+        TypeAnnotatedExpression::Block {
+            statements, value, ..
+        } => {
+            let value = lower_expression(value, type_parameter_names, diagnostics);
+            if statements.is_empty() {
+                return value;
+            }
+            ExecutableExpression::Block {
+                statements: lower_statements(statements, type_parameter_names, diagnostics),
+                value: Box::new(value),
+            }
+        }
         TypeAnnotatedExpression::Match { target, arms, .. } => {
             let Some(lowered_arms) = lower_match_arms(arms, type_parameter_names, diagnostics)
             else {
@@ -762,6 +1168,7 @@ fn lower_expression(
                         operator: ExecutableBinaryOperator::Add,
                         left: Box::new(left),
                         right: Box::new(right),
+                        operation_description: None,
                     })
                 }
             }
@@ -769,6 +1176,18 @@ fn lower_expression(
     }
 }
 
+fn enum_variant_type_reference(
+    enum_variant_reference: &EnumVariantReference,
+) -> ExecutableTypeReference {
+    ExecutableTypeReference::NominalType {
+        nominal_type_reference: None,
+        name: format!(
+            "{}.{}",
+            enum_variant_reference.enum_name, enum_variant_reference.variant_name
+        ),
+    }
+}
+
 fn lower_type_reference_to_type_reference(
     type_reference: &TypeAnnotatedResolvedTypeArgument,
     type_parameter_names: &[String],
@@ -820,12 +1239,7 @@ fn lower_type_reference_to_type_reference(
             base_name,
             arguments,
         } => ExecutableTypeReference::NominalTypeApplication {
-            base_nominal_type_reference: base_nominal_type_reference.as_ref().map(|reference| {
-                ExecutableNominalTypeReference {
-                    package_path: reference.package_path.clone(),
-                    symbol_name: reference.symbol_name.clone(),
-                }
-            }),
+            base_nominal_type_reference: base_nominal_type_reference.clone(),
             base_name: base_name.clone(),
             arguments: arguments
                 .iter()
@@ -838,12 +1252,7 @@ fn lower_type_reference_to_type_reference(
             nominal_type_reference,
             name,
         } => ExecutableTypeReference::NominalType {
-            nominal_type_reference: nominal_type_reference.as_ref().map(|reference| {
-                ExecutableNominalTypeReference {
-                    package_path: reference.package_path.clone(),
-                    symbol_name: reference.symbol_name.clone(),
-                }
-            }),
+            nominal_type_reference: nominal_type_reference.clone(),
             name: name.clone(),
         },
     }
@@ -858,7 +1267,7 @@ fn lower_type_name_to_type_reference(
     if type_name.names.is_empty() {
         diagnostics.push(PhaseDiagnostic::new(
             "build mode requires non-empty type names",
-            type_name.span.clone(),
+            type_name.span,
         ));
         return None;
     }
@@ -905,7 +1314,7 @@ fn lower_type_name_segment_to_type_reference(
                     "type parameter '{}' does not take type arguments",
                     type_name_segment.name
                 ),
-                type_name_segment.span.clone(),
+                type_name_segment.span,
             ));
             return None;
         }
@@ -919,7 +1328,7 @@ fn lower_type_name_segment_to_type_reference(
             if has_type_arguments {
                 diagnostics.push(PhaseDiagnostic::new(
                     "built-in type 'int64' does not take type arguments",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -929,7 +1338,7 @@ fn lower_type_name_segment_to_type_reference(
             if has_type_arguments {
                 diagnostics.push(PhaseDiagnostic::new(
                     "built-in type 'boolean' does not take type arguments",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -939,7 +1348,7 @@ fn lower_type_name_segment_to_type_reference(
             if has_type_arguments {
                 diagnostics.push(PhaseDiagnostic::new(
                     "built-in type 'string' does not take type arguments",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -949,7 +1358,7 @@ fn lower_type_name_segment_to_type_reference(
             if has_type_arguments {
                 diagnostics.push(PhaseDiagnostic::new(
                     "built-in type 'nil' does not take type arguments",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -958,7 +1367,7 @@ fn lower_type_name_segment_to_type_reference(
             } else {
                 diagnostics.push(PhaseDiagnostic::new(
                     "build mode does not support nil as a struct field type yet",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 None
             }
@@ -967,7 +1376,7 @@ fn lower_type_name_segment_to_type_reference(
             if has_type_arguments {
                 diagnostics.push(PhaseDiagnostic::new(
                     "built-in type 'never' does not take type arguments",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -980,7 +1389,7 @@ fn lower_type_name_segment_to_type_reference(
                         "built-in type 'List' expects 1 type argument, got {}",
                         type_name_segment.type_arguments.len()
                     ),
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -998,7 +1407,7 @@ fn lower_type_name_segment_to_type_reference(
             if type_name_segment.type_arguments.is_empty() {
                 diagnostics.push(PhaseDiagnostic::new(
                     "function type must include at least a return type",
-                    type_name_segment.span.clone(),
+                    type_name_segment.span,
                 ));
                 return None;
             }
@@ -1035,24 +1444,13 @@ fn lower_type_name_segment_to_type_reference(
                     })
                     .collect::<Option<Vec<_>>>()?;
                 Some(ExecutableTypeReference::NominalTypeApplication {
-                    base_nominal_type_reference: type_name_segment
-                        .nominal_type_reference
-                        .as_ref()
-                        .map(|reference| ExecutableNominalTypeReference {
-                            package_path: reference.package_path.clone(),
-                            symbol_name: reference.symbol_name.clone(),
-                        }),
+                    base_nominal_type_reference: type_name_segment.nominal_type_reference.clone(),
                     base_name: type_name_segment.name.clone(),
                     arguments,
                 })
             } else {
                 Some(ExecutableTypeReference::NominalType {
-                    nominal_type_reference: type_name_segment.nominal_type_reference.as_ref().map(
-                        |reference| ExecutableNominalTypeReference {
-                            package_path: reference.package_path.clone(),
-                            symbol_name: reference.symbol_name.clone(),
-                        },
-                    ),
+                    nominal_type_reference: type_name_segment.nominal_type_reference.clone(),
                     name: type_name_segment.name.clone(),
                 })
             }
@@ -1070,6 +1468,10 @@ fn lower_match_arms(
         let pattern = lower_match_pattern(&arm.pattern, type_parameter_names, diagnostics)?;
         lowered_arms.push(ExecutableMatchArm {
             pattern,
+            guard: arm
+                .guard
+                .as_ref()
+                .map(|guard| lower_expression(guard, type_parameter_names, diagnostics)),
             value: lower_expression(&arm.value, type_parameter_names, diagnostics),
         });
     }
@@ -1105,14 +1507,22 @@ fn lower_match_pattern(
                 type_reference,
             })
         }
+        TypeAnnotatedMatchPattern::Literal { value, .. } => Some(ExecutableMatchPattern::Literal {
+            value: match value {
+                TypeAnnotatedMatchLiteral::Integer(value) => {
+                    ExecutableMatchLiteral::Integer(*value)
+                }
+                TypeAnnotatedMatchLiteral::String(value) => {
+                    ExecutableMatchLiteral::String(value.clone())
+                }
+                TypeAnnotatedMatchLiteral::Boolean(value) => {
+                    ExecutableMatchLiteral::Boolean(*value)
+                }
+            },
+        }),
     }
 }
 
 fn fallback_span() -> Span {
-    Span {
-        start: 0,
-        end: 0,
-        line: 1,
-        column: 1,
-    }
+    Span::new(0, 0)
 }
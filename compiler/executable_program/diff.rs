@@ -0,0 +1,312 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::disassembly::{
+    disassemble_function, disassemble_method, method_signature_text, qualified_name,
+    type_parameters_text, type_text,
+};
+use crate::{ExecutableInterfaceDeclaration, ExecutableProgram, ExecutableStructDeclaration};
+
+// What changed between two programs, such as two builds of one entrypoint.
+// Functions and types are named as disassembly names them, and methods as
+// `Type.method`. A function or method has changed when its disassembly has,
+// which includes the source positions that its abort messages name. Type
+// changes cover structs and interfaces and describe how their shape changed;
+// changes to method bodies are listed with the functions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramDiff {
+    pub function_changes: Vec<FunctionChange>,
+    pub type_changes: Vec<TypeChange>,
+}
+
+impl ProgramDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.function_changes.is_empty() && self.type_changes.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionChange {
+    pub name: String,
+    pub kind: ChangeKind,
+}
+
+// `details` describes each change to a changed type's shape, such as
+// "field 'x' changed from int64 to string", and is empty otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub details: Vec<String>,
+}
+
+// Changes are listed in name order.
+#[must_use]
+pub fn diff_programs(
+    old_program: &ExecutableProgram,
+    new_program: &ExecutableProgram,
+) -> ProgramDiff {
+    let old_function_text_by_name = function_text_by_name(old_program);
+    let new_function_text_by_name = function_text_by_name(new_program);
+    let function_changes = all_names(&old_function_text_by_name, &new_function_text_by_name)
+        .into_iter()
+        .filter_map(|name| {
+            let kind = change_kind(
+                old_function_text_by_name.get(&name),
+                new_function_text_by_name.get(&name),
+            )?;
+            Some(FunctionChange { name, kind })
+        })
+        .collect();
+
+    let old_type_shape_by_name = type_shape_by_name(old_program);
+    let new_type_shape_by_name = type_shape_by_name(new_program);
+    let type_changes = all_names(&old_type_shape_by_name, &new_type_shape_by_name)
+        .into_iter()
+        .filter_map(|name| {
+            let old_type_shape = old_type_shape_by_name.get(&name);
+            let new_type_shape = new_type_shape_by_name.get(&name);
+            let kind = change_kind(old_type_shape, new_type_shape)?;
+            let details = match (old_type_shape, new_type_shape) {
+                (Some(old_type_shape), Some(new_type_shape)) => {
+                    type_shape_changes(old_type_shape, new_type_shape)
+                }
+                _ => Vec::new(),
+            };
+            Some(TypeChange {
+                name,
+                kind,
+                details,
+            })
+        })
+        .collect();
+
+    ProgramDiff {
+        function_changes,
+        type_changes,
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct TypeShape {
+    kind: &'static str,
+    type_parameters: String,
+    implemented_interfaces: BTreeSet<String>,
+    fields: Vec<(String, String)>,
+    method_signature_by_name: BTreeMap<String, String>,
+}
+
+fn function_text_by_name(program: &ExecutableProgram) -> BTreeMap<String, String> {
+    let mut function_text_by_name = BTreeMap::new();
+    for function_declaration in &program.function_declarations {
+        function_text_by_name.insert(
+            qualified_name(
+                &function_declaration.callable_reference.package_path,
+                &function_declaration.callable_reference.symbol_name,
+            ),
+            disassemble_function(function_declaration),
+        );
+    }
+    for struct_declaration in &program.struct_declarations {
+        let struct_name = qualified_name(
+            &struct_declaration.struct_reference.package_path,
+            &struct_declaration.struct_reference.symbol_name,
+        );
+        for method in &struct_declaration.methods {
+            function_text_by_name.insert(
+                format!("{struct_name}.{}", method.name),
+                disassemble_method(method),
+            );
+        }
+    }
+    function_text_by_name
+}
+
+fn type_shape_by_name(program: &ExecutableProgram) -> BTreeMap<String, TypeShape> {
+    let mut type_shape_by_name = BTreeMap::new();
+    for interface_declaration in &program.interface_declarations {
+        type_shape_by_name.insert(
+            qualified_name(
+                &interface_declaration.interface_reference.package_path,
+                &interface_declaration.interface_reference.symbol_name,
+            ),
+            interface_shape(interface_declaration),
+        );
+    }
+    for struct_declaration in &program.struct_declarations {
+        type_shape_by_name.insert(
+            qualified_name(
+                &struct_declaration.struct_reference.package_path,
+                &struct_declaration.struct_reference.symbol_name,
+            ),
+            struct_shape(struct_declaration),
+        );
+    }
+    type_shape_by_name
+}
+
+fn interface_shape(interface_declaration: &ExecutableInterfaceDeclaration) -> TypeShape {
+    TypeShape {
+        kind: "interface",
+        type_parameters: String::new(),
+        implemented_interfaces: BTreeSet::new(),
+        fields: Vec::new(),
+        method_signature_by_name: interface_declaration
+            .methods
+            .iter()
+            .map(|method| {
+                (
+                    method.name.clone(),
+                    method_signature_text(
+                        &method.name,
+                        method.self_mutable,
+                        &method.parameters,
+                        &method.return_type,
+                    ),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn struct_shape(struct_declaration: &ExecutableStructDeclaration) -> TypeShape {
+    TypeShape {
+        kind: "struct",
+        type_parameters: type_parameters_text(&struct_declaration.type_parameter_names, |_| None),
+        implemented_interfaces: struct_declaration
+            .implemented_interfaces
+            .iter()
+            .map(|interface_reference| {
+                qualified_name(
+                    &interface_reference.package_path,
+                    &interface_reference.symbol_name,
+                )
+            })
+            .collect(),
+        fields: struct_declaration
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), type_text(&field.type_reference)))
+            .collect(),
+        method_signature_by_name: struct_declaration
+            .methods
+            .iter()
+            .map(|method| {
+                (
+                    method.name.clone(),
+                    method_signature_text(
+                        &method.name,
+                        method.self_mutable,
+                        &method.parameters,
+                        &method.return_type,
+                    ),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn all_names<T>(
+    old_value_by_name: &BTreeMap<String, T>,
+    new_value_by_name: &BTreeMap<String, T>,
+) -> BTreeSet<String> {
+    old_value_by_name
+        .keys()
+        .chain(new_value_by_name.keys())
+        .cloned()
+        .collect()
+}
+
+fn change_kind<T: PartialEq>(old_value: Option<&T>, new_value: Option<&T>) -> Option<ChangeKind> {
+    match (old_value, new_value) {
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(old_value), Some(new_value)) if old_value != new_value => Some(ChangeKind::Changed),
+        _ => None,
+    }
+}
+
+fn type_shape_changes(old_type_shape: &TypeShape, new_type_shape: &TypeShape) -> Vec<String> {
+    if old_type_shape.kind != new_type_shape.kind {
+        return vec![format!(
+            "changed from {} to {}",
+            old_type_shape.kind, new_type_shape.kind
+        )];
+    }
+    let mut changes = Vec::new();
+    if old_type_shape.type_parameters != new_type_shape.type_parameters {
+        changes.push(format!(
+            "type parameters changed from '{}' to '{}'",
+            old_type_shape.type_parameters, new_type_shape.type_parameters
+        ));
+    }
+    for interface_name in old_type_shape
+        .implemented_interfaces
+        .difference(&new_type_shape.implemented_interfaces)
+    {
+        changes.push(format!("no longer implements {interface_name}"));
+    }
+    for interface_name in new_type_shape
+        .implemented_interfaces
+        .difference(&old_type_shape.implemented_interfaces)
+    {
+        changes.push(format!("now implements {interface_name}"));
+    }
+
+    let old_field_type_by_name = old_type_shape.fields.iter().cloned().collect();
+    let new_field_type_by_name = new_type_shape.fields.iter().cloned().collect();
+    push_member_changes(
+        &mut changes,
+        "field",
+        &old_field_type_by_name,
+        &new_field_type_by_name,
+    );
+    let old_field_names = old_type_shape.fields.iter().map(|(name, _)| name);
+    let new_field_names = new_type_shape.fields.iter().map(|(name, _)| name);
+    if old_field_type_by_name
+        .keys()
+        .eq(new_field_type_by_name.keys())
+        && !old_field_names.eq(new_field_names)
+    {
+        changes.push("fields reordered".to_string());
+    }
+    push_member_changes(
+        &mut changes,
+        "method",
+        &old_type_shape.method_signature_by_name,
+        &new_type_shape.method_signature_by_name,
+    );
+    changes
+}
+
+fn push_member_changes(
+    changes: &mut Vec<String>,
+    member_kind: &str,
+    old_description_by_name: &BTreeMap<String, String>,
+    new_description_by_name: &BTreeMap<String, String>,
+) {
+    for name in all_names(old_description_by_name, new_description_by_name) {
+        match (
+            old_description_by_name.get(&name),
+            new_description_by_name.get(&name),
+        ) {
+            (Some(_), None) => changes.push(format!("{member_kind} '{name}' removed")),
+            (None, Some(_)) => changes.push(format!("{member_kind} '{name}' added")),
+            (Some(old_description), Some(new_description))
+                if old_description != new_description =>
+            {
+                changes.push(format!(
+                    "{member_kind} '{name}' changed from {old_description} to {new_description}"
+                ));
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,671 @@
+use std::fmt::Write as _;
+
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__program_references::EnumVariantReference;
+
+use crate::{
+    ExecutableAssignTarget, ExecutableBinaryOperator, ExecutableCallTarget,
+    ExecutableConstantDeclaration, ExecutableExpression, ExecutableFunctionDeclaration,
+    ExecutableInterfaceDeclaration, ExecutableLayoutTable, ExecutableMatchArm,
+    ExecutableMatchLiteral, ExecutableMatchPattern, ExecutableMethodDeclaration,
+    ExecutableParameterDeclaration, ExecutableProgram, ExecutableStatement,
+    ExecutableStructDeclaration, ExecutableTypeReference, ExecutableUnaryOperator,
+};
+
+const INDENTATION: &str = "    ";
+
+// Renders a program as source-like text for reading and for golden tests, so
+// the output only changes when the program does. Declarations keep their
+// program order. Every binary and unary operation is parenthesized, and one
+// that can abort is followed by its operation description in braces.
+// Declarations and calls outside the root package show their package path, as
+// in `std/math::max`, and layouts are listed last.
+#[must_use]
+pub fn disassemble_program(program: &ExecutableProgram) -> String {
+    let mut disassembler = Disassembler::default();
+    disassembler.line(&format!(
+        "entrypoint {}",
+        qualified_name(
+            &program.entrypoint_callable_reference.package_path,
+            &program.entrypoint_callable_reference.symbol_name,
+        )
+    ));
+    for constant_declaration in &program.constant_declarations {
+        disassembler.blank_line();
+        disassembler.constant_declaration(constant_declaration);
+    }
+    for interface_declaration in &program.interface_declarations {
+        disassembler.blank_line();
+        disassembler.interface_declaration(interface_declaration);
+    }
+    for struct_declaration in &program.struct_declarations {
+        disassembler.blank_line();
+        disassembler.struct_declaration(struct_declaration);
+    }
+    for function_declaration in &program.function_declarations {
+        disassembler.blank_line();
+        disassembler.function_declaration(function_declaration);
+    }
+    disassembler.blank_line();
+    disassembler.layout_table(&program.layout_table);
+    disassembler.output
+}
+
+pub(crate) fn disassemble_function(function_declaration: &ExecutableFunctionDeclaration) -> String {
+    let mut disassembler = Disassembler::default();
+    disassembler.function_declaration(function_declaration);
+    disassembler.output
+}
+
+pub(crate) fn disassemble_method(method: &ExecutableMethodDeclaration) -> String {
+    let mut disassembler = Disassembler::default();
+    disassembler.method_declaration(method);
+    disassembler.output
+}
+
+#[derive(Default)]
+struct Disassembler {
+    output: String,
+    indentation: usize,
+}
+
+impl Disassembler {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indentation {
+            self.output.push_str(INDENTATION);
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn blank_line(&mut self) {
+        self.output.push('\n');
+    }
+
+    fn constant_declaration(&mut self, constant_declaration: &ExecutableConstantDeclaration) {
+        let initializer = self.expression(&constant_declaration.initializer);
+        self.line(&format!(
+            "constant {}: {} = {initializer}",
+            qualified_name(
+                &constant_declaration.constant_reference.package_path,
+                &constant_declaration.constant_reference.symbol_name,
+            ),
+            type_text(&constant_declaration.type_reference),
+        ));
+    }
+
+    fn interface_declaration(&mut self, interface_declaration: &ExecutableInterfaceDeclaration) {
+        self.line(&format!(
+            "interface {} {{",
+            qualified_name(
+                &interface_declaration.interface_reference.package_path,
+                &interface_declaration.interface_reference.symbol_name,
+            )
+        ));
+        self.indentation += 1;
+        for method in &interface_declaration.methods {
+            self.line(&format!(
+                "method {}",
+                method_signature_text(
+                    &method.name,
+                    method.self_mutable,
+                    &method.parameters,
+                    &method.return_type,
+                )
+            ));
+        }
+        self.indentation -= 1;
+        self.line("}");
+    }
+
+    fn struct_declaration(&mut self, struct_declaration: &ExecutableStructDeclaration) {
+        let mut header = format!(
+            "struct {}{}",
+            qualified_name(
+                &struct_declaration.struct_reference.package_path,
+                &struct_declaration.struct_reference.symbol_name,
+            ),
+            type_parameters_text(&struct_declaration.type_parameter_names, |_| None),
+        );
+        if !struct_declaration.implemented_interfaces.is_empty() {
+            let interface_names = struct_declaration
+                .implemented_interfaces
+                .iter()
+                .map(|interface_reference| {
+                    qualified_name(
+                        &interface_reference.package_path,
+                        &interface_reference.symbol_name,
+                    )
+                })
+                .collect::<Vec<_>>();
+            write!(header, " implements {}", interface_names.join(", ")).unwrap();
+        }
+        self.line(&format!("{header} {{"));
+        self.indentation += 1;
+        for field in &struct_declaration.fields {
+            self.line(&format!(
+                "field {}: {}",
+                field.name,
+                type_text(&field.type_reference)
+            ));
+        }
+        for method in &struct_declaration.methods {
+            self.method_declaration(method);
+        }
+        self.indentation -= 1;
+        self.line("}");
+    }
+
+    fn method_declaration(&mut self, method: &ExecutableMethodDeclaration) {
+        self.line(&format!(
+            "method {} {{",
+            method_signature_text(
+                &method.name,
+                method.self_mutable,
+                &method.parameters,
+                &method.return_type,
+            )
+        ));
+        self.statement_block(&method.statements);
+        self.line("}");
+    }
+
+    fn function_declaration(&mut self, function_declaration: &ExecutableFunctionDeclaration) {
+        let type_parameters =
+            type_parameters_text(&function_declaration.type_parameter_names, |name| {
+                function_declaration
+                    .type_parameter_constraint_interface_reference_by_name
+                    .get(name)
+                    .map(|interface_reference| {
+                        qualified_name(
+                            &interface_reference.package_path,
+                            &interface_reference.symbol_name,
+                        )
+                    })
+            });
+        self.line(&format!(
+            "function {}{type_parameters}({}) -> {} {{",
+            qualified_name(
+                &function_declaration.callable_reference.package_path,
+                &function_declaration.callable_reference.symbol_name,
+            ),
+            parameters_text(&function_declaration.parameters),
+            type_text(&function_declaration.return_type),
+        ));
+        self.statement_block(&function_declaration.statements);
+        self.line("}");
+    }
+
+    fn layout_table(&mut self, layout_table: &ExecutableLayoutTable) {
+        self.line("layouts {");
+        self.indentation += 1;
+        for struct_layout in &layout_table.struct_layouts {
+            self.line(&format!(
+                "struct {} size {} align {} {{",
+                qualified_name(
+                    &struct_layout.struct_reference.package_path,
+                    &struct_layout.struct_reference.symbol_name,
+                ),
+                struct_layout.size_bytes,
+                struct_layout.alignment_bytes,
+            ));
+            self.indentation += 1;
+            for field in &struct_layout.fields {
+                self.line(&format!(
+                    "field {} offset {} size {}",
+                    field.name, field.offset_bytes, field.size_bytes
+                ));
+            }
+            self.indentation -= 1;
+            self.line("}");
+        }
+        self.line(&format!(
+            "enum size {}",
+            layout_table.enum_layout.size_bytes
+        ));
+        let union_layout = &layout_table.union_layout;
+        self.line(&format!(
+            "union size {} tag offset {} payload offset {}",
+            union_layout.size_bytes,
+            union_layout.tag_offset_bytes,
+            union_layout.payload_offset_bytes,
+        ));
+        let list_layout = &layout_table.list_layout;
+        self.line(&format!(
+            "list header size {} length offset {} data pointer offset {} element stride {}",
+            list_layout.header_size_bytes,
+            list_layout.length_offset_bytes,
+            list_layout.data_pointer_offset_bytes,
+            list_layout.element_stride_bytes,
+        ));
+        self.indentation -= 1;
+        self.line("}");
+    }
+
+    fn statement_block(&mut self, statements: &[ExecutableStatement]) {
+        self.indentation += 1;
+        for statement in statements {
+            self.statement(statement);
+        }
+        self.indentation -= 1;
+    }
+
+    fn statement(&mut self, statement: &ExecutableStatement) {
+        match statement {
+            ExecutableStatement::Binding {
+                name,
+                mutable,
+                initializer,
+            } => {
+                let initializer = self.expression(initializer);
+                let keyword = if *mutable { "let mut" } else { "let" };
+                self.line(&format!("{keyword} {name} = {initializer}"));
+            }
+            ExecutableStatement::Assign { target, value } => {
+                let target = match target {
+                    ExecutableAssignTarget::Name { name } => name.clone(),
+                    ExecutableAssignTarget::Index { target, index } => {
+                        format!("{}[{}]", self.expression(target), self.expression(index))
+                    }
+                };
+                let value = self.expression(value);
+                self.line(&format!("{target} = {value}"));
+            }
+            ExecutableStatement::If {
+                condition,
+                then_statements,
+                else_statements,
+            } => {
+                let condition = self.expression(condition);
+                self.line(&format!("if {condition} {{"));
+                self.statement_block(then_statements);
+                if let Some(else_statements) = else_statements {
+                    self.line("} else {");
+                    self.statement_block(else_statements);
+                }
+                self.line("}");
+            }
+            ExecutableStatement::For {
+                condition,
+                body_statements,
+            } => {
+                match condition {
+                    Some(condition) => {
+                        let condition = self.expression(condition);
+                        self.line(&format!("for {condition} {{"));
+                    }
+                    None => self.line("for {"),
+                }
+                self.statement_block(body_statements);
+                self.line("}");
+            }
+            ExecutableStatement::Break => self.line("break"),
+            ExecutableStatement::Continue => self.line("continue"),
+            ExecutableStatement::Expression { expression } => {
+                let expression = self.expression(expression);
+                self.line(&expression);
+            }
+            ExecutableStatement::Return { value } => {
+                let value = self.expression(value);
+                self.line(&format!("return {value}"));
+            }
+        }
+    }
+
+    // Expressions that contain statements span several lines; their inner
+    // lines are indented one level past the line the expression starts on.
+    fn expression(&mut self, expression: &ExecutableExpression) -> String {
+        match expression {
+            ExecutableExpression::IntegerLiteral { value } => value.to_string(),
+            ExecutableExpression::BooleanLiteral { value } => value.to_string(),
+            ExecutableExpression::NilLiteral => "nil".to_string(),
+            ExecutableExpression::StringLiteral { value } => format!("{value:?}"),
+            ExecutableExpression::ListLiteral {
+                elements,
+                element_type,
+            } => format!(
+                "List[{}][{}]",
+                type_text(element_type),
+                self.expression_list(elements)
+            ),
+            ExecutableExpression::Identifier {
+                name,
+                constant_reference,
+                callable_reference,
+                ..
+            } => match (constant_reference, callable_reference) {
+                (Some(constant_reference), _) => format!(
+                    "constant {}",
+                    qualified_name(
+                        &constant_reference.package_path,
+                        &constant_reference.symbol_name
+                    )
+                ),
+                (None, Some(callable_reference)) => format!(
+                    "function {}",
+                    qualified_name(
+                        &callable_reference.package_path,
+                        &callable_reference.symbol_name
+                    )
+                ),
+                (None, None) => name.clone(),
+            },
+            ExecutableExpression::EnumVariantLiteral {
+                enum_variant_reference,
+                ..
+            } => format!(
+                "{}.{}",
+                enum_variant_reference.enum_name, enum_variant_reference.variant_name
+            ),
+            ExecutableExpression::EnumVariantName {
+                value,
+                enum_variant_references,
+            } => format!(
+                "variant_name({} of {})",
+                self.expression(value),
+                enum_variants_text(enum_variant_references)
+            ),
+            ExecutableExpression::EnumVariantFromString {
+                value,
+                enum_variant_references,
+                ..
+            } => format!(
+                "variant_from_string({} of {})",
+                self.expression(value),
+                enum_variants_text(enum_variant_references)
+            ),
+            ExecutableExpression::StructLiteral {
+                type_reference,
+                fields,
+                ..
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.name, self.expression(&field.value)))
+                    .collect::<Vec<_>>();
+                if fields.is_empty() {
+                    format!("{} {{}}", type_text(type_reference))
+                } else {
+                    format!("{} {{ {} }}", type_text(type_reference), fields.join(", "))
+                }
+            }
+            ExecutableExpression::FieldAccess { target, field, .. } => {
+                format!("{}.{field}", self.expression(target))
+            }
+            ExecutableExpression::IndexAccess { target, index } => {
+                format!("{}[{}]", self.expression(target), self.expression(index))
+            }
+            ExecutableExpression::Unary {
+                operator,
+                expression,
+                operation_description,
+            } => {
+                let operator = match operator {
+                    ExecutableUnaryOperator::Not => "not ",
+                    ExecutableUnaryOperator::Negate => "-",
+                };
+                format!(
+                    "({operator}{}){}",
+                    self.expression(expression),
+                    operation_description_text(operation_description.as_deref())
+                )
+            }
+            ExecutableExpression::Binary {
+                operator,
+                left,
+                right,
+                operation_description,
+            } => format!(
+                "({} {} {}){}",
+                self.expression(left),
+                binary_operator_text(*operator),
+                self.expression(right),
+                operation_description_text(operation_description.as_deref())
+            ),
+            ExecutableExpression::Call {
+                callee,
+                call_target,
+                arguments,
+                type_arguments,
+            } => {
+                let callee = match call_target {
+                    Some(ExecutableCallTarget::BuiltinFunction { function_name }) => {
+                        format!("builtin {function_name}")
+                    }
+                    Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }) => {
+                        qualified_name(
+                            &callable_reference.package_path,
+                            &callable_reference.symbol_name,
+                        )
+                    }
+                    None => self.expression(callee),
+                };
+                let type_arguments = if type_arguments.is_empty() {
+                    String::new()
+                } else {
+                    format!("[{}]", types_text(type_arguments))
+                };
+                format!(
+                    "{callee}{type_arguments}({})",
+                    self.expression_list(arguments)
+                )
+            }
+            ExecutableExpression::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => format!(
+                "if {} {{ {} }} else {{ {} }}",
+                self.expression(condition),
+                self.expression(then_branch),
+                self.expression(else_branch)
+            ),
+            ExecutableExpression::Block { statements, value } => {
+                let mut nested = self.nested();
+                for statement in statements {
+                    nested.statement(statement);
+                }
+                let value = nested.expression(value);
+                nested.line(&value);
+                self.close_nested(nested, "{")
+            }
+            ExecutableExpression::Match { target, arms } => {
+                let target = self.expression(target);
+                let mut nested = self.nested();
+                for arm in arms {
+                    nested.match_arm(arm);
+                }
+                self.close_nested(nested, &format!("match {target} {{"))
+            }
+            ExecutableExpression::Matches {
+                value,
+                type_reference,
+            } => format!(
+                "({} matches {})",
+                self.expression(value),
+                type_text(type_reference)
+            ),
+        }
+    }
+
+    fn match_arm(&mut self, arm: &ExecutableMatchArm) {
+        let pattern = match &arm.pattern {
+            ExecutableMatchPattern::Type { type_reference } => type_text(type_reference),
+            ExecutableMatchPattern::Binding {
+                binding_name,
+                type_reference,
+            } => format!("{binding_name}: {}", type_text(type_reference)),
+            ExecutableMatchPattern::Literal { value } => match value {
+                ExecutableMatchLiteral::Integer(value) => value.to_string(),
+                ExecutableMatchLiteral::String(value) => format!("{value:?}"),
+                ExecutableMatchLiteral::Boolean(value) => value.to_string(),
+            },
+        };
+        let guard = match &arm.guard {
+            Some(guard) => format!(" if {}", self.expression(guard)),
+            None => String::new(),
+        };
+        let value = self.expression(&arm.value);
+        self.line(&format!("{pattern}{guard} => {value}"));
+    }
+
+    fn nested(&self) -> Disassembler {
+        Disassembler {
+            output: String::new(),
+            indentation: self.indentation + 1,
+        }
+    }
+
+    fn close_nested(&self, nested: Disassembler, opening: &str) -> String {
+        let mut text = nested.output;
+        text.insert_str(0, &format!("{opening}\n"));
+        for _ in 0..self.indentation {
+            text.push_str(INDENTATION);
+        }
+        text.push('}');
+        text
+    }
+
+    fn expression_list(&mut self, expressions: &[ExecutableExpression]) -> String {
+        expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub(crate) fn qualified_name(package_path: &PackagePath, symbol_name: &SymbolName) -> String {
+    if package_path.is_empty() {
+        symbol_name.to_string()
+    } else {
+        format!("{package_path}::{symbol_name}")
+    }
+}
+
+pub(crate) fn type_parameters_text(
+    type_parameter_names: &[String],
+    constraint_by_name: impl Fn(&str) -> Option<String>,
+) -> String {
+    if type_parameter_names.is_empty() {
+        return String::new();
+    }
+    let type_parameters = type_parameter_names
+        .iter()
+        .map(|name| match constraint_by_name(name) {
+            Some(constraint) => format!("{name}: {constraint}"),
+            None => name.clone(),
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", type_parameters.join(", "))
+}
+
+fn parameters_text(parameters: &[ExecutableParameterDeclaration]) -> String {
+    parameters
+        .iter()
+        .map(|parameter| {
+            let mutable = if parameter.mutable { "mut " } else { "" };
+            format!(
+                "{mutable}{}: {}",
+                parameter.name,
+                type_text(&parameter.type_reference)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn method_signature_text(
+    name: &str,
+    self_mutable: bool,
+    parameters: &[ExecutableParameterDeclaration],
+    return_type: &ExecutableTypeReference,
+) -> String {
+    let receiver = if self_mutable { "mut self" } else { "self" };
+    let parameters = if parameters.is_empty() {
+        receiver.to_string()
+    } else {
+        format!("{receiver}, {}", parameters_text(parameters))
+    };
+    format!("{name}({parameters}) -> {}", type_text(return_type))
+}
+
+fn enum_variants_text(enum_variant_references: &[EnumVariantReference]) -> String {
+    enum_variant_references
+        .iter()
+        .map(|reference| format!("{}.{}", reference.enum_name, reference.variant_name))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn operation_description_text(operation_description: Option<&str>) -> String {
+    match operation_description {
+        Some(operation_description) => format!(" {{{operation_description}}}"),
+        None => String::new(),
+    }
+}
+
+fn binary_operator_text(operator: ExecutableBinaryOperator) -> &'static str {
+    match operator {
+        ExecutableBinaryOperator::Add => "+",
+        ExecutableBinaryOperator::Subtract => "-",
+        ExecutableBinaryOperator::Multiply => "*",
+        ExecutableBinaryOperator::Divide => "/",
+        ExecutableBinaryOperator::Modulo => "%",
+        ExecutableBinaryOperator::BitwiseAnd => "&",
+        ExecutableBinaryOperator::BitwiseOr => "|",
+        ExecutableBinaryOperator::BitwiseXor => "^",
+        ExecutableBinaryOperator::ShiftLeft => "<<",
+        ExecutableBinaryOperator::ShiftRight => ">>",
+        ExecutableBinaryOperator::EqualEqual => "==",
+        ExecutableBinaryOperator::NotEqual => "!=",
+        ExecutableBinaryOperator::LessThan => "<",
+        ExecutableBinaryOperator::LessThanOrEqual => "<=",
+        ExecutableBinaryOperator::GreaterThan => ">",
+        ExecutableBinaryOperator::GreaterThanOrEqual => ">=",
+        ExecutableBinaryOperator::And => "and",
+        ExecutableBinaryOperator::Or => "or",
+    }
+}
+
+fn types_text(type_references: &[ExecutableTypeReference]) -> String {
+    type_references
+        .iter()
+        .map(type_text)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn type_text(type_reference: &ExecutableTypeReference) -> String {
+    match type_reference {
+        ExecutableTypeReference::Int64 => "int64".to_string(),
+        ExecutableTypeReference::Boolean => "boolean".to_string(),
+        ExecutableTypeReference::String => "string".to_string(),
+        ExecutableTypeReference::Nil => "nil".to_string(),
+        ExecutableTypeReference::Never => "never".to_string(),
+        ExecutableTypeReference::List { element_type } => {
+            format!("List[{}]", type_text(element_type))
+        }
+        ExecutableTypeReference::Function {
+            parameter_types,
+            return_type,
+        } => format!(
+            "function({}) -> {}",
+            types_text(parameter_types),
+            type_text(return_type)
+        ),
+        ExecutableTypeReference::Union { members } => members
+            .iter()
+            .map(type_text)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        ExecutableTypeReference::TypeParameter { name }
+        | ExecutableTypeReference::NominalType { name, .. } => name.clone(),
+        ExecutableTypeReference::NominalTypeApplication {
+            base_name,
+            arguments,
+            ..
+        } => format!("{base_name}[{}]", types_text(arguments)),
+    }
+}
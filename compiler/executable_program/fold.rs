@@ -0,0 +1,288 @@
+use crate::{
+    ExecutableAssignTarget, ExecutableConstantDeclaration, ExecutableExpression,
+    ExecutableFunctionDeclaration, ExecutableMatchArm, ExecutableMethodDeclaration,
+    ExecutableProgram, ExecutableStatement, ExecutableStructDeclaration,
+    ExecutableStructLiteralField,
+};
+
+// Rebuilding traversal of executable statements and expressions. Every method
+// defaults to folding the node's children and reassembling it unchanged, so an
+// implementation overrides only the nodes it rewrites. Calling the matching
+// `fold_*_children` function first gives the rewrite already-folded operands.
+pub trait ExecutableFold {
+    fn fold_statements(
+        &mut self,
+        statements: Vec<ExecutableStatement>,
+    ) -> Vec<ExecutableStatement> {
+        fold_each_statement(self, statements)
+    }
+
+    fn fold_statement(&mut self, statement: ExecutableStatement) -> ExecutableStatement {
+        fold_statement_children(self, statement)
+    }
+
+    fn fold_expression(&mut self, expression: ExecutableExpression) -> ExecutableExpression {
+        fold_expression_children(self, expression)
+    }
+}
+
+// Constant initializers are folded first, then function bodies, then method
+// bodies, each in declaration order.
+pub fn fold_program<F: ExecutableFold + ?Sized>(
+    folder: &mut F,
+    program: ExecutableProgram,
+) -> ExecutableProgram {
+    let ExecutableProgram {
+        entrypoint_callable_reference,
+        constant_declarations,
+        interface_declarations,
+        struct_declarations,
+        function_declarations,
+        layout_table,
+    } = program;
+    let constant_declarations = constant_declarations
+        .into_iter()
+        .map(|constant_declaration| ExecutableConstantDeclaration {
+            initializer: folder.fold_expression(constant_declaration.initializer),
+            ..constant_declaration
+        })
+        .collect();
+    let function_declarations = function_declarations
+        .into_iter()
+        .map(|function_declaration| ExecutableFunctionDeclaration {
+            statements: folder.fold_statements(function_declaration.statements),
+            ..function_declaration
+        })
+        .collect();
+    let struct_declarations = struct_declarations
+        .into_iter()
+        .map(|struct_declaration| ExecutableStructDeclaration {
+            methods: struct_declaration
+                .methods
+                .into_iter()
+                .map(|method| ExecutableMethodDeclaration {
+                    statements: folder.fold_statements(method.statements),
+                    ..method
+                })
+                .collect(),
+            ..struct_declaration
+        })
+        .collect();
+    ExecutableProgram {
+        entrypoint_callable_reference,
+        constant_declarations,
+        interface_declarations,
+        struct_declarations,
+        function_declarations,
+        layout_table,
+    }
+}
+
+pub fn fold_each_statement<F: ExecutableFold + ?Sized>(
+    folder: &mut F,
+    statements: Vec<ExecutableStatement>,
+) -> Vec<ExecutableStatement> {
+    statements
+        .into_iter()
+        .map(|statement| folder.fold_statement(statement))
+        .collect()
+}
+
+pub fn fold_statement_children<F: ExecutableFold + ?Sized>(
+    folder: &mut F,
+    statement: ExecutableStatement,
+) -> ExecutableStatement {
+    match statement {
+        ExecutableStatement::Binding {
+            name,
+            mutable,
+            initializer,
+        } => ExecutableStatement::Binding {
+            name,
+            mutable,
+            initializer: folder.fold_expression(initializer),
+        },
+        ExecutableStatement::Assign { target, value } => {
+            let target = match target {
+                ExecutableAssignTarget::Name { name } => ExecutableAssignTarget::Name { name },
+                ExecutableAssignTarget::Index { target, index } => ExecutableAssignTarget::Index {
+                    target: fold_boxed_expression(folder, target),
+                    index: fold_boxed_expression(folder, index),
+                },
+            };
+            ExecutableStatement::Assign {
+                target,
+                value: folder.fold_expression(value),
+            }
+        }
+        ExecutableStatement::If {
+            condition,
+            then_statements,
+            else_statements,
+        } => ExecutableStatement::If {
+            condition: folder.fold_expression(condition),
+            then_statements: folder.fold_statements(then_statements),
+            else_statements: else_statements
+                .map(|else_statements| folder.fold_statements(else_statements)),
+        },
+        ExecutableStatement::For {
+            condition,
+            body_statements,
+        } => ExecutableStatement::For {
+            condition: condition.map(|condition| folder.fold_expression(condition)),
+            body_statements: folder.fold_statements(body_statements),
+        },
+        ExecutableStatement::Expression { expression } => ExecutableStatement::Expression {
+            expression: folder.fold_expression(expression),
+        },
+        ExecutableStatement::Return { value } => ExecutableStatement::Return {
+            value: folder.fold_expression(value),
+        },
+        ExecutableStatement::Break => ExecutableStatement::Break,
+        ExecutableStatement::Continue => ExecutableStatement::Continue,
+    }
+}
+
+pub fn fold_expression_children<F: ExecutableFold + ?Sized>(
+    folder: &mut F,
+    expression: ExecutableExpression,
+) -> ExecutableExpression {
+    match expression {
+        ExecutableExpression::IntegerLiteral { .. }
+        | ExecutableExpression::BooleanLiteral { .. }
+        | ExecutableExpression::NilLiteral
+        | ExecutableExpression::StringLiteral { .. }
+        | ExecutableExpression::Identifier { .. }
+        | ExecutableExpression::EnumVariantLiteral { .. } => expression,
+        ExecutableExpression::ListLiteral {
+            elements,
+            element_type,
+        } => ExecutableExpression::ListLiteral {
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_expression(element))
+                .collect(),
+            element_type,
+        },
+        ExecutableExpression::StructLiteral {
+            struct_reference,
+            type_reference,
+            fields,
+        } => ExecutableExpression::StructLiteral {
+            struct_reference,
+            type_reference,
+            fields: fields
+                .into_iter()
+                .map(|field| ExecutableStructLiteralField {
+                    name: field.name,
+                    value: folder.fold_expression(field.value),
+                })
+                .collect(),
+        },
+        ExecutableExpression::FieldAccess {
+            target,
+            field,
+            type_reference,
+        } => ExecutableExpression::FieldAccess {
+            target: fold_boxed_expression(folder, target),
+            field,
+            type_reference,
+        },
+        ExecutableExpression::IndexAccess { target, index } => ExecutableExpression::IndexAccess {
+            target: fold_boxed_expression(folder, target),
+            index: fold_boxed_expression(folder, index),
+        },
+        ExecutableExpression::Unary {
+            operator,
+            expression,
+            operation_description,
+        } => ExecutableExpression::Unary {
+            operator,
+            expression: fold_boxed_expression(folder, expression),
+            operation_description,
+        },
+        ExecutableExpression::Binary {
+            operator,
+            left,
+            right,
+            operation_description,
+        } => ExecutableExpression::Binary {
+            operator,
+            left: fold_boxed_expression(folder, left),
+            right: fold_boxed_expression(folder, right),
+            operation_description,
+        },
+        ExecutableExpression::Call {
+            callee,
+            call_target,
+            arguments,
+            type_arguments,
+        } => ExecutableExpression::Call {
+            callee: fold_boxed_expression(folder, callee),
+            call_target,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_expression(argument))
+                .collect(),
+            type_arguments,
+        },
+        ExecutableExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            type_reference,
+        } => ExecutableExpression::If {
+            condition: fold_boxed_expression(folder, condition),
+            then_branch: fold_boxed_expression(folder, then_branch),
+            else_branch: fold_boxed_expression(folder, else_branch),
+            type_reference,
+        },
+        ExecutableExpression::Block { statements, value } => ExecutableExpression::Block {
+            statements: folder.fold_statements(statements),
+            value: fold_boxed_expression(folder, value),
+        },
+        ExecutableExpression::Match { target, arms } => ExecutableExpression::Match {
+            target: fold_boxed_expression(folder, target),
+            arms: arms
+                .into_iter()
+                .map(|arm| ExecutableMatchArm {
+                    pattern: arm.pattern,
+                    guard: arm.guard.map(|guard| folder.fold_expression(guard)),
+                    value: folder.fold_expression(arm.value),
+                })
+                .collect(),
+        },
+        ExecutableExpression::EnumVariantName {
+            value,
+            enum_variant_references,
+        } => ExecutableExpression::EnumVariantName {
+            value: fold_boxed_expression(folder, value),
+            enum_variant_references,
+        },
+        ExecutableExpression::EnumVariantFromString {
+            value,
+            enum_variant_references,
+            type_reference,
+        } => ExecutableExpression::EnumVariantFromString {
+            value: fold_boxed_expression(folder, value),
+            enum_variant_references,
+            type_reference,
+        },
+        ExecutableExpression::Matches {
+            value,
+            type_reference,
+        } => ExecutableExpression::Matches {
+            value: fold_boxed_expression(folder, value),
+            type_reference,
+        },
+    }
+}
+
+// The box is reused rather than reallocated for the folded expression.
+fn fold_boxed_expression<F: ExecutableFold + ?Sized>(
+    folder: &mut F,
+    mut expression: Box<ExecutableExpression>,
+) -> Box<ExecutableExpression> {
+    *expression = folder.fold_expression(*expression);
+    expression
+}
@@ -1,41 +1,57 @@
+mod diff;
+mod disassembly;
+mod fold;
+mod validation;
+mod visit;
+
 use std::collections::BTreeMap;
 
+use compiler__program_references::{
+    CallableReference, ConstantReference, EnumVariantReference, InterfaceReference,
+    NominalTypeReference, StructReference,
+};
 use serde::{Deserialize, Serialize};
 
+pub use diff::{ChangeKind, FunctionChange, ProgramDiff, TypeChange, diff_programs};
+pub use disassembly::disassemble_program;
+pub use fold::{
+    ExecutableFold, fold_each_statement, fold_expression_children, fold_program,
+    fold_statement_children,
+};
+pub use validation::validate_program;
+pub use visit::{
+    ExecutableVisitor, visit_expression_children, visit_program, visit_statement_children,
+    visit_statements,
+};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableProgram {
-    pub entrypoint_callable_reference: ExecutableCallableReference,
+    pub entrypoint_callable_reference: CallableReference,
     pub constant_declarations: Vec<ExecutableConstantDeclaration>,
     pub interface_declarations: Vec<ExecutableInterfaceDeclaration>,
     pub struct_declarations: Vec<ExecutableStructDeclaration>,
     pub function_declarations: Vec<ExecutableFunctionDeclaration>,
+    pub layout_table: ExecutableLayoutTable,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableFunctionDeclaration {
     pub name: String,
-    pub callable_reference: ExecutableCallableReference,
+    pub callable_reference: CallableReference,
     pub type_parameter_names: Vec<String>,
-    pub type_parameter_constraint_interface_reference_by_name:
-        BTreeMap<String, ExecutableInterfaceReference>,
+    pub type_parameter_constraint_interface_reference_by_name: BTreeMap<String, InterfaceReference>,
     pub parameters: Vec<ExecutableParameterDeclaration>,
     pub return_type: ExecutableTypeReference,
     pub statements: Vec<ExecutableStatement>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ExecutableCallableReference {
-    pub package_path: String,
-    pub symbol_name: String,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ExecutableCallTarget {
     BuiltinFunction {
         function_name: String,
     },
     UserDefinedFunction {
-        callable_reference: ExecutableCallableReference,
+        callable_reference: CallableReference,
     },
 }
 
@@ -49,43 +65,13 @@ pub struct ExecutableParameterDeclaration {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableStructDeclaration {
     pub name: String,
-    pub struct_reference: ExecutableStructReference,
+    pub struct_reference: StructReference,
     pub type_parameter_names: Vec<String>,
-    pub implemented_interfaces: Vec<ExecutableInterfaceReference>,
+    pub implemented_interfaces: Vec<InterfaceReference>,
     pub fields: Vec<ExecutableStructFieldDeclaration>,
     pub methods: Vec<ExecutableMethodDeclaration>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ExecutableStructReference {
-    pub package_path: String,
-    pub symbol_name: String,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ExecutableInterfaceReference {
-    pub package_path: String,
-    pub symbol_name: String,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ExecutableNominalTypeReference {
-    pub package_path: String,
-    pub symbol_name: String,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ExecutableConstantReference {
-    pub package_path: String,
-    pub symbol_name: String,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct ExecutableEnumVariantReference {
-    pub enum_name: String,
-    pub variant_name: String,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableStructFieldDeclaration {
     pub name: String,
@@ -104,7 +90,7 @@ pub struct ExecutableMethodDeclaration {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableInterfaceDeclaration {
     pub name: String,
-    pub interface_reference: ExecutableInterfaceReference,
+    pub interface_reference: InterfaceReference,
     pub methods: Vec<ExecutableInterfaceMethodDeclaration>,
 }
 
@@ -119,7 +105,7 @@ pub struct ExecutableInterfaceMethodDeclaration {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableConstantDeclaration {
     pub name: String,
-    pub constant_reference: ExecutableConstantReference,
+    pub constant_reference: ConstantReference,
     pub type_reference: ExecutableTypeReference,
     pub initializer: ExecutableExpression,
 }
@@ -145,12 +131,12 @@ pub enum ExecutableTypeReference {
         name: String,
     },
     NominalTypeApplication {
-        base_nominal_type_reference: Option<ExecutableNominalTypeReference>,
+        base_nominal_type_reference: Option<NominalTypeReference>,
         base_name: String,
         arguments: Vec<ExecutableTypeReference>,
     },
     NominalType {
-        nominal_type_reference: Option<ExecutableNominalTypeReference>,
+        nominal_type_reference: Option<NominalTypeReference>,
         name: String,
     },
 }
@@ -214,35 +200,52 @@ pub enum ExecutableExpression {
     },
     Identifier {
         name: String,
-        constant_reference: Option<ExecutableConstantReference>,
-        callable_reference: Option<ExecutableCallableReference>,
+        constant_reference: Option<ConstantReference>,
+        callable_reference: Option<CallableReference>,
         type_reference: ExecutableTypeReference,
     },
     EnumVariantLiteral {
-        enum_variant_reference: ExecutableEnumVariantReference,
+        enum_variant_reference: EnumVariantReference,
+        type_reference: ExecutableTypeReference,
+    },
+    // The name of an enum value, which is one of the listed variants.
+    EnumVariantName {
+        value: Box<ExecutableExpression>,
+        enum_variant_references: Vec<EnumVariantReference>,
+    },
+    // The listed variant named by a string, or nil when none is, typed as the
+    // union of the variants and nil.
+    EnumVariantFromString {
+        value: Box<ExecutableExpression>,
+        enum_variant_references: Vec<EnumVariantReference>,
         type_reference: ExecutableTypeReference,
     },
     StructLiteral {
-        struct_reference: ExecutableStructReference,
+        struct_reference: StructReference,
         type_reference: ExecutableTypeReference,
         fields: Vec<ExecutableStructLiteralField>,
     },
     FieldAccess {
         target: Box<ExecutableExpression>,
         field: String,
+        type_reference: Option<ExecutableTypeReference>,
     },
     IndexAccess {
         target: Box<ExecutableExpression>,
         index: Box<ExecutableExpression>,
     },
+    // `operation_description` is set on int64 operations that can overflow or
+    // divide by zero, and names the operation when it aborts.
     Unary {
         operator: ExecutableUnaryOperator,
         expression: Box<ExecutableExpression>,
+        operation_description: Option<String>,
     },
     Binary {
         operator: ExecutableBinaryOperator,
         left: Box<ExecutableExpression>,
         right: Box<ExecutableExpression>,
+        operation_description: Option<String>,
     },
     Call {
         callee: Box<ExecutableExpression>,
@@ -250,6 +253,18 @@ pub enum ExecutableExpression {
         arguments: Vec<ExecutableExpression>,
         type_arguments: Vec<ExecutableTypeReference>,
     },
+    // `type_reference` joins the types of both branches.
+    If {
+        condition: Box<ExecutableExpression>,
+        then_branch: Box<ExecutableExpression>,
+        else_branch: Box<ExecutableExpression>,
+        type_reference: ExecutableTypeReference,
+    },
+    // The statements run in their own scope, then the value is evaluated.
+    Block {
+        statements: Vec<ExecutableStatement>,
+        value: Box<ExecutableExpression>,
+    },
     Match {
         target: Box<ExecutableExpression>,
         arms: Vec<ExecutableMatchArm>,
@@ -263,6 +278,7 @@ pub enum ExecutableExpression {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutableMatchArm {
     pub pattern: ExecutableMatchPattern,
+    pub guard: Option<ExecutableExpression>,
     pub value: ExecutableExpression,
 }
 
@@ -275,6 +291,27 @@ pub enum ExecutableMatchPattern {
         binding_name: String,
         type_reference: ExecutableTypeReference,
     },
+    Literal {
+        value: ExecutableMatchLiteral,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutableMatchLiteral {
+    Integer(i64),
+    String(String),
+    Boolean(bool),
+}
+
+impl ExecutableMatchLiteral {
+    #[must_use]
+    pub fn type_reference(&self) -> ExecutableTypeReference {
+        match self {
+            ExecutableMatchLiteral::Integer(_) => ExecutableTypeReference::Int64,
+            ExecutableMatchLiteral::String(_) => ExecutableTypeReference::String,
+            ExecutableMatchLiteral::Boolean(_) => ExecutableTypeReference::Boolean,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -284,6 +321,11 @@ pub enum ExecutableBinaryOperator {
     Multiply,
     Divide,
     Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     EqualEqual,
     NotEqual,
     LessThan,
@@ -305,3 +347,46 @@ pub struct ExecutableStructLiteralField {
     pub name: String,
     pub value: ExecutableExpression,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableLayoutTable {
+    pub struct_layouts: Vec<ExecutableStructLayout>,
+    pub enum_layout: ExecutableEnumLayout,
+    pub union_layout: ExecutableUnionLayout,
+    pub list_layout: ExecutableListLayout,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableStructLayout {
+    pub struct_reference: StructReference,
+    pub size_bytes: u32,
+    pub alignment_bytes: u32,
+    pub fields: Vec<ExecutableFieldLayout>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableFieldLayout {
+    pub name: String,
+    pub offset_bytes: u32,
+    pub size_bytes: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableEnumLayout {
+    pub size_bytes: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableUnionLayout {
+    pub size_bytes: u32,
+    pub tag_offset_bytes: u32,
+    pub payload_offset_bytes: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutableListLayout {
+    pub header_size_bytes: u32,
+    pub length_offset_bytes: u32,
+    pub data_pointer_offset_bytes: u32,
+    pub element_stride_bytes: u32,
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use compiler__executable_program::{
+    ExecutableAssignTarget, ExecutableBinaryOperator, ExecutableEnumLayout, ExecutableExpression,
+    ExecutableFold, ExecutableFunctionDeclaration, ExecutableLayoutTable, ExecutableListLayout,
+    ExecutableProgram, ExecutableStatement, ExecutableTypeReference, ExecutableUnionLayout,
+    ExecutableVisitor, disassemble_program, fold_each_statement, fold_expression_children,
+    visit_expression_children, visit_statement_children, visit_statements,
+};
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__program_references::CallableReference;
+
+fn identifier(name: &str) -> ExecutableExpression {
+    ExecutableExpression::Identifier {
+        name: name.to_string(),
+        constant_reference: None,
+        callable_reference: None,
+        type_reference: ExecutableTypeReference::Int64,
+    }
+}
+
+fn integer(value: i64) -> ExecutableExpression {
+    ExecutableExpression::IntegerLiteral { value }
+}
+
+// for i < 3 { if i == 1 { x = i + 2 } else { break } }
+fn nested_statements() -> Vec<ExecutableStatement> {
+    vec![ExecutableStatement::For {
+        condition: Some(ExecutableExpression::Binary {
+            operator: ExecutableBinaryOperator::LessThan,
+            left: Box::new(identifier("i")),
+            right: Box::new(integer(3)),
+            operation_description: None,
+        }),
+        body_statements: vec![ExecutableStatement::If {
+            condition: ExecutableExpression::Binary {
+                operator: ExecutableBinaryOperator::EqualEqual,
+                left: Box::new(identifier("i")),
+                right: Box::new(integer(1)),
+                operation_description: None,
+            },
+            then_statements: vec![ExecutableStatement::Assign {
+                target: ExecutableAssignTarget::Name {
+                    name: "x".to_string(),
+                },
+                value: ExecutableExpression::Binary {
+                    operator: ExecutableBinaryOperator::Add,
+                    left: Box::new(identifier("i")),
+                    right: Box::new(integer(2)),
+                    operation_description: None,
+                },
+            }],
+            else_statements: Some(vec![ExecutableStatement::Break]),
+        }],
+    }]
+}
+
+#[derive(Default)]
+struct NodeCounter {
+    statement_count: usize,
+    integer_literals: Vec<i64>,
+}
+
+impl ExecutableVisitor for NodeCounter {
+    fn visit_statement(&mut self, statement: &ExecutableStatement) {
+        self.statement_count += 1;
+        visit_statement_children(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &ExecutableExpression) {
+        if let ExecutableExpression::IntegerLiteral { value } = expression {
+            self.integer_literals.push(*value);
+        }
+        visit_expression_children(self, expression);
+    }
+}
+
+struct IntegerDoubler;
+
+impl ExecutableFold for IntegerDoubler {
+    fn fold_expression(&mut self, expression: ExecutableExpression) -> ExecutableExpression {
+        match fold_expression_children(self, expression) {
+            ExecutableExpression::IntegerLiteral { value } => integer(value * 2),
+            expression => expression,
+        }
+    }
+}
+
+struct BreakRemover;
+
+impl ExecutableFold for BreakRemover {
+    fn fold_statements(
+        &mut self,
+        statements: Vec<ExecutableStatement>,
+    ) -> Vec<ExecutableStatement> {
+        fold_each_statement(self, statements)
+            .into_iter()
+            .filter(|statement| !matches!(statement, ExecutableStatement::Break))
+            .collect()
+    }
+}
+
+#[test]
+fn visitor_reaches_nested_statements_and_expressions() {
+    let mut counter = NodeCounter::default();
+    visit_statements(&mut counter, &nested_statements());
+    assert_eq!(counter.statement_count, 4);
+    assert_eq!(counter.integer_literals, [3, 1, 2]);
+}
+
+#[test]
+fn fold_rewrites_expressions_inside_nested_statements() {
+    let statements = fold_each_statement(&mut IntegerDoubler, nested_statements());
+    let mut counter = NodeCounter::default();
+    visit_statements(&mut counter, &statements);
+    assert_eq!(counter.statement_count, 4);
+    assert_eq!(counter.integer_literals, [6, 2, 4]);
+}
+
+#[test]
+fn fold_rewrites_nested_statement_lists() {
+    let statements = BreakRemover.fold_statements(nested_statements());
+    let mut counter = NodeCounter::default();
+    visit_statements(&mut counter, &statements);
+    assert_eq!(counter.statement_count, 3);
+}
+
+#[test]
+fn disassembly_renders_nested_statements_and_layouts() {
+    let callable_reference = CallableReference {
+        package_path: PackagePath::new("tools"),
+        symbol_name: SymbolName::new("count"),
+    };
+    let program = ExecutableProgram {
+        entrypoint_callable_reference: callable_reference.clone(),
+        constant_declarations: Vec::new(),
+        interface_declarations: Vec::new(),
+        struct_declarations: Vec::new(),
+        function_declarations: vec![ExecutableFunctionDeclaration {
+            name: "count".to_string(),
+            callable_reference,
+            type_parameter_names: Vec::new(),
+            type_parameter_constraint_interface_reference_by_name: BTreeMap::new(),
+            parameters: Vec::new(),
+            return_type: ExecutableTypeReference::Nil,
+            statements: nested_statements(),
+        }],
+        layout_table: ExecutableLayoutTable {
+            struct_layouts: Vec::new(),
+            enum_layout: ExecutableEnumLayout { size_bytes: 8 },
+            union_layout: ExecutableUnionLayout {
+                size_bytes: 16,
+                tag_offset_bytes: 0,
+                payload_offset_bytes: 8,
+            },
+            list_layout: ExecutableListLayout {
+                header_size_bytes: 16,
+                length_offset_bytes: 0,
+                data_pointer_offset_bytes: 8,
+                element_stride_bytes: 8,
+            },
+        },
+    };
+    assert_eq!(
+        disassemble_program(&program),
+        "\
+entrypoint tools::count
+
+function tools::count() -> nil {
+    for (i < 3) {
+        if (i == 1) {
+            x = (i + 2)
+        } else {
+            break
+        }
+    }
+}
+
+layouts {
+    enum size 8
+    union size 16 tag offset 0 payload offset 8
+    list header size 16 length offset 0 data pointer offset 8 element stride 8
+}
+"
+    );
+}
@@ -0,0 +1,505 @@
+use std::collections::BTreeSet;
+
+use compiler__interned_names::{PackagePath, SymbolName};
+
+use crate::visit::{ExecutableVisitor, visit_expression_children, visit_statements};
+use crate::{
+    ExecutableCallTarget, ExecutableExpression, ExecutableFunctionDeclaration,
+    ExecutableMatchPattern, ExecutableProgram, ExecutableStructDeclaration,
+    ExecutableTypeReference,
+};
+
+// Checks that a program is internally consistent before it is executed:
+// references resolve, calls pass as many arguments as their callee takes,
+// type references are well-formed, and no symbol is declared twice. Lowering
+// always produces valid programs; this guards against programs that were
+// edited or produced by a different compiler version. Every violation is
+// reported, in declaration order, and an empty result means the program is
+// valid.
+#[must_use]
+pub fn validate_program(program: &ExecutableProgram) -> Vec<String> {
+    let mut validator = ProgramValidator {
+        program,
+        type_parameter_names: Vec::new(),
+        violations: Vec::new(),
+    };
+    validator.validate_declarations();
+    validator.validate_bodies();
+    validator.violations
+}
+
+struct ProgramValidator<'program> {
+    program: &'program ExecutableProgram,
+    type_parameter_names: Vec<String>,
+    violations: Vec<String>,
+}
+
+impl<'program> ProgramValidator<'program> {
+    fn validate_declarations(&mut self) {
+        let program = self.program;
+
+        let mut constant_names = BTreeSet::new();
+        for constant_declaration in &program.constant_declarations {
+            let reference = &constant_declaration.constant_reference;
+            let constant_name = qualified_name(&reference.package_path, &reference.symbol_name);
+            if !constant_names.insert(constant_name.clone()) {
+                self.violate(format!("duplicate constant '{constant_name}'"));
+            }
+            self.validate_type_reference(&constant_declaration.type_reference);
+        }
+
+        let mut interface_names = BTreeSet::new();
+        for interface_declaration in &program.interface_declarations {
+            let reference = &interface_declaration.interface_reference;
+            let interface_name = qualified_name(&reference.package_path, &reference.symbol_name);
+            if !interface_names.insert(interface_name.clone()) {
+                self.violate(format!("duplicate interface '{interface_name}'"));
+            }
+            for method in &interface_declaration.methods {
+                for parameter in &method.parameters {
+                    self.validate_type_reference(&parameter.type_reference);
+                }
+                self.validate_type_reference(&method.return_type);
+            }
+        }
+
+        let mut struct_names = BTreeSet::new();
+        for struct_declaration in &program.struct_declarations {
+            let reference = &struct_declaration.struct_reference;
+            let struct_name = qualified_name(&reference.package_path, &reference.symbol_name);
+            if !struct_names.insert(struct_name.clone()) {
+                self.violate(format!("duplicate struct '{struct_name}'"));
+            }
+            if !program
+                .layout_table
+                .struct_layouts
+                .iter()
+                .any(|struct_layout| struct_layout.struct_reference == *reference)
+            {
+                self.violate(format!("struct '{struct_name}' has no layout"));
+            }
+            for interface_reference in &struct_declaration.implemented_interfaces {
+                self.validate_interface_reference(
+                    &interface_reference.package_path,
+                    &interface_reference.symbol_name,
+                );
+            }
+            self.type_parameter_names
+                .clone_from(&struct_declaration.type_parameter_names);
+            let mut field_names = BTreeSet::new();
+            for field in &struct_declaration.fields {
+                if !field_names.insert(field.name.as_str()) {
+                    self.violate(format!(
+                        "duplicate field '{}.{}'",
+                        struct_declaration.name, field.name
+                    ));
+                }
+                self.validate_type_reference(&field.type_reference);
+            }
+            let mut method_names = BTreeSet::new();
+            for method in &struct_declaration.methods {
+                if !method_names.insert(method.name.as_str()) {
+                    self.violate(format!(
+                        "duplicate method '{}.{}'",
+                        struct_declaration.name, method.name
+                    ));
+                }
+                for parameter in &method.parameters {
+                    self.validate_type_reference(&parameter.type_reference);
+                }
+                self.validate_type_reference(&method.return_type);
+            }
+            self.type_parameter_names.clear();
+        }
+
+        let mut function_names = BTreeSet::new();
+        for function_declaration in &program.function_declarations {
+            let reference = &function_declaration.callable_reference;
+            let function_name = qualified_name(&reference.package_path, &reference.symbol_name);
+            if !function_names.insert(function_name.clone()) {
+                self.violate(format!("duplicate function '{function_name}'"));
+            }
+            for interface_reference in function_declaration
+                .type_parameter_constraint_interface_reference_by_name
+                .values()
+            {
+                self.validate_interface_reference(
+                    &interface_reference.package_path,
+                    &interface_reference.symbol_name,
+                );
+            }
+            self.type_parameter_names
+                .clone_from(&function_declaration.type_parameter_names);
+            for parameter in &function_declaration.parameters {
+                self.validate_type_reference(&parameter.type_reference);
+            }
+            self.validate_type_reference(&function_declaration.return_type);
+            self.type_parameter_names.clear();
+        }
+
+        let entrypoint = &program.entrypoint_callable_reference;
+        if self
+            .function_declaration(&entrypoint.package_path, &entrypoint.symbol_name)
+            .is_none()
+        {
+            self.violate(format!(
+                "entrypoint '{}' is not a declared function",
+                qualified_name(&entrypoint.package_path, &entrypoint.symbol_name)
+            ));
+        }
+    }
+
+    fn validate_bodies(&mut self) {
+        let program = self.program;
+        for constant_declaration in &program.constant_declarations {
+            self.visit_expression(&constant_declaration.initializer);
+        }
+        for function_declaration in &program.function_declarations {
+            self.type_parameter_names
+                .clone_from(&function_declaration.type_parameter_names);
+            visit_statements(self, &function_declaration.statements);
+        }
+        for struct_declaration in &program.struct_declarations {
+            self.type_parameter_names
+                .clone_from(&struct_declaration.type_parameter_names);
+            for method in &struct_declaration.methods {
+                visit_statements(self, &method.statements);
+            }
+        }
+        self.type_parameter_names.clear();
+    }
+
+    fn validate_type_reference(&mut self, type_reference: &ExecutableTypeReference) {
+        match type_reference {
+            ExecutableTypeReference::Int64
+            | ExecutableTypeReference::Boolean
+            | ExecutableTypeReference::String
+            | ExecutableTypeReference::Nil
+            | ExecutableTypeReference::Never => {}
+            ExecutableTypeReference::List { element_type } => {
+                self.validate_type_reference(element_type);
+            }
+            ExecutableTypeReference::Function {
+                parameter_types,
+                return_type,
+            } => {
+                for parameter_type in parameter_types {
+                    self.validate_type_reference(parameter_type);
+                }
+                self.validate_type_reference(return_type);
+            }
+            ExecutableTypeReference::Union { members } => {
+                for member in members {
+                    self.validate_type_reference(member);
+                }
+            }
+            ExecutableTypeReference::TypeParameter { name } => {
+                if !self.type_parameter_names.contains(name) {
+                    self.violate(format!("unknown type parameter '{name}'"));
+                }
+            }
+            ExecutableTypeReference::NominalType {
+                nominal_type_reference,
+                name,
+            } => {
+                // Enum variant types are named `Enum.Variant` and have no
+                // declaration to resolve against.
+                if let Some(nominal_type_reference) = nominal_type_reference
+                    && !name.contains('.')
+                    && self
+                        .struct_declaration(
+                            &nominal_type_reference.package_path,
+                            &nominal_type_reference.symbol_name,
+                        )
+                        .is_none()
+                    && !self.interface_exists(
+                        &nominal_type_reference.package_path,
+                        &nominal_type_reference.symbol_name,
+                    )
+                {
+                    self.violate(format!("unknown type '{name}'"));
+                }
+            }
+            ExecutableTypeReference::NominalTypeApplication {
+                base_nominal_type_reference,
+                base_name,
+                arguments,
+            } => {
+                if let Some(base_nominal_type_reference) = base_nominal_type_reference {
+                    match self.struct_declaration(
+                        &base_nominal_type_reference.package_path,
+                        &base_nominal_type_reference.symbol_name,
+                    ) {
+                        Some(struct_declaration)
+                            if struct_declaration.type_parameter_names.len() != arguments.len() =>
+                        {
+                            self.violate(format!(
+                                "type '{base_name}' takes {} type argument(s), got {}",
+                                struct_declaration.type_parameter_names.len(),
+                                arguments.len()
+                            ));
+                        }
+                        Some(_) => {}
+                        None => self.violate(format!("unknown type '{base_name}'")),
+                    }
+                }
+                for argument in arguments {
+                    self.validate_type_reference(argument);
+                }
+            }
+        }
+    }
+
+    fn validate_interface_reference(
+        &mut self,
+        package_path: &PackagePath,
+        symbol_name: &SymbolName,
+    ) {
+        if !self.interface_exists(package_path, symbol_name) {
+            self.violate(format!(
+                "unknown interface '{}'",
+                qualified_name(package_path, symbol_name)
+            ));
+        }
+    }
+
+    fn validate_call(
+        &mut self,
+        call_target: Option<&ExecutableCallTarget>,
+        arguments: &[ExecutableExpression],
+        type_arguments: &[ExecutableTypeReference],
+    ) {
+        for type_argument in type_arguments {
+            self.validate_type_reference(type_argument);
+        }
+        let Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }) = call_target
+        else {
+            return;
+        };
+        let function_name = qualified_name(
+            &callable_reference.package_path,
+            &callable_reference.symbol_name,
+        );
+        let Some(function_declaration) = self.function_declaration(
+            &callable_reference.package_path,
+            &callable_reference.symbol_name,
+        ) else {
+            self.violate(format!("call to unknown function '{function_name}'"));
+            return;
+        };
+        if function_declaration.parameters.len() != arguments.len() {
+            self.violate(format!(
+                "call to '{function_name}' passes {} argument(s), but it takes {}",
+                arguments.len(),
+                function_declaration.parameters.len()
+            ));
+        }
+        if !type_arguments.is_empty()
+            && function_declaration.type_parameter_names.len() != type_arguments.len()
+        {
+            self.violate(format!(
+                "call to '{function_name}' passes {} type argument(s), but it takes {}",
+                type_arguments.len(),
+                function_declaration.type_parameter_names.len()
+            ));
+        }
+    }
+
+    fn validate_struct_literal(
+        &mut self,
+        struct_declaration: &ExecutableStructDeclaration,
+        provided_field_names: &[&str],
+    ) {
+        let mut seen_field_names = BTreeSet::new();
+        for field_name in provided_field_names {
+            if !seen_field_names.insert(*field_name) {
+                self.violate(format!(
+                    "struct literal sets field '{}.{field_name}' more than once",
+                    struct_declaration.name
+                ));
+            } else if !struct_declaration
+                .fields
+                .iter()
+                .any(|field| field.name == *field_name)
+            {
+                self.violate(format!(
+                    "struct literal sets unknown field '{}.{field_name}'",
+                    struct_declaration.name
+                ));
+            }
+        }
+        for field in &struct_declaration.fields {
+            if !seen_field_names.contains(field.name.as_str()) {
+                self.violate(format!(
+                    "struct literal is missing field '{}.{}'",
+                    struct_declaration.name, field.name
+                ));
+            }
+        }
+    }
+
+    fn function_declaration(
+        &self,
+        package_path: &PackagePath,
+        symbol_name: &SymbolName,
+    ) -> Option<&'program ExecutableFunctionDeclaration> {
+        self.program
+            .function_declarations
+            .iter()
+            .find(|function_declaration| {
+                function_declaration.callable_reference.package_path == *package_path
+                    && function_declaration.callable_reference.symbol_name == *symbol_name
+            })
+    }
+
+    fn struct_declaration(
+        &self,
+        package_path: &PackagePath,
+        symbol_name: &SymbolName,
+    ) -> Option<&'program ExecutableStructDeclaration> {
+        self.program
+            .struct_declarations
+            .iter()
+            .find(|struct_declaration| {
+                struct_declaration.struct_reference.package_path == *package_path
+                    && struct_declaration.struct_reference.symbol_name == *symbol_name
+            })
+    }
+
+    fn interface_exists(&self, package_path: &PackagePath, symbol_name: &SymbolName) -> bool {
+        self.program
+            .interface_declarations
+            .iter()
+            .any(|interface_declaration| {
+                interface_declaration.interface_reference.package_path == *package_path
+                    && interface_declaration.interface_reference.symbol_name == *symbol_name
+            })
+    }
+
+    fn violate(&mut self, violation: String) {
+        self.violations.push(violation);
+    }
+}
+
+impl ExecutableVisitor for ProgramValidator<'_> {
+    fn visit_expression(&mut self, expression: &ExecutableExpression) {
+        match expression {
+            ExecutableExpression::IntegerLiteral { .. }
+            | ExecutableExpression::BooleanLiteral { .. }
+            | ExecutableExpression::NilLiteral
+            | ExecutableExpression::StringLiteral { .. }
+            | ExecutableExpression::IndexAccess { .. }
+            | ExecutableExpression::Unary { .. }
+            | ExecutableExpression::Binary { .. }
+            | ExecutableExpression::Block { .. }
+            | ExecutableExpression::EnumVariantName { .. } => {}
+            ExecutableExpression::ListLiteral { element_type, .. } => {
+                self.validate_type_reference(element_type);
+            }
+            ExecutableExpression::Identifier {
+                constant_reference,
+                callable_reference,
+                type_reference,
+                ..
+            } => {
+                if let Some(constant_reference) = constant_reference
+                    && !self
+                        .program
+                        .constant_declarations
+                        .iter()
+                        .any(|constant_declaration| {
+                            constant_declaration.constant_reference == *constant_reference
+                        })
+                {
+                    self.violate(format!(
+                        "reference to unknown constant '{}'",
+                        qualified_name(
+                            &constant_reference.package_path,
+                            &constant_reference.symbol_name
+                        )
+                    ));
+                }
+                if let Some(callable_reference) = callable_reference
+                    && self
+                        .function_declaration(
+                            &callable_reference.package_path,
+                            &callable_reference.symbol_name,
+                        )
+                        .is_none()
+                {
+                    self.violate(format!(
+                        "reference to unknown function '{}'",
+                        qualified_name(
+                            &callable_reference.package_path,
+                            &callable_reference.symbol_name
+                        )
+                    ));
+                }
+                self.validate_type_reference(type_reference);
+            }
+            ExecutableExpression::EnumVariantLiteral { type_reference, .. }
+            | ExecutableExpression::EnumVariantFromString { type_reference, .. }
+            | ExecutableExpression::Matches { type_reference, .. }
+            | ExecutableExpression::If { type_reference, .. } => {
+                self.validate_type_reference(type_reference);
+            }
+            ExecutableExpression::StructLiteral {
+                struct_reference,
+                type_reference,
+                fields,
+            } => {
+                match self.struct_declaration(
+                    &struct_reference.package_path,
+                    &struct_reference.symbol_name,
+                ) {
+                    Some(struct_declaration) => {
+                        let provided_field_names = fields
+                            .iter()
+                            .map(|field| field.name.as_str())
+                            .collect::<Vec<_>>();
+                        self.validate_struct_literal(struct_declaration, &provided_field_names);
+                    }
+                    None => self.violate(format!(
+                        "struct literal of unknown struct '{}'",
+                        qualified_name(
+                            &struct_reference.package_path,
+                            &struct_reference.symbol_name
+                        )
+                    )),
+                }
+                self.validate_type_reference(type_reference);
+            }
+            ExecutableExpression::FieldAccess { type_reference, .. } => {
+                if let Some(type_reference) = type_reference {
+                    self.validate_type_reference(type_reference);
+                }
+            }
+            ExecutableExpression::Call {
+                call_target,
+                arguments,
+                type_arguments,
+                ..
+            } => self.validate_call(call_target.as_ref(), arguments, type_arguments),
+            ExecutableExpression::Match { arms, .. } => {
+                for arm in arms {
+                    match &arm.pattern {
+                        ExecutableMatchPattern::Type { type_reference }
+                        | ExecutableMatchPattern::Binding { type_reference, .. } => {
+                            self.validate_type_reference(type_reference);
+                        }
+                        ExecutableMatchPattern::Literal { .. } => {}
+                    }
+                }
+            }
+        }
+        visit_expression_children(self, expression);
+    }
+}
+
+fn qualified_name(package_path: &PackagePath, symbol_name: &SymbolName) -> String {
+    if package_path.is_empty() {
+        symbol_name.to_string()
+    } else {
+        format!("{package_path}::{symbol_name}")
+    }
+}
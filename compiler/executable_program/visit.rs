@@ -0,0 +1,147 @@
+use crate::{ExecutableAssignTarget, ExecutableExpression, ExecutableProgram, ExecutableStatement};
+
+// Read-only traversal of executable statements and expressions. Every method
+// defaults to visiting the node's children, so an implementation overrides
+// only the nodes it inspects and calls the matching `visit_*_children`
+// function to keep descending.
+pub trait ExecutableVisitor {
+    fn visit_statement(&mut self, statement: &ExecutableStatement) {
+        visit_statement_children(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &ExecutableExpression) {
+        visit_expression_children(self, expression);
+    }
+}
+
+// Constant initializers are visited first, then function bodies, then method
+// bodies, each in declaration order.
+pub fn visit_program<V: ExecutableVisitor + ?Sized>(visitor: &mut V, program: &ExecutableProgram) {
+    for constant_declaration in &program.constant_declarations {
+        visitor.visit_expression(&constant_declaration.initializer);
+    }
+    for function_declaration in &program.function_declarations {
+        visit_statements(visitor, &function_declaration.statements);
+    }
+    for struct_declaration in &program.struct_declarations {
+        for method in &struct_declaration.methods {
+            visit_statements(visitor, &method.statements);
+        }
+    }
+}
+
+pub fn visit_statements<V: ExecutableVisitor + ?Sized>(
+    visitor: &mut V,
+    statements: &[ExecutableStatement],
+) {
+    for statement in statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn visit_statement_children<V: ExecutableVisitor + ?Sized>(
+    visitor: &mut V,
+    statement: &ExecutableStatement,
+) {
+    match statement {
+        ExecutableStatement::Binding { initializer, .. } => visitor.visit_expression(initializer),
+        ExecutableStatement::Assign { target, value } => {
+            if let ExecutableAssignTarget::Index { target, index } = target {
+                visitor.visit_expression(target);
+                visitor.visit_expression(index);
+            }
+            visitor.visit_expression(value);
+        }
+        ExecutableStatement::If {
+            condition,
+            then_statements,
+            else_statements,
+        } => {
+            visitor.visit_expression(condition);
+            visit_statements(visitor, then_statements);
+            if let Some(else_statements) = else_statements {
+                visit_statements(visitor, else_statements);
+            }
+        }
+        ExecutableStatement::For {
+            condition,
+            body_statements,
+        } => {
+            if let Some(condition) = condition {
+                visitor.visit_expression(condition);
+            }
+            visit_statements(visitor, body_statements);
+        }
+        ExecutableStatement::Expression { expression } => visitor.visit_expression(expression),
+        ExecutableStatement::Return { value } => visitor.visit_expression(value),
+        ExecutableStatement::Break | ExecutableStatement::Continue => {}
+    }
+}
+
+pub fn visit_expression_children<V: ExecutableVisitor + ?Sized>(
+    visitor: &mut V,
+    expression: &ExecutableExpression,
+) {
+    match expression {
+        ExecutableExpression::IntegerLiteral { .. }
+        | ExecutableExpression::BooleanLiteral { .. }
+        | ExecutableExpression::NilLiteral
+        | ExecutableExpression::StringLiteral { .. }
+        | ExecutableExpression::Identifier { .. }
+        | ExecutableExpression::EnumVariantLiteral { .. } => {}
+        ExecutableExpression::ListLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        ExecutableExpression::StructLiteral { fields, .. } => {
+            for field in fields {
+                visitor.visit_expression(&field.value);
+            }
+        }
+        ExecutableExpression::FieldAccess { target, .. } => visitor.visit_expression(target),
+        ExecutableExpression::IndexAccess { target, index } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(index);
+        }
+        ExecutableExpression::Unary { expression, .. } => visitor.visit_expression(expression),
+        ExecutableExpression::Binary { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        ExecutableExpression::Call {
+            callee, arguments, ..
+        } => {
+            visitor.visit_expression(callee);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        ExecutableExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(then_branch);
+            visitor.visit_expression(else_branch);
+        }
+        ExecutableExpression::Block { statements, value } => {
+            visit_statements(visitor, statements);
+            visitor.visit_expression(value);
+        }
+        ExecutableExpression::Match { target, arms } => {
+            visitor.visit_expression(target);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    visitor.visit_expression(guard);
+                }
+                visitor.visit_expression(&arm.value);
+            }
+        }
+        ExecutableExpression::EnumVariantName { value, .. }
+        | ExecutableExpression::EnumVariantFromString { value, .. }
+        | ExecutableExpression::Matches { value, .. } => visitor.visit_expression(value),
+    }
+}
@@ -44,7 +44,7 @@ pub fn build_exports(
                         path: file.path.to_path_buf(),
                         diagnostic: PhaseDiagnostic::new(
                             format!("duplicate exported symbol '{name}'"),
-                            member.span.clone(),
+                            member.span,
                         ),
                     });
                     continue;
@@ -54,7 +54,7 @@ pub fn build_exports(
                         path: file.path.to_path_buf(),
                         diagnostic: PhaseDiagnostic::new(
                             format!("exported symbol '{name}' is not declared in this package"),
-                            member.span.clone(),
+                            member.span,
                         ),
                     });
                     continue;
@@ -64,7 +64,7 @@ pub fn build_exports(
                         path: file.path.to_path_buf(),
                         diagnostic: PhaseDiagnostic::new(
                             format!("exported symbol '{name}' is not declared in this package"),
-                            member.span.clone(),
+                            member.span,
                         ),
                     });
                     continue;
@@ -74,7 +74,7 @@ pub fn build_exports(
                         path: file.path.to_path_buf(),
                         diagnostic: PhaseDiagnostic::new(
                             format!("exported symbol '{name}' must be declared visible"),
-                            member.span.clone(),
+                            member.span,
                         ),
                     });
                 }
@@ -0,0 +1,235 @@
+use compiler__fix_edits::{TextEdit, apply_text_edits};
+use compiler__parsing::parse_file;
+use compiler__source::{FileRole, leading_comment_start, trailing_comment_end};
+use compiler__syntax::{
+    SyntaxDeclaration, SyntaxMemberVisibility, SyntaxParsedFile, SyntaxStructMemberItem,
+    SyntaxTypeDeclaration, SyntaxTypeDeclarationKind,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeclarationGroup {
+    Constants,
+    Types,
+    Functions,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MethodOrder {
+    Alphabetical,
+    PublicFirst,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileOrganizationOptions {
+    pub declaration_group_order: Vec<DeclarationGroup>,
+    pub method_order: MethodOrder,
+}
+
+impl Default for FileOrganizationOptions {
+    fn default() -> Self {
+        Self {
+            declaration_group_order: vec![
+                DeclarationGroup::Constants,
+                DeclarationGroup::Types,
+                DeclarationGroup::Functions,
+            ],
+            method_order: MethodOrder::Alphabetical,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileOrganizationError {
+    SourceDoesNotParse,
+    DeclarationSharesLine,
+    OrganizedSourceDoesNotMatch,
+}
+
+struct MovableDeclaration {
+    group: DeclarationGroup,
+    start: usize,
+    end: usize,
+    methods: Vec<MovableMethod>,
+}
+
+struct MovableMethod {
+    name: String,
+    visibility: SyntaxMemberVisibility,
+    start: usize,
+    end: usize,
+}
+
+// Declarations are reordered by swapping their source text between the slots
+// that constants, types, and functions already occupy, so imports, exports,
+// tests, and the whitespace between declarations stay where they are. Groups
+// missing from the configured order go last, and declarations keep their
+// relative order within a group.
+pub fn organize_file_text_edits(
+    source_text: &str,
+    role: FileRole,
+    options: &FileOrganizationOptions,
+) -> Result<Vec<TextEdit>, FileOrganizationError> {
+    let parsed_file = parse_file(source_text, role);
+    if !parsed_file.diagnostics.is_empty() {
+        return Err(FileOrganizationError::SourceDoesNotParse);
+    }
+    let declarations = movable_declarations(source_text, &parsed_file.value)?;
+    let mut organized_declarations = declarations
+        .iter()
+        .map(|declaration| {
+            (
+                declaration.group,
+                organized_declaration_text(source_text, declaration, options.method_order),
+            )
+        })
+        .collect::<Vec<_>>();
+    organized_declarations.sort_by_key(|(group, _)| {
+        options
+            .declaration_group_order
+            .iter()
+            .position(|ordered_group| ordered_group == group)
+            .unwrap_or(options.declaration_group_order.len())
+    });
+
+    let text_edits = declarations
+        .iter()
+        .zip(&organized_declarations)
+        .filter(|(slot, (_, organized_text))| source_text[slot.start..slot.end] != *organized_text)
+        .map(|(slot, (_, organized_text))| TextEdit {
+            start_byte_offset: slot.start,
+            end_byte_offset: slot.end,
+            replacement_text: organized_text.clone(),
+        })
+        .collect::<Vec<_>>();
+    if text_edits.is_empty() {
+        return Ok(text_edits);
+    }
+
+    let organized_texts = organized_declarations
+        .iter()
+        .map(|(_, organized_text)| organized_text.as_str())
+        .collect::<Vec<_>>();
+    check_organized_source(source_text, role, &text_edits, &organized_texts)?;
+    Ok(text_edits)
+}
+
+// The organized source must re-parse into exactly the planned declaration
+// texts in the planned order, so a bad slot boundary can never drop,
+// duplicate, or reformat code.
+fn check_organized_source(
+    source_text: &str,
+    role: FileRole,
+    text_edits: &[TextEdit],
+    organized_texts: &[&str],
+) -> Result<(), FileOrganizationError> {
+    let organized_source_text = apply_text_edits(source_text, text_edits)
+        .map_err(|_| FileOrganizationError::OrganizedSourceDoesNotMatch)?;
+    let reparsed_file = parse_file(&organized_source_text, role);
+    if !reparsed_file.diagnostics.is_empty() {
+        return Err(FileOrganizationError::OrganizedSourceDoesNotMatch);
+    }
+    let reparsed_texts = movable_declarations(&organized_source_text, &reparsed_file.value)?
+        .iter()
+        .map(|declaration| &organized_source_text[declaration.start..declaration.end])
+        .collect::<Vec<_>>();
+    if reparsed_texts != organized_texts {
+        return Err(FileOrganizationError::OrganizedSourceDoesNotMatch);
+    }
+    Ok(())
+}
+
+fn movable_declarations(
+    source_text: &str,
+    file: &SyntaxParsedFile,
+) -> Result<Vec<MovableDeclaration>, FileOrganizationError> {
+    let mut declarations = Vec::new();
+    for declaration in file.top_level_declarations() {
+        let (group, span, methods) = match declaration {
+            SyntaxDeclaration::Constant(constant_declaration) => (
+                DeclarationGroup::Constants,
+                &constant_declaration.span,
+                Vec::new(),
+            ),
+            SyntaxDeclaration::Type(type_declaration) => (
+                DeclarationGroup::Types,
+                &type_declaration.span,
+                movable_methods(source_text, type_declaration)?,
+            ),
+            SyntaxDeclaration::Function(function_declaration) => (
+                DeclarationGroup::Functions,
+                &function_declaration.span,
+                Vec::new(),
+            ),
+            SyntaxDeclaration::Import(_)
+            | SyntaxDeclaration::Exports(_)
+            | SyntaxDeclaration::Package(_)
+            | SyntaxDeclaration::Group(_)
+            | SyntaxDeclaration::Test(_)
+            | SyntaxDeclaration::StaticAssert(_) => continue,
+        };
+        declarations.push(MovableDeclaration {
+            group,
+            start: leading_comment_start(source_text, span.start)
+                .ok_or(FileOrganizationError::DeclarationSharesLine)?,
+            end: trailing_comment_end(source_text, span.end),
+            methods,
+        });
+    }
+    Ok(declarations)
+}
+
+// A lone method never moves, so it may share a line with the struct header.
+fn movable_methods(
+    source_text: &str,
+    type_declaration: &SyntaxTypeDeclaration,
+) -> Result<Vec<MovableMethod>, FileOrganizationError> {
+    let SyntaxTypeDeclarationKind::Struct { items } = &type_declaration.kind else {
+        return Ok(Vec::new());
+    };
+    let methods = items
+        .iter()
+        .filter_map(|item| match item {
+            SyntaxStructMemberItem::Method(method) => Some(method),
+            SyntaxStructMemberItem::DocComment(_) | SyntaxStructMemberItem::Field(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if methods.len() < 2 {
+        return Ok(Vec::new());
+    }
+    methods
+        .into_iter()
+        .map(|method| {
+            Ok(MovableMethod {
+                name: method.name.clone(),
+                visibility: method.visibility,
+                start: leading_comment_start(source_text, method.span.start)
+                    .ok_or(FileOrganizationError::DeclarationSharesLine)?,
+                end: method.span.end,
+            })
+        })
+        .collect()
+}
+
+fn organized_declaration_text(
+    source_text: &str,
+    declaration: &MovableDeclaration,
+    method_order: MethodOrder,
+) -> String {
+    let mut ordered_methods = declaration.methods.iter().collect::<Vec<_>>();
+    match method_order {
+        MethodOrder::Alphabetical => {
+            ordered_methods.sort_by(|left, right| left.name.cmp(&right.name));
+        }
+        MethodOrder::PublicFirst => ordered_methods
+            .sort_by_key(|method| method.visibility == SyntaxMemberVisibility::Private),
+    }
+    let mut organized_text = String::new();
+    let mut copied_until = declaration.start;
+    for (slot, method) in declaration.methods.iter().zip(ordered_methods) {
+        organized_text.push_str(&source_text[copied_until..slot.start]);
+        organized_text.push_str(&source_text[method.start..method.end]);
+        copied_until = slot.end;
+    }
+    organized_text.push_str(&source_text[copied_until..declaration.end]);
+    organized_text
+}
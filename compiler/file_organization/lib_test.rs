@@ -0,0 +1,173 @@
+use compiler__file_organization::{
+    DeclarationGroup, FileOrganizationError, FileOrganizationOptions, MethodOrder,
+    organize_file_text_edits,
+};
+use compiler__fix_edits::apply_text_edits;
+use compiler__source::FileRole;
+
+fn organize(source_text: &str, options: &FileOrganizationOptions) -> String {
+    let text_edits = organize_file_text_edits(source_text, FileRole::Library, options)
+        .expect("source should organize");
+    apply_text_edits(source_text, &text_edits).expect("edits should apply")
+}
+
+#[test]
+fn organize_file_groups_declarations_and_keeps_comments_attached() {
+    let source_text = "\
+import std/math { max }
+
+function run() -> int64 {
+    return LIMIT
+}
+
+/// The largest accepted value.
+LIMIT: int64 := 10 // inclusive
+
+type Point :: struct { x: int64 }
+";
+    let output = organize(source_text, &FileOrganizationOptions::default());
+    assert_eq!(
+        output,
+        "\
+import std/math { max }
+
+/// The largest accepted value.
+LIMIT: int64 := 10 // inclusive
+
+type Point :: struct { x: int64 }
+
+function run() -> int64 {
+    return LIMIT
+}
+"
+    );
+}
+
+#[test]
+fn organize_file_sorts_methods_alphabetically_around_fields() {
+    let source_text = "\
+type Counter :: struct {
+    count: int64,
+
+    function reset(self) -> Counter {
+        return Counter { count: 0 }
+    },
+
+    // Reads the current count.
+    public function current(self) -> int64 {
+        return self.count
+    },
+}
+";
+    let output = organize(source_text, &FileOrganizationOptions::default());
+    assert_eq!(
+        output,
+        "\
+type Counter :: struct {
+    count: int64,
+
+    // Reads the current count.
+    public function current(self) -> int64 {
+        return self.count
+    },
+
+    function reset(self) -> Counter {
+        return Counter { count: 0 }
+    },
+}
+"
+    );
+}
+
+#[test]
+fn organize_file_follows_configured_convention() {
+    let source_text = "\
+type Counter :: struct {
+    function b(self) -> int64 {
+        return 1
+    },
+    public function a(self) -> int64 {
+        return 2
+    },
+    function c(self) -> int64 {
+        return 3
+    },
+}
+
+function run() -> int64 {
+    return 0
+}
+";
+    let options = FileOrganizationOptions {
+        declaration_group_order: vec![DeclarationGroup::Functions],
+        method_order: MethodOrder::PublicFirst,
+    };
+    let output = organize(source_text, &options);
+    assert_eq!(
+        output,
+        "\
+function run() -> int64 {
+    return 0
+}
+
+type Counter :: struct {
+    public function a(self) -> int64 {
+        return 2
+    },
+    function b(self) -> int64 {
+        return 1
+    },
+    function c(self) -> int64 {
+        return 3
+    },
+}
+"
+    );
+}
+
+#[test]
+fn organize_file_moves_deprecated_attributes_with_their_declarations() {
+    let source_text = "\
+function run() -> int64 {
+    return LIMIT
+}
+
+/// The largest accepted value.
+@deprecated(\"use MAXIMUM\")
+LIMIT: int64 := 10
+";
+    let output = organize(source_text, &FileOrganizationOptions::default());
+    assert_eq!(
+        output,
+        "\
+/// The largest accepted value.
+@deprecated(\"use MAXIMUM\")
+LIMIT: int64 := 10
+
+function run() -> int64 {
+    return LIMIT
+}
+"
+    );
+}
+
+#[test]
+fn organize_file_returns_no_edits_for_organized_source() {
+    let source_text = "LIMIT: int64 := 10\n\nfunction run() -> int64 {\n    return LIMIT\n}\n";
+    let text_edits = organize_file_text_edits(
+        source_text,
+        FileRole::Library,
+        &FileOrganizationOptions::default(),
+    );
+    assert_eq!(text_edits, Ok(Vec::new()));
+}
+
+#[test]
+fn organize_file_rejects_source_with_parse_errors() {
+    let text_edits = organize_file_text_edits(
+        "function run( -> int64 {\n}\n",
+        FileRole::Library,
+        &FileOrganizationOptions::default(),
+    );
+    assert_eq!(text_edits, Err(FileOrganizationError::SourceDoesNotParse));
+}
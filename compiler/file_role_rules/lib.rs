@@ -1,11 +1,18 @@
+use std::path::Path;
+
 use compiler__diagnostics::PhaseDiagnostic;
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
-use compiler__source::{FileRole, Span};
+use compiler__source::{FileRole, KnownAttribute, LanguageVersion, Span};
 use compiler__syntax::{
-    SyntaxDeclaration, SyntaxFunctionDeclaration, SyntaxParsedFile, SyntaxTopLevelVisibility,
-    SyntaxTypeName,
+    SyntaxDeclaration, SyntaxFunctionDeclaration, SyntaxPackageDeclaration,
+    SyntaxPackageFieldValue, SyntaxParsedFile, SyntaxTopLevelVisibility, SyntaxTypeName,
 };
 
+// Like every other rule the compiler enforces, the limits are fixed rather
+// than configured per workspace.
+const MAXIMUM_FILE_LINE_COUNT: usize = 1000;
+const MAXIMUM_ENTRYPOINT_HELPER_COUNT: usize = 10;
+
 /// Run file-role policy checks.
 ///
 /// Every check that requires knowledge of file role belongs here.
@@ -16,22 +23,36 @@ use compiler__syntax::{
 /// - binary contract (exactly one `main`, no type parameters, no parameters,
 ///   returns `nil`)
 ///
+/// The same goes for `@test` and `@bench` functions, which are only allowed in
+/// `.test.copp` files and share the `main` contract apart from the count.
+///
+/// The file name decides the role, so a name that reads as more than one role
+/// (`tool.bin.test.copp`, `PACKAGE.test.copp`) is rejected here too.
+///
 /// Keeping role-conditional rules in one owner pass avoids brittle cross-pass
 /// suppression ("emit in one pass, silence in another") and keeps diagnostic
 /// intent deterministic.
+///
+/// Limit violations only describe how a file is organized, so they never keep
+/// later phases from checking it.
 #[must_use]
-pub fn check_file(file: &SyntaxParsedFile) -> PhaseOutput<()> {
+pub fn check_file(file: &SyntaxParsedFile, path: &Path, source_text: &str) -> PhaseOutput<()> {
     let mut diagnostics = Vec::new();
+    check_file_name_roles(path, &mut diagnostics);
     check_exports_declaration_roles(file, &mut diagnostics);
+    check_package_declaration_roles(file, &mut diagnostics);
     check_test_declaration_roles(file, &mut diagnostics);
     check_visible_declaration_roles(file, &mut diagnostics);
     check_main_function_roles(file, &mut diagnostics);
+    check_test_function_roles(file, &mut diagnostics);
 
     let status = if diagnostics.is_empty() {
         PhaseStatus::Ok
     } else {
         PhaseStatus::PreventsDownstreamExecution
     };
+    check_file_line_count(source_text, &mut diagnostics);
+    check_entrypoint_helper_count(file, &mut diagnostics);
 
     PhaseOutput {
         value: (),
@@ -41,13 +62,45 @@ pub fn check_file(file: &SyntaxParsedFile) -> PhaseOutput<()> {
     }
 }
 
+// Only the last suffix picks the role, so a role marker earlier in the name
+// would otherwise be silently ignored. Reported at the start of the file,
+// since the name has no span of its own.
+fn check_file_name_roles(path: &Path, diagnostics: &mut Vec<PhaseDiagnostic>) {
+    let Some(file_name) = path.file_name().and_then(|file_name| file_name.to_str()) else {
+        return;
+    };
+    if file_name == "PACKAGE.copp" {
+        return;
+    }
+    let Some(stem) = file_name.strip_suffix(".copp") else {
+        return;
+    };
+    let mut segments = stem.split('.').collect::<Vec<_>>();
+    if matches!(segments.last(), Some(&("bin" | "test"))) && segments.len() > 1 {
+        segments.pop();
+    }
+    let has_another_role = segments[0] == "PACKAGE"
+        || segments[1..]
+            .iter()
+            .any(|segment| matches!(*segment, "bin" | "test"));
+    if has_another_role {
+        diagnostics.push(PhaseDiagnostic::new(
+            format!("file name '{file_name}' names more than one file role"),
+            Span::new(0, 0),
+        ));
+    }
+}
+
 fn check_exports_declaration_roles(
     file: &SyntaxParsedFile,
     diagnostics: &mut Vec<PhaseDiagnostic>,
 ) {
     for declaration in file.top_level_declarations() {
         if file.role == FileRole::PackageManifest
-            && !matches!(declaration, SyntaxDeclaration::Exports(_))
+            && !matches!(
+                declaration,
+                SyntaxDeclaration::Exports(_) | SyntaxDeclaration::Package(_)
+            )
         {
             if matches!(
                 declaration,
@@ -60,8 +113,8 @@ fn check_exports_declaration_roles(
                 continue;
             }
             diagnostics.push(PhaseDiagnostic::new(
-                "PACKAGE.copp may only contain exports declarations",
-                declaration_span(declaration).clone(),
+                "PACKAGE.copp may only contain package and exports declarations",
+                *declaration_span(declaration),
             ));
             continue;
         }
@@ -71,12 +124,159 @@ fn check_exports_declaration_roles(
         {
             diagnostics.push(PhaseDiagnostic::new(
                 "exports declarations are only allowed in PACKAGE.copp",
-                declaration_span(declaration).clone(),
+                *declaration_span(declaration),
+            ));
+        }
+    }
+}
+
+fn check_package_declaration_roles(
+    file: &SyntaxParsedFile,
+    diagnostics: &mut Vec<PhaseDiagnostic>,
+) {
+    let package_declarations = file
+        .top_level_declarations()
+        .filter_map(|declaration| match declaration {
+            SyntaxDeclaration::Package(package_declaration) => Some(package_declaration),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if file.role != FileRole::PackageManifest {
+        for package_declaration in package_declarations {
+            diagnostics.push(PhaseDiagnostic::new(
+                "package declarations are only allowed in PACKAGE.copp",
+                package_declaration.span,
+            ));
+        }
+        return;
+    }
+    for (index, package_declaration) in package_declarations.into_iter().enumerate() {
+        if index > 0 {
+            diagnostics.push(PhaseDiagnostic::new(
+                "PACKAGE.copp may contain at most one package declaration",
+                package_declaration.span,
+            ));
+            continue;
+        }
+        check_package_declaration_fields(package_declaration, diagnostics);
+    }
+}
+
+// Package metadata names the package's executables and is read by tooling
+// outside the compiler, so its values are checked here rather than left to
+// each consumer.
+fn check_package_declaration_fields(
+    package_declaration: &SyntaxPackageDeclaration,
+    diagnostics: &mut Vec<PhaseDiagnostic>,
+) {
+    let mut seen_field_names = Vec::new();
+    for field in &package_declaration.fields {
+        if seen_field_names.contains(&field.name.as_str()) {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("duplicate package field '{}'", field.name),
+                field.name_span,
+            ));
+            continue;
+        }
+        seen_field_names.push(field.name.as_str());
+        match (field.name.as_str(), &field.value) {
+            ("name", SyntaxPackageFieldValue::String { value, span }) => {
+                if !is_valid_package_name(value) {
+                    diagnostics.push(PhaseDiagnostic::new(
+                        "package name must start with a lowercase letter and contain only lowercase letters, digits, and underscores",
+                        *span,
+                    ));
+                }
+            }
+            ("version", SyntaxPackageFieldValue::String { value, span }) => {
+                if !is_valid_package_version(value) {
+                    diagnostics.push(PhaseDiagnostic::new(
+                        "package version must have the form MAJOR.MINOR.PATCH",
+                        *span,
+                    ));
+                }
+            }
+            ("description", SyntaxPackageFieldValue::String { .. }) => {}
+            ("language_version", SyntaxPackageFieldValue::String { value, span }) => {
+                if LanguageVersion::from_name(value).is_none() {
+                    let supported_versions =
+                        LanguageVersion::ALL.map(LanguageVersion::name).join(", ");
+                    diagnostics.push(PhaseDiagnostic::new(
+                        format!(
+                            "unknown language version '{value}'; supported versions are {supported_versions}"
+                        ),
+                        *span,
+                    ));
+                }
+            }
+            ("entrypoints", SyntaxPackageFieldValue::List { items, .. }) => {
+                check_package_entrypoints(items, diagnostics);
+            }
+            (
+                "name" | "version" | "description" | "language_version",
+                SyntaxPackageFieldValue::List { span, .. },
+            ) => {
+                diagnostics.push(PhaseDiagnostic::new(
+                    format!("package field '{}' must be a string", field.name),
+                    *span,
+                ));
+            }
+            ("entrypoints", SyntaxPackageFieldValue::String { span, .. }) => {
+                diagnostics.push(PhaseDiagnostic::new(
+                    "package field 'entrypoints' must be a list of strings",
+                    *span,
+                ));
+            }
+            _ => diagnostics.push(PhaseDiagnostic::new(
+                format!("unknown package field '{}'", field.name),
+                field.name_span,
+            )),
+        }
+    }
+}
+
+fn check_package_entrypoints(items: &[(String, Span)], diagnostics: &mut Vec<PhaseDiagnostic>) {
+    let mut seen_entrypoints = Vec::new();
+    for (entrypoint, span) in items {
+        let is_valid_entrypoint = entrypoint
+            .strip_suffix(".bin.copp")
+            .is_some_and(|stem| !stem.is_empty() && !stem.ends_with('/'))
+            && entrypoint
+                .split('/')
+                .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+        if !is_valid_entrypoint {
+            diagnostics.push(PhaseDiagnostic::new(
+                "package entrypoint must be the path of a .bin.copp file relative to the package",
+                *span,
+            ));
+        } else if seen_entrypoints.contains(&entrypoint.as_str()) {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("duplicate package entrypoint '{entrypoint}'"),
+                *span,
             ));
+        } else {
+            seen_entrypoints.push(entrypoint.as_str());
         }
     }
 }
 
+fn is_valid_package_name(name: &str) -> bool {
+    name.starts_with(|character: char| character.is_ascii_lowercase())
+        && name.chars().all(|character| {
+            character.is_ascii_lowercase() || character.is_ascii_digit() || character == '_'
+        })
+}
+
+fn is_valid_package_version(version: &str) -> bool {
+    let parts = version.split('.').collect::<Vec<_>>();
+    parts.len() == 3
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part.chars().all(|character| character.is_ascii_digit())
+                && (*part == "0" || !part.starts_with('0'))
+        })
+}
+
 fn check_test_declaration_roles(file: &SyntaxParsedFile, diagnostics: &mut Vec<PhaseDiagnostic>) {
     if file.role == FileRole::Test {
         return;
@@ -85,11 +285,11 @@ fn check_test_declaration_roles(file: &SyntaxParsedFile, diagnostics: &mut Vec<P
         match declaration {
             SyntaxDeclaration::Group(group_declaration) => diagnostics.push(PhaseDiagnostic::new(
                 "group declarations are only allowed in .test.copp files",
-                group_declaration.span.clone(),
+                group_declaration.span,
             )),
             SyntaxDeclaration::Test(test_declaration) => diagnostics.push(PhaseDiagnostic::new(
                 "test declarations are only allowed in .test.copp files",
-                test_declaration.span.clone(),
+                test_declaration.span,
             )),
             _ => {}
         }
@@ -122,24 +322,18 @@ fn check_visible_declaration_roles(
         match declaration {
             SyntaxDeclaration::Type(type_declaration) => {
                 if type_declaration.visibility == SyntaxTopLevelVisibility::Visible {
-                    diagnostics.push(PhaseDiagnostic::new(message, type_declaration.span.clone()));
+                    diagnostics.push(PhaseDiagnostic::new(message, type_declaration.span));
                 }
             }
             SyntaxDeclaration::Constant(constant_declaration)
                 if constant_declaration.visibility == SyntaxTopLevelVisibility::Visible =>
             {
-                diagnostics.push(PhaseDiagnostic::new(
-                    message,
-                    constant_declaration.span.clone(),
-                ));
+                diagnostics.push(PhaseDiagnostic::new(message, constant_declaration.span));
             }
             SyntaxDeclaration::Function(function_declaration)
                 if function_declaration.visibility == SyntaxTopLevelVisibility::Visible =>
             {
-                diagnostics.push(PhaseDiagnostic::new(
-                    message,
-                    function_declaration.span.clone(),
-                ));
+                diagnostics.push(PhaseDiagnostic::new(message, function_declaration.span));
             }
             _ => {}
         }
@@ -168,7 +362,7 @@ fn check_main_function_roles(file: &SyntaxParsedFile, diagnostics: &mut Vec<Phas
                 for function in main_functions {
                     diagnostics.push(PhaseDiagnostic::new(
                         ".bin.copp files must declare exactly one main function",
-                        function.name_span.clone(),
+                        function.name_span,
                     ));
                 }
                 return;
@@ -179,13 +373,71 @@ fn check_main_function_roles(file: &SyntaxParsedFile, diagnostics: &mut Vec<Phas
             for function in main_functions {
                 diagnostics.push(PhaseDiagnostic::new(
                     "main is only allowed in .bin.copp files",
-                    function.name_span.clone(),
+                    function.name_span,
                 ));
             }
         }
     }
 }
 
+// Reported on the first line past the limit, where the file would be split.
+fn check_file_line_count(source_text: &str, diagnostics: &mut Vec<PhaseDiagnostic>) {
+    let limit = MAXIMUM_FILE_LINE_COUNT;
+    let line_count = source_text.lines().count();
+    if line_count <= limit {
+        return;
+    }
+    let first_line_start = std::iter::once(0)
+        .chain(source_text.match_indices('\n').map(|(index, _)| index + 1))
+        .nth(limit)
+        .unwrap_or(0);
+    let first_line = source_text[first_line_start..]
+        .lines()
+        .next()
+        .unwrap_or_default();
+    diagnostics.push(PhaseDiagnostic::new(
+        format!("file spans {line_count} lines, more than the limit of {limit}"),
+        Span::new(
+            first_line_start,
+            first_line_start + first_line.trim_end().len(),
+        ),
+    ));
+}
+
+// An entrypoint holds `main` and a few helpers. Past the limit, each further
+// declaration is reported, since it belongs in a library file of the package
+// where it can be tested.
+fn check_entrypoint_helper_count(file: &SyntaxParsedFile, diagnostics: &mut Vec<PhaseDiagnostic>) {
+    let limit = MAXIMUM_ENTRYPOINT_HELPER_COUNT;
+    if file.role != FileRole::BinaryEntrypoint {
+        return;
+    }
+    let helpers = file
+        .top_level_declarations()
+        .filter_map(|declaration| match declaration {
+            SyntaxDeclaration::Type(type_declaration) => {
+                Some((&type_declaration.name, type_declaration.name_span))
+            }
+            SyntaxDeclaration::Constant(constant_declaration) => {
+                Some((&constant_declaration.name, constant_declaration.name_span))
+            }
+            SyntaxDeclaration::Function(function_declaration)
+                if function_declaration.name != "main" =>
+            {
+                Some((&function_declaration.name, function_declaration.name_span))
+            }
+            _ => None,
+        });
+    for (name, name_span) in helpers.skip(limit) {
+        diagnostics.push(PhaseDiagnostic::new(
+            format!(
+                ".bin.copp file declares more helpers than the limit of {limit}; move '{name}' into a library file"
+            ),
+            name_span,
+        ));
+    }
+}
+
 fn check_binary_main_signature(
     main_function_declaration: &SyntaxFunctionDeclaration,
     diagnostics: &mut Vec<PhaseDiagnostic>,
@@ -193,48 +445,94 @@ fn check_binary_main_signature(
     if !main_function_declaration.type_parameters.is_empty() {
         diagnostics.push(PhaseDiagnostic::new(
             "main in .bin.copp must not declare type parameters",
-            main_function_declaration.name_span.clone(),
+            main_function_declaration.name_span,
         ));
     }
     if !main_function_declaration.parameters.is_empty() {
         diagnostics.push(PhaseDiagnostic::new(
             "main in .bin.copp must not declare parameters",
-            main_function_declaration.name_span.clone(),
+            main_function_declaration.name_span,
         ));
     }
     if !is_nil_type(&main_function_declaration.return_type) {
         diagnostics.push(PhaseDiagnostic::new(
             "main in .bin.copp must return nil",
-            main_function_declaration.return_type.span.clone(),
+            main_function_declaration.return_type.span,
         ));
     }
 }
 
+fn check_test_function_roles(file: &SyntaxParsedFile, diagnostics: &mut Vec<PhaseDiagnostic>) {
+    for declaration in file.top_level_declarations() {
+        let SyntaxDeclaration::Function(function_declaration) = declaration else {
+            continue;
+        };
+        let Some(attribute) = test_function_attribute(function_declaration) else {
+            continue;
+        };
+        let attribute_name = attribute.name();
+        if file.role != FileRole::Test {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("'@{attribute_name}' functions are only allowed in .test.copp files"),
+                function_declaration.name_span,
+            ));
+            continue;
+        }
+        if !function_declaration.type_parameters.is_empty() {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("'@{attribute_name}' function must not declare type parameters"),
+                function_declaration.name_span,
+            ));
+        }
+        if !function_declaration.parameters.is_empty() {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("'@{attribute_name}' function must not declare parameters"),
+                function_declaration.name_span,
+            ));
+        }
+        if !is_nil_type(&function_declaration.return_type) {
+            diagnostics.push(PhaseDiagnostic::new(
+                format!("'@{attribute_name}' function must return nil"),
+                function_declaration.return_type.span,
+            ));
+        }
+    }
+}
+
+fn test_function_attribute(
+    function_declaration: &SyntaxFunctionDeclaration,
+) -> Option<KnownAttribute> {
+    function_declaration
+        .attributes
+        .iter()
+        .filter_map(|attribute| KnownAttribute::from_name(&attribute.name))
+        .find(|attribute| matches!(attribute, KnownAttribute::Test | KnownAttribute::Bench))
+}
+
 fn is_nil_type(type_name: &SyntaxTypeName) -> bool {
     type_name.names.len() == 1 && type_name.names[0].name == "nil"
 }
 
 fn fallback_file_span(file: &SyntaxParsedFile) -> Span {
     if let Some(declaration) = file.top_level_declarations().next() {
-        return declaration_span(declaration).clone();
+        return *declaration_span(declaration);
     }
 
-    Span {
-        start: 0,
-        end: 0,
-        line: 1,
-        column: 1,
-    }
+    Span::new(0, 0)
 }
 
 fn declaration_span(declaration: &SyntaxDeclaration) -> &Span {
     match declaration {
         SyntaxDeclaration::Import(import_declaration) => &import_declaration.span,
         SyntaxDeclaration::Exports(exports_declaration) => &exports_declaration.span,
+        SyntaxDeclaration::Package(package_declaration) => &package_declaration.span,
         SyntaxDeclaration::Type(type_declaration) => &type_declaration.span,
         SyntaxDeclaration::Constant(constant_declaration) => &constant_declaration.span,
         SyntaxDeclaration::Function(function_declaration) => &function_declaration.span,
         SyntaxDeclaration::Group(group_declaration) => &group_declaration.span,
         SyntaxDeclaration::Test(test_declaration) => &test_declaration.span,
+        SyntaxDeclaration::StaticAssert(static_assert_declaration) => {
+            &static_assert_declaration.span
+        }
     }
 }
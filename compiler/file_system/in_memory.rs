@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{FileSystem, FileSystemDirectoryEntry, FileSystemEntryKind, not_found};
+
+// Directories exist as the ancestors of stored files and as explicitly created
+// paths. Writing a file does not require its parent directory to exist.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryFileSystem {
+    contents_by_path: RefCell<BTreeMap<PathBuf, Vec<u8>>>,
+    created_directories: RefCell<BTreeSet<PathBuf>>,
+}
+
+impl InMemoryFileSystem {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_file(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) {
+        self.contents_by_path
+            .get_mut()
+            .insert(path.into(), source.into().into_bytes());
+    }
+
+    pub(crate) fn contains_file(&self, path: &Path) -> bool {
+        self.contents_by_path.borrow().contains_key(path)
+    }
+
+    pub(crate) fn is_directory(&self, path: &Path) -> bool {
+        self.created_directories
+            .borrow()
+            .iter()
+            .any(|directory| directory.starts_with(path))
+            || self
+                .contents_by_path
+                .borrow()
+                .keys()
+                .any(|file_path| file_path != path && file_path.starts_with(path))
+    }
+}
+
+impl FileSystem for InMemoryFileSystem {
+    fn entry_kind(&self, path: &Path) -> io::Result<FileSystemEntryKind> {
+        if self.contains_file(path) {
+            Ok(FileSystemEntryKind::File)
+        } else if self.is_directory(path) {
+            Ok(FileSystemEntryKind::Directory)
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.contents_by_path
+            .borrow()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn read_directory(&self, path: &Path) -> io::Result<Vec<FileSystemDirectoryEntry>> {
+        if !self.is_directory(path) {
+            return Err(not_found(path));
+        }
+        let contents_by_path = self.contents_by_path.borrow();
+        let created_directories = self.created_directories.borrow();
+        let child_paths = contents_by_path
+            .keys()
+            .chain(created_directories.iter())
+            .filter_map(|descendant_path| {
+                let relative_path = descendant_path.strip_prefix(path).ok()?;
+                let child_name = relative_path.components().next()?;
+                Some(path.join(child_name))
+            })
+            .collect::<BTreeSet<_>>();
+        Ok(child_paths
+            .into_iter()
+            .map(|child_path| {
+                let kind = if contents_by_path.contains_key(&child_path) {
+                    FileSystemEntryKind::File
+                } else {
+                    FileSystemEntryKind::Directory
+                };
+                FileSystemDirectoryEntry {
+                    path: child_path,
+                    kind,
+                }
+            })
+            .collect())
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        if self.is_directory(path) {
+            return Err(io::Error::new(
+                ErrorKind::IsADirectory,
+                format!("is a directory: {}", path.display()),
+            ));
+        }
+        self.contents_by_path
+            .borrow_mut()
+            .insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn create_directory_all(&self, path: &Path) -> io::Result<()> {
+        if self.contains_file(path) {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("file exists: {}", path.display()),
+            ));
+        }
+        self.created_directories
+            .borrow_mut()
+            .insert(path.to_path_buf());
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.contents_by_path
+            .borrow_mut()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn remove_directory_all(&self, path: &Path) -> io::Result<()> {
+        if !self.is_directory(path) {
+            return Err(not_found(path));
+        }
+        self.contents_by_path
+            .borrow_mut()
+            .retain(|file_path, _| !file_path.starts_with(path));
+        self.created_directories
+            .borrow_mut()
+            .retain(|directory| !directory.starts_with(path));
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+mod in_memory;
+mod overlay;
+mod real;
+
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+pub use in_memory::InMemoryFileSystem;
+pub use overlay::OverlayFileSystem;
+pub use real::RealFileSystem;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileSystemEntryKind {
+    File,
+    Directory,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSystemDirectoryEntry {
+    pub path: PathBuf,
+    pub kind: FileSystemEntryKind,
+}
+
+// File access the compiler needs for analysis and build output. Paths are
+// absolute, and entry kinds follow symlinks the way `std::fs::metadata` does.
+// Writes take `&self` so that one file system can be shared by every stage of
+// a build.
+pub trait FileSystem {
+    fn entry_kind(&self, path: &Path) -> io::Result<FileSystemEntryKind>;
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn read_directory(&self, path: &Path) -> io::Result<Vec<FileSystemDirectoryEntry>>;
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    fn create_directory_all(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    fn remove_directory_all(&self, path: &Path) -> io::Result<()>;
+
+    // Resolves symlinks and, on case-insensitive file systems, the stored
+    // spelling of each component, so that two paths naming the same entry
+    // compare equal. File systems without links return existing paths as they
+    // are.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.entry_kind(path)?;
+        Ok(path.to_path_buf())
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+    }
+
+    fn file_size_bytes(&self, path: &Path) -> io::Result<u64> {
+        Ok(self.read(path)?.len() as u64)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.entry_kind(path)
+            .is_ok_and(|kind| kind == FileSystemEntryKind::File)
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        ErrorKind::NotFound,
+        format!("no such file or directory: {}", path.display()),
+    )
+}
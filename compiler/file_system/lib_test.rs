@@ -0,0 +1,191 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use compiler__file_system::{
+    FileSystem, FileSystemDirectoryEntry, FileSystemEntryKind, InMemoryFileSystem,
+    OverlayFileSystem, RealFileSystem,
+};
+use tests__test_workspace::TestWorkspace;
+
+fn workspace_file_system() -> InMemoryFileSystem {
+    let mut file_system = InMemoryFileSystem::new();
+    file_system.insert_file("/workspace/COPPICE_WORKSPACE", "");
+    file_system.insert_file("/workspace/app/PACKAGE.copp", "");
+    file_system.insert_file("/workspace/app/main.bin.copp", "function main() {}\n");
+    file_system.insert_file("/workspace/app/util/strings.copp", "");
+    file_system
+}
+
+#[test]
+fn in_memory_file_system_treats_file_ancestors_as_directories() {
+    let file_system = workspace_file_system();
+    assert_eq!(
+        file_system
+            .entry_kind(Path::new("/workspace/app"))
+            .expect("directory should exist"),
+        FileSystemEntryKind::Directory
+    );
+    assert!(file_system.is_file(Path::new("/workspace/app/PACKAGE.copp")));
+    assert!(!file_system.is_file(Path::new("/workspace/app/util")));
+    assert_eq!(
+        file_system
+            .read_to_string(Path::new("/workspace/app/main.bin.copp"))
+            .expect("file should exist"),
+        "function main() {}\n"
+    );
+}
+
+#[test]
+fn in_memory_file_system_lists_direct_children_in_order() {
+    let file_system = workspace_file_system();
+    let entries = file_system
+        .read_directory(Path::new("/workspace/app"))
+        .expect("directory should exist");
+    assert_eq!(
+        entries,
+        vec![
+            FileSystemDirectoryEntry {
+                path: PathBuf::from("/workspace/app/PACKAGE.copp"),
+                kind: FileSystemEntryKind::File,
+            },
+            FileSystemDirectoryEntry {
+                path: PathBuf::from("/workspace/app/main.bin.copp"),
+                kind: FileSystemEntryKind::File,
+            },
+            FileSystemDirectoryEntry {
+                path: PathBuf::from("/workspace/app/util"),
+                kind: FileSystemEntryKind::Directory,
+            },
+        ]
+    );
+}
+
+#[test]
+fn in_memory_file_system_reports_missing_paths_as_not_found() {
+    let file_system = workspace_file_system();
+    let error = file_system
+        .read_to_string(Path::new("/workspace/app/missing.copp"))
+        .expect_err("file should not exist");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert!(
+        file_system
+            .read_directory(Path::new("/workspace/app/main.bin.copp"))
+            .is_err()
+    );
+}
+
+#[test]
+fn in_memory_file_system_writes_and_removes_build_outputs() {
+    let file_system = workspace_file_system();
+    let build_directory = Path::new("/workspace/.coppice/build");
+    file_system
+        .create_directory_all(build_directory)
+        .expect("directory should be created");
+    assert!(
+        file_system
+            .read_directory(build_directory)
+            .expect("directory should exist")
+            .is_empty()
+    );
+
+    let object_path = build_directory.join("main.o");
+    file_system
+        .write(&object_path, b"object")
+        .expect("file should be written");
+    assert_eq!(
+        file_system
+            .file_size_bytes(&object_path)
+            .expect("file should exist"),
+        6
+    );
+    file_system
+        .remove_directory_all(Path::new("/workspace/.coppice"))
+        .expect("directory should be removed");
+    assert!(!file_system.is_file(&object_path));
+    assert!(file_system.is_file(Path::new("/workspace/app/PACKAGE.copp")));
+}
+
+#[test]
+fn overlay_file_system_prefers_overlay_files_and_hides_removed_base_files() {
+    let base = workspace_file_system();
+    let mut file_system = OverlayFileSystem::new(&base);
+    file_system.insert_file("/workspace/app/main.bin.copp", "function main() {\n}\n");
+    file_system.insert_file("/workspace/app/draft.copp", "");
+    file_system
+        .remove_file(Path::new("/workspace/app/util/strings.copp"))
+        .expect("base file should be hidden");
+
+    assert_eq!(
+        file_system
+            .read_to_string(Path::new("/workspace/app/main.bin.copp"))
+            .expect("file should exist"),
+        "function main() {\n}\n"
+    );
+    let entry_paths: Vec<PathBuf> = file_system
+        .read_directory(Path::new("/workspace/app"))
+        .expect("directory should exist")
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect();
+    assert_eq!(
+        entry_paths,
+        vec![
+            PathBuf::from("/workspace/app/PACKAGE.copp"),
+            PathBuf::from("/workspace/app/draft.copp"),
+            PathBuf::from("/workspace/app/main.bin.copp"),
+            PathBuf::from("/workspace/app/util"),
+        ]
+    );
+    assert!(
+        base.is_file(Path::new("/workspace/app/util/strings.copp")),
+        "removing through the overlay should leave the base untouched"
+    );
+}
+
+#[test]
+fn overlay_file_system_canonicalizes_overlay_only_files_beneath_base_directories() {
+    let base = workspace_file_system();
+    let mut file_system = OverlayFileSystem::new(&base);
+    file_system.insert_file("/workspace/app/draft.copp", "");
+
+    assert_eq!(
+        file_system
+            .canonicalize(Path::new("/workspace/app/draft.copp"))
+            .expect("overlay file should canonicalize"),
+        PathBuf::from("/workspace/app/draft.copp")
+    );
+    assert_eq!(
+        file_system
+            .canonicalize(Path::new("/workspace/app/missing.copp"))
+            .expect_err("missing file should not canonicalize")
+            .kind(),
+        ErrorKind::NotFound
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn real_file_system_follows_symlinked_directories() {
+    let workspace = TestWorkspace::new("coppice_file_system_test", &[]);
+    let root = workspace.path();
+    std::fs::create_dir_all(root.join("shared")).expect("directory should be created");
+    std::os::unix::fs::symlink(root.join("shared"), root.join("linked"))
+        .expect("symlink should be created");
+
+    let file_system = RealFileSystem;
+    let linked_entry = file_system
+        .read_directory(root)
+        .expect("directory should exist")
+        .into_iter()
+        .find(|entry| entry.path == root.join("linked"))
+        .expect("symlink should be listed");
+    let canonical_link = file_system
+        .canonicalize(&root.join("linked"))
+        .expect("symlink should canonicalize");
+    let canonical_target = file_system
+        .canonicalize(&root.join("shared"))
+        .expect("directory should canonicalize");
+
+    assert_eq!(linked_entry.kind, FileSystemEntryKind::Directory);
+    assert_eq!(canonical_link, canonical_target);
+}
@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    FileSystem, FileSystemDirectoryEntry, FileSystemEntryKind, InMemoryFileSystem, not_found,
+};
+
+// Layers in-memory files over a base file system, for example unsaved editor
+// buffers over the disk. Writes and removals only change the overlay: removing
+// a base path hides it rather than deleting it.
+pub struct OverlayFileSystem<'a> {
+    base: &'a dyn FileSystem,
+    overlay: InMemoryFileSystem,
+    removed_paths: RefCell<BTreeSet<PathBuf>>,
+}
+
+impl<'a> OverlayFileSystem<'a> {
+    #[must_use]
+    pub fn new(base: &'a dyn FileSystem) -> Self {
+        Self {
+            base,
+            overlay: InMemoryFileSystem::new(),
+            removed_paths: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    pub fn insert_file(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) {
+        self.overlay.insert_file(path, source);
+    }
+
+    fn is_removed_from_base(&self, path: &Path) -> bool {
+        self.removed_paths
+            .borrow()
+            .iter()
+            .any(|removed_path| path.starts_with(removed_path))
+    }
+
+    fn base_entry_kind(&self, path: &Path) -> io::Result<FileSystemEntryKind> {
+        if self.is_removed_from_base(path) {
+            return Err(not_found(path));
+        }
+        self.base.entry_kind(path)
+    }
+}
+
+impl FileSystem for OverlayFileSystem<'_> {
+    fn entry_kind(&self, path: &Path) -> io::Result<FileSystemEntryKind> {
+        self.overlay
+            .entry_kind(path)
+            .or_else(|_| self.base_entry_kind(path))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if self.overlay.contains_file(path) {
+            return self.overlay.read(path);
+        }
+        if self.is_removed_from_base(path) {
+            return Err(not_found(path));
+        }
+        self.base.read(path)
+    }
+
+    fn read_directory(&self, path: &Path) -> io::Result<Vec<FileSystemDirectoryEntry>> {
+        let base_is_directory = self
+            .base_entry_kind(path)
+            .is_ok_and(|kind| kind == FileSystemEntryKind::Directory);
+        let overlay_is_directory = self.overlay.is_directory(path);
+        if !base_is_directory && !overlay_is_directory {
+            return Err(not_found(path));
+        }
+        let mut kind_by_path = BTreeMap::new();
+        if base_is_directory {
+            for entry in self.base.read_directory(path)? {
+                if !self.is_removed_from_base(&entry.path) {
+                    kind_by_path.insert(entry.path, entry.kind);
+                }
+            }
+        }
+        if overlay_is_directory {
+            for entry in self.overlay.read_directory(path)? {
+                kind_by_path.insert(entry.path, entry.kind);
+            }
+        }
+        Ok(kind_by_path
+            .into_iter()
+            .map(|(path, kind)| FileSystemDirectoryEntry { path, kind })
+            .collect())
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.overlay.write(path, contents)
+    }
+
+    fn create_directory_all(&self, path: &Path) -> io::Result<()> {
+        self.overlay.create_directory_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let removed_from_overlay = self.overlay.remove_file(path).is_ok();
+        let removed_from_base = self
+            .base_entry_kind(path)
+            .is_ok_and(|kind| kind != FileSystemEntryKind::Directory);
+        if removed_from_base {
+            self.removed_paths.borrow_mut().insert(path.to_path_buf());
+        }
+        if removed_from_overlay || removed_from_base {
+            Ok(())
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    // Overlay entries have no links of their own, so an entry that only exists
+    // in the overlay keeps its name beneath its canonicalized parent.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.base_entry_kind(path).is_ok() {
+            return self.base.canonicalize(path);
+        }
+        self.overlay.entry_kind(path)?;
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(file_name)) => Ok(self.canonicalize(parent)?.join(file_name)),
+            _ => Ok(path.to_path_buf()),
+        }
+    }
+
+    fn remove_directory_all(&self, path: &Path) -> io::Result<()> {
+        let removed_from_overlay = self.overlay.remove_directory_all(path).is_ok();
+        let removed_from_base = self
+            .base_entry_kind(path)
+            .is_ok_and(|kind| kind == FileSystemEntryKind::Directory);
+        if removed_from_base {
+            self.removed_paths.borrow_mut().insert(path.to_path_buf());
+        }
+        if removed_from_overlay || removed_from_base {
+            Ok(())
+        } else {
+            Err(not_found(path))
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{FileSystem, FileSystemDirectoryEntry, FileSystemEntryKind};
+
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn entry_kind(&self, path: &Path) -> io::Result<FileSystemEntryKind> {
+        Ok(metadata_entry_kind(&fs::metadata(path)?))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    // Dangling symlinks are skipped. Symlinked directories are reported as
+    // directories, so walkers that follow them must guard against cycles.
+    fn read_directory(&self, path: &Path) -> io::Result<Vec<FileSystemDirectoryEntry>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let kind = if file_type.is_symlink() {
+                match fs::metadata(entry.path()) {
+                    Ok(metadata) => metadata_entry_kind(&metadata),
+                    Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                    Err(error) => return Err(error),
+                }
+            } else if file_type.is_dir() {
+                FileSystemEntryKind::Directory
+            } else if file_type.is_file() {
+                FileSystemEntryKind::File
+            } else {
+                FileSystemEntryKind::Other
+            };
+            entries.push(FileSystemDirectoryEntry {
+                path: entry.path(),
+                kind,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn create_directory_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_directory_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn file_size_bytes(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+}
+
+fn metadata_entry_kind(metadata: &fs::Metadata) -> FileSystemEntryKind {
+    if metadata.is_file() {
+        FileSystemEntryKind::File
+    } else if metadata.is_dir() {
+        FileSystemEntryKind::Directory
+    } else {
+        FileSystemEntryKind::Other
+    }
+}
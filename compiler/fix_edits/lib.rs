@@ -17,6 +17,26 @@ pub struct MergedTextEdits {
     pub rejected_text_edits: Vec<RejectedTextEdit>,
 }
 
+// Edits that are only correct when applied together, such as every occurrence
+// of a rename, tagged with whatever identifies the fix that proposed them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEditGroup<P> {
+    pub provenance: P,
+    pub text_edits: Vec<TextEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEditGroupConflict<P> {
+    pub skipped: P,
+    pub conflicting: P,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergedTextEditGroups<P> {
+    pub accepted_text_edits: Vec<TextEdit>,
+    pub conflicts: Vec<TextEditGroupConflict<P>>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ApplyTextEditsError {
     InvalidRange {
@@ -64,6 +84,67 @@ pub fn merge_text_edits(text_edits: &[TextEdit]) -> MergedTextEdits {
     }
 }
 
+// Groups are accepted whole or skipped whole, in the order given, so earlier
+// groups take priority. A group is skipped when one of its edits overlaps an
+// edit of an accepted group without being a byte-identical replacement, and
+// the conflict names the first accepted group it overlaps. Two insertions at
+// the same offset overlap, since either order would be a guess.
+#[must_use]
+pub fn merge_text_edit_groups<P: Clone>(groups: &[TextEditGroup<P>]) -> MergedTextEditGroups<P> {
+    let mut accepted_text_edits: Vec<(TextEdit, usize)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (group_index, group) in groups.iter().enumerate() {
+        let conflicting_group_index = group.text_edits.iter().find_map(|text_edit| {
+            accepted_text_edits
+                .iter()
+                .find(|(accepted_text_edit, _)| {
+                    accepted_text_edit != text_edit
+                        && text_edits_overlap(accepted_text_edit, text_edit)
+                })
+                .map(|(_, accepted_group_index)| *accepted_group_index)
+        });
+        if let Some(conflicting_group_index) = conflicting_group_index {
+            conflicts.push(TextEditGroupConflict {
+                skipped: group.provenance.clone(),
+                conflicting: groups[conflicting_group_index].provenance.clone(),
+            });
+            continue;
+        }
+        for text_edit in &group.text_edits {
+            if !accepted_text_edits
+                .iter()
+                .any(|(accepted_text_edit, _)| accepted_text_edit == text_edit)
+            {
+                accepted_text_edits.push((text_edit.clone(), group_index));
+            }
+        }
+    }
+
+    let mut accepted_text_edits = accepted_text_edits
+        .into_iter()
+        .map(|(text_edit, _)| text_edit)
+        .collect::<Vec<_>>();
+    accepted_text_edits.sort_by(|left, right| {
+        left.start_byte_offset
+            .cmp(&right.start_byte_offset)
+            .then(left.end_byte_offset.cmp(&right.end_byte_offset))
+    });
+    MergedTextEditGroups {
+        accepted_text_edits,
+        conflicts,
+    }
+}
+
+fn text_edits_overlap(left: &TextEdit, right: &TextEdit) -> bool {
+    if left.start_byte_offset == left.end_byte_offset
+        && right.start_byte_offset == right.end_byte_offset
+    {
+        return left.start_byte_offset == right.start_byte_offset;
+    }
+    left.start_byte_offset < right.end_byte_offset && right.start_byte_offset < left.end_byte_offset
+}
+
 pub fn apply_text_edits(
     source_text: &str,
     text_edits: &[TextEdit],
@@ -1,4 +1,15 @@
-use compiler__fix_edits::{ApplyTextEditsError, TextEdit, apply_text_edits, merge_text_edits};
+use compiler__fix_edits::{
+    ApplyTextEditsError, TextEdit, TextEditGroup, TextEditGroupConflict, apply_text_edits,
+    merge_text_edit_groups, merge_text_edits,
+};
+
+fn text_edit(start_byte_offset: usize, end_byte_offset: usize, replacement_text: &str) -> TextEdit {
+    TextEdit {
+        start_byte_offset,
+        end_byte_offset,
+        replacement_text: replacement_text.to_string(),
+    }
+}
 
 #[test]
 fn merge_text_edits_rejects_overlap() {
@@ -19,6 +30,66 @@ fn merge_text_edits_rejects_overlap() {
     assert_eq!(result.rejected_text_edits.len(), 1);
 }
 
+#[test]
+fn merge_text_edit_groups_skips_a_conflicting_group_whole() {
+    let result = merge_text_edit_groups(&[
+        TextEditGroup {
+            provenance: "matches",
+            text_edits: vec![text_edit(4, 9, "true")],
+        },
+        TextEditGroup {
+            provenance: "rename",
+            text_edits: vec![text_edit(0, 2, "ab"), text_edit(6, 8, "ab")],
+        },
+        TextEditGroup {
+            provenance: "trailing",
+            text_edits: vec![text_edit(12, 12, ";")],
+        },
+    ]);
+
+    assert_eq!(
+        result.accepted_text_edits,
+        vec![text_edit(4, 9, "true"), text_edit(12, 12, ";")]
+    );
+    assert_eq!(
+        result.conflicts,
+        vec![TextEditGroupConflict {
+            skipped: "rename",
+            conflicting: "matches",
+        }]
+    );
+}
+
+#[test]
+fn merge_text_edit_groups_accepts_byte_identical_edits_once() {
+    let result = merge_text_edit_groups(&[
+        TextEditGroup {
+            provenance: "first",
+            text_edits: vec![text_edit(2, 4, "x")],
+        },
+        TextEditGroup {
+            provenance: "second",
+            text_edits: vec![text_edit(2, 4, "x"), text_edit(6, 6, "y")],
+        },
+        TextEditGroup {
+            provenance: "third",
+            text_edits: vec![text_edit(6, 6, "z")],
+        },
+    ]);
+
+    assert_eq!(
+        result.accepted_text_edits,
+        vec![text_edit(2, 4, "x"), text_edit(6, 6, "y")]
+    );
+    assert_eq!(
+        result.conflicts,
+        vec![TextEditGroupConflict {
+            skipped: "third",
+            conflicting: "second",
+        }]
+    );
+}
+
 #[test]
 fn apply_text_edits_applies_in_descending_offset_order() {
     let output = apply_text_edits(
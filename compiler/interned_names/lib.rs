@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// Every distinct name that is in use is stored once, and an interned name
+// shares that one copy, so reading a name never takes the lock; only interning
+// one does. The interner holds its copies weakly and removes the ones nothing
+// uses once it has grown to twice the names that were in use when it last
+// removed them. A long-lived process such as the language server therefore
+// keeps about the names of the analyses it still holds, rather than every name
+// it has seen.
+#[derive(Default)]
+struct Interner {
+    interned_text_by_text: HashMap<Box<str>, Weak<str>>,
+    removal_threshold: usize,
+}
+
+const MINIMUM_REMOVAL_THRESHOLD: usize = 64;
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Mutex::default)
+}
+
+fn intern(text: &str) -> Arc<str> {
+    let mut interner = interner()
+        .lock()
+        .expect("interner lock should not be poisoned");
+    if let Some(interned_text) = interner
+        .interned_text_by_text
+        .get(text)
+        .and_then(Weak::upgrade)
+    {
+        return interned_text;
+    }
+    if interner.interned_text_by_text.len() >= interner.removal_threshold {
+        interner
+            .interned_text_by_text
+            .retain(|_, interned_text| interned_text.strong_count() > 0);
+        interner.removal_threshold =
+            (interner.interned_text_by_text.len() * 2).max(MINIMUM_REMOVAL_THRESHOLD);
+    }
+    let interned_text: Arc<str> = Arc::from(text);
+    interner
+        .interned_text_by_text
+        .insert(Box::from(text), Arc::downgrade(&interned_text));
+    interned_text
+}
+
+// The number of names the interner stores, including copies that nothing uses
+// anymore but that have not been removed yet.
+#[must_use]
+pub fn interned_name_count() -> usize {
+    interner()
+        .lock()
+        .expect("interner lock should not be poisoned")
+        .interned_text_by_text
+        .len()
+}
+
+// Equality and hashing use the address of the one stored copy, but ordering
+// compares the text so that sorted maps keep the same order from one
+// compilation to the next. Names serialize as their text.
+macro_rules! interned_name {
+    ($name:ident) => {
+        #[derive(Clone)]
+        pub struct $name(Arc<str>);
+
+        impl $name {
+            #[must_use]
+            pub fn new(text: &str) -> Self {
+                Self(intern(text))
+            }
+
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            #[must_use]
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                Arc::ptr_eq(&self.0, &other.0)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                Arc::as_ptr(&self.0).cast::<u8>().hash(state);
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                if self == other {
+                    return Ordering::Equal;
+                }
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(self.as_str(), formatter)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(|text| Self::new(&text))
+            }
+        }
+    };
+}
+
+// A workspace-relative package path, such as `std/math`. The root package's
+// path is empty.
+interned_name!(PackagePath);
+
+// The name of a top-level type, function, or constant within its package.
+interned_name!(SymbolName);
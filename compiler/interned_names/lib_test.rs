@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+
+use compiler__interned_names::{PackagePath, SymbolName};
+
+#[test]
+fn interned_names_compare_by_text() {
+    let first = SymbolName::new("parse");
+    assert_eq!(first, SymbolName::new("parse"));
+    let separately_allocated = format!("par{}", "se");
+    assert_eq!(first, SymbolName::new(&separately_allocated));
+    assert!(HashSet::from([first.clone()]).contains(&SymbolName::new(&separately_allocated)));
+    assert_eq!(first.as_str(), "parse");
+    assert_eq!(first, "parse");
+    assert_ne!(first, SymbolName::new("print"));
+
+    let mut package_paths = vec![
+        PackagePath::new("std/text"),
+        PackagePath::new(""),
+        PackagePath::new("std/math"),
+    ];
+    package_paths.sort();
+    assert_eq!(package_paths, ["", "std/math", "std/text"]);
+    assert!(package_paths[0].is_empty());
+    assert_eq!(format!("{:?}", package_paths[1]), "\"std/math\"");
+}
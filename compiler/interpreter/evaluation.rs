@@ -0,0 +1,889 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use compiler__executable_program::{
+    ExecutableAssignTarget, ExecutableBinaryOperator, ExecutableCallTarget, ExecutableExpression,
+    ExecutableMatchArm, ExecutableMatchPattern, ExecutableParameterDeclaration, ExecutableProgram,
+    ExecutableStatement, ExecutableUnaryOperator,
+};
+use compiler__program_references::CallableReference;
+use compiler__runtime_interface::{
+    ABORT_FUNCTION_CONTRACT, ABS_FUNCTION_CONTRACT, ARITHMETIC_OVERFLOW_MESSAGE,
+    ASSERT_FUNCTION_CONTRACT, BUILTIN_FUNCTION_CONTRACTS, CHECKED_ADD_FUNCTION_CONTRACT,
+    CHECKED_MUL_FUNCTION_CONTRACT, CHECKED_SUB_FUNCTION_CONTRACT, ExecutionContext,
+    INTEGER_DIVISION_BY_ZERO_MESSAGE, INTEGER_REMAINDER_BY_ZERO_MESSAGE, MAX_FUNCTION_CONTRACT,
+    MIN_FUNCTION_CONTRACT, NOW_FUNCTION_CONTRACT, OverflowMode, POW_FUNCTION_CONTRACT,
+    POW_NEGATIVE_EXPONENT_MESSAGE, PRINT_FUNCTION_CONTRACT, RANDOM_INT_EMPTY_RANGE_MESSAGE,
+    RANDOM_INT_FUNCTION_CONTRACT, SATURATING_ADD_FUNCTION_CONTRACT,
+    SATURATING_MUL_FUNCTION_CONTRACT, SATURATING_SUB_FUNCTION_CONTRACT,
+    UNREACHABLE_FUNCTION_CONTRACT, arithmetic_abort_message, next_random_value,
+    random_int_in_range, wrapping_pow,
+};
+
+use crate::values::{
+    StructValue, Value, render_value, runtime_value_from_value, value_from_runtime_value,
+    value_matches_literal, value_matches_runtime_type, value_matches_type, values_equal,
+};
+use crate::{RegisteredHostFunction, unix_time_seconds};
+
+// Each coppice call nests several evaluator frames on the host stack, so deep
+// recursion aborts before it can overflow the stack.
+const MAX_CALL_DEPTH: usize = 256;
+
+// Evaluation errors abort the whole call with a message, the way a native
+// build writes the message to stderr and exits.
+pub(crate) type EvaluationResult<T> = Result<T, String>;
+
+enum StatementFlow {
+    Next,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+pub(crate) struct Evaluator<'interpreter> {
+    pub(crate) program: &'interpreter ExecutableProgram,
+    pub(crate) host_function_by_name: &'interpreter mut BTreeMap<String, RegisteredHostFunction>,
+    pub(crate) execution_context: &'interpreter ExecutionContext,
+    pub(crate) random_state: &'interpreter mut u64,
+    pub(crate) call_depth: usize,
+}
+
+type Locals = BTreeMap<String, Value>;
+
+impl Evaluator<'_> {
+    pub(crate) fn call_function(
+        &mut self,
+        callable_reference: &CallableReference,
+        arguments: Vec<Value>,
+    ) -> EvaluationResult<Value> {
+        let program = self.program;
+        let function_declaration = program
+            .function_declarations
+            .iter()
+            .find(|function_declaration| {
+                function_declaration.callable_reference == *callable_reference
+            })
+            .ok_or_else(|| {
+                format!(
+                    "unknown function '{}::{}'",
+                    callable_reference.package_path, callable_reference.symbol_name
+                )
+            })?;
+        let locals = bind_parameters(
+            &function_declaration.name,
+            &function_declaration.parameters,
+            arguments,
+        )?;
+        self.evaluate_body(&function_declaration.statements, locals)
+    }
+
+    fn call_method(
+        &mut self,
+        receiver: Value,
+        method_name: &str,
+        arguments: Vec<Value>,
+    ) -> EvaluationResult<Value> {
+        let Value::Struct(struct_value) = &receiver else {
+            return Err(format!(
+                "cannot call method '{method_name}' on a {} value",
+                receiver.description()
+            ));
+        };
+        let program = self.program;
+        let struct_declaration = program
+            .struct_declarations
+            .iter()
+            .find(|struct_declaration| {
+                struct_declaration.struct_reference == struct_value.struct_reference
+            })
+            .ok_or_else(|| {
+                format!(
+                    "unknown struct '{}'",
+                    struct_value.struct_reference.symbol_name
+                )
+            })?;
+        let method_declaration = struct_declaration
+            .methods
+            .iter()
+            .find(|method_declaration| method_declaration.name == method_name)
+            .ok_or_else(|| format!("unknown method '{}.{method_name}'", struct_declaration.name))?;
+        let mut locals = bind_parameters(
+            &format!("{}.{method_name}", struct_declaration.name),
+            &method_declaration.parameters,
+            arguments,
+        )?;
+        locals.insert("self".to_string(), receiver);
+        self.evaluate_body(&method_declaration.statements, locals)
+    }
+
+    fn evaluate_body(
+        &mut self,
+        statements: &[ExecutableStatement],
+        mut locals: Locals,
+    ) -> EvaluationResult<Value> {
+        if self.call_depth == MAX_CALL_DEPTH {
+            return Err(format!(
+                "call depth exceeded the limit of {MAX_CALL_DEPTH} nested calls"
+            ));
+        }
+        self.call_depth += 1;
+        let flow = self.evaluate_statements(statements, &mut locals);
+        self.call_depth -= 1;
+        match flow? {
+            StatementFlow::Next => Ok(Value::Nil),
+            StatementFlow::Return(value) => Ok(value),
+            StatementFlow::Break | StatementFlow::Continue => {
+                Err("break or continue used outside loop".to_string())
+            }
+        }
+    }
+
+    fn evaluate_statements(
+        &mut self,
+        statements: &[ExecutableStatement],
+        locals: &mut Locals,
+    ) -> EvaluationResult<StatementFlow> {
+        for statement in statements {
+            let flow = self.evaluate_statement(statement, locals)?;
+            if !matches!(flow, StatementFlow::Next) {
+                return Ok(flow);
+            }
+        }
+        Ok(StatementFlow::Next)
+    }
+
+    fn evaluate_statement(
+        &mut self,
+        statement: &ExecutableStatement,
+        locals: &mut Locals,
+    ) -> EvaluationResult<StatementFlow> {
+        match statement {
+            ExecutableStatement::Binding {
+                name, initializer, ..
+            } => {
+                let value = self.evaluate_expression(initializer, locals)?;
+                locals.insert(name.clone(), value);
+            }
+            ExecutableStatement::Assign { target, value } => match target {
+                ExecutableAssignTarget::Name { name } => {
+                    let value = self.evaluate_expression(value, locals)?;
+                    let local = locals
+                        .get_mut(name)
+                        .ok_or_else(|| format!("unknown local '{name}'"))?;
+                    *local = value;
+                }
+                ExecutableAssignTarget::Index { target, index } => {
+                    let target = self.evaluate_expression(target, locals)?;
+                    let index = self.evaluate_expression(index, locals)?;
+                    let value = self.evaluate_expression(value, locals)?;
+                    let Value::List(elements) = target else {
+                        return Err(format!(
+                            "index assignment target must be a list, got {}",
+                            target.description()
+                        ));
+                    };
+                    let mut elements = elements.borrow_mut();
+                    let element_index = list_index(&index, elements.len())?;
+                    elements[element_index] = value;
+                }
+            },
+            ExecutableStatement::If {
+                condition,
+                then_statements,
+                else_statements,
+            } => {
+                if self.evaluate_condition(condition, locals)? {
+                    return self.evaluate_statements(then_statements, locals);
+                }
+                if let Some(else_statements) = else_statements {
+                    return self.evaluate_statements(else_statements, locals);
+                }
+            }
+            ExecutableStatement::For {
+                condition,
+                body_statements,
+            } => loop {
+                if let Some(condition) = condition
+                    && !self.evaluate_condition(condition, locals)?
+                {
+                    break;
+                }
+                match self.evaluate_statements(body_statements, locals)? {
+                    StatementFlow::Next | StatementFlow::Continue => {}
+                    StatementFlow::Break => break,
+                    flow @ StatementFlow::Return(_) => return Ok(flow),
+                }
+            },
+            ExecutableStatement::Break => return Ok(StatementFlow::Break),
+            ExecutableStatement::Continue => return Ok(StatementFlow::Continue),
+            ExecutableStatement::Expression { expression } => {
+                self.evaluate_expression(expression, locals)?;
+            }
+            ExecutableStatement::Return { value } => {
+                return Ok(StatementFlow::Return(
+                    self.evaluate_expression(value, locals)?,
+                ));
+            }
+        }
+        Ok(StatementFlow::Next)
+    }
+
+    fn evaluate_condition(
+        &mut self,
+        condition: &ExecutableExpression,
+        locals: &mut Locals,
+    ) -> EvaluationResult<bool> {
+        match self.evaluate_expression(condition, locals)? {
+            Value::Boolean(value) => Ok(value),
+            value => Err(format!(
+                "condition must be boolean, got {}",
+                value.description()
+            )),
+        }
+    }
+
+    fn evaluate_expression(
+        &mut self,
+        expression: &ExecutableExpression,
+        locals: &mut Locals,
+    ) -> EvaluationResult<Value> {
+        match expression {
+            ExecutableExpression::IntegerLiteral { value } => Ok(Value::Int64(*value)),
+            ExecutableExpression::BooleanLiteral { value } => Ok(Value::Boolean(*value)),
+            ExecutableExpression::NilLiteral => Ok(Value::Nil),
+            ExecutableExpression::StringLiteral { value } => Ok(Value::String(value.clone())),
+            ExecutableExpression::ListLiteral { elements, .. } => {
+                let elements = self.evaluate_expressions(elements, locals)?;
+                Ok(Value::list(elements))
+            }
+            ExecutableExpression::Identifier {
+                name,
+                constant_reference,
+                callable_reference,
+                ..
+            } => {
+                if let Some(value) = locals.get(name) {
+                    return Ok(value.clone());
+                }
+                if let Some(constant_reference) = constant_reference {
+                    let program = self.program;
+                    let constant_declaration = program
+                        .constant_declarations
+                        .iter()
+                        .find(|constant_declaration| {
+                            constant_declaration.constant_reference == *constant_reference
+                        })
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown constant '{}::{}'",
+                                constant_reference.package_path, constant_reference.symbol_name
+                            )
+                        })?;
+                    // Like native builds, the initializer is evaluated at each
+                    // use rather than once.
+                    return self.evaluate_expression(
+                        &constant_declaration.initializer,
+                        &mut Locals::new(),
+                    );
+                }
+                if let Some(callable_reference) = callable_reference {
+                    return Ok(Value::Function(callable_reference.clone()));
+                }
+                Err(format!("unknown local '{name}'"))
+            }
+            ExecutableExpression::EnumVariantLiteral {
+                enum_variant_reference,
+                ..
+            } => Ok(Value::EnumVariant(enum_variant_reference.clone())),
+            ExecutableExpression::EnumVariantName { value, .. } => {
+                match self.evaluate_expression(value, locals)? {
+                    Value::EnumVariant(enum_variant_reference) => {
+                        Ok(Value::String(enum_variant_reference.variant_name))
+                    }
+                    value => Err(format!(
+                        "variant_name expects an enum value, got {}",
+                        value.description()
+                    )),
+                }
+            }
+            ExecutableExpression::EnumVariantFromString {
+                value,
+                enum_variant_references,
+                ..
+            } => match self.evaluate_expression(value, locals)? {
+                Value::String(name) => Ok(enum_variant_references
+                    .iter()
+                    .find(|enum_variant_reference| enum_variant_reference.variant_name == name)
+                    .map_or(Value::Nil, |enum_variant_reference| {
+                        Value::EnumVariant(enum_variant_reference.clone())
+                    })),
+                value => Err(format!(
+                    "from_string expects a string, got {}",
+                    value.description()
+                )),
+            },
+            ExecutableExpression::StructLiteral {
+                struct_reference,
+                fields,
+                ..
+            } => {
+                // Field values are evaluated in source order and then stored
+                // in declaration order, which is the order every struct value
+                // is rendered and passed to the host in.
+                let mut provided_fields = Vec::with_capacity(fields.len());
+                for field in fields {
+                    let value = self.evaluate_expression(&field.value, locals)?;
+                    provided_fields.push((field.name.as_str(), value));
+                }
+                let program = self.program;
+                let struct_declaration = program
+                    .struct_declarations
+                    .iter()
+                    .find(|struct_declaration| {
+                        struct_declaration.struct_reference == *struct_reference
+                    })
+                    .ok_or_else(|| format!("unknown struct '{}'", struct_reference.symbol_name))?;
+                let fields = struct_declaration
+                    .fields
+                    .iter()
+                    .map(|declared_field| {
+                        let index = provided_fields
+                            .iter()
+                            .position(|(name, _)| *name == declared_field.name)
+                            .ok_or_else(|| {
+                                format!(
+                                    "missing field '{}.{}'",
+                                    struct_declaration.name, declared_field.name
+                                )
+                            })?;
+                        let (_, value) = provided_fields.swap_remove(index);
+                        Ok((declared_field.name.clone(), value))
+                    })
+                    .collect::<EvaluationResult<_>>()?;
+                Ok(Value::Struct(Rc::new(StructValue {
+                    struct_reference: struct_reference.clone(),
+                    fields,
+                })))
+            }
+            ExecutableExpression::FieldAccess { target, field, .. } => {
+                match self.evaluate_expression(target, locals)? {
+                    Value::List(elements) if field == "length" => {
+                        Ok(Value::Int64(list_length(elements.borrow().len())))
+                    }
+                    Value::Struct(struct_value) => struct_value
+                        .fields
+                        .iter()
+                        .find(|(name, _)| name == field)
+                        .map(|(_, value)| value.clone())
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown field '{}.{field}'",
+                                struct_value.struct_reference.symbol_name
+                            )
+                        }),
+                    value => Err(format!(
+                        "unknown field '{field}' on a {} value",
+                        value.description()
+                    )),
+                }
+            }
+            ExecutableExpression::IndexAccess { target, index } => {
+                let target = self.evaluate_expression(target, locals)?;
+                let index = self.evaluate_expression(index, locals)?;
+                let Value::List(elements) = target else {
+                    return Err(format!(
+                        "index access target must be a list, got {}",
+                        target.description()
+                    ));
+                };
+                let elements = elements.borrow();
+                Ok(elements[list_index(&index, elements.len())?].clone())
+            }
+            ExecutableExpression::Unary {
+                operator,
+                expression,
+                operation_description,
+            } => match (operator, self.evaluate_expression(expression, locals)?) {
+                (ExecutableUnaryOperator::Not, Value::Boolean(value)) => Ok(Value::Boolean(!value)),
+                (ExecutableUnaryOperator::Negate, Value::Int64(value)) => int64_result(
+                    value.overflowing_neg(),
+                    self.execution_context.overflow_mode,
+                    operation_description.as_deref(),
+                ),
+                (_, value) => Err(format!(
+                    "invalid operand for unary operator: {}",
+                    value.description()
+                )),
+            },
+            ExecutableExpression::Binary {
+                operator,
+                left,
+                right,
+                operation_description,
+            } => {
+                let left = self.evaluate_expression(left, locals)?;
+                // The right operand of `and` and `or` may rely on narrowing by
+                // the left one, as in `value matches int64 and value > 0`, so
+                // it is only evaluated when it decides the result.
+                match (operator, &left) {
+                    (ExecutableBinaryOperator::And, Value::Boolean(false)) => {
+                        return Ok(Value::Boolean(false));
+                    }
+                    (ExecutableBinaryOperator::Or, Value::Boolean(true)) => {
+                        return Ok(Value::Boolean(true));
+                    }
+                    _ => {}
+                }
+                let right = self.evaluate_expression(right, locals)?;
+                evaluate_binary_operator(
+                    *operator,
+                    &left,
+                    &right,
+                    self.execution_context.overflow_mode,
+                    operation_description.as_deref(),
+                )
+            }
+            ExecutableExpression::Call {
+                callee,
+                call_target,
+                arguments,
+                ..
+            } => self.evaluate_call(callee, call_target.as_ref(), arguments, locals),
+            ExecutableExpression::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                if self.evaluate_condition(condition, locals)? {
+                    self.evaluate_expression(then_branch, locals)
+                } else {
+                    self.evaluate_expression(else_branch, locals)
+                }
+            }
+            ExecutableExpression::Block { statements, value } => {
+                match self.evaluate_statements(statements, locals)? {
+                    StatementFlow::Next => self.evaluate_expression(value, locals),
+                    StatementFlow::Return(_) | StatementFlow::Break | StatementFlow::Continue => {
+                        Err("return, break, or continue cannot leave a block expression"
+                            .to_string())
+                    }
+                }
+            }
+            ExecutableExpression::Match { target, arms } => {
+                let target = self.evaluate_expression(target, locals)?;
+                self.evaluate_match(&target, arms, locals)
+            }
+            ExecutableExpression::Matches {
+                value,
+                type_reference,
+            } => {
+                let value = self.evaluate_expression(value, locals)?;
+                Ok(Value::Boolean(value_matches_type(
+                    &value,
+                    type_reference,
+                    &self.program.struct_declarations,
+                )))
+            }
+        }
+    }
+
+    fn evaluate_expressions(
+        &mut self,
+        expressions: &[ExecutableExpression],
+        locals: &mut Locals,
+    ) -> EvaluationResult<Vec<Value>> {
+        expressions
+            .iter()
+            .map(|expression| self.evaluate_expression(expression, locals))
+            .collect()
+    }
+
+    fn evaluate_match(
+        &mut self,
+        target: &Value,
+        arms: &[ExecutableMatchArm],
+        locals: &mut Locals,
+    ) -> EvaluationResult<Value> {
+        for arm in arms {
+            let pattern_matches = match &arm.pattern {
+                ExecutableMatchPattern::Type { type_reference }
+                | ExecutableMatchPattern::Binding { type_reference, .. } => {
+                    value_matches_type(target, type_reference, &self.program.struct_declarations)
+                }
+                ExecutableMatchPattern::Literal { value } => value_matches_literal(target, value),
+            };
+            if !pattern_matches {
+                continue;
+            }
+            if let ExecutableMatchPattern::Binding { binding_name, .. } = &arm.pattern {
+                locals.insert(binding_name.clone(), target.clone());
+            }
+            if let Some(guard) = &arm.guard {
+                match self.evaluate_expression(guard, locals)? {
+                    Value::Boolean(true) => {}
+                    Value::Boolean(false) => continue,
+                    guard => {
+                        return Err(format!(
+                            "match guard must be boolean, got {}",
+                            guard.description()
+                        ));
+                    }
+                }
+            }
+            return self.evaluate_expression(&arm.value, locals);
+        }
+        Err(format!(
+            "no match arm matched a {} value",
+            target.description()
+        ))
+    }
+
+    fn evaluate_call(
+        &mut self,
+        callee: &ExecutableExpression,
+        call_target: Option<&ExecutableCallTarget>,
+        arguments: &[ExecutableExpression],
+        locals: &mut Locals,
+    ) -> EvaluationResult<Value> {
+        match call_target {
+            Some(ExecutableCallTarget::BuiltinFunction { function_name }) => {
+                let arguments = self.evaluate_expressions(arguments, locals)?;
+                self.call_builtin_function(function_name, &arguments)
+            }
+            Some(ExecutableCallTarget::UserDefinedFunction { callable_reference }) => {
+                let arguments = self.evaluate_expressions(arguments, locals)?;
+                self.call_function(callable_reference, arguments)
+            }
+            None => {
+                if let ExecutableExpression::FieldAccess {
+                    target,
+                    field: method_name,
+                    ..
+                } = callee
+                {
+                    let receiver = self.evaluate_expression(target, locals)?;
+                    let arguments = self.evaluate_expressions(arguments, locals)?;
+                    return self.call_method(receiver, method_name, arguments);
+                }
+                let callee = self.evaluate_expression(callee, locals)?;
+                let arguments = self.evaluate_expressions(arguments, locals)?;
+                let Value::Function(callable_reference) = callee else {
+                    return Err(format!(
+                        "cannot call non-function value of type {}",
+                        callee.description()
+                    ));
+                };
+                self.call_function(&callable_reference, arguments)
+            }
+        }
+    }
+
+    // Host functions take precedence over the runtime's own builtins, so a
+    // host can redirect `print` and the other replaceable builtins.
+    fn call_builtin_function(
+        &mut self,
+        function_name: &str,
+        arguments: &[Value],
+    ) -> EvaluationResult<Value> {
+        if let Some(host_function) = self.host_function_by_name.get_mut(function_name) {
+            let host_arguments = arguments
+                .iter()
+                .map(runtime_value_from_value)
+                .collect::<EvaluationResult<Vec<_>>>()?;
+            let signature = &host_function.signature;
+            if host_arguments.len() != signature.parameter_types.len()
+                || !host_arguments.iter().zip(&signature.parameter_types).all(
+                    |(argument, parameter_type)| {
+                        value_matches_runtime_type(argument, *parameter_type)
+                    },
+                )
+            {
+                return Err(format!(
+                    "host function '{function_name}' called with arguments that do not match its signature"
+                ));
+            }
+            let result = (host_function.function)(&host_arguments)?;
+            if !value_matches_runtime_type(&result, host_function.signature.return_type) {
+                return Err(format!(
+                    "host function '{function_name}' returned a value that does not match its signature"
+                ));
+            }
+            return value_from_runtime_value(
+                &result,
+                &self.program.entrypoint_callable_reference.package_path,
+                &self.program.struct_declarations,
+            );
+        }
+
+        if let Some(capability) = BUILTIN_FUNCTION_CONTRACTS
+            .iter()
+            .find(|contract| contract.language_name == function_name)
+            .and_then(|contract| contract.required_capability)
+            && !self.execution_context.execution_policy.permits(capability)
+        {
+            return Err(capability.denial_message());
+        }
+
+        match (function_name, arguments) {
+            (name, [Value::String(message)]) if name == PRINT_FUNCTION_CONTRACT.language_name => {
+                match &self.execution_context.output_sink {
+                    Some(output_sink) => {
+                        output_sink.write_standard_output(format!("{message}\n").as_bytes());
+                    }
+                    None => println!("{message}"),
+                }
+                Ok(Value::Nil)
+            }
+            (name, [Value::String(message)]) if name == ABORT_FUNCTION_CONTRACT.language_name => {
+                Err(message.clone())
+            }
+            (name, []) if name == UNREACHABLE_FUNCTION_CONTRACT.language_name => {
+                Err("entered unreachable code".to_string())
+            }
+            (name, [Value::Boolean(condition), Value::String(description)])
+                if name == ASSERT_FUNCTION_CONTRACT.language_name =>
+            {
+                if *condition {
+                    Ok(Value::Nil)
+                } else {
+                    Err(format!("assertion failed: {description}"))
+                }
+            }
+            (name, []) if name == NOW_FUNCTION_CONTRACT.language_name => Ok(Value::Int64(
+                self.execution_context
+                    .fixed_now_unix_seconds
+                    .unwrap_or_else(unix_time_seconds),
+            )),
+            (name, [Value::Int64(minimum), Value::Int64(maximum)])
+                if name == RANDOM_INT_FUNCTION_CONTRACT.language_name =>
+            {
+                if maximum < minimum {
+                    return Err(RANDOM_INT_EMPTY_RANGE_MESSAGE.to_string());
+                }
+                let random_value = next_random_value(self.random_state);
+                Ok(Value::Int64(random_int_in_range(
+                    random_value,
+                    *minimum,
+                    *maximum,
+                )))
+            }
+            (name, [Value::Int64(value)]) if name == ABS_FUNCTION_CONTRACT.language_name => {
+                Ok(Value::Int64(value.wrapping_abs()))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == MIN_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(Value::Int64(*left.min(right)))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == MAX_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(Value::Int64(*left.max(right)))
+            }
+            (name, [Value::Int64(base), Value::Int64(exponent)])
+                if name == POW_FUNCTION_CONTRACT.language_name =>
+            {
+                wrapping_pow(*base, *exponent)
+                    .map(Value::Int64)
+                    .ok_or_else(|| POW_NEGATIVE_EXPONENT_MESSAGE.to_string())
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == CHECKED_ADD_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(left.checked_add(*right).map_or(Value::Nil, Value::Int64))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == CHECKED_SUB_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(left.checked_sub(*right).map_or(Value::Nil, Value::Int64))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == CHECKED_MUL_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(left.checked_mul(*right).map_or(Value::Nil, Value::Int64))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == SATURATING_ADD_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(Value::Int64(left.saturating_add(*right)))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == SATURATING_SUB_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(Value::Int64(left.saturating_sub(*right)))
+            }
+            (name, [Value::Int64(left), Value::Int64(right)])
+                if name == SATURATING_MUL_FUNCTION_CONTRACT.language_name =>
+            {
+                Ok(Value::Int64(left.saturating_mul(*right)))
+            }
+            ("assert_equals", [left, right, Value::String(description)]) => {
+                let rendered_left = render_value(left);
+                let rendered_right = render_value(right);
+                if rendered_left == rendered_right {
+                    Ok(Value::Nil)
+                } else {
+                    Err(format!(
+                        "assertion failed: {description}: left is {rendered_left}, right is {rendered_right}"
+                    ))
+                }
+            }
+            ("debug_string", [value]) => Ok(Value::String(render_value(value))),
+            ("string", [Value::Int64(value)]) => Ok(Value::String(value.to_string())),
+            ("string", [Value::Boolean(value)]) => Ok(Value::String(value.to_string())),
+            ("string", [Value::Nil]) => Ok(Value::String("nil".to_string())),
+            _ => Err(format!(
+                "unknown builtin function '{function_name}' for the given arguments"
+            )),
+        }
+    }
+}
+
+fn bind_parameters(
+    callable_name: &str,
+    parameters: &[ExecutableParameterDeclaration],
+    arguments: Vec<Value>,
+) -> EvaluationResult<Locals> {
+    if parameters.len() != arguments.len() {
+        return Err(format!(
+            "'{callable_name}' expected {} argument(s), got {}",
+            parameters.len(),
+            arguments.len()
+        ));
+    }
+    Ok(parameters
+        .iter()
+        .map(|parameter| parameter.name.clone())
+        .zip(arguments)
+        .collect())
+}
+
+fn evaluate_binary_operator(
+    operator: ExecutableBinaryOperator,
+    left: &Value,
+    right: &Value,
+    overflow_mode: OverflowMode,
+    operation_description: Option<&str>,
+) -> EvaluationResult<Value> {
+    match (operator, left, right) {
+        (ExecutableBinaryOperator::EqualEqual, _, _) => {
+            Ok(Value::Boolean(values_equal(left, right)))
+        }
+        (ExecutableBinaryOperator::NotEqual, _, _) => {
+            Ok(Value::Boolean(!values_equal(left, right)))
+        }
+        (ExecutableBinaryOperator::Add, Value::String(left), Value::String(right)) => {
+            Ok(Value::String(format!("{left}{right}")))
+        }
+        (ExecutableBinaryOperator::And, Value::Boolean(left), Value::Boolean(right)) => {
+            Ok(Value::Boolean(*left && *right))
+        }
+        (ExecutableBinaryOperator::Or, Value::Boolean(left), Value::Boolean(right)) => {
+            Ok(Value::Boolean(*left || *right))
+        }
+        (operator, Value::Int64(left), Value::Int64(right)) => evaluate_int64_operator(
+            operator,
+            *left,
+            *right,
+            overflow_mode,
+            operation_description,
+        ),
+        _ => Err(format!(
+            "invalid operands for binary operator: {} and {}",
+            left.description(),
+            right.description()
+        )),
+    }
+}
+
+// Arithmetic, including `i64::MIN / -1`, wraps on overflow like native builds
+// unless the run aborts on overflow; division and remainder by zero abort
+// either way. Shift amounts use only their low six bits, matching the native
+// shift instructions.
+fn evaluate_int64_operator(
+    operator: ExecutableBinaryOperator,
+    left: i64,
+    right: i64,
+    overflow_mode: OverflowMode,
+    operation_description: Option<&str>,
+) -> EvaluationResult<Value> {
+    let int64 = |result| int64_result(result, overflow_mode, operation_description);
+    Ok(match operator {
+        ExecutableBinaryOperator::Add => int64(left.overflowing_add(right))?,
+        ExecutableBinaryOperator::Subtract => int64(left.overflowing_sub(right))?,
+        ExecutableBinaryOperator::Multiply => int64(left.overflowing_mul(right))?,
+        ExecutableBinaryOperator::Divide if right == 0 => {
+            return Err(arithmetic_abort_message(
+                INTEGER_DIVISION_BY_ZERO_MESSAGE,
+                operation_description,
+            ));
+        }
+        ExecutableBinaryOperator::Divide => int64(left.overflowing_div(right))?,
+        ExecutableBinaryOperator::Modulo if right == 0 => {
+            return Err(arithmetic_abort_message(
+                INTEGER_REMAINDER_BY_ZERO_MESSAGE,
+                operation_description,
+            ));
+        }
+        ExecutableBinaryOperator::Modulo => Value::Int64(left.wrapping_rem(right)),
+        ExecutableBinaryOperator::BitwiseAnd => Value::Int64(left & right),
+        ExecutableBinaryOperator::BitwiseOr => Value::Int64(left | right),
+        ExecutableBinaryOperator::BitwiseXor => Value::Int64(left ^ right),
+        ExecutableBinaryOperator::ShiftLeft => Value::Int64(left.wrapping_shl(shift_amount(right))),
+        ExecutableBinaryOperator::ShiftRight => {
+            Value::Int64(left.wrapping_shr(shift_amount(right)))
+        }
+        ExecutableBinaryOperator::LessThan => Value::Boolean(left < right),
+        ExecutableBinaryOperator::LessThanOrEqual => Value::Boolean(left <= right),
+        ExecutableBinaryOperator::GreaterThan => Value::Boolean(left > right),
+        ExecutableBinaryOperator::GreaterThanOrEqual => Value::Boolean(left >= right),
+        ExecutableBinaryOperator::EqualEqual
+        | ExecutableBinaryOperator::NotEqual
+        | ExecutableBinaryOperator::And
+        | ExecutableBinaryOperator::Or => {
+            return Err("invalid int64 operands for logical operator".to_string());
+        }
+    })
+}
+
+// Only operations the program describes abort on overflow, as in native
+// builds.
+fn int64_result(
+    (value, overflowed): (i64, bool),
+    overflow_mode: OverflowMode,
+    operation_description: Option<&str>,
+) -> EvaluationResult<Value> {
+    if overflowed
+        && overflow_mode == OverflowMode::Abort
+        && let Some(operation_description) = operation_description
+    {
+        return Err(arithmetic_abort_message(
+            ARITHMETIC_OVERFLOW_MESSAGE,
+            Some(operation_description),
+        ));
+    }
+    Ok(Value::Int64(value))
+}
+
+fn shift_amount(right: i64) -> u32 {
+    u32::try_from(right & 63).unwrap_or_default()
+}
+
+fn list_index(index: &Value, length: usize) -> EvaluationResult<usize> {
+    let Value::Int64(index) = index else {
+        return Err(format!(
+            "list index must be int64, got {}",
+            index.description()
+        ));
+    };
+    usize::try_from(*index)
+        .ok()
+        .filter(|index| *index < length)
+        .ok_or_else(|| format!("list index {index} out of bounds for length {length}"))
+}
+
+fn list_length(length: usize) -> i64 {
+    i64::try_from(length).unwrap_or(i64::MAX)
+}
@@ -0,0 +1,236 @@
+mod evaluation;
+mod values;
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use compiler__executable_program::{ExecutableProgram, validate_program};
+use compiler__reports::{CompilerFailure, CompilerFailureDetail, CompilerFailureKind};
+use compiler__runtime_interface::{
+    BUILTIN_FUNCTION_CONTRACTS, BUILTIN_FUNCTION_NAMES_WITHOUT_CONTRACT, ExecutionContext,
+    RuntimeType,
+};
+
+use evaluation::Evaluator;
+use values::{runtime_value_from_value, value_from_runtime_value, value_matches_type};
+
+// A value crossing the boundary between a host and an interpreted program.
+// Structs carry their fields in declaration order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeValue {
+    Int64(i64),
+    Boolean(bool),
+    String(String),
+    Nil,
+    List(Vec<RuntimeValue>),
+    Struct {
+        type_name: String,
+        fields: Vec<(String, RuntimeValue)>,
+    },
+    EnumVariant {
+        enum_name: String,
+        variant_name: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostFunctionSignature {
+    pub parameter_types: Vec<RuntimeType>,
+    pub return_type: RuntimeType,
+}
+
+// Returning an error aborts the running program with that message.
+pub type HostFunction = Box<dyn FnMut(&[RuntimeValue]) -> Result<RuntimeValue, String>>;
+
+pub(crate) struct RegisteredHostFunction {
+    signature: HostFunctionSignature,
+    function: HostFunction,
+}
+
+// Executes a lowered program in-process instead of emitting an executable.
+// Program semantics follow native builds; where a native build would trap,
+// such as an out-of-bounds index, the call fails with a `RunFailed` error.
+pub struct Interpreter {
+    program: ExecutableProgram,
+    host_function_by_name: BTreeMap<String, RegisteredHostFunction>,
+    execution_context: ExecutionContext,
+    random_state: u64,
+}
+
+impl Interpreter {
+    // The program is validated first, so that a program that was not produced
+    // by this compiler fails with every violation listed instead of partway
+    // through a run.
+    pub fn new(program: ExecutableProgram) -> Result<Self, CompilerFailure> {
+        let violations = validate_program(&program);
+        if !violations.is_empty() {
+            return Err(CompilerFailure {
+                kind: CompilerFailureKind::RunFailed,
+                message: format!(
+                    "program failed validation with {} violation(s)",
+                    violations.len()
+                ),
+                path: None,
+                details: violations
+                    .into_iter()
+                    .map(|message| CompilerFailureDetail {
+                        message,
+                        path: None,
+                    })
+                    .collect(),
+            });
+        }
+        Ok(Self {
+            program,
+            host_function_by_name: BTreeMap::new(),
+            execution_context: ExecutionContext::default(),
+            random_state: unix_time_seconds().cast_unsigned(),
+        })
+    }
+
+    // The execution policy restricts runtime builtins only; host functions,
+    // including those that replace a builtin, are always permitted. Setting a
+    // context restarts the `random_int` sequence from its seed.
+    pub fn set_execution_context(&mut self, execution_context: ExecutionContext) {
+        self.random_state = execution_context
+            .random_seed
+            .unwrap_or_else(unix_time_seconds)
+            .cast_unsigned();
+        self.execution_context = execution_context;
+    }
+
+    // Host functions are called by programs through builtin call targets. A
+    // host function named after a runtime builtin such as `print` replaces
+    // it, and must then have the builtin's signature. Builtins without a
+    // runtime contract cannot be replaced.
+    pub fn register_host_function(
+        &mut self,
+        name: &str,
+        signature: HostFunctionSignature,
+        function: HostFunction,
+    ) -> Result<(), CompilerFailure> {
+        if let Some(runtime_function_contract) = BUILTIN_FUNCTION_CONTRACTS
+            .iter()
+            .find(|contract| contract.language_name == name)
+            && (signature.parameter_types != runtime_function_contract.parameter_types
+                || signature.return_type != runtime_function_contract.return_type)
+        {
+            return Err(run_failed(format!(
+                "host function '{name}' must match the signature of the builtin it replaces"
+            )));
+        }
+        if BUILTIN_FUNCTION_NAMES_WITHOUT_CONTRACT.contains(&name) {
+            return Err(run_failed(format!(
+                "host functions cannot replace the builtin '{name}', which has no runtime contract"
+            )));
+        }
+        if self.host_function_by_name.contains_key(name) {
+            return Err(run_failed(format!(
+                "host function '{name}' is already registered"
+            )));
+        }
+        self.host_function_by_name.insert(
+            name.to_string(),
+            RegisteredHostFunction {
+                signature,
+                function,
+            },
+        );
+        Ok(())
+    }
+
+    // Calls a non-generic top-level function declared in the entrypoint's
+    // package.
+    pub fn call_function(
+        &mut self,
+        function_name: &str,
+        arguments: &[RuntimeValue],
+    ) -> Result<RuntimeValue, CompilerFailure> {
+        let entrypoint_package_path = &self.program.entrypoint_callable_reference.package_path;
+        let function_declaration = self
+            .program
+            .function_declarations
+            .iter()
+            .find(|function_declaration| {
+                function_declaration.callable_reference.package_path == *entrypoint_package_path
+                    && function_declaration.name == function_name
+            })
+            .ok_or_else(|| run_failed(format!("unknown function '{function_name}'")))?;
+        if !function_declaration.type_parameter_names.is_empty() {
+            return Err(run_failed(format!(
+                "generic function '{function_name}' cannot be called from the host"
+            )));
+        }
+        if function_declaration.parameters.len() != arguments.len() {
+            return Err(run_failed(format!(
+                "function '{function_name}' expected {} argument(s), got {}",
+                function_declaration.parameters.len(),
+                arguments.len()
+            )));
+        }
+        let mut argument_values = Vec::with_capacity(arguments.len());
+        for (parameter, argument) in function_declaration.parameters.iter().zip(arguments) {
+            let value = value_from_runtime_value(
+                argument,
+                entrypoint_package_path,
+                &self.program.struct_declarations,
+            )
+            .map_err(run_failed)?;
+            if !value_matches_type(
+                &value,
+                &parameter.type_reference,
+                &self.program.struct_declarations,
+            ) {
+                return Err(run_failed(format!(
+                    "argument '{}' of function '{function_name}' cannot be a {} value",
+                    parameter.name,
+                    value.description()
+                )));
+            }
+            argument_values.push(value);
+        }
+
+        let mut evaluator = Evaluator {
+            program: &self.program,
+            host_function_by_name: &mut self.host_function_by_name,
+            execution_context: &self.execution_context,
+            random_state: &mut self.random_state,
+            call_depth: 0,
+        };
+        let result = evaluator
+            .call_function(&function_declaration.callable_reference, argument_values)
+            .map_err(run_failed)?;
+        runtime_value_from_value(&result).map_err(run_failed)
+    }
+
+    pub fn run_entrypoint(&mut self) -> Result<(), CompilerFailure> {
+        let mut evaluator = Evaluator {
+            program: &self.program,
+            host_function_by_name: &mut self.host_function_by_name,
+            execution_context: &self.execution_context,
+            random_state: &mut self.random_state,
+            call_depth: 0,
+        };
+        evaluator
+            .call_function(&self.program.entrypoint_callable_reference, Vec::new())
+            .map_err(run_failed)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn unix_time_seconds() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| {
+            i64::try_from(duration.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+fn run_failed(message: String) -> CompilerFailure {
+    CompilerFailure {
+        kind: CompilerFailureKind::RunFailed,
+        message,
+        path: None,
+        details: Vec::new(),
+    }
+}
@@ -0,0 +1,328 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use compiler__executable_program::{
+    ExecutableBinaryOperator, ExecutableCallTarget, ExecutableEnumLayout, ExecutableExpression,
+    ExecutableFunctionDeclaration, ExecutableLayoutTable, ExecutableListLayout,
+    ExecutableParameterDeclaration, ExecutableProgram, ExecutableStatement,
+    ExecutableTypeReference, ExecutableUnionLayout,
+};
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__interpreter::{HostFunctionSignature, Interpreter, RuntimeValue};
+use compiler__program_references::CallableReference;
+use compiler__runtime_interface::RuntimeType;
+
+fn callable_reference(symbol_name: &str) -> CallableReference {
+    CallableReference {
+        package_path: PackagePath::new(""),
+        symbol_name: SymbolName::new(symbol_name),
+    }
+}
+
+fn identifier(name: &str, type_reference: ExecutableTypeReference) -> ExecutableExpression {
+    ExecutableExpression::Identifier {
+        name: name.to_string(),
+        constant_reference: None,
+        callable_reference: None,
+        type_reference,
+    }
+}
+
+fn parameter(
+    name: &str,
+    type_reference: ExecutableTypeReference,
+) -> ExecutableParameterDeclaration {
+    ExecutableParameterDeclaration {
+        name: name.to_string(),
+        mutable: false,
+        type_reference,
+    }
+}
+
+fn function(
+    name: &str,
+    parameters: Vec<ExecutableParameterDeclaration>,
+    return_type: ExecutableTypeReference,
+    statements: Vec<ExecutableStatement>,
+) -> ExecutableFunctionDeclaration {
+    ExecutableFunctionDeclaration {
+        name: name.to_string(),
+        callable_reference: callable_reference(name),
+        type_parameter_names: Vec::new(),
+        type_parameter_constraint_interface_reference_by_name: BTreeMap::new(),
+        parameters,
+        return_type,
+        statements,
+    }
+}
+
+fn program(function_declarations: Vec<ExecutableFunctionDeclaration>) -> ExecutableProgram {
+    ExecutableProgram {
+        entrypoint_callable_reference: callable_reference("main"),
+        constant_declarations: Vec::new(),
+        interface_declarations: Vec::new(),
+        struct_declarations: Vec::new(),
+        function_declarations,
+        layout_table: ExecutableLayoutTable {
+            struct_layouts: Vec::new(),
+            enum_layout: ExecutableEnumLayout { size_bytes: 8 },
+            union_layout: ExecutableUnionLayout {
+                size_bytes: 16,
+                tag_offset_bytes: 0,
+                payload_offset_bytes: 8,
+            },
+            list_layout: ExecutableListLayout {
+                header_size_bytes: 16,
+                length_offset_bytes: 0,
+                data_pointer_offset_bytes: 8,
+                element_stride_bytes: 8,
+            },
+        },
+    }
+}
+
+// function scale(value: int64) -> int64 { return host_factor() * value }
+// function item(index: int64) -> int64 { return [10, 20][index] }
+fn host_program() -> ExecutableProgram {
+    let scale = function(
+        "scale",
+        vec![parameter("value", ExecutableTypeReference::Int64)],
+        ExecutableTypeReference::Int64,
+        vec![ExecutableStatement::Return {
+            value: ExecutableExpression::Binary {
+                operator: ExecutableBinaryOperator::Multiply,
+                left: Box::new(ExecutableExpression::Call {
+                    callee: Box::new(identifier(
+                        "host_factor",
+                        ExecutableTypeReference::Function {
+                            parameter_types: Vec::new(),
+                            return_type: Box::new(ExecutableTypeReference::Int64),
+                        },
+                    )),
+                    call_target: Some(ExecutableCallTarget::BuiltinFunction {
+                        function_name: "host_factor".to_string(),
+                    }),
+                    arguments: Vec::new(),
+                    type_arguments: Vec::new(),
+                }),
+                right: Box::new(identifier("value", ExecutableTypeReference::Int64)),
+                operation_description: None,
+            },
+        }],
+    );
+    let item = function(
+        "item",
+        vec![parameter("index", ExecutableTypeReference::Int64)],
+        ExecutableTypeReference::Int64,
+        vec![ExecutableStatement::Return {
+            value: ExecutableExpression::IndexAccess {
+                target: Box::new(ExecutableExpression::ListLiteral {
+                    elements: vec![
+                        ExecutableExpression::IntegerLiteral { value: 10 },
+                        ExecutableExpression::IntegerLiteral { value: 20 },
+                    ],
+                    element_type: ExecutableTypeReference::Int64,
+                }),
+                index: Box::new(identifier("index", ExecutableTypeReference::Int64)),
+            },
+        }],
+    );
+    let main = function(
+        "main",
+        Vec::new(),
+        ExecutableTypeReference::Nil,
+        vec![ExecutableStatement::Return {
+            value: ExecutableExpression::NilLiteral,
+        }],
+    );
+    program(vec![scale, item, main])
+}
+
+#[test]
+fn calls_functions_that_call_host_functions() {
+    let call_count = Rc::new(RefCell::new(0));
+    let mut interpreter = Interpreter::new(host_program()).expect("program should be valid");
+    let host_call_count = Rc::clone(&call_count);
+    interpreter
+        .register_host_function(
+            "host_factor",
+            HostFunctionSignature {
+                parameter_types: Vec::new(),
+                return_type: RuntimeType::Int64,
+            },
+            Box::new(move |_| {
+                *host_call_count.borrow_mut() += 1;
+                Ok(RuntimeValue::Int64(3))
+            }),
+        )
+        .expect("host function should register");
+
+    let result = interpreter
+        .call_function("scale", &[RuntimeValue::Int64(7)])
+        .expect("call should succeed");
+    assert_eq!(result, RuntimeValue::Int64(21));
+    assert_eq!(*call_count.borrow(), 1);
+}
+
+#[test]
+fn host_function_errors_and_out_of_bounds_indexes_abort_the_call() {
+    let mut interpreter = Interpreter::new(host_program()).expect("program should be valid");
+    interpreter
+        .register_host_function(
+            "host_factor",
+            HostFunctionSignature {
+                parameter_types: Vec::new(),
+                return_type: RuntimeType::Int64,
+            },
+            Box::new(|_| Err("factor unavailable".to_string())),
+        )
+        .expect("host function should register");
+
+    let failure = interpreter
+        .call_function("scale", &[RuntimeValue::Int64(7)])
+        .expect_err("host error should abort");
+    assert_eq!(failure.message, "factor unavailable");
+
+    assert_eq!(
+        interpreter
+            .call_function("item", &[RuntimeValue::Int64(1)])
+            .expect("in-bounds index should succeed"),
+        RuntimeValue::Int64(20)
+    );
+    let failure = interpreter
+        .call_function("item", &[RuntimeValue::Int64(2)])
+        .expect_err("out-of-bounds index should abort");
+    assert_eq!(failure.message, "list index 2 out of bounds for length 2");
+}
+
+// Shift amounts are masked to the low six bits, like native shifts.
+#[test]
+fn masks_shift_amounts_to_the_operand_width() {
+    // function shift(value: int64, amount: int64) -> int64 { return value << amount }
+    let shift = function(
+        "shift",
+        vec![
+            parameter("value", ExecutableTypeReference::Int64),
+            parameter("amount", ExecutableTypeReference::Int64),
+        ],
+        ExecutableTypeReference::Int64,
+        vec![ExecutableStatement::Return {
+            value: ExecutableExpression::Binary {
+                operator: ExecutableBinaryOperator::ShiftLeft,
+                left: Box::new(identifier("value", ExecutableTypeReference::Int64)),
+                right: Box::new(identifier("amount", ExecutableTypeReference::Int64)),
+                operation_description: None,
+            },
+        }],
+    );
+    let mut shift_program = host_program();
+    shift_program.function_declarations.push(shift);
+    let mut interpreter = Interpreter::new(shift_program).expect("program should be valid");
+
+    for (amount, expected) in [(2, 12), (64, 3), (65, 6), (-1, i64::MIN)] {
+        assert_eq!(
+            interpreter
+                .call_function(
+                    "shift",
+                    &[RuntimeValue::Int64(3), RuntimeValue::Int64(amount)]
+                )
+                .expect("call should succeed"),
+            RuntimeValue::Int64(expected)
+        );
+    }
+}
+
+#[test]
+fn rejects_arguments_and_replacements_that_do_not_match_signatures() {
+    let mut interpreter = Interpreter::new(host_program()).expect("program should be valid");
+    assert!(
+        interpreter
+            .call_function("scale", &[RuntimeValue::Boolean(true)])
+            .is_err()
+    );
+    assert!(
+        interpreter
+            .register_host_function(
+                "print",
+                HostFunctionSignature {
+                    parameter_types: vec![RuntimeType::Int64],
+                    return_type: RuntimeType::Nil,
+                },
+                Box::new(|_| Ok(RuntimeValue::Nil)),
+            )
+            .is_err()
+    );
+    let failure = interpreter
+        .register_host_function(
+            "checked_add",
+            HostFunctionSignature {
+                parameter_types: vec![RuntimeType::Int64, RuntimeType::Int64],
+                return_type: RuntimeType::Int64,
+            },
+            Box::new(|_| Ok(RuntimeValue::Nil)),
+        )
+        .expect_err("a replacement must return int64 | nil like checked_add");
+    assert_eq!(
+        failure.message,
+        "host function 'checked_add' must match the signature of the builtin it replaces"
+    );
+    let failure = interpreter
+        .register_host_function(
+            "debug_string",
+            HostFunctionSignature {
+                parameter_types: vec![RuntimeType::Int64],
+                return_type: RuntimeType::String,
+            },
+            Box::new(|_| Ok(RuntimeValue::Nil)),
+        )
+        .expect_err("a builtin without a runtime contract should not be replaceable");
+    assert_eq!(
+        failure.message,
+        "host functions cannot replace the builtin 'debug_string', which has no runtime contract"
+    );
+}
+
+#[test]
+fn rejects_programs_that_fail_validation() {
+    let mut invalid_program = host_program();
+    // function caller() -> int64 { return scale() }
+    invalid_program.function_declarations.push(function(
+        "caller",
+        Vec::new(),
+        ExecutableTypeReference::Int64,
+        vec![ExecutableStatement::Return {
+            value: ExecutableExpression::Call {
+                callee: Box::new(identifier("scale", ExecutableTypeReference::Int64)),
+                call_target: Some(ExecutableCallTarget::UserDefinedFunction {
+                    callable_reference: callable_reference("scale"),
+                }),
+                arguments: Vec::new(),
+                type_arguments: Vec::new(),
+            },
+        }],
+    ));
+    let duplicate_item = invalid_program.function_declarations[1].clone();
+    invalid_program.function_declarations.push(duplicate_item);
+    invalid_program.entrypoint_callable_reference = callable_reference("start");
+
+    let Err(failure) = Interpreter::new(invalid_program) else {
+        panic!("invalid program should be rejected");
+    };
+    assert_eq!(
+        failure.message,
+        "program failed validation with 3 violation(s)"
+    );
+    assert_eq!(
+        failure
+            .details
+            .iter()
+            .map(|detail| detail.message.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "duplicate function 'item'",
+            "entrypoint 'start' is not a declared function",
+            "call to 'scale' passes 0 argument(s), but it takes 1",
+        ]
+    );
+}
@@ -0,0 +1,325 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use compiler__executable_program::{
+    ExecutableMatchLiteral, ExecutableStructDeclaration, ExecutableTypeReference,
+};
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__program_references::{
+    CallableReference, EnumVariantReference, NominalTypeReference, StructReference,
+};
+use compiler__runtime_interface::RuntimeType;
+
+use crate::RuntimeValue;
+
+// Lists and structs are shared by reference, as they are in native builds, so
+// an index assignment is visible through every alias of the list.
+#[derive(Clone, Debug)]
+pub(crate) enum Value {
+    Int64(i64),
+    Boolean(bool),
+    String(String),
+    Nil,
+    List(Rc<RefCell<Vec<Value>>>),
+    Struct(Rc<StructValue>),
+    EnumVariant(EnumVariantReference),
+    Function(CallableReference),
+}
+
+#[derive(Debug)]
+pub(crate) struct StructValue {
+    pub(crate) struct_reference: StructReference,
+    pub(crate) fields: Vec<(String, Value)>,
+}
+
+impl Value {
+    pub(crate) fn list(elements: Vec<Value>) -> Self {
+        Self::List(Rc::new(RefCell::new(elements)))
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            Self::Int64(_) => "int64",
+            Self::Boolean(_) => "boolean",
+            Self::String(_) => "string",
+            Self::Nil => "nil",
+            Self::List(_) => "list",
+            Self::Struct(_) => "struct",
+            Self::EnumVariant(_) => "enum variant",
+            Self::Function(_) => "function",
+        }
+    }
+}
+
+// Scalars and enum variants compare by value; lists, structs, and functions
+// compare by identity.
+pub(crate) fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Int64(left), Value::Int64(right)) => left == right,
+        (Value::Boolean(left), Value::Boolean(right)) => left == right,
+        (Value::String(left), Value::String(right)) => left == right,
+        (Value::Nil, Value::Nil) => true,
+        (Value::List(left), Value::List(right)) => Rc::ptr_eq(left, right),
+        (Value::Struct(left), Value::Struct(right)) => Rc::ptr_eq(left, right),
+        (Value::EnumVariant(left), Value::EnumVariant(right)) => left == right,
+        (Value::Function(left), Value::Function(right)) => left == right,
+        _ => false,
+    }
+}
+
+// How `debug_string` and `assert_equals` show a value, matching native
+// builds: strings are quoted and structs list their fields in declaration
+// order. Two values are equal for `assert_equals` when they render the same.
+pub(crate) fn render_value(value: &Value) -> String {
+    match value {
+        Value::Int64(value) => value.to_string(),
+        Value::Boolean(value) => value.to_string(),
+        Value::String(value) => format!("\"{value}\""),
+        Value::Nil => "nil".to_string(),
+        Value::List(elements) => format!(
+            "[{}]",
+            elements
+                .borrow()
+                .iter()
+                .map(render_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Struct(struct_value) => {
+            let struct_name = &struct_value.struct_reference.symbol_name;
+            if struct_value.fields.is_empty() {
+                return format!("{struct_name} {{}}");
+            }
+            let fields = struct_value
+                .fields
+                .iter()
+                .map(|(name, value)| format!("{name}: {}", render_value(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{struct_name} {{ {fields} }}")
+        }
+        Value::EnumVariant(enum_variant_reference) => format!(
+            "{}.{}",
+            enum_variant_reference.enum_name, enum_variant_reference.variant_name
+        ),
+        Value::Function(callable_reference) => callable_reference.symbol_name.to_string(),
+    }
+}
+
+pub(crate) fn value_matches_literal(value: &Value, literal: &ExecutableMatchLiteral) -> bool {
+    match (value, literal) {
+        (Value::Int64(value), ExecutableMatchLiteral::Integer(literal)) => value == literal,
+        (Value::String(value), ExecutableMatchLiteral::String(literal)) => value == literal,
+        (Value::Boolean(value), ExecutableMatchLiteral::Boolean(literal)) => value == literal,
+        _ => false,
+    }
+}
+
+// Whether a value inhabits a type, used for union matches and to validate
+// values crossing the host boundary. A struct inhabits the interfaces it
+// implements. List element types and type parameters are not checked.
+pub(crate) fn value_matches_type(
+    value: &Value,
+    type_reference: &ExecutableTypeReference,
+    struct_declarations: &[ExecutableStructDeclaration],
+) -> bool {
+    match type_reference {
+        ExecutableTypeReference::Int64 => matches!(value, Value::Int64(_)),
+        ExecutableTypeReference::Boolean => matches!(value, Value::Boolean(_)),
+        ExecutableTypeReference::String => matches!(value, Value::String(_)),
+        ExecutableTypeReference::Nil => matches!(value, Value::Nil),
+        ExecutableTypeReference::Never => false,
+        ExecutableTypeReference::List { .. } => matches!(value, Value::List(_)),
+        ExecutableTypeReference::Function { .. } => matches!(value, Value::Function(_)),
+        ExecutableTypeReference::TypeParameter { .. } => true,
+        ExecutableTypeReference::Union { members } => members
+            .iter()
+            .any(|member| value_matches_type(value, member, struct_declarations)),
+        ExecutableTypeReference::NominalType {
+            nominal_type_reference,
+            name,
+        } => match value {
+            Value::EnumVariant(enum_variant_reference) => {
+                *name == enum_variant_reference.enum_name
+                    || *name
+                        == format!(
+                            "{}.{}",
+                            enum_variant_reference.enum_name, enum_variant_reference.variant_name
+                        )
+            }
+            Value::Struct(struct_value) => struct_matches_nominal_type(
+                &struct_value.struct_reference,
+                nominal_type_reference.as_ref(),
+                name,
+                struct_declarations,
+            ),
+            _ => false,
+        },
+        ExecutableTypeReference::NominalTypeApplication {
+            base_nominal_type_reference,
+            base_name,
+            ..
+        } => match value {
+            Value::Struct(struct_value) => struct_matches_nominal_type(
+                &struct_value.struct_reference,
+                base_nominal_type_reference.as_ref(),
+                base_name,
+                struct_declarations,
+            ),
+            _ => false,
+        },
+    }
+}
+
+fn struct_matches_nominal_type(
+    struct_reference: &StructReference,
+    nominal_type_reference: Option<&NominalTypeReference>,
+    name: &str,
+    struct_declarations: &[ExecutableStructDeclaration],
+) -> bool {
+    let names_match = |package_path: &PackagePath, symbol_name: &SymbolName| {
+        nominal_type_reference.map_or(*symbol_name == name, |nominal_type_reference| {
+            nominal_type_reference.package_path == *package_path
+                && nominal_type_reference.symbol_name == *symbol_name
+        })
+    };
+    if names_match(
+        &struct_reference.package_path,
+        &struct_reference.symbol_name,
+    ) {
+        return true;
+    }
+    struct_declarations
+        .iter()
+        .find(|struct_declaration| struct_declaration.struct_reference == *struct_reference)
+        .is_some_and(|struct_declaration| {
+            struct_declaration
+                .implemented_interfaces
+                .iter()
+                .any(|interface_reference| {
+                    names_match(
+                        &interface_reference.package_path,
+                        &interface_reference.symbol_name,
+                    )
+                })
+        })
+}
+
+pub(crate) fn value_matches_runtime_type(value: &RuntimeValue, runtime_type: RuntimeType) -> bool {
+    match runtime_type {
+        RuntimeType::Boolean => matches!(value, RuntimeValue::Boolean(_)),
+        RuntimeType::Int64 => matches!(value, RuntimeValue::Int64(_)),
+        RuntimeType::Int64OrNil => matches!(value, RuntimeValue::Int64(_) | RuntimeValue::Nil),
+        RuntimeType::Nil => matches!(value, RuntimeValue::Nil),
+        RuntimeType::Never => false,
+        RuntimeType::String => matches!(value, RuntimeValue::String(_)),
+    }
+}
+
+pub(crate) fn runtime_value_from_value(value: &Value) -> Result<RuntimeValue, String> {
+    Ok(match value {
+        Value::Int64(value) => RuntimeValue::Int64(*value),
+        Value::Boolean(value) => RuntimeValue::Boolean(*value),
+        Value::String(value) => RuntimeValue::String(value.clone()),
+        Value::Nil => RuntimeValue::Nil,
+        Value::List(elements) => RuntimeValue::List(
+            elements
+                .borrow()
+                .iter()
+                .map(runtime_value_from_value)
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Struct(struct_value) => RuntimeValue::Struct {
+            type_name: struct_value.struct_reference.symbol_name.to_string(),
+            fields: struct_value
+                .fields
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), runtime_value_from_value(value)?)))
+                .collect::<Result<_, String>>()?,
+        },
+        Value::EnumVariant(enum_variant_reference) => RuntimeValue::EnumVariant {
+            enum_name: enum_variant_reference.enum_name.clone(),
+            variant_name: enum_variant_reference.variant_name.clone(),
+        },
+        Value::Function(callable_reference) => {
+            return Err(format!(
+                "function value '{}' cannot be passed to the host",
+                callable_reference.symbol_name
+            ));
+        }
+    })
+}
+
+// Struct values from the host are resolved by name, preferring a struct
+// declared in `package_path`, and must provide exactly the declared fields.
+pub(crate) fn value_from_runtime_value(
+    runtime_value: &RuntimeValue,
+    package_path: &PackagePath,
+    struct_declarations: &[ExecutableStructDeclaration],
+) -> Result<Value, String> {
+    Ok(match runtime_value {
+        RuntimeValue::Int64(value) => Value::Int64(*value),
+        RuntimeValue::Boolean(value) => Value::Boolean(*value),
+        RuntimeValue::String(value) => Value::String(value.clone()),
+        RuntimeValue::Nil => Value::Nil,
+        RuntimeValue::List(elements) => Value::list(
+            elements
+                .iter()
+                .map(|element| value_from_runtime_value(element, package_path, struct_declarations))
+                .collect::<Result<_, _>>()?,
+        ),
+        RuntimeValue::Struct { type_name, fields } => {
+            let mut candidates = struct_declarations
+                .iter()
+                .filter(|struct_declaration| struct_declaration.name == *type_name);
+            let struct_declaration = candidates
+                .clone()
+                .find(|struct_declaration| {
+                    struct_declaration.struct_reference.package_path == *package_path
+                })
+                .or_else(|| candidates.next())
+                .ok_or_else(|| format!("unknown struct '{type_name}'"))?;
+            if fields.len() != struct_declaration.fields.len() {
+                return Err(format!(
+                    "struct '{type_name}' expects {} field(s), got {}",
+                    struct_declaration.fields.len(),
+                    fields.len()
+                ));
+            }
+            let mut converted_fields = Vec::with_capacity(fields.len());
+            for declared_field in &struct_declaration.fields {
+                let (_, field_value) = fields
+                    .iter()
+                    .find(|(name, _)| *name == declared_field.name)
+                    .ok_or_else(|| {
+                        format!("missing field '{type_name}.{}'", declared_field.name)
+                    })?;
+                let field_value =
+                    value_from_runtime_value(field_value, package_path, struct_declarations)?;
+                if !value_matches_type(
+                    &field_value,
+                    &declared_field.type_reference,
+                    struct_declarations,
+                ) {
+                    return Err(format!(
+                        "field '{type_name}.{}' cannot hold a {} value",
+                        declared_field.name,
+                        field_value.description()
+                    ));
+                }
+                converted_fields.push((declared_field.name.clone(), field_value));
+            }
+            Value::Struct(Rc::new(StructValue {
+                struct_reference: struct_declaration.struct_reference.clone(),
+                fields: converted_fields,
+            }))
+        }
+        RuntimeValue::EnumVariant {
+            enum_name,
+            variant_name,
+        } => Value::EnumVariant(EnumVariantReference {
+            enum_name: enum_name.clone(),
+            variant_name: variant_name.clone(),
+        }),
+    })
+}
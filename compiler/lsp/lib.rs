@@ -1,25 +1,46 @@
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::{self, BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
 
-use compiler__analysis_session::AnalysisSession;
-use compiler__reports::{CompilerFailure, CompilerFailureKind, RenderedDiagnostic};
-use compiler__source::path_to_key;
+use compiler__analysis_session::{
+    AnalysisPhase, AnalysisProgress, AnalysisSession, AnalyzedTargetSummary, CancellationToken,
+    ProgressObserver,
+};
+use compiler__file_organization::{FileOrganizationOptions, organize_file_text_edits};
+use compiler__refactorings::{Refactoring, RefactoringKind};
+use compiler__reports::{CompilerFailure, CompilerFailureKind, RenderedDiagnostic, RenderedSpan};
+use compiler__source::{FileRole, LineIndex, Span, floor_char_boundary, path_to_key};
 use serde_json::{Value, json};
 
 pub fn run_lsp_stdio(workspace_root_override: Option<&str>) -> Result<(), CompilerFailure> {
-    let stdin = io::stdin();
+    let (message_sender, message_receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut reader = BufReader::new(stdin.lock());
+        read_lsp_messages(&mut reader, &message_sender);
+    });
     let stdout = io::stdout();
-    let mut reader = BufReader::new(stdin.lock());
     let mut writer = BufWriter::new(stdout.lock());
     let mut lsp_server = LspServer::new(workspace_root_override);
-    lsp_server.run(&mut reader, &mut writer)
+    lsp_server.run(&message_receiver, &mut writer)
+}
+
+struct IncomingMessage {
+    message: Value,
+    document_cancellation_token: Option<CancellationToken>,
 }
 
 struct LspServer {
     analysis_session: AnalysisSession,
     shutdown_requested: bool,
+    work_done_progress_supported: bool,
+    next_work_done_progress_id: u64,
     published_diagnostic_uri_set: BTreeSet<String>,
+    code_actions_by_uri: BTreeMap<String, Vec<Value>>,
+    refactoring_code_actions_by_uri: BTreeMap<String, Vec<RefactoringCodeAction>>,
     source_override_by_path: BTreeMap<String, String>,
 }
 
@@ -30,33 +51,39 @@ impl LspServer {
                 workspace_root_override.map(ToString::to_string),
             ),
             shutdown_requested: false,
+            work_done_progress_supported: false,
+            next_work_done_progress_id: 0,
             published_diagnostic_uri_set: BTreeSet::new(),
+            code_actions_by_uri: BTreeMap::new(),
+            refactoring_code_actions_by_uri: BTreeMap::new(),
             source_override_by_path: BTreeMap::new(),
         }
     }
 
-    fn run<R: BufRead, W: Write>(
+    fn run<W: Write>(
         &mut self,
-        reader: &mut R,
+        receiver: &Receiver<Result<IncomingMessage, CompilerFailure>>,
         writer: &mut W,
     ) -> Result<(), CompilerFailure> {
         loop {
-            let Some(message_bytes) = read_lsp_message(reader)? else {
+            let Ok(incoming_message) = receiver.recv() else {
                 return Ok(());
             };
-            let message: Value =
-                serde_json::from_slice(&message_bytes).map_err(|error| CompilerFailure {
-                    kind: CompilerFailureKind::RunFailed,
-                    message: format!("invalid lsp json payload: {error}"),
-                    path: None,
-                    details: Vec::new(),
-                })?;
+            let IncomingMessage {
+                message,
+                document_cancellation_token,
+            } = incoming_message?;
 
             if let Some(method) = message.get("method").and_then(Value::as_str) {
                 if message.get("id").is_some() {
                     self.handle_request(writer, &message, method)?;
                 } else {
-                    let should_exit = self.handle_notification(writer, &message, method)?;
+                    let should_exit = self.handle_notification(
+                        writer,
+                        &message,
+                        method,
+                        document_cancellation_token.as_ref(),
+                    )?;
                     if should_exit {
                         if self.shutdown_requested {
                             return Ok(());
@@ -83,12 +110,17 @@ impl LspServer {
         let id = message.get("id").cloned().unwrap_or(Value::Null);
         match method {
             "initialize" => {
+                self.work_done_progress_supported = message
+                    .pointer("/params/capabilities/window/workDoneProgress")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
                 let result = json!({
                     "capabilities": {
                         "textDocumentSync": {
                             "openClose": true,
                             "change": 1
-                        }
+                        },
+                        "codeActionProvider": true
                     },
                     "serverInfo": {
                         "name": "coppice-lsp",
@@ -115,6 +147,17 @@ impl LspServer {
                     }),
                 )
             }
+            "textDocument/codeAction" => {
+                let code_actions = self.code_actions_for_request(message);
+                write_lsp_message(
+                    writer,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": code_actions,
+                    }),
+                )
+            }
             _ => write_lsp_message(
                 writer,
                 &json!({
@@ -134,6 +177,7 @@ impl LspServer {
         writer: &mut W,
         message: &Value,
         method: &str,
+        cancellation_token: Option<&CancellationToken>,
     ) -> Result<bool, CompilerFailure> {
         match method {
             "exit" => Ok(true),
@@ -150,7 +194,12 @@ impl LspServer {
                 let Some(text) = text_document.get("text").and_then(Value::as_str) else {
                     return Ok(false);
                 };
-                self.update_document_and_publish(writer, uri, text.to_string())?;
+                self.update_document_and_publish(
+                    writer,
+                    uri,
+                    text.to_string(),
+                    cancellation_token,
+                )?;
                 Ok(false)
             }
             "textDocument/didChange" => {
@@ -174,7 +223,12 @@ impl LspServer {
                 else {
                     return Ok(false);
                 };
-                self.update_document_and_publish(writer, uri, new_text.to_string())?;
+                self.update_document_and_publish(
+                    writer,
+                    uri,
+                    new_text.to_string(),
+                    cancellation_token,
+                )?;
                 Ok(false)
             }
             "textDocument/didClose" => {
@@ -187,7 +241,7 @@ impl LspServer {
                 let Some(uri) = text_document.get("uri").and_then(Value::as_str) else {
                     return Ok(false);
                 };
-                self.close_document_and_publish(writer, uri)?;
+                self.close_document_and_publish(writer, uri, cancellation_token)?;
                 Ok(false)
             }
             _ => Ok(false),
@@ -199,6 +253,7 @@ impl LspServer {
         writer: &mut W,
         uri: &str,
         text: String,
+        cancellation_token: Option<&CancellationToken>,
     ) -> Result<(), CompilerFailure> {
         let Some(absolute_path) = uri_to_file_path(uri) else {
             return Ok(());
@@ -208,13 +263,14 @@ impl LspServer {
             .open_or_update_document(&target_path, text.clone());
         self.source_override_by_path
             .insert(target_path.clone(), text);
-        self.reanalyze_target_and_publish(writer, &target_path)
+        self.reanalyze_target_and_publish(writer, &target_path, cancellation_token)
     }
 
     fn close_document_and_publish<W: Write>(
         &mut self,
         writer: &mut W,
         uri: &str,
+        cancellation_token: Option<&CancellationToken>,
     ) -> Result<(), CompilerFailure> {
         let Some(absolute_path) = uri_to_file_path(uri) else {
             return Ok(());
@@ -222,20 +278,33 @@ impl LspServer {
         let target_path = path_to_key(&absolute_path);
         self.analysis_session.close_document(&target_path);
         self.source_override_by_path.remove(&target_path);
-        self.reanalyze_target_and_publish(writer, &target_path)
+        self.reanalyze_target_and_publish(writer, &target_path, cancellation_token)
     }
 
     fn reanalyze_target_and_publish<W: Write>(
         &mut self,
         writer: &mut W,
         target_path: &str,
+        cancellation_token: Option<&CancellationToken>,
     ) -> Result<(), CompilerFailure> {
-        match self.analysis_session.analyze_target(target_path) {
-            Ok(analyzed_target_summary) => self.publish_analyzed_target(
-                writer,
-                analyzed_target_summary.diagnostics,
-                target_path,
-            ),
+        let analysis_result = if self.work_done_progress_supported {
+            self.analyze_target_with_work_done_progress(writer, target_path, cancellation_token)?
+        } else {
+            self.analysis_session
+                .analyze_target(target_path, None, cancellation_token)
+        };
+        match analysis_result {
+            Ok(analyzed_target_summary) => {
+                self.record_refactoring_code_actions(&analyzed_target_summary.refactorings_by_path);
+                self.publish_analyzed_target(
+                    writer,
+                    analyzed_target_summary.diagnostics,
+                    target_path,
+                )
+            }
+            // A newer change to the document has been received and its
+            // analysis will publish instead.
+            Err(error) if matches!(error.kind, CompilerFailureKind::Cancelled) => Ok(()),
             Err(error) => {
                 Self::publish_log_message(writer, &error.message)?;
                 if let Some(target_uri) = Self::path_to_uri(target_path) {
@@ -247,6 +316,59 @@ impl LspServer {
         }
     }
 
+    // The progress is used without waiting for the client to answer the create
+    // request; responses are ignored like every other client response.
+    fn analyze_target_with_work_done_progress<W: Write>(
+        &mut self,
+        writer: &mut W,
+        target_path: &str,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<Result<AnalyzedTargetSummary, CompilerFailure>, CompilerFailure> {
+        let token = format!("coppice/analysis/{}", self.next_work_done_progress_id);
+        self.next_work_done_progress_id += 1;
+        write_lsp_message(
+            writer,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": token,
+                "method": "window/workDoneProgress/create",
+                "params": {
+                    "token": token,
+                },
+            }),
+        )?;
+        publish_work_done_progress(
+            writer,
+            &token,
+            &json!({
+                "kind": "begin",
+                "title": "Analyzing",
+                "percentage": 0,
+            }),
+        )?;
+        let progress_observer = WorkDoneProgressObserver {
+            writer: RefCell::new(writer),
+            token: &token,
+            last_reported_percentage: Cell::new(0),
+            write_failure: RefCell::new(None),
+        };
+        let analysis_result = self.analysis_session.analyze_target(
+            target_path,
+            Some(&progress_observer),
+            cancellation_token,
+        );
+        let WorkDoneProgressObserver {
+            writer,
+            write_failure,
+            ..
+        } = progress_observer;
+        if let Some(write_failure) = write_failure.into_inner() {
+            return Err(write_failure);
+        }
+        publish_work_done_progress(writer.into_inner(), &token, &json!({ "kind": "end" }))?;
+        Ok(analysis_result)
+    }
+
     fn publish_analyzed_target<W: Write>(
         &mut self,
         writer: &mut W,
@@ -255,6 +377,7 @@ impl LspServer {
     ) -> Result<(), CompilerFailure> {
         let mut diagnostics_by_uri = BTreeMap::<String, Vec<Value>>::new();
         let mut source_by_diagnostic_path = BTreeMap::<String, Option<String>>::new();
+        let mut code_actions_by_uri = BTreeMap::<String, Vec<Value>>::new();
         for diagnostic in diagnostics {
             let Some(uri) = Self::diagnostic_path_to_uri(&diagnostic.path) else {
                 continue;
@@ -262,13 +385,17 @@ impl LspServer {
             let source = source_by_diagnostic_path
                 .entry(diagnostic.path.clone())
                 .or_insert_with(|| self.load_source_for_diagnostic_path(&diagnostic.path));
+            let lsp_diagnostic =
+                rendered_diagnostic_to_lsp_diagnostic(&diagnostic, &uri, source.as_deref());
+            if let Some(source) = source.as_deref() {
+                code_actions_by_uri.entry(uri.clone()).or_default().extend(
+                    suggested_fix_code_actions(&diagnostic, &lsp_diagnostic, &uri, source),
+                );
+            }
             diagnostics_by_uri
                 .entry(uri)
                 .or_default()
-                .push(rendered_diagnostic_to_lsp_diagnostic(
-                    &diagnostic,
-                    source.as_deref(),
-                ));
+                .push(lsp_diagnostic);
         }
 
         if let Some(target_uri) = Self::path_to_uri(target_path) {
@@ -290,9 +417,107 @@ impl LspServer {
         }
 
         self.published_diagnostic_uri_set = current_uri_set;
+        self.code_actions_by_uri = code_actions_by_uri;
         Ok(())
     }
 
+    fn record_refactoring_code_actions(
+        &mut self,
+        refactorings_by_path: &BTreeMap<String, Vec<Refactoring>>,
+    ) {
+        let mut refactoring_code_actions_by_uri = BTreeMap::new();
+        for (path, refactorings) in refactorings_by_path {
+            let Some(uri) = Self::diagnostic_path_to_uri(path) else {
+                continue;
+            };
+            let Some(source) = self.load_source_for_diagnostic_path(path) else {
+                continue;
+            };
+            let refactoring_code_actions = refactorings
+                .iter()
+                .map(|refactoring| refactoring_code_action(refactoring, &uri, &source))
+                .collect::<Vec<_>>();
+            refactoring_code_actions_by_uri.insert(uri, refactoring_code_actions);
+        }
+        self.refactoring_code_actions_by_uri = refactoring_code_actions_by_uri;
+    }
+
+    fn code_actions_for_request(&self, message: &Value) -> Vec<Value> {
+        let params = message.get("params");
+        let Some(uri) = params
+            .and_then(|params| params.get("textDocument"))
+            .and_then(|text_document| text_document.get("uri"))
+            .and_then(Value::as_str)
+        else {
+            return Vec::new();
+        };
+        let Some(requested_range) = params
+            .and_then(|params| params.get("range"))
+            .and_then(lsp_range_bounds)
+        else {
+            return Vec::new();
+        };
+        let mut code_actions = self
+            .code_actions_by_uri
+            .get(uri)
+            .map(|code_actions| {
+                code_actions
+                    .iter()
+                    .filter(|code_action| {
+                        code_action["diagnostics"][0]
+                            .get("range")
+                            .is_some_and(|range| lsp_range_overlaps(range, requested_range))
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if let Some(refactoring_code_actions) = self.refactoring_code_actions_by_uri.get(uri) {
+            code_actions.extend(
+                refactoring_code_actions
+                    .iter()
+                    .filter(|refactoring_code_action| {
+                        refactoring_code_action
+                            .trigger_ranges
+                            .iter()
+                            .any(|range| lsp_range_overlaps(range, requested_range))
+                    })
+                    .map(|refactoring_code_action| refactoring_code_action.code_action.clone()),
+            );
+        }
+        code_actions.extend(self.organize_file_code_action(uri));
+        code_actions
+    }
+
+    fn organize_file_code_action(&self, uri: &str) -> Option<Value> {
+        let absolute_path = uri_to_file_path(uri)?;
+        let role = FileRole::from_path(&absolute_path)?;
+        let source = self.load_source_for_diagnostic_path(&path_to_key(&absolute_path))?;
+        let text_edits =
+            organize_file_text_edits(&source, role, &FileOrganizationOptions::default()).ok()?;
+        if text_edits.is_empty() {
+            return None;
+        }
+        let text_edits = text_edits
+            .iter()
+            .map(|text_edit| {
+                lsp_text_edit(
+                    &source,
+                    text_edit.start_byte_offset,
+                    text_edit.end_byte_offset,
+                    &text_edit.replacement_text,
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(json!({
+            "title": "Organize file",
+            "kind": "source.organizeFile",
+            "edit": {
+                "changes": { uri: text_edits },
+            },
+        }))
+    }
+
     fn publish_log_message<W: Write>(writer: &mut W, message: &str) -> Result<(), CompilerFailure> {
         write_lsp_message(
             writer,
@@ -366,32 +591,246 @@ impl LspServer {
     }
 }
 
+// A refactoring is offered when the requested range touches any of its
+// trigger spans, such as the declaration name or one of its references.
+struct RefactoringCodeAction {
+    trigger_ranges: Vec<Value>,
+    code_action: Value,
+}
+
+// Reports only when the overall percentage changes, because the pipeline reports
+// after every file of every phase.
+struct WorkDoneProgressObserver<'a, W: Write> {
+    writer: RefCell<&'a mut W>,
+    token: &'a str,
+    last_reported_percentage: Cell<usize>,
+    write_failure: RefCell<Option<CompilerFailure>>,
+}
+
+impl<W: Write> ProgressObserver for WorkDoneProgressObserver<'_, W> {
+    fn analysis_progressed(&self, progress: AnalysisProgress) {
+        const PHASE_COUNT: usize = 5;
+        let (phase_index, phase_title) = match progress.phase {
+            AnalysisPhase::Parsing => (0, "parsing"),
+            AnalysisPhase::SyntaxRules => (1, "checking syntax"),
+            AnalysisPhase::Resolution => (2, "resolving imports"),
+            AnalysisPhase::SemanticLowering => (3, "lowering"),
+            AnalysisPhase::TypeAnalysis => (4, "type checking"),
+        };
+        let percentage = (phase_index * 100
+            + progress.completed_file_count * 100 / progress.file_count.max(1))
+            / PHASE_COUNT;
+        if percentage == self.last_reported_percentage.get()
+            || self.write_failure.borrow().is_some()
+        {
+            return;
+        }
+        self.last_reported_percentage.set(percentage);
+        let result = publish_work_done_progress(
+            *self.writer.borrow_mut(),
+            self.token,
+            &json!({
+                "kind": "report",
+                "message": format!(
+                    "{phase_title} ({}/{} packages)",
+                    progress.completed_package_count, progress.package_count
+                ),
+                "percentage": percentage,
+            }),
+        );
+        if let Err(error) = result {
+            *self.write_failure.borrow_mut() = Some(error);
+        }
+    }
+}
+
+fn publish_work_done_progress<W: Write>(
+    writer: &mut W,
+    token: &str,
+    value: &Value,
+) -> Result<(), CompilerFailure> {
+    write_lsp_message(
+        writer,
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "$/progress",
+            "params": {
+                "token": token,
+                "value": value,
+            },
+        }),
+    )
+}
+
 fn rendered_diagnostic_to_lsp_diagnostic(
     diagnostic: &RenderedDiagnostic,
+    uri: &str,
     source: Option<&str>,
 ) -> Value {
-    let ((start_line, start_character), (end_line, end_character)) =
-        if let Some(source_text) = source {
-            span_to_lsp_range(source_text, diagnostic.span.start, diagnostic.span.end)
-        } else {
-            let line = diagnostic.span.line.saturating_sub(1);
-            let character = diagnostic.span.column.saturating_sub(1);
-            ((line, character), (line, character + 1))
-        };
+    let mut lsp_diagnostic = json!({
+        "range": rendered_span_to_lsp_range_value(&diagnostic.span, source),
+        "severity": if diagnostic.is_error() { 1 } else { 2 },
+        "source": "coppice",
+        "message": diagnostic.message,
+    });
+    if !diagnostic.related_spans.is_empty() {
+        lsp_diagnostic["relatedInformation"] = diagnostic
+            .related_spans
+            .iter()
+            .map(|related_span| {
+                // Related spans in another file are placed by their line and
+                // column, since that file's source is not loaded here.
+                let (related_uri, related_source) = match &related_span.path {
+                    Some(related_path) => (
+                        LspServer::diagnostic_path_to_uri(related_path)
+                            .unwrap_or_else(|| uri.to_string()),
+                        None,
+                    ),
+                    None => (uri.to_string(), source),
+                };
+                json!({
+                    "location": {
+                        "uri": related_uri,
+                        "range": rendered_span_to_lsp_range_value(&related_span.span, related_source),
+                    },
+                    "message": related_span.message,
+                })
+            })
+            .collect();
+    }
+    lsp_diagnostic
+}
+
+fn suggested_fix_code_actions(
+    diagnostic: &RenderedDiagnostic,
+    lsp_diagnostic: &Value,
+    uri: &str,
+    source: &str,
+) -> Vec<Value> {
+    diagnostic
+        .suggested_fixes
+        .iter()
+        .map(|suggested_fix| {
+            let text_edits = suggested_fix
+                .text_edits
+                .iter()
+                .map(|text_edit| {
+                    lsp_text_edit(
+                        source,
+                        text_edit.span.start,
+                        text_edit.span.end,
+                        &text_edit.replacement_text,
+                    )
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "title": suggested_fix.label,
+                "kind": "quickfix",
+                "diagnostics": [lsp_diagnostic],
+                "edit": {
+                    "changes": { uri: text_edits },
+                },
+            })
+        })
+        .collect()
+}
+
+fn refactoring_code_action(
+    refactoring: &Refactoring,
+    uri: &str,
+    source: &str,
+) -> RefactoringCodeAction {
+    let text_edits = refactoring
+        .text_edits
+        .iter()
+        .map(|text_edit| {
+            lsp_text_edit(
+                source,
+                text_edit.start_byte_offset,
+                text_edit.end_byte_offset,
+                &text_edit.replacement_text,
+            )
+        })
+        .collect::<Vec<_>>();
+    let kind = match refactoring.kind {
+        RefactoringKind::Inline => "refactor.inline",
+    };
+    RefactoringCodeAction {
+        trigger_ranges: refactoring
+            .trigger_spans
+            .iter()
+            .map(|span| span_to_lsp_range_value(span, source))
+            .collect(),
+        code_action: json!({
+            "title": refactoring.label,
+            "kind": kind,
+            "edit": {
+                "changes": { uri: text_edits },
+            },
+        }),
+    }
+}
+
+fn lsp_text_edit(
+    source: &str,
+    start_byte_offset: usize,
+    end_byte_offset: usize,
+    replacement_text: &str,
+) -> Value {
+    let line_index = LineIndex::new(source);
+    let start = line_index.utf16_position(start_byte_offset);
+    let end = line_index.utf16_position(end_byte_offset);
     json!({
         "range": {
-            "start": {
-                "line": start_line,
-                "character": start_character,
-            },
-            "end": {
-                "line": end_line,
-                "character": end_character,
-            },
+            "start": { "line": start.0, "character": start.1 },
+            "end": { "line": end.0, "character": end.1 },
+        },
+        "newText": replacement_text,
+    })
+}
+
+fn lsp_range_bounds(range: &Value) -> Option<((u64, u64), (u64, u64))> {
+    let position = |position: &Value| {
+        Some((
+            position.get("line")?.as_u64()?,
+            position.get("character")?.as_u64()?,
+        ))
+    };
+    Some((position(range.get("start")?)?, position(range.get("end")?)?))
+}
+
+fn lsp_range_overlaps(range: &Value, requested_range: ((u64, u64), (u64, u64))) -> bool {
+    lsp_range_bounds(range)
+        .is_some_and(|range| range.0 <= requested_range.1 && requested_range.0 <= range.1)
+}
+
+fn rendered_span_to_lsp_range_value(span: &RenderedSpan, source: Option<&str>) -> Value {
+    let range = if let Some(source_text) = source {
+        span_to_lsp_range(source_text, span.start, span.end)
+    } else {
+        let line = span.line.saturating_sub(1);
+        let character = span.column.saturating_sub(1);
+        ((line, character), (line, character + 1))
+    };
+    lsp_range_value(range)
+}
+
+fn span_to_lsp_range_value(span: &Span, source: &str) -> Value {
+    lsp_range_value(span_to_lsp_range(source, span.start, span.end))
+}
+
+fn lsp_range_value(
+    ((start_line, start_character), (end_line, end_character)): ((usize, usize), (usize, usize)),
+) -> Value {
+    json!({
+        "start": {
+            "line": start_line,
+            "character": start_character,
+        },
+        "end": {
+            "line": end_line,
+            "character": end_character,
         },
-        "severity": 1,
-        "source": "coppice",
-        "message": diagnostic.message,
     })
 }
 
@@ -400,8 +839,8 @@ fn span_to_lsp_range(
     raw_start_byte_offset: usize,
     raw_end_byte_offset: usize,
 ) -> ((usize, usize), (usize, usize)) {
-    let start_byte_offset = clamp_to_char_boundary(source, raw_start_byte_offset);
-    let mut end_byte_offset = clamp_to_char_boundary(source, raw_end_byte_offset);
+    let start_byte_offset = floor_char_boundary(source, raw_start_byte_offset);
+    let mut end_byte_offset = floor_char_boundary(source, raw_end_byte_offset);
     if end_byte_offset < start_byte_offset {
         end_byte_offset = start_byte_offset;
     }
@@ -409,29 +848,13 @@ fn span_to_lsp_range(
         end_byte_offset =
             next_char_boundary(source, start_byte_offset).unwrap_or(start_byte_offset);
     }
+    let line_index = LineIndex::new(source);
     (
-        byte_offset_to_lsp_position(source, start_byte_offset),
-        byte_offset_to_lsp_position(source, end_byte_offset),
+        line_index.utf16_position(start_byte_offset),
+        line_index.utf16_position(end_byte_offset),
     )
 }
 
-fn byte_offset_to_lsp_position(source: &str, raw_byte_offset: usize) -> (usize, usize) {
-    let byte_offset = clamp_to_char_boundary(source, raw_byte_offset);
-    let prefix = &source[..byte_offset];
-    let line = prefix.bytes().filter(|byte| *byte == b'\n').count();
-    let line_start_byte_offset = prefix.rfind('\n').map_or(0, |index| index + 1);
-    let utf16_character = prefix[line_start_byte_offset..].encode_utf16().count();
-    (line, utf16_character)
-}
-
-fn clamp_to_char_boundary(source: &str, raw_byte_offset: usize) -> usize {
-    let mut byte_offset = raw_byte_offset.min(source.len());
-    while byte_offset > 0 && !source.is_char_boundary(byte_offset) {
-        byte_offset -= 1;
-    }
-    byte_offset
-}
-
 fn next_char_boundary(source: &str, byte_offset: usize) -> Option<usize> {
     if byte_offset >= source.len() {
         return None;
@@ -442,6 +865,62 @@ fn next_char_boundary(source: &str, byte_offset: usize) -> Option<usize> {
         .map(|character| byte_offset + character.len_utf8())
 }
 
+// Runs on its own thread so that a newer change to a document cancels the
+// analysis of its previous text while that analysis is still running.
+fn read_lsp_messages<R: BufRead>(
+    reader: &mut R,
+    sender: &Sender<Result<IncomingMessage, CompilerFailure>>,
+) {
+    let mut cancellation_token_by_document_uri = BTreeMap::<String, CancellationToken>::new();
+    loop {
+        let incoming_message = match read_lsp_message(reader) {
+            Ok(Some(message_bytes)) => parse_lsp_message(&message_bytes).map(|message| {
+                let document_cancellation_token = document_change_uri(&message).map(|uri| {
+                    let cancellation_token = CancellationToken::new();
+                    if let Some(previous_cancellation_token) = cancellation_token_by_document_uri
+                        .insert(uri.to_string(), cancellation_token.clone())
+                    {
+                        previous_cancellation_token.cancel();
+                    }
+                    cancellation_token
+                });
+                IncomingMessage {
+                    message,
+                    document_cancellation_token,
+                }
+            }),
+            Ok(None) => return,
+            Err(error) => Err(error),
+        };
+        let is_failure = incoming_message.is_err();
+        if sender.send(incoming_message).is_err() || is_failure {
+            return;
+        }
+    }
+}
+
+fn document_change_uri(message: &Value) -> Option<&str> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    if !matches!(
+        method,
+        "textDocument/didOpen" | "textDocument/didChange" | "textDocument/didClose"
+    ) {
+        return None;
+    }
+    message
+        .pointer("/params/textDocument/uri")
+        .and_then(Value::as_str)
+}
+
+fn parse_lsp_message(message_bytes: &[u8]) -> Result<Value, CompilerFailure> {
+    serde_json::from_slice(message_bytes).map_err(|error| CompilerFailure {
+        kind: CompilerFailureKind::RunFailed,
+        message: format!("invalid lsp json payload: {error}"),
+        path: None,
+        details: Vec::new(),
+    })
+}
+
 fn read_lsp_message<R: BufRead>(reader: &mut R) -> Result<Option<Vec<u8>>, CompilerFailure> {
     let mut content_length: Option<usize> = None;
     loop {
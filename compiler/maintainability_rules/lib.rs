@@ -0,0 +1,186 @@
+use compiler__diagnostics::PhaseDiagnostic;
+use compiler__phase_results::{PhaseOutput, PhaseStatus};
+use compiler__semantic_program::{
+    SemanticBlock, SemanticDeclaration, SemanticFile, SemanticParameterDeclaration,
+    SemanticTypeDeclarationKind, SemanticVisitor, visit_block_children,
+};
+use compiler__source::{LineIndex, Span};
+
+// Like every other rule the compiler enforces, the limits are fixed rather
+// than configured per workspace.
+const MAXIMUM_FUNCTION_LINE_COUNT: usize = 100;
+const MAXIMUM_NESTING_DEPTH: usize = 5;
+const MAXIMUM_PARAMETER_COUNT: usize = 6;
+
+#[derive(Clone, Copy)]
+enum MaintainabilityViolationKind {
+    FunctionLineCount,
+    NestingDepth,
+    ParameterCount,
+}
+
+struct MaintainabilityViolation<'a> {
+    kind: MaintainabilityViolationKind,
+    callable_kind: &'static str,
+    name: &'a str,
+    measured: usize,
+    limit: usize,
+    span: Span,
+}
+
+struct Callable<'a> {
+    kind: &'static str,
+    name: &'a str,
+    parameters: &'a [SemanticParameterDeclaration],
+    body: &'a SemanticBlock,
+    span: Span,
+}
+
+// Violations only describe how a function is written, so they never keep
+// later phases from checking it.
+#[must_use]
+pub fn check_file(file: &SemanticFile, source_text: &str) -> PhaseOutput<()> {
+    let line_index = LineIndex::new(source_text);
+    let mut violations = Vec::new();
+    for callable in callables(file) {
+        check_callable(&callable, source_text, &line_index, &mut violations);
+    }
+    PhaseOutput {
+        value: (),
+        diagnostics: render_diagnostics(&violations),
+        safe_autofixes: Vec::new(),
+        status: PhaseStatus::Ok,
+    }
+}
+
+fn render_diagnostics(violations: &[MaintainabilityViolation<'_>]) -> Vec<PhaseDiagnostic> {
+    violations
+        .iter()
+        .map(|violation| {
+            let MaintainabilityViolation {
+                callable_kind,
+                name,
+                measured,
+                limit,
+                ..
+            } = violation;
+            let message = match violation.kind {
+                MaintainabilityViolationKind::FunctionLineCount => format!(
+                    "{callable_kind} '{name}' spans {measured} lines, more than the limit of {limit}"
+                ),
+                MaintainabilityViolationKind::NestingDepth => format!(
+                    "{callable_kind} '{name}' nests blocks {measured} deep, more than the limit of {limit}"
+                ),
+                MaintainabilityViolationKind::ParameterCount => format!(
+                    "{callable_kind} '{name}' has {measured} parameters, more than the limit of {limit}"
+                ),
+            };
+            PhaseDiagnostic::new(message, violation.span)
+        })
+        .collect()
+}
+
+fn callables(file: &SemanticFile) -> Vec<Callable<'_>> {
+    let mut callables = Vec::new();
+    for declaration in &file.declarations {
+        match declaration {
+            SemanticDeclaration::Function(function_declaration) => callables.push(Callable {
+                kind: "function",
+                name: &function_declaration.name,
+                parameters: &function_declaration.parameters,
+                body: &function_declaration.body,
+                span: function_declaration.span,
+            }),
+            SemanticDeclaration::Type(type_declaration) => {
+                if let SemanticTypeDeclarationKind::Struct { methods, .. } = &type_declaration.kind
+                {
+                    callables.extend(methods.iter().map(|method| Callable {
+                        kind: "method",
+                        name: &method.name,
+                        parameters: &method.parameters,
+                        body: &method.body,
+                        span: method.span,
+                    }));
+                }
+            }
+            SemanticDeclaration::Constant(_) | SemanticDeclaration::StaticAssert(_) => {}
+        }
+    }
+    callables
+}
+
+fn check_callable<'a>(
+    callable: &Callable<'a>,
+    source_text: &str,
+    line_index: &LineIndex<'_>,
+    violations: &mut Vec<MaintainabilityViolation<'a>>,
+) {
+    let header_span = header_span(callable, source_text);
+    let measurements = [
+        (
+            MaintainabilityViolationKind::FunctionLineCount,
+            MAXIMUM_FUNCTION_LINE_COUNT,
+            line_index.line(callable.span.end) - line_index.line(callable.span.start) + 1,
+        ),
+        (
+            MaintainabilityViolationKind::NestingDepth,
+            MAXIMUM_NESTING_DEPTH,
+            nesting_depth(callable.body),
+        ),
+        (
+            MaintainabilityViolationKind::ParameterCount,
+            MAXIMUM_PARAMETER_COUNT,
+            callable.parameters.len(),
+        ),
+    ];
+    for (kind, limit, measured) in measurements {
+        if measured > limit {
+            violations.push(MaintainabilityViolation {
+                kind,
+                callable_kind: callable.kind,
+                name: callable.name,
+                measured,
+                limit,
+                span: header_span,
+            });
+        }
+    }
+}
+
+// The header runs from the start of the declaration to its body, so that the
+// whole body is not underlined.
+fn header_span(callable: &Callable<'_>, source_text: &str) -> Span {
+    let body_start = callable.body.span.start;
+    let header_text = source_text
+        .get(callable.span.start..body_start)
+        .unwrap_or_default();
+    Span::new(
+        callable.span.start,
+        callable.span.start + header_text.trim_end().len(),
+    )
+}
+
+// Statements directly in the body are at depth zero, and every block nested
+// within it adds one.
+fn nesting_depth(body: &SemanticBlock) -> usize {
+    let mut visitor = NestingDepthVisitor {
+        depth: 0,
+        maximum_depth: 0,
+    };
+    visit_block_children(&mut visitor, body);
+    visitor.maximum_depth
+}
+
+struct NestingDepthVisitor {
+    depth: usize,
+    maximum_depth: usize,
+}
+
+impl SemanticVisitor for NestingDepthVisitor {
+    fn visit_block(&mut self, block: &SemanticBlock) {
+        self.depth += 1;
+        self.maximum_depth = self.maximum_depth.max(self.depth);
+        visit_block_children(self, block);
+        self.depth -= 1;
+    }
+}
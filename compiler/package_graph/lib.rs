@@ -5,13 +5,14 @@ use compiler__diagnostics::PhaseDiagnostic;
 use compiler__source::Span;
 use compiler__symbols::PackageDiagnostic;
 use compiler__visibility::ResolvedImport;
+use serde::Serialize;
 
 type ImportAdjacencyByPackage = BTreeMap<String, BTreeSet<String>>;
-type ImportSiteByEdge = BTreeMap<(String, String), ImportSite>;
+type ImportSitesByEdge = BTreeMap<(String, String), Vec<ImportSite>>;
 
 pub fn check_cycles(resolved_imports: &[ResolvedImport], diagnostics: &mut Vec<PackageDiagnostic>) {
-    let (adjacency_by_package, first_import_site_by_edge) =
-        import_adjacency_and_first_site_by_edge(resolved_imports);
+    let (adjacency_by_package, import_sites_by_edge) =
+        import_adjacency_and_sites_by_edge(resolved_imports);
 
     let Some(cycle) = first_cycle_in_graph(&adjacency_by_package) else {
         return;
@@ -20,36 +21,97 @@ pub fn check_cycles(resolved_imports: &[ResolvedImport], diagnostics: &mut Vec<P
         return;
     }
 
-    let source = &cycle[0];
-    let target = &cycle[1];
-    let import_edge = (source.clone(), target.clone());
-    let Some(import_site) = first_import_site_by_edge.get(&import_edge) else {
-        return;
-    };
-
     let cycle_display = cycle
         .iter()
-        .map(|package| {
-            if package.is_empty() {
-                "workspace".to_string()
-            } else {
-                format!("workspace/{package}")
-            }
-        })
+        .map(|package| package_display_path(package))
         .collect::<Vec<String>>()
         .join(" -> ");
-    diagnostics.push(PackageDiagnostic {
-        path: import_site.path.clone(),
-        diagnostic: PhaseDiagnostic::new(
-            format!("package import cycle detected: {cycle_display}"),
-            import_site.span.clone(),
-        ),
-    });
+    for edge in cycle.windows(2) {
+        let import_edge = (edge[0].clone(), edge[1].clone());
+        let Some(import_sites) = import_sites_by_edge.get(&import_edge) else {
+            continue;
+        };
+        for import_site in import_sites {
+            diagnostics.push(PackageDiagnostic {
+                path: import_site.path.clone(),
+                diagnostic: PhaseDiagnostic::new(
+                    format!("package import cycle detected: {cycle_display}"),
+                    import_site.span,
+                ),
+            });
+        }
+    }
+}
+
+pub struct PackageDependencyRule {
+    pub source_package_pattern: String,
+    pub target_package_pattern: String,
+}
+
+// Rules are allow-lists: a package matched by the source pattern of any rule
+// may only import packages matched by the target pattern of one of those
+// rules. Packages not matched by any source pattern are unrestricted.
+pub fn check_dependency_rules(
+    resolved_imports: &[ResolvedImport],
+    dependency_rules: &[PackageDependencyRule],
+    diagnostics: &mut Vec<PackageDiagnostic>,
+) {
+    for import in resolved_imports {
+        if import.source_package_path == import.target_package_path {
+            continue;
+        }
+        let source_package = package_display_path(&import.source_package_path);
+        let target_package = package_display_path(&import.target_package_path);
+        let applicable_rules = dependency_rules
+            .iter()
+            .filter(|rule| package_pattern_matches(&rule.source_package_pattern, &source_package))
+            .collect::<Vec<_>>();
+        if applicable_rules.is_empty()
+            || applicable_rules
+                .iter()
+                .any(|rule| package_pattern_matches(&rule.target_package_pattern, &target_package))
+        {
+            continue;
+        }
+        let rules_display = applicable_rules
+            .iter()
+            .map(|rule| {
+                format!(
+                    "allow {} -> {}",
+                    rule.source_package_pattern, rule.target_package_pattern
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        diagnostics.push(PackageDiagnostic {
+            path: import.source_path.clone(),
+            diagnostic: PhaseDiagnostic::new(
+                format!(
+                    "import of '{target_package}' from '{source_package}' is not allowed by dependency rules: {rules_display}"
+                ),
+                import.import_span,
+            ),
+        });
+    }
+}
+
+// A trailing `/*` matches the named package and every package nested beneath
+// it; any other pattern matches exactly one package.
+fn package_pattern_matches(pattern: &str, package: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => {
+            package == prefix
+                || package
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+        None => package == pattern,
+    }
 }
 
 #[must_use]
 pub fn package_paths_in_cycle(resolved_imports: &[ResolvedImport]) -> BTreeSet<String> {
-    let (adjacency_by_package, _) = import_adjacency_and_first_site_by_edge(resolved_imports);
+    let (adjacency_by_package, _) = import_adjacency_and_sites_by_edge(resolved_imports);
     let Some(cycle) = first_cycle_in_graph(&adjacency_by_package) else {
         return BTreeSet::new();
     };
@@ -59,11 +121,95 @@ pub fn package_paths_in_cycle(resolved_imports: &[ResolvedImport]) -> BTreeSet<S
     cycle[..cycle.len() - 1].iter().cloned().collect()
 }
 
-fn import_adjacency_and_first_site_by_edge(
+#[derive(Clone, Debug, Serialize)]
+pub struct PackageImportGraph {
+    pub packages: Vec<String>,
+    pub edges: Vec<PackageImportEdge>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PackageImportEdge {
+    pub source_package: String,
+    pub target_package: String,
+    pub symbols: Vec<String>,
+}
+
+// `package_paths` names every package in the graph, so that packages without
+// imports in either direction still appear as nodes.
+#[must_use]
+pub fn package_import_graph<'a>(
+    package_paths: impl IntoIterator<Item = &'a String>,
     resolved_imports: &[ResolvedImport],
-) -> (ImportAdjacencyByPackage, ImportSiteByEdge) {
+) -> PackageImportGraph {
+    let mut packages = package_paths
+        .into_iter()
+        .map(|package_path| package_display_path(package_path))
+        .collect::<BTreeSet<_>>();
+    let mut symbols_by_edge = BTreeMap::<(String, String), BTreeSet<String>>::new();
+    for import in resolved_imports {
+        let source_package = package_display_path(&import.source_package_path);
+        let target_package = package_display_path(&import.target_package_path);
+        packages.insert(source_package.clone());
+        packages.insert(target_package.clone());
+        if source_package == target_package {
+            continue;
+        }
+        symbols_by_edge
+            .entry((source_package, target_package))
+            .or_default()
+            .extend(
+                import
+                    .bindings
+                    .iter()
+                    .map(|binding| binding.imported_name.clone()),
+            );
+    }
+    PackageImportGraph {
+        packages: packages.into_iter().collect(),
+        edges: symbols_by_edge
+            .into_iter()
+            .map(
+                |((source_package, target_package), symbols)| PackageImportEdge {
+                    source_package,
+                    target_package,
+                    symbols: symbols.into_iter().collect(),
+                },
+            )
+            .collect(),
+    }
+}
+
+#[must_use]
+pub fn render_package_import_graph_dot(graph: &PackageImportGraph) -> String {
+    let mut lines = vec!["digraph packages {".to_string()];
+    for package in &graph.packages {
+        lines.push(format!("    \"{package}\";"));
+    }
+    for edge in &graph.edges {
+        lines.push(format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"];",
+            edge.source_package,
+            edge.target_package,
+            edge.symbols.join(", ")
+        ));
+    }
+    lines.push("}".to_string());
+    format!("{}\n", lines.join("\n"))
+}
+
+fn package_display_path(package_path: &str) -> String {
+    if package_path.is_empty() {
+        "workspace".to_string()
+    } else {
+        format!("workspace/{package_path}")
+    }
+}
+
+fn import_adjacency_and_sites_by_edge(
+    resolved_imports: &[ResolvedImport],
+) -> (ImportAdjacencyByPackage, ImportSitesByEdge) {
     let mut adjacency_by_package: ImportAdjacencyByPackage = BTreeMap::new();
-    let mut first_import_site_by_edge: ImportSiteByEdge = BTreeMap::new();
+    let mut import_sites_by_edge: ImportSitesByEdge = BTreeMap::new();
 
     for import in resolved_imports {
         let source_package_path = import.source_package_path.clone();
@@ -83,15 +229,16 @@ fn import_adjacency_and_first_site_by_edge(
             .entry(source_package_path.clone())
             .or_default()
             .insert(target_package_path.clone());
-        first_import_site_by_edge
+        import_sites_by_edge
             .entry((source_package_path, target_package_path))
-            .or_insert_with(|| ImportSite {
+            .or_default()
+            .push(ImportSite {
                 path: import.source_path.clone(),
-                span: import.import_span.clone(),
+                span: import.import_span,
             });
     }
 
-    (adjacency_by_package, first_import_site_by_edge)
+    (adjacency_by_package, import_sites_by_edge)
 }
 
 #[derive(Clone)]
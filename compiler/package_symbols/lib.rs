@@ -5,11 +5,12 @@ use compiler__packages::PackageId;
 use compiler__semantic_program::{
     SemanticDeclaration, SemanticFile, SemanticFunctionDeclaration, SemanticTopLevelVisibility,
     SemanticTypeDeclaration, SemanticTypeDeclarationKind, SemanticTypeName, SemanticTypeParameter,
+    deprecation_message,
 };
 use compiler__semantic_types::{
-    GenericTypeParameter, ImportedBinding, ImportedMethodSignature, ImportedSymbol,
-    ImportedTypeDeclaration, ImportedTypeShape, NominalTypeId, NominalTypeRef, Type,
-    TypedFunctionSignature, type_from_builtin_name,
+    GenericTypeParameter, ImportedBinding, ImportedDeprecation, ImportedMethodSignature,
+    ImportedSymbol, ImportedTypeDeclaration, ImportedTypeShape, NominalTypeId, NominalTypeRef,
+    Type, TypedFunctionSignature, normalize_union, type_from_builtin_name,
 };
 use compiler__source::{FileRole, Span, compare_paths};
 
@@ -57,165 +58,179 @@ pub struct ResolvedImportSummary {
     pub bindings: Vec<ResolvedImportBindingSummary>,
 }
 
-pub struct TypedPublicSymbolTable {
+// Public symbols are indexed one package at a time, the first time bindings
+// of an import of that package are requested, so packages that no file
+// imports are never indexed or typed.
+pub struct TypedPublicSymbolTable<'a> {
+    library_files_by_package_id: BTreeMap<PackageId, Vec<(&'a Path, &'a SemanticFile)>>,
+    indexed_package_ids: BTreeSet<PackageId>,
     symbol_id_by_lookup_key: BTreeMap<PublicSymbolLookupKey, PublicSymbolId>,
     typed_symbol_by_id: BTreeMap<PublicSymbolId, TypedPublicSymbol>,
+    deprecation_by_symbol_id: BTreeMap<PublicSymbolId, ImportedDeprecation>,
+    nominal_type_id_by_lookup_key: BTreeMap<PublicSymbolLookupKey, NominalTypeId>,
 }
 
-impl TypedPublicSymbolTable {
+impl TypedPublicSymbolTable<'_> {
     #[must_use]
     pub fn imported_bindings_by_file(
-        &self,
+        &mut self,
         resolved_imports: &[ResolvedImportSummary],
     ) -> BTreeMap<PathBuf, Vec<ImportedBinding>> {
+        for resolved_import in resolved_imports {
+            self.index_package(resolved_import.target_package_id);
+        }
         build_imported_bindings_by_file(
             resolved_imports,
             &self.symbol_id_by_lookup_key,
             &self.typed_symbol_by_id,
+            &self.deprecation_by_symbol_id,
+            &self.nominal_type_id_by_lookup_key,
         )
     }
-}
-
-#[must_use]
-pub fn build_typed_public_symbol_table(
-    package_symbol_file_inputs: &[PackageSymbolFileInput<'_>],
-    _resolved_imports: &[ResolvedImportSummary],
-) -> TypedPublicSymbolTable {
-    let (symbol_id_by_lookup_key, public_symbol_definition_by_id) =
-        collect_public_symbol_index(package_symbol_file_inputs);
-
-    let typed_symbol_by_id =
-        resolve_public_symbol_types(&symbol_id_by_lookup_key, &public_symbol_definition_by_id);
-
-    TypedPublicSymbolTable {
-        symbol_id_by_lookup_key,
-        typed_symbol_by_id,
-    }
-}
 
-fn collect_public_symbol_index(
-    package_symbol_file_inputs: &[PackageSymbolFileInput<'_>],
-) -> (
-    BTreeMap<PublicSymbolLookupKey, PublicSymbolId>,
-    BTreeMap<PublicSymbolId, PublicSymbolDefinition>,
-) {
-    let mut symbol_id_by_lookup_key = BTreeMap::new();
-    let mut public_symbol_definition_by_id = BTreeMap::new();
+    // Types named in a package's public signatures resolve against that same
+    // package, so one package can be indexed without indexing any other.
+    fn index_package(&mut self, package_id: PackageId) {
+        if !self.indexed_package_ids.insert(package_id) {
+            return;
+        }
+        let Some(library_files) = self.library_files_by_package_id.get(&package_id) else {
+            return;
+        };
 
-    let mut ordered_file_inputs: Vec<&PackageSymbolFileInput<'_>> =
-        package_symbol_file_inputs.iter().collect();
-    ordered_file_inputs.sort_by(|left, right| {
-        left.package_id
-            .cmp(&right.package_id)
-            .then(compare_paths(left.path, right.path))
-    });
+        let mut public_symbol_definition_by_id = BTreeMap::new();
+        for (path, semantic_file) in library_files {
+            for declaration in &semantic_file.declarations {
+                let (name, name_span, deprecation, is_public) = match declaration {
+                    SemanticDeclaration::Type(type_declaration) => (
+                        &type_declaration.name,
+                        type_declaration.name_span,
+                        deprecation_message(&type_declaration.attributes),
+                        type_declaration.visibility == SemanticTopLevelVisibility::Visible,
+                    ),
+                    SemanticDeclaration::Function(function_declaration) => (
+                        &function_declaration.name,
+                        function_declaration.name_span,
+                        deprecation_message(&function_declaration.attributes),
+                        function_declaration.visibility == SemanticTopLevelVisibility::Visible,
+                    ),
+                    SemanticDeclaration::Constant(constant_declaration) => (
+                        &constant_declaration.name,
+                        constant_declaration.name_span,
+                        deprecation_message(&constant_declaration.attributes),
+                        constant_declaration.visibility == SemanticTopLevelVisibility::Visible,
+                    ),
+                    SemanticDeclaration::StaticAssert(_) => continue,
+                };
+                if !is_public {
+                    continue;
+                }
 
-    for file_input in ordered_file_inputs {
-        if file_input.semantic_file.role != FileRole::Library {
-            continue;
-        }
+                let public_symbol_definition = match declaration {
+                    SemanticDeclaration::Type(type_declaration) => {
+                        PublicSymbolDefinition::Type(type_declaration.clone())
+                    }
+                    SemanticDeclaration::Function(function_declaration) => {
+                        PublicSymbolDefinition::Function(function_declaration.clone())
+                    }
+                    SemanticDeclaration::Constant(constant_declaration) => {
+                        PublicSymbolDefinition::Constant(constant_declaration.type_name.clone())
+                    }
+                    SemanticDeclaration::StaticAssert(_) => continue,
+                };
+
+                let lookup_key = PublicSymbolLookupKey {
+                    package_id,
+                    symbol_name: name.clone(),
+                };
+                if self.symbol_id_by_lookup_key.contains_key(&lookup_key) {
+                    continue;
+                }
 
-        for declaration in &file_input.semantic_file.declarations {
-            let (name, is_public) = match declaration {
-                SemanticDeclaration::Type(type_declaration) => (
-                    &type_declaration.name,
-                    type_declaration.visibility == SemanticTopLevelVisibility::Visible,
-                ),
-                SemanticDeclaration::Function(function_declaration) => (
-                    &function_declaration.name,
-                    function_declaration.visibility == SemanticTopLevelVisibility::Visible,
-                ),
-                SemanticDeclaration::Constant(constant_declaration) => (
-                    &constant_declaration.name,
-                    constant_declaration.visibility == SemanticTopLevelVisibility::Visible,
-                ),
-            };
-            if !is_public {
-                continue;
+                let symbol_id = PublicSymbolId(self.symbol_id_by_lookup_key.len());
+                if matches!(public_symbol_definition, PublicSymbolDefinition::Type(_)) {
+                    self.nominal_type_id_by_lookup_key.insert(
+                        lookup_key.clone(),
+                        NominalTypeId {
+                            package_id,
+                            symbol_name: name.clone(),
+                        },
+                    );
+                }
+                if let Some(message) = deprecation {
+                    self.deprecation_by_symbol_id.insert(
+                        symbol_id,
+                        ImportedDeprecation {
+                            message: message.to_string(),
+                            declaration_path: path.to_path_buf(),
+                            declaration_span: name_span,
+                        },
+                    );
+                }
+                self.symbol_id_by_lookup_key.insert(lookup_key, symbol_id);
+                public_symbol_definition_by_id.insert(symbol_id, public_symbol_definition);
             }
+        }
 
-            let public_symbol_definition = match declaration {
-                SemanticDeclaration::Type(type_declaration) => {
-                    PublicSymbolDefinition::Type(type_declaration.clone())
+        for (symbol_id, definition) in public_symbol_definition_by_id {
+            let typed_symbol = match definition {
+                PublicSymbolDefinition::Type(type_declaration) => {
+                    TypedPublicSymbol::Type(type_declaration)
                 }
-                SemanticDeclaration::Function(function_declaration) => {
-                    PublicSymbolDefinition::Function(function_declaration.clone())
+                PublicSymbolDefinition::Function(function_declaration) => {
+                    TypedPublicSymbol::Function(function_declaration)
                 }
-                SemanticDeclaration::Constant(constant_declaration) => {
-                    PublicSymbolDefinition::Constant(constant_declaration.type_name.clone())
+                PublicSymbolDefinition::Constant(type_name) => {
+                    TypedPublicSymbol::Constant(resolve_type_name_to_semantic_type(
+                        &type_name,
+                        package_id,
+                        &self.nominal_type_id_by_lookup_key,
+                        &[],
+                    ))
                 }
             };
-
-            let lookup_key = PublicSymbolLookupKey {
-                package_id: file_input.package_id,
-                symbol_name: name.clone(),
-            };
-            if symbol_id_by_lookup_key.contains_key(&lookup_key) {
-                continue;
-            }
-
-            let symbol_id = PublicSymbolId(symbol_id_by_lookup_key.len());
-            symbol_id_by_lookup_key.insert(lookup_key, symbol_id);
-            public_symbol_definition_by_id.insert(symbol_id, public_symbol_definition);
+            self.typed_symbol_by_id.insert(symbol_id, typed_symbol);
         }
     }
-
-    (symbol_id_by_lookup_key, public_symbol_definition_by_id)
 }
 
-fn resolve_public_symbol_types(
-    symbol_id_by_lookup_key: &BTreeMap<PublicSymbolLookupKey, PublicSymbolId>,
-    public_symbol_definition_by_id: &BTreeMap<PublicSymbolId, PublicSymbolDefinition>,
-) -> BTreeMap<PublicSymbolId, TypedPublicSymbol> {
-    let mut typed_symbol_by_id = BTreeMap::new();
-    for (symbol_id, definition) in public_symbol_definition_by_id {
-        let typed_symbol = match definition {
-            PublicSymbolDefinition::Type(type_declaration) => {
-                TypedPublicSymbol::Type(type_declaration.clone())
-            }
-            PublicSymbolDefinition::Function(function_declaration) => {
-                TypedPublicSymbol::Function(function_declaration.clone())
-            }
-            PublicSymbolDefinition::Constant(_) => TypedPublicSymbol::Constant(Type::Unknown),
-        };
-        typed_symbol_by_id.insert(*symbol_id, typed_symbol);
-    }
-
-    let nominal_type_id_by_lookup_key =
-        nominal_type_id_by_lookup_key(symbol_id_by_lookup_key, &typed_symbol_by_id);
-    let lookup_key_by_symbol_id: BTreeMap<PublicSymbolId, PublicSymbolLookupKey> =
-        symbol_id_by_lookup_key
-            .iter()
-            .map(|(lookup_key, symbol_id)| (*symbol_id, lookup_key.clone()))
-            .collect();
-
-    for (symbol_id, definition) in public_symbol_definition_by_id {
-        let PublicSymbolDefinition::Constant(type_name) = definition else {
-            continue;
-        };
-        let Some(lookup_key) = lookup_key_by_symbol_id.get(symbol_id) else {
+#[must_use]
+pub fn build_typed_public_symbol_table<'a>(
+    package_symbol_file_inputs: &[PackageSymbolFileInput<'a>],
+) -> TypedPublicSymbolTable<'a> {
+    let mut library_files_by_package_id: BTreeMap<PackageId, Vec<(&Path, &SemanticFile)>> =
+        BTreeMap::new();
+    for file_input in package_symbol_file_inputs {
+        if file_input.semantic_file.role != FileRole::Library {
             continue;
-        };
-        let value_type = resolve_type_name_to_semantic_type(
-            type_name,
-            lookup_key.package_id,
-            &nominal_type_id_by_lookup_key,
-            &[],
-        );
-        typed_symbol_by_id.insert(*symbol_id, TypedPublicSymbol::Constant(value_type));
+        }
+        library_files_by_package_id
+            .entry(file_input.package_id)
+            .or_default()
+            .push((file_input.path, file_input.semantic_file));
+    }
+    for library_files in library_files_by_package_id.values_mut() {
+        library_files.sort_by(|left, right| compare_paths(left.0, right.0));
     }
 
-    typed_symbol_by_id
+    TypedPublicSymbolTable {
+        library_files_by_package_id,
+        indexed_package_ids: BTreeSet::new(),
+        symbol_id_by_lookup_key: BTreeMap::new(),
+        typed_symbol_by_id: BTreeMap::new(),
+        deprecation_by_symbol_id: BTreeMap::new(),
+        nominal_type_id_by_lookup_key: BTreeMap::new(),
+    }
 }
 
 fn build_imported_bindings_by_file(
     resolved_imports: &[ResolvedImportSummary],
     symbol_id_by_lookup_key: &BTreeMap<PublicSymbolLookupKey, PublicSymbolId>,
     typed_symbol_by_id: &BTreeMap<PublicSymbolId, TypedPublicSymbol>,
+    deprecation_by_symbol_id: &BTreeMap<PublicSymbolId, ImportedDeprecation>,
+    nominal_type_id_by_lookup_key: &BTreeMap<PublicSymbolLookupKey, NominalTypeId>,
 ) -> BTreeMap<PathBuf, Vec<ImportedBinding>> {
     let mut imported_by_file: BTreeMap<PathBuf, Vec<ImportedBinding>> = BTreeMap::new();
-    let nominal_type_id_by_lookup_key =
-        nominal_type_id_by_lookup_key(symbol_id_by_lookup_key, typed_symbol_by_id);
 
     for resolved_import in resolved_imports {
         let imported_for_file = imported_by_file
@@ -238,14 +253,14 @@ fn build_imported_bindings_by_file(
                     ImportedSymbol::Type(imported_type_declaration(
                         type_declaration,
                         resolved_import.target_package_id,
-                        &nominal_type_id_by_lookup_key,
+                        nominal_type_id_by_lookup_key,
                     ))
                 }
                 TypedPublicSymbol::Function(function_declaration) => {
                     ImportedSymbol::Function(imported_function_signature(
                         function_declaration,
                         resolved_import.target_package_id,
-                        &nominal_type_id_by_lookup_key,
+                        nominal_type_id_by_lookup_key,
                     ))
                 }
                 TypedPublicSymbol::Constant(value_type) => {
@@ -257,8 +272,9 @@ fn build_imported_bindings_by_file(
                 local_name: binding.local_name.clone(),
                 imported_package_path: resolved_import.target_package_path.clone(),
                 imported_symbol_name: binding.imported_name.clone(),
-                span: binding.span.clone(),
+                span: binding.span,
                 symbol,
+                deprecation: deprecation_by_symbol_id.get(symbol_id).cloned(),
             });
         }
     }
@@ -266,29 +282,6 @@ fn build_imported_bindings_by_file(
     imported_by_file
 }
 
-fn nominal_type_id_by_lookup_key(
-    symbol_id_by_lookup_key: &BTreeMap<PublicSymbolLookupKey, PublicSymbolId>,
-    typed_symbol_by_id: &BTreeMap<PublicSymbolId, TypedPublicSymbol>,
-) -> BTreeMap<PublicSymbolLookupKey, NominalTypeId> {
-    let mut nominal_type_id_by_lookup_key = BTreeMap::new();
-    for (lookup_key, symbol_id) in symbol_id_by_lookup_key {
-        if !matches!(
-            typed_symbol_by_id.get(symbol_id),
-            Some(TypedPublicSymbol::Type(_))
-        ) {
-            continue;
-        }
-        nominal_type_id_by_lookup_key.insert(
-            lookup_key.clone(),
-            NominalTypeId {
-                package_id: lookup_key.package_id,
-                symbol_name: lookup_key.symbol_name.clone(),
-            },
-        );
-    }
-    nominal_type_id_by_lookup_key
-}
-
 fn imported_type_declaration(
     type_declaration: &SemanticTypeDeclaration,
     target_package_id: PackageId,
@@ -324,6 +317,11 @@ fn imported_type_declaration(
                 .map(|method| ImportedMethodSignature {
                     name: method.name.clone(),
                     self_mutable: method.self_mutable,
+                    parameter_names: method
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.name.clone())
+                        .collect(),
                     parameter_types: method
                         .parameters
                         .iter()
@@ -355,6 +353,11 @@ fn imported_type_declaration(
                 .map(|method| ImportedMethodSignature {
                     name: method.name.clone(),
                     self_mutable: method.self_mutable,
+                    parameter_names: method
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.name.clone())
+                        .collect(),
                     parameter_types: method
                         .parameters
                         .iter()
@@ -586,29 +589,5 @@ fn resolve_type_name_to_semantic_type(
     if resolved.is_empty() {
         return Type::Unknown;
     }
-    if resolved.len() == 1 {
-        return resolved.remove(0);
-    }
-
-    let mut flattened = Vec::new();
-    let mut seen = BTreeSet::new();
-    for value_type in resolved {
-        if let Type::Union(inner) = value_type {
-            for inner_type in inner {
-                let key = inner_type.display();
-                if seen.insert(key) {
-                    flattened.push(inner_type);
-                }
-            }
-        } else {
-            let key = value_type.display();
-            if seen.insert(key) {
-                flattened.push(value_type);
-            }
-        }
-    }
-    if flattened.len() == 1 {
-        return flattened.remove(0);
-    }
-    Type::Union(flattened)
+    normalize_union(resolved)
 }
@@ -0,0 +1,55 @@
+use compiler__source::Span;
+use compiler__syntax::SyntaxComment;
+
+pub(crate) fn attach_comments(source: &str, comment_spans: &[Span]) -> Vec<SyntaxComment> {
+    comment_spans
+        .iter()
+        .map(|span| {
+            let line_start = source[..span.start]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let code_before = &source[line_start..span.start];
+            let attached_span = if code_before.trim().is_empty() {
+                next_code_line_span(source, comment_spans, span.end)
+            } else {
+                Some(trimmed_span(line_start, code_before))
+            };
+            SyntaxComment {
+                text: source[span.start..span.end].trim_end().to_string(),
+                span: *span,
+                attached_span,
+            }
+        })
+        .collect()
+}
+
+// Comment lines between a comment and its code share the same attachment, so
+// a block of comments describes the code under the whole block.
+fn next_code_line_span(source: &str, comment_spans: &[Span], offset: usize) -> Option<Span> {
+    let mut line_start = offset + source[offset..].find('\n')? + 1;
+    loop {
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |index| line_start + index);
+        let comment_start = comment_spans
+            .iter()
+            .map(|span| span.start)
+            .find(|start| (line_start..line_end).contains(start));
+        let code = &source[line_start..comment_start.unwrap_or(line_end)];
+        if !code.trim().is_empty() {
+            return Some(trimmed_span(line_start, code));
+        }
+        if comment_start.is_none() || line_end == source.len() {
+            return None;
+        }
+        line_start = line_end + 1;
+    }
+}
+
+fn trimmed_span(start: usize, text: &str) -> Span {
+    let leading_whitespace_length = text.len() - text.trim_start().len();
+    Span::new(
+        start + leading_whitespace_length,
+        start + text.trim_end().len(),
+    )
+}
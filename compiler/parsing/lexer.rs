@@ -73,26 +73,61 @@ impl Keyword {
     }
 }
 
+// Words that act as keywords only where a name could not appear, and are
+// lexed as identifiers so that they stay usable as names everywhere else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContextualKeyword {
+    // keep-sorted start
+    Defer,
+    Guard,
+    In,
+    Package,
+    StaticAssert,
+    Step,
+    // keep-sorted end
+}
+
+impl ContextualKeyword {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            // keep-sorted start
+            ContextualKeyword::Defer => "defer",
+            ContextualKeyword::Guard => "guard",
+            ContextualKeyword::In => "in",
+            ContextualKeyword::Package => "package",
+            ContextualKeyword::StaticAssert => "static_assert",
+            ContextualKeyword::Step => "step",
+            // keep-sorted end
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum Symbol {
     // keep-sorted start
+    Ampersand,
     Arrow,
     Assign,
+    At,
     BangEqual,
+    Caret,
     Colon,
     Comma,
     Dot,
+    DotDot,
     DoubleColon,
     Equal,
     EqualEqual,
     FatArrow,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     LeftBrace,
     LeftBracket,
     LeftParenthesis,
     Less,
     LessEqual,
+    LessLess,
     Minus,
     Percent,
     Pipe,
@@ -140,9 +175,8 @@ pub(crate) struct Lexer<'a> {
     source: &'a str,
     bytes: &'a [u8],
     index: usize,
-    line: usize,
-    column: usize,
     lex_errors: Vec<LexError>,
+    comment_spans: Vec<Span>,
     interpolation_brace_depth: Option<usize>,
 }
 
@@ -152,9 +186,8 @@ impl<'a> Lexer<'a> {
             source,
             bytes: source.as_bytes(),
             index: 0,
-            line: 1,
-            column: 1,
             lex_errors: Vec::new(),
+            comment_spans: Vec::new(),
             interpolation_brace_depth: None,
         }
     }
@@ -172,6 +205,10 @@ impl<'a> Lexer<'a> {
         normalize_newlines_to_statement_terminators(tokens)
     }
 
+    pub(crate) fn take_comment_spans(&mut self) -> Vec<Span> {
+        std::mem::take(&mut self.comment_spans)
+    }
+
     pub(crate) fn into_errors(self) -> Vec<LexError> {
         self.lex_errors
     }
@@ -179,17 +216,11 @@ impl<'a> Lexer<'a> {
     fn next_token(&mut self) -> Token {
         self.skip_whitespace();
         let start = self.index;
-        let (line, column) = (self.line, self.column);
 
         if self.index >= self.bytes.len() {
             return Token {
                 kind: TokenKind::EndOfFile,
-                span: Span {
-                    start,
-                    end: start,
-                    line,
-                    column,
-                },
+                span: Span::new(start, start),
             };
         }
 
@@ -199,130 +230,126 @@ impl<'a> Lexer<'a> {
                 self.advance();
                 Token {
                     kind: TokenKind::Newline,
-                    span: Span {
-                        start,
-                        end: start + 1,
-                        line,
-                        column,
-                    },
+                    span: Span::new(start, start + 1),
                 }
             }
-            b'(' => self.single(Symbol::LeftParenthesis, 1, start, line, column),
-            b')' => self.single(Symbol::RightParenthesis, 1, start, line, column),
+            b'(' => self.single(Symbol::LeftParenthesis, 1, start),
+            b')' => self.single(Symbol::RightParenthesis, 1, start),
             b'{' => {
                 if let Some(ref mut depth) = self.interpolation_brace_depth {
                     *depth += 1;
                 }
-                self.single(Symbol::LeftBrace, 1, start, line, column)
+                self.single(Symbol::LeftBrace, 1, start)
             }
             b'}' => {
                 if let Some(depth) = self.interpolation_brace_depth {
                     if depth > 0 {
                         self.interpolation_brace_depth = Some(depth - 1);
-                        self.single(Symbol::RightBrace, 1, start, line, column)
+                        self.single(Symbol::RightBrace, 1, start)
                     } else {
                         self.advance();
-                        self.lex_string_continuation(start, line, column)
+                        self.lex_string_continuation(start)
                     }
                 } else {
-                    self.single(Symbol::RightBrace, 1, start, line, column)
+                    self.single(Symbol::RightBrace, 1, start)
                 }
             }
-            b'[' => self.single(Symbol::LeftBracket, 1, start, line, column),
-            b']' => self.single(Symbol::RightBracket, 1, start, line, column),
-            b',' => self.single(Symbol::Comma, 1, start, line, column),
-            b'.' => self.single(Symbol::Dot, 1, start, line, column),
-            b'|' => self.single(Symbol::Pipe, 1, start, line, column),
+            b'[' => self.single(Symbol::LeftBracket, 1, start),
+            b']' => self.single(Symbol::RightBracket, 1, start),
+            b',' => self.single(Symbol::Comma, 1, start),
+            b'.' => {
+                if self.match_bytes(b"..") {
+                    self.single(Symbol::DotDot, 2, start)
+                } else {
+                    self.single(Symbol::Dot, 1, start)
+                }
+            }
+            b'|' => self.single(Symbol::Pipe, 1, start),
+            b'&' => self.single(Symbol::Ampersand, 1, start),
+            b'^' => self.single(Symbol::Caret, 1, start),
             b'<' => {
                 if self.match_bytes(b"<=") {
-                    self.single(Symbol::LessEqual, 2, start, line, column)
+                    self.single(Symbol::LessEqual, 2, start)
+                } else if self.match_bytes(b"<<") {
+                    self.single(Symbol::LessLess, 2, start)
                 } else {
-                    self.single(Symbol::Less, 1, start, line, column)
+                    self.single(Symbol::Less, 1, start)
                 }
             }
             b'>' => {
                 if self.match_bytes(b">=") {
-                    self.single(Symbol::GreaterEqual, 2, start, line, column)
+                    self.single(Symbol::GreaterEqual, 2, start)
+                } else if self.match_bytes(b">>") {
+                    self.single(Symbol::GreaterGreater, 2, start)
                 } else {
-                    self.single(Symbol::Greater, 1, start, line, column)
+                    self.single(Symbol::Greater, 1, start)
                 }
             }
             b':' => {
                 if self.match_bytes(b"::") {
-                    self.single(Symbol::DoubleColon, 2, start, line, column)
+                    self.single(Symbol::DoubleColon, 2, start)
                 } else if self.match_bytes(b":=") {
-                    self.single(Symbol::Assign, 2, start, line, column)
+                    self.single(Symbol::Assign, 2, start)
                 } else {
-                    self.single(Symbol::Colon, 1, start, line, column)
+                    self.single(Symbol::Colon, 1, start)
                 }
             }
             b'-' => {
                 if self.match_bytes(b"->") {
-                    self.single(Symbol::Arrow, 2, start, line, column)
+                    self.single(Symbol::Arrow, 2, start)
                 } else {
-                    self.single(Symbol::Minus, 1, start, line, column)
+                    self.single(Symbol::Minus, 1, start)
                 }
             }
-            b'+' => self.single(Symbol::Plus, 1, start, line, column),
-            b'%' => self.single(Symbol::Percent, 1, start, line, column),
-            b'*' => self.single(Symbol::Star, 1, start, line, column),
+            b'@' => self.single(Symbol::At, 1, start),
+            b'+' => self.single(Symbol::Plus, 1, start),
+            b'%' => self.single(Symbol::Percent, 1, start),
+            b'*' => self.single(Symbol::Star, 1, start),
             b'/' => {
                 if self.match_bytes(b"///") {
-                    self.lex_doc_comment(start, line, column)
+                    self.lex_doc_comment(start)
                 } else {
-                    self.single(Symbol::Slash, 1, start, line, column)
+                    self.single(Symbol::Slash, 1, start)
                 }
             }
             b'!' => {
                 if self.match_bytes(b"!=") {
-                    self.single(Symbol::BangEqual, 2, start, line, column)
+                    self.single(Symbol::BangEqual, 2, start)
                 } else {
                     let message = format!("unexpected character '{}'", self.peek_char());
                     self.advance();
-                    self.error_token(message, start, line, column)
+                    self.error_token(message, start)
                 }
             }
             b'=' => {
                 if self.match_bytes(b"==") {
-                    self.single(Symbol::EqualEqual, 2, start, line, column)
+                    self.single(Symbol::EqualEqual, 2, start)
                 } else if self.match_bytes(b"=>") {
-                    self.single(Symbol::FatArrow, 2, start, line, column)
+                    self.single(Symbol::FatArrow, 2, start)
                 } else {
-                    self.single(Symbol::Equal, 1, start, line, column)
+                    self.single(Symbol::Equal, 1, start)
                 }
             }
-            b'"' => self.lex_string(start, line, column),
-            b'0'..=b'9' => self.lex_integer(start, line, column),
-            b'a'..=b'z' | b'A'..=b'Z' | b'_' => self.lex_identifier(start, line, column),
+            b'"' => self.lex_string(start),
+            b'0'..=b'9' => self.lex_integer(start),
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => self.lex_identifier(start),
             _ => {
                 let message = format!("unexpected character '{}'", self.peek_char());
                 self.advance();
-                self.error_token(message, start, line, column)
+                self.error_token(message, start)
             }
         }
     }
 
-    fn single(
-        &mut self,
-        symbol: Symbol,
-        length: usize,
-        start: usize,
-        line: usize,
-        column: usize,
-    ) -> Token {
+    fn single(&mut self, symbol: Symbol, length: usize, start: usize) -> Token {
         self.advance_by(length);
         Token {
             kind: TokenKind::Symbol(symbol),
-            span: Span {
-                start,
-                end: start + length,
-                line,
-                column,
-            },
+            span: Span::new(start, start + length),
         }
     }
 
-    fn lex_string(&mut self, start: usize, line: usize, column: usize) -> Token {
+    fn lex_string(&mut self, start: usize) -> Token {
         self.advance();
         let mut accumulated = String::new();
         while self.index < self.bytes.len() {
@@ -331,12 +358,7 @@ impl<'a> Lexer<'a> {
                 self.advance();
                 return Token {
                     kind: TokenKind::StringLiteral(accumulated),
-                    span: Span {
-                        start,
-                        end: self.index,
-                        line,
-                        column,
-                    },
+                    span: Span::new(start, self.index),
                 };
             }
             if byte == b'{' {
@@ -344,12 +366,7 @@ impl<'a> Lexer<'a> {
                 self.interpolation_brace_depth = Some(0);
                 return Token {
                     kind: TokenKind::StringInterpolationStart(accumulated),
-                    span: Span {
-                        start,
-                        end: self.index,
-                        line,
-                        column,
-                    },
+                    span: Span::new(start, self.index),
                 };
             }
             if byte == b'\\' {
@@ -376,21 +393,11 @@ impl<'a> Lexer<'a> {
                         self.advance();
                         self.lex_errors.push(LexError {
                             message: format!("unknown escape sequence '\\{}'", escaped as char),
-                            span: Span {
-                                start: escape_start,
-                                end: self.index,
-                                line,
-                                column,
-                            },
+                            span: Span::new(escape_start, self.index),
                         });
                         return Token {
                             kind: TokenKind::Error,
-                            span: Span {
-                                start,
-                                end: self.index,
-                                line,
-                                column,
-                            },
+                            span: Span::new(start, self.index),
                         };
                     }
                 }
@@ -405,25 +412,15 @@ impl<'a> Lexer<'a> {
 
         self.lex_errors.push(LexError {
             message: "unterminated string literal".to_string(),
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         });
         Token {
             kind: TokenKind::Error,
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         }
     }
 
-    fn lex_string_continuation(&mut self, start: usize, line: usize, column: usize) -> Token {
+    fn lex_string_continuation(&mut self, start: usize) -> Token {
         let mut accumulated = String::new();
         while self.index < self.bytes.len() {
             let byte = self.peek_byte();
@@ -432,12 +429,7 @@ impl<'a> Lexer<'a> {
                 self.interpolation_brace_depth = None;
                 return Token {
                     kind: TokenKind::StringInterpolationEnd(accumulated),
-                    span: Span {
-                        start,
-                        end: self.index,
-                        line,
-                        column,
-                    },
+                    span: Span::new(start, self.index),
                 };
             }
             if byte == b'{' {
@@ -445,12 +437,7 @@ impl<'a> Lexer<'a> {
                 self.interpolation_brace_depth = Some(0);
                 return Token {
                     kind: TokenKind::StringInterpolationMiddle(accumulated),
-                    span: Span {
-                        start,
-                        end: self.index,
-                        line,
-                        column,
-                    },
+                    span: Span::new(start, self.index),
                 };
             }
             if byte == b'\\' {
@@ -477,21 +464,11 @@ impl<'a> Lexer<'a> {
                         self.advance();
                         self.lex_errors.push(LexError {
                             message: format!("unknown escape sequence '\\{}'", escaped as char),
-                            span: Span {
-                                start: escape_start,
-                                end: self.index,
-                                line,
-                                column,
-                            },
+                            span: Span::new(escape_start, self.index),
                         });
                         return Token {
                             kind: TokenKind::Error,
-                            span: Span {
-                                start,
-                                end: self.index,
-                                line,
-                                column,
-                            },
+                            span: Span::new(start, self.index),
                         };
                     }
                 }
@@ -507,25 +484,15 @@ impl<'a> Lexer<'a> {
         self.interpolation_brace_depth = None;
         self.lex_errors.push(LexError {
             message: "unterminated string literal".to_string(),
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         });
         Token {
             kind: TokenKind::Error,
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         }
     }
 
-    fn lex_integer(&mut self, start: usize, line: usize, column: usize) -> Token {
+    fn lex_integer(&mut self, start: usize) -> Token {
         while self.index < self.bytes.len() {
             match self.peek_byte() {
                 b'0'..=b'9' => self.advance(),
@@ -537,36 +504,21 @@ impl<'a> Lexer<'a> {
         if let Ok(value) = value {
             Token {
                 kind: TokenKind::IntegerLiteral(value),
-                span: Span {
-                    start,
-                    end: self.index,
-                    line,
-                    column,
-                },
+                span: Span::new(start, self.index),
             }
         } else {
             self.lex_errors.push(LexError {
                 message: "integer literal out of range".to_string(),
-                span: Span {
-                    start,
-                    end: self.index,
-                    line,
-                    column,
-                },
+                span: Span::new(start, self.index),
             });
             Token {
                 kind: TokenKind::Error,
-                span: Span {
-                    start,
-                    end: self.index,
-                    line,
-                    column,
-                },
+                span: Span::new(start, self.index),
             }
         }
     }
 
-    fn lex_identifier(&mut self, start: usize, line: usize, column: usize) -> Token {
+    fn lex_identifier(&mut self, start: usize) -> Token {
         while self.index < self.bytes.len() {
             match self.peek_byte() {
                 b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' => self.advance(),
@@ -610,16 +562,11 @@ impl<'a> Lexer<'a> {
         };
         Token {
             kind,
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         }
     }
 
-    fn lex_doc_comment(&mut self, start: usize, line: usize, column: usize) -> Token {
+    fn lex_doc_comment(&mut self, start: usize) -> Token {
         self.advance_by(3);
         let content_start = self.index;
         while self.index < self.bytes.len() && self.peek_byte() != b'\n' {
@@ -631,40 +578,19 @@ impl<'a> Lexer<'a> {
         }
         Token {
             kind: TokenKind::DocComment(text),
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         }
     }
 
-    fn error_token(
-        &mut self,
-        message: impl Into<String>,
-        start: usize,
-        line: usize,
-        column: usize,
-    ) -> Token {
+    fn error_token(&mut self, message: impl Into<String>, start: usize) -> Token {
         let message = message.into();
         self.lex_errors.push(LexError {
             message,
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         });
         Token {
             kind: TokenKind::Error,
-            span: Span {
-                start,
-                end: self.index,
-                line,
-                column,
-            },
+            span: Span::new(start, self.index),
         }
     }
 
@@ -679,10 +605,12 @@ impl<'a> Lexer<'a> {
                         break;
                     }
                     if self.match_bytes(b"//") {
+                        let start = self.index;
                         self.advance_by(2);
                         while self.index < self.bytes.len() && self.peek_byte() != b'\n' {
                             self.advance();
                         }
+                        self.comment_spans.push(Span::new(start, self.index));
                     } else {
                         break;
                     }
@@ -694,12 +622,6 @@ impl<'a> Lexer<'a> {
 
     fn advance(&mut self) {
         if self.index < self.bytes.len() {
-            if self.bytes[self.index] == b'\n' {
-                self.line += 1;
-                self.column = 1;
-            } else {
-                self.column += 1;
-            }
             self.index += 1;
         }
     }
@@ -766,7 +688,7 @@ fn normalize_newlines_to_statement_terminators(tokens: Vec<Token>) -> Vec<Token>
                 && is_statement_terminator_trigger(&previous_token.kind)
                 && is_statement_start(&token.kind)
             {
-                let span = previous_token.span.clone();
+                let span = previous_token.span;
                 output.push(Token {
                     kind: TokenKind::StatementTerminator,
                     span,
@@ -1,3 +1,4 @@
+mod comments;
 mod lexer;
 mod parser;
 
@@ -10,6 +11,7 @@ use compiler__syntax::SyntaxParsedFile;
 pub fn parse_file(source: &str, role: FileRole) -> PhaseOutput<SyntaxParsedFile> {
     let mut lexer = lexer::Lexer::new(source);
     let tokens = lexer.lex_all_tokens();
+    let comment_spans = lexer.take_comment_spans();
     let mut diagnostics: Vec<PhaseDiagnostic> = lexer
         .into_errors()
         .into_iter()
@@ -17,7 +19,8 @@ pub fn parse_file(source: &str, role: FileRole) -> PhaseOutput<SyntaxParsedFile>
         .collect();
 
     let mut parser = parser::Parser::new(tokens);
-    let file = parser.parse_file_tokens(role);
+    let mut file = parser.parse_file_tokens(role);
+    file.comments = comments::attach_comments(source, &comment_spans);
     diagnostics.extend(parser.into_diagnostics());
 
     let status = if diagnostics.is_empty() {
@@ -1,11 +1,11 @@
-use crate::lexer::{Keyword, Symbol};
+use crate::lexer::{ContextualKeyword, Keyword, Symbol};
 use compiler__source::Span;
 use compiler__syntax::{
     SyntaxConstantDeclaration, SyntaxFieldDeclaration, SyntaxFunctionDeclaration,
     SyntaxInterfaceMethodDeclaration, SyntaxMemberVisibility, SyntaxMethodDeclaration,
-    SyntaxParameterDeclaration, SyntaxStructMemberItem, SyntaxTestDeclaration,
-    SyntaxTestGroupDeclaration, SyntaxTopLevelVisibility, SyntaxTypeDeclaration,
-    SyntaxTypeDeclarationKind, SyntaxTypeName,
+    SyntaxParameterDeclaration, SyntaxStaticAssertDeclaration, SyntaxStructMemberItem,
+    SyntaxTestDeclaration, SyntaxTestGroupDeclaration, SyntaxTopLevelVisibility,
+    SyntaxTypeDeclaration, SyntaxTypeDeclarationKind, SyntaxTypeName,
 };
 
 use super::{ExpressionSpan, InvalidConstructKind, ParseError, ParseResult, Parser, RecoveredKind};
@@ -54,12 +54,7 @@ impl Parser {
             name,
             name_span,
             tests,
-            span: Span {
-                start: start.start,
-                end: end.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, end.end),
         })
     }
 
@@ -70,16 +65,28 @@ impl Parser {
         Ok(SyntaxTestDeclaration {
             name,
             name_span,
-            span: Span {
-                start: start.start,
-                end: body.span.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, body.span.end),
             body,
         })
     }
 
+    pub(super) fn parse_static_assert_declaration(
+        &mut self,
+    ) -> ParseResult<SyntaxStaticAssertDeclaration> {
+        let start = self.expect_contextual_keyword(ContextualKeyword::StaticAssert)?;
+        self.expect_symbol(Symbol::LeftParenthesis)?;
+        let condition = self.parse_expression()?;
+        self.expect_symbol(Symbol::Comma)?;
+        let (message, message_span) = self.expect_string_literal()?;
+        let end = self.expect_symbol(Symbol::RightParenthesis)?;
+        Ok(SyntaxStaticAssertDeclaration {
+            condition,
+            message,
+            message_span,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
     pub(super) fn parse_type_declaration(
         &mut self,
         visibility: SyntaxTopLevelVisibility,
@@ -92,18 +99,13 @@ impl Parser {
         }
         let implemented_interfaces = self.parse_implemented_interfaces()?;
         self.expect_symbol(Symbol::DoubleColon)?;
-        let start = name_span.clone();
+        let start = name_span;
         if self.peek_is_keyword(Keyword::Struct) {
             self.expect_keyword(Keyword::Struct)?;
             self.expect_symbol(Symbol::LeftBrace)?;
             let items = self.parse_struct_members();
             let right_brace = self.expect_symbol(Symbol::RightBrace)?;
-            let span = Span {
-                start: start.start,
-                end: right_brace.end,
-                line: start.line,
-                column: start.column,
-            };
+            let span = Span::new(start.start, right_brace.end);
             return Ok(SyntaxTypeDeclaration {
                 name,
                 name_span,
@@ -111,6 +113,7 @@ impl Parser {
                 implemented_interfaces,
                 kind: SyntaxTypeDeclarationKind::Struct { items },
                 visibility,
+                attributes: Vec::new(),
                 span,
             });
         }
@@ -121,12 +124,7 @@ impl Parser {
                 self.report_parse_error(&recovery);
             }
             let right_brace = self.expect_symbol(Symbol::RightBrace)?;
-            let span = Span {
-                start: start.start,
-                end: right_brace.end,
-                line: start.line,
-                column: start.column,
-            };
+            let span = Span::new(start.start, right_brace.end);
             return Ok(SyntaxTypeDeclaration {
                 name,
                 name_span,
@@ -134,6 +132,7 @@ impl Parser {
                 implemented_interfaces,
                 kind: SyntaxTypeDeclarationKind::Enum { variants },
                 visibility,
+                attributes: Vec::new(),
                 span,
             });
         }
@@ -141,12 +140,7 @@ impl Parser {
             self.expect_keyword(Keyword::Interface)?;
             let methods = self.parse_interface_methods()?;
             let right_brace = self.expect_symbol(Symbol::RightBrace)?;
-            let span = Span {
-                start: start.start,
-                end: right_brace.end,
-                line: start.line,
-                column: start.column,
-            };
+            let span = Span::new(start.start, right_brace.end);
             return Ok(SyntaxTypeDeclaration {
                 name,
                 name_span,
@@ -154,6 +148,7 @@ impl Parser {
                 implemented_interfaces,
                 kind: SyntaxTypeDeclarationKind::Interface { methods },
                 visibility,
+                attributes: Vec::new(),
                 span,
             });
         }
@@ -161,12 +156,7 @@ impl Parser {
         let end = variants
             .last()
             .map_or(start.end, |variant| variant.span.end);
-        let span = Span {
-            start: start.start,
-            end,
-            line: start.line,
-            column: start.column,
-        };
+        let span = Span::new(start.start, end);
         Ok(SyntaxTypeDeclaration {
             name,
             name_span,
@@ -174,6 +164,7 @@ impl Parser {
             implemented_interfaces,
             kind: SyntaxTypeDeclarationKind::Union { variants },
             visibility,
+            attributes: Vec::new(),
             span,
         })
     }
@@ -253,12 +244,7 @@ impl Parser {
             self_mutable,
             parameters,
             return_type: return_type.clone(),
-            span: Span {
-                start: start.start,
-                end: return_type.span.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, return_type.span.end),
         })
     }
 
@@ -321,12 +307,7 @@ impl Parser {
         let (name, name_span) = self.expect_identifier()?;
         self.expect_symbol(Symbol::Colon)?;
         let type_name = self.parse_type_name()?;
-        let span = Span {
-            start: name_span.start,
-            end: type_name.span.end,
-            line: name_span.line,
-            column: name_span.column,
-        };
+        let span = Span::new(name_span.start, type_name.span.end);
         Ok(SyntaxFieldDeclaration {
             name,
             type_name,
@@ -360,12 +341,7 @@ impl Parser {
             return_type,
             body,
             visibility,
-            span: Span {
-                start: start.start,
-                end: body_end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, body_end),
         })
     }
 
@@ -455,12 +431,8 @@ impl Parser {
             return_type,
             body,
             visibility,
-            span: Span {
-                start: start.start,
-                end: body_end,
-                line: start.line,
-                column: start.column,
-            },
+            attributes: Vec::new(),
+            span: Span::new(start.start, body_end),
         })
     }
 
@@ -480,18 +452,14 @@ impl Parser {
         let type_name = self.parse_type_name()?;
         self.expect_symbol(Symbol::Assign)?;
         let expression = self.parse_expression()?;
-        let span = Span {
-            start: name_span.start,
-            end: expression.span().end,
-            line: name_span.line,
-            column: name_span.column,
-        };
+        let span = Span::new(name_span.start, expression.span().end);
         Ok(SyntaxConstantDeclaration {
             name,
             name_span,
             type_name,
             expression,
             visibility,
+            attributes: Vec::new(),
             span,
         })
     }
@@ -539,12 +507,7 @@ impl Parser {
         let (name, name_span) = self.expect_identifier()?;
         self.expect_symbol(Symbol::Colon)?;
         let type_name = self.parse_type_name()?;
-        let span = Span {
-            start: span_start.start,
-            end: type_name.span.end,
-            line: span_start.line,
-            column: span_start.column,
-        };
+        let span = Span::new(span_start.start, type_name.span.end);
         Ok(SyntaxParameterDeclaration {
             name,
             name_span,
@@ -12,12 +12,7 @@ impl Parser {
         let end = self.expect_symbol(Symbol::RightBrace)?;
         Ok(SyntaxExportsDeclaration {
             members,
-            span: Span {
-                start: start.start,
-                end: end.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, end.end),
         })
     }
 
@@ -1,9 +1,10 @@
 use crate::lexer::{Keyword, Symbol, TokenKind};
 use compiler__source::Span;
 use compiler__syntax::{
-    SyntaxBinaryOperator, SyntaxExpression, SyntaxMatchArm, SyntaxMatchPattern,
-    SyntaxNameReferenceKind, SyntaxStringInterpolationPart, SyntaxStructLiteralField,
-    SyntaxTypeName, SyntaxTypeNameSegment, SyntaxUnaryOperator,
+    SyntaxBinaryOperator, SyntaxBlock, SyntaxBlockItem, SyntaxExpression, SyntaxMatchArm,
+    SyntaxMatchLiteral, SyntaxMatchPattern, SyntaxNameReferenceKind, SyntaxStatement,
+    SyntaxStringInterpolationPart, SyntaxStructLiteralField, SyntaxTypeName, SyntaxTypeNameSegment,
+    SyntaxUnaryOperator,
 };
 
 use super::{
@@ -24,14 +25,10 @@ impl Parser {
             if !self.peek_is_keyword(Keyword::Or) {
                 break;
             }
-            let operator_span = self.advance().span.clone();
+            let operator_span = self.advance().span;
             let right = self.parse_and()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator: SyntaxBinaryOperator::Or,
                 left: Box::new(expression),
@@ -48,14 +45,10 @@ impl Parser {
             if !self.peek_is_keyword(Keyword::And) {
                 break;
             }
-            let operator_span = self.advance().span.clone();
+            let operator_span = self.advance().span;
             let right = self.parse_equality()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator: SyntaxBinaryOperator::And,
                 left: Box::new(expression),
@@ -70,7 +63,7 @@ impl Parser {
         let mut expression = self.parse_comparison()?;
         loop {
             if self.peek_is_symbol(Symbol::Equal) {
-                let operator_span = self.advance().span.clone();
+                let operator_span = self.advance().span;
                 self.defer_parse_error(ParseError::Recovered {
                     kind: RecoveredKind::UnexpectedEqualsInExpression,
                     span: operator_span,
@@ -85,14 +78,10 @@ impl Parser {
             } else {
                 break;
             };
-            let operator_span = self.advance().span.clone();
+            let operator_span = self.advance().span;
             let right = self.parse_comparison()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator,
                 left: Box::new(expression),
@@ -104,10 +93,10 @@ impl Parser {
     }
 
     pub(super) fn parse_comparison(&mut self) -> ParseResult<SyntaxExpression> {
-        let mut expression = self.parse_additive()?;
+        let mut expression = self.parse_bitwise_or()?;
         loop {
             if self.peek_is_keyword(Keyword::Matches) {
-                let operator_span = self.advance().span.clone();
+                let operator_span = self.advance().span;
                 let type_name = self.parse_type_name()?;
                 if type_name
                     .names
@@ -119,12 +108,8 @@ impl Parser {
                         span: type_name.span,
                     });
                 }
-                let span = Span {
-                    start: expression.span().start,
-                    end: type_name.span.end,
-                    line: operator_span.line,
-                    column: operator_span.column,
-                };
+                let span = Span::new(expression.span().start, type_name.span.end)
+                    .anchored_at(operator_span.start);
                 expression = SyntaxExpression::Matches {
                     value: Box::new(expression),
                     type_name,
@@ -143,14 +128,94 @@ impl Parser {
             } else {
                 break;
             };
-            let operator_span = self.advance().span.clone();
-            let right = self.parse_additive()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
+            let operator_span = self.advance().span;
+            let right = self.parse_bitwise_or()?;
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
+            expression = SyntaxExpression::Binary {
+                operator,
+                left: Box::new(expression),
+                right: Box::new(right),
+                span,
             };
+        }
+        Ok(expression)
+    }
+
+    pub(super) fn parse_bitwise_or(&mut self) -> ParseResult<SyntaxExpression> {
+        let mut expression = self.parse_bitwise_xor()?;
+        loop {
+            if !self.peek_is_symbol(Symbol::Pipe) {
+                break;
+            }
+            let operator_span = self.advance().span;
+            let right = self.parse_bitwise_xor()?;
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
+            expression = SyntaxExpression::Binary {
+                operator: SyntaxBinaryOperator::BitwiseOr,
+                left: Box::new(expression),
+                right: Box::new(right),
+                span,
+            };
+        }
+        Ok(expression)
+    }
+
+    pub(super) fn parse_bitwise_xor(&mut self) -> ParseResult<SyntaxExpression> {
+        let mut expression = self.parse_bitwise_and()?;
+        loop {
+            if !self.peek_is_symbol(Symbol::Caret) {
+                break;
+            }
+            let operator_span = self.advance().span;
+            let right = self.parse_bitwise_and()?;
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
+            expression = SyntaxExpression::Binary {
+                operator: SyntaxBinaryOperator::BitwiseXor,
+                left: Box::new(expression),
+                right: Box::new(right),
+                span,
+            };
+        }
+        Ok(expression)
+    }
+
+    pub(super) fn parse_bitwise_and(&mut self) -> ParseResult<SyntaxExpression> {
+        let mut expression = self.parse_shift()?;
+        loop {
+            if !self.peek_is_symbol(Symbol::Ampersand) {
+                break;
+            }
+            let operator_span = self.advance().span;
+            let right = self.parse_shift()?;
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
+            expression = SyntaxExpression::Binary {
+                operator: SyntaxBinaryOperator::BitwiseAnd,
+                left: Box::new(expression),
+                right: Box::new(right),
+                span,
+            };
+        }
+        Ok(expression)
+    }
+
+    pub(super) fn parse_shift(&mut self) -> ParseResult<SyntaxExpression> {
+        let mut expression = self.parse_additive()?;
+        loop {
+            let operator = if self.peek_is_symbol(Symbol::LessLess) {
+                SyntaxBinaryOperator::ShiftLeft
+            } else if self.peek_is_symbol(Symbol::GreaterGreater) {
+                SyntaxBinaryOperator::ShiftRight
+            } else {
+                break;
+            };
+            let operator_span = self.advance().span;
+            let right = self.parse_additive()?;
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator,
                 left: Box::new(expression),
@@ -171,14 +236,10 @@ impl Parser {
             } else {
                 break;
             };
-            let operator_span = self.advance().span.clone();
+            let operator_span = self.advance().span;
             let right = self.parse_multiplicative()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator,
                 left: Box::new(expression),
@@ -201,14 +262,10 @@ impl Parser {
             } else {
                 break;
             };
-            let operator_span = self.advance().span.clone();
+            let operator_span = self.advance().span;
             let right = self.parse_postfix()?;
-            let span = Span {
-                start: expression.span().start,
-                end: right.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(expression.span().start, right.span().end)
+                .anchored_at(operator_span.start);
             expression = SyntaxExpression::Binary {
                 operator,
                 left: Box::new(expression),
@@ -226,12 +283,8 @@ impl Parser {
                 let left_parenthesis = self.expect_symbol(Symbol::LeftParenthesis)?;
                 let arguments = self.parse_arguments();
                 let right_parenthesis = self.expect_symbol(Symbol::RightParenthesis)?;
-                let span = Span {
-                    start: expression.span().start,
-                    end: right_parenthesis.end,
-                    line: left_parenthesis.line,
-                    column: left_parenthesis.column,
-                };
+                let span = Span::new(expression.span().start, right_parenthesis.end)
+                    .anchored_at(left_parenthesis.start);
                 expression = SyntaxExpression::Call {
                     callee: Box::new(expression),
                     type_arguments: Vec::new(),
@@ -244,17 +297,14 @@ impl Parser {
                 let checkpoint = self.checkpoint();
                 let generic_call_result = self.parse_type_argument_list();
                 if let Ok((type_arguments, _right_bracket)) = generic_call_result
+                    && !self.has_parse_errors_since(checkpoint)
                     && self.peek_is_symbol(Symbol::LeftParenthesis)
                 {
                     let left_parenthesis = self.expect_symbol(Symbol::LeftParenthesis)?;
                     let arguments = self.parse_arguments();
                     let right_parenthesis = self.expect_symbol(Symbol::RightParenthesis)?;
-                    let span = Span {
-                        start: expression.span().start,
-                        end: right_parenthesis.end,
-                        line: left_parenthesis.line,
-                        column: left_parenthesis.column,
-                    };
+                    let span = Span::new(expression.span().start, right_parenthesis.end)
+                        .anchored_at(left_parenthesis.start);
                     expression = SyntaxExpression::Call {
                         callee: Box::new(expression),
                         type_arguments,
@@ -268,12 +318,8 @@ impl Parser {
                 let left_bracket = self.expect_symbol(Symbol::LeftBracket)?;
                 let index = self.parse_expression()?;
                 let right_bracket = self.expect_symbol(Symbol::RightBracket)?;
-                let span = Span {
-                    start: expression.span().start,
-                    end: right_bracket.end,
-                    line: left_bracket.line,
-                    column: left_bracket.column,
-                };
+                let span = Span::new(expression.span().start, right_bracket.end)
+                    .anchored_at(left_bracket.start);
                 expression = SyntaxExpression::IndexAccess {
                     target: Box::new(expression),
                     index: Box::new(index),
@@ -284,12 +330,8 @@ impl Parser {
             if self.peek_is_symbol(Symbol::Dot) {
                 let dot = self.expect_symbol(Symbol::Dot)?;
                 let (field, field_span) = self.expect_identifier()?;
-                let span = Span {
-                    start: expression.span().start,
-                    end: field_span.end,
-                    line: dot.line,
-                    column: dot.column,
-                };
+                let span =
+                    Span::new(expression.span().start, field_span.end).anchored_at(dot.start);
                 expression = SyntaxExpression::FieldAccess {
                     target: Box::new(expression),
                     field,
@@ -307,12 +349,7 @@ impl Parser {
         if self.peek_is_keyword(Keyword::Not) {
             let operator_span = self.advance().span;
             let expression = self.parse_unary()?;
-            let span = Span {
-                start: operator_span.start,
-                end: expression.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(operator_span.start, expression.span().end);
             return Ok(SyntaxExpression::Unary {
                 operator: SyntaxUnaryOperator::Not,
                 expression: Box::new(expression),
@@ -322,12 +359,7 @@ impl Parser {
         if self.peek_is_symbol(Symbol::Minus) {
             let operator_span = self.advance().span;
             let expression = self.parse_unary()?;
-            let span = Span {
-                start: operator_span.start,
-                end: expression.span().end,
-                line: operator_span.line,
-                column: operator_span.column,
-            };
+            let span = Span::new(operator_span.start, expression.span().end);
             return Ok(SyntaxExpression::Unary {
                 operator: SyntaxUnaryOperator::Negate,
                 expression: Box::new(expression),
@@ -370,6 +402,13 @@ impl Parser {
     }
 
     pub(super) fn parse_primary(&mut self) -> ParseResult<SyntaxExpression> {
+        if self.peek_is_keyword(Keyword::If) {
+            return self.parse_if_expression();
+        }
+        if self.block_expressions_allowed && self.peek_is_symbol(Symbol::LeftBrace) {
+            let block = self.parse_block()?;
+            return Self::block_expression_from_block(block);
+        }
         let token = self.advance();
         match token.kind {
             TokenKind::IntegerLiteral(value) => Ok(SyntaxExpression::IntegerLiteral {
@@ -402,7 +441,7 @@ impl Parser {
                     let mut segment = SyntaxTypeNameSegment {
                         name,
                         type_arguments: Vec::new(),
-                        span: token.span.clone(),
+                        span: token.span,
                     };
                     if self.peek_is_symbol(Symbol::LeftBracket) {
                         let (type_arguments, right_bracket) = self.parse_type_argument_list()?;
@@ -439,7 +478,8 @@ impl Parser {
             TokenKind::Keyword(Keyword::Match) => self.parse_match_expression(&token.span),
             TokenKind::Symbol(Symbol::LeftBracket) => self.parse_list_literal(&token.span),
             TokenKind::Symbol(Symbol::LeftParenthesis) => {
-                let expression = self.parse_expression()?;
+                let expression =
+                    self.with_block_expressions_allowed(true, Self::parse_expression)?;
                 self.expect_symbol(Symbol::RightParenthesis)?;
                 Ok(expression)
             }
@@ -458,12 +498,7 @@ impl Parser {
         let left_brace = self.expect_symbol(Symbol::LeftBrace)?;
         let fields = self.parse_struct_literal_fields();
         let right_brace = self.expect_symbol(Symbol::RightBrace)?;
-        let span = Span {
-            start: type_name.span.start,
-            end: right_brace.end,
-            line: left_brace.line,
-            column: left_brace.column,
-        };
+        let span = Span::new(type_name.span.start, right_brace.end).anchored_at(left_brace.start);
         Ok(SyntaxExpression::StructLiteral {
             type_name,
             fields,
@@ -477,12 +512,7 @@ impl Parser {
     ) -> ParseResult<SyntaxExpression> {
         let elements = self.parse_list_literal_elements();
         let right_bracket = self.expect_symbol(Symbol::RightBracket)?;
-        let span = Span {
-            start: start_span.start,
-            end: right_bracket.end,
-            line: start_span.line,
-            column: start_span.column,
-        };
+        let span = Span::new(start_span.start, right_bracket.end);
         Ok(SyntaxExpression::ListLiteral { elements, span })
     }
 
@@ -490,16 +520,11 @@ impl Parser {
         &mut self,
         start_span: &Span,
     ) -> ParseResult<SyntaxExpression> {
-        let target = self.parse_expression()?;
+        let target = self.parse_expression_before_block()?;
         self.expect_symbol(Symbol::LeftBrace)?;
         let arms = self.parse_match_arms();
         let right_brace = self.expect_symbol(Symbol::RightBrace)?;
-        let span = Span {
-            start: start_span.start,
-            end: right_brace.end,
-            line: start_span.line,
-            column: start_span.column,
-        };
+        let span = Span::new(start_span.start, right_brace.end);
         Ok(SyntaxExpression::Match {
             target: Box::new(target),
             arms,
@@ -567,6 +592,12 @@ impl Parser {
 
     pub(super) fn parse_match_arm(&mut self) -> ParseResult<SyntaxMatchArm> {
         let pattern = self.parse_match_pattern()?;
+        let guard = if self.peek_is_keyword(Keyword::If) {
+            self.advance();
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
         self.expect_symbol(Symbol::FatArrow)?;
         if self.peek_is_symbol(Symbol::RightBrace)
             || self.peek_is_symbol(Symbol::Comma)
@@ -578,28 +609,83 @@ impl Parser {
             });
         }
         let value = self.parse_expression()?;
-        let span = Span {
-            start: pattern.span().start,
-            end: value.span().end,
-            line: pattern.span().line,
-            column: pattern.span().column,
-        };
+        let span = Span::new(pattern.span().start, value.span().end);
         Ok(SyntaxMatchArm {
             pattern,
+            guard,
             value,
             span,
         })
     }
 
+    fn parse_if_expression(&mut self) -> ParseResult<SyntaxExpression> {
+        let start = self.expect_keyword(Keyword::If)?;
+        let condition = self.parse_expression_before_block()?;
+        let then_block = self.parse_block()?;
+        if !self.peek_is_keyword(Keyword::Else) {
+            return Err(ParseError::InvalidConstruct {
+                kind: InvalidConstructKind::IfExpressionRequiresElse,
+                span: Span::new(start.start, then_block.span.end),
+            });
+        }
+        self.advance();
+        let else_block = self.parse_block()?;
+        Self::if_expression_from_blocks(condition, then_block, else_block, start)
+    }
+
+    fn if_expression_from_blocks(
+        condition: SyntaxExpression,
+        then_block: SyntaxBlock,
+        else_block: SyntaxBlock,
+        start: Span,
+    ) -> ParseResult<SyntaxExpression> {
+        let span = Span::new(start.start, else_block.span.end);
+        Ok(SyntaxExpression::If {
+            condition: Box::new(condition),
+            then_branch: Box::new(Self::block_expression_from_block(then_block)?),
+            else_branch: Box::new(Self::block_expression_from_block(else_block)?),
+            span,
+        })
+    }
+
+    // A block's value is its last statement, which parses as an expression
+    // statement, or as an `if` statement when it is an `if`/`else` chain.
+    fn block_expression_from_block(mut block: SyntaxBlock) -> ParseResult<SyntaxExpression> {
+        let value = match block.items.pop() {
+            Some(SyntaxBlockItem::Statement(SyntaxStatement::Expression { value, .. })) => value,
+            Some(SyntaxBlockItem::Statement(SyntaxStatement::If {
+                condition,
+                then_block,
+                else_block: Some(else_block),
+                span,
+            })) => Self::if_expression_from_blocks(condition, then_block, else_block, span)?,
+            _ => {
+                return Err(ParseError::InvalidConstruct {
+                    kind: InvalidConstructKind::BlockExpressionRequiresValue,
+                    span: block.span,
+                });
+            }
+        };
+        let span = block.span;
+        Ok(SyntaxExpression::Block {
+            block,
+            value: Box::new(value),
+            span,
+        })
+    }
+
     fn next_token_starts_match_arm(&mut self) -> bool {
         let checkpoint = self.checkpoint();
-        let starts_match_arm =
-            self.parse_match_pattern().is_ok() && self.peek_is_symbol(Symbol::FatArrow);
+        let starts_match_arm = self.parse_match_pattern().is_ok()
+            && (self.peek_is_symbol(Symbol::FatArrow) || self.peek_is_keyword(Keyword::If));
         self.restore(checkpoint);
         starts_match_arm
     }
 
     pub(super) fn parse_match_pattern(&mut self) -> ParseResult<SyntaxMatchPattern> {
+        if let Some(pattern) = self.parse_match_literal_pattern() {
+            return Ok(pattern);
+        }
         let (name, name_span, can_be_binding_name) = if self.peek_is_keyword(Keyword::Nil) {
             let (name, span) = self.expect_type_name_part()?;
             (name, span, false)
@@ -620,12 +706,7 @@ impl Parser {
                     span: type_name.span,
                 });
             }
-            let span = Span {
-                start: name_span.start,
-                end: type_name.span.end,
-                line: name_span.line,
-                column: name_span.column,
-            };
+            let span = Span::new(name_span.start, type_name.span.end);
             return Ok(SyntaxMatchPattern::Binding {
                 name,
                 name_span,
@@ -635,7 +716,7 @@ impl Parser {
         }
 
         let mut qualified_name = name;
-        let mut qualified_span = name_span.clone();
+        let mut qualified_span = name_span;
         while self.peek_is_symbol(Symbol::Dot) {
             self.advance();
             let (segment, segment_span) = self.expect_identifier()?;
@@ -647,12 +728,7 @@ impl Parser {
             let (_, right_bracket) = self.parse_type_argument_list()?;
             return Err(ParseError::InvalidConstruct {
                 kind: InvalidConstructKind::PatternTypeArgumentsNotSupported,
-                span: Span {
-                    start: name_span.start,
-                    end: right_bracket.end,
-                    line: name_span.line,
-                    column: name_span.column,
-                },
+                span: Span::new(name_span.start, right_bracket.end),
             });
         }
 
@@ -660,9 +736,9 @@ impl Parser {
             names: vec![SyntaxTypeNameSegment {
                 name: qualified_name,
                 type_arguments: Vec::new(),
-                span: qualified_span.clone(),
+                span: qualified_span,
             }],
-            span: qualified_span.clone(),
+            span: qualified_span,
         };
         Ok(SyntaxMatchPattern::Type {
             type_name,
@@ -670,6 +746,34 @@ impl Parser {
         })
     }
 
+    fn parse_match_literal_pattern(&mut self) -> Option<SyntaxMatchPattern> {
+        let negated_span = if self.peek_is_symbol(Symbol::Minus)
+            && matches!(self.peek_n(1).kind, TokenKind::IntegerLiteral(_))
+        {
+            Some(self.advance().span)
+        } else {
+            None
+        };
+        let value = match &self.peek().kind {
+            TokenKind::IntegerLiteral(value) => {
+                SyntaxMatchLiteral::Integer(if negated_span.is_some() {
+                    value.wrapping_neg()
+                } else {
+                    *value
+                })
+            }
+            TokenKind::StringLiteral(value) => SyntaxMatchLiteral::String(value.clone()),
+            TokenKind::BooleanLiteral(value) => SyntaxMatchLiteral::Boolean(*value),
+            _ => return None,
+        };
+        let token_span = self.advance().span;
+        let span = Span::new(
+            negated_span.map_or(token_span.start, |span| span.start),
+            token_span.end,
+        );
+        Some(SyntaxMatchPattern::Literal { value, span })
+    }
+
     pub(super) fn parse_struct_literal_fields(&mut self) -> Vec<SyntaxStructLiteralField> {
         let mut fields = Vec::new();
         self.skip_statement_terminators();
@@ -706,12 +810,7 @@ impl Parser {
         let (name, name_span) = self.expect_identifier()?;
         self.expect_symbol(Symbol::Colon)?;
         let value = self.parse_expression()?;
-        let span = Span {
-            start: name_span.start,
-            end: value.span().end,
-            line: name_span.line,
-            column: name_span.column,
-        };
+        let span = Span::new(name_span.start, value.span().end);
         Ok(SyntaxStructLiteralField {
             name,
             name_span,
@@ -745,12 +844,7 @@ impl Parser {
                     if !text.is_empty() {
                         parts.push(SyntaxStringInterpolationPart::Literal(text));
                     }
-                    let span = Span {
-                        start: start_span.start,
-                        end: token.span.end,
-                        line: start_span.line,
-                        column: start_span.column,
-                    };
+                    let span = Span::new(start_span.start, token.span.end);
                     return Ok(SyntaxExpression::StringInterpolation { parts, span });
                 }
                 _ => {
@@ -14,12 +14,7 @@ impl Parser {
         Ok(SyntaxImportDeclaration {
             package_path,
             members,
-            span: Span {
-                start: start.start,
-                end: end.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, end.end),
         })
     }
 
@@ -84,12 +79,7 @@ impl Parser {
             name,
             alias,
             alias_span,
-            span: Span {
-                start: name_span.start,
-                end,
-                line: name_span.line,
-                column: name_span.column,
-            },
+            span: Span::new(name_span.start, end),
         })
     }
 }
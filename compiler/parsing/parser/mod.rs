@@ -1,16 +1,18 @@
-use crate::lexer::{Keyword, Symbol, Token, TokenKind};
+use crate::lexer::{ContextualKeyword, Keyword, Symbol, Token, TokenKind};
 use compiler__diagnostics::PhaseDiagnostic;
 use compiler__source::FileRole;
 use compiler__source::Span;
 use compiler__syntax::{
-    SyntaxDeclaration, SyntaxDocComment, SyntaxExpression, SyntaxFileItem, SyntaxMemberVisibility,
-    SyntaxParsedFile, SyntaxTopLevelVisibility,
+    SyntaxAttribute, SyntaxAttributeArgument, SyntaxDeclaration, SyntaxDocComment,
+    SyntaxExpression, SyntaxFileItem, SyntaxMemberVisibility, SyntaxParsedFile,
+    SyntaxTopLevelVisibility,
 };
 
 mod declarations;
 mod exports;
 mod expressions;
 mod imports;
+mod package;
 mod recovery;
 mod statements;
 mod types;
@@ -26,6 +28,7 @@ pub(super) enum UnexpectedTokenKind {
 #[derive(Clone, Debug)]
 pub(super) enum MissingTokenKind {
     Keyword { keyword: Keyword },
+    ContextualKeyword { keyword: ContextualKeyword },
     Symbol,
 }
 
@@ -34,6 +37,9 @@ pub(super) enum InvalidConstructKind {
     FirstMethodParameterMustBeSelf,
     ConstantsRequireExplicitTypeAnnotation,
     PatternTypeArgumentsNotSupported,
+    IfExpressionRequiresElse,
+    BlockExpressionRequiresValue,
+    GuardRequiresElse,
 }
 
 #[derive(Clone, Debug)]
@@ -49,6 +55,7 @@ pub(super) enum RecoveredKind {
     ExpectedCommaOrRightBraceAfterEnumVariant,
     ExpectedCommaBetweenMatchArms,
     UnexpectedEqualsInExpression,
+    AttributesMustPrecedeDeclaration,
 }
 
 // Parser error variants represent syntactic construction failures. Parseable
@@ -85,6 +92,9 @@ pub(crate) struct Parser {
     position: usize,
     parse_errors: Vec<ParseError>,
     deferred_parse_errors: Vec<ParseError>,
+    // Cleared while parsing an expression that a `{` block follows, such as an
+    // `if` condition, so that the `{` is not read as a block expression.
+    block_expressions_allowed: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -101,6 +111,7 @@ impl Parser {
             position: 0,
             parse_errors: Vec::new(),
             deferred_parse_errors: Vec::new(),
+            block_expressions_allowed: true,
         }
     }
 
@@ -114,7 +125,11 @@ impl Parser {
     pub(crate) fn parse_file_tokens(&mut self, role: FileRole) -> SyntaxParsedFile {
         let items = self.parse_declarations();
 
-        SyntaxParsedFile { role, items }
+        SyntaxParsedFile {
+            role,
+            items,
+            comments: Vec::new(),
+        }
     }
 
     fn parse_declarations(&mut self) -> Vec<SyntaxFileItem> {
@@ -142,6 +157,9 @@ impl Parser {
     }
 
     fn parse_declaration(&mut self) -> ParseResult<SyntaxDeclaration> {
+        if self.peek_is_symbol(Symbol::At) {
+            return self.parse_attributed_declaration();
+        }
         if self.peek_is_keyword(Keyword::Visible) {
             let visibility = self.parse_top_level_visibility();
             if self.peek_is_keyword(Keyword::Type) {
@@ -178,6 +196,13 @@ impl Parser {
         if self.peek_is_keyword(Keyword::Test) {
             return self.parse_test_declaration().map(SyntaxDeclaration::Test);
         }
+        if self.peek_is_contextual_keyword(ContextualKeyword::StaticAssert)
+            && self.peek_second_is_symbol(Symbol::LeftParenthesis)
+        {
+            return self
+                .parse_static_assert_declaration()
+                .map(SyntaxDeclaration::StaticAssert);
+        }
         if self.peek_is_keyword(Keyword::Import) {
             return self
                 .parse_import_declaration()
@@ -188,6 +213,13 @@ impl Parser {
                 .parse_exports_declaration()
                 .map(SyntaxDeclaration::Exports);
         }
+        if self.peek_is_contextual_keyword(ContextualKeyword::Package)
+            && self.peek_second_is_symbol(Symbol::LeftBrace)
+        {
+            return self
+                .parse_package_declaration()
+                .map(SyntaxDeclaration::Package);
+        }
         if self.peek_is_keyword(Keyword::Function) {
             return self
                 .parse_function(SyntaxTopLevelVisibility::Private)
@@ -212,6 +244,72 @@ impl Parser {
         })
     }
 
+    // Attributes sit on their own lines above the declaration, after any doc
+    // comment. They are kept outside the declaration span, the way visibility
+    // and `type` keywords are.
+    fn parse_attributed_declaration(&mut self) -> ParseResult<SyntaxDeclaration> {
+        let mut attributes = Vec::new();
+        while self.peek_is_symbol(Symbol::At) {
+            attributes.push(self.parse_attribute()?);
+            self.skip_statement_terminators();
+        }
+        let mut declaration = self.parse_declaration()?;
+        match &mut declaration {
+            SyntaxDeclaration::Type(type_declaration) => {
+                type_declaration.attributes = attributes;
+            }
+            SyntaxDeclaration::Function(function_declaration) => {
+                function_declaration.attributes = attributes;
+            }
+            SyntaxDeclaration::Constant(constant_declaration) => {
+                constant_declaration.attributes = attributes;
+            }
+            SyntaxDeclaration::Import(_)
+            | SyntaxDeclaration::Exports(_)
+            | SyntaxDeclaration::Package(_)
+            | SyntaxDeclaration::Group(_)
+            | SyntaxDeclaration::Test(_)
+            | SyntaxDeclaration::StaticAssert(_) => {
+                self.report_parse_error(&ParseError::Recovered {
+                    kind: RecoveredKind::AttributesMustPrecedeDeclaration,
+                    span: attributes[0].span,
+                });
+            }
+        }
+        Ok(declaration)
+    }
+
+    fn parse_attribute(&mut self) -> ParseResult<SyntaxAttribute> {
+        let start = self.expect_symbol(Symbol::At)?;
+        // Attribute names are not reserved words, so that `@test` can be one.
+        let (name, name_span) = if let TokenKind::Keyword(keyword) = self.peek().kind {
+            let span = self.advance().span;
+            (keyword.as_str().to_string(), span)
+        } else {
+            self.expect_identifier()?
+        };
+        let mut arguments = Vec::new();
+        let mut end = name_span.end;
+        if self.peek_is_symbol(Symbol::LeftParenthesis) {
+            self.advance();
+            while !self.peek_is_symbol(Symbol::RightParenthesis) {
+                let (value, span) = self.expect_string_literal()?;
+                arguments.push(SyntaxAttributeArgument { value, span });
+                if !self.peek_is_symbol(Symbol::Comma) {
+                    break;
+                }
+                self.advance();
+            }
+            end = self.expect_symbol(Symbol::RightParenthesis)?.end;
+        }
+        Ok(SyntaxAttribute {
+            name,
+            name_span,
+            arguments,
+            span: Span::new(start.start, end),
+        })
+    }
+
     fn parse_top_level_visibility(&mut self) -> SyntaxTopLevelVisibility {
         if self.peek_is_keyword(Keyword::Visible) {
             self.advance();
@@ -234,6 +332,14 @@ impl Parser {
         matches!(self.peek().kind, TokenKind::Keyword(found) if found == keyword)
     }
 
+    fn peek_is_contextual_keyword(&self, keyword: ContextualKeyword) -> bool {
+        self.peek_n_is_contextual_keyword(0, keyword)
+    }
+
+    fn peek_n_is_contextual_keyword(&self, n: usize, keyword: ContextualKeyword) -> bool {
+        matches!(&self.peek_n(n).kind, TokenKind::Identifier(name) if name == keyword.as_str())
+    }
+
     fn peek_is_identifier(&self) -> bool {
         matches!(self.peek().kind, TokenKind::Identifier(_))
     }
@@ -285,6 +391,13 @@ impl Parser {
         }
     }
 
+    // A speculative parse that only succeeded by recovering from errors should
+    // be abandoned like one that failed.
+    fn has_parse_errors_since(&self, checkpoint: ParserCheckpoint) -> bool {
+        self.parse_errors.len() > checkpoint.parse_errors_len
+            || self.deferred_parse_errors.len() > checkpoint.deferred_parse_errors_len
+    }
+
     fn restore(&mut self, checkpoint: ParserCheckpoint) {
         self.position = checkpoint.position;
         self.parse_errors.truncate(checkpoint.parse_errors_len);
@@ -292,6 +405,21 @@ impl Parser {
             .truncate(checkpoint.deferred_parse_errors_len);
     }
 
+    fn with_block_expressions_allowed<T>(
+        &mut self,
+        allowed: bool,
+        parse: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let previous = std::mem::replace(&mut self.block_expressions_allowed, allowed);
+        let result = parse(self);
+        self.block_expressions_allowed = previous;
+        result
+    }
+
+    fn parse_expression_before_block(&mut self) -> ParseResult<SyntaxExpression> {
+        self.with_block_expressions_allowed(false, Self::parse_expression)
+    }
+
     fn skip_statement_terminators(&mut self) {
         while matches!(self.peek().kind, TokenKind::StatementTerminator) {
             self.advance();
@@ -303,29 +431,21 @@ impl Parser {
             return None;
         }
         let mut lines = Vec::new();
-        let start_span = self.peek().span.clone();
+        let start_span = self.peek().span;
         let mut end = start_span.end;
-        let mut end_line = start_span.line;
         while let TokenKind::DocComment(line) = self.peek().kind.clone() {
             let token = self.advance();
             lines.push(line);
             end = token.span.end;
-            end_line = token.span.line;
         }
         Some(SyntaxDocComment {
             lines,
-            span: Span {
-                start: start_span.start,
-                end,
-                line: start_span.line,
-                column: start_span.column,
-            },
-            end_line,
+            span: Span::new(start_span.start, end),
         })
     }
 
     fn peek_span(&self) -> Span {
-        self.peek().span.clone()
+        self.peek().span
     }
 
     fn report_parse_error(&mut self, error: &ParseError) {
@@ -357,16 +477,19 @@ impl Parser {
                     ),
                     UnexpectedTokenKind::ExpectedExpression => "expected expression".to_string(),
                 };
-                Some(PhaseDiagnostic::new(message, span.clone()))
+                Some(PhaseDiagnostic::new(message, *span))
             }
             ParseError::MissingToken { kind, span } => {
                 let message = match kind {
                     MissingTokenKind::Keyword { keyword } => {
                         format!("expected keyword '{keyword:?}'")
                     }
+                    MissingTokenKind::ContextualKeyword { keyword } => {
+                        format!("expected keyword '{keyword:?}'")
+                    }
                     MissingTokenKind::Symbol => "expected symbol".to_string(),
                 };
-                Some(PhaseDiagnostic::new(message, span.clone()))
+                Some(PhaseDiagnostic::new(message, *span))
             }
             ParseError::InvalidConstruct { kind, span } => {
                 let message = match kind {
@@ -379,8 +502,17 @@ impl Parser {
                     InvalidConstructKind::PatternTypeArgumentsNotSupported => {
                         "match patterns must not include type arguments".to_string()
                     }
+                    InvalidConstructKind::IfExpressionRequiresElse => {
+                        "'if' used as a value must have an 'else' branch".to_string()
+                    }
+                    InvalidConstructKind::BlockExpressionRequiresValue => {
+                        "block used as a value must end with an expression".to_string()
+                    }
+                    InvalidConstructKind::GuardRequiresElse => {
+                        "'guard' must be followed by an 'else' block".to_string()
+                    }
                 };
-                Some(PhaseDiagnostic::new(message, span.clone()))
+                Some(PhaseDiagnostic::new(message, *span))
             }
             ParseError::Recovered { kind, span } => {
                 let message = match kind {
@@ -415,8 +547,12 @@ impl Parser {
                     RecoveredKind::UnexpectedEqualsInExpression => {
                         "unexpected '=' in expression".to_string()
                     }
+                    RecoveredKind::AttributesMustPrecedeDeclaration => {
+                        "attributes must come before a type, function, or constant declaration"
+                            .to_string()
+                    }
                 };
-                Some(PhaseDiagnostic::new(message, span.clone()))
+                Some(PhaseDiagnostic::new(message, *span))
             }
             ParseError::UnparsableToken => None,
         }
@@ -442,9 +578,11 @@ impl ExpressionSpan for SyntaxExpression {
             | SyntaxExpression::Call { span, .. }
             | SyntaxExpression::Unary { span, .. }
             | SyntaxExpression::Binary { span, .. }
+            | SyntaxExpression::If { span, .. }
+            | SyntaxExpression::Block { span, .. }
             | SyntaxExpression::Match { span, .. }
             | SyntaxExpression::Matches { span, .. }
-            | SyntaxExpression::StringInterpolation { span, .. } => span.clone(),
+            | SyntaxExpression::StringInterpolation { span, .. } => *span,
         }
     }
 }
@@ -0,0 +1,88 @@
+use crate::lexer::{ContextualKeyword, Symbol};
+use compiler__source::Span;
+use compiler__syntax::{SyntaxPackageDeclaration, SyntaxPackageField, SyntaxPackageFieldValue};
+
+use super::{ParseResult, Parser};
+
+impl Parser {
+    pub(super) fn parse_package_declaration(&mut self) -> ParseResult<SyntaxPackageDeclaration> {
+        let start = self.expect_contextual_keyword(ContextualKeyword::Package)?;
+        self.expect_symbol(Symbol::LeftBrace)?;
+        let fields = self.parse_package_fields();
+        let end = self.expect_symbol(Symbol::RightBrace)?;
+        Ok(SyntaxPackageDeclaration {
+            fields,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    fn parse_package_fields(&mut self) -> Vec<SyntaxPackageField> {
+        let mut fields = Vec::new();
+        self.skip_statement_terminators();
+        if self.peek_is_symbol(Symbol::RightBrace) {
+            return fields;
+        }
+
+        loop {
+            self.skip_statement_terminators();
+            if let Some(field) =
+                self.parse_list_item_with_recovery(Symbol::Comma, Symbol::RightBrace, |parser| {
+                    parser.parse_package_field()
+                })
+            {
+                fields.push(field);
+            } else if self.peek_is_symbol(Symbol::RightBrace) {
+                break;
+            }
+
+            self.skip_statement_terminators();
+            if self.peek_is_symbol(Symbol::Comma) {
+                self.advance();
+                self.skip_statement_terminators();
+                if self.peek_is_symbol(Symbol::RightBrace) {
+                    break;
+                }
+                continue;
+            }
+            break;
+        }
+
+        fields
+    }
+
+    fn parse_package_field(&mut self) -> ParseResult<SyntaxPackageField> {
+        let (name, name_span) = self.expect_identifier()?;
+        self.expect_symbol(Symbol::Colon)?;
+        let value = if self.peek_is_symbol(Symbol::LeftBracket) {
+            self.parse_package_field_list()?
+        } else {
+            let (value, span) = self.expect_string_literal()?;
+            SyntaxPackageFieldValue::String { value, span }
+        };
+        Ok(SyntaxPackageField {
+            name,
+            name_span,
+            value,
+        })
+    }
+
+    fn parse_package_field_list(&mut self) -> ParseResult<SyntaxPackageFieldValue> {
+        let start = self.expect_symbol(Symbol::LeftBracket)?;
+        let mut items = Vec::new();
+        self.skip_statement_terminators();
+        while !self.peek_is_symbol(Symbol::RightBracket) {
+            items.push(self.expect_string_literal()?);
+            self.skip_statement_terminators();
+            if !self.peek_is_symbol(Symbol::Comma) {
+                break;
+            }
+            self.advance();
+            self.skip_statement_terminators();
+        }
+        let end = self.expect_symbol(Symbol::RightBracket)?;
+        Ok(SyntaxPackageFieldValue::List {
+            items,
+            span: Span::new(start.start, end.end),
+        })
+    }
+}
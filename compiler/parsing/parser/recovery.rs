@@ -1,4 +1,4 @@
-use crate::lexer::{Keyword, Symbol, TokenKind};
+use crate::lexer::{ContextualKeyword, Keyword, Symbol, TokenKind};
 use compiler__source::Span;
 
 use super::{MissingTokenKind, ParseError, ParseResult, Parser, UnexpectedTokenKind};
@@ -89,6 +89,20 @@ impl Parser {
         }
     }
 
+    pub(super) fn expect_contextual_keyword(
+        &mut self,
+        keyword: ContextualKeyword,
+    ) -> ParseResult<Span> {
+        let token = self.advance();
+        match &token.kind {
+            TokenKind::Identifier(name) if name == keyword.as_str() => Ok(token.span),
+            _ => Err(ParseError::MissingToken {
+                kind: MissingTokenKind::ContextualKeyword { keyword },
+                span: token.span,
+            }),
+        }
+    }
+
     pub(super) fn expect_symbol(&mut self, symbol: Symbol) -> ParseResult<Span> {
         let token = self.advance();
         match token.kind {
@@ -119,10 +133,12 @@ impl Parser {
             if brace_depth == 0 {
                 if self.peek_is_keyword(Keyword::Import)
                     || self.peek_is_keyword(Keyword::Exports)
+                    || self.peek_is_contextual_keyword(ContextualKeyword::Package)
                     || self.peek_is_keyword(Keyword::Type)
                     || self.peek_is_keyword(Keyword::Function)
                     || self.peek_is_keyword(Keyword::Group)
                     || self.peek_is_keyword(Keyword::Test)
+                    || self.peek_is_contextual_keyword(ContextualKeyword::StaticAssert)
                 {
                     return;
                 }
@@ -158,7 +174,9 @@ impl Parser {
                 || self.peek_is_keyword(Keyword::Assert)
                 || self.peek_is_keyword(Keyword::Break)
                 || self.peek_is_keyword(Keyword::Continue)
+                || self.peek_starts_contextual_statement(ContextualKeyword::Defer)
                 || self.peek_is_keyword(Keyword::If)
+                || self.peek_starts_contextual_statement(ContextualKeyword::Guard)
                 || self.peek_is_keyword(Keyword::For)
                 || self.peek_is_keyword(Keyword::Print)
             {
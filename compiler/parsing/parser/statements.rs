@@ -1,15 +1,15 @@
-use crate::lexer::{Keyword, Symbol, TokenKind};
+use crate::lexer::{ContextualKeyword, Keyword, Symbol, Token, TokenKind};
 use compiler__source::Span;
 use compiler__syntax::{
     SyntaxAssignTarget, SyntaxBlock, SyntaxBlockItem, SyntaxExpression, SyntaxStatement,
 };
 
-use super::{ExpressionSpan, ParseResult, Parser};
+use super::{ExpressionSpan, InvalidConstructKind, ParseError, ParseResult, Parser};
 
 impl Parser {
     fn parse_condition_expression_with_recovery(&mut self) -> ParseResult<SyntaxExpression> {
         let checkpoint = self.checkpoint();
-        match self.parse_expression() {
+        match self.parse_expression_before_block() {
             Ok(expression) => Ok(expression),
             Err(error) => {
                 self.restore(checkpoint);
@@ -48,6 +48,10 @@ impl Parser {
     }
 
     pub(super) fn parse_block(&mut self) -> ParseResult<SyntaxBlock> {
+        self.with_block_expressions_allowed(true, Self::parse_block_items)
+    }
+
+    fn parse_block_items(&mut self) -> ParseResult<SyntaxBlock> {
         let start = self.expect_symbol(Symbol::LeftBrace)?;
         let mut items = Vec::new();
         self.skip_statement_terminators();
@@ -69,12 +73,7 @@ impl Parser {
         let end = self.expect_symbol(Symbol::RightBrace)?;
         Ok(SyntaxBlock {
             items,
-            span: Span {
-                start: start.start,
-                end: end.end,
-                line: start.line,
-                column: start.column,
-            },
+            span: Span::new(start.start, end.end),
         })
     }
 
@@ -96,6 +95,12 @@ impl Parser {
             let span = self.expect_keyword(Keyword::Continue)?;
             return Ok(SyntaxStatement::Continue { span });
         }
+        if self.peek_starts_contextual_statement(ContextualKeyword::Defer) {
+            let start = self.expect_contextual_keyword(ContextualKeyword::Defer)?;
+            let value = self.parse_expression()?;
+            let span = Span::new(start.start, value.span().end);
+            return Ok(SyntaxStatement::Defer { value, span });
+        }
         if self.peek_is_keyword(Keyword::If) {
             let start = self.expect_keyword(Keyword::If)?;
             let condition = match self.parse_condition_expression_with_recovery() {
@@ -121,13 +126,8 @@ impl Parser {
             };
             let end_span = else_block
                 .as_ref()
-                .map_or_else(|| then_block.span.clone(), |block| block.span.clone());
-            let span = Span {
-                start: start.start,
-                end: end_span.end,
-                line: start.line,
-                column: start.column,
-            };
+                .map_or_else(|| then_block.span, |block| block.span);
+            let span = Span::new(start.start, end_span.end);
             return Ok(SyntaxStatement::If {
                 condition,
                 then_block,
@@ -135,6 +135,37 @@ impl Parser {
                 span,
             });
         }
+        if self.peek_starts_contextual_statement(ContextualKeyword::Guard) {
+            let start = self.expect_contextual_keyword(ContextualKeyword::Guard)?;
+            let condition = match self.parse_condition_expression_with_recovery() {
+                Ok(condition) => condition,
+                Err(error) => {
+                    self.consume_condition_block_after_recovery();
+                    return Err(error);
+                }
+            };
+            if !self.peek_is_keyword(Keyword::Else) {
+                let end = condition.span().end;
+                self.consume_condition_block_after_recovery();
+                return Err(ParseError::InvalidConstruct {
+                    kind: InvalidConstructKind::GuardRequiresElse,
+                    span: Span::new(start.start, end),
+                });
+            }
+            self.advance();
+            let else_block = self.parse_condition_block_with_recovery()?;
+            let span = Span::new(start.start, else_block.span.end);
+            return Ok(SyntaxStatement::Guard {
+                condition,
+                else_block,
+                span,
+            });
+        }
+        if self.peek_is_keyword(Keyword::For)
+            && self.peek_n_is_contextual_keyword(2, ContextualKeyword::In)
+        {
+            return self.parse_range_for_statement();
+        }
         if self.peek_is_keyword(Keyword::For) {
             let start = self.expect_keyword(Keyword::For)?;
             let condition = if self.peek_is_symbol(Symbol::LeftBrace) {
@@ -149,12 +180,7 @@ impl Parser {
                 }
             };
             let body = self.parse_condition_block_with_recovery()?;
-            let span = Span {
-                start: start.start,
-                end: body.span.end,
-                line: start.line,
-                column: start.column,
-            };
+            let span = Span::new(start.start, body.span.end);
             return Ok(SyntaxStatement::For {
                 condition,
                 body,
@@ -173,12 +199,7 @@ impl Parser {
             };
             self.expect_symbol(Symbol::Assign)?;
             let initializer = self.parse_expression()?;
-            let span = Span {
-                start: name_span.start,
-                end: initializer.span().end,
-                line: name_span.line,
-                column: name_span.column,
-            };
+            let span = Span::new(name_span.start, initializer.span().end);
             return Ok(SyntaxStatement::Binding {
                 name,
                 name_span,
@@ -199,16 +220,11 @@ impl Parser {
                 let value = self.parse_expression()?;
                 return match assignment_target {
                     SyntaxExpression::NameReference { name, span, .. } => {
-                        let statement_span = Span {
-                            start: span.start,
-                            end: value.span().end,
-                            line: span.line,
-                            column: span.column,
-                        };
+                        let statement_span = Span::new(span.start, value.span().end);
                         Ok(SyntaxStatement::Assign {
                             target: SyntaxAssignTarget::Name {
                                 name,
-                                name_span: span.clone(),
+                                name_span: span,
                                 span,
                             },
                             value,
@@ -220,12 +236,8 @@ impl Parser {
                         index,
                         span,
                     } => {
-                        let statement_span = Span {
-                            start: span.start,
-                            end: value.span().end,
-                            line: span.line,
-                            column: span.column,
-                        };
+                        let statement_span =
+                            Span::new(span.start, value.span().end).anchored_at(span.anchor);
                         Ok(SyntaxStatement::Assign {
                             target: SyntaxAssignTarget::Index {
                                 target,
@@ -260,12 +272,7 @@ impl Parser {
             };
             self.expect_symbol(Symbol::Assign)?;
             let initializer = self.parse_expression()?;
-            let span = Span {
-                start: name_span.start,
-                end: initializer.span().end,
-                line: name_span.line,
-                column: name_span.column,
-            };
+            let span = Span::new(name_span.start, initializer.span().end);
             return Ok(SyntaxStatement::Binding {
                 name,
                 name_span,
@@ -281,6 +288,126 @@ impl Parser {
         Ok(SyntaxStatement::Expression { value, span })
     }
 
+    // `defer` and `guard` begin a statement only when an operand follows
+    // directly, which cannot happen where they are names. An operand that
+    // could also continue a name, such as `(x)` or `[x]`, makes `guard` a
+    // statement when its `else` follows, and makes `defer` a statement unless
+    // the parentheses are a complete call.
+    pub(super) fn peek_starts_contextual_statement(&self, keyword: ContextualKeyword) -> bool {
+        if !self.peek_is_contextual_keyword(keyword) {
+            return false;
+        }
+        match &self.peek_n(1).kind {
+            TokenKind::Identifier(_)
+            | TokenKind::IntegerLiteral(_)
+            | TokenKind::StringLiteral(_)
+            | TokenKind::StringInterpolationStart(_)
+            | TokenKind::BooleanLiteral(_)
+            | TokenKind::Keyword(
+                Keyword::Not
+                | Keyword::Nil
+                | Keyword::If
+                | Keyword::Match
+                | Keyword::Abort
+                | Keyword::Assert
+                | Keyword::Print,
+            )
+            | TokenKind::Symbol(Symbol::LeftBrace) => true,
+            TokenKind::Symbol(Symbol::LeftParenthesis | Symbol::LeftBracket | Symbol::Minus)
+                if keyword == ContextualKeyword::Guard =>
+            {
+                self.else_follows_in_statement()
+            }
+            TokenKind::Symbol(Symbol::LeftParenthesis) => !matches!(
+                self.token_after_parenthesized_group(1).kind,
+                TokenKind::StatementTerminator
+                    | TokenKind::EndOfFile
+                    | TokenKind::Symbol(
+                        Symbol::RightBrace
+                            | Symbol::Dot
+                            | Symbol::LeftParenthesis
+                            | Symbol::LeftBracket
+                            | Symbol::Assign
+                    )
+            ),
+            _ => false,
+        }
+    }
+
+    fn else_follows_in_statement(&self) -> bool {
+        let mut depth = 0usize;
+        let mut offset = 1;
+        loop {
+            match self.peek_n(offset).kind {
+                TokenKind::Symbol(
+                    Symbol::LeftParenthesis | Symbol::LeftBracket | Symbol::LeftBrace,
+                ) => depth += 1,
+                TokenKind::Symbol(
+                    Symbol::RightParenthesis | Symbol::RightBracket | Symbol::RightBrace,
+                ) => {
+                    if depth == 0 {
+                        return false;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::Keyword(Keyword::Else) if depth == 0 => return true,
+                TokenKind::Symbol(Symbol::Assign) | TokenKind::StatementTerminator
+                    if depth == 0 =>
+                {
+                    return false;
+                }
+                TokenKind::EndOfFile => return false,
+                _ => {}
+            }
+            offset += 1;
+        }
+    }
+
+    fn token_after_parenthesized_group(&self, open_offset: usize) -> &Token {
+        let mut depth = 0usize;
+        let mut offset = open_offset;
+        loop {
+            match self.peek_n(offset).kind {
+                TokenKind::Symbol(Symbol::LeftParenthesis) => depth += 1,
+                TokenKind::Symbol(Symbol::RightParenthesis) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.peek_n(offset + 1);
+                    }
+                }
+                TokenKind::EndOfFile => return self.peek_n(offset),
+                _ => {}
+            }
+            offset += 1;
+        }
+    }
+
+    fn parse_range_for_statement(&mut self) -> ParseResult<SyntaxStatement> {
+        let start_span = self.expect_keyword(Keyword::For)?;
+        let (name, name_span) = self.expect_identifier()?;
+        self.expect_contextual_keyword(ContextualKeyword::In)?;
+        let start = Box::new(self.parse_expression_before_block()?);
+        self.expect_symbol(Symbol::DotDot)?;
+        let end = Box::new(self.parse_expression_before_block()?);
+        let step = if self.peek_is_contextual_keyword(ContextualKeyword::Step) {
+            self.advance();
+            Some(Box::new(self.parse_expression_before_block()?))
+        } else {
+            None
+        };
+        let body = self.parse_condition_block_with_recovery()?;
+        let span = Span::new(start_span.start, body.span.end);
+        Ok(SyntaxStatement::ForRange {
+            name,
+            name_span,
+            start,
+            end,
+            step,
+            body,
+            span,
+        })
+    }
+
     fn can_start_return_expression(&self) -> bool {
         !matches!(
             self.peek().kind,
@@ -15,25 +15,20 @@ impl Parser {
             let next = self.parse_type_name_member()?;
             names.push(next);
         }
-        let first_span = names[0].span.clone();
+        let first_span = names[0].span;
         let end = names
             .last()
             .map_or(first_span.end, |segment| segment.span.end);
         Ok(SyntaxTypeName {
             names,
-            span: Span {
-                start: first_span.start,
-                end,
-                line: first_span.line,
-                column: first_span.column,
-            },
+            span: Span::new(first_span.start, end),
         })
     }
 
     pub(super) fn parse_union_type_declaration(&mut self) -> ParseResult<Vec<SyntaxTypeName>> {
         let mut variants = Vec::new();
         let first_segment = self.parse_type_name_member()?;
-        let first_segment_span = first_segment.span.clone();
+        let first_segment_span = first_segment.span;
         variants.push(SyntaxTypeName {
             names: vec![first_segment],
             span: first_segment_span,
@@ -43,7 +38,7 @@ impl Parser {
             let segment = self.parse_type_name_member()?;
             variants.push(SyntaxTypeName {
                 names: vec![segment.clone()],
-                span: segment.span.clone(),
+                span: segment.span,
             });
         }
         Ok(variants)
@@ -110,12 +105,7 @@ impl Parser {
         Ok(SyntaxTypeNameSegment {
             name: "function".to_string(),
             type_arguments: function_type_arguments,
-            span: Span {
-                start: function_span.start,
-                end: return_type_name.span.end,
-                line: function_span.line,
-                column: function_span.column,
-            },
+            span: Span::new(function_span.start, return_type_name.span.end),
         })
     }
 
@@ -219,10 +209,7 @@ impl Parser {
         loop {
             self.skip_statement_terminators();
             let (name, span) = self.expect_identifier()?;
-            variants.push(SyntaxEnumVariant {
-                name,
-                span: span.clone(),
-            });
+            variants.push(SyntaxEnumVariant { name, span });
 
             self.skip_statement_terminators();
             if self.peek_is_symbol(Symbol::Comma) {
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use compiler__diagnostics::{FileScopedDiagnostic, PhaseDiagnostic};
 use compiler__safe_autofix::SafeAutofix;
 
+#[derive(Clone)]
 pub struct PhaseOutput<T> {
     pub value: T,
     pub diagnostics: Vec<PhaseDiagnostic>,
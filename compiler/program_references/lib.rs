@@ -0,0 +1,44 @@
+use compiler__interned_names::{PackagePath, SymbolName};
+use serde::{Deserialize, Serialize};
+
+// References to top-level declarations, shared by the type-annotated and
+// executable programs so that lowering clones them instead of converting them.
+// A declaration is named by its package and its symbol name within that
+// package.
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CallableReference {
+    pub package_path: PackagePath,
+    pub symbol_name: SymbolName,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StructReference {
+    pub package_path: PackagePath,
+    pub symbol_name: SymbolName,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InterfaceReference {
+    pub package_path: PackagePath,
+    pub symbol_name: SymbolName,
+}
+
+// A struct or interface used as a type.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NominalTypeReference {
+    pub package_path: PackagePath,
+    pub symbol_name: SymbolName,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ConstantReference {
+    pub package_path: PackagePath,
+    pub symbol_name: SymbolName,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EnumVariantReference {
+    pub enum_name: String,
+    pub variant_name: String,
+}
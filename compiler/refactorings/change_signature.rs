@@ -0,0 +1,587 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use compiler__fix_edits::{TextEdit, apply_text_edits};
+use compiler__program_references::CallableReference;
+use compiler__source::Span;
+use compiler__type_annotated_program::{
+    TypeAnnotatedAssignTarget, TypeAnnotatedCallTarget, TypeAnnotatedExpression,
+    TypeAnnotatedMethodReference, TypeAnnotatedParameterDeclaration, TypeAnnotatedStatement,
+    TypeAnnotatedStringInterpolationPart, TypeResolvedDeclarations,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignatureTarget {
+    Function(CallableReference),
+    Method(TypeAnnotatedMethodReference),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignatureParameter {
+    Existing {
+        index: usize,
+    },
+    Added {
+        name: String,
+        type_name: String,
+        placeholder_argument: String,
+    },
+}
+
+// The new parameter list, in order. Existing parameters that are not listed
+// are removed, along with their arguments at every call site.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureChange {
+    pub target: SignatureTarget,
+    pub parameters: Vec<SignatureParameter>,
+}
+
+pub struct ChangeSignatureFileInput<'a> {
+    pub path: &'a str,
+    pub source_text: &'a str,
+    pub declarations: &'a TypeResolvedDeclarations,
+}
+
+#[derive(Clone, Debug)]
+pub enum ChangeSignatureError {
+    DeclarationNotFound,
+    ParameterIndexOutOfRange { index: usize },
+    ParameterListedTwice { index: usize },
+    DuplicateParameterName { name: String },
+    MethodImplementsInterfaceMethod,
+    FunctionUsedAsValue { path: String, span: Span },
+    CallSiteNotRewritable { path: String, span: Span },
+}
+
+// Call sites are found through the call targets that type analysis recorded,
+// so every file that can reach the target must be in `files`. Method calls
+// dispatched through an interface cannot be told apart from calls to other
+// implementations, so methods that implement an interface method are rejected.
+pub fn change_signature_text_edits(
+    files: &[ChangeSignatureFileInput<'_>],
+    signature_change: &SignatureChange,
+) -> Result<BTreeMap<String, Vec<TextEdit>>, ChangeSignatureError> {
+    let (declaration_file, declaration) = find_signature_declaration(files, signature_change)?;
+    validate_signature_parameters(&signature_change.parameters, declaration.parameters)?;
+
+    let mut text_edits_by_path = BTreeMap::<String, Vec<TextEdit>>::new();
+    text_edits_by_path
+        .entry(declaration_file.path.to_string())
+        .or_default()
+        .push(declaration_text_edit(
+            declaration_file,
+            &declaration,
+            &signature_change.parameters,
+        )?);
+    for file in files {
+        let call_site_rewriter = CallSiteRewriter {
+            file,
+            signature_change,
+            declared_parameter_count: declaration.parameters.len(),
+        };
+        let text_edits = call_site_rewriter.file_text_edits()?;
+        if !text_edits.is_empty() {
+            text_edits_by_path
+                .entry(file.path.to_string())
+                .or_default()
+                .extend(text_edits);
+        }
+    }
+    for text_edits in text_edits_by_path.values_mut() {
+        text_edits.sort_by_key(|text_edit| text_edit.start_byte_offset);
+    }
+    Ok(text_edits_by_path)
+}
+
+struct SignatureDeclaration<'a> {
+    span: &'a Span,
+    is_method: bool,
+    parameters: &'a [TypeAnnotatedParameterDeclaration],
+}
+
+fn find_signature_declaration<'a>(
+    files: &'a [ChangeSignatureFileInput<'a>],
+    signature_change: &SignatureChange,
+) -> Result<(&'a ChangeSignatureFileInput<'a>, SignatureDeclaration<'a>), ChangeSignatureError> {
+    for file in files {
+        match &signature_change.target {
+            SignatureTarget::Function(callable_reference) => {
+                if let Some(function) = file
+                    .declarations
+                    .function_declarations
+                    .iter()
+                    .find(|function| function.callable_reference == *callable_reference)
+                {
+                    return Ok((
+                        file,
+                        SignatureDeclaration {
+                            span: &function.span,
+                            is_method: false,
+                            parameters: &function.parameters,
+                        },
+                    ));
+                }
+            }
+            SignatureTarget::Method(method_reference) => {
+                let receiver_type_reference = &method_reference.receiver_type_reference;
+                let Some(struct_declaration) =
+                    file.declarations
+                        .struct_declarations
+                        .iter()
+                        .find(|struct_declaration| {
+                            struct_declaration.struct_reference.package_path
+                                == receiver_type_reference.package_path
+                                && struct_declaration.struct_reference.symbol_name
+                                    == receiver_type_reference.symbol_name
+                        })
+                else {
+                    continue;
+                };
+                let method = struct_declaration
+                    .methods
+                    .iter()
+                    .find(|method| method.name == method_reference.method_name)
+                    .ok_or(ChangeSignatureError::DeclarationNotFound)?;
+                let implements_interface_method = struct_declaration
+                    .implemented_interfaces
+                    .iter()
+                    .any(|interface_reference| {
+                        files
+                            .iter()
+                            .flat_map(|file| &file.declarations.interface_declarations)
+                            .filter(|interface| {
+                                interface.interface_reference == *interface_reference
+                            })
+                            .flat_map(|interface| &interface.methods)
+                            .any(|interface_method| interface_method.name == method.name)
+                    });
+                if implements_interface_method {
+                    return Err(ChangeSignatureError::MethodImplementsInterfaceMethod);
+                }
+                return Ok((
+                    file,
+                    SignatureDeclaration {
+                        span: &method.span,
+                        is_method: true,
+                        parameters: &method.parameters,
+                    },
+                ));
+            }
+        }
+    }
+    Err(ChangeSignatureError::DeclarationNotFound)
+}
+
+fn validate_signature_parameters(
+    signature_parameters: &[SignatureParameter],
+    declared_parameters: &[TypeAnnotatedParameterDeclaration],
+) -> Result<(), ChangeSignatureError> {
+    let mut listed_indices = BTreeSet::new();
+    let mut parameter_names = BTreeSet::new();
+    for signature_parameter in signature_parameters {
+        let name = match signature_parameter {
+            SignatureParameter::Existing { index } => {
+                if *index >= declared_parameters.len() {
+                    return Err(ChangeSignatureError::ParameterIndexOutOfRange { index: *index });
+                }
+                if !listed_indices.insert(*index) {
+                    return Err(ChangeSignatureError::ParameterListedTwice { index: *index });
+                }
+                &declared_parameters[*index].name
+            }
+            SignatureParameter::Added { name, .. } => name,
+        };
+        if !parameter_names.insert(name.as_str()) {
+            return Err(ChangeSignatureError::DuplicateParameterName { name: name.clone() });
+        }
+    }
+    Ok(())
+}
+
+// The whole parameter list is rewritten on one line. A method keeps its
+// receiver text (`self` or `mut self`) in front.
+fn declaration_text_edit(
+    file: &ChangeSignatureFileInput<'_>,
+    declaration: &SignatureDeclaration<'_>,
+    signature_parameters: &[SignatureParameter],
+) -> Result<TextEdit, ChangeSignatureError> {
+    let not_rewritable = || ChangeSignatureError::CallSiteNotRewritable {
+        path: file.path.to_string(),
+        span: *declaration.span,
+    };
+    let source_text = file.source_text;
+    let declaration_text = source_text
+        .get(declaration.span.start..declaration.span.end)
+        .ok_or_else(not_rewritable)?;
+    let open_offset =
+        declaration.span.start + declaration_text.find('(').ok_or_else(not_rewritable)?;
+    let close_offset =
+        matching_close_parenthesis(source_text, open_offset).ok_or_else(not_rewritable)?;
+
+    let mut parameter_texts = Vec::new();
+    if declaration.is_method {
+        let receiver_text = source_text[open_offset + 1..close_offset]
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        parameter_texts.push(receiver_text.to_string());
+    }
+    for signature_parameter in signature_parameters {
+        parameter_texts.push(match signature_parameter {
+            SignatureParameter::Existing { index } => {
+                let span = &declaration.parameters[*index].span;
+                source_text[span.start..span.end].to_string()
+            }
+            SignatureParameter::Added {
+                name, type_name, ..
+            } => format!("{name}: {type_name}"),
+        });
+    }
+    Ok(TextEdit {
+        start_byte_offset: open_offset + 1,
+        end_byte_offset: close_offset,
+        replacement_text: parameter_texts.join(", "),
+    })
+}
+
+struct CallSiteRewriter<'a> {
+    file: &'a ChangeSignatureFileInput<'a>,
+    signature_change: &'a SignatureChange,
+    declared_parameter_count: usize,
+}
+
+impl CallSiteRewriter<'_> {
+    fn file_text_edits(&self) -> Result<Vec<TextEdit>, ChangeSignatureError> {
+        let declarations = self.file.declarations;
+        let mut text_edits = Vec::new();
+        for constant in &declarations.constant_declarations {
+            text_edits.extend(self.expression_text_edits(&constant.initializer)?);
+        }
+        for struct_declaration in &declarations.struct_declarations {
+            for method in &struct_declaration.methods {
+                text_edits.extend(self.statements_text_edits(&method.statements)?);
+            }
+        }
+        for function in &declarations.function_declarations {
+            text_edits.extend(self.statements_text_edits(&function.statements)?);
+        }
+        Ok(text_edits)
+    }
+
+    fn statements_text_edits(
+        &self,
+        statements: &[TypeAnnotatedStatement],
+    ) -> Result<Vec<TextEdit>, ChangeSignatureError> {
+        let mut text_edits = Vec::new();
+        for statement in statements {
+            match statement {
+                TypeAnnotatedStatement::Binding { initializer, .. } => {
+                    text_edits.extend(self.expression_text_edits(initializer)?);
+                }
+                TypeAnnotatedStatement::Assign { target, value, .. } => {
+                    if let TypeAnnotatedAssignTarget::Index { target, index, .. } = target {
+                        text_edits.extend(self.expression_text_edits(target)?);
+                        text_edits.extend(self.expression_text_edits(index)?);
+                    }
+                    text_edits.extend(self.expression_text_edits(value)?);
+                }
+                TypeAnnotatedStatement::If {
+                    condition,
+                    then_statements,
+                    else_statements,
+                    ..
+                } => {
+                    text_edits.extend(self.expression_text_edits(condition)?);
+                    text_edits.extend(self.statements_text_edits(then_statements)?);
+                    if let Some(else_statements) = else_statements {
+                        text_edits.extend(self.statements_text_edits(else_statements)?);
+                    }
+                }
+                TypeAnnotatedStatement::For {
+                    condition,
+                    body_statements,
+                    ..
+                } => {
+                    if let Some(condition) = condition {
+                        text_edits.extend(self.expression_text_edits(condition)?);
+                    }
+                    text_edits.extend(self.statements_text_edits(body_statements)?);
+                }
+                TypeAnnotatedStatement::ForRange {
+                    start,
+                    end,
+                    step,
+                    body_statements,
+                    ..
+                } => {
+                    text_edits.extend(self.expression_text_edits(start)?);
+                    text_edits.extend(self.expression_text_edits(end)?);
+                    if let Some(step) = step {
+                        text_edits.extend(self.expression_text_edits(step)?);
+                    }
+                    text_edits.extend(self.statements_text_edits(body_statements)?);
+                }
+                TypeAnnotatedStatement::Defer { value, .. }
+                | TypeAnnotatedStatement::Expression { value, .. }
+                | TypeAnnotatedStatement::Return { value, .. } => {
+                    text_edits.extend(self.expression_text_edits(value)?);
+                }
+                TypeAnnotatedStatement::Break { .. } | TypeAnnotatedStatement::Continue { .. } => {}
+            }
+        }
+        Ok(text_edits)
+    }
+
+    // Edits never overlap: a rewritten call site replaces its whole argument
+    // list, so the edits for calls nested in its arguments are applied to the
+    // argument text instead of being returned separately.
+    fn expression_text_edits(
+        &self,
+        expression: &TypeAnnotatedExpression,
+    ) -> Result<Vec<TextEdit>, ChangeSignatureError> {
+        let mut text_edits = Vec::new();
+        match expression {
+            TypeAnnotatedExpression::IntegerLiteral { .. }
+            | TypeAnnotatedExpression::BooleanLiteral { .. }
+            | TypeAnnotatedExpression::NilLiteral { .. }
+            | TypeAnnotatedExpression::StringLiteral { .. }
+            | TypeAnnotatedExpression::EnumVariantLiteral { .. } => {}
+            TypeAnnotatedExpression::NameReference {
+                callable_reference,
+                span,
+                ..
+            } => {
+                if let SignatureTarget::Function(target_reference) = &self.signature_change.target
+                    && callable_reference.as_ref() == Some(target_reference)
+                {
+                    return Err(ChangeSignatureError::FunctionUsedAsValue {
+                        path: self.file.path.to_string(),
+                        span: *span,
+                    });
+                }
+            }
+            TypeAnnotatedExpression::ListLiteral { elements, .. } => {
+                for element in elements {
+                    text_edits.extend(self.expression_text_edits(element)?);
+                }
+            }
+            TypeAnnotatedExpression::StructLiteral { fields, .. } => {
+                for field in fields {
+                    text_edits.extend(self.expression_text_edits(&field.value)?);
+                }
+            }
+            TypeAnnotatedExpression::FieldAccess { target, .. }
+            | TypeAnnotatedExpression::EnumVariantName { value: target, .. }
+            | TypeAnnotatedExpression::EnumVariantFromString { value: target, .. }
+            | TypeAnnotatedExpression::Matches { value: target, .. } => {
+                text_edits.extend(self.expression_text_edits(target)?);
+            }
+            TypeAnnotatedExpression::IndexAccess { target, index, .. } => {
+                text_edits.extend(self.expression_text_edits(target)?);
+                text_edits.extend(self.expression_text_edits(index)?);
+            }
+            TypeAnnotatedExpression::Unary { expression, .. } => {
+                text_edits.extend(self.expression_text_edits(expression)?);
+            }
+            TypeAnnotatedExpression::Binary { left, right, .. } => {
+                text_edits.extend(self.expression_text_edits(left)?);
+                text_edits.extend(self.expression_text_edits(right)?);
+            }
+            TypeAnnotatedExpression::Call {
+                callee,
+                call_target,
+                arguments,
+                type_arguments,
+                span,
+                ..
+            } => {
+                if !matches!(
+                    callee.as_ref(),
+                    TypeAnnotatedExpression::NameReference { .. }
+                ) {
+                    text_edits.extend(self.expression_text_edits(callee)?);
+                }
+                let mut argument_text_edits = Vec::new();
+                for argument in arguments {
+                    argument_text_edits.push(self.expression_text_edits(argument)?);
+                }
+                if self.is_target_call(call_target.as_ref()) {
+                    let arguments_start = type_arguments
+                        .last()
+                        .map_or(expression_span(callee).end, |type_argument| {
+                            type_argument.span.end
+                        });
+                    text_edits.push(self.call_site_text_edit(
+                        span,
+                        arguments_start,
+                        arguments,
+                        &argument_text_edits,
+                    )?);
+                } else {
+                    text_edits.extend(argument_text_edits.into_iter().flatten());
+                }
+            }
+            TypeAnnotatedExpression::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                text_edits.extend(self.expression_text_edits(condition)?);
+                text_edits.extend(self.expression_text_edits(then_branch)?);
+                text_edits.extend(self.expression_text_edits(else_branch)?);
+            }
+            TypeAnnotatedExpression::Block {
+                statements, value, ..
+            } => {
+                text_edits.extend(self.statements_text_edits(statements)?);
+                text_edits.extend(self.expression_text_edits(value)?);
+            }
+            TypeAnnotatedExpression::Match { target, arms, .. } => {
+                text_edits.extend(self.expression_text_edits(target)?);
+                for arm in arms {
+                    if let Some(guard) = &arm.guard {
+                        text_edits.extend(self.expression_text_edits(guard)?);
+                    }
+                    text_edits.extend(self.expression_text_edits(&arm.value)?);
+                }
+            }
+            TypeAnnotatedExpression::StringInterpolation { parts, .. } => {
+                for part in parts {
+                    if let TypeAnnotatedStringInterpolationPart::Expression(expression) = part {
+                        text_edits.extend(self.expression_text_edits(expression)?);
+                    }
+                }
+            }
+        }
+        Ok(text_edits)
+    }
+
+    fn is_target_call(&self, call_target: Option<&TypeAnnotatedCallTarget>) -> bool {
+        match (&self.signature_change.target, call_target) {
+            (
+                SignatureTarget::Function(target_reference),
+                Some(TypeAnnotatedCallTarget::UserDefinedFunction { callable_reference }),
+            ) => callable_reference == target_reference,
+            (
+                SignatureTarget::Method(target_reference),
+                Some(TypeAnnotatedCallTarget::UserDefinedMethod { method_reference }),
+            ) => method_reference == target_reference,
+            _ => false,
+        }
+    }
+
+    fn call_site_text_edit(
+        &self,
+        call_span: &Span,
+        arguments_start: usize,
+        arguments: &[TypeAnnotatedExpression],
+        argument_text_edits: &[Vec<TextEdit>],
+    ) -> Result<TextEdit, ChangeSignatureError> {
+        let not_rewritable = || ChangeSignatureError::CallSiteNotRewritable {
+            path: self.file.path.to_string(),
+            span: *call_span,
+        };
+        let source_text = self.file.source_text;
+        if arguments.len() != self.declared_parameter_count {
+            return Err(not_rewritable());
+        }
+        let open_offset = arguments_start
+            + source_text
+                .get(arguments_start..call_span.end)
+                .and_then(|text| text.find('('))
+                .ok_or_else(not_rewritable)?;
+        let close_offset =
+            matching_close_parenthesis(source_text, open_offset).ok_or_else(not_rewritable)?;
+        if close_offset + 1 != call_span.end {
+            return Err(not_rewritable());
+        }
+
+        let mut argument_texts = Vec::new();
+        for signature_parameter in &self.signature_change.parameters {
+            argument_texts.push(match signature_parameter {
+                SignatureParameter::Existing { index } => {
+                    let span = expression_span(&arguments[*index]);
+                    let shifted_text_edits = argument_text_edits[*index]
+                        .iter()
+                        .map(|text_edit| TextEdit {
+                            start_byte_offset: text_edit.start_byte_offset - span.start,
+                            end_byte_offset: text_edit.end_byte_offset - span.start,
+                            replacement_text: text_edit.replacement_text.clone(),
+                        })
+                        .collect::<Vec<_>>();
+                    let argument_text = source_text
+                        .get(span.start..span.end)
+                        .ok_or_else(not_rewritable)?;
+                    apply_text_edits(argument_text, &shifted_text_edits)
+                        .map_err(|_| not_rewritable())?
+                }
+                SignatureParameter::Added {
+                    placeholder_argument,
+                    ..
+                } => placeholder_argument.clone(),
+            });
+        }
+        Ok(TextEdit {
+            start_byte_offset: open_offset + 1,
+            end_byte_offset: close_offset,
+            replacement_text: argument_texts.join(", "),
+        })
+    }
+}
+
+// Parameter lists and argument lists may nest parentheses through function
+// types and calls. Parentheses inside string literals are skipped.
+fn matching_close_parenthesis(source_text: &str, open_offset: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, character) in source_text[open_offset..].char_indices() {
+        if in_string {
+            match character {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match character {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open_offset + offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn expression_span(expression: &TypeAnnotatedExpression) -> &Span {
+    match expression {
+        TypeAnnotatedExpression::IntegerLiteral { span, .. }
+        | TypeAnnotatedExpression::BooleanLiteral { span, .. }
+        | TypeAnnotatedExpression::NilLiteral { span }
+        | TypeAnnotatedExpression::StringLiteral { span, .. }
+        | TypeAnnotatedExpression::ListLiteral { span, .. }
+        | TypeAnnotatedExpression::NameReference { span, .. }
+        | TypeAnnotatedExpression::EnumVariantLiteral { span, .. }
+        | TypeAnnotatedExpression::EnumVariantName { span, .. }
+        | TypeAnnotatedExpression::EnumVariantFromString { span, .. }
+        | TypeAnnotatedExpression::StructLiteral { span, .. }
+        | TypeAnnotatedExpression::FieldAccess { span, .. }
+        | TypeAnnotatedExpression::IndexAccess { span, .. }
+        | TypeAnnotatedExpression::Unary { span, .. }
+        | TypeAnnotatedExpression::Binary { span, .. }
+        | TypeAnnotatedExpression::Call { span, .. }
+        | TypeAnnotatedExpression::If { span, .. }
+        | TypeAnnotatedExpression::Block { span, .. }
+        | TypeAnnotatedExpression::Match { span, .. }
+        | TypeAnnotatedExpression::Matches { span, .. }
+        | TypeAnnotatedExpression::StringInterpolation { span, .. } => span,
+    }
+}
@@ -0,0 +1,82 @@
+use compiler__fix_edits::TextEdit;
+use compiler__source::{Span, leading_comment_start, line_end, line_removal_range, line_start};
+use compiler__type_annotated_program::TypeAnnotatedInlineCandidate;
+
+use crate::{Refactoring, RefactoringKind};
+
+#[must_use]
+pub fn inline_refactorings(
+    source_text: &str,
+    inline_candidates: &[TypeAnnotatedInlineCandidate],
+) -> Vec<Refactoring> {
+    inline_candidates
+        .iter()
+        .filter_map(|inline_candidate| inline_refactoring(source_text, inline_candidate))
+        .collect()
+}
+
+fn inline_refactoring(
+    source_text: &str,
+    inline_candidate: &TypeAnnotatedInlineCandidate,
+) -> Option<Refactoring> {
+    let initializer_text = source_text
+        .get(inline_candidate.initializer_span.start..inline_candidate.initializer_span.end)?;
+    let replacement_text = if inline_candidate.initializer_requires_parentheses {
+        format!("({initializer_text})")
+    } else {
+        initializer_text.to_string()
+    };
+    let (removal_start, removal_end) =
+        declaration_removal_range(source_text, &inline_candidate.declaration_span)?;
+    let mut text_edits = vec![TextEdit {
+        start_byte_offset: removal_start,
+        end_byte_offset: removal_end,
+        replacement_text: String::new(),
+    }];
+    for reference_span in &inline_candidate.reference_spans {
+        if source_text.get(reference_span.start..reference_span.end)
+            != Some(inline_candidate.name.as_str())
+        {
+            return None;
+        }
+        text_edits.push(TextEdit {
+            start_byte_offset: reference_span.start,
+            end_byte_offset: reference_span.end,
+            replacement_text: replacement_text.clone(),
+        });
+    }
+    text_edits.sort_by_key(|text_edit| text_edit.start_byte_offset);
+
+    let mut trigger_spans = vec![inline_candidate.name_span];
+    trigger_spans.extend(inline_candidate.reference_spans.iter().copied());
+    Some(Refactoring {
+        kind: RefactoringKind::Inline,
+        label: format!("inline '{}'", inline_candidate.name),
+        trigger_spans,
+        text_edits,
+    })
+}
+
+// The declaration must sit on lines of its own. Those lines are removed along
+// with the comment and attribute lines directly above them and, when the
+// declaration was surrounded by blank lines, one of those blank lines.
+fn declaration_removal_range(source_text: &str, declaration_span: &Span) -> Option<(usize, usize)> {
+    let declaration_line_start = line_start(source_text, declaration_span.start);
+    let declaration_line_end = line_end(source_text, declaration_span.end);
+    if !source_text[declaration_line_start..declaration_span.start]
+        .trim()
+        .is_empty()
+        || !source_text[declaration_span.end..declaration_line_end]
+            .trim()
+            .is_empty()
+    {
+        return None;
+    }
+
+    let removal_start = leading_comment_start(source_text, declaration_span.start)?;
+    Some(line_removal_range(
+        source_text,
+        removal_start,
+        declaration_span.end,
+    ))
+}
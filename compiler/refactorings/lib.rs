@@ -0,0 +1,30 @@
+mod change_signature;
+mod inline;
+mod move_declaration;
+
+use compiler__fix_edits::TextEdit;
+use compiler__source::Span;
+
+pub use change_signature::{
+    ChangeSignatureError, ChangeSignatureFileInput, SignatureChange, SignatureParameter,
+    SignatureTarget, change_signature_text_edits,
+};
+pub use inline::inline_refactorings;
+pub use move_declaration::{
+    MoveDeclarationError, MoveDeclarationFileInput, MovedSymbol, move_declaration,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefactoringKind {
+    Inline,
+}
+
+// A refactoring is offered wherever one of its trigger spans is selected. Its
+// edits all refer to the source snapshot the refactoring was computed from.
+#[derive(Clone, Debug)]
+pub struct Refactoring {
+    pub kind: RefactoringKind,
+    pub label: String,
+    pub trigger_spans: Vec<Span>,
+    pub text_edits: Vec<TextEdit>,
+}
@@ -0,0 +1,415 @@
+use compiler__fix_edits::apply_text_edits;
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__packages::PackageId;
+use compiler__parsing::parse_file;
+use compiler__program_references::{CallableReference, NominalTypeReference};
+use compiler__refactorings::{
+    ChangeSignatureError, ChangeSignatureFileInput, MoveDeclarationError, MoveDeclarationFileInput,
+    MovedSymbol, RefactoringKind, SignatureChange, SignatureParameter, SignatureTarget,
+    change_signature_text_edits, inline_refactorings, move_declaration,
+};
+use compiler__semantic_lowering::lower_parsed_file;
+use compiler__source::{FileRole, LanguageVersion, Span};
+use compiler__type_analysis::check_package_unit;
+use compiler__type_annotated_program::{
+    TypeAnnotatedInlineCandidate, TypeAnnotatedMethodReference, TypeResolvedDeclarations,
+};
+
+fn span_of(source_text: &str, text: &str, occurrence: usize) -> Span {
+    let start = source_text
+        .match_indices(text)
+        .nth(occurrence)
+        .expect("text should occur in source")
+        .0;
+    Span::new(start, start + text.len())
+}
+
+#[test]
+fn inline_refactorings_replace_references_and_remove_binding() {
+    let source_text = "\
+function run() -> int64 {
+    limit := 1 + 2
+    return limit * limit
+}
+";
+    let inline_candidate = TypeAnnotatedInlineCandidate {
+        name: "limit".to_string(),
+        name_span: span_of(source_text, "limit", 0),
+        declaration_span: span_of(source_text, "limit := 1 + 2", 0),
+        initializer_span: span_of(source_text, "1 + 2", 0),
+        initializer_requires_parentheses: true,
+        reference_spans: vec![
+            span_of(source_text, "limit", 1),
+            span_of(source_text, "limit", 2),
+        ],
+    };
+
+    let refactorings = inline_refactorings(source_text, &[inline_candidate]);
+    assert_eq!(refactorings.len(), 1);
+    assert_eq!(refactorings[0].kind, RefactoringKind::Inline);
+    assert_eq!(refactorings[0].label, "inline 'limit'");
+    assert_eq!(refactorings[0].trigger_spans.len(), 3);
+    let output =
+        apply_text_edits(source_text, &refactorings[0].text_edits).expect("edits should apply");
+    assert_eq!(
+        output,
+        "function run() -> int64 {\n    return (1 + 2) * (1 + 2)\n}\n"
+    );
+}
+
+#[test]
+fn inline_refactorings_remove_constant_with_doc_comment_and_blank_line() {
+    let source_text = "\
+/// The largest accepted value.
+LIMIT: int64 := 10
+
+function run() -> int64 {
+    return LIMIT
+}
+";
+    let inline_candidate = TypeAnnotatedInlineCandidate {
+        name: "LIMIT".to_string(),
+        name_span: span_of(source_text, "LIMIT", 0),
+        declaration_span: span_of(source_text, "LIMIT: int64 := 10", 0),
+        initializer_span: span_of(source_text, "10", 0),
+        initializer_requires_parentheses: false,
+        reference_spans: vec![span_of(source_text, "LIMIT", 1)],
+    };
+
+    let refactorings = inline_refactorings(source_text, &[inline_candidate]);
+    let output =
+        apply_text_edits(source_text, &refactorings[0].text_edits).expect("edits should apply");
+    assert_eq!(output, "function run() -> int64 {\n    return 10\n}\n");
+}
+
+#[test]
+fn inline_refactorings_skip_declarations_sharing_a_line() {
+    let source_text = "function run() -> int64 { limit := 1\n    return limit\n}\n";
+    let inline_candidate = TypeAnnotatedInlineCandidate {
+        name: "limit".to_string(),
+        name_span: span_of(source_text, "limit", 0),
+        declaration_span: span_of(source_text, "limit := 1", 0),
+        initializer_span: span_of(source_text, "1", 0),
+        initializer_requires_parentheses: false,
+        reference_spans: vec![span_of(source_text, "limit", 1)],
+    };
+
+    assert!(inline_refactorings(source_text, &[inline_candidate]).is_empty());
+}
+
+fn type_resolved_declarations(source_text: &str) -> TypeResolvedDeclarations {
+    let parsed_file = parse_file(source_text, FileRole::Library);
+    let semantic_file = lower_parsed_file(&parsed_file.value);
+    let Ok(declarations) = check_package_unit(
+        PackageId(0),
+        "app",
+        source_text,
+        &semantic_file.value,
+        &[],
+        &[],
+        &[],
+        LanguageVersion::default(),
+    )
+    .value
+    .resolved_declarations
+    else {
+        panic!("source should type check");
+    };
+    declarations
+}
+
+fn change_signature(source_text: &str, signature_change: &SignatureChange) -> String {
+    let declarations = type_resolved_declarations(source_text);
+    let files = [ChangeSignatureFileInput {
+        path: "app/lib.copp",
+        source_text,
+        declarations: &declarations,
+    }];
+    let text_edits_by_path =
+        change_signature_text_edits(&files, signature_change).expect("signature should change");
+    apply_text_edits(source_text, &text_edits_by_path["app/lib.copp"]).expect("edits should apply")
+}
+
+#[test]
+fn change_signature_rewrites_declaration_and_nested_call_sites() {
+    let source_text = "\
+function scale(value: int64, factor: int64, negate: boolean) -> int64 {
+    if negate {
+        return -value * factor
+    }
+    return value * factor
+}
+
+function run() -> int64 {
+    return scale(scale(1, 2, true), 3, false)
+}
+";
+    let signature_change = SignatureChange {
+        target: SignatureTarget::Function(CallableReference {
+            package_path: PackagePath::new("app"),
+            symbol_name: SymbolName::new("scale"),
+        }),
+        parameters: vec![
+            SignatureParameter::Existing { index: 1 },
+            SignatureParameter::Existing { index: 0 },
+            SignatureParameter::Added {
+                name: "offset".to_string(),
+                type_name: "int64".to_string(),
+                placeholder_argument: "0".to_string(),
+            },
+        ],
+    };
+    assert_eq!(
+        change_signature(source_text, &signature_change),
+        "\
+function scale(factor: int64, value: int64, offset: int64) -> int64 {
+    if negate {
+        return -value * factor
+    }
+    return value * factor
+}
+
+function run() -> int64 {
+    return scale(3, scale(2, 1, 0), 0)
+}
+"
+    );
+}
+
+#[test]
+fn change_signature_rewrites_method_and_keeps_receiver() {
+    let source_text = "\
+type Counter :: struct {
+    count: int64,
+
+    public function add(self, amount: int64) -> int64 {
+        return self.count + amount
+    },
+}
+
+function run() -> int64 {
+    counter := Counter { count: 1 }
+    return counter.add(2)
+}
+";
+    let signature_change = SignatureChange {
+        target: SignatureTarget::Method(TypeAnnotatedMethodReference {
+            receiver_type_reference: NominalTypeReference {
+                package_path: PackagePath::new("app"),
+                symbol_name: SymbolName::new("Counter"),
+            },
+            method_name: "add".to_string(),
+        }),
+        parameters: vec![
+            SignatureParameter::Existing { index: 0 },
+            SignatureParameter::Added {
+                name: "times".to_string(),
+                type_name: "int64".to_string(),
+                placeholder_argument: "1".to_string(),
+            },
+        ],
+    };
+    let output = change_signature(source_text, &signature_change);
+    assert!(output.contains("public function add(self, amount: int64, times: int64) -> int64 {"));
+    assert!(output.contains("return counter.add(2, 1)"));
+}
+
+#[test]
+fn change_signature_rejects_function_used_as_value() {
+    let source_text = "\
+function double(value: int64) -> int64 {
+    return value * 2
+}
+
+function run() -> int64 {
+    apply := double
+    return apply(1)
+}
+";
+    let declarations = type_resolved_declarations(source_text);
+    let files = [ChangeSignatureFileInput {
+        path: "app/lib.copp",
+        source_text,
+        declarations: &declarations,
+    }];
+    let signature_change = SignatureChange {
+        target: SignatureTarget::Function(CallableReference {
+            package_path: PackagePath::new("app"),
+            symbol_name: SymbolName::new("double"),
+        }),
+        parameters: Vec::new(),
+    };
+    assert!(matches!(
+        change_signature_text_edits(&files, &signature_change),
+        Err(ChangeSignatureError::FunctionUsedAsValue { .. })
+    ));
+}
+
+fn moved_sources(
+    files: &[MoveDeclarationFileInput<'_>],
+    symbol_path: &str,
+    symbol_name: &str,
+    destination_path: &str,
+) -> Vec<String> {
+    let symbol = MovedSymbol {
+        path: symbol_path.to_string(),
+        name: symbol_name.to_string(),
+    };
+    let text_edits_by_path =
+        move_declaration(files, &symbol, destination_path).expect("declaration should move");
+    files
+        .iter()
+        .map(|file| match text_edits_by_path.get(file.path) {
+            Some(text_edits) => {
+                apply_text_edits(file.source_text, text_edits).expect("edits should apply")
+            }
+            None => file.source_text.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn move_declaration_within_package_imports_remaining_references() {
+    let source_text = "\
+import std/math { max }
+
+visible LIMIT: int64 := 10
+
+/// Clamps to the limit.
+visible function clamp(value: int64) -> int64 {
+    return max(value, LIMIT)
+}
+
+function run() -> int64 {
+    return clamp(12)
+}
+";
+    let destination_text = "function other() -> int64 {\n    return 0\n}\n";
+    let files = [
+        MoveDeclarationFileInput {
+            path: "app/lib.copp",
+            package_path: "workspace/app",
+            source_text,
+        },
+        MoveDeclarationFileInput {
+            path: "app/clamp.copp",
+            package_path: "workspace/app",
+            source_text: destination_text,
+        },
+    ];
+    let output = moved_sources(&files, "app/lib.copp", "clamp", "app/clamp.copp");
+    assert_eq!(
+        output[0],
+        "\
+import workspace/app { clamp }
+
+visible LIMIT: int64 := 10
+
+function run() -> int64 {
+    return clamp(12)
+}
+"
+    );
+    assert_eq!(
+        output[1],
+        "\
+import std/math { max }
+import workspace/app { LIMIT }
+
+function other() -> int64 {
+    return 0
+}
+
+/// Clamps to the limit.
+visible function clamp(value: int64) -> int64 {
+    return max(value, LIMIT)
+}
+"
+    );
+}
+
+#[test]
+fn move_declaration_across_packages_rewrites_imports_and_exports() {
+    let files = [
+        MoveDeclarationFileInput {
+            path: "app/PACKAGE.copp",
+            package_path: "workspace/app",
+            source_text: "exports { Point, origin }\n",
+        },
+        MoveDeclarationFileInput {
+            path: "app/lib.copp",
+            package_path: "workspace/app",
+            source_text: "\
+visible type Point :: struct { x: int64 }
+
+visible function origin() -> Point {
+    return Point { x: 0 }
+}
+",
+        },
+        MoveDeclarationFileInput {
+            path: "geometry/PACKAGE.copp",
+            package_path: "workspace/geometry",
+            source_text: "",
+        },
+        MoveDeclarationFileInput {
+            path: "geometry/lib.copp",
+            package_path: "workspace/geometry",
+            source_text: "",
+        },
+        MoveDeclarationFileInput {
+            path: "main.bin.copp",
+            package_path: "workspace",
+            source_text: "\
+import workspace/app { Point as AppPoint, origin }
+
+function main() -> nil {
+    point: AppPoint := origin()
+}
+",
+        },
+    ];
+    let output = moved_sources(&files, "app/lib.copp", "Point", "geometry/lib.copp");
+    assert_eq!(output[0], "exports { origin }\n");
+    assert_eq!(
+        output[1],
+        "\
+import workspace/geometry { Point }
+
+visible function origin() -> Point {
+    return Point { x: 0 }
+}
+"
+    );
+    assert_eq!(output[2], "exports { Point }\n");
+    assert_eq!(output[3], "visible type Point :: struct { x: int64 }\n");
+    assert!(output[4].starts_with(
+        "import workspace/app { origin }\nimport workspace/geometry { Point as AppPoint }\n"
+    ));
+}
+
+#[test]
+fn move_declaration_rejects_references_to_file_private_declarations() {
+    let files = [
+        MoveDeclarationFileInput {
+            path: "app/lib.copp",
+            package_path: "workspace/app",
+            source_text: "LIMIT: int64 := 10\n\nvisible function limit() -> int64 {\n    return LIMIT\n}\n",
+        },
+        MoveDeclarationFileInput {
+            path: "app/other.copp",
+            package_path: "workspace/app",
+            source_text: "",
+        },
+    ];
+    let symbol = MovedSymbol {
+        path: "app/lib.copp".to_string(),
+        name: "limit".to_string(),
+    };
+    assert_eq!(
+        move_declaration(&files, &symbol, "app/other.copp"),
+        Err(MoveDeclarationError::ReferencesFilePrivateDeclaration {
+            name: "LIMIT".to_string()
+        })
+    );
+}
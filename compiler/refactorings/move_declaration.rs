@@ -0,0 +1,952 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+use compiler__fix_edits::{TextEdit, apply_text_edits};
+use compiler__parsing::parse_file;
+use compiler__source::{
+    FileRole, Span, leading_comment_start, line_end, line_removal_range, trailing_comment_end,
+};
+use compiler__syntax::{
+    SyntaxAssignTarget, SyntaxBlock, SyntaxBlockItem, SyntaxDeclaration, SyntaxExpression,
+    SyntaxImportMember, SyntaxMatchPattern, SyntaxParsedFile, SyntaxStatement,
+    SyntaxStringInterpolationPart, SyntaxStructMemberItem, SyntaxTopLevelVisibility,
+    SyntaxTypeDeclarationKind, SyntaxTypeName,
+};
+
+// `package_path` uses import form (`workspace` or `workspace/<path>`), so it
+// can be compared with the package paths written in import declarations.
+pub struct MoveDeclarationFileInput<'a> {
+    pub path: &'a str,
+    pub package_path: &'a str,
+    pub source_text: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovedSymbol {
+    pub path: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveDeclarationError {
+    FileNotFound { path: String },
+    SourceDoesNotParse { path: String },
+    DeclarationNotFound,
+    DeclarationSharesLine,
+    InvalidDestination,
+    NameConflict { path: String, name: String },
+    ReferencesFilePrivateDeclaration { name: String },
+    ReferencesSourcePackageDeclaration { name: String },
+    FilePrivateDeclarationStillReferenced,
+    AliasedImportWouldBecomeLocal { path: String },
+    SymbolNotExported { path: String },
+    PackageManifestNotFound { package_path: String },
+    MovedSourceDoesNotParse { path: String },
+}
+
+// Every file that may import the symbol must be in `files`, along with the
+// package manifests of both packages when moving across packages. A symbol
+// exported by its old package is exported by its new package instead; a symbol
+// that was not exported may only move to a package where all of its importers
+// live.
+pub fn move_declaration(
+    files: &[MoveDeclarationFileInput<'_>],
+    symbol: &MovedSymbol,
+    destination_path: &str,
+) -> Result<BTreeMap<String, Vec<TextEdit>>, MoveDeclarationError> {
+    let parsed_files = files
+        .iter()
+        .map(ParsedFileInput::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    let source = find_file(&parsed_files, &symbol.path)?;
+    let destination = find_file(&parsed_files, destination_path)?;
+    if source.input.path == destination.input.path || destination.role != FileRole::Library {
+        return Err(MoveDeclarationError::InvalidDestination);
+    }
+    let (declaration, visibility) = source
+        .parsed
+        .top_level_declarations()
+        .find_map(|declaration| movable_declaration(declaration, &symbol.name))
+        .ok_or(MoveDeclarationError::DeclarationNotFound)?;
+    let destination_binding = declared_names(&destination.parsed).remove(&symbol.name);
+    if destination_binding
+        .is_some_and(|package_path| package_path != Some(source.input.package_path))
+    {
+        return Err(MoveDeclarationError::NameConflict {
+            path: destination.input.path.to_string(),
+            name: symbol.name.clone(),
+        });
+    }
+
+    let mut plan = MovePlan::default();
+    let moved_start = leading_comment_start(
+        source.input.source_text,
+        declaration_span(declaration).start,
+    )
+    .ok_or(MoveDeclarationError::DeclarationSharesLine)?;
+    let moved_end =
+        trailing_comment_end(source.input.source_text, declaration_span(declaration).end);
+    let (removal_start, removal_end) =
+        line_removal_range(source.input.source_text, moved_start, moved_end);
+    plan.push(
+        source.input.path,
+        TextEdit {
+            start_byte_offset: removal_start,
+            end_byte_offset: removal_end,
+            replacement_text: String::new(),
+        },
+    );
+    plan.push(
+        destination.input.path,
+        appended_declaration_edit(
+            destination.input.source_text,
+            &source.input.source_text[moved_start..moved_end],
+        ),
+    );
+
+    let moved_references = referenced_names_in_declaration(declaration);
+    let remaining_references = source
+        .parsed
+        .top_level_declarations()
+        .filter(|other| !std::ptr::eq(*other, declaration))
+        .flat_map(referenced_names_in_declaration)
+        .collect::<BTreeSet<_>>();
+    plan_moved_references(
+        &mut plan,
+        source,
+        destination,
+        &symbol.name,
+        &moved_references,
+        &remaining_references,
+    )?;
+
+    let same_package = source.input.package_path == destination.input.package_path;
+    let mut importer_paths = Vec::new();
+    if remaining_references.contains(&symbol.name) {
+        if visibility == SyntaxTopLevelVisibility::Private {
+            return Err(MoveDeclarationError::FilePrivateDeclarationStillReferenced);
+        }
+        importer_paths.push(source.input.path);
+        plan.member_list_change(
+            source.input.path,
+            MemberList::Import(destination.input.package_path),
+        )
+        .added_member_texts
+        .push(symbol.name.clone());
+    }
+    for file in &parsed_files {
+        let Some(member) = imported_member(&file.parsed, source.input.package_path, &symbol.name)
+        else {
+            continue;
+        };
+        let becomes_local = file.input.path == destination.input.path;
+        if becomes_local && member.alias.is_some() {
+            return Err(MoveDeclarationError::AliasedImportWouldBecomeLocal {
+                path: file.input.path.to_string(),
+            });
+        }
+        if !becomes_local && same_package {
+            continue;
+        }
+        plan.member_list_change(
+            file.input.path,
+            MemberList::Import(source.input.package_path),
+        )
+        .removed_member_names
+        .insert(symbol.name.clone());
+        if !becomes_local {
+            importer_paths.push(file.input.path);
+            plan.member_list_change(
+                file.input.path,
+                MemberList::Import(destination.input.package_path),
+            )
+            .added_member_texts
+            .push(import_member_text(&symbol.name, member.alias.as_deref()));
+        }
+    }
+    if !same_package {
+        plan_moved_export(
+            &mut plan,
+            &parsed_files,
+            source,
+            destination,
+            &symbol.name,
+            &importer_paths,
+        )?;
+    }
+
+    let parsed_file_by_path = parsed_files
+        .iter()
+        .map(|file| (file.input.path, file))
+        .collect::<BTreeMap<_, _>>();
+    // Import edits come first so that imports inserted into an empty
+    // destination precede the appended declaration.
+    let mut text_edits_by_path = BTreeMap::<String, Vec<TextEdit>>::new();
+    for (path, change_by_member_list) in &plan.member_list_changes_by_path {
+        let file = parsed_file_by_path[path.as_str()];
+        let is_followed_by_declarations =
+            *path == destination.input.path || !file.input.source_text.trim().is_empty();
+        text_edits_by_path.insert(
+            path.clone(),
+            member_list_text_edits(file, change_by_member_list, is_followed_by_declarations),
+        );
+    }
+    for (path, text_edits) in plan.text_edits_by_path {
+        text_edits_by_path
+            .entry(path)
+            .or_default()
+            .extend(text_edits);
+    }
+    for (path, text_edits) in &mut text_edits_by_path {
+        text_edits.sort_by_key(|text_edit| text_edit.start_byte_offset);
+        check_moved_source(parsed_file_by_path[path.as_str()], text_edits)?;
+    }
+    Ok(text_edits_by_path)
+}
+
+struct ParsedFileInput<'a> {
+    input: &'a MoveDeclarationFileInput<'a>,
+    role: FileRole,
+    parsed: SyntaxParsedFile,
+}
+
+impl<'a> ParsedFileInput<'a> {
+    fn parse(input: &'a MoveDeclarationFileInput<'a>) -> Result<Self, MoveDeclarationError> {
+        let does_not_parse = || MoveDeclarationError::SourceDoesNotParse {
+            path: input.path.to_string(),
+        };
+        let role = FileRole::from_path(Path::new(input.path)).ok_or_else(does_not_parse)?;
+        let parsed_file = parse_file(input.source_text, role);
+        if !parsed_file.diagnostics.is_empty() {
+            return Err(does_not_parse());
+        }
+        Ok(Self {
+            input,
+            role,
+            parsed: parsed_file.value,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MemberList<'a> {
+    Import(&'a str),
+    Exports,
+}
+
+#[derive(Default)]
+struct MemberListChange {
+    removed_member_names: BTreeSet<String>,
+    added_member_texts: Vec<String>,
+}
+
+#[derive(Default)]
+struct MovePlan<'a> {
+    text_edits_by_path: BTreeMap<String, Vec<TextEdit>>,
+    member_list_changes_by_path: BTreeMap<String, BTreeMap<MemberList<'a>, MemberListChange>>,
+}
+
+impl<'a> MovePlan<'a> {
+    fn push(&mut self, path: &str, text_edit: TextEdit) {
+        self.text_edits_by_path
+            .entry(path.to_string())
+            .or_default()
+            .push(text_edit);
+    }
+
+    fn member_list_change(
+        &mut self,
+        path: &str,
+        member_list: MemberList<'a>,
+    ) -> &mut MemberListChange {
+        self.member_list_changes_by_path
+            .entry(path.to_string())
+            .or_default()
+            .entry(member_list)
+            .or_default()
+    }
+}
+
+fn find_file<'a, 'b>(
+    parsed_files: &'b [ParsedFileInput<'a>],
+    path: &str,
+) -> Result<&'b ParsedFileInput<'a>, MoveDeclarationError> {
+    parsed_files
+        .iter()
+        .find(|file| file.input.path == path)
+        .ok_or_else(|| MoveDeclarationError::FileNotFound {
+            path: path.to_string(),
+        })
+}
+
+fn movable_declaration<'a>(
+    declaration: &'a SyntaxDeclaration,
+    name: &str,
+) -> Option<(&'a SyntaxDeclaration, SyntaxTopLevelVisibility)> {
+    let (declaration_name, visibility) = match declaration {
+        SyntaxDeclaration::Type(type_declaration) => {
+            (&type_declaration.name, type_declaration.visibility)
+        }
+        SyntaxDeclaration::Constant(constant_declaration) => {
+            (&constant_declaration.name, constant_declaration.visibility)
+        }
+        SyntaxDeclaration::Function(function_declaration) => {
+            (&function_declaration.name, function_declaration.visibility)
+        }
+        SyntaxDeclaration::Import(_)
+        | SyntaxDeclaration::Exports(_)
+        | SyntaxDeclaration::Package(_)
+        | SyntaxDeclaration::Group(_)
+        | SyntaxDeclaration::Test(_)
+        | SyntaxDeclaration::StaticAssert(_) => return None,
+    };
+    (declaration_name == name).then_some((declaration, visibility))
+}
+
+fn declaration_span(declaration: &SyntaxDeclaration) -> &Span {
+    match declaration {
+        SyntaxDeclaration::Import(import_declaration) => &import_declaration.span,
+        SyntaxDeclaration::Exports(exports_declaration) => &exports_declaration.span,
+        SyntaxDeclaration::Package(package_declaration) => &package_declaration.span,
+        SyntaxDeclaration::Type(type_declaration) => &type_declaration.span,
+        SyntaxDeclaration::Constant(constant_declaration) => &constant_declaration.span,
+        SyntaxDeclaration::Function(function_declaration) => &function_declaration.span,
+        SyntaxDeclaration::Group(group_declaration) => &group_declaration.span,
+        SyntaxDeclaration::Test(test_declaration) => &test_declaration.span,
+        SyntaxDeclaration::StaticAssert(static_assert_declaration) => {
+            &static_assert_declaration.span
+        }
+    }
+}
+
+// Top-level names a file declares or imports, by local name, with the import
+// package path for imported names.
+fn declared_names(file: &SyntaxParsedFile) -> BTreeMap<String, Option<&str>> {
+    let mut package_path_by_local_name = BTreeMap::new();
+    for declaration in file.top_level_declarations() {
+        match declaration {
+            SyntaxDeclaration::Import(import_declaration) => {
+                for member in &import_declaration.members {
+                    package_path_by_local_name.insert(
+                        member.alias.clone().unwrap_or_else(|| member.name.clone()),
+                        Some(import_declaration.package_path.as_str()),
+                    );
+                }
+            }
+            SyntaxDeclaration::Type(type_declaration) => {
+                package_path_by_local_name.insert(type_declaration.name.clone(), None);
+            }
+            SyntaxDeclaration::Constant(constant_declaration) => {
+                package_path_by_local_name.insert(constant_declaration.name.clone(), None);
+            }
+            SyntaxDeclaration::Function(function_declaration) => {
+                package_path_by_local_name.insert(function_declaration.name.clone(), None);
+            }
+            SyntaxDeclaration::Exports(_)
+            | SyntaxDeclaration::Package(_)
+            | SyntaxDeclaration::Group(_)
+            | SyntaxDeclaration::Test(_)
+            | SyntaxDeclaration::StaticAssert(_) => {}
+        }
+    }
+    package_path_by_local_name
+}
+
+fn imported_member<'a>(
+    file: &'a SyntaxParsedFile,
+    package_path: &str,
+    name: &str,
+) -> Option<&'a SyntaxImportMember> {
+    file.top_level_declarations()
+        .filter_map(|declaration| match declaration {
+            SyntaxDeclaration::Import(import_declaration)
+                if import_declaration.package_path == package_path =>
+            {
+                Some(import_declaration)
+            }
+            _ => None,
+        })
+        .flat_map(|import_declaration| &import_declaration.members)
+        .find(|member| member.name == name)
+}
+
+fn import_member_text(name: &str, alias: Option<&str>) -> String {
+    match alias {
+        Some(alias) => format!("{name} as {alias}"),
+        None => name.to_string(),
+    }
+}
+
+// Names the moved declaration uses must resolve the same way in the
+// destination. Same-package declarations from the source file are imported;
+// imports the source file no longer needs are dropped.
+fn plan_moved_references<'a>(
+    plan: &mut MovePlan<'a>,
+    source: &'a ParsedFileInput<'a>,
+    destination: &'a ParsedFileInput<'a>,
+    symbol_name: &str,
+    moved_references: &BTreeSet<String>,
+    remaining_references: &BTreeSet<String>,
+) -> Result<(), MoveDeclarationError> {
+    let destination_names = declared_names(&destination.parsed);
+    let mut source_declarations = BTreeMap::new();
+    for declaration in source.parsed.top_level_declarations() {
+        for name in moved_references {
+            if let Some((_, visibility)) = movable_declaration(declaration, name) {
+                source_declarations.insert(name.as_str(), visibility);
+            }
+        }
+    }
+    for name in moved_references {
+        if name == symbol_name {
+            continue;
+        }
+        let (package_path, member_text) = if let Some(visibility) =
+            source_declarations.get(name.as_str())
+        {
+            if *visibility == SyntaxTopLevelVisibility::Private {
+                return Err(MoveDeclarationError::ReferencesFilePrivateDeclaration {
+                    name: name.clone(),
+                });
+            }
+            if source.input.package_path != destination.input.package_path {
+                return Err(MoveDeclarationError::ReferencesSourcePackageDeclaration {
+                    name: name.clone(),
+                });
+            }
+            (source.input.package_path, name.clone())
+        } else if let Some((package_path, member)) = source_import_member(&source.parsed, name) {
+            if !remaining_references.contains(name) {
+                plan.member_list_change(source.input.path, MemberList::Import(package_path))
+                    .removed_member_names
+                    .insert(member.name.clone());
+            }
+            (
+                package_path,
+                import_member_text(&member.name, member.alias.as_deref()),
+            )
+        } else {
+            continue;
+        };
+        match destination_names.get(name) {
+            Some(Some(existing_package_path)) if *existing_package_path == package_path => {}
+            Some(None) if package_path == destination.input.package_path => {}
+            Some(_) => {
+                return Err(MoveDeclarationError::NameConflict {
+                    path: destination.input.path.to_string(),
+                    name: name.clone(),
+                });
+            }
+            None => plan
+                .member_list_change(destination.input.path, MemberList::Import(package_path))
+                .added_member_texts
+                .push(member_text),
+        }
+    }
+    Ok(())
+}
+
+fn source_import_member<'a>(
+    file: &'a SyntaxParsedFile,
+    local_name: &str,
+) -> Option<(&'a str, &'a SyntaxImportMember)> {
+    file.top_level_declarations()
+        .filter_map(|declaration| match declaration {
+            SyntaxDeclaration::Import(import_declaration) => Some(import_declaration),
+            _ => None,
+        })
+        .flat_map(|import_declaration| {
+            import_declaration
+                .members
+                .iter()
+                .map(|member| (import_declaration.package_path.as_str(), member))
+        })
+        .find(|(_, member)| member.alias.as_deref().unwrap_or(&member.name) == local_name)
+}
+
+fn plan_moved_export<'a>(
+    plan: &mut MovePlan<'a>,
+    parsed_files: &'a [ParsedFileInput<'a>],
+    source: &'a ParsedFileInput<'a>,
+    destination: &'a ParsedFileInput<'a>,
+    symbol_name: &str,
+    importer_paths: &[&str],
+) -> Result<(), MoveDeclarationError> {
+    let package_manifest = |package_path: &str| {
+        parsed_files
+            .iter()
+            .find(|file| {
+                file.role == FileRole::PackageManifest && file.input.package_path == package_path
+            })
+            .ok_or_else(|| MoveDeclarationError::PackageManifestNotFound {
+                package_path: package_path.to_string(),
+            })
+    };
+    let source_manifest = package_manifest(source.input.package_path)?;
+    let is_exported = source_manifest
+        .parsed
+        .top_level_declarations()
+        .any(|declaration| {
+            matches!(declaration, SyntaxDeclaration::Exports(exports_declaration)
+                if exports_declaration.members.iter().any(|member| member.name == symbol_name))
+        });
+    if !is_exported {
+        for importer_path in importer_paths {
+            let importer = find_file(parsed_files, importer_path)?;
+            if importer.input.package_path != destination.input.package_path {
+                return Err(MoveDeclarationError::SymbolNotExported {
+                    path: (*importer_path).to_string(),
+                });
+            }
+        }
+        return Ok(());
+    }
+    let destination_manifest = package_manifest(destination.input.package_path)?;
+    plan.member_list_change(source_manifest.input.path, MemberList::Exports)
+        .removed_member_names
+        .insert(symbol_name.to_string());
+    plan.member_list_change(destination_manifest.input.path, MemberList::Exports)
+        .added_member_texts
+        .push(symbol_name.to_string());
+    Ok(())
+}
+
+// Each changed import or exports declaration is rewritten as a whole, so
+// removing and adding members of one declaration never produces overlapping
+// edits. New import declarations go after the last existing import.
+fn member_list_text_edits(
+    file: &ParsedFileInput<'_>,
+    change_by_member_list: &BTreeMap<MemberList<'_>, MemberListChange>,
+    is_followed_by_declarations: bool,
+) -> Vec<TextEdit> {
+    let source_text = file.input.source_text;
+    let mut text_edits = Vec::new();
+    let mut removed_declaration_starts = BTreeSet::new();
+    let mut new_declarations_text = String::new();
+    for (member_list, change) in change_by_member_list {
+        let existing_members = file
+            .parsed
+            .top_level_declarations()
+            .find_map(|declaration| match (member_list, declaration) {
+                (
+                    MemberList::Import(package_path),
+                    SyntaxDeclaration::Import(import_declaration),
+                ) if import_declaration.package_path == *package_path => Some((
+                    &import_declaration.span,
+                    import_declaration
+                        .members
+                        .iter()
+                        .map(|member| (member.name.as_str(), &member.span))
+                        .collect::<Vec<_>>(),
+                )),
+                (MemberList::Exports, SyntaxDeclaration::Exports(exports_declaration)) => Some((
+                    &exports_declaration.span,
+                    exports_declaration
+                        .members
+                        .iter()
+                        .map(|member| (member.name.as_str(), &member.span))
+                        .collect::<Vec<_>>(),
+                )),
+                _ => None,
+            });
+        let Some((declaration_span, members)) = existing_members else {
+            if !change.added_member_texts.is_empty() {
+                let keyword = match member_list {
+                    MemberList::Import(package_path) => format!("import {package_path}"),
+                    MemberList::Exports => "exports".to_string(),
+                };
+                let _ = writeln!(
+                    new_declarations_text,
+                    "{keyword} {{ {} }}",
+                    change.added_member_texts.join(", ")
+                );
+            }
+            continue;
+        };
+        let mut member_texts = members
+            .iter()
+            .filter(|(name, _)| !change.removed_member_names.contains(*name))
+            .map(|(_, span)| source_text[span.start..span.end].to_string())
+            .collect::<Vec<_>>();
+        member_texts.extend(change.added_member_texts.iter().cloned());
+        let (Some((_, first_member_span)), Some((_, last_member_span))) =
+            (members.first(), members.last())
+        else {
+            continue;
+        };
+        if member_texts.is_empty() {
+            removed_declaration_starts.insert(declaration_span.start);
+            let (removal_start, removal_end) =
+                line_removal_range(source_text, declaration_span.start, declaration_span.end);
+            text_edits.push(TextEdit {
+                start_byte_offset: removal_start,
+                end_byte_offset: removal_end,
+                replacement_text: String::new(),
+            });
+        } else {
+            text_edits.push(TextEdit {
+                start_byte_offset: first_member_span.start,
+                end_byte_offset: last_member_span.end,
+                replacement_text: member_texts.join(", "),
+            });
+        }
+    }
+    if !new_declarations_text.is_empty() {
+        text_edits.push(new_declarations_edit(
+            file,
+            &new_declarations_text,
+            &removed_declaration_starts,
+            is_followed_by_declarations,
+        ));
+    }
+    text_edits
+}
+
+fn new_declarations_edit(
+    file: &ParsedFileInput<'_>,
+    new_declarations_text: &str,
+    removed_declaration_starts: &BTreeSet<usize>,
+    is_followed_by_declarations: bool,
+) -> TextEdit {
+    let source_text = file.input.source_text;
+    let last_header_end = file
+        .parsed
+        .top_level_declarations()
+        .filter(|declaration| {
+            matches!(
+                declaration,
+                SyntaxDeclaration::Import(_)
+                    | SyntaxDeclaration::Exports(_)
+                    | SyntaxDeclaration::Package(_)
+            )
+        })
+        .map(declaration_span)
+        .filter(|span| !removed_declaration_starts.contains(&span.start))
+        .map(|span| span.end)
+        .max();
+    let Some(last_header_end) = last_header_end else {
+        let suffix = if is_followed_by_declarations {
+            "\n"
+        } else {
+            ""
+        };
+        return TextEdit {
+            start_byte_offset: 0,
+            end_byte_offset: 0,
+            replacement_text: format!("{new_declarations_text}{suffix}"),
+        };
+    };
+    let offset = line_end(source_text, last_header_end);
+    let prefix = if source_text[..offset].ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    TextEdit {
+        start_byte_offset: offset,
+        end_byte_offset: offset,
+        replacement_text: format!("{prefix}{new_declarations_text}"),
+    }
+}
+
+fn appended_declaration_edit(destination_text: &str, moved_text: &str) -> TextEdit {
+    let separator = if destination_text.trim().is_empty() || destination_text.ends_with("\n\n") {
+        ""
+    } else if destination_text.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    TextEdit {
+        start_byte_offset: destination_text.len(),
+        end_byte_offset: destination_text.len(),
+        replacement_text: format!("{separator}{moved_text}\n"),
+    }
+}
+
+fn check_moved_source(
+    file: &ParsedFileInput<'_>,
+    text_edits: &[TextEdit],
+) -> Result<(), MoveDeclarationError> {
+    let does_not_parse = || MoveDeclarationError::MovedSourceDoesNotParse {
+        path: file.input.path.to_string(),
+    };
+    let moved_source_text =
+        apply_text_edits(file.input.source_text, text_edits).map_err(|_| does_not_parse())?;
+    if !parse_file(&moved_source_text, file.role)
+        .diagnostics
+        .is_empty()
+    {
+        return Err(does_not_parse());
+    }
+    Ok(())
+}
+
+// Names are collected syntactically, so a local binding that shares a name
+// with a top-level declaration is treated as a reference to it.
+fn referenced_names_in_declaration(declaration: &SyntaxDeclaration) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    match declaration {
+        SyntaxDeclaration::Import(_)
+        | SyntaxDeclaration::Exports(_)
+        | SyntaxDeclaration::Package(_) => {}
+        SyntaxDeclaration::Type(type_declaration) => {
+            for type_parameter in &type_declaration.type_parameters {
+                if let Some(constraint) = &type_parameter.constraint {
+                    collect_type_name(constraint, &mut names);
+                }
+            }
+            for interface in &type_declaration.implemented_interfaces {
+                collect_type_name(interface, &mut names);
+            }
+            match &type_declaration.kind {
+                SyntaxTypeDeclarationKind::Struct { items } => {
+                    for item in items {
+                        match item {
+                            SyntaxStructMemberItem::DocComment(_) => {}
+                            SyntaxStructMemberItem::Field(field) => {
+                                collect_type_name(&field.type_name, &mut names);
+                            }
+                            SyntaxStructMemberItem::Method(method) => {
+                                for parameter in &method.parameters {
+                                    collect_type_name(&parameter.type_name, &mut names);
+                                }
+                                collect_type_name(&method.return_type, &mut names);
+                                collect_block(&method.body, &mut names);
+                            }
+                        }
+                    }
+                }
+                SyntaxTypeDeclarationKind::Enum { .. } => {}
+                SyntaxTypeDeclarationKind::Interface { methods } => {
+                    for method in methods {
+                        for parameter in &method.parameters {
+                            collect_type_name(&parameter.type_name, &mut names);
+                        }
+                        collect_type_name(&method.return_type, &mut names);
+                    }
+                }
+                SyntaxTypeDeclarationKind::Union { variants } => {
+                    for variant in variants {
+                        collect_type_name(variant, &mut names);
+                    }
+                }
+            }
+        }
+        SyntaxDeclaration::Constant(constant_declaration) => {
+            collect_type_name(&constant_declaration.type_name, &mut names);
+            collect_expression(&constant_declaration.expression, &mut names);
+        }
+        SyntaxDeclaration::Function(function_declaration) => {
+            for type_parameter in &function_declaration.type_parameters {
+                if let Some(constraint) = &type_parameter.constraint {
+                    collect_type_name(constraint, &mut names);
+                }
+            }
+            for parameter in &function_declaration.parameters {
+                collect_type_name(&parameter.type_name, &mut names);
+            }
+            collect_type_name(&function_declaration.return_type, &mut names);
+            collect_block(&function_declaration.body, &mut names);
+        }
+        SyntaxDeclaration::Group(group_declaration) => {
+            for test_declaration in &group_declaration.tests {
+                collect_block(&test_declaration.body, &mut names);
+            }
+        }
+        SyntaxDeclaration::Test(test_declaration) => {
+            collect_block(&test_declaration.body, &mut names);
+        }
+        SyntaxDeclaration::StaticAssert(static_assert_declaration) => {
+            collect_expression(&static_assert_declaration.condition, &mut names);
+        }
+    }
+    names
+}
+
+fn collect_block(block: &SyntaxBlock, names: &mut BTreeSet<String>) {
+    for item in &block.items {
+        let SyntaxBlockItem::Statement(statement) = item else {
+            continue;
+        };
+        match statement {
+            SyntaxStatement::Binding {
+                type_name,
+                initializer,
+                ..
+            } => {
+                if let Some(type_name) = type_name {
+                    collect_type_name(type_name, names);
+                }
+                collect_expression(initializer, names);
+            }
+            SyntaxStatement::Assign { target, value, .. } => {
+                match target {
+                    SyntaxAssignTarget::Name { name, .. } => {
+                        names.insert(name.clone());
+                    }
+                    SyntaxAssignTarget::Index { target, index, .. } => {
+                        collect_expression(target, names);
+                        collect_expression(index, names);
+                    }
+                }
+                collect_expression(value, names);
+            }
+            SyntaxStatement::Return { value, .. } => {
+                if let Some(value) = value {
+                    collect_expression(value, names);
+                }
+            }
+            SyntaxStatement::Break { .. } | SyntaxStatement::Continue { .. } => {}
+            SyntaxStatement::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                collect_expression(condition, names);
+                collect_block(then_block, names);
+                if let Some(else_block) = else_block {
+                    collect_block(else_block, names);
+                }
+            }
+            SyntaxStatement::Guard {
+                condition,
+                else_block,
+                ..
+            } => {
+                collect_expression(condition, names);
+                collect_block(else_block, names);
+            }
+            SyntaxStatement::For {
+                condition, body, ..
+            } => {
+                if let Some(condition) = condition {
+                    collect_expression(condition, names);
+                }
+                collect_block(body, names);
+            }
+            SyntaxStatement::ForRange {
+                start,
+                end,
+                step,
+                body,
+                ..
+            } => {
+                collect_expression(start, names);
+                collect_expression(end, names);
+                if let Some(step) = step {
+                    collect_expression(step, names);
+                }
+                collect_block(body, names);
+            }
+            SyntaxStatement::Defer { value, .. } | SyntaxStatement::Expression { value, .. } => {
+                collect_expression(value, names);
+            }
+        }
+    }
+}
+
+fn collect_expression(expression: &SyntaxExpression, names: &mut BTreeSet<String>) {
+    match expression {
+        SyntaxExpression::IntegerLiteral { .. }
+        | SyntaxExpression::NilLiteral { .. }
+        | SyntaxExpression::BooleanLiteral { .. }
+        | SyntaxExpression::StringLiteral { .. } => {}
+        SyntaxExpression::ListLiteral { elements, .. } => {
+            for element in elements {
+                collect_expression(element, names);
+            }
+        }
+        SyntaxExpression::NameReference { name, .. } => {
+            names.insert(name.clone());
+        }
+        SyntaxExpression::StructLiteral {
+            type_name, fields, ..
+        } => {
+            collect_type_name(type_name, names);
+            for field in fields {
+                collect_expression(&field.value, names);
+            }
+        }
+        SyntaxExpression::FieldAccess { target, .. }
+        | SyntaxExpression::Unary {
+            expression: target, ..
+        } => collect_expression(target, names),
+        SyntaxExpression::IndexAccess { target, index, .. } => {
+            collect_expression(target, names);
+            collect_expression(index, names);
+        }
+        SyntaxExpression::Call {
+            callee,
+            type_arguments,
+            arguments,
+            ..
+        } => {
+            collect_expression(callee, names);
+            for type_argument in type_arguments {
+                collect_type_name(type_argument, names);
+            }
+            for argument in arguments {
+                collect_expression(argument, names);
+            }
+        }
+        SyntaxExpression::Binary { left, right, .. } => {
+            collect_expression(left, names);
+            collect_expression(right, names);
+        }
+        SyntaxExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_expression(condition, names);
+            collect_expression(then_branch, names);
+            collect_expression(else_branch, names);
+        }
+        SyntaxExpression::Block { block, value, .. } => {
+            collect_block(block, names);
+            collect_expression(value, names);
+        }
+        SyntaxExpression::Match { target, arms, .. } => {
+            collect_expression(target, names);
+            for arm in arms {
+                match &arm.pattern {
+                    SyntaxMatchPattern::Type { type_name, .. }
+                    | SyntaxMatchPattern::Binding { type_name, .. } => {
+                        collect_type_name(type_name, names);
+                    }
+                    SyntaxMatchPattern::Literal { .. } => {}
+                }
+                if let Some(guard) = &arm.guard {
+                    collect_expression(guard, names);
+                }
+                collect_expression(&arm.value, names);
+            }
+        }
+        SyntaxExpression::Matches {
+            value, type_name, ..
+        } => {
+            collect_expression(value, names);
+            collect_type_name(type_name, names);
+        }
+        SyntaxExpression::StringInterpolation { parts, .. } => {
+            for part in parts {
+                if let SyntaxStringInterpolationPart::Expression(expression) = part {
+                    collect_expression(expression, names);
+                }
+            }
+        }
+    }
+}
+
+fn collect_type_name(type_name: &SyntaxTypeName, names: &mut BTreeSet<String>) {
+    for segment in &type_name.names {
+        names.insert(segment.name.clone());
+        for type_argument in &segment.type_arguments {
+            collect_type_name(type_argument, names);
+        }
+    }
+}
@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
-use compiler__source::Span;
+use compiler__source::{LineIndex, Span};
+
+mod text_rendering;
+
+pub use text_rendering::{DiagnosticTextOptions, render_diagnostics_text};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ReportFormat {
@@ -38,6 +42,40 @@ impl FromStr for ReportFormat {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+impl GraphFormat {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("invalid graph format '{value}'")),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DiagnosticPhase {
@@ -46,15 +84,104 @@ pub enum DiagnosticPhase {
     FileRoleRules,
     Resolution,
     SemanticLowering,
+    MaintainabilityRules,
     TypeAnalysis,
 }
 
+impl DiagnosticPhase {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Parsing => "parsing",
+            Self::SyntaxRules => "syntax_rules",
+            Self::FileRoleRules => "file_role_rules",
+            Self::Resolution => "resolution",
+            Self::SemanticLowering => "semantic_lowering",
+            Self::MaintainabilityRules => "maintainability_rules",
+            Self::TypeAnalysis => "type_analysis",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RenderedDiagnostic {
     pub phase: DiagnosticPhase,
+    #[serde(default, skip_serializing_if = "RenderedDiagnosticSeverity::is_error")]
+    pub severity: RenderedDiagnosticSeverity,
     pub path: String,
     pub message: String,
-    pub span: Span,
+    pub span: RenderedSpan,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_spans: Vec<RenderedRelatedSpan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_fixes: Vec<RenderedSuggestedFix>,
+}
+
+impl RenderedDiagnostic {
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity.is_error()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderedDiagnosticSeverity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl RenderedDiagnosticSeverity {
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        *self == Self::Error
+    }
+}
+
+// `path` is only set when the related span is in another file than the
+// diagnostic.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RenderedRelatedSpan {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub span: RenderedSpan,
+}
+
+// A span together with the line and column of its anchor, which reports show
+// and which are only derived once a span is rendered.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct RenderedSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl RenderedSpan {
+    #[must_use]
+    pub fn new(span: Span, line_index: &LineIndex) -> Self {
+        let (line, column) = line_index.line_column(span.anchor);
+        Self {
+            start: span.start,
+            end: span.end,
+            line,
+            column,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RenderedSuggestedFix {
+    pub label: String,
+    pub text_edits: Vec<RenderedTextEdit>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RenderedTextEdit {
+    pub span: RenderedSpan,
+    pub replacement_text: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -82,6 +209,9 @@ pub enum CompilerFailureKind {
     WorkspaceDiscoveryFailed,
     BuildFailed,
     RunFailed,
+    Cancelled,
+    DiagnosticBaselineNotConfigured,
+    DeclarationNotFound,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use compiler__source::{display_width, floor_char_boundary};
+
+use crate::{RenderedDiagnostic, RenderedDiagnosticSeverity, RenderedSpan};
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_BOLD_RED: &str = "\x1b[1;31m";
+const ANSI_BOLD_GREEN: &str = "\x1b[1;32m";
+const ANSI_BOLD_YELLOW: &str = "\x1b[1;33m";
+const ANSI_BOLD_CYAN: &str = "\x1b[1;36m";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiagnosticTextOptions {
+    pub color: bool,
+    pub context_line_count: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Help => "help",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Severity::Error => ANSI_BOLD_RED,
+            Severity::Warning => ANSI_BOLD_YELLOW,
+            Severity::Note => ANSI_BOLD_CYAN,
+            Severity::Help => ANSI_BOLD_GREEN,
+        }
+    }
+}
+
+#[must_use]
+pub fn render_diagnostics_text(
+    diagnostics: &[RenderedDiagnostic],
+    source_by_path: &BTreeMap<String, String>,
+    options: DiagnosticTextOptions,
+) -> String {
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        let source = source_by_path
+            .get(&diagnostic.path)
+            .map_or("", String::as_str);
+        render_source_location(
+            &mut output,
+            &diagnostic.path,
+            source,
+            &diagnostic.span,
+            match diagnostic.severity {
+                RenderedDiagnosticSeverity::Error => Severity::Error,
+                RenderedDiagnosticSeverity::Warning => Severity::Warning,
+            },
+            &diagnostic.message,
+            options,
+        );
+        for related_span in &diagnostic.related_spans {
+            let related_path = related_span.path.as_ref().unwrap_or(&diagnostic.path);
+            let related_source = source_by_path.get(related_path).map_or("", String::as_str);
+            render_source_location(
+                &mut output,
+                related_path,
+                related_source,
+                &related_span.span,
+                Severity::Note,
+                &related_span.message,
+                options,
+            );
+        }
+        for suggested_fix in &diagnostic.suggested_fixes {
+            let Some(first_text_edit) = suggested_fix.text_edits.first() else {
+                continue;
+            };
+            render_source_location(
+                &mut output,
+                &diagnostic.path,
+                source,
+                &first_text_edit.span,
+                Severity::Help,
+                &suggested_fix.label,
+                options,
+            );
+        }
+    }
+    output
+}
+
+fn render_source_location(
+    output: &mut String,
+    path: &str,
+    source: &str,
+    span: &RenderedSpan,
+    severity: Severity,
+    message: &str,
+    options: DiagnosticTextOptions,
+) {
+    let line = span.line;
+    let column = span.column;
+    let (bold, severity_color, reset) = if options.color {
+        (ANSI_BOLD, severity.color(), ANSI_RESET)
+    } else {
+        ("", "", "")
+    };
+    let label = severity.label();
+    let _ = writeln!(
+        output,
+        "{bold}{path}:{line}:{column}:{reset} {severity_color}{label}:{reset} {message}"
+    );
+
+    let source_lines = source.lines().collect::<Vec<_>>();
+    let line_index = line.saturating_sub(1);
+    let context_start_index = line_index.saturating_sub(options.context_line_count);
+    for context_line_text in source_lines
+        .get(context_start_index..line_index)
+        .unwrap_or_default()
+    {
+        let _ = writeln!(output, "  {context_line_text}");
+    }
+
+    let line_text = source_lines.get(line_index).copied().unwrap_or("");
+    let _ = writeln!(output, "  {line_text}");
+    if !line_text.is_empty() {
+        let (line_prefix, line_rest) = split_at_column(line_text, column);
+        let caret_indent = " ".repeat(display_width(line_prefix));
+        let underline = "^".repeat(underline_width(span, line_rest));
+        let _ = writeln!(output, "  {caret_indent}{severity_color}{underline}{reset}");
+    }
+
+    let context_end_index = (line_index + 1 + options.context_line_count).min(source_lines.len());
+    for context_line_text in source_lines
+        .get(line_index + 1..context_end_index)
+        .unwrap_or_default()
+    {
+        let _ = writeln!(output, "  {context_line_text}");
+    }
+}
+
+fn split_at_column(line_text: &str, column: usize) -> (&str, &str) {
+    let column_offset = line_text
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(line_text.len(), |(offset, _)| offset);
+    line_text.split_at(column_offset)
+}
+
+// Spans that continue past the end of their first line are underlined up to
+// the end of that line. Widths are measured in terminal columns, so wide
+// characters get two carets.
+fn underline_width(span: &RenderedSpan, line_rest: &str) -> usize {
+    let span_length = span.end.saturating_sub(span.start);
+    display_width(&line_rest[..floor_char_boundary(line_rest, span_length)]).max(1)
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use compiler__binding as binding;
 use compiler__diagnostics::FileScopedDiagnostic;
 use compiler__exports as exports;
-use compiler__package_graph as package_graph;
+use compiler__package_graph::{self as package_graph, PackageDependencyRule};
 use compiler__phase_results::{FileScopedPhaseOutput, PhaseStatus};
 use compiler__symbols::{self as symbols, PackageFile};
 use compiler__syntax::SyntaxParsedFile;
@@ -21,7 +21,10 @@ pub struct ResolutionArtifacts {
 }
 
 #[must_use]
-pub fn resolve_files(files: &[ResolutionFile<'_>]) -> FileScopedPhaseOutput<ResolutionArtifacts> {
+pub fn resolve_files(
+    files: &[ResolutionFile<'_>],
+    dependency_rules: &[PackageDependencyRule],
+) -> FileScopedPhaseOutput<ResolutionArtifacts> {
     let package_files: Vec<PackageFile<'_>> = files
         .iter()
         .map(|file| PackageFile {
@@ -45,6 +48,11 @@ pub fn resolve_files(files: &[ResolutionFile<'_>]) -> FileScopedPhaseOutput<Reso
         &mut package_diagnostics,
     );
     package_graph::check_cycles(&resolved_imports, &mut package_diagnostics);
+    package_graph::check_dependency_rules(
+        &resolved_imports,
+        dependency_rules,
+        &mut package_diagnostics,
+    );
     let cycle_package_paths = package_graph::package_paths_in_cycle(&resolved_imports);
     let bindings_by_file = visibility::resolved_bindings_by_file(&resolved_imports);
     binding::check_bindings(&package_files, &bindings_by_file, &mut package_diagnostics);
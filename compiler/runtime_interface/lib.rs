@@ -1,17 +1,241 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RuntimeType {
     Boolean,
+    Int64,
+    // `int64 | nil`, which the checked builtins return.
+    Int64OrNil,
     Nil,
     Never,
     String,
 }
 
+// A process resource that builtins reach outside the program itself. An
+// `ExecutionPolicy` can deny capabilities to programs that are not trusted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RuntimeCapability {
+    StandardOutput,
+}
+
+impl RuntimeCapability {
+    pub const ALL: &[RuntimeCapability] = &[RuntimeCapability::StandardOutput];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeCapability::StandardOutput => "standard_output",
+        }
+    }
+
+    // Native executables read the policy from their environment, where setting
+    // this variable denies the capability.
+    #[must_use]
+    pub fn denial_environment_variable(self) -> &'static str {
+        match self {
+            RuntimeCapability::StandardOutput => "COPPICE_DENY_STANDARD_OUTPUT",
+        }
+    }
+
+    // Written to stderr when a denied builtin is called, before the program
+    // exits with the same status as `abort`.
+    #[must_use]
+    pub fn denial_message(self) -> String {
+        format!(
+            "capability '{}' is denied by the execution policy",
+            self.name()
+        )
+    }
+}
+
+// The default policy permits every capability; the sandboxed profile denies
+// them all.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionPolicy {
+    pub denied_capabilities: BTreeSet<RuntimeCapability>,
+}
+
+impl ExecutionPolicy {
+    #[must_use]
+    pub fn sandboxed() -> Self {
+        Self {
+            denied_capabilities: RuntimeCapability::ALL.iter().copied().collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn permits(&self, capability: RuntimeCapability) -> bool {
+        !self.denied_capabilities.contains(&capability)
+    }
+}
+
+// Native executables write the message to stderr and exit with this status
+// when a program aborts, whether through `abort`, a failed `assert`, a denied
+// capability, or an overflow in a run that aborts on overflow.
+pub const ABORT_EXIT_CODE: i32 = 1;
+
+pub const RANDOM_SEED_ENVIRONMENT_VARIABLE: &str = "COPPICE_RANDOM_SEED";
+pub const FIXED_NOW_ENVIRONMENT_VARIABLE: &str = "COPPICE_FIXED_NOW_UNIX_SECONDS";
+pub const RANDOM_INT_EMPTY_RANGE_MESSAGE: &str = "random_int range is empty";
+pub const POW_NEGATIVE_EXPONENT_MESSAGE: &str = "pow exponent is negative";
+pub const ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE: &str = "COPPICE_ABORT_ON_OVERFLOW";
+pub const ARITHMETIC_OVERFLOW_MESSAGE: &str = "arithmetic overflow";
+pub const INTEGER_DIVISION_BY_ZERO_MESSAGE: &str = "integer division by zero";
+pub const INTEGER_REMAINDER_BY_ZERO_MESSAGE: &str = "integer remainder by zero";
+pub const RANGE_STEP_ZERO_MESSAGE: &str = "range step is zero";
+
+// How `+`, `-`, `*`, `/`, and unary minus treat an int64 result that does not
+// fit. Runs wrap by default, as release builds of most languages do; debugging
+// runs can abort instead, naming the operation and where it is. Division and
+// remainder by zero abort either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    #[default]
+    Wrap,
+    Abort,
+}
+
+// Follows one of the arithmetic messages above with the operation's source
+// text and position, when the program records them.
+#[must_use]
+pub fn arithmetic_abort_message(message: &str, operation_description: Option<&str>) -> String {
+    match operation_description {
+        Some(operation_description) => format!("{message}: {operation_description}"),
+        None => message.to_string(),
+    }
+}
+
+// Receives what a program writes to its standard streams, so that tools can
+// capture the output of a run instead of letting it inherit the host's.
+pub trait OutputSink: Send {
+    fn write_standard_output(&mut self, bytes: &[u8]);
+    fn write_standard_error(&mut self, bytes: &[u8]);
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    pub standard_output: Vec<u8>,
+    pub standard_error: Vec<u8>,
+}
+
+impl OutputSink for CapturedOutput {
+    fn write_standard_output(&mut self, bytes: &[u8]) {
+        self.standard_output.extend_from_slice(bytes);
+    }
+
+    fn write_standard_error(&mut self, bytes: &[u8]) {
+        self.standard_error.extend_from_slice(bytes);
+    }
+}
+
+// A sink shared between the caller, who reads it back after the run, and the
+// run writing to it. Handles are equal when they share the same sink.
+#[derive(Clone)]
+pub struct SharedOutputSink(Arc<Mutex<dyn OutputSink>>);
+
+impl SharedOutputSink {
+    #[must_use]
+    pub fn new(output_sink: Arc<Mutex<dyn OutputSink>>) -> Self {
+        Self(output_sink)
+    }
+
+    pub fn write_standard_output(&self, bytes: &[u8]) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_standard_output(bytes);
+    }
+
+    pub fn write_standard_error(&self, bytes: &[u8]) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_standard_error(bytes);
+    }
+}
+
+impl fmt::Debug for SharedOutputSink {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("SharedOutputSink")
+    }
+}
+
+impl PartialEq for SharedOutputSink {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedOutputSink {}
+
+// Everything a run needs besides the program. When the random seed or the
+// fixed time is unset it comes from the system clock; setting both makes every
+// run of a program observe the same values. Native executables read them and
+// the overflow mode from the environment variables above. Without an output
+// sink the program writes to the host's stdout and stderr.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionContext {
+    pub execution_policy: ExecutionPolicy,
+    pub random_seed: Option<i64>,
+    pub fixed_now_unix_seconds: Option<i64>,
+    pub overflow_mode: OverflowMode,
+    pub output_sink: Option<SharedOutputSink>,
+}
+
+// `random_int` draws from a SplitMix64 sequence whose state starts at the
+// seed. Native executables emit the same steps, so a seed produces the same
+// values whether a program is built or interpreted.
+#[must_use]
+pub fn next_random_value(random_state: &mut u64) -> u64 {
+    *random_state = random_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut value = *random_state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+// Maps a random value into `minimum..=maximum`, which must not be empty.
+#[must_use]
+pub fn random_int_in_range(random_value: u64, minimum: i64, maximum: i64) -> i64 {
+    let range_size = maximum
+        .wrapping_sub(minimum)
+        .cast_unsigned()
+        .wrapping_add(1);
+    if range_size == 0 {
+        return random_value.cast_signed();
+    }
+    minimum.wrapping_add((random_value % range_size).cast_signed())
+}
+
+// `pow` multiplies by squaring with wrapping multiplication, which native
+// executables emit as the same loop. A negative exponent has no int64 result.
+#[must_use]
+pub fn wrapping_pow(base: i64, exponent: i64) -> Option<i64> {
+    if exponent < 0 {
+        return None;
+    }
+    let mut result: i64 = 1;
+    let mut base = base;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+    Some(result)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RuntimeFunctionContract {
     pub language_name: &'static str,
     pub lowered_symbol_name: &'static str,
     pub parameter_types: &'static [RuntimeType],
     pub return_type: RuntimeType,
+    pub required_capability: Option<RuntimeCapability>,
 }
 
 pub const USER_ENTRYPOINT_FUNCTION_NAME: &str = "main";
@@ -21,6 +245,7 @@ pub const PRINT_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionCont
     lowered_symbol_name: "coppice_runtime_print",
     parameter_types: &[RuntimeType::String],
     return_type: RuntimeType::Nil,
+    required_capability: Some(RuntimeCapability::StandardOutput),
 };
 
 pub const ABORT_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
@@ -28,11 +253,201 @@ pub const ABORT_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionCont
     lowered_symbol_name: "coppice_runtime_abort",
     parameter_types: &[RuntimeType::String],
     return_type: RuntimeType::Never,
+    required_capability: None,
 };
 
+pub const UNREACHABLE_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "unreachable",
+    lowered_symbol_name: "coppice_runtime_unreachable",
+    parameter_types: &[],
+    return_type: RuntimeType::Never,
+    required_capability: None,
+};
+
+// Programs call `assert` with a condition only; lowering adds the condition's
+// source text and position as a description for the failure message.
 pub const ASSERT_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
     language_name: "assert",
     lowered_symbol_name: "coppice_runtime_assert",
-    parameter_types: &[RuntimeType::Boolean],
+    parameter_types: &[RuntimeType::Boolean, RuntimeType::String],
     return_type: RuntimeType::Nil,
+    required_capability: None,
+};
+
+pub const NOW_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "now",
+    lowered_symbol_name: "coppice_runtime_now",
+    parameter_types: &[],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const RANDOM_INT_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "random_int",
+    lowered_symbol_name: "coppice_runtime_random_int",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+// The math builtins wrap on overflow like the arithmetic operators. `min` and
+// `max` are generic in the language, but int64 is the only ordered type that
+// reaches the runtime. `sqrt` has no contract until there is a float type.
+pub const ABS_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "abs",
+    lowered_symbol_name: "coppice_runtime_abs",
+    parameter_types: &[RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const MIN_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "min",
+    lowered_symbol_name: "coppice_runtime_min",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const MAX_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "max",
+    lowered_symbol_name: "coppice_runtime_max",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
 };
+
+// Aborts with `POW_NEGATIVE_EXPONENT_MESSAGE` when the exponent is negative.
+pub const POW_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "pow",
+    lowered_symbol_name: "coppice_runtime_pow",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+// The checked builtins return nil instead of wrapping.
+pub const CHECKED_ADD_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "checked_add",
+    lowered_symbol_name: "coppice_runtime_checked_add",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64OrNil,
+    required_capability: None,
+};
+
+pub const CHECKED_SUB_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "checked_sub",
+    lowered_symbol_name: "coppice_runtime_checked_sub",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64OrNil,
+    required_capability: None,
+};
+
+pub const CHECKED_MUL_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "checked_mul",
+    lowered_symbol_name: "coppice_runtime_checked_mul",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64OrNil,
+    required_capability: None,
+};
+
+// The saturating builtins clamp to the int64 range instead of wrapping.
+pub const SATURATING_ADD_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "saturating_add",
+    lowered_symbol_name: "coppice_runtime_saturating_add",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const SATURATING_SUB_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "saturating_sub",
+    lowered_symbol_name: "coppice_runtime_saturating_sub",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const SATURATING_MUL_FUNCTION_CONTRACT: RuntimeFunctionContract = RuntimeFunctionContract {
+    language_name: "saturating_mul",
+    lowered_symbol_name: "coppice_runtime_saturating_mul",
+    parameter_types: &[RuntimeType::Int64, RuntimeType::Int64],
+    return_type: RuntimeType::Int64,
+    required_capability: None,
+};
+
+pub const BUILTIN_FUNCTION_CONTRACTS: &[RuntimeFunctionContract] = &[
+    PRINT_FUNCTION_CONTRACT,
+    ABORT_FUNCTION_CONTRACT,
+    UNREACHABLE_FUNCTION_CONTRACT,
+    ASSERT_FUNCTION_CONTRACT,
+    NOW_FUNCTION_CONTRACT,
+    RANDOM_INT_FUNCTION_CONTRACT,
+    ABS_FUNCTION_CONTRACT,
+    MIN_FUNCTION_CONTRACT,
+    MAX_FUNCTION_CONTRACT,
+    POW_FUNCTION_CONTRACT,
+    CHECKED_ADD_FUNCTION_CONTRACT,
+    CHECKED_SUB_FUNCTION_CONTRACT,
+    CHECKED_MUL_FUNCTION_CONTRACT,
+    SATURATING_ADD_FUNCTION_CONTRACT,
+    SATURATING_SUB_FUNCTION_CONTRACT,
+    SATURATING_MUL_FUNCTION_CONTRACT,
+];
+
+// Builtins without a runtime contract: lowering expands them in place, or
+// their signature is generic or has a type a runtime type cannot describe.
+// Together with `BUILTIN_FUNCTION_CONTRACTS` these are every builtin function
+// a program can call.
+pub const BUILTIN_FUNCTION_NAMES_WITHOUT_CONTRACT: &[&str] = &[
+    "string",
+    "format",
+    "variant_name",
+    "assert_equals",
+    "debug_string",
+];
+
+// One line per runtime contract that compiled executables depend on. Native
+// executables record these lines when they are built, so that running one
+// after the contracts changed fails with the lines that differ.
+#[must_use]
+pub fn runtime_contract_lines() -> Vec<String> {
+    let mut lines = BUILTIN_FUNCTION_CONTRACTS
+        .iter()
+        .map(|contract| {
+            let parameter_types = contract
+                .parameter_types
+                .iter()
+                .map(|parameter_type| format!("{parameter_type:?}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let required_capability = contract
+                .required_capability
+                .map_or_else(String::new, |capability| {
+                    format!(" requires {}", capability.name())
+                });
+            format!(
+                "builtin {}({parameter_types}) -> {:?} as {}{required_capability}",
+                contract.language_name, contract.return_type, contract.lowered_symbol_name
+            )
+        })
+        .collect::<Vec<_>>();
+    for capability in RuntimeCapability::ALL {
+        lines.push(format!(
+            "capability {} denied by {}",
+            capability.name(),
+            capability.denial_environment_variable()
+        ));
+    }
+    lines.push(format!("abort exit code {ABORT_EXIT_CODE}"));
+    lines.push(format!(
+        "random seed variable {RANDOM_SEED_ENVIRONMENT_VARIABLE}"
+    ));
+    lines.push(format!(
+        "fixed now variable {FIXED_NOW_ENVIRONMENT_VARIABLE}"
+    ));
+    lines.push(format!(
+        "abort on overflow variable {ABORT_ON_OVERFLOW_ENVIRONMENT_VARIABLE}"
+    ));
+    lines
+}
@@ -1,14 +1,19 @@
 use compiler__fix_edits::TextEdit;
 
+// `rule` names the check that proposed the fix. Together with the phase that
+// emitted it, it identifies a fix that was skipped for conflicting with
+// another, until diagnostics have codes.
 #[derive(Clone, Debug)]
 pub struct SafeAutofix {
+    pub rule: &'static str,
     pub text_edits: Vec<TextEdit>,
 }
 
 impl SafeAutofix {
     #[must_use]
-    pub fn from_text_edit(text_edit: TextEdit) -> Self {
+    pub fn from_text_edit(rule: &'static str, text_edit: TextEdit) -> Self {
         Self {
+            rule,
             text_edits: vec![text_edit],
         }
     }
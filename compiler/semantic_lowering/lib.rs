@@ -1,5 +1,6 @@
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
 use compiler__semantic_program as semantic;
+use compiler__source::{AttributeTarget, KnownAttribute};
 use compiler__syntax as syntax;
 
 struct LoweringContext {
@@ -56,8 +57,15 @@ pub fn lower_parsed_file(
                 }
                 syntax::SyntaxDeclaration::Import(_)
                 | syntax::SyntaxDeclaration::Exports(_)
+                | syntax::SyntaxDeclaration::Package(_)
                 | syntax::SyntaxDeclaration::Group(_)
                 | syntax::SyntaxDeclaration::Test(_) => {}
+                syntax::SyntaxDeclaration::StaticAssert(static_assert_declaration) => {
+                    pending_doc_comment = None;
+                    declarations.push(semantic::SemanticDeclaration::StaticAssert(
+                        lower_static_assert_declaration(static_assert_declaration, &mut context),
+                    ));
+                }
             },
         }
     }
@@ -76,8 +84,7 @@ pub fn lower_parsed_file(
 fn lower_doc_comment(doc_comment: &syntax::SyntaxDocComment) -> semantic::SemanticDocComment {
     semantic::SemanticDocComment {
         lines: doc_comment.lines.clone(),
-        span: doc_comment.span.clone(),
-        end_line: doc_comment.end_line,
+        span: doc_comment.span,
     }
 }
 
@@ -106,7 +113,7 @@ fn lower_type_declaration(
 ) -> semantic::SemanticTypeDeclaration {
     semantic::SemanticTypeDeclaration {
         name: type_declaration.name.clone(),
-        name_span: type_declaration.name_span.clone(),
+        name_span: type_declaration.name_span,
         type_parameters: type_declaration
             .type_parameters
             .iter()
@@ -119,8 +126,9 @@ fn lower_type_declaration(
             .collect(),
         kind: lower_type_declaration_kind(&type_declaration.kind, context),
         doc,
+        attributes: lower_attributes(&type_declaration.attributes, AttributeTarget::Type),
         visibility: lower_top_level_visibility(type_declaration.visibility),
-        span: type_declaration.span.clone(),
+        span: type_declaration.span,
     }
 }
 
@@ -176,7 +184,7 @@ fn lower_type_declaration_kind(
 fn lower_enum_variant(variant: &syntax::SyntaxEnumVariant) -> semantic::SemanticEnumVariant {
     semantic::SemanticEnumVariant {
         name: variant.name.clone(),
-        span: variant.span.clone(),
+        span: variant.span,
     }
 }
 
@@ -189,7 +197,7 @@ fn lower_field_declaration(
         type_name: lower_type_name(&field.type_name),
         doc,
         visibility: lower_member_visibility(field.visibility),
-        span: field.span.clone(),
+        span: field.span,
     }
 }
 
@@ -200,8 +208,8 @@ fn lower_method_declaration(
 ) -> semantic::SemanticMethodDeclaration {
     semantic::SemanticMethodDeclaration {
         name: method.name.clone(),
-        name_span: method.name_span.clone(),
-        self_span: method.self_span.clone(),
+        name_span: method.name_span,
+        self_span: method.self_span,
         self_mutable: method.self_mutable,
         parameters: method
             .parameters
@@ -212,7 +220,7 @@ fn lower_method_declaration(
         body: lower_block(&method.body, context),
         doc,
         visibility: lower_member_visibility(method.visibility),
-        span: method.span.clone(),
+        span: method.span,
     }
 }
 
@@ -221,8 +229,8 @@ fn lower_interface_method_declaration(
 ) -> semantic::SemanticInterfaceMethodDeclaration {
     semantic::SemanticInterfaceMethodDeclaration {
         name: method.name.clone(),
-        name_span: method.name_span.clone(),
-        self_span: method.self_span.clone(),
+        name_span: method.name_span,
+        self_span: method.self_span,
         self_mutable: method.self_mutable,
         parameters: method
             .parameters
@@ -230,10 +238,38 @@ fn lower_interface_method_declaration(
             .map(lower_parameter_declaration)
             .collect(),
         return_type: lower_type_name(&method.return_type),
-        span: method.span.clone(),
+        span: method.span,
     }
 }
 
+fn lower_attributes(
+    attributes: &[syntax::SyntaxAttribute],
+    target: AttributeTarget,
+) -> Vec<semantic::SemanticAttribute> {
+    let mut lowered_attributes: Vec<semantic::SemanticAttribute> = Vec::new();
+    for attribute in attributes {
+        let Some(kind) = KnownAttribute::from_name(&attribute.name) else {
+            continue;
+        };
+        if !kind.applies_to(target)
+            || attribute.arguments.len() != kind.argument_count()
+            || semantic::find_attribute(&lowered_attributes, kind).is_some()
+        {
+            continue;
+        }
+        lowered_attributes.push(semantic::SemanticAttribute {
+            kind,
+            arguments: attribute
+                .arguments
+                .iter()
+                .map(|argument| argument.value.clone())
+                .collect(),
+            span: attribute.span,
+        });
+    }
+    lowered_attributes
+}
+
 fn lower_constant_declaration(
     constant: &syntax::SyntaxConstantDeclaration,
     context: &mut LoweringContext,
@@ -241,12 +277,24 @@ fn lower_constant_declaration(
 ) -> semantic::SemanticConstantDeclaration {
     semantic::SemanticConstantDeclaration {
         name: constant.name.clone(),
-        name_span: constant.name_span.clone(),
+        name_span: constant.name_span,
         type_name: lower_type_name(&constant.type_name),
         expression: lower_expression(&constant.expression, context),
         doc,
+        attributes: lower_attributes(&constant.attributes, AttributeTarget::Constant),
         visibility: lower_top_level_visibility(constant.visibility),
-        span: constant.span.clone(),
+        span: constant.span,
+    }
+}
+
+fn lower_static_assert_declaration(
+    static_assert: &syntax::SyntaxStaticAssertDeclaration,
+    context: &mut LoweringContext,
+) -> semantic::SemanticStaticAssertDeclaration {
+    semantic::SemanticStaticAssertDeclaration {
+        condition: lower_expression(&static_assert.condition, context),
+        message: static_assert.message.clone(),
+        span: static_assert.span,
     }
 }
 
@@ -257,7 +305,7 @@ fn lower_function_declaration(
 ) -> semantic::SemanticFunctionDeclaration {
     semantic::SemanticFunctionDeclaration {
         name: function.name.clone(),
-        name_span: function.name_span.clone(),
+        name_span: function.name_span,
         type_parameters: function
             .type_parameters
             .iter()
@@ -271,8 +319,9 @@ fn lower_function_declaration(
         return_type: lower_type_name(&function.return_type),
         body: lower_block(&function.body, context),
         doc,
+        attributes: lower_attributes(&function.attributes, AttributeTarget::Function),
         visibility: lower_top_level_visibility(function.visibility),
-        span: function.span.clone(),
+        span: function.span,
     }
 }
 
@@ -281,10 +330,10 @@ fn lower_parameter_declaration(
 ) -> semantic::SemanticParameterDeclaration {
     semantic::SemanticParameterDeclaration {
         name: parameter.name.clone(),
-        name_span: parameter.name_span.clone(),
+        name_span: parameter.name_span,
         mutable: parameter.mutable,
         type_name: lower_type_name(&parameter.type_name),
-        span: parameter.span.clone(),
+        span: parameter.span,
     }
 }
 
@@ -303,7 +352,7 @@ fn lower_block(
                 }
             })
             .collect(),
-        span: block.span.clone(),
+        span: block.span,
     }
 }
 
@@ -321,11 +370,11 @@ fn lower_statement(
             span,
         } => semantic::SemanticStatement::Binding {
             name: name.clone(),
-            name_span: name_span.clone(),
+            name_span: *name_span,
             mutable: *mutable,
             type_name: type_name.as_ref().map(lower_type_name),
             initializer: lower_expression(initializer, context),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxStatement::Assign {
             target,
@@ -334,20 +383,24 @@ fn lower_statement(
         } => semantic::SemanticStatement::Assign {
             target: lower_assign_target(target, context),
             value: lower_expression(value, context),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxStatement::Return { value, span } => semantic::SemanticStatement::Return {
             value: value
                 .as_ref()
                 .map(|expression| lower_expression(expression, context)),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxStatement::Break { span } => {
-            semantic::SemanticStatement::Break { span: span.clone() }
+            semantic::SemanticStatement::Break { span: *span }
         }
         syntax::SyntaxStatement::Continue { span } => {
-            semantic::SemanticStatement::Continue { span: span.clone() }
+            semantic::SemanticStatement::Continue { span: *span }
         }
+        syntax::SyntaxStatement::Defer { value, span } => semantic::SemanticStatement::Defer {
+            value: lower_expression(value, context),
+            span: *span,
+        },
         syntax::SyntaxStatement::If {
             condition,
             then_block,
@@ -357,7 +410,16 @@ fn lower_statement(
             condition: lower_expression(condition, context),
             then_block: lower_block(then_block, context),
             else_block: else_block.as_ref().map(|block| lower_block(block, context)),
-            span: span.clone(),
+            span: *span,
+        },
+        syntax::SyntaxStatement::Guard {
+            condition,
+            else_block,
+            span,
+        } => semantic::SemanticStatement::Guard {
+            condition: lower_expression(condition, context),
+            else_block: lower_block(else_block, context),
+            span: *span,
         },
         syntax::SyntaxStatement::For {
             condition,
@@ -368,12 +430,31 @@ fn lower_statement(
                 .as_ref()
                 .map(|expression| lower_expression(expression, context)),
             body: lower_block(body, context),
-            span: span.clone(),
+            span: *span,
+        },
+        syntax::SyntaxStatement::ForRange {
+            name,
+            name_span,
+            start,
+            end,
+            step,
+            body,
+            span,
+        } => semantic::SemanticStatement::ForRange {
+            name: name.clone(),
+            name_span: *name_span,
+            start: lower_expression(start, context),
+            end: lower_expression(end, context),
+            step: step
+                .as_ref()
+                .map(|expression| Box::new(lower_expression(expression, context))),
+            body: lower_block(body, context),
+            span: *span,
         },
         syntax::SyntaxStatement::Expression { value, span } => {
             semantic::SemanticStatement::Expression {
                 value: lower_expression(value, context),
-                span: span.clone(),
+                span: *span,
             }
         }
     }
@@ -390,8 +471,8 @@ fn lower_assign_target(
             span,
         } => semantic::SemanticAssignTarget::Name {
             name: name.clone(),
-            name_span: name_span.clone(),
-            span: span.clone(),
+            name_span: *name_span,
+            span: *span,
         },
         syntax::SyntaxAssignTarget::Index {
             target,
@@ -400,7 +481,7 @@ fn lower_assign_target(
         } => semantic::SemanticAssignTarget::Index {
             target: Box::new(lower_expression(target, context)),
             index: Box::new(lower_expression(index, context)),
-            span: span.clone(),
+            span: *span,
         },
     }
 }
@@ -415,25 +496,24 @@ fn lower_expression(
             semantic::SemanticExpression::IntegerLiteral {
                 id,
                 value: *value,
-                span: span.clone(),
+                span: *span,
             }
         }
-        syntax::SyntaxExpression::NilLiteral { span } => semantic::SemanticExpression::NilLiteral {
-            id,
-            span: span.clone(),
-        },
+        syntax::SyntaxExpression::NilLiteral { span } => {
+            semantic::SemanticExpression::NilLiteral { id, span: *span }
+        }
         syntax::SyntaxExpression::BooleanLiteral { value, span } => {
             semantic::SemanticExpression::BooleanLiteral {
                 id,
                 value: *value,
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxExpression::StringLiteral { value, span } => {
             semantic::SemanticExpression::StringLiteral {
                 id,
                 value: value.clone(),
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxExpression::ListLiteral { elements, span } => {
@@ -443,7 +523,7 @@ fn lower_expression(
                     .iter()
                     .map(|element| lower_expression(element, context))
                     .collect(),
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxExpression::NameReference { name, kind, span } => {
@@ -458,7 +538,7 @@ fn lower_expression(
                         semantic::SemanticNameReferenceKind::Builtin
                     }
                 },
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxExpression::StructLiteral {
@@ -472,7 +552,7 @@ fn lower_expression(
                 .iter()
                 .map(|field| lower_struct_literal_field(field, context))
                 .collect(),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxExpression::FieldAccess {
             target,
@@ -483,8 +563,8 @@ fn lower_expression(
             id,
             target: Box::new(lower_expression(target, context)),
             field: field.clone(),
-            field_span: field_span.clone(),
-            span: span.clone(),
+            field_span: *field_span,
+            span: *span,
         },
         syntax::SyntaxExpression::IndexAccess {
             target,
@@ -494,7 +574,7 @@ fn lower_expression(
             id,
             target: Box::new(lower_expression(target, context)),
             index: Box::new(lower_expression(index, context)),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxExpression::Call {
             callee,
@@ -509,7 +589,7 @@ fn lower_expression(
                 .iter()
                 .map(|argument| lower_expression(argument, context))
                 .collect(),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxExpression::Unary {
             operator,
@@ -519,7 +599,7 @@ fn lower_expression(
             id,
             operator: lower_unary_operator(*operator),
             expression: Box::new(lower_expression(expression, context)),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxExpression::Binary {
             operator,
@@ -531,8 +611,28 @@ fn lower_expression(
             operator: lower_binary_operator(*operator),
             left: Box::new(lower_expression(left, context)),
             right: Box::new(lower_expression(right, context)),
-            span: span.clone(),
+            span: *span,
         },
+        syntax::SyntaxExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
+        } => semantic::SemanticExpression::If {
+            id,
+            condition: Box::new(lower_expression(condition, context)),
+            then_branch: Box::new(lower_expression(then_branch, context)),
+            else_branch: Box::new(lower_expression(else_branch, context)),
+            span: *span,
+        },
+        syntax::SyntaxExpression::Block { block, value, span } => {
+            semantic::SemanticExpression::Block {
+                id,
+                block: lower_block(block, context),
+                value: Box::new(lower_expression(value, context)),
+                span: *span,
+            }
+        }
         syntax::SyntaxExpression::Match { target, arms, span } => {
             semantic::SemanticExpression::Match {
                 id,
@@ -541,7 +641,7 @@ fn lower_expression(
                     .iter()
                     .map(|arm| lower_match_arm(arm, context))
                     .collect(),
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxExpression::Matches {
@@ -552,7 +652,7 @@ fn lower_expression(
             id,
             value: Box::new(lower_expression(value, context)),
             type_name: lower_type_name(type_name),
-            span: span.clone(),
+            span: *span,
         },
         syntax::SyntaxExpression::StringInterpolation { parts, span } => {
             semantic::SemanticExpression::StringInterpolation {
@@ -570,7 +670,7 @@ fn lower_expression(
                         }
                     })
                     .collect(),
-                span: span.clone(),
+                span: *span,
             }
         }
     }
@@ -585,6 +685,11 @@ fn lower_binary_operator(
         syntax::SyntaxBinaryOperator::Multiply => semantic::SemanticBinaryOperator::Multiply,
         syntax::SyntaxBinaryOperator::Divide => semantic::SemanticBinaryOperator::Divide,
         syntax::SyntaxBinaryOperator::Modulo => semantic::SemanticBinaryOperator::Modulo,
+        syntax::SyntaxBinaryOperator::BitwiseAnd => semantic::SemanticBinaryOperator::BitwiseAnd,
+        syntax::SyntaxBinaryOperator::BitwiseOr => semantic::SemanticBinaryOperator::BitwiseOr,
+        syntax::SyntaxBinaryOperator::BitwiseXor => semantic::SemanticBinaryOperator::BitwiseXor,
+        syntax::SyntaxBinaryOperator::ShiftLeft => semantic::SemanticBinaryOperator::ShiftLeft,
+        syntax::SyntaxBinaryOperator::ShiftRight => semantic::SemanticBinaryOperator::ShiftRight,
         syntax::SyntaxBinaryOperator::EqualEqual => semantic::SemanticBinaryOperator::EqualEqual,
         syntax::SyntaxBinaryOperator::NotEqual => semantic::SemanticBinaryOperator::NotEqual,
         syntax::SyntaxBinaryOperator::LessThan => semantic::SemanticBinaryOperator::LessThan,
@@ -613,9 +718,9 @@ fn lower_struct_literal_field(
 ) -> semantic::SemanticStructLiteralField {
     semantic::SemanticStructLiteralField {
         name: field.name.clone(),
-        name_span: field.name_span.clone(),
+        name_span: field.name_span,
         value: lower_expression(&field.value, context),
-        span: field.span.clone(),
+        span: field.span,
     }
 }
 
@@ -625,8 +730,12 @@ fn lower_match_arm(
 ) -> semantic::SemanticMatchArm {
     semantic::SemanticMatchArm {
         pattern: lower_match_pattern(&arm.pattern),
+        guard: arm
+            .guard
+            .as_ref()
+            .map(|guard| lower_expression(guard, context)),
         value: lower_expression(&arm.value, context),
-        span: arm.span.clone(),
+        span: arm.span,
     }
 }
 
@@ -635,7 +744,7 @@ fn lower_match_pattern(pattern: &syntax::SyntaxMatchPattern) -> semantic::Semant
         syntax::SyntaxMatchPattern::Type { type_name, span } => {
             semantic::SemanticMatchPattern::Type {
                 type_name: lower_type_name(type_name),
-                span: span.clone(),
+                span: *span,
             }
         }
         syntax::SyntaxMatchPattern::Binding {
@@ -645,10 +754,26 @@ fn lower_match_pattern(pattern: &syntax::SyntaxMatchPattern) -> semantic::Semant
             span,
         } => semantic::SemanticMatchPattern::Binding {
             name: name.clone(),
-            name_span: name_span.clone(),
+            name_span: *name_span,
             type_name: lower_type_name(type_name),
-            span: span.clone(),
+            span: *span,
         },
+        syntax::SyntaxMatchPattern::Literal { value, span } => {
+            semantic::SemanticMatchPattern::Literal {
+                value: match value {
+                    syntax::SyntaxMatchLiteral::Integer(value) => {
+                        semantic::SemanticMatchLiteral::Integer(*value)
+                    }
+                    syntax::SyntaxMatchLiteral::String(value) => {
+                        semantic::SemanticMatchLiteral::String(value.clone())
+                    }
+                    syntax::SyntaxMatchLiteral::Boolean(value) => {
+                        semantic::SemanticMatchLiteral::Boolean(*value)
+                    }
+                },
+                span: *span,
+            }
+        }
     }
 }
 
@@ -660,10 +785,10 @@ fn lower_type_name(type_name: &syntax::SyntaxTypeName) -> semantic::SemanticType
             .map(|segment| semantic::SemanticTypeNameSegment {
                 name: segment.name.clone(),
                 type_arguments: segment.type_arguments.iter().map(lower_type_name).collect(),
-                span: segment.span.clone(),
+                span: segment.span,
             })
             .collect(),
-        span: type_name.span.clone(),
+        span: type_name.span,
     }
 }
 
@@ -673,6 +798,6 @@ fn lower_type_parameter(
     semantic::SemanticTypeParameter {
         name: type_parameter.name.clone(),
         constraint: type_parameter.constraint.as_ref().map(lower_type_name),
-        span: type_parameter.span.clone(),
+        span: type_parameter.span,
     }
 }
@@ -0,0 +1,384 @@
+use crate::{
+    SemanticAssignTarget, SemanticBlock, SemanticConstantDeclaration, SemanticDeclaration,
+    SemanticExpression, SemanticFile, SemanticFunctionDeclaration, SemanticMatchArm,
+    SemanticMethodDeclaration, SemanticStatement, SemanticStaticAssertDeclaration,
+    SemanticStringInterpolationPart, SemanticStructLiteralField, SemanticTypeDeclaration,
+    SemanticTypeDeclarationKind,
+};
+
+// Rebuilding traversal of semantic blocks, statements, and expressions. Every
+// method defaults to folding the node's children and reassembling it
+// unchanged, so an implementation overrides only the nodes it rewrites.
+// Calling the matching `fold_*_children` function first gives the rewrite
+// already-folded operands. Expression ids and spans are carried over as is.
+pub trait SemanticFold {
+    fn fold_block(&mut self, block: SemanticBlock) -> SemanticBlock {
+        fold_block_children(self, block)
+    }
+
+    fn fold_statement(&mut self, statement: SemanticStatement) -> SemanticStatement {
+        fold_statement_children(self, statement)
+    }
+
+    fn fold_expression(&mut self, expression: SemanticExpression) -> SemanticExpression {
+        fold_expression_children(self, expression)
+    }
+}
+
+pub fn fold_file<F: SemanticFold + ?Sized>(folder: &mut F, file: SemanticFile) -> SemanticFile {
+    SemanticFile {
+        role: file.role,
+        declarations: file
+            .declarations
+            .into_iter()
+            .map(|declaration| fold_declaration(folder, declaration))
+            .collect(),
+    }
+}
+
+pub fn fold_declaration<F: SemanticFold + ?Sized>(
+    folder: &mut F,
+    declaration: SemanticDeclaration,
+) -> SemanticDeclaration {
+    match declaration {
+        SemanticDeclaration::Type(type_declaration) => {
+            let kind = match type_declaration.kind {
+                SemanticTypeDeclarationKind::Struct { fields, methods } => {
+                    SemanticTypeDeclarationKind::Struct {
+                        fields,
+                        methods: methods
+                            .into_iter()
+                            .map(|method| SemanticMethodDeclaration {
+                                body: folder.fold_block(method.body),
+                                ..method
+                            })
+                            .collect(),
+                    }
+                }
+                kind => kind,
+            };
+            SemanticDeclaration::Type(SemanticTypeDeclaration {
+                kind,
+                ..type_declaration
+            })
+        }
+        SemanticDeclaration::Constant(constant_declaration) => {
+            SemanticDeclaration::Constant(SemanticConstantDeclaration {
+                expression: folder.fold_expression(constant_declaration.expression),
+                ..constant_declaration
+            })
+        }
+        SemanticDeclaration::Function(function_declaration) => {
+            SemanticDeclaration::Function(SemanticFunctionDeclaration {
+                body: folder.fold_block(function_declaration.body),
+                ..function_declaration
+            })
+        }
+        SemanticDeclaration::StaticAssert(static_assert_declaration) => {
+            SemanticDeclaration::StaticAssert(SemanticStaticAssertDeclaration {
+                condition: folder.fold_expression(static_assert_declaration.condition),
+                ..static_assert_declaration
+            })
+        }
+    }
+}
+
+pub fn fold_block_children<F: SemanticFold + ?Sized>(
+    folder: &mut F,
+    block: SemanticBlock,
+) -> SemanticBlock {
+    SemanticBlock {
+        statements: block
+            .statements
+            .into_iter()
+            .map(|statement| folder.fold_statement(statement))
+            .collect(),
+        span: block.span,
+    }
+}
+
+pub fn fold_statement_children<F: SemanticFold + ?Sized>(
+    folder: &mut F,
+    statement: SemanticStatement,
+) -> SemanticStatement {
+    match statement {
+        SemanticStatement::Binding {
+            name,
+            name_span,
+            mutable,
+            type_name,
+            initializer,
+            span,
+        } => SemanticStatement::Binding {
+            name,
+            name_span,
+            mutable,
+            type_name,
+            initializer: folder.fold_expression(initializer),
+            span,
+        },
+        SemanticStatement::Assign {
+            target,
+            value,
+            span,
+        } => {
+            let target = match target {
+                SemanticAssignTarget::Name { .. } => target,
+                SemanticAssignTarget::Index {
+                    target,
+                    index,
+                    span,
+                } => SemanticAssignTarget::Index {
+                    target: fold_boxed_expression(folder, target),
+                    index: fold_boxed_expression(folder, index),
+                    span,
+                },
+            };
+            SemanticStatement::Assign {
+                target,
+                value: folder.fold_expression(value),
+                span,
+            }
+        }
+        SemanticStatement::Return { value, span } => SemanticStatement::Return {
+            value: value.map(|value| folder.fold_expression(value)),
+            span,
+        },
+        SemanticStatement::Break { .. } | SemanticStatement::Continue { .. } => statement,
+        SemanticStatement::Defer { value, span } => SemanticStatement::Defer {
+            value: folder.fold_expression(value),
+            span,
+        },
+        SemanticStatement::If {
+            condition,
+            then_block,
+            else_block,
+            span,
+        } => SemanticStatement::If {
+            condition: folder.fold_expression(condition),
+            then_block: folder.fold_block(then_block),
+            else_block: else_block.map(|else_block| folder.fold_block(else_block)),
+            span,
+        },
+        SemanticStatement::Guard {
+            condition,
+            else_block,
+            span,
+        } => SemanticStatement::Guard {
+            condition: folder.fold_expression(condition),
+            else_block: folder.fold_block(else_block),
+            span,
+        },
+        SemanticStatement::For {
+            condition,
+            body,
+            span,
+        } => SemanticStatement::For {
+            condition: condition.map(|condition| folder.fold_expression(condition)),
+            body: folder.fold_block(body),
+            span,
+        },
+        SemanticStatement::ForRange {
+            name,
+            name_span,
+            start,
+            end,
+            step,
+            body,
+            span,
+        } => SemanticStatement::ForRange {
+            name,
+            name_span,
+            start: folder.fold_expression(start),
+            end: folder.fold_expression(end),
+            step: step.map(|step| Box::new(folder.fold_expression(*step))),
+            body: folder.fold_block(body),
+            span,
+        },
+        SemanticStatement::Expression { value, span } => SemanticStatement::Expression {
+            value: folder.fold_expression(value),
+            span,
+        },
+    }
+}
+
+pub fn fold_expression_children<F: SemanticFold + ?Sized>(
+    folder: &mut F,
+    expression: SemanticExpression,
+) -> SemanticExpression {
+    match expression {
+        SemanticExpression::IntegerLiteral { .. }
+        | SemanticExpression::NilLiteral { .. }
+        | SemanticExpression::BooleanLiteral { .. }
+        | SemanticExpression::StringLiteral { .. }
+        | SemanticExpression::NameReference { .. } => expression,
+        SemanticExpression::ListLiteral { id, elements, span } => SemanticExpression::ListLiteral {
+            id,
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_expression(element))
+                .collect(),
+            span,
+        },
+        SemanticExpression::StructLiteral {
+            id,
+            type_name,
+            fields,
+            span,
+        } => SemanticExpression::StructLiteral {
+            id,
+            type_name,
+            fields: fields
+                .into_iter()
+                .map(|field| SemanticStructLiteralField {
+                    value: folder.fold_expression(field.value),
+                    ..field
+                })
+                .collect(),
+            span,
+        },
+        SemanticExpression::FieldAccess {
+            id,
+            target,
+            field,
+            field_span,
+            span,
+        } => SemanticExpression::FieldAccess {
+            id,
+            target: fold_boxed_expression(folder, target),
+            field,
+            field_span,
+            span,
+        },
+        SemanticExpression::IndexAccess {
+            id,
+            target,
+            index,
+            span,
+        } => SemanticExpression::IndexAccess {
+            id,
+            target: fold_boxed_expression(folder, target),
+            index: fold_boxed_expression(folder, index),
+            span,
+        },
+        SemanticExpression::Call {
+            id,
+            callee,
+            type_arguments,
+            arguments,
+            span,
+        } => SemanticExpression::Call {
+            id,
+            callee: fold_boxed_expression(folder, callee),
+            type_arguments,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_expression(argument))
+                .collect(),
+            span,
+        },
+        SemanticExpression::Unary {
+            id,
+            operator,
+            expression,
+            span,
+        } => SemanticExpression::Unary {
+            id,
+            operator,
+            expression: fold_boxed_expression(folder, expression),
+            span,
+        },
+        SemanticExpression::Binary {
+            id,
+            operator,
+            left,
+            right,
+            span,
+        } => SemanticExpression::Binary {
+            id,
+            operator,
+            left: fold_boxed_expression(folder, left),
+            right: fold_boxed_expression(folder, right),
+            span,
+        },
+        SemanticExpression::If {
+            id,
+            condition,
+            then_branch,
+            else_branch,
+            span,
+        } => SemanticExpression::If {
+            id,
+            condition: fold_boxed_expression(folder, condition),
+            then_branch: fold_boxed_expression(folder, then_branch),
+            else_branch: fold_boxed_expression(folder, else_branch),
+            span,
+        },
+        SemanticExpression::Block {
+            id,
+            block,
+            value,
+            span,
+        } => SemanticExpression::Block {
+            id,
+            block: folder.fold_block(block),
+            value: fold_boxed_expression(folder, value),
+            span,
+        },
+        SemanticExpression::Match {
+            id,
+            target,
+            arms,
+            span,
+        } => SemanticExpression::Match {
+            id,
+            target: fold_boxed_expression(folder, target),
+            arms: arms
+                .into_iter()
+                .map(|arm| SemanticMatchArm {
+                    guard: arm.guard.map(|guard| folder.fold_expression(guard)),
+                    value: folder.fold_expression(arm.value),
+                    ..arm
+                })
+                .collect(),
+            span,
+        },
+        SemanticExpression::Matches {
+            id,
+            value,
+            type_name,
+            span,
+        } => SemanticExpression::Matches {
+            id,
+            value: fold_boxed_expression(folder, value),
+            type_name,
+            span,
+        },
+        SemanticExpression::StringInterpolation { id, parts, span } => {
+            SemanticExpression::StringInterpolation {
+                id,
+                parts: parts
+                    .into_iter()
+                    .map(|part| match part {
+                        SemanticStringInterpolationPart::Literal(_) => part,
+                        SemanticStringInterpolationPart::Expression(expression) => {
+                            SemanticStringInterpolationPart::Expression(
+                                folder.fold_expression(expression),
+                            )
+                        }
+                    })
+                    .collect(),
+                span,
+            }
+        }
+    }
+}
+
+// The box is reused rather than reallocated for the folded expression.
+fn fold_boxed_expression<F: SemanticFold + ?Sized>(
+    folder: &mut F,
+    mut expression: Box<SemanticExpression>,
+) -> Box<SemanticExpression> {
+    *expression = folder.fold_expression(*expression);
+    expression
+}
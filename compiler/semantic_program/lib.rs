@@ -1,4 +1,16 @@
-use compiler__source::{FileRole, Span};
+mod fold;
+mod visit;
+
+use compiler__source::{FileRole, KnownAttribute, Span};
+
+pub use fold::{
+    SemanticFold, fold_block_children, fold_declaration, fold_expression_children, fold_file,
+    fold_statement_children,
+};
+pub use visit::{
+    SemanticVisitor, visit_block_children, visit_declaration, visit_expression_children,
+    visit_file, visit_statement_children,
+};
 
 #[derive(Clone)]
 pub struct SemanticFile {
@@ -11,6 +23,7 @@ pub enum SemanticDeclaration {
     Type(SemanticTypeDeclaration),
     Constant(SemanticConstantDeclaration),
     Function(SemanticFunctionDeclaration),
+    StaticAssert(SemanticStaticAssertDeclaration),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -29,7 +42,31 @@ pub enum SemanticMemberVisibility {
 pub struct SemanticDocComment {
     pub lines: Vec<String>,
     pub span: Span,
-    pub end_line: usize,
+}
+
+/// A known attribute with the expected number of arguments. Unknown or
+/// malformed attributes are reported by syntax rules and not lowered.
+#[derive(Clone, Debug)]
+pub struct SemanticAttribute {
+    pub kind: KnownAttribute,
+    pub arguments: Vec<String>,
+    pub span: Span,
+}
+
+#[must_use]
+pub fn find_attribute(
+    attributes: &[SemanticAttribute],
+    kind: KnownAttribute,
+) -> Option<&SemanticAttribute> {
+    attributes.iter().find(|attribute| attribute.kind == kind)
+}
+
+/// The message of a `@deprecated` attribute, if the declaration has one.
+#[must_use]
+pub fn deprecation_message(attributes: &[SemanticAttribute]) -> Option<&str> {
+    find_attribute(attributes, KnownAttribute::Deprecated)
+        .and_then(|attribute| attribute.arguments.first())
+        .map(String::as_str)
 }
 
 #[derive(Clone, Debug)]
@@ -40,6 +77,7 @@ pub struct SemanticTypeDeclaration {
     pub implemented_interfaces: Vec<SemanticTypeName>,
     pub kind: SemanticTypeDeclarationKind,
     pub doc: Option<SemanticDocComment>,
+    pub attributes: Vec<SemanticAttribute>,
     pub visibility: SemanticTopLevelVisibility,
     pub span: Span,
 }
@@ -108,10 +146,18 @@ pub struct SemanticConstantDeclaration {
     pub type_name: SemanticTypeName,
     pub expression: SemanticExpression,
     pub doc: Option<SemanticDocComment>,
+    pub attributes: Vec<SemanticAttribute>,
     pub visibility: SemanticTopLevelVisibility,
     pub span: Span,
 }
 
+#[derive(Clone, Debug)]
+pub struct SemanticStaticAssertDeclaration {
+    pub condition: SemanticExpression,
+    pub message: String,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug)]
 pub struct SemanticFunctionDeclaration {
     pub name: String,
@@ -121,6 +167,7 @@ pub struct SemanticFunctionDeclaration {
     pub return_type: SemanticTypeName,
     pub body: SemanticBlock,
     pub doc: Option<SemanticDocComment>,
+    pub attributes: Vec<SemanticAttribute>,
     pub visibility: SemanticTopLevelVisibility,
     pub span: Span,
 }
@@ -165,17 +212,35 @@ pub enum SemanticStatement {
     Continue {
         span: Span,
     },
+    Defer {
+        value: SemanticExpression,
+        span: Span,
+    },
     If {
         condition: SemanticExpression,
         then_block: SemanticBlock,
         else_block: Option<SemanticBlock>,
         span: Span,
     },
+    Guard {
+        condition: SemanticExpression,
+        else_block: SemanticBlock,
+        span: Span,
+    },
     For {
         condition: Option<SemanticExpression>,
         body: SemanticBlock,
         span: Span,
     },
+    ForRange {
+        name: String,
+        name_span: Span,
+        start: SemanticExpression,
+        end: SemanticExpression,
+        step: Option<Box<SemanticExpression>>,
+        body: SemanticBlock,
+        span: Span,
+    },
     Expression {
         value: SemanticExpression,
         span: Span,
@@ -273,6 +338,20 @@ pub enum SemanticExpression {
         right: Box<SemanticExpression>,
         span: Span,
     },
+    // Both branches are `Block` expressions.
+    If {
+        id: SemanticExpressionId,
+        condition: Box<SemanticExpression>,
+        then_branch: Box<SemanticExpression>,
+        else_branch: Box<SemanticExpression>,
+        span: Span,
+    },
+    Block {
+        id: SemanticExpressionId,
+        block: SemanticBlock,
+        value: Box<SemanticExpression>,
+        span: Span,
+    },
     Match {
         id: SemanticExpressionId,
         target: Box<SemanticExpression>,
@@ -308,6 +387,11 @@ pub enum SemanticBinaryOperator {
     Multiply,
     Divide,
     Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     EqualEqual,
     NotEqual,
     LessThan,
@@ -355,6 +439,7 @@ pub struct SemanticStructLiteralField {
 #[derive(Clone, Debug)]
 pub struct SemanticMatchArm {
     pub pattern: SemanticMatchPattern,
+    pub guard: Option<SemanticExpression>,
     pub value: SemanticExpression,
     pub span: Span,
 }
@@ -371,6 +456,10 @@ pub enum SemanticMatchPattern {
         type_name: SemanticTypeName,
         span: Span,
     },
+    Literal {
+        value: SemanticMatchLiteral,
+        span: Span,
+    },
 }
 
 impl SemanticMatchPattern {
@@ -378,7 +467,15 @@ impl SemanticMatchPattern {
     pub fn span(&self) -> Span {
         match self {
             SemanticMatchPattern::Type { span, .. }
-            | SemanticMatchPattern::Binding { span, .. } => span.clone(),
+            | SemanticMatchPattern::Binding { span, .. }
+            | SemanticMatchPattern::Literal { span, .. } => *span,
         }
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SemanticMatchLiteral {
+    Integer(i64),
+    String(String),
+    Boolean(bool),
+}
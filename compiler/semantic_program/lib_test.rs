@@ -0,0 +1,144 @@
+use compiler__semantic_program::{
+    SemanticBinaryOperator, SemanticBlock, SemanticDeclaration, SemanticExpression,
+    SemanticExpressionId, SemanticFile, SemanticFold, SemanticFunctionDeclaration,
+    SemanticNameReferenceKind, SemanticStatement, SemanticStringInterpolationPart,
+    SemanticTopLevelVisibility, SemanticTypeName, SemanticVisitor, fold_expression_children,
+    fold_file, visit_expression_children, visit_file,
+};
+use compiler__source::{FileRole, Span};
+
+fn span() -> Span {
+    Span::new(0, 0)
+}
+
+fn name_reference(id: u32, name: &str) -> SemanticExpression {
+    SemanticExpression::NameReference {
+        id: SemanticExpressionId(id),
+        name: name.to_string(),
+        kind: SemanticNameReferenceKind::UserDefined,
+        span: span(),
+    }
+}
+
+// function main() { if a < b { return "${c}" } }
+fn file_with_nested_name_references() -> SemanticFile {
+    let condition = SemanticExpression::Binary {
+        id: SemanticExpressionId(2),
+        operator: SemanticBinaryOperator::LessThan,
+        left: Box::new(name_reference(0, "a")),
+        right: Box::new(name_reference(1, "b")),
+        span: span(),
+    };
+    let interpolation = SemanticExpression::StringInterpolation {
+        id: SemanticExpressionId(4),
+        parts: vec![SemanticStringInterpolationPart::Expression(name_reference(
+            3, "c",
+        ))],
+        span: span(),
+    };
+    let body = SemanticBlock {
+        statements: vec![SemanticStatement::If {
+            condition,
+            then_block: SemanticBlock {
+                statements: vec![SemanticStatement::Return {
+                    value: Some(interpolation),
+                    span: span(),
+                }],
+                span: span(),
+            },
+            else_block: None,
+            span: span(),
+        }],
+        span: span(),
+    };
+    SemanticFile {
+        role: FileRole::Library,
+        declarations: vec![SemanticDeclaration::Function(SemanticFunctionDeclaration {
+            name: "main".to_string(),
+            name_span: span(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: SemanticTypeName {
+                names: Vec::new(),
+                span: span(),
+            },
+            body,
+            doc: None,
+            attributes: Vec::new(),
+            visibility: SemanticTopLevelVisibility::Private,
+            span: span(),
+        })],
+    }
+}
+
+#[derive(Default)]
+struct NameReferenceCollector {
+    names: Vec<String>,
+}
+
+impl SemanticVisitor for NameReferenceCollector {
+    fn visit_expression(&mut self, expression: &SemanticExpression) {
+        if let SemanticExpression::NameReference { name, .. } = expression {
+            self.names.push(name.clone());
+        }
+        visit_expression_children(self, expression);
+    }
+}
+
+struct NameReferenceRenamer;
+
+impl SemanticFold for NameReferenceRenamer {
+    fn fold_expression(&mut self, expression: SemanticExpression) -> SemanticExpression {
+        match fold_expression_children(self, expression) {
+            SemanticExpression::NameReference {
+                id,
+                name,
+                kind,
+                span,
+            } => SemanticExpression::NameReference {
+                id,
+                name: name.to_uppercase(),
+                kind,
+                span,
+            },
+            expression => expression,
+        }
+    }
+}
+
+#[test]
+fn visitor_reaches_expressions_nested_in_statements_and_interpolations() {
+    let mut collector = NameReferenceCollector::default();
+    visit_file(&mut collector, &file_with_nested_name_references());
+    assert_eq!(collector.names, ["a", "b", "c"]);
+}
+
+#[test]
+fn fold_rewrites_nested_expressions_and_keeps_expression_ids() {
+    let file = fold_file(
+        &mut NameReferenceRenamer,
+        file_with_nested_name_references(),
+    );
+
+    let mut collector = NameReferenceCollector::default();
+    visit_file(&mut collector, &file);
+    assert_eq!(collector.names, ["A", "B", "C"]);
+
+    let SemanticDeclaration::Function(function_declaration) = &file.declarations[0] else {
+        panic!("expected function declaration");
+    };
+    let SemanticStatement::If { condition, .. } = &function_declaration.body.statements[0] else {
+        panic!("expected if statement");
+    };
+    let SemanticExpression::Binary { id, left, .. } = condition else {
+        panic!("expected binary condition");
+    };
+    assert_eq!(*id, SemanticExpressionId(2));
+    assert!(matches!(
+        left.as_ref(),
+        SemanticExpression::NameReference {
+            id: SemanticExpressionId(0),
+            ..
+        }
+    ));
+}
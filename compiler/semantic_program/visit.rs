@@ -0,0 +1,199 @@
+use crate::{
+    SemanticAssignTarget, SemanticBlock, SemanticDeclaration, SemanticExpression, SemanticFile,
+    SemanticStatement, SemanticStringInterpolationPart, SemanticTypeDeclarationKind,
+};
+
+// Read-only traversal of semantic blocks, statements, and expressions. Every
+// method defaults to visiting the node's children, so an implementation
+// overrides only the nodes it inspects and calls the matching
+// `visit_*_children` function to keep descending.
+pub trait SemanticVisitor {
+    fn visit_block(&mut self, block: &SemanticBlock) {
+        visit_block_children(self, block);
+    }
+
+    fn visit_statement(&mut self, statement: &SemanticStatement) {
+        visit_statement_children(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &SemanticExpression) {
+        visit_expression_children(self, expression);
+    }
+}
+
+// Declarations are visited in source order. Only constant initializers and
+// function and method bodies hold expressions.
+pub fn visit_file<V: SemanticVisitor + ?Sized>(visitor: &mut V, file: &SemanticFile) {
+    for declaration in &file.declarations {
+        visit_declaration(visitor, declaration);
+    }
+}
+
+pub fn visit_declaration<V: SemanticVisitor + ?Sized>(
+    visitor: &mut V,
+    declaration: &SemanticDeclaration,
+) {
+    match declaration {
+        SemanticDeclaration::Type(type_declaration) => {
+            if let SemanticTypeDeclarationKind::Struct { methods, .. } = &type_declaration.kind {
+                for method in methods {
+                    visitor.visit_block(&method.body);
+                }
+            }
+        }
+        SemanticDeclaration::Constant(constant_declaration) => {
+            visitor.visit_expression(&constant_declaration.expression);
+        }
+        SemanticDeclaration::Function(function_declaration) => {
+            visitor.visit_block(&function_declaration.body);
+        }
+        SemanticDeclaration::StaticAssert(static_assert_declaration) => {
+            visitor.visit_expression(&static_assert_declaration.condition);
+        }
+    }
+}
+
+pub fn visit_block_children<V: SemanticVisitor + ?Sized>(visitor: &mut V, block: &SemanticBlock) {
+    for statement in &block.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn visit_statement_children<V: SemanticVisitor + ?Sized>(
+    visitor: &mut V,
+    statement: &SemanticStatement,
+) {
+    match statement {
+        SemanticStatement::Binding { initializer, .. } => visitor.visit_expression(initializer),
+        SemanticStatement::Assign { target, value, .. } => {
+            if let SemanticAssignTarget::Index { target, index, .. } = target {
+                visitor.visit_expression(target);
+                visitor.visit_expression(index);
+            }
+            visitor.visit_expression(value);
+        }
+        SemanticStatement::Return { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        SemanticStatement::Break { .. } | SemanticStatement::Continue { .. } => {}
+        SemanticStatement::If {
+            condition,
+            then_block,
+            else_block,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(then_block);
+            if let Some(else_block) = else_block {
+                visitor.visit_block(else_block);
+            }
+        }
+        SemanticStatement::Guard {
+            condition,
+            else_block,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(else_block);
+        }
+        SemanticStatement::For {
+            condition, body, ..
+        } => {
+            if let Some(condition) = condition {
+                visitor.visit_expression(condition);
+            }
+            visitor.visit_block(body);
+        }
+        SemanticStatement::ForRange {
+            start,
+            end,
+            step,
+            body,
+            ..
+        } => {
+            visitor.visit_expression(start);
+            visitor.visit_expression(end);
+            if let Some(step) = step {
+                visitor.visit_expression(step);
+            }
+            visitor.visit_block(body);
+        }
+        SemanticStatement::Defer { value, .. } | SemanticStatement::Expression { value, .. } => {
+            visitor.visit_expression(value);
+        }
+    }
+}
+
+pub fn visit_expression_children<V: SemanticVisitor + ?Sized>(
+    visitor: &mut V,
+    expression: &SemanticExpression,
+) {
+    match expression {
+        SemanticExpression::IntegerLiteral { .. }
+        | SemanticExpression::NilLiteral { .. }
+        | SemanticExpression::BooleanLiteral { .. }
+        | SemanticExpression::StringLiteral { .. }
+        | SemanticExpression::NameReference { .. } => {}
+        SemanticExpression::ListLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        SemanticExpression::StructLiteral { fields, .. } => {
+            for field in fields {
+                visitor.visit_expression(&field.value);
+            }
+        }
+        SemanticExpression::FieldAccess { target, .. } => visitor.visit_expression(target),
+        SemanticExpression::IndexAccess { target, index, .. } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(index);
+        }
+        SemanticExpression::Call {
+            callee, arguments, ..
+        } => {
+            visitor.visit_expression(callee);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        SemanticExpression::Unary { expression, .. } => visitor.visit_expression(expression),
+        SemanticExpression::Binary { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        SemanticExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(then_branch);
+            visitor.visit_expression(else_branch);
+        }
+        SemanticExpression::Block { block, value, .. } => {
+            visitor.visit_block(block);
+            visitor.visit_expression(value);
+        }
+        SemanticExpression::Match { target, arms, .. } => {
+            visitor.visit_expression(target);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    visitor.visit_expression(guard);
+                }
+                visitor.visit_expression(&arm.value);
+            }
+        }
+        SemanticExpression::Matches { value, .. } => visitor.visit_expression(value),
+        SemanticExpression::StringInterpolation { parts, .. } => {
+            for part in parts {
+                if let SemanticStringInterpolationPart::Expression(expression) = part {
+                    visitor.visit_expression(expression);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,902 @@
+mod printing;
+
+use compiler__semantic_program::{
+    SemanticAssignTarget, SemanticAttribute, SemanticBinaryOperator, SemanticBlock,
+    SemanticConstantDeclaration, SemanticDeclaration, SemanticDocComment, SemanticEnumVariant,
+    SemanticExpression, SemanticExpressionId, SemanticFieldDeclaration, SemanticFile,
+    SemanticFunctionDeclaration, SemanticInterfaceMethodDeclaration, SemanticMatchArm,
+    SemanticMatchLiteral, SemanticMatchPattern, SemanticMemberVisibility,
+    SemanticMethodDeclaration, SemanticNameReferenceKind, SemanticParameterDeclaration,
+    SemanticStatement, SemanticStaticAssertDeclaration, SemanticStringInterpolationPart,
+    SemanticStructLiteralField, SemanticTopLevelVisibility, SemanticTypeDeclaration,
+    SemanticTypeDeclarationKind, SemanticTypeName, SemanticTypeNameSegment, SemanticTypeParameter,
+    SemanticUnaryOperator,
+};
+use compiler__source::{FileRole, KnownAttribute, Span};
+
+pub use printing::print_semantic_file;
+
+const VARIABLE_NAMES: [&str; 6] = ["count", "total", "value", "items", "label", "result"];
+const FUNCTION_NAMES: [&str; 4] = ["compute", "render", "combine", "score"];
+const METHOD_NAMES: [&str; 3] = ["area", "describe", "resize"];
+const FIELD_NAMES: [&str; 4] = ["x", "y", "name", "size"];
+const CONSTANT_NAMES: [&str; 3] = ["LIMIT", "GREETING", "ENABLED"];
+const TYPE_NAMES: [&str; 4] = ["Point", "Shape", "Entry", "Holder"];
+const BUILTIN_TYPE_NAMES: [&str; 4] = ["int64", "boolean", "string", "nil"];
+const TYPE_PARAMETER_NAMES: [&str; 2] = ["T", "U"];
+const ENUM_VARIANT_NAMES: [&str; 3] = ["Red", "Green", "Blue"];
+const BUILTIN_CALLEE_NAMES: [&str; 3] = ["print", "assert", "abort"];
+const STRING_VALUES: [&str; 5] = ["", "hello", "two words", "{braces}", "back\\slash"];
+const DOC_COMMENT_LINES: [&str; 3] = ["Returns the total.", "", "  Indented detail."];
+const BINARY_OPERATORS: [SemanticBinaryOperator; 18] = [
+    SemanticBinaryOperator::Add,
+    SemanticBinaryOperator::Subtract,
+    SemanticBinaryOperator::Multiply,
+    SemanticBinaryOperator::Divide,
+    SemanticBinaryOperator::Modulo,
+    SemanticBinaryOperator::BitwiseAnd,
+    SemanticBinaryOperator::BitwiseOr,
+    SemanticBinaryOperator::BitwiseXor,
+    SemanticBinaryOperator::ShiftLeft,
+    SemanticBinaryOperator::ShiftRight,
+    SemanticBinaryOperator::EqualEqual,
+    SemanticBinaryOperator::NotEqual,
+    SemanticBinaryOperator::LessThan,
+    SemanticBinaryOperator::LessThanOrEqual,
+    SemanticBinaryOperator::GreaterThan,
+    SemanticBinaryOperator::GreaterThanOrEqual,
+    SemanticBinaryOperator::And,
+    SemanticBinaryOperator::Or,
+];
+
+const EXPRESSION_DEPTH: u32 = 2;
+const BLOCK_DEPTH: u32 = 2;
+const GENERATED_SPAN: Span = Span::new(0, 0);
+
+// Builds a random semantic file from a seed; the same seed always builds the
+// same file. The file has the shape lowering produces, but names need not
+// resolve and operand types need not agree, so it exercises the printer and
+// parser and is raw input for fuzzing later phases. Spans are empty and
+// expression ids are numbered in pre-order, as lowering numbers them.
+#[must_use]
+pub fn generate_semantic_file(seed: u64) -> SemanticFile {
+    let mut generator = SemanticFileGenerator {
+        random_state: seed,
+        next_expression_id: 0,
+    };
+    generator.file()
+}
+
+struct SemanticFileGenerator {
+    random_state: u64,
+    next_expression_id: u32,
+}
+
+impl SemanticFileGenerator {
+    // SplitMix64, the same generator the runtime uses for `random_int`.
+    fn next_random(&mut self) -> u64 {
+        self.random_state = self.random_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.random_state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    fn index_below(&mut self, count: usize) -> usize {
+        let count = u64::try_from(count).expect("count should fit in u64");
+        usize::try_from(self.next_random() % count).expect("index should fit in usize")
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next_random() % 100 < percent
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.index_below(items.len())]
+    }
+
+    // A prefix of `names`, so generated siblings never share a name.
+    fn distinct_names(&mut self, names: &[&str], minimum: usize, maximum: usize) -> Vec<String> {
+        let count = minimum + self.index_below(maximum - minimum + 1);
+        names[..count].iter().map(ToString::to_string).collect()
+    }
+
+    fn expression_id(&mut self) -> SemanticExpressionId {
+        let id = SemanticExpressionId(self.next_expression_id);
+        self.next_expression_id += 1;
+        id
+    }
+
+    fn file(&mut self) -> SemanticFile {
+        let declaration_count = 1 + self.index_below(5);
+        SemanticFile {
+            role: FileRole::Library,
+            declarations: (0..declaration_count).map(|_| self.declaration()).collect(),
+        }
+    }
+
+    fn declaration(&mut self) -> SemanticDeclaration {
+        match self.index_below(10) {
+            0..=2 => SemanticDeclaration::Type(self.type_declaration()),
+            3 | 4 => SemanticDeclaration::Constant(self.constant_declaration()),
+            5..=8 => SemanticDeclaration::Function(self.function_declaration()),
+            _ => SemanticDeclaration::StaticAssert(SemanticStaticAssertDeclaration {
+                condition: self.expression(EXPRESSION_DEPTH),
+                message: self.pick(&STRING_VALUES).to_string(),
+                span: GENERATED_SPAN,
+            }),
+        }
+    }
+
+    fn type_declaration(&mut self) -> SemanticTypeDeclaration {
+        let name = self.pick(&TYPE_NAMES).to_string();
+        let type_parameters = self.type_parameters();
+        let (kind, implemented_interfaces) = match self.index_below(4) {
+            0 => {
+                let implemented_interfaces = if self.chance(30) {
+                    (0..=self.index_below(2))
+                        .map(|_| self.simple_type_name(&TYPE_NAMES))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                (self.struct_kind(), implemented_interfaces)
+            }
+            1 => {
+                let variants = self
+                    .distinct_names(&ENUM_VARIANT_NAMES, 1, ENUM_VARIANT_NAMES.len())
+                    .into_iter()
+                    .map(|name| SemanticEnumVariant {
+                        name,
+                        span: GENERATED_SPAN,
+                    })
+                    .collect();
+                (SemanticTypeDeclarationKind::Enum { variants }, Vec::new())
+            }
+            2 => {
+                let methods = self
+                    .distinct_names(&METHOD_NAMES, 0, 2)
+                    .into_iter()
+                    .map(|name| SemanticInterfaceMethodDeclaration {
+                        name,
+                        name_span: GENERATED_SPAN,
+                        self_span: GENERATED_SPAN,
+                        self_mutable: self.chance(30),
+                        parameters: self.parameters(),
+                        return_type: self.type_name(1),
+                        span: GENERATED_SPAN,
+                    })
+                    .collect();
+                (
+                    SemanticTypeDeclarationKind::Interface { methods },
+                    Vec::new(),
+                )
+            }
+            _ => {
+                let variants = (0..2 + self.index_below(2))
+                    .map(|_| SemanticTypeName {
+                        names: vec![self.type_name_segment(1)],
+                        span: GENERATED_SPAN,
+                    })
+                    .collect();
+                (SemanticTypeDeclarationKind::Union { variants }, Vec::new())
+            }
+        };
+        SemanticTypeDeclaration {
+            name,
+            name_span: GENERATED_SPAN,
+            type_parameters,
+            implemented_interfaces,
+            kind,
+            doc: self.doc_comment(),
+            attributes: self.attributes(false),
+            visibility: self.top_level_visibility(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn struct_kind(&mut self) -> SemanticTypeDeclarationKind {
+        let fields = self
+            .distinct_names(&FIELD_NAMES, 0, 3)
+            .into_iter()
+            .map(|name| SemanticFieldDeclaration {
+                name,
+                type_name: self.type_name(1),
+                doc: self.doc_comment(),
+                visibility: self.member_visibility(),
+                span: GENERATED_SPAN,
+            })
+            .collect();
+        let methods = self
+            .distinct_names(&METHOD_NAMES, 0, 2)
+            .into_iter()
+            .map(|name| SemanticMethodDeclaration {
+                name,
+                name_span: GENERATED_SPAN,
+                self_span: GENERATED_SPAN,
+                self_mutable: self.chance(30),
+                parameters: self.parameters(),
+                return_type: self.type_name(1),
+                body: self.block(BLOCK_DEPTH, EXPRESSION_DEPTH, false),
+                doc: self.doc_comment(),
+                visibility: self.member_visibility(),
+                span: GENERATED_SPAN,
+            })
+            .collect();
+        SemanticTypeDeclarationKind::Struct { fields, methods }
+    }
+
+    fn constant_declaration(&mut self) -> SemanticConstantDeclaration {
+        SemanticConstantDeclaration {
+            name: self.pick(&CONSTANT_NAMES).to_string(),
+            name_span: GENERATED_SPAN,
+            type_name: self.type_name(1),
+            expression: self.expression(EXPRESSION_DEPTH),
+            doc: self.doc_comment(),
+            attributes: self.attributes(false),
+            visibility: self.top_level_visibility(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn function_declaration(&mut self) -> SemanticFunctionDeclaration {
+        SemanticFunctionDeclaration {
+            name: self.pick(&FUNCTION_NAMES).to_string(),
+            name_span: GENERATED_SPAN,
+            type_parameters: self.type_parameters(),
+            parameters: self.parameters(),
+            return_type: self.type_name(1),
+            body: self.block(BLOCK_DEPTH, EXPRESSION_DEPTH, false),
+            doc: self.doc_comment(),
+            attributes: self.attributes(true),
+            visibility: self.top_level_visibility(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn type_parameters(&mut self) -> Vec<SemanticTypeParameter> {
+        if !self.chance(30) {
+            return Vec::new();
+        }
+        self.distinct_names(&TYPE_PARAMETER_NAMES, 1, TYPE_PARAMETER_NAMES.len())
+            .into_iter()
+            .map(|name| SemanticTypeParameter {
+                name,
+                constraint: self.chance(40).then(|| self.simple_type_name(&TYPE_NAMES)),
+                span: GENERATED_SPAN,
+            })
+            .collect()
+    }
+
+    fn parameters(&mut self) -> Vec<SemanticParameterDeclaration> {
+        self.distinct_names(&VARIABLE_NAMES, 0, 3)
+            .into_iter()
+            .map(|name| SemanticParameterDeclaration {
+                name,
+                name_span: GENERATED_SPAN,
+                mutable: self.chance(20),
+                type_name: self.type_name(1),
+                span: GENERATED_SPAN,
+            })
+            .collect()
+    }
+
+    fn doc_comment(&mut self) -> Option<SemanticDocComment> {
+        if !self.chance(20) {
+            return None;
+        }
+        let lines = (0..=self.index_below(2))
+            .map(|_| self.pick(&DOC_COMMENT_LINES).to_string())
+            .collect();
+        Some(SemanticDocComment {
+            lines,
+            span: GENERATED_SPAN,
+        })
+    }
+
+    fn attributes(&mut self, is_function: bool) -> Vec<SemanticAttribute> {
+        let mut attributes = Vec::new();
+        if is_function && self.chance(15) {
+            attributes.push(SemanticAttribute {
+                kind: self.pick(&[KnownAttribute::Test, KnownAttribute::Bench]),
+                arguments: Vec::new(),
+                span: GENERATED_SPAN,
+            });
+        }
+        if self.chance(15) {
+            attributes.push(SemanticAttribute {
+                kind: KnownAttribute::Deprecated,
+                arguments: vec![self.pick(&STRING_VALUES).to_string()],
+                span: GENERATED_SPAN,
+            });
+        }
+        attributes
+    }
+
+    fn top_level_visibility(&mut self) -> SemanticTopLevelVisibility {
+        if self.chance(50) {
+            SemanticTopLevelVisibility::Visible
+        } else {
+            SemanticTopLevelVisibility::Private
+        }
+    }
+
+    fn member_visibility(&mut self) -> SemanticMemberVisibility {
+        if self.chance(50) {
+            SemanticMemberVisibility::Public
+        } else {
+            SemanticMemberVisibility::Private
+        }
+    }
+
+    // Function types are never union members: a function type's return type
+    // would absorb the rest of the union.
+    fn type_name(&mut self, depth: u32) -> SemanticTypeName {
+        if depth > 0 && self.chance(10) {
+            let mut type_arguments: Vec<SemanticTypeName> = (0..self.index_below(3))
+                .map(|_| self.type_name(depth - 1))
+                .collect();
+            type_arguments.push(self.type_name(depth - 1));
+            return SemanticTypeName {
+                names: vec![SemanticTypeNameSegment {
+                    name: "function".to_string(),
+                    type_arguments,
+                    span: GENERATED_SPAN,
+                }],
+                span: GENERATED_SPAN,
+            };
+        }
+        let segment_count = if self.chance(20) { 2 } else { 1 };
+        SemanticTypeName {
+            names: (0..segment_count)
+                .map(|_| self.type_name_segment(depth))
+                .collect(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn type_name_segment(&mut self, depth: u32) -> SemanticTypeNameSegment {
+        if self.chance(50) {
+            return self.simple_type_name_segment(&BUILTIN_TYPE_NAMES);
+        }
+        let type_arguments = if depth > 0 && self.chance(30) {
+            (0..=self.index_below(2))
+                .map(|_| self.type_name(depth - 1))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        SemanticTypeNameSegment {
+            name: self.pick(&TYPE_NAMES).to_string(),
+            type_arguments,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn simple_type_name(&mut self, names: &[&str]) -> SemanticTypeName {
+        SemanticTypeName {
+            names: vec![self.simple_type_name_segment(names)],
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn simple_type_name_segment(&mut self, names: &[&str]) -> SemanticTypeNameSegment {
+        SemanticTypeNameSegment {
+            name: self.pick(names).to_string(),
+            type_arguments: Vec::new(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    // Matches and match patterns take a single segment without type arguments.
+    fn pattern_type_name(&mut self) -> SemanticTypeName {
+        if self.chance(50) {
+            self.simple_type_name(&BUILTIN_TYPE_NAMES)
+        } else {
+            self.simple_type_name(&TYPE_NAMES)
+        }
+    }
+
+    // Statements nest up to `block_depth` blocks deeper, and their expressions
+    // up to `expression_depth` levels. A block expression's value follows its
+    // statements.
+    fn block(
+        &mut self,
+        block_depth: u32,
+        expression_depth: u32,
+        followed_by_value: bool,
+    ) -> SemanticBlock {
+        let statement_count = self.index_below(5);
+        SemanticBlock {
+            statements: (0..statement_count)
+                .map(|index| {
+                    let ends_block = !followed_by_value && index + 1 == statement_count;
+                    self.statement(block_depth, expression_depth, ends_block)
+                })
+                .collect(),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    // A bare `return` ends its block: inside parentheses no terminator follows
+    // it, so the next statement would be read as its value.
+    fn statement(
+        &mut self,
+        block_depth: u32,
+        expression_depth: u32,
+        ends_block: bool,
+    ) -> SemanticStatement {
+        let choice = if block_depth == 0 {
+            self.index_below(6)
+        } else {
+            self.index_below(10)
+        };
+        match choice {
+            0 => SemanticStatement::Binding {
+                name: self.pick(&VARIABLE_NAMES).to_string(),
+                name_span: GENERATED_SPAN,
+                mutable: self.chance(30),
+                type_name: self.chance(30).then(|| self.type_name(1)),
+                initializer: self.expression(expression_depth),
+                span: GENERATED_SPAN,
+            },
+            1 => {
+                let target = if self.chance(50) {
+                    SemanticAssignTarget::Name {
+                        name: self.pick(&VARIABLE_NAMES).to_string(),
+                        name_span: GENERATED_SPAN,
+                        span: GENERATED_SPAN,
+                    }
+                } else {
+                    SemanticAssignTarget::Index {
+                        target: Box::new(self.assignable_expression()),
+                        index: Box::new(self.expression(1)),
+                        span: GENERATED_SPAN,
+                    }
+                };
+                SemanticStatement::Assign {
+                    target,
+                    value: self.expression(expression_depth),
+                    span: GENERATED_SPAN,
+                }
+            }
+            2 => SemanticStatement::Return {
+                value: if ends_block && self.chance(30) {
+                    None
+                } else {
+                    Some(self.expression(expression_depth))
+                },
+                span: GENERATED_SPAN,
+            },
+            3 => {
+                if self.chance(50) {
+                    SemanticStatement::Break {
+                        span: GENERATED_SPAN,
+                    }
+                } else {
+                    SemanticStatement::Continue {
+                        span: GENERATED_SPAN,
+                    }
+                }
+            }
+            4 => SemanticStatement::Defer {
+                value: self.call_statement_expression(expression_depth.min(1)),
+                span: GENERATED_SPAN,
+            },
+            5 => SemanticStatement::Expression {
+                value: self.call_statement_expression(expression_depth),
+                span: GENERATED_SPAN,
+            },
+            6 => SemanticStatement::If {
+                condition: self.expression(expression_depth),
+                then_block: self.block(block_depth - 1, expression_depth, false),
+                else_block: self
+                    .chance(50)
+                    .then(|| self.block(block_depth - 1, expression_depth, false)),
+                span: GENERATED_SPAN,
+            },
+            7 => SemanticStatement::Guard {
+                condition: self.expression(expression_depth),
+                else_block: self.block(block_depth - 1, expression_depth, false),
+                span: GENERATED_SPAN,
+            },
+            8 => SemanticStatement::For {
+                condition: self.chance(70).then(|| self.expression(expression_depth)),
+                body: self.block(block_depth - 1, expression_depth, false),
+                span: GENERATED_SPAN,
+            },
+            _ => SemanticStatement::ForRange {
+                name: self.pick(&VARIABLE_NAMES).to_string(),
+                name_span: GENERATED_SPAN,
+                start: self.expression(expression_depth.min(1)),
+                end: self.expression(expression_depth.min(1)),
+                step: self
+                    .chance(30)
+                    .then(|| Box::new(self.expression(expression_depth.min(1)))),
+                body: self.block(block_depth - 1, expression_depth, false),
+                span: GENERATED_SPAN,
+            },
+        }
+    }
+
+    // An assignment target is a postfix chain that starts with a name.
+    fn assignable_expression(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        if self.chance(50) {
+            return SemanticExpression::FieldAccess {
+                id,
+                target: Box::new(self.variable_reference()),
+                field: self.pick(&FIELD_NAMES).to_string(),
+                field_span: GENERATED_SPAN,
+                span: GENERATED_SPAN,
+            };
+        }
+        SemanticExpression::NameReference {
+            id,
+            name: self.pick(&VARIABLE_NAMES).to_string(),
+            kind: SemanticNameReferenceKind::UserDefined,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    // Expression statements must start with a name or keyword; anything else
+    // on a new line continues the previous statement.
+    fn call_statement_expression(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        let callee = match self.index_below(3) {
+            0 => self.builtin_reference(),
+            1 => self.assignable_expression(),
+            _ => self.function_reference(),
+        };
+        SemanticExpression::Call {
+            id,
+            callee: Box::new(callee),
+            type_arguments: Vec::new(),
+            arguments: self.arguments(depth),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn arguments(&mut self, depth: u32) -> Vec<SemanticExpression> {
+        (0..self.index_below(3))
+            .map(|_| self.expression(depth))
+            .collect()
+    }
+
+    fn expression(&mut self, depth: u32) -> SemanticExpression {
+        if depth == 0 {
+            return self.atom();
+        }
+        match self.index_below(18) {
+            0 => {
+                let id = self.expression_id();
+                SemanticExpression::ListLiteral {
+                    id,
+                    elements: (0..self.index_below(4))
+                        .map(|_| self.expression(depth - 1))
+                        .collect(),
+                    span: GENERATED_SPAN,
+                }
+            }
+            1 => self.struct_literal(depth),
+            2 => {
+                let id = self.expression_id();
+                SemanticExpression::FieldAccess {
+                    id,
+                    target: Box::new(self.expression(depth - 1)),
+                    field: self.pick(&FIELD_NAMES).to_string(),
+                    field_span: GENERATED_SPAN,
+                    span: GENERATED_SPAN,
+                }
+            }
+            3 => {
+                let id = self.expression_id();
+                SemanticExpression::IndexAccess {
+                    id,
+                    target: Box::new(self.expression(depth - 1)),
+                    index: Box::new(self.expression(depth - 1)),
+                    span: GENERATED_SPAN,
+                }
+            }
+            4 | 5 => self.call(depth),
+            6 => {
+                let id = self.expression_id();
+                SemanticExpression::Unary {
+                    id,
+                    operator: if self.chance(50) {
+                        SemanticUnaryOperator::Not
+                    } else {
+                        SemanticUnaryOperator::Negate
+                    },
+                    expression: Box::new(self.expression(depth - 1)),
+                    span: GENERATED_SPAN,
+                }
+            }
+            7..=9 => {
+                let id = self.expression_id();
+                SemanticExpression::Binary {
+                    id,
+                    operator: self.pick(&BINARY_OPERATORS),
+                    left: Box::new(self.expression(depth - 1)),
+                    right: Box::new(self.expression(depth - 1)),
+                    span: GENERATED_SPAN,
+                }
+            }
+            10 => self.if_expression(depth),
+            11 => self.block_expression(depth),
+            12 => self.match_expression(depth),
+            13 => {
+                let id = self.expression_id();
+                SemanticExpression::Matches {
+                    id,
+                    value: Box::new(self.expression(depth - 1)),
+                    type_name: self.pattern_type_name(),
+                    span: GENERATED_SPAN,
+                }
+            }
+            14 => self.string_interpolation(),
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        match self.index_below(7) {
+            0 => SemanticExpression::IntegerLiteral {
+                id,
+                value: i64::try_from(self.next_random() % 1000).expect("value should fit in i64"),
+                span: GENERATED_SPAN,
+            },
+            1 => SemanticExpression::BooleanLiteral {
+                id,
+                value: self.chance(50),
+                span: GENERATED_SPAN,
+            },
+            2 => SemanticExpression::StringLiteral {
+                id,
+                value: self.pick(&STRING_VALUES).to_string(),
+                span: GENERATED_SPAN,
+            },
+            3 => SemanticExpression::NilLiteral {
+                id,
+                span: GENERATED_SPAN,
+            },
+            4 => SemanticExpression::NameReference {
+                id,
+                name: self.pick(&CONSTANT_NAMES).to_string(),
+                kind: SemanticNameReferenceKind::UserDefined,
+                span: GENERATED_SPAN,
+            },
+            _ => SemanticExpression::NameReference {
+                id,
+                name: self.pick(&VARIABLE_NAMES).to_string(),
+                kind: SemanticNameReferenceKind::UserDefined,
+                span: GENERATED_SPAN,
+            },
+        }
+    }
+
+    fn variable_reference(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        SemanticExpression::NameReference {
+            id,
+            name: self.pick(&VARIABLE_NAMES).to_string(),
+            kind: SemanticNameReferenceKind::UserDefined,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn function_reference(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        SemanticExpression::NameReference {
+            id,
+            name: self.pick(&FUNCTION_NAMES).to_string(),
+            kind: SemanticNameReferenceKind::UserDefined,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn builtin_reference(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        SemanticExpression::NameReference {
+            id,
+            name: self.pick(&BUILTIN_CALLEE_NAMES).to_string(),
+            kind: SemanticNameReferenceKind::Builtin,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn call(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        let callee = match self.index_below(4) {
+            0 => self.builtin_reference(),
+            1 => self.expression(depth - 1),
+            _ => self.function_reference(),
+        };
+        let is_builtin = matches!(
+            callee,
+            SemanticExpression::NameReference {
+                kind: SemanticNameReferenceKind::Builtin,
+                ..
+            }
+        );
+        let type_arguments = if !is_builtin && self.chance(20) {
+            (0..=self.index_below(2))
+                .map(|_| self.type_name(1))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        SemanticExpression::Call {
+            id,
+            callee: Box::new(callee),
+            type_arguments,
+            arguments: self.arguments(depth - 1),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn struct_literal(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        let fields = self
+            .distinct_names(&FIELD_NAMES, 0, 2)
+            .into_iter()
+            .map(|name| SemanticStructLiteralField {
+                name,
+                name_span: GENERATED_SPAN,
+                value: self.expression(depth - 1),
+                span: GENERATED_SPAN,
+            })
+            .collect();
+        let type_arguments = if self.chance(20) {
+            vec![self.simple_type_name(&BUILTIN_TYPE_NAMES)]
+        } else {
+            Vec::new()
+        };
+        SemanticExpression::StructLiteral {
+            id,
+            type_name: SemanticTypeName {
+                names: vec![SemanticTypeNameSegment {
+                    name: self.pick(&TYPE_NAMES).to_string(),
+                    type_arguments,
+                    span: GENERATED_SPAN,
+                }],
+                span: GENERATED_SPAN,
+            },
+            fields,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn if_expression(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        SemanticExpression::If {
+            id,
+            condition: Box::new(self.expression(depth - 1)),
+            then_branch: Box::new(self.block_expression(depth)),
+            else_branch: Box::new(self.block_expression(depth)),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn block_expression(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        SemanticExpression::Block {
+            id,
+            block: self.block(0, depth - 1, true),
+            value: Box::new(self.block_value(depth - 1)),
+            span: GENERATED_SPAN,
+        }
+    }
+
+    // A block's value sits on its own line after the block's statements, so
+    // like an expression statement it must not start with `(`, `[`, `-`, or
+    // `{`.
+    fn block_value(&mut self, depth: u32) -> SemanticExpression {
+        if depth == 0 {
+            return self.atom();
+        }
+        match self.index_below(5) {
+            0 => self.call_statement_expression(depth - 1),
+            1 => {
+                let id = self.expression_id();
+                SemanticExpression::Binary {
+                    id,
+                    operator: self.pick(&BINARY_OPERATORS),
+                    left: Box::new(self.atom()),
+                    right: Box::new(self.expression(depth - 1)),
+                    span: GENERATED_SPAN,
+                }
+            }
+            2 => self.if_expression(depth),
+            3 => self.struct_literal(depth),
+            _ => self.atom(),
+        }
+    }
+
+    fn match_expression(&mut self, depth: u32) -> SemanticExpression {
+        let id = self.expression_id();
+        let target = self.expression(depth - 1);
+        let arms = (0..=self.index_below(3))
+            .map(|_| {
+                let pattern = match self.index_below(3) {
+                    0 => SemanticMatchPattern::Type {
+                        type_name: self.pattern_type_name(),
+                        span: GENERATED_SPAN,
+                    },
+                    1 => SemanticMatchPattern::Binding {
+                        name: self.pick(&VARIABLE_NAMES).to_string(),
+                        name_span: GENERATED_SPAN,
+                        type_name: self.pattern_type_name(),
+                        span: GENERATED_SPAN,
+                    },
+                    _ => SemanticMatchPattern::Literal {
+                        value: self.match_literal(),
+                        span: GENERATED_SPAN,
+                    },
+                };
+                SemanticMatchArm {
+                    pattern,
+                    guard: self.chance(20).then(|| self.expression(depth - 1)),
+                    value: self.expression(depth - 1),
+                    span: GENERATED_SPAN,
+                }
+            })
+            .collect();
+        SemanticExpression::Match {
+            id,
+            target: Box::new(target),
+            arms,
+            span: GENERATED_SPAN,
+        }
+    }
+
+    fn match_literal(&mut self) -> SemanticMatchLiteral {
+        match self.index_below(3) {
+            0 => SemanticMatchLiteral::Integer(
+                i64::try_from(self.next_random() % 200).expect("value should fit in i64") - 100,
+            ),
+            1 => SemanticMatchLiteral::String(self.pick(&STRING_VALUES).to_string()),
+            _ => SemanticMatchLiteral::Boolean(self.chance(50)),
+        }
+    }
+
+    // Literal parts are never empty or adjacent, as lowering leaves them, and
+    // interpolated expressions hold no strings or braces of their own.
+    fn string_interpolation(&mut self) -> SemanticExpression {
+        let id = self.expression_id();
+        let mut parts = Vec::new();
+        for index in 0..=self.index_below(2) {
+            if index > 0 || self.chance(50) {
+                parts.push(SemanticStringInterpolationPart::Literal(
+                    self.pick(&["total: ", " and ", "{"]).to_string(),
+                ));
+            }
+            let expression = if self.chance(50) {
+                self.variable_reference()
+            } else {
+                let id = self.expression_id();
+                SemanticExpression::Binary {
+                    id,
+                    operator: SemanticBinaryOperator::Add,
+                    left: Box::new(self.variable_reference()),
+                    right: Box::new(SemanticExpression::IntegerLiteral {
+                        id: self.expression_id(),
+                        value: 1,
+                        span: GENERATED_SPAN,
+                    }),
+                    span: GENERATED_SPAN,
+                }
+            };
+            parts.push(SemanticStringInterpolationPart::Expression(expression));
+        }
+        if self.chance(50) {
+            parts.push(SemanticStringInterpolationPart::Literal("!".to_string()));
+        }
+        SemanticExpression::StringInterpolation {
+            id,
+            parts,
+            span: GENERATED_SPAN,
+        }
+    }
+}
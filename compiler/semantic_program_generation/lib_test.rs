@@ -0,0 +1,59 @@
+use compiler__parsing::parse_file;
+use compiler__semantic_lowering::lower_parsed_file;
+use compiler__semantic_program::SemanticFile;
+use compiler__semantic_program_generation::{generate_semantic_file, print_semantic_file};
+
+const SEED_COUNT: u64 = 500;
+
+#[test]
+fn printed_programs_parse_and_lower_to_the_same_program() {
+    for seed in 0..SEED_COUNT {
+        let file = generate_semantic_file(seed);
+        let source = print_semantic_file(&file);
+        let parsed = parse_file(&source, file.role);
+        assert!(
+            parsed.diagnostics.is_empty(),
+            "seed {seed} printed source that does not parse: {:?}\n{source}",
+            parsed.diagnostics
+        );
+        let lowered = lower_parsed_file(&parsed.value);
+        assert!(
+            lowered.diagnostics.is_empty(),
+            "seed {seed} printed source that does not lower: {:?}\n{source}",
+            lowered.diagnostics
+        );
+        assert_eq!(
+            structure_without_spans(&lowered.value),
+            structure_without_spans(&file),
+            "seed {seed} changed across a print, parse, and lower round trip:\n{source}"
+        );
+    }
+}
+
+// Generated files have no source positions, so the round trip compares
+// everything but spans.
+fn structure_without_spans(file: &SemanticFile) -> String {
+    let structure = format!("{:?} {:?}", file.role, file.declarations);
+    let mut result = String::with_capacity(structure.len());
+    let mut rest = structure.as_str();
+    while let Some(start) = rest.find("Span { ") {
+        let end = start + rest[start..].find('}').expect("span should be closed") + 1;
+        result.push_str(&rest[..start]);
+        result.push_str("Span");
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[test]
+fn generation_is_deterministic_per_seed() {
+    assert_eq!(
+        print_semantic_file(&generate_semantic_file(7)),
+        print_semantic_file(&generate_semantic_file(7))
+    );
+    assert_ne!(
+        print_semantic_file(&generate_semantic_file(7)),
+        print_semantic_file(&generate_semantic_file(8))
+    );
+}
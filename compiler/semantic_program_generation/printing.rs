@@ -0,0 +1,767 @@
+use compiler__semantic_program::{
+    SemanticAssignTarget, SemanticAttribute, SemanticBinaryOperator, SemanticBlock,
+    SemanticDeclaration, SemanticDocComment, SemanticExpression, SemanticFile,
+    SemanticMatchLiteral, SemanticMatchPattern, SemanticMemberVisibility,
+    SemanticParameterDeclaration, SemanticStatement, SemanticStringInterpolationPart,
+    SemanticTopLevelVisibility, SemanticTypeDeclaration, SemanticTypeDeclarationKind,
+    SemanticTypeName, SemanticTypeNameSegment, SemanticTypeParameter, SemanticUnaryOperator,
+    SemanticVisitor, visit_expression_children,
+};
+
+const LOWEST_PRECEDENCE: u8 = 0;
+const COMPARISON_PRECEDENCE: u8 = 4;
+const UNARY_PRECEDENCE: u8 = 11;
+const POSTFIX_PRECEDENCE: u8 = 12;
+
+// Prints a semantic file as source that parses and lowers back to the same
+// file. Layout is fixed (four-space indentation, one statement per line, a
+// blank line between declarations) and parentheses appear only where the
+// grammar needs them, so printing a file, then parsing, lowering, and printing
+// it again gives the same text. Integer literals must be non-negative, as
+// lowering leaves them.
+#[must_use]
+pub fn print_semantic_file(file: &SemanticFile) -> String {
+    let mut printer = SourcePrinter {
+        output: String::new(),
+        indentation: 0,
+    };
+    for (index, declaration) in file.declarations.iter().enumerate() {
+        if index > 0 {
+            printer.output.push('\n');
+        }
+        printer.write_declaration(declaration);
+    }
+    printer.output
+}
+
+struct SourcePrinter {
+    output: String,
+    indentation: usize,
+}
+
+impl SourcePrinter {
+    fn start_line(&mut self) {
+        for _ in 0..self.indentation {
+            self.output.push_str("    ");
+        }
+    }
+
+    fn write_declaration(&mut self, declaration: &SemanticDeclaration) {
+        match declaration {
+            SemanticDeclaration::Type(type_declaration) => {
+                self.write_type_declaration(type_declaration);
+            }
+            SemanticDeclaration::Constant(constant_declaration) => {
+                self.write_doc_comment(constant_declaration.doc.as_ref());
+                self.write_attributes(&constant_declaration.attributes);
+                self.write_top_level_visibility(constant_declaration.visibility);
+                self.output.push_str(&constant_declaration.name);
+                self.output.push_str(": ");
+                self.output
+                    .push_str(&type_name_text(&constant_declaration.type_name));
+                self.output.push_str(" := ");
+                self.write_expression(&constant_declaration.expression, LOWEST_PRECEDENCE);
+            }
+            SemanticDeclaration::Function(function_declaration) => {
+                self.write_doc_comment(function_declaration.doc.as_ref());
+                self.write_attributes(&function_declaration.attributes);
+                self.write_top_level_visibility(function_declaration.visibility);
+                self.output.push_str("function ");
+                self.output.push_str(&function_declaration.name);
+                self.output
+                    .push_str(&type_parameters_text(&function_declaration.type_parameters));
+                self.output.push('(');
+                self.output
+                    .push_str(&parameters_text(&function_declaration.parameters));
+                self.output.push_str(") -> ");
+                self.output
+                    .push_str(&type_name_text(&function_declaration.return_type));
+                self.output.push(' ');
+                self.write_block(&function_declaration.body, None);
+            }
+            SemanticDeclaration::StaticAssert(static_assert_declaration) => {
+                self.output.push_str("static_assert(");
+                self.write_expression(&static_assert_declaration.condition, LOWEST_PRECEDENCE);
+                self.output.push_str(", ");
+                self.output
+                    .push_str(&string_literal_text(&static_assert_declaration.message));
+                self.output.push(')');
+            }
+        }
+        self.output.push('\n');
+    }
+
+    fn write_type_declaration(&mut self, type_declaration: &SemanticTypeDeclaration) {
+        self.write_doc_comment(type_declaration.doc.as_ref());
+        self.write_attributes(&type_declaration.attributes);
+        self.write_top_level_visibility(type_declaration.visibility);
+        self.output.push_str("type ");
+        self.output.push_str(&type_declaration.name);
+        self.output
+            .push_str(&type_parameters_text(&type_declaration.type_parameters));
+        if !type_declaration.implemented_interfaces.is_empty() {
+            self.output.push_str(" implements ");
+            self.output.push_str(&comma_separated(
+                type_declaration
+                    .implemented_interfaces
+                    .iter()
+                    .map(type_name_text),
+            ));
+        }
+        self.output.push_str(" :: ");
+        match &type_declaration.kind {
+            SemanticTypeDeclarationKind::Struct { fields, methods } => {
+                self.output.push_str("struct {");
+                if fields.is_empty() && methods.is_empty() {
+                    self.output.push('}');
+                    return;
+                }
+                self.output.push('\n');
+                self.indentation += 1;
+                for field in fields {
+                    self.write_doc_comment(field.doc.as_ref());
+                    self.start_line();
+                    self.output
+                        .push_str(member_visibility_prefix(field.visibility));
+                    self.output.push_str(&field.name);
+                    self.output.push_str(": ");
+                    self.output.push_str(&type_name_text(&field.type_name));
+                    self.output.push_str(",\n");
+                }
+                for method in methods {
+                    self.write_doc_comment(method.doc.as_ref());
+                    self.start_line();
+                    self.output
+                        .push_str(member_visibility_prefix(method.visibility));
+                    self.output.push_str("function ");
+                    self.output.push_str(&method.name);
+                    self.output.push('(');
+                    self.output.push_str(&method_parameters_text(
+                        method.self_mutable,
+                        &method.parameters,
+                    ));
+                    self.output.push_str(") -> ");
+                    self.output.push_str(&type_name_text(&method.return_type));
+                    self.output.push(' ');
+                    self.write_block(&method.body, None);
+                    self.output.push_str(",\n");
+                }
+                self.indentation -= 1;
+                self.output.push('}');
+            }
+            SemanticTypeDeclarationKind::Enum { variants } => {
+                self.output.push_str("enum {\n");
+                for variant in variants {
+                    self.output.push_str("    ");
+                    self.output.push_str(&variant.name);
+                    self.output.push_str(",\n");
+                }
+                self.output.push('}');
+            }
+            SemanticTypeDeclarationKind::Interface { methods } => {
+                self.output.push_str("interface {");
+                if methods.is_empty() {
+                    self.output.push('}');
+                    return;
+                }
+                self.output.push('\n');
+                for method in methods {
+                    self.output.push_str("    function ");
+                    self.output.push_str(&method.name);
+                    self.output.push('(');
+                    self.output.push_str(&method_parameters_text(
+                        method.self_mutable,
+                        &method.parameters,
+                    ));
+                    self.output.push_str(") -> ");
+                    self.output.push_str(&type_name_text(&method.return_type));
+                    self.output.push_str(",\n");
+                }
+                self.output.push('}');
+            }
+            SemanticTypeDeclarationKind::Union { variants } => {
+                let variants: Vec<_> = variants.iter().map(type_name_text).collect();
+                self.output.push_str(&variants.join(" | "));
+            }
+        }
+    }
+
+    fn write_doc_comment(&mut self, doc: Option<&SemanticDocComment>) {
+        for line in doc.iter().flat_map(|doc| &doc.lines) {
+            self.start_line();
+            self.output.push_str("///");
+            if !line.is_empty() {
+                self.output.push(' ');
+                self.output.push_str(line);
+            }
+            self.output.push('\n');
+        }
+    }
+
+    fn write_attributes(&mut self, attributes: &[SemanticAttribute]) {
+        for attribute in attributes {
+            self.output.push('@');
+            self.output.push_str(attribute.kind.name());
+            if !attribute.arguments.is_empty() {
+                self.output.push('(');
+                self.output.push_str(&comma_separated(
+                    attribute
+                        .arguments
+                        .iter()
+                        .map(|argument| string_literal_text(argument)),
+                ));
+                self.output.push(')');
+            }
+            self.output.push('\n');
+        }
+    }
+
+    fn write_top_level_visibility(&mut self, visibility: SemanticTopLevelVisibility) {
+        if visibility == SemanticTopLevelVisibility::Visible {
+            self.output.push_str("visible ");
+        }
+    }
+
+    // A block's value is printed as its last line, which is how the parser
+    // tells a block expression's value apart from its statements.
+    fn write_block(&mut self, block: &SemanticBlock, value: Option<&SemanticExpression>) {
+        if block.statements.is_empty() && value.is_none() {
+            self.output.push_str("{}");
+            return;
+        }
+        self.output.push_str("{\n");
+        self.indentation += 1;
+        for statement in &block.statements {
+            self.write_statement(statement);
+        }
+        if let Some(value) = value {
+            self.start_line();
+            self.write_expression(value, LOWEST_PRECEDENCE);
+            self.output.push('\n');
+        }
+        self.indentation -= 1;
+        self.start_line();
+        self.output.push('}');
+    }
+
+    // If branches are always printed as blocks, whether or not the branch is a
+    // block expression.
+    fn write_branch(&mut self, branch: &SemanticExpression) {
+        if let SemanticExpression::Block { block, value, .. } = branch {
+            self.write_block(block, Some(value));
+        } else {
+            self.output.push_str("{\n");
+            self.indentation += 1;
+            self.start_line();
+            self.write_expression(branch, LOWEST_PRECEDENCE);
+            self.output.push('\n');
+            self.indentation -= 1;
+            self.start_line();
+            self.output.push('}');
+        }
+    }
+
+    fn write_statement(&mut self, statement: &SemanticStatement) {
+        self.start_line();
+        match statement {
+            SemanticStatement::Binding {
+                name,
+                mutable,
+                type_name,
+                initializer,
+                ..
+            } => {
+                if *mutable {
+                    self.output.push_str("mut ");
+                }
+                self.output.push_str(name);
+                if let Some(type_name) = type_name {
+                    self.output.push_str(": ");
+                    self.output.push_str(&type_name_text(type_name));
+                }
+                self.output.push_str(" := ");
+                self.write_expression(initializer, LOWEST_PRECEDENCE);
+            }
+            SemanticStatement::Assign { target, value, .. } => {
+                match target {
+                    SemanticAssignTarget::Name { name, .. } => self.output.push_str(name),
+                    SemanticAssignTarget::Index { target, index, .. } => {
+                        self.write_postfix_target(target, true);
+                        self.output.push('[');
+                        self.write_expression(index, LOWEST_PRECEDENCE);
+                        self.output.push(']');
+                    }
+                }
+                self.output.push_str(" = ");
+                self.write_expression(value, LOWEST_PRECEDENCE);
+            }
+            SemanticStatement::Return { value, .. } => {
+                self.output.push_str("return");
+                if let Some(value) = value {
+                    self.output.push(' ');
+                    self.write_expression(value, LOWEST_PRECEDENCE);
+                }
+            }
+            SemanticStatement::Break { .. } => self.output.push_str("break"),
+            SemanticStatement::Continue { .. } => self.output.push_str("continue"),
+            SemanticStatement::Defer { value, .. } => {
+                self.output.push_str("defer ");
+                self.write_expression(value, LOWEST_PRECEDENCE);
+            }
+            SemanticStatement::If {
+                condition,
+                then_block,
+                else_block,
+                ..
+            } => {
+                self.output.push_str("if ");
+                self.write_expression_before_block(condition);
+                self.output.push(' ');
+                self.write_block(then_block, None);
+                if let Some(else_block) = else_block {
+                    self.output.push_str(" else ");
+                    self.write_block(else_block, None);
+                }
+            }
+            SemanticStatement::Guard {
+                condition,
+                else_block,
+                ..
+            } => {
+                self.output.push_str("guard ");
+                self.write_expression_before_block(condition);
+                self.output.push_str(" else ");
+                self.write_block(else_block, None);
+            }
+            SemanticStatement::For {
+                condition, body, ..
+            } => {
+                self.output.push_str("for ");
+                if let Some(condition) = condition {
+                    self.write_expression_before_block(condition);
+                    self.output.push(' ');
+                }
+                self.write_block(body, None);
+            }
+            SemanticStatement::ForRange {
+                name,
+                start,
+                end,
+                step,
+                body,
+                ..
+            } => {
+                self.output.push_str("for ");
+                self.output.push_str(name);
+                self.output.push_str(" in ");
+                self.write_expression_before_block(start);
+                self.output.push_str("..");
+                self.write_expression_before_block(end);
+                if let Some(step) = step {
+                    self.output.push_str(" step ");
+                    self.write_expression_before_block(step);
+                }
+                self.output.push(' ');
+                self.write_block(body, None);
+            }
+            SemanticStatement::Expression { value, .. } => {
+                self.write_expression(value, LOWEST_PRECEDENCE);
+            }
+        }
+        self.output.push('\n');
+    }
+
+    // Conditions, range bounds, and match targets are followed by `{`, so the
+    // parser reads no block expressions there and takes a capitalized name
+    // followed by `{` as a struct literal. Any expression that could hold a
+    // `{` or start a struct literal is parenthesized.
+    fn write_expression_before_block(&mut self, expression: &SemanticExpression) {
+        let mut finder = BraceFinder { found: false };
+        finder.visit_expression(expression);
+        if finder.found {
+            self.output.push('(');
+            self.write_expression(expression, LOWEST_PRECEDENCE);
+            self.output.push(')');
+        } else {
+            self.write_expression(expression, LOWEST_PRECEDENCE);
+        }
+    }
+
+    // A capitalized name directly followed by `[` starts a struct literal, so
+    // such a name is parenthesized when it is indexed or given type arguments.
+    fn write_postfix_target(&mut self, target: &SemanticExpression, followed_by_bracket: bool) {
+        let needs_parentheses = precedence(target) < POSTFIX_PRECEDENCE
+            || (followed_by_bracket && is_capitalized_name_reference(target));
+        self.write_parenthesized_if(needs_parentheses, target);
+    }
+
+    fn write_parenthesized_if(&mut self, needs_parentheses: bool, expression: &SemanticExpression) {
+        if needs_parentheses {
+            self.output.push('(');
+            self.write_expression(expression, LOWEST_PRECEDENCE);
+            self.output.push(')');
+        } else {
+            self.write_expression(expression, LOWEST_PRECEDENCE);
+        }
+    }
+
+    fn write_expression(&mut self, expression: &SemanticExpression, minimum_precedence: u8) {
+        if precedence(expression) < minimum_precedence {
+            self.write_parenthesized_if(true, expression);
+            return;
+        }
+        match expression {
+            SemanticExpression::IntegerLiteral { value, .. } => {
+                self.output.push_str(&value.to_string());
+            }
+            SemanticExpression::NilLiteral { .. } => self.output.push_str("nil"),
+            SemanticExpression::BooleanLiteral { value, .. } => {
+                self.output.push_str(if *value { "true" } else { "false" });
+            }
+            SemanticExpression::StringLiteral { value, .. } => {
+                self.output.push_str(&string_literal_text(value));
+            }
+            SemanticExpression::ListLiteral { elements, .. } => {
+                self.output.push('[');
+                self.write_comma_separated_expressions(elements);
+                self.output.push(']');
+            }
+            SemanticExpression::NameReference { name, .. } => self.output.push_str(name),
+            SemanticExpression::StructLiteral {
+                type_name, fields, ..
+            } => {
+                self.output.push_str(&type_name_text(type_name));
+                if fields.is_empty() {
+                    self.output.push_str(" {}");
+                    return;
+                }
+                self.output.push_str(" { ");
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        self.output.push_str(", ");
+                    }
+                    self.output.push_str(&field.name);
+                    self.output.push_str(": ");
+                    self.write_expression(&field.value, LOWEST_PRECEDENCE);
+                }
+                self.output.push_str(" }");
+            }
+            SemanticExpression::FieldAccess { target, field, .. } => {
+                self.write_postfix_target(target, false);
+                self.output.push('.');
+                self.output.push_str(field);
+            }
+            SemanticExpression::IndexAccess { target, index, .. } => {
+                self.write_postfix_target(target, true);
+                self.output.push('[');
+                self.write_expression(index, LOWEST_PRECEDENCE);
+                self.output.push(']');
+            }
+            SemanticExpression::Call {
+                callee,
+                type_arguments,
+                arguments,
+                ..
+            } => {
+                // `a[b](c)` would read as a generic call, so an indexed callee
+                // is parenthesized.
+                if matches!(**callee, SemanticExpression::IndexAccess { .. }) {
+                    self.write_parenthesized_if(true, callee);
+                } else {
+                    self.write_postfix_target(callee, !type_arguments.is_empty());
+                }
+                if !type_arguments.is_empty() {
+                    self.output.push('[');
+                    self.output
+                        .push_str(&comma_separated(type_arguments.iter().map(type_name_text)));
+                    self.output.push(']');
+                }
+                self.output.push('(');
+                self.write_comma_separated_expressions(arguments);
+                self.output.push(')');
+            }
+            SemanticExpression::Unary {
+                operator,
+                expression,
+                ..
+            } => {
+                self.output.push_str(match operator {
+                    SemanticUnaryOperator::Not => "not ",
+                    SemanticUnaryOperator::Negate => "-",
+                });
+                self.write_expression(expression, UNARY_PRECEDENCE);
+            }
+            SemanticExpression::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let operator_precedence = binary_operator_precedence(*operator);
+                // The right operand of `*`, `/`, and `%` is parsed without
+                // unary operators.
+                let right_precedence = if operator_precedence == 10 {
+                    POSTFIX_PRECEDENCE
+                } else {
+                    operator_precedence + 1
+                };
+                self.write_expression(left, operator_precedence);
+                self.output.push(' ');
+                self.output.push_str(binary_operator_text(*operator));
+                self.output.push(' ');
+                self.write_expression(right, right_precedence);
+            }
+            SemanticExpression::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.output.push_str("if ");
+                self.write_expression_before_block(condition);
+                self.output.push(' ');
+                self.write_branch(then_branch);
+                self.output.push_str(" else ");
+                self.write_branch(else_branch);
+            }
+            SemanticExpression::Block { block, value, .. } => {
+                self.write_block(block, Some(value));
+            }
+            SemanticExpression::Match { target, arms, .. } => {
+                self.output.push_str("match ");
+                self.write_expression_before_block(target);
+                self.output.push_str(" {\n");
+                self.indentation += 1;
+                for arm in arms {
+                    self.start_line();
+                    self.output.push_str(&match_pattern_text(&arm.pattern));
+                    if let Some(guard) = &arm.guard {
+                        self.output.push_str(" if ");
+                        self.write_expression_before_block(guard);
+                    }
+                    self.output.push_str(" => ");
+                    self.write_expression(&arm.value, LOWEST_PRECEDENCE);
+                    self.output.push_str(",\n");
+                }
+                self.indentation -= 1;
+                self.start_line();
+                self.output.push('}');
+            }
+            SemanticExpression::Matches {
+                value, type_name, ..
+            } => {
+                self.write_expression(value, COMPARISON_PRECEDENCE);
+                self.output.push_str(" matches ");
+                self.output.push_str(&type_name_text(type_name));
+            }
+            SemanticExpression::StringInterpolation { parts, .. } => {
+                self.output.push('"');
+                for part in parts {
+                    match part {
+                        SemanticStringInterpolationPart::Literal(text) => {
+                            self.output.push_str(&escape_string_text(text));
+                        }
+                        SemanticStringInterpolationPart::Expression(expression) => {
+                            self.output.push('{');
+                            self.write_expression(expression, LOWEST_PRECEDENCE);
+                            self.output.push('}');
+                        }
+                    }
+                }
+                self.output.push('"');
+            }
+        }
+    }
+
+    fn write_comma_separated_expressions(&mut self, expressions: &[SemanticExpression]) {
+        for (index, expression) in expressions.iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+            self.write_expression(expression, LOWEST_PRECEDENCE);
+        }
+    }
+}
+
+struct BraceFinder {
+    found: bool,
+}
+
+impl SemanticVisitor for BraceFinder {
+    fn visit_expression(&mut self, expression: &SemanticExpression) {
+        match expression {
+            SemanticExpression::StructLiteral { .. }
+            | SemanticExpression::If { .. }
+            | SemanticExpression::Block { .. }
+            | SemanticExpression::Match { .. } => self.found = true,
+            _ if is_capitalized_name_reference(expression) => self.found = true,
+            _ => visit_expression_children(self, expression),
+        }
+    }
+}
+
+fn is_capitalized_name_reference(expression: &SemanticExpression) -> bool {
+    matches!(
+        expression,
+        SemanticExpression::NameReference { name, .. }
+            if name.starts_with(|character: char| character.is_ascii_uppercase())
+    )
+}
+
+// Binding strength, from `or` (1) to postfix operators and primaries (12).
+fn precedence(expression: &SemanticExpression) -> u8 {
+    match expression {
+        SemanticExpression::Binary { operator, .. } => binary_operator_precedence(*operator),
+        SemanticExpression::Matches { .. } => COMPARISON_PRECEDENCE,
+        SemanticExpression::Unary { .. } => UNARY_PRECEDENCE,
+        _ => POSTFIX_PRECEDENCE,
+    }
+}
+
+fn binary_operator_precedence(operator: SemanticBinaryOperator) -> u8 {
+    match operator {
+        SemanticBinaryOperator::Or => 1,
+        SemanticBinaryOperator::And => 2,
+        SemanticBinaryOperator::EqualEqual | SemanticBinaryOperator::NotEqual => 3,
+        SemanticBinaryOperator::LessThan
+        | SemanticBinaryOperator::LessThanOrEqual
+        | SemanticBinaryOperator::GreaterThan
+        | SemanticBinaryOperator::GreaterThanOrEqual => COMPARISON_PRECEDENCE,
+        SemanticBinaryOperator::BitwiseOr => 5,
+        SemanticBinaryOperator::BitwiseXor => 6,
+        SemanticBinaryOperator::BitwiseAnd => 7,
+        SemanticBinaryOperator::ShiftLeft | SemanticBinaryOperator::ShiftRight => 8,
+        SemanticBinaryOperator::Add | SemanticBinaryOperator::Subtract => 9,
+        SemanticBinaryOperator::Multiply
+        | SemanticBinaryOperator::Divide
+        | SemanticBinaryOperator::Modulo => 10,
+    }
+}
+
+fn binary_operator_text(operator: SemanticBinaryOperator) -> &'static str {
+    match operator {
+        SemanticBinaryOperator::Add => "+",
+        SemanticBinaryOperator::Subtract => "-",
+        SemanticBinaryOperator::Multiply => "*",
+        SemanticBinaryOperator::Divide => "/",
+        SemanticBinaryOperator::Modulo => "%",
+        SemanticBinaryOperator::BitwiseAnd => "&",
+        SemanticBinaryOperator::BitwiseOr => "|",
+        SemanticBinaryOperator::BitwiseXor => "^",
+        SemanticBinaryOperator::ShiftLeft => "<<",
+        SemanticBinaryOperator::ShiftRight => ">>",
+        SemanticBinaryOperator::EqualEqual => "==",
+        SemanticBinaryOperator::NotEqual => "!=",
+        SemanticBinaryOperator::LessThan => "<",
+        SemanticBinaryOperator::LessThanOrEqual => "<=",
+        SemanticBinaryOperator::GreaterThan => ">",
+        SemanticBinaryOperator::GreaterThanOrEqual => ">=",
+        SemanticBinaryOperator::And => "and",
+        SemanticBinaryOperator::Or => "or",
+    }
+}
+
+fn match_pattern_text(pattern: &SemanticMatchPattern) -> String {
+    match pattern {
+        SemanticMatchPattern::Type { type_name, .. } => type_name_text(type_name),
+        SemanticMatchPattern::Binding {
+            name, type_name, ..
+        } => format!("{name}: {}", type_name_text(type_name)),
+        SemanticMatchPattern::Literal { value, .. } => match value {
+            SemanticMatchLiteral::Integer(value) => value.to_string(),
+            SemanticMatchLiteral::String(value) => string_literal_text(value),
+            SemanticMatchLiteral::Boolean(value) => value.to_string(),
+        },
+    }
+}
+
+fn type_parameters_text(type_parameters: &[SemanticTypeParameter]) -> String {
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+    let type_parameters =
+        comma_separated(type_parameters.iter().map(
+            |type_parameter| match &type_parameter.constraint {
+                Some(constraint) => {
+                    format!("{}: {}", type_parameter.name, type_name_text(constraint))
+                }
+                None => type_parameter.name.clone(),
+            },
+        ));
+    format!("[{type_parameters}]")
+}
+
+fn parameters_text(parameters: &[SemanticParameterDeclaration]) -> String {
+    comma_separated(parameters.iter().map(|parameter| {
+        let mutable = if parameter.mutable { "mut " } else { "" };
+        format!(
+            "{mutable}{}: {}",
+            parameter.name,
+            type_name_text(&parameter.type_name)
+        )
+    }))
+}
+
+fn method_parameters_text(
+    self_mutable: bool,
+    parameters: &[SemanticParameterDeclaration],
+) -> String {
+    let receiver = if self_mutable { "mut self" } else { "self" };
+    if parameters.is_empty() {
+        return receiver.to_string();
+    }
+    format!("{receiver}, {}", parameters_text(parameters))
+}
+
+fn member_visibility_prefix(visibility: SemanticMemberVisibility) -> &'static str {
+    match visibility {
+        SemanticMemberVisibility::Public => "public ",
+        SemanticMemberVisibility::Private => "",
+    }
+}
+
+fn type_name_text(type_name: &SemanticTypeName) -> String {
+    let segments: Vec<_> = type_name.names.iter().map(type_name_segment_text).collect();
+    segments.join(" | ")
+}
+
+// Function types are stored as a `function` segment whose last type argument
+// is the return type.
+fn type_name_segment_text(segment: &SemanticTypeNameSegment) -> String {
+    if segment.name == "function"
+        && let Some((return_type, parameter_types)) = segment.type_arguments.split_last()
+    {
+        return format!(
+            "function({}) -> {}",
+            comma_separated(parameter_types.iter().map(type_name_text)),
+            type_name_text(return_type)
+        );
+    }
+    if segment.type_arguments.is_empty() {
+        return segment.name.clone();
+    }
+    format!(
+        "{}[{}]",
+        segment.name,
+        comma_separated(segment.type_arguments.iter().map(type_name_text))
+    )
+}
+
+fn string_literal_text(value: &str) -> String {
+    format!("\"{}\"", escape_string_text(value))
+}
+
+fn escape_string_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        if matches!(character, '\\' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+fn comma_separated(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
@@ -1,4 +1,5 @@
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 
 use compiler__packages::PackageId;
 use compiler__source::Span;
@@ -106,6 +107,52 @@ impl Type {
     }
 }
 
+/// Flattens nested unions, drops `never` and duplicate members, and orders the
+/// remaining members canonically so equivalent unions compare and display the
+/// same regardless of how they were spelled.
+#[must_use]
+pub fn normalize_union(types: Vec<Type>) -> Type {
+    let mut members = Vec::new();
+    for value_type in types {
+        collect_union_members(value_type, &mut members);
+    }
+    members.sort_by_cached_key(|member| (union_member_rank(member), member.display()));
+    if members.len() <= 1 {
+        return members.pop().unwrap_or(Type::Never);
+    }
+    Type::Union(members)
+}
+
+fn collect_union_members(value_type: Type, members: &mut Vec<Type>) {
+    match value_type {
+        Type::Union(inner) => {
+            for inner_type in inner {
+                collect_union_members(inner_type, members);
+            }
+        }
+        Type::Never => {}
+        _ => {
+            if !members.contains(&value_type) {
+                members.push(value_type);
+            }
+        }
+    }
+}
+
+fn union_member_rank(member: &Type) -> u8 {
+    match member {
+        Type::Integer64 => 0,
+        Type::Boolean => 1,
+        Type::String => 2,
+        Type::List(_) => 3,
+        Type::Named(_) | Type::Applied { .. } => 4,
+        Type::Function { .. } => 5,
+        Type::TypeParameter(_) => 6,
+        Type::Unknown | Type::Never | Type::Union(_) => 7,
+        Type::Nil => 8,
+    }
+}
+
 #[must_use]
 pub fn type_from_builtin_name(name: &str) -> Option<Type> {
     match name {
@@ -125,6 +172,23 @@ pub struct TypedFunctionSignature {
     pub return_type: Type,
 }
 
+// A function provided by an embedding host instead of declared in source.
+// Programs call it by name like a builtin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostFunctionContract {
+    pub name: String,
+    pub parameter_types: Vec<Type>,
+    pub return_type: Type,
+}
+
+// A builtin the analyzed package may not call. The policy source names the
+// rule that forbids it, so that its diagnostic can point there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenBuiltin {
+    pub name: String,
+    pub policy_source: String,
+}
+
 #[derive(Clone)]
 pub struct GenericTypeParameter {
     pub name: String,
@@ -157,6 +221,7 @@ pub struct ImportedTypeDeclaration {
 pub struct ImportedMethodSignature {
     pub name: String,
     pub self_mutable: bool,
+    pub parameter_names: Vec<String>,
     pub parameter_types: Vec<Type>,
     pub return_type: Type,
 }
@@ -186,4 +251,14 @@ pub struct ImportedBinding {
     pub imported_symbol_name: String,
     pub span: Span,
     pub symbol: ImportedSymbol,
+    pub deprecation: Option<ImportedDeprecation>,
+}
+
+// A `@deprecated` attribute on an imported declaration. The declaration's
+// file and name span are kept so uses can point back at it.
+#[derive(Clone)]
+pub struct ImportedDeprecation {
+    pub message: String,
+    pub declaration_path: PathBuf,
+    pub declaration_span: Span,
 }
@@ -0,0 +1,68 @@
+// An attribute the compiler understands. Attributes are written as `@name` or
+// `@name("argument", ...)` on their own lines before a declaration; any other
+// name is reported as unknown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KnownAttribute {
+    Deprecated,
+    Test,
+    Bench,
+}
+
+// The kind of top-level declaration an attribute is written on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Type,
+    Function,
+    Constant,
+}
+
+impl KnownAttribute {
+    pub const ALL: [Self; 3] = [Self::Deprecated, Self::Test, Self::Bench];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|attribute| attribute.name() == name)
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Deprecated => "deprecated",
+            Self::Test => "test",
+            Self::Bench => "bench",
+        }
+    }
+
+    // Every attribute argument is a string literal.
+    #[must_use]
+    pub fn argument_count(self) -> usize {
+        match self {
+            Self::Deprecated => 1,
+            Self::Test | Self::Bench => 0,
+        }
+    }
+
+    #[must_use]
+    pub fn applies_to(self, target: AttributeTarget) -> bool {
+        match self {
+            Self::Deprecated => matches!(
+                target,
+                AttributeTarget::Type | AttributeTarget::Function | AttributeTarget::Constant
+            ),
+            Self::Test | Self::Bench => target == AttributeTarget::Function,
+        }
+    }
+}
+
+impl AttributeTarget {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Function => "function",
+            Self::Constant => "constant",
+        }
+    }
+}
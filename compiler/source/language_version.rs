@@ -0,0 +1,127 @@
+// A language version a package declares with `language_version` in its
+// manifest. Each version only adds features, so a package that declares none
+// gets the latest version and an older one opts out of the newer features.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LanguageVersion {
+    Version1,
+    #[default]
+    Version2,
+}
+
+impl LanguageVersion {
+    pub const ALL: [Self; 2] = [Self::Version1, Self::Version2];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|version| version.name() == name)
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Version1 => "1",
+            Self::Version2 => "2",
+        }
+    }
+}
+
+// A language feature that is unavailable before some language version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LanguageFeature {
+    GuardStatements,
+    RangeForLoops,
+    DeferStatements,
+    FormatBuiltin,
+    EnumBuiltins,
+    BitwiseOperators,
+    MatchGuards,
+    LiteralPatterns,
+    IfAndBlockExpressions,
+    Attributes,
+    StaticAssertions,
+    MathBuiltins,
+    OverflowArithmeticBuiltins,
+    ClockAndRandomBuiltins,
+    ValueRenderingBuiltins,
+    UnreachableBuiltin,
+}
+
+impl LanguageFeature {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GuardStatements => "guard statements",
+            Self::RangeForLoops => "range for loops",
+            Self::DeferStatements => "defer statements",
+            Self::FormatBuiltin => "format calls",
+            Self::EnumBuiltins => "enum builtins",
+            Self::BitwiseOperators => "bitwise operators",
+            Self::MatchGuards => "match guards",
+            Self::LiteralPatterns => "literal patterns",
+            Self::IfAndBlockExpressions => "if and block expressions",
+            Self::Attributes => "attributes",
+            Self::StaticAssertions => "static assertions",
+            Self::MathBuiltins => "math builtins",
+            Self::OverflowArithmeticBuiltins => "checked and saturating arithmetic builtins",
+            Self::ClockAndRandomBuiltins => "clock and random builtins",
+            Self::ValueRenderingBuiltins => "assert_equals and debug_string calls",
+            Self::UnreachableBuiltin => "unreachable calls",
+        }
+    }
+
+    #[must_use]
+    pub fn required_version(self) -> LanguageVersion {
+        match self {
+            Self::GuardStatements
+            | Self::RangeForLoops
+            | Self::DeferStatements
+            | Self::FormatBuiltin
+            | Self::EnumBuiltins
+            | Self::BitwiseOperators
+            | Self::MatchGuards
+            | Self::LiteralPatterns
+            | Self::IfAndBlockExpressions
+            | Self::Attributes
+            | Self::StaticAssertions
+            | Self::MathBuiltins
+            | Self::OverflowArithmeticBuiltins
+            | Self::ClockAndRandomBuiltins
+            | Self::ValueRenderingBuiltins
+            | Self::UnreachableBuiltin => LanguageVersion::Version2,
+        }
+    }
+}
+
+// A word that only became reserved in some language version, so a package
+// that declares an older one may still use it as a name. Words that act as
+// keywords only in context, such as `guard`, are never reserved, and neither
+// are builtin function names, which any binding or declaration shadows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionedReservedWord {
+    Package,
+    StaticAssert,
+}
+
+impl VersionedReservedWord {
+    pub const ALL: [Self; 2] = [Self::Package, Self::StaticAssert];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|word| word.name() == name)
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Package => "package",
+            Self::StaticAssert => "static_assert",
+        }
+    }
+
+    #[must_use]
+    pub fn reserved_since(self) -> LanguageVersion {
+        match self {
+            Self::Package | Self::StaticAssert => LanguageVersion::Version2,
+        }
+    }
+}
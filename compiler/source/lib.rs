@@ -1,7 +1,19 @@
+mod attributes;
+mod display_width;
+mod language_version;
+mod line_index;
 mod path_order;
 mod source_file;
+mod source_lines;
 mod span;
 
+pub use attributes::{AttributeTarget, KnownAttribute};
+pub use display_width::display_width;
+pub use language_version::{LanguageFeature, LanguageVersion, VersionedReservedWord};
+pub use line_index::{LineIndex, floor_char_boundary};
 pub use path_order::{compare_paths, path_to_key};
 pub use source_file::{FileId, FileRole, SourceFile};
+pub use source_lines::{
+    leading_comment_start, line_end, line_removal_range, line_start, trailing_comment_end,
+};
 pub use span::Span;
@@ -0,0 +1,104 @@
+use compiler__source::{
+    AttributeTarget, KnownAttribute, LanguageFeature, LanguageVersion, LineIndex,
+    VersionedReservedWord, display_width, floor_char_boundary,
+};
+
+#[test]
+fn line_index_maps_offsets_to_lines_and_character_columns() {
+    let source_text = "first\nsé x\n\nlast";
+    let line_index = LineIndex::new(source_text);
+
+    assert_eq!(line_index.line_column(0), (1, 1));
+    assert_eq!(line_index.line_column(5), (1, 6));
+    assert_eq!(line_index.line_column(6), (2, 1));
+    assert_eq!(line_index.line_column(10), (2, 4));
+    assert_eq!(line_index.line_column(12), (3, 1));
+    assert_eq!(line_index.line_column(source_text.len()), (4, 5));
+}
+
+#[test]
+fn line_index_clamps_offsets_past_the_end_of_the_text() {
+    let line_index = LineIndex::new("one\ntwo");
+
+    assert_eq!(line_index.line(100), 2);
+    assert_eq!(line_index.line_column(100), (2, 4));
+}
+
+#[test]
+fn line_index_counts_emoji_and_cjk_as_single_characters() {
+    let source_text = "x := \"😀漢字\" + y";
+    let line_index = LineIndex::new(source_text);
+    let y_offset = source_text.find('y').unwrap();
+
+    assert_eq!(line_index.line_column(y_offset), (1, 14));
+    assert_eq!(line_index.utf16_position(y_offset), (0, 14));
+}
+
+#[test]
+fn line_index_counts_utf16_code_units_for_positions() {
+    let source_text = "a\n😀漢b";
+    let line_index = LineIndex::new(source_text);
+    let b_offset = source_text.find('b').unwrap();
+
+    assert_eq!(line_index.utf16_position(0), (0, 0));
+    assert_eq!(line_index.utf16_position(b_offset), (1, 3));
+    assert_eq!(line_index.line_column(b_offset), (2, 3));
+}
+
+#[test]
+fn line_index_maps_offsets_inside_a_character_to_its_start() {
+    let source_text = "a😀b";
+    let line_index = LineIndex::new(source_text);
+
+    assert_eq!(floor_char_boundary(source_text, 3), 1);
+    assert_eq!(line_index.line_column(3), (1, 2));
+    assert_eq!(line_index.utf16_position(3), (0, 1));
+}
+
+#[test]
+fn display_width_counts_wide_characters_as_two_columns() {
+    assert_eq!(display_width("abc"), 3);
+    assert_eq!(display_width("漢字"), 4);
+    assert_eq!(display_width("😀 ok"), 5);
+    assert_eq!(display_width("e\u{301}"), 1);
+}
+
+#[test]
+fn language_versions_are_named_and_ordered_by_release() {
+    assert_eq!(
+        LanguageVersion::from_name("1"),
+        Some(LanguageVersion::Version1)
+    );
+    assert_eq!(LanguageVersion::from_name("3"), None);
+    assert_eq!(LanguageVersion::default(), LanguageVersion::Version2);
+    assert!(LanguageVersion::Version1 < LanguageFeature::DeferStatements.required_version());
+    assert!(LanguageVersion::Version1 < LanguageFeature::StaticAssertions.required_version());
+    assert!(LanguageVersion::Version1 < LanguageFeature::MathBuiltins.required_version());
+}
+
+#[test]
+fn versioned_reserved_words_are_looked_up_by_name() {
+    assert_eq!(
+        VersionedReservedWord::from_name("package"),
+        Some(VersionedReservedWord::Package)
+    );
+    assert_eq!(VersionedReservedWord::from_name("guard"), None);
+    assert_eq!(VersionedReservedWord::from_name("min"), None);
+    assert_eq!(
+        VersionedReservedWord::StaticAssert.reserved_since(),
+        LanguageVersion::Version2
+    );
+}
+
+#[test]
+fn known_attributes_are_looked_up_by_name() {
+    assert_eq!(
+        KnownAttribute::from_name("deprecated"),
+        Some(KnownAttribute::Deprecated)
+    );
+    assert_eq!(KnownAttribute::from_name("inline"), None);
+    assert_eq!(KnownAttribute::Deprecated.argument_count(), 1);
+    assert!(KnownAttribute::Deprecated.applies_to(AttributeTarget::Constant));
+    assert_eq!(KnownAttribute::Test.argument_count(), 0);
+    assert!(!KnownAttribute::Bench.applies_to(AttributeTarget::Type));
+}
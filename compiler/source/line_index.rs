@@ -0,0 +1,61 @@
+// Maps byte offsets in a source text to line and column numbers. Offsets
+// that fall inside a multi-byte character map to the start of that character,
+// and an offset past the end of the text maps to the end of its last line.
+pub struct LineIndex<'a> {
+    source_text: &'a str,
+    line_start_offsets: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    #[must_use]
+    pub fn new(source_text: &'a str) -> Self {
+        let line_start_offsets = std::iter::once(0)
+            .chain(source_text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            source_text,
+            line_start_offsets,
+        }
+    }
+
+    // Returns the 1-based line containing the offset.
+    #[must_use]
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_start_offsets
+            .partition_point(|&line_start_offset| line_start_offset <= offset)
+    }
+
+    // Returns the 1-based line and column of the offset, where columns count
+    // characters, as reports show them.
+    #[must_use]
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let (line, line_prefix) = self.line_prefix(offset);
+        (line, line_prefix.chars().count() + 1)
+    }
+
+    // Returns the 0-based line and character of the offset, where characters
+    // count UTF-16 code units, as the language server protocol expects.
+    #[must_use]
+    pub fn utf16_position(&self, offset: usize) -> (usize, usize) {
+        let (line, line_prefix) = self.line_prefix(offset);
+        (line - 1, line_prefix.encode_utf16().count())
+    }
+
+    fn line_prefix(&self, offset: usize) -> (usize, &'a str) {
+        let offset = floor_char_boundary(self.source_text, offset);
+        let line = self.line(offset);
+        let line_start_offset = self.line_start_offsets[line - 1];
+        (line, &self.source_text[line_start_offset..offset])
+    }
+}
+
+// Returns the largest offset that is no greater than `offset` and lies on a
+// character boundary of `text`.
+#[must_use]
+pub fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
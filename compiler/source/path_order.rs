@@ -1,9 +1,32 @@
 use std::cmp::Ordering;
-use std::path::Path;
+use std::path::{Component, Path, Prefix};
 
+// Keys use `/` separators and drop `.` components, and verbatim Windows disk
+// prefixes are written as plain drive letters, so that spellings of the same
+// canonical path produce the same key.
 #[must_use]
 pub fn path_to_key(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
+    let mut key = String::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => {
+                    key.push(char::from(drive));
+                    key.push(':');
+                }
+                _ => key.push_str(&prefix.as_os_str().to_string_lossy()),
+            },
+            Component::RootDir => key.push('/'),
+            Component::CurDir => {}
+            Component::ParentDir | Component::Normal(_) => {
+                if !key.is_empty() && !key.ends_with('/') {
+                    key.push('/');
+                }
+                key.push_str(&component.as_os_str().to_string_lossy());
+            }
+        }
+    }
+    key.replace('\\', "/")
 }
 
 #[must_use]
@@ -0,0 +1,76 @@
+// Line boundaries around byte offsets, for edits that move or remove whole
+// lines of source text.
+
+#[must_use]
+pub fn line_start(source_text: &str, offset: usize) -> usize {
+    source_text[..offset]
+        .rfind('\n')
+        .map_or(0, |newline_offset| newline_offset + 1)
+}
+
+// The returned offset is just past the line's newline, if it has one.
+#[must_use]
+pub fn line_end(source_text: &str, offset: usize) -> usize {
+    source_text[offset..]
+        .find('\n')
+        .map_or(source_text.len(), |newline_offset| {
+            offset + newline_offset + 1
+        })
+}
+
+// Removes the lines `start..end` occupies and, when they were preceded by a
+// blank line or the start of the file, one following blank line.
+#[must_use]
+pub fn line_removal_range(source_text: &str, start: usize, end: usize) -> (usize, usize) {
+    let removal_start = line_start(source_text, start);
+    let mut removal_end = line_end(source_text, end);
+    let preceded_by_blank_line = removal_start == 0
+        || source_text[line_start(source_text, removal_start - 1)..removal_start]
+            .trim()
+            .is_empty();
+    let next_line_end = line_end(source_text, removal_end);
+    if preceded_by_blank_line
+        && removal_end < next_line_end
+        && source_text[removal_end..next_line_end].trim().is_empty()
+    {
+        removal_end = next_line_end;
+    }
+    (removal_start, removal_end)
+}
+
+// Declaration spans start after visibility and `type` keywords, and the
+// comment and attribute lines directly above a declaration belong to it. None
+// when anything else precedes the declaration on its line.
+#[must_use]
+pub fn leading_comment_start(source_text: &str, declaration_start: usize) -> Option<usize> {
+    let mut start = line_start(source_text, declaration_start);
+    if !source_text[start..declaration_start]
+        .split_whitespace()
+        .all(|word| matches!(word, "visible" | "public" | "type"))
+    {
+        return None;
+    }
+    while start > 0 {
+        let previous_line_start = line_start(source_text, start - 1);
+        let previous_line = source_text[previous_line_start..start].trim_start();
+        if !previous_line.starts_with("//") && !previous_line.starts_with('@') {
+            break;
+        }
+        start = previous_line_start;
+    }
+    Some(start)
+}
+
+// A comment after a declaration on its last line belongs to it.
+#[must_use]
+pub fn trailing_comment_end(source_text: &str, declaration_end: usize) -> usize {
+    let rest_of_line = source_text[declaration_end..]
+        .split('\n')
+        .next()
+        .unwrap_or_default();
+    if rest_of_line.trim_start().starts_with("//") {
+        declaration_end + rest_of_line.len()
+    } else {
+        declaration_end
+    }
+}
@@ -1,9 +1,27 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// A byte range in a source file. Diagnostics point at `anchor`, which is the
+// start of the range except for operator expressions such as calls, binary
+// operations, and field accesses, which point at their operator. Line and
+// column numbers are derived from a `LineIndex` of the file when a span is
+// rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Span {
     pub start: usize,
     pub end: usize,
-    pub line: usize,
-    pub column: usize,
+    pub anchor: usize,
+}
+
+impl Span {
+    #[must_use]
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            anchor: start,
+        }
+    }
+
+    #[must_use]
+    pub const fn anchored_at(self, anchor: usize) -> Self {
+        Self { anchor, ..self }
+    }
 }
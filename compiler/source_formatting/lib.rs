@@ -1,12 +1,20 @@
 use compiler__fix_edits::TextEdit;
 
+// String literals cannot span lines, so trimming trailing whitespace never
+// changes a program's meaning.
 #[must_use]
 pub fn canonicalize_source_text(source_text: &str) -> String {
-    let normalized_line_endings = source_text.replace("\r\n", "\n").replace('\r', "\n");
-    if normalized_line_endings.is_empty() {
-        return normalized_line_endings;
+    let trimmed_lines = source_text
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .split('\n')
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if trimmed_lines.is_empty() {
+        return trimmed_lines;
     }
-    let without_trailing_newlines = normalized_line_endings.trim_end_matches('\n');
+    let without_trailing_newlines = trimmed_lines.trim_end_matches('\n');
     format!("{without_trailing_newlines}\n")
 }
 
@@ -22,3 +30,46 @@ pub fn formatting_text_edits(source_text: &str) -> Vec<TextEdit> {
         replacement_text: canonical_source_text,
     }]
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormattingVerificationError {
+    NotIdempotent,
+    LineContentChanged { line_number: usize },
+}
+
+// Verification mode: the formatter's edits are only returned once formatting
+// its output is a no-op and every line keeps its text apart from surrounding
+// whitespace, so no comment is dropped or moved onto another line.
+pub fn verified_formatting_text_edits(
+    source_text: &str,
+) -> Result<Vec<TextEdit>, FormattingVerificationError> {
+    let canonical_source_text = canonicalize_source_text(source_text);
+    if canonicalize_source_text(&canonical_source_text) != canonical_source_text {
+        return Err(FormattingVerificationError::NotIdempotent);
+    }
+    let source_lines = content_lines(source_text);
+    let canonical_source_lines = content_lines(&canonical_source_text);
+    let line_count = source_lines.len().max(canonical_source_lines.len());
+    for line_index in 0..line_count {
+        if source_lines.get(line_index) != canonical_source_lines.get(line_index) {
+            return Err(FormattingVerificationError::LineContentChanged {
+                line_number: line_index + 1,
+            });
+        }
+    }
+    Ok(formatting_text_edits(source_text))
+}
+
+// Lines are split the way the formatter normalizes line endings, and blank
+// lines at the end of the file are ignored since the formatter removes them.
+fn content_lines(source_text: &str) -> Vec<&str> {
+    let mut lines = source_text
+        .split("\r\n")
+        .flat_map(|line| line.split(['\r', '\n']))
+        .map(str::trim_end)
+        .collect::<Vec<_>>();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
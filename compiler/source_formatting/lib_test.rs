@@ -1,4 +1,6 @@
-use compiler__source_formatting::{canonicalize_source_text, formatting_text_edits};
+use compiler__source_formatting::{
+    canonicalize_source_text, formatting_text_edits, verified_formatting_text_edits,
+};
 
 #[test]
 fn canonicalize_source_text_normalizes_line_endings_and_trailing_newlines() {
@@ -6,6 +8,12 @@ fn canonicalize_source_text_normalizes_line_endings_and_trailing_newlines() {
     assert_eq!(output, "a\nb\n");
 }
 
+#[test]
+fn canonicalize_source_text_trims_trailing_whitespace() {
+    let output = canonicalize_source_text("a  \n\tb\t\n");
+    assert_eq!(output, "a\n\tb\n");
+}
+
 #[test]
 fn formatting_text_edits_returns_empty_for_already_canonical_text() {
     let edits = formatting_text_edits("a\nb\n");
@@ -20,3 +28,25 @@ fn formatting_text_edits_returns_whole_file_replacement_when_not_canonical() {
     assert_eq!(edits[0].end_byte_offset, 4);
     assert_eq!(edits[0].replacement_text, "a\nb\n");
 }
+
+#[test]
+fn canonicalize_source_text_is_idempotent() {
+    for source_text in [
+        "",
+        "\n\n",
+        "a  \r\n// comment \r\n\r\n",
+        "a\rb // trailing\t\n\n\n",
+        "function f() -> nil {\n    // own line\n    return\n}",
+    ] {
+        let formatted = canonicalize_source_text(source_text);
+        assert_eq!(canonicalize_source_text(&formatted), formatted);
+    }
+}
+
+#[test]
+fn verified_formatting_text_edits_keeps_comment_lines() {
+    let source_text = "a := 1 // one  \r\n// two\r\n\r\n";
+    let edits = verified_formatting_text_edits(source_text).expect("formatting should verify");
+    assert_eq!(edits, formatting_text_edits(source_text));
+    assert_eq!(edits[0].replacement_text, "a := 1 // one\n// two\n");
+}
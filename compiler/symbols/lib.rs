@@ -79,21 +79,23 @@ pub fn top_level_symbol(declaration: &SyntaxDeclaration) -> Option<TopLevelSymbo
         SyntaxDeclaration::Type(type_declaration) => Some(TopLevelSymbol {
             name: type_declaration.name.clone(),
             visibility: type_declaration.visibility,
-            name_span: type_declaration.name_span.clone(),
+            name_span: type_declaration.name_span,
         }),
         SyntaxDeclaration::Constant(constant_declaration) => Some(TopLevelSymbol {
             name: constant_declaration.name.clone(),
             visibility: constant_declaration.visibility,
-            name_span: constant_declaration.name_span.clone(),
+            name_span: constant_declaration.name_span,
         }),
         SyntaxDeclaration::Function(function_declaration) => Some(TopLevelSymbol {
             name: function_declaration.name.clone(),
             visibility: function_declaration.visibility,
-            name_span: function_declaration.name_span.clone(),
+            name_span: function_declaration.name_span,
         }),
         SyntaxDeclaration::Import(_)
         | SyntaxDeclaration::Exports(_)
+        | SyntaxDeclaration::Package(_)
         | SyntaxDeclaration::Group(_)
-        | SyntaxDeclaration::Test(_) => None,
+        | SyntaxDeclaration::Test(_)
+        | SyntaxDeclaration::StaticAssert(_) => None,
     }
 }
@@ -27,10 +27,36 @@ pub struct SyntaxExportsMember {
     pub span: Span,
 }
 
+#[derive(Clone, Debug)]
+pub struct SyntaxPackageDeclaration {
+    pub fields: Vec<SyntaxPackageField>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct SyntaxPackageField {
+    pub name: String,
+    pub name_span: Span,
+    pub value: SyntaxPackageFieldValue,
+}
+
+#[derive(Clone, Debug)]
+pub enum SyntaxPackageFieldValue {
+    String {
+        value: String,
+        span: Span,
+    },
+    List {
+        items: Vec<(String, Span)>,
+        span: Span,
+    },
+}
+
 #[derive(Clone, Debug)]
 pub struct SyntaxParsedFile {
     pub role: FileRole,
     pub items: Vec<SyntaxFileItem>,
+    pub comments: Vec<SyntaxComment>,
 }
 
 #[derive(Clone, Debug)]
@@ -46,17 +72,49 @@ impl SyntaxParsedFile {
             SyntaxFileItem::Declaration(declaration) => Some(declaration.as_ref()),
         })
     }
+
+    // Whether replacing `start..end` would drop a comment or leave one beside
+    // other code. Rewriting within a comment's attached code keeps it attached.
+    #[must_use]
+    pub fn edit_disturbs_comments(&self, start: usize, end: usize) -> bool {
+        self.comments.iter().any(|comment| {
+            let overlaps_comment = if start == end {
+                comment.span.start < start && start < comment.span.end
+            } else {
+                start < comment.span.end && comment.span.start < end
+            };
+            let moves_attached_code = comment.attached_span.is_some_and(|attached_span| {
+                start < attached_span.end
+                    && attached_span.start < end
+                    && (start < attached_span.start || attached_span.end < end)
+            });
+            overlaps_comment || moves_attached_code
+        })
+    }
+}
+
+// A `//` line comment, attached to the code it describes: a trailing comment
+// to the code before it on its line, and a comment on its own line to the next
+// line of code unless a blank line comes first. Comments with nothing to
+// attach to have no `attached_span`.
+#[derive(Clone, Debug)]
+pub struct SyntaxComment {
+    pub text: String,
+    pub span: Span,
+    pub attached_span: Option<Span>,
 }
 
 #[derive(Clone, Debug)]
 pub enum SyntaxDeclaration {
     Import(SyntaxImportDeclaration),
     Exports(SyntaxExportsDeclaration),
+    Package(SyntaxPackageDeclaration),
     Type(SyntaxTypeDeclaration),
     Constant(SyntaxConstantDeclaration),
     Function(SyntaxFunctionDeclaration),
     Group(SyntaxTestGroupDeclaration),
     Test(SyntaxTestDeclaration),
+    StaticAssert(SyntaxStaticAssertDeclaration),
 }
 
 #[derive(Clone, Debug)]
@@ -75,11 +133,38 @@ pub struct SyntaxTestDeclaration {
     pub span: Span,
 }
 
+/// A top-level `static_assert(condition, "message")`. The condition is
+/// evaluated during type analysis, which reports the message when it is false.
+#[derive(Clone, Debug)]
+pub struct SyntaxStaticAssertDeclaration {
+    pub condition: SyntaxExpression,
+    pub message: String,
+    pub message_span: Span,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug)]
 pub struct SyntaxDocComment {
     pub lines: Vec<String>,
     pub span: Span,
-    pub end_line: usize,
+}
+
+/// An `@name` or `@name("argument", ...)` attribute written on its own line
+/// before a type, function, or constant declaration. The name is not checked
+/// against the known attributes until syntax rules run. `span` covers the whole
+/// attribute.
+#[derive(Clone, Debug)]
+pub struct SyntaxAttribute {
+    pub name: String,
+    pub name_span: Span,
+    pub arguments: Vec<SyntaxAttributeArgument>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct SyntaxAttributeArgument {
+    pub value: String,
+    pub span: Span,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -102,6 +187,7 @@ pub struct SyntaxTypeDeclaration {
     pub implemented_interfaces: Vec<SyntaxTypeName>,
     pub kind: SyntaxTypeDeclarationKind,
     pub visibility: SyntaxTopLevelVisibility,
+    pub attributes: Vec<SyntaxAttribute>,
     pub span: Span,
 }
 
@@ -173,6 +259,7 @@ pub struct SyntaxConstantDeclaration {
     pub type_name: SyntaxTypeName,
     pub expression: SyntaxExpression,
     pub visibility: SyntaxTopLevelVisibility,
+    pub attributes: Vec<SyntaxAttribute>,
     pub span: Span,
 }
 
@@ -185,6 +272,7 @@ pub struct SyntaxFunctionDeclaration {
     pub return_type: SyntaxTypeName,
     pub body: SyntaxBlock,
     pub visibility: SyntaxTopLevelVisibility,
+    pub attributes: Vec<SyntaxAttribute>,
     pub span: Span,
 }
 
@@ -234,17 +322,40 @@ pub enum SyntaxStatement {
     Continue {
         span: Span,
     },
+    // `defer value`, which runs `value` when the enclosing function returns.
+    Defer {
+        value: SyntaxExpression,
+        span: Span,
+    },
     If {
         condition: SyntaxExpression,
         then_block: SyntaxBlock,
         else_block: Option<SyntaxBlock>,
         span: Span,
     },
+    // `guard condition else { ... }`, whose else block must exit the enclosing
+    // scope.
+    Guard {
+        condition: SyntaxExpression,
+        else_block: SyntaxBlock,
+        span: Span,
+    },
     For {
         condition: Option<SyntaxExpression>,
         body: SyntaxBlock,
         span: Span,
     },
+    // `for name in start..end step amount { ... }`, which counts from `start`
+    // toward `end`, excluding it. `step` defaults to 1 and may be negative.
+    ForRange {
+        name: String,
+        name_span: Span,
+        start: Box<SyntaxExpression>,
+        end: Box<SyntaxExpression>,
+        step: Option<Box<SyntaxExpression>>,
+        body: SyntaxBlock,
+        span: Span,
+    },
     Expression {
         value: SyntaxExpression,
         span: Span,
@@ -330,6 +441,21 @@ pub enum SyntaxExpression {
         right: Box<SyntaxExpression>,
         span: Span,
     },
+    // Both branches are `Block` expressions; `if` used as a value always has
+    // an `else`.
+    If {
+        condition: Box<SyntaxExpression>,
+        then_branch: Box<SyntaxExpression>,
+        else_branch: Box<SyntaxExpression>,
+        span: Span,
+    },
+    // `{ statements... value }`, whose statements run in their own scope and
+    // whose value is the final expression.
+    Block {
+        block: SyntaxBlock,
+        value: Box<SyntaxExpression>,
+        span: Span,
+    },
     Match {
         target: Box<SyntaxExpression>,
         arms: Vec<SyntaxMatchArm>,
@@ -359,6 +485,11 @@ pub enum SyntaxBinaryOperator {
     Multiply,
     Divide,
     Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     EqualEqual,
     NotEqual,
     LessThan,
@@ -406,6 +537,7 @@ pub struct SyntaxStructLiteralField {
 #[derive(Clone, Debug)]
 pub struct SyntaxMatchArm {
     pub pattern: SyntaxMatchPattern,
+    pub guard: Option<SyntaxExpression>,
     pub value: SyntaxExpression,
     pub span: Span,
 }
@@ -422,15 +554,26 @@ pub enum SyntaxMatchPattern {
         type_name: SyntaxTypeName,
         span: Span,
     },
+    Literal {
+        value: SyntaxMatchLiteral,
+        span: Span,
+    },
 }
 
 impl SyntaxMatchPattern {
     #[must_use]
     pub fn span(&self) -> Span {
         match self {
-            SyntaxMatchPattern::Type { span, .. } | SyntaxMatchPattern::Binding { span, .. } => {
-                span.clone()
-            }
+            SyntaxMatchPattern::Type { span, .. }
+            | SyntaxMatchPattern::Binding { span, .. }
+            | SyntaxMatchPattern::Literal { span, .. } => *span,
         }
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxMatchLiteral {
+    Integer(i64),
+    String(String),
+    Boolean(bool),
+}
@@ -1,15 +1,30 @@
 use compiler__diagnostics::PhaseDiagnostic;
+use compiler__fix_edits::TextEdit;
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
-use compiler__source::Span;
+use compiler__safe_autofix::SafeAutofix;
+use compiler__source::{AttributeTarget, KnownAttribute, LineIndex, Span};
 use compiler__syntax::{
-    SyntaxBlock, SyntaxBlockItem, SyntaxDeclaration, SyntaxFileItem, SyntaxParsedFile,
-    SyntaxStatement, SyntaxStructMemberItem, SyntaxTypeDeclarationKind,
+    SyntaxAttribute, SyntaxBlock, SyntaxBlockItem, SyntaxDeclaration, SyntaxFileItem,
+    SyntaxImportDeclaration, SyntaxParsedFile, SyntaxStatement, SyntaxStructMemberItem,
+    SyntaxTypeDeclarationKind,
 };
 
-#[derive(Clone, Copy)]
 enum SyntaxRuleViolationKind {
     ImportAfterDeclaration,
     DocCommentMustDocumentDeclaration,
+    UnknownAttribute {
+        name: String,
+    },
+    AttributeArgumentCount {
+        attribute: KnownAttribute,
+    },
+    AttributeNotApplicable {
+        attribute: KnownAttribute,
+        target: AttributeTarget,
+    },
+    DuplicateAttribute {
+        attribute: KnownAttribute,
+    },
 }
 
 struct SyntaxRuleViolation {
@@ -18,21 +33,27 @@ struct SyntaxRuleViolation {
 }
 
 #[must_use]
-pub fn check_file(file: &SyntaxParsedFile) -> PhaseOutput<()> {
+pub fn check_file(file: &SyntaxParsedFile, source_text: &str) -> PhaseOutput<()> {
     let mut violations = Vec::new();
     check_import_order(file, &mut violations);
-    check_doc_comment_placement(file, &mut violations);
-    let diagnostics = render_diagnostics(&violations);
-    let status = if diagnostics.is_empty() {
-        PhaseStatus::Ok
-    } else {
+    check_doc_comment_placement(file, &LineIndex::new(source_text), &mut violations);
+    check_attributes(file, &mut violations);
+    let mut diagnostics = render_diagnostics(&violations);
+    let mut safe_autofixes = Vec::new();
+    if let Some((diagnostic, safe_autofix)) = check_import_sorting(file, source_text) {
+        diagnostics.push(diagnostic);
+        safe_autofixes.push(safe_autofix);
+    }
+    let status = if diagnostics.iter().any(PhaseDiagnostic::is_error) {
         PhaseStatus::PreventsDownstreamExecution
+    } else {
+        PhaseStatus::Ok
     };
 
     PhaseOutput {
         value: (),
         diagnostics,
-        safe_autofixes: Vec::new(),
+        safe_autofixes,
         status,
     }
 }
@@ -41,19 +62,103 @@ fn render_diagnostics(violations: &[SyntaxRuleViolation]) -> Vec<PhaseDiagnostic
     violations
         .iter()
         .map(|violation| {
-            let message = match violation.kind {
+            let message = match &violation.kind {
                 SyntaxRuleViolationKind::ImportAfterDeclaration => {
-                    "import declarations must appear before top-level declarations"
+                    "import declarations must appear before top-level declarations".to_string()
                 }
                 SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration => {
-                    "doc comment must document a declaration"
+                    "doc comment must document a declaration".to_string()
+                }
+                SyntaxRuleViolationKind::UnknownAttribute { name } => {
+                    format!("unknown attribute '@{name}'")
+                }
+                SyntaxRuleViolationKind::AttributeArgumentCount { attribute } => {
+                    let arguments = match attribute.argument_count() {
+                        0 => "no arguments".to_string(),
+                        1 => "one string argument".to_string(),
+                        count => format!("{count} string arguments"),
+                    };
+                    format!("'@{}' takes {arguments}", attribute.name())
+                }
+                SyntaxRuleViolationKind::AttributeNotApplicable { attribute, target } => {
+                    format!(
+                        "'@{}' cannot be applied to a {} declaration",
+                        attribute.name(),
+                        target.name()
+                    )
+                }
+                SyntaxRuleViolationKind::DuplicateAttribute { attribute } => {
+                    format!("duplicate attribute '@{}'", attribute.name())
                 }
             };
-            PhaseDiagnostic::new(message, violation.span.clone())
+            PhaseDiagnostic::new(message, violation.span)
         })
         .collect()
 }
 
+fn check_attributes(file: &SyntaxParsedFile, violations: &mut Vec<SyntaxRuleViolation>) {
+    for declaration in file.top_level_declarations() {
+        let (attributes, target) = match declaration {
+            SyntaxDeclaration::Type(type_declaration) => {
+                (&type_declaration.attributes, AttributeTarget::Type)
+            }
+            SyntaxDeclaration::Function(function_declaration) => {
+                (&function_declaration.attributes, AttributeTarget::Function)
+            }
+            SyntaxDeclaration::Constant(constant_declaration) => {
+                (&constant_declaration.attributes, AttributeTarget::Constant)
+            }
+            SyntaxDeclaration::Import(_)
+            | SyntaxDeclaration::Exports(_)
+            | SyntaxDeclaration::Package(_)
+            | SyntaxDeclaration::Group(_)
+            | SyntaxDeclaration::Test(_)
+            | SyntaxDeclaration::StaticAssert(_) => continue,
+        };
+        check_declaration_attributes(attributes, target, violations);
+    }
+}
+
+fn check_declaration_attributes(
+    attributes: &[SyntaxAttribute],
+    target: AttributeTarget,
+    violations: &mut Vec<SyntaxRuleViolation>,
+) {
+    let mut seen_attributes = Vec::new();
+    for attribute in attributes {
+        let Some(known_attribute) = KnownAttribute::from_name(&attribute.name) else {
+            violations.push(SyntaxRuleViolation {
+                kind: SyntaxRuleViolationKind::UnknownAttribute {
+                    name: attribute.name.clone(),
+                },
+                span: attribute.span,
+            });
+            continue;
+        };
+        let kind = if seen_attributes.contains(&known_attribute) {
+            SyntaxRuleViolationKind::DuplicateAttribute {
+                attribute: known_attribute,
+            }
+        } else if !known_attribute.applies_to(target) {
+            SyntaxRuleViolationKind::AttributeNotApplicable {
+                attribute: known_attribute,
+                target,
+            }
+        } else if attribute.arguments.len() != known_attribute.argument_count() {
+            SyntaxRuleViolationKind::AttributeArgumentCount {
+                attribute: known_attribute,
+            }
+        } else {
+            seen_attributes.push(known_attribute);
+            continue;
+        };
+        violations.push(SyntaxRuleViolation {
+            kind,
+            span: attribute.span,
+        });
+    }
+}
+
 fn check_import_order(file: &SyntaxParsedFile, violations: &mut Vec<SyntaxRuleViolation>) {
     let mut saw_non_import_declaration = false;
     for declaration in file.top_level_declarations() {
@@ -62,31 +167,132 @@ fn check_import_order(file: &SyntaxParsedFile, violations: &mut Vec<SyntaxRuleVi
                 if saw_non_import_declaration {
                     violations.push(SyntaxRuleViolation {
                         kind: SyntaxRuleViolationKind::ImportAfterDeclaration,
-                        span: import_declaration.span.clone(),
+                        span: import_declaration.span,
                     });
                 }
             }
             SyntaxDeclaration::Exports(_)
+            | SyntaxDeclaration::Package(_)
             | SyntaxDeclaration::Type(_)
             | SyntaxDeclaration::Constant(_)
             | SyntaxDeclaration::Function(_)
             | SyntaxDeclaration::Group(_)
-            | SyntaxDeclaration::Test(_) => {
+            | SyntaxDeclaration::Test(_)
+            | SyntaxDeclaration::StaticAssert(_) => {
                 saw_non_import_declaration = true;
             }
         }
     }
 }
 
-fn check_doc_comment_placement(file: &SyntaxParsedFile, violations: &mut Vec<SyntaxRuleViolation>) {
-    check_file_item_doc_comments(&file.items, violations);
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportOriginGroup {
+    Standard,
+    External,
+    Workspace,
+}
+
+fn import_origin_group(package_path: &str) -> ImportOriginGroup {
+    let origin = package_path.split('/').next().unwrap_or(package_path);
+    match origin {
+        "std" => ImportOriginGroup::Standard,
+        "external" => ImportOriginGroup::External,
+        _ => ImportOriginGroup::Workspace,
+    }
+}
+
+// Imports are canonically grouped by origin (std, external, workspace), with a
+// blank line between groups, and sorted by package path within each group. The
+// leading import block is only rewritten when nothing but whitespace separates
+// its imports, so comments are never dropped. An unsorted block is reported as
+// a warning that carries the sorting fix.
+fn check_import_sorting(
+    file: &SyntaxParsedFile,
+    source_text: &str,
+) -> Option<(PhaseDiagnostic, SafeAutofix)> {
+    let mut imports = Vec::new();
+    for item in &file.items {
+        let SyntaxFileItem::Declaration(declaration) = item else {
+            break;
+        };
+        let SyntaxDeclaration::Import(import_declaration) = declaration.as_ref() else {
+            break;
+        };
+        imports.push(import_declaration);
+    }
+    let (Some(first_import), Some(last_import)) = (imports.first(), imports.last()) else {
+        return None;
+    };
+    for pair in imports.windows(2) {
+        if !source_text[pair[0].span.end..pair[1].span.start]
+            .trim()
+            .is_empty()
+        {
+            return None;
+        }
+    }
+    let start_byte_offset = first_import.span.start;
+    let end_byte_offset = last_import.span.end;
+    let original_text = &source_text[start_byte_offset..end_byte_offset];
+
+    let mut sorted_imports = imports.clone();
+    sorted_imports.sort_by(|left, right| {
+        import_origin_group(&left.package_path)
+            .cmp(&import_origin_group(&right.package_path))
+            .then_with(|| left.package_path.cmp(&right.package_path))
+    });
+    let mut replacement_text = String::new();
+    let mut previous_group = None;
+    for import_declaration in sorted_imports {
+        let group = import_origin_group(&import_declaration.package_path);
+        if let Some(previous_group) = previous_group {
+            replacement_text.push_str(if previous_group == group {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        previous_group = Some(group);
+        replacement_text.push_str(import_source_text(import_declaration, source_text));
+    }
+    if replacement_text == original_text {
+        return None;
+    }
+    let diagnostic = PhaseDiagnostic::warning(
+        "imports are not grouped by origin and sorted by package path",
+        Span::new(start_byte_offset, end_byte_offset),
+    );
+    let safe_autofix = SafeAutofix::from_text_edit(
+        "import_order",
+        TextEdit {
+            start_byte_offset,
+            end_byte_offset,
+            replacement_text,
+        },
+    );
+    Some((diagnostic, safe_autofix))
+}
+
+fn import_source_text<'a>(
+    import_declaration: &SyntaxImportDeclaration,
+    source_text: &'a str,
+) -> &'a str {
+    &source_text[import_declaration.span.start..import_declaration.span.end]
+}
+
+fn check_doc_comment_placement(
+    file: &SyntaxParsedFile,
+    line_index: &LineIndex,
+    violations: &mut Vec<SyntaxRuleViolation>,
+) {
+    check_file_item_doc_comments(&file.items, line_index, violations);
     for declaration in file.top_level_declarations() {
         match declaration {
             SyntaxDeclaration::Type(type_declaration) => {
                 let SyntaxTypeDeclarationKind::Struct { items } = &type_declaration.kind else {
                     continue;
                 };
-                check_struct_member_doc_comments(items, violations);
+                check_struct_member_doc_comments(items, line_index, violations);
                 for item in items {
                     let SyntaxStructMemberItem::Method(method_declaration) = item else {
                         continue;
@@ -107,13 +313,16 @@ fn check_doc_comment_placement(file: &SyntaxParsedFile, violations: &mut Vec<Syn
             }
             SyntaxDeclaration::Import(_)
             | SyntaxDeclaration::Exports(_)
-            | SyntaxDeclaration::Constant(_) => {}
+            | SyntaxDeclaration::Package(_)
+            | SyntaxDeclaration::Constant(_)
+            | SyntaxDeclaration::StaticAssert(_) => {}
         }
     }
 }
 
 fn check_file_item_doc_comments(
     items: &[SyntaxFileItem],
+    line_index: &LineIndex,
     violations: &mut Vec<SyntaxRuleViolation>,
 ) {
     for (index, item) in items.iter().enumerate() {
@@ -123,30 +332,49 @@ fn check_file_item_doc_comments(
         let Some(SyntaxFileItem::Declaration(declaration)) = items.get(index + 1) else {
             violations.push(SyntaxRuleViolation {
                 kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                span: doc_comment.span.clone(),
+                span: doc_comment.span,
             });
             continue;
         };
-        let declaration_line = match declaration.as_ref() {
-            SyntaxDeclaration::Import(import_declaration) => import_declaration.span.line,
-            SyntaxDeclaration::Exports(exports_declaration) => exports_declaration.span.line,
-            SyntaxDeclaration::Type(type_declaration) => type_declaration.span.line,
-            SyntaxDeclaration::Constant(constant_declaration) => constant_declaration.span.line,
-            SyntaxDeclaration::Function(function_declaration) => function_declaration.span.line,
-            SyntaxDeclaration::Group(group_declaration) => group_declaration.span.line,
-            SyntaxDeclaration::Test(test_declaration) => test_declaration.span.line,
+        // Attributes sit between a doc comment and the declaration it
+        // documents.
+        let declaration_start = match declaration.as_ref() {
+            SyntaxDeclaration::Import(import_declaration) => import_declaration.span.start,
+            SyntaxDeclaration::Exports(exports_declaration) => exports_declaration.span.start,
+            SyntaxDeclaration::Package(package_declaration) => package_declaration.span.start,
+            SyntaxDeclaration::Type(type_declaration) => {
+                first_attribute_start(&type_declaration.attributes, type_declaration.span)
+            }
+            SyntaxDeclaration::Constant(constant_declaration) => {
+                first_attribute_start(&constant_declaration.attributes, constant_declaration.span)
+            }
+            SyntaxDeclaration::Function(function_declaration) => {
+                first_attribute_start(&function_declaration.attributes, function_declaration.span)
+            }
+            SyntaxDeclaration::Group(group_declaration) => group_declaration.span.start,
+            SyntaxDeclaration::Test(test_declaration) => test_declaration.span.start,
+            SyntaxDeclaration::StaticAssert(static_assert_declaration) => {
+                static_assert_declaration.span.start
+            }
         };
-        if declaration_line != doc_comment.end_line + 1 {
+        if line_index.line(declaration_start) != line_index.line(doc_comment.span.end) + 1 {
             violations.push(SyntaxRuleViolation {
                 kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                span: doc_comment.span.clone(),
+                span: doc_comment.span,
             });
         }
     }
 }
 
+fn first_attribute_start(attributes: &[SyntaxAttribute], declaration_span: Span) -> usize {
+    attributes
+        .first()
+        .map_or(declaration_span.start, |attribute| attribute.span.start)
+}
+
 fn check_struct_member_doc_comments(
     items: &[SyntaxStructMemberItem],
+    line_index: &LineIndex,
     violations: &mut Vec<SyntaxRuleViolation>,
 ) {
     for (index, item) in items.iter().enumerate() {
@@ -156,29 +384,29 @@ fn check_struct_member_doc_comments(
         let Some(next_item) = items.get(index + 1) else {
             violations.push(SyntaxRuleViolation {
                 kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                span: doc_comment.span.clone(),
+                span: doc_comment.span,
             });
             continue;
         };
-        let declaration_line = match next_item {
+        let declaration_start = match next_item {
             SyntaxStructMemberItem::Field(field_declaration) => {
-                field_declaration.as_ref().span.line
+                field_declaration.as_ref().span.start
             }
             SyntaxStructMemberItem::Method(method_declaration) => {
-                method_declaration.as_ref().span.line
+                method_declaration.as_ref().span.start
             }
             SyntaxStructMemberItem::DocComment(_) => {
                 violations.push(SyntaxRuleViolation {
                     kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                    span: doc_comment.span.clone(),
+                    span: doc_comment.span,
                 });
                 continue;
             }
         };
-        if declaration_line != doc_comment.end_line + 1 {
+        if line_index.line(declaration_start) != line_index.line(doc_comment.span.end) + 1 {
             violations.push(SyntaxRuleViolation {
                 kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                span: doc_comment.span.clone(),
+                span: doc_comment.span,
             });
         }
     }
@@ -189,7 +417,7 @@ fn check_block_doc_comments(block: &SyntaxBlock, violations: &mut Vec<SyntaxRule
         match item {
             SyntaxBlockItem::DocComment(doc_comment) => violations.push(SyntaxRuleViolation {
                 kind: SyntaxRuleViolationKind::DocCommentMustDocumentDeclaration,
-                span: doc_comment.span.clone(),
+                span: doc_comment.span,
             }),
             SyntaxBlockItem::Statement(statement) => match statement {
                 SyntaxStatement::If {
@@ -202,7 +430,10 @@ fn check_block_doc_comments(block: &SyntaxBlock, violations: &mut Vec<SyntaxRule
                         check_block_doc_comments(block, violations);
                     }
                 }
-                SyntaxStatement::For { body, .. } => {
+                SyntaxStatement::Guard { else_block, .. } => {
+                    check_block_doc_comments(else_block, violations);
+                }
+                SyntaxStatement::For { body, .. } | SyntaxStatement::ForRange { body, .. } => {
                     check_block_doc_comments(body, violations);
                 }
                 SyntaxStatement::Binding { .. }
@@ -210,6 +441,7 @@ fn check_block_doc_comments(block: &SyntaxBlock, violations: &mut Vec<SyntaxRule
                 | SyntaxStatement::Return { .. }
                 | SyntaxStatement::Break { .. }
                 | SyntaxStatement::Continue { .. }
+                | SyntaxStatement::Defer { .. }
                 | SyntaxStatement::Expression { .. } => {}
             },
         }
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use compiler__semantic_types::Type;
 
 use super::TypeChecker;
@@ -64,38 +62,4 @@ impl TypeChecker<'_> {
             .iter()
             .any(|implemented_interface| implemented_interface.resolved_type == *expected_type)
     }
-
-    pub(super) fn normalize_union(types: Vec<Type>) -> Type {
-        let mut flat = Vec::new();
-        let mut seen = HashSet::new();
-        for value_type in types {
-            if let Type::Union(inner) = value_type {
-                for inner_type in inner {
-                    if matches!(inner_type, Type::Never) {
-                        continue;
-                    }
-                    let key = inner_type.display();
-                    if seen.insert(key) {
-                        flat.push(inner_type);
-                    }
-                }
-            } else {
-                if matches!(value_type, Type::Never) {
-                    continue;
-                }
-                let key = value_type.display();
-                if seen.insert(key) {
-                    flat.push(value_type);
-                }
-            }
-        }
-        if flat.is_empty() {
-            return Type::Never;
-        }
-        if flat.len() == 1 {
-            flat.remove(0)
-        } else {
-            Type::Union(flat)
-        }
-    }
 }
@@ -1,17 +1,20 @@
 use std::collections::HashSet;
 
+use compiler__diagnostics::{SuggestedEdit, SuggestedFix};
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__program_references::CallableReference;
 use compiler__semantic_program::{
-    SemanticConstantDeclaration, SemanticFunctionDeclaration, SemanticTypeDeclaration,
-    SemanticTypeDeclarationKind,
+    SemanticConstantDeclaration, SemanticFunctionDeclaration, SemanticTopLevelVisibility,
+    SemanticTypeDeclaration, SemanticTypeDeclarationKind,
 };
 use compiler__semantic_types::{
     GenericTypeParameter, ImportedTypeShape, NominalTypeId, NominalTypeRef, TypedFunctionSignature,
 };
+use compiler__source::Span;
 
 use super::{
     FunctionInfo, ImplementedInterfaceEntry, ImportedTypeDeclaration, InterfaceMethodSignature,
-    MethodInfo, MethodKey, TypeAnnotatedCallTarget, TypeAnnotatedCallableReference, TypeChecker,
-    TypeInfo, TypeKind,
+    MethodInfo, MethodKey, TypeAnnotatedCallTarget, TypeChecker, TypeInfo, TypeKind,
 };
 
 struct ImportedTypeBinding {
@@ -44,7 +47,7 @@ impl TypeChecker<'_> {
             else {
                 continue;
             };
-            if self.types.contains_key(&imported_binding.local_name) {
+            if self.types.contains_local_name(&imported_binding.local_name) {
                 continue;
             }
             let kind = match &imported_binding.type_declaration.kind {
@@ -88,7 +91,10 @@ impl TypeChecker<'_> {
                         seen.insert(field_name.clone());
                         resolved_fields.push((field_name.clone(), field_type.clone()));
                     }
-                    if let Some(info) = self.types.get_mut(&imported_binding.local_name) {
+                    if let Some(info) = self
+                        .types
+                        .get_by_local_name_mut(&imported_binding.local_name)
+                    {
                         info.kind = TypeKind::Struct {
                             fields: resolved_fields,
                         };
@@ -105,11 +111,15 @@ impl TypeChecker<'_> {
                         resolved_methods.push(InterfaceMethodSignature {
                             name: method.name.clone(),
                             self_mutable: method.self_mutable,
+                            parameter_names: method.parameter_names.clone(),
                             parameter_types: method.parameter_types.clone(),
                             return_type: method.return_type.clone(),
                         });
                     }
-                    if let Some(info) = self.types.get_mut(&imported_binding.local_name) {
+                    if let Some(info) = self
+                        .types
+                        .get_by_local_name_mut(&imported_binding.local_name)
+                    {
                         info.kind = TypeKind::Interface {
                             methods: resolved_methods,
                         };
@@ -125,7 +135,10 @@ impl TypeChecker<'_> {
                         }
                         resolved_variants.push(variant.clone());
                     }
-                    if let Some(info) = self.types.get_mut(&imported_binding.local_name) {
+                    if let Some(info) = self
+                        .types
+                        .get_by_local_name_mut(&imported_binding.local_name)
+                    {
                         info.kind = TypeKind::Union {
                             variants: resolved_variants,
                         };
@@ -161,9 +174,13 @@ impl TypeChecker<'_> {
                     parameter_types: imported_binding.signature.parameter_types,
                     return_type: imported_binding.signature.return_type,
                     call_target: TypeAnnotatedCallTarget::UserDefinedFunction {
-                        callable_reference: TypeAnnotatedCallableReference {
-                            package_path: imported_binding_info.imported_package_path.clone(),
-                            symbol_name: imported_binding_info.imported_symbol_name.clone(),
+                        callable_reference: CallableReference {
+                            package_path: PackagePath::new(
+                                &imported_binding_info.imported_package_path,
+                            ),
+                            symbol_name: SymbolName::new(
+                                &imported_binding_info.imported_symbol_name,
+                            ),
                         },
                     },
                 },
@@ -212,18 +229,18 @@ impl TypeChecker<'_> {
         }
     }
 
-    pub(super) fn collect_type_declarations(&mut self, types: &[SemanticTypeDeclaration]) {
+    pub(super) fn collect_type_declarations(&mut self, types: &[&SemanticTypeDeclaration]) {
         self.collect_type_declaration_headers(types);
         self.resolve_type_declaration_definitions(types);
     }
 
-    fn collect_type_declaration_headers(&mut self, types: &[SemanticTypeDeclaration]) {
+    fn collect_type_declaration_headers(&mut self, types: &[&SemanticTypeDeclaration]) {
         for type_declaration in types {
             self.check_type_name(&type_declaration.name, &type_declaration.name_span);
-            if self.types.contains_key(&type_declaration.name) {
+            if self.types.contains_local_name(&type_declaration.name) {
                 self.error(
                     format!("duplicate type '{}'", type_declaration.name),
-                    type_declaration.span.clone(),
+                    type_declaration.span,
                 );
                 continue;
             }
@@ -262,12 +279,12 @@ impl TypeChecker<'_> {
         }
     }
 
-    fn resolve_type_declaration_definitions(&mut self, types: &[SemanticTypeDeclaration]) {
+    fn resolve_type_declaration_definitions(&mut self, types: &[&SemanticTypeDeclaration]) {
         for type_declaration in types {
             let names_and_spans = type_declaration
                 .type_parameters
                 .iter()
-                .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+                .map(|parameter| (parameter.name.clone(), parameter.span))
                 .collect::<Vec<_>>();
             self.push_type_parameters(&names_and_spans);
             let resolved_type_parameters = type_declaration
@@ -287,11 +304,11 @@ impl TypeChecker<'_> {
                 .implemented_interfaces
                 .iter()
                 .map(|implemented_interface| ImplementedInterfaceEntry {
-                    source_span: Some(implemented_interface.span.clone()),
+                    source_span: Some(implemented_interface.span),
                     resolved_type: self.resolve_type_name(implemented_interface),
                 })
                 .collect::<Vec<_>>();
-            if let Some(info) = self.types.get_mut(&type_declaration.name) {
+            if let Some(info) = self.types.get_by_local_name_mut(&type_declaration.name) {
                 info.type_parameters = resolved_type_parameters;
                 info.implemented_interface_entries = resolved_implemented_interface_entries;
             }
@@ -307,7 +324,7 @@ impl TypeChecker<'_> {
                                     "duplicate field '{}' in '{}'",
                                     field.name, type_declaration.name
                                 ),
-                                field.span.clone(),
+                                field.span,
                             );
                             continue;
                         }
@@ -315,7 +332,7 @@ impl TypeChecker<'_> {
                         let field_type = self.resolve_type_name(&field.type_name);
                         resolved_fields.push((field.name.clone(), field_type));
                     }
-                    if let Some(info) = self.types.get_mut(&type_declaration.name) {
+                    if let Some(info) = self.types.get_by_local_name_mut(&type_declaration.name) {
                         info.kind = TypeKind::Struct {
                             fields: resolved_fields,
                         };
@@ -332,7 +349,7 @@ impl TypeChecker<'_> {
                                     "duplicate method '{}.{}'",
                                     type_declaration.name, method.name
                                 ),
-                                method.name_span.clone(),
+                                method.name_span,
                             );
                             continue;
                         }
@@ -346,11 +363,16 @@ impl TypeChecker<'_> {
                         resolved_methods.push(InterfaceMethodSignature {
                             name: method.name.clone(),
                             self_mutable: method.self_mutable,
+                            parameter_names: method
+                                .parameters
+                                .iter()
+                                .map(|parameter| parameter.name.clone())
+                                .collect(),
                             parameter_types,
                             return_type,
                         });
                     }
-                    if let Some(info) = self.types.get_mut(&type_declaration.name) {
+                    if let Some(info) = self.types.get_by_local_name_mut(&type_declaration.name) {
                         info.kind = TypeKind::Interface {
                             methods: resolved_methods,
                         };
@@ -363,7 +385,7 @@ impl TypeChecker<'_> {
                                 "enum type '{}' cannot declare type parameters",
                                 type_declaration.name
                             ),
-                            type_declaration.span.clone(),
+                            type_declaration.span,
                         );
                     }
                     let mut resolved_variants = Vec::new();
@@ -372,7 +394,7 @@ impl TypeChecker<'_> {
                         if seen.contains(variant.name.as_str()) {
                             self.error(
                                 format!("duplicate enum variant '{}'", variant.name),
-                                variant.span.clone(),
+                                variant.span,
                             );
                             continue;
                         }
@@ -393,7 +415,7 @@ impl TypeChecker<'_> {
                             ),
                         }));
                     }
-                    if let Some(info) = self.types.get_mut(&type_declaration.name) {
+                    if let Some(info) = self.types.get_by_local_name_mut(&type_declaration.name) {
                         info.kind = TypeKind::Union {
                             variants: resolved_variants,
                         };
@@ -404,22 +426,19 @@ impl TypeChecker<'_> {
                     let mut seen = HashSet::new();
                     for variant in variants {
                         if variant.names.len() != 1 {
-                            self.error("union variants must be single types", variant.span.clone());
+                            self.error("union variants must be single types", variant.span);
                             continue;
                         }
                         let variant_type = self.resolve_type_name(variant);
                         let key = variant_type.display();
                         if seen.contains(key.as_str()) {
-                            self.error(
-                                format!("duplicate union variant '{key}'"),
-                                variant.span.clone(),
-                            );
+                            self.error(format!("duplicate union variant '{key}'"), variant.span);
                             continue;
                         }
                         seen.insert(key);
                         resolved_variants.push(variant_type);
                     }
-                    if let Some(info) = self.types.get_mut(&type_declaration.name) {
+                    if let Some(info) = self.types.get_by_local_name_mut(&type_declaration.name) {
                         info.kind = TypeKind::Union {
                             variants: resolved_variants,
                         };
@@ -432,14 +451,14 @@ impl TypeChecker<'_> {
 
     pub(super) fn collect_function_signatures(
         &mut self,
-        functions: &[SemanticFunctionDeclaration],
+        functions: &[&SemanticFunctionDeclaration],
     ) {
         for function in functions {
             self.check_function_name(&function.name, &function.name_span);
             if self.functions.contains_key(&function.name) {
                 self.error(
                     format!("duplicate function '{}'", function.name),
-                    function.name_span.clone(),
+                    function.name_span,
                 );
                 continue;
             }
@@ -447,7 +466,7 @@ impl TypeChecker<'_> {
             let names_and_spans = function
                 .type_parameters
                 .iter()
-                .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+                .map(|parameter| (parameter.name.clone(), parameter.span))
                 .collect::<Vec<_>>();
             self.push_type_parameters(&names_and_spans);
             let return_type = self.resolve_type_name(&function.return_type);
@@ -479,9 +498,9 @@ impl TypeChecker<'_> {
                     parameter_types,
                     return_type,
                     call_target: TypeAnnotatedCallTarget::UserDefinedFunction {
-                        callable_reference: TypeAnnotatedCallableReference {
-                            package_path: self.package_path.clone(),
-                            symbol_name: function.name.clone(),
+                        callable_reference: CallableReference {
+                            package_path: PackagePath::new(&self.package_path),
+                            symbol_name: SymbolName::new(&function.name),
                         },
                     },
                 },
@@ -489,14 +508,14 @@ impl TypeChecker<'_> {
         }
     }
 
-    pub(super) fn collect_method_signatures(&mut self, types: &[SemanticTypeDeclaration]) {
+    pub(super) fn collect_method_signatures(&mut self, types: &[&SemanticTypeDeclaration]) {
         for type_declaration in types {
             match &type_declaration.kind {
                 SemanticTypeDeclarationKind::Struct { methods, .. } => {
                     let names_and_spans = type_declaration
                         .type_parameters
                         .iter()
-                        .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+                        .map(|parameter| (parameter.name.clone(), parameter.span))
                         .collect::<Vec<_>>();
                     self.push_type_parameters(&names_and_spans);
 
@@ -515,7 +534,7 @@ impl TypeChecker<'_> {
                                     "duplicate method '{}.{}'",
                                     type_declaration.name, method.name
                                 ),
-                                method.name_span.clone(),
+                                method.name_span,
                             );
                             continue;
                         }
@@ -542,7 +561,7 @@ impl TypeChecker<'_> {
                     let names_and_spans = type_declaration
                         .type_parameters
                         .iter()
-                        .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+                        .map(|parameter| (parameter.name.clone(), parameter.span))
                         .collect::<Vec<_>>();
                     self.push_type_parameters(&names_and_spans);
 
@@ -589,7 +608,7 @@ impl TypeChecker<'_> {
 
     pub(super) fn check_constant_declarations(
         &mut self,
-        constants: &[SemanticConstantDeclaration],
+        constants: &[&SemanticConstantDeclaration],
     ) {
         for constant in constants {
             self.check_constant_name(&constant.name, &constant.name_span);
@@ -598,7 +617,7 @@ impl TypeChecker<'_> {
             if self.constants.contains_key(&constant.name) {
                 self.error(
                     format!("duplicate constant '{name}'", name = constant.name),
-                    constant.span.clone(),
+                    constant.span,
                 );
                 continue;
             }
@@ -612,9 +631,14 @@ impl TypeChecker<'_> {
                         declared_type.display(),
                         value_type.display()
                     ),
-                    constant.span.clone(),
+                    constant.span,
                 );
             }
+            if constant.visibility == SemanticTopLevelVisibility::Private
+                && value_type == declared_type
+            {
+                self.record_constant_inline_initializer(constant);
+            }
             self.constants.insert(
                 constant.name.clone(),
                 super::ConstantInfo {
@@ -628,7 +652,7 @@ impl TypeChecker<'_> {
         }
     }
 
-    pub(super) fn check_type_interface_conformance(&mut self, types: &[SemanticTypeDeclaration]) {
+    pub(super) fn check_type_interface_conformance(&mut self, types: &[&SemanticTypeDeclaration]) {
         for type_declaration in types {
             if !matches!(
                 type_declaration.kind,
@@ -640,7 +664,7 @@ impl TypeChecker<'_> {
                         "only struct types can declare implements clauses; '{}' is not a struct",
                         type_declaration.name
                     ),
-                    type_declaration.implemented_interfaces[0].span.clone(),
+                    type_declaration.implemented_interfaces[0].span,
                 );
                 continue;
             }
@@ -655,22 +679,25 @@ impl TypeChecker<'_> {
     }
 
     fn check_struct_interface_conformance(&mut self, type_declaration: &SemanticTypeDeclaration) {
-        let struct_type_info = self.types.get(&type_declaration.name).cloned();
+        let struct_type_info = self
+            .types
+            .get_by_local_name(&type_declaration.name)
+            .cloned();
         let Some(struct_type_info) = struct_type_info else {
             return;
         };
         let struct_type_id = struct_type_info.nominal_type_id;
         let implemented_interface_entries = struct_type_info.implemented_interface_entries;
-        let fallback_span = type_declaration.implemented_interfaces.first().map_or_else(
-            || type_declaration.span.clone(),
-            |interface| interface.span.clone(),
-        );
+        let fallback_span = type_declaration
+            .implemented_interfaces
+            .first()
+            .map_or_else(|| type_declaration.span, |interface| interface.span);
         let mut seen_interface_names = HashSet::new();
         for implemented_interface_entry in implemented_interface_entries {
             let implemented_interface_type = implemented_interface_entry.resolved_type;
             let diagnostic_span = implemented_interface_entry
                 .source_span
-                .unwrap_or_else(|| fallback_span.clone());
+                .unwrap_or(fallback_span);
             if implemented_interface_type == super::Type::Unknown {
                 continue;
             }
@@ -678,7 +705,7 @@ impl TypeChecker<'_> {
             if seen_interface_names.contains(interface_name.as_str()) {
                 self.error(
                     format!("duplicate implements entry '{interface_name}'"),
-                    diagnostic_span.clone(),
+                    diagnostic_span,
                 );
                 continue;
             }
@@ -689,7 +716,7 @@ impl TypeChecker<'_> {
             else {
                 self.error(
                     format!("implemented type '{interface_name}' must be an interface declaration"),
-                    diagnostic_span.clone(),
+                    diagnostic_span,
                 );
                 continue;
             };
@@ -700,7 +727,7 @@ impl TypeChecker<'_> {
             let TypeKind::Interface { methods } = &interface_type_info.kind else {
                 self.error(
                     format!("implemented type '{interface_name}' must be an interface declaration"),
-                    diagnostic_span.clone(),
+                    diagnostic_span,
                 );
                 continue;
             };
@@ -712,12 +739,15 @@ impl TypeChecker<'_> {
                     method_name: interface_method.name.clone(),
                 };
                 let Some(struct_method) = self.methods.get(&method_key) else {
-                    self.error(
+                    let suggested_fix = self
+                        .interface_method_stub_suggested_fix(type_declaration, &interface_method);
+                    self.error_with_suggested_fix(
                         format!(
                             "type '{}' does not implement interface '{}': missing method '{}'",
                             type_declaration.name, interface_name, interface_method.name
                         ),
-                        diagnostic_span.clone(),
+                        diagnostic_span,
+                        suggested_fix,
                     );
                     continue;
                 };
@@ -730,13 +760,69 @@ impl TypeChecker<'_> {
                             "type '{}' method '{}' does not match interface '{}'",
                             type_declaration.name, interface_method.name, interface_name
                         ),
-                        diagnostic_span.clone(),
+                        diagnostic_span,
                     );
                 }
             }
         }
     }
 
+    // The stub is inserted as the last struct member, after the closing
+    // delimiter of the previous member, in the formatter's member layout.
+    // Parameters are underscore-prefixed because the stub body does not use
+    // them.
+    fn interface_method_stub_suggested_fix(
+        &self,
+        type_declaration: &SemanticTypeDeclaration,
+        interface_method: &InterfaceMethodSignature,
+    ) -> SuggestedFix {
+        let closing_brace_offset = type_declaration.span.end.saturating_sub(1);
+        let body_prefix = self.source_text[..closing_brace_offset].trim_end();
+        let insertion_offset = body_prefix.len();
+        let member_separator = if body_prefix.ends_with(['{', ',']) {
+            ""
+        } else {
+            ","
+        };
+        let self_parameter = if interface_method.self_mutable {
+            "mut self"
+        } else {
+            "self"
+        };
+        let parameters = std::iter::once(self_parameter.to_string())
+            .chain(
+                interface_method
+                    .parameter_names
+                    .iter()
+                    .zip(&interface_method.parameter_types)
+                    .map(|(name, parameter_type)| {
+                        let unused_prefix = if name.starts_with('_') { "" } else { "_" };
+                        format!("{unused_prefix}{name}: {}", parameter_type.display())
+                    }),
+            )
+            .collect::<Vec<_>>()
+            .join(", ");
+        let stub = format!(
+            "    function {}({parameters}) -> {} {{\n        abort(\"not implemented\")\n    }},",
+            interface_method.name,
+            interface_method.return_type.display(),
+        );
+        let closing_newline =
+            if self.source_text[insertion_offset..closing_brace_offset].contains('\n') {
+                ""
+            } else {
+                "\n"
+            };
+        let replacement_text = format!("{member_separator}\n{stub}{closing_newline}");
+        SuggestedFix {
+            label: format!("add stub for method '{}'", interface_method.name),
+            edits: vec![SuggestedEdit {
+                span: Span::new(insertion_offset, insertion_offset),
+                replacement_text,
+            }],
+        }
+    }
+
     fn resolve_type_parameter_constraint_as_interface(
         &mut self,
         constraint: &compiler__semantic_program::SemanticTypeName,
@@ -751,7 +837,7 @@ impl TypeChecker<'_> {
                 format!(
                     "constraint for type parameter '{type_parameter_name}' must be an interface type"
                 ),
-                constraint.span.clone(),
+                constraint.span,
             );
             return super::Type::Unknown;
         };
@@ -764,7 +850,7 @@ impl TypeChecker<'_> {
                 format!(
                     "constraint for type parameter '{type_parameter_name}' must be an interface type"
                 ),
-                constraint.span.clone(),
+                constraint.span,
             );
             return super::Type::Unknown;
         }
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use compiler__program_references::{ConstantReference, EnumVariantReference, StructReference};
+use compiler__semantic_program::SemanticExpressionId;
+use compiler__semantic_types::Type;
+use compiler__type_annotated_program::{
+    TypeAnnotatedCallTarget, TypeAnnotatedResolvedTypeArgument,
+};
+
+// What the checker learned about individual expressions, recorded while
+// checking and read back when building the type-annotated program. A new kind
+// of per-expression annotation is a field with a record method and a lookup
+// here, not another parameter on every annotation-building function.
+// Calls answered from the variants of an enum. `Enum.all()` lists them in
+// declaration order and `Enum.count()` counts them, while `variant_name` and
+// `Enum.from_string` map between the variants and their names at runtime.
+#[derive(Clone)]
+pub(super) enum EnumCall {
+    All(Vec<EnumVariantReference>),
+    Count(usize),
+    VariantName(Vec<EnumVariantReference>),
+    FromString(Vec<EnumVariantReference>),
+}
+
+#[derive(Default)]
+pub(super) struct ExpressionAnnotations {
+    annotation_by_expression_id: BTreeMap<SemanticExpressionId, ExpressionAnnotation>,
+}
+
+#[derive(Default)]
+struct ExpressionAnnotation {
+    resolved_type: Option<Type>,
+    call_target: Option<TypeAnnotatedCallTarget>,
+    constant_reference: Option<ConstantReference>,
+    resolved_type_argument_types: Option<Vec<TypeAnnotatedResolvedTypeArgument>>,
+    struct_reference: Option<StructReference>,
+    enum_variant_reference: Option<EnumVariantReference>,
+    enum_call: Option<EnumCall>,
+    format_template_parts: Option<Vec<String>>,
+    assertion_description: Option<String>,
+    operation_description: Option<String>,
+}
+
+impl ExpressionAnnotations {
+    pub(super) fn record_resolved_type(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        resolved_type: Type,
+    ) {
+        self.annotation_mut(expression_id).resolved_type = Some(resolved_type);
+    }
+
+    pub(super) fn record_call_target(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        call_target: TypeAnnotatedCallTarget,
+    ) {
+        self.annotation_mut(expression_id).call_target = Some(call_target);
+    }
+
+    pub(super) fn record_constant_reference(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        constant_reference: ConstantReference,
+    ) {
+        self.annotation_mut(expression_id).constant_reference = Some(constant_reference);
+    }
+
+    pub(super) fn record_resolved_type_argument_types(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        resolved_type_argument_types: Vec<TypeAnnotatedResolvedTypeArgument>,
+    ) {
+        self.annotation_mut(expression_id)
+            .resolved_type_argument_types = Some(resolved_type_argument_types);
+    }
+
+    pub(super) fn record_struct_reference(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        struct_reference: StructReference,
+    ) {
+        self.annotation_mut(expression_id).struct_reference = Some(struct_reference);
+    }
+
+    pub(super) fn record_enum_variant_reference(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        enum_variant_reference: EnumVariantReference,
+    ) {
+        self.annotation_mut(expression_id).enum_variant_reference = Some(enum_variant_reference);
+    }
+
+    pub(super) fn record_enum_call(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        enum_call: EnumCall,
+    ) {
+        self.annotation_mut(expression_id).enum_call = Some(enum_call);
+    }
+
+    pub(super) fn record_format_template_parts(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        format_template_parts: Vec<String>,
+    ) {
+        self.annotation_mut(expression_id).format_template_parts = Some(format_template_parts);
+    }
+
+    pub(super) fn record_assertion_description(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        assertion_description: String,
+    ) {
+        self.annotation_mut(expression_id).assertion_description = Some(assertion_description);
+    }
+
+    pub(super) fn record_operation_description(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        operation_description: String,
+    ) {
+        self.annotation_mut(expression_id).operation_description = Some(operation_description);
+    }
+
+    pub(super) fn resolved_type(&self, expression_id: SemanticExpressionId) -> Option<&Type> {
+        self.annotation(expression_id)?.resolved_type.as_ref()
+    }
+
+    pub(super) fn call_target(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&TypeAnnotatedCallTarget> {
+        self.annotation(expression_id)?.call_target.as_ref()
+    }
+
+    pub(super) fn constant_reference(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&ConstantReference> {
+        self.annotation(expression_id)?.constant_reference.as_ref()
+    }
+
+    pub(super) fn resolved_type_argument_types(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&Vec<TypeAnnotatedResolvedTypeArgument>> {
+        self.annotation(expression_id)?
+            .resolved_type_argument_types
+            .as_ref()
+    }
+
+    pub(super) fn struct_reference(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&StructReference> {
+        self.annotation(expression_id)?.struct_reference.as_ref()
+    }
+
+    pub(super) fn enum_variant_reference(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&EnumVariantReference> {
+        self.annotation(expression_id)?
+            .enum_variant_reference
+            .as_ref()
+    }
+
+    pub(super) fn enum_call(&self, expression_id: SemanticExpressionId) -> Option<&EnumCall> {
+        self.annotation(expression_id)?.enum_call.as_ref()
+    }
+
+    pub(super) fn format_template_parts(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&[String]> {
+        self.annotation(expression_id)?
+            .format_template_parts
+            .as_deref()
+    }
+
+    pub(super) fn assertion_description(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&str> {
+        self.annotation(expression_id)?
+            .assertion_description
+            .as_deref()
+    }
+
+    pub(super) fn operation_description(
+        &self,
+        expression_id: SemanticExpressionId,
+    ) -> Option<&str> {
+        self.annotation(expression_id)?
+            .operation_description
+            .as_deref()
+    }
+
+    fn annotation(&self, expression_id: SemanticExpressionId) -> Option<&ExpressionAnnotation> {
+        self.annotation_by_expression_id.get(&expression_id)
+    }
+
+    fn annotation_mut(&mut self, expression_id: SemanticExpressionId) -> &mut ExpressionAnnotation {
+        self.annotation_by_expression_id
+            .entry(expression_id)
+            .or_default()
+    }
+}
@@ -1,18 +1,26 @@
 use std::collections::HashMap;
+use std::fmt::Write;
 
+use compiler__diagnostics::{SuggestedEdit, SuggestedFix};
 use compiler__fix_edits::TextEdit;
+use compiler__interned_names::{PackagePath, SymbolName};
+use compiler__program_references::{EnumVariantReference, NominalTypeReference, StructReference};
 use compiler__safe_autofix::SafeAutofix;
 use compiler__semantic_program::{
-    SemanticBinaryOperator, SemanticExpression, SemanticMatchArm, SemanticMatchPattern,
-    SemanticStructLiteralField, SemanticTypeName, SemanticUnaryOperator,
+    SemanticBinaryOperator, SemanticBlock, SemanticExpression, SemanticExpressionId,
+    SemanticMatchArm, SemanticMatchLiteral, SemanticMatchPattern, SemanticStructLiteralField,
+    SemanticTypeName, SemanticUnaryOperator,
 };
-use compiler__source::Span;
+use compiler__source::{LanguageFeature, LineIndex, Span};
 
-use compiler__semantic_types::{GenericTypeParameter, NominalTypeId, Type};
+use compiler__semantic_types::{
+    GenericTypeParameter, NominalTypeId, NominalTypeRef, Type, normalize_union,
+};
 
+use super::expression_annotations::EnumCall;
 use super::{
-    ExpressionSpan, MethodKey, TypeAnnotatedCallTarget, TypeAnnotatedEnumVariantReference,
-    TypeAnnotatedStructReference, TypeChecker, TypeKind,
+    ExpressionSpan, MethodKey, NarrowingBranch, StatementSpan, TypeAnnotatedCallTarget,
+    TypeAnnotatedMethodReference, TypeChecker, TypeKind,
 };
 
 struct InstantiatedFunctionSignature {
@@ -31,12 +39,13 @@ struct ResolvedCallTarget {
 
 struct ResolvedStructFields {
     struct_display_name: String,
-    struct_reference: TypeAnnotatedStructReference,
+    struct_reference: StructReference,
     fields: Vec<(String, Type)>,
 }
 
 impl TypeChecker<'_> {
     pub(super) fn check_expression(&mut self, expression: &SemanticExpression) -> Type {
+        let expected_type = self.expected_expression_type.take();
         let resolved_type = match expression {
             SemanticExpression::IntegerLiteral { .. } => Type::Integer64,
             SemanticExpression::NilLiteral { .. } => Type::Nil,
@@ -65,52 +74,38 @@ impl TypeChecker<'_> {
                 field_span,
                 ..
             } => {
-                if let SemanticExpression::NameReference { name, .. } = target.as_ref() {
-                    let is_enum_like_union = self.types.get(name).is_some_and(|info| {
-                        if let TypeKind::Union { variants } = &info.kind {
-                            let enum_like_prefix = format!("{name}.");
-                            !variants.is_empty()
-                                && variants.iter().all(|variant| {
-                                    matches!(
-                                        variant,
-                                        Type::Named(named)
-                                            if named.display_name.starts_with(&enum_like_prefix)
-                                    )
-                                })
-                        } else {
-                            false
-                        }
-                    });
-                    if is_enum_like_union {
-                        if let Some(variant_type) = self.resolve_enum_variant_type(name, field) {
-                            self.enum_variant_reference_by_expression_id.insert(
-                                *id,
-                                TypeAnnotatedEnumVariantReference {
-                                    enum_name: name.clone(),
-                                    variant_name: field.clone(),
-                                },
-                            );
-                            return variant_type;
-                        }
-                        self.error(
-                            format!("unknown enum variant '{name}.{field}'"),
-                            field_span.clone(),
+                if let SemanticExpression::NameReference { name, .. } = target.as_ref()
+                    && self.enum_like_union_variants(name).is_some()
+                {
+                    if let Some(variant_type) = self.resolve_enum_variant_type(name, field) {
+                        self.record_type_reference(name, target.span());
+                        self.report_deprecated_use(name, target.span());
+                        self.expression_annotations.record_enum_variant_reference(
+                            *id,
+                            EnumVariantReference {
+                                enum_name: name.clone(),
+                                variant_name: field.clone(),
+                            },
                         );
-                        return Type::Unknown;
+                        return variant_type;
                     }
+                    self.error(
+                        format!("unknown enum variant '{name}.{field}'"),
+                        *field_span,
+                    );
+                    return Type::Unknown;
                 }
                 let target_type = self.check_expression(target);
                 if let Type::List(_) = target_type {
                     if field == "length" {
                         return Type::Integer64;
                     }
-                    self.error(
-                        format!("unknown property 'List.{field}'"),
-                        field_span.clone(),
-                    );
+                    self.error(format!("unknown property 'List.{field}'"), *field_span);
                     return Type::Unknown;
                 }
-                self.resolve_field_access_type(&target_type, field, field_span)
+                let field_type = self.resolve_field_access_type(&target_type, field, field_span);
+                self.lookup_field_narrowing(target, field)
+                    .unwrap_or(field_type)
             }
             SemanticExpression::IndexAccess { target, index, .. } => {
                 let target_type = self.check_expression(target);
@@ -130,6 +125,15 @@ impl TypeChecker<'_> {
                     }
                 }
             }
+            SemanticExpression::Call {
+                id,
+                callee,
+                type_arguments,
+                arguments,
+                span,
+            } if self.is_enum_type_call(callee) => {
+                self.check_enum_type_call(*id, callee, type_arguments, arguments, *span)
+            }
             SemanticExpression::Call {
                 callee,
                 type_arguments,
@@ -137,9 +141,21 @@ impl TypeChecker<'_> {
                 span,
                 ..
             } => {
+                let expected_argument_types = self.non_generic_callee_parameter_types(callee);
                 let argument_types = arguments
                     .iter()
-                    .map(|argument| self.check_expression(argument))
+                    .enumerate()
+                    .map(|(index, argument)| {
+                        match expected_argument_types
+                            .as_ref()
+                            .and_then(|parameter_types| parameter_types.get(index))
+                        {
+                            Some(parameter_type) => {
+                                self.check_expression_with_expected_type(argument, parameter_type)
+                            }
+                            None => self.check_expression(argument),
+                        }
+                    })
                     .collect::<Vec<_>>();
                 let resolved_target = if let SemanticExpression::NameReference {
                     id,
@@ -148,13 +164,70 @@ impl TypeChecker<'_> {
                     ..
                 } = callee.as_ref()
                 {
+                    self.check_builtin_call_is_allowed(name, *span);
                     if self.name_reference_resolves_to_value_binding(name) {
                         None
+                    } else if let Some(info) = self.functions.get(name).cloned() {
+                        self.record_function_reference(name, span);
+                        self.report_deprecated_use(name, *span);
+                        let instantiated = self.instantiate_function_call_signature(
+                            name,
+                            &info.type_parameters,
+                            &info.parameter_types,
+                            &info.return_type,
+                            type_arguments,
+                            &argument_types,
+                            expected_type.as_ref(),
+                            span,
+                        );
+                        let resolved_target = ResolvedCallTarget {
+                            display_name: name.clone(),
+                            parameter_types: instantiated.parameter_types,
+                            return_type: instantiated.return_type,
+                            resolved_type_arguments: instantiated.resolved_type_arguments,
+                            call_target: Some(info.call_target.clone()),
+                        };
+                        self.expression_annotations.record_resolved_type(
+                            *id,
+                            Type::Function {
+                                parameter_types: resolved_target.parameter_types.clone(),
+                                return_type: Box::new(resolved_target.return_type.clone()),
+                            },
+                        );
+                        Some(resolved_target)
+                    } else if let Some(info) = self.imported_functions.get(name).cloned() {
+                        self.mark_import_used(name);
+                        self.report_deprecated_use(name, *span);
+                        let instantiated = self.instantiate_function_call_signature(
+                            name,
+                            &info.type_parameters,
+                            &info.parameter_types,
+                            &info.return_type,
+                            type_arguments,
+                            &argument_types,
+                            expected_type.as_ref(),
+                            span,
+                        );
+                        let resolved_target = ResolvedCallTarget {
+                            display_name: name.clone(),
+                            parameter_types: instantiated.parameter_types,
+                            return_type: instantiated.return_type,
+                            resolved_type_arguments: instantiated.resolved_type_arguments,
+                            call_target: Some(info.call_target.clone()),
+                        };
+                        self.expression_annotations.record_resolved_type(
+                            *id,
+                            Type::Function {
+                                parameter_types: resolved_target.parameter_types.clone(),
+                                return_type: Box::new(resolved_target.return_type.clone()),
+                            },
+                        );
+                        Some(resolved_target)
                     } else if name == "string" {
                         if !type_arguments.is_empty() {
                             self.error(
                                 format!("builtin conversion '{name}' does not take type arguments"),
-                                span.clone(),
+                                *span,
                             );
                         }
                         let argument_type =
@@ -165,7 +238,7 @@ impl TypeChecker<'_> {
                         ) {
                             self.error(
                                 format!("cannot convert {} to string", argument_type.display()),
-                                arguments.first().map_or(span.clone(), ExpressionSpan::span),
+                                arguments.first().map_or(*span, ExpressionSpan::span),
                             );
                         }
                         let return_type = Type::String;
@@ -178,7 +251,7 @@ impl TypeChecker<'_> {
                                 function_name: name.clone(),
                             }),
                         };
-                        self.resolved_type_by_expression_id.insert(
+                        self.expression_annotations.record_resolved_type(
                             *id,
                             Type::Function {
                                 parameter_types: resolved_target.parameter_types.clone(),
@@ -186,24 +259,80 @@ impl TypeChecker<'_> {
                             },
                         );
                         Some(resolved_target)
-                    } else if let Some(info) = self.functions.get(name).cloned() {
-                        let instantiated = self.instantiate_function_call_signature(
-                            name,
-                            &info.type_parameters,
-                            &info.parameter_types,
-                            &info.return_type,
-                            type_arguments,
+                    } else if name == "variant_name" {
+                        self.check_language_feature_is_available(
+                            LanguageFeature::EnumBuiltins,
+                            *span,
+                        );
+                        if !type_arguments.is_empty() {
+                            self.error(
+                                format!("builtin '{name}' does not take type arguments"),
+                                *span,
+                            );
+                        }
+                        let argument_type =
+                            argument_types.first().cloned().unwrap_or(Type::Unknown);
+                        if let Some(variant_references) =
+                            enum_variant_references_for_type(&argument_type)
+                        {
+                            self.expression_annotations.record_enum_call(
+                                super::semantic_expression_id(expression),
+                                EnumCall::VariantName(variant_references),
+                            );
+                        } else if !matches!(argument_type, Type::Unknown | Type::Never) {
+                            self.error(
+                                format!(
+                                    "variant_name requires an enum value, got {}",
+                                    argument_type.display()
+                                ),
+                                arguments.first().map_or(*span, ExpressionSpan::span),
+                            );
+                        }
+                        let resolved_target = ResolvedCallTarget {
+                            display_name: name.clone(),
+                            parameter_types: vec![argument_type],
+                            return_type: Type::String,
+                            resolved_type_arguments: Vec::new(),
+                            call_target: Some(TypeAnnotatedCallTarget::BuiltinFunction {
+                                function_name: name.clone(),
+                            }),
+                        };
+                        self.expression_annotations.record_resolved_type(
+                            *id,
+                            Type::Function {
+                                parameter_types: resolved_target.parameter_types.clone(),
+                                return_type: Box::new(resolved_target.return_type.clone()),
+                            },
+                        );
+                        Some(resolved_target)
+                    } else if name == "format" {
+                        self.check_language_feature_is_available(
+                            LanguageFeature::FormatBuiltin,
+                            *span,
+                        );
+                        if !type_arguments.is_empty() {
+                            self.error(
+                                format!("builtin '{name}' does not take type arguments"),
+                                *span,
+                            );
+                        }
+                        self.check_format_call(
+                            super::semantic_expression_id(expression),
+                            arguments,
                             &argument_types,
-                            span,
+                            *span,
+                            expression.span(),
                         );
                         let resolved_target = ResolvedCallTarget {
                             display_name: name.clone(),
-                            parameter_types: instantiated.parameter_types,
-                            return_type: instantiated.return_type,
-                            resolved_type_arguments: instantiated.resolved_type_arguments,
-                            call_target: Some(info.call_target.clone()),
+                            parameter_types: argument_types.clone(),
+                            return_type: Type::String,
+                            resolved_type_arguments: Vec::new(),
+                            call_target: Some(TypeAnnotatedCallTarget::BuiltinFunction {
+                                function_name: name.clone(),
+                            }),
                         };
-                        self.resolved_type_by_expression_id.insert(
+                        self.expression_annotations.record_resolved_type(
                             *id,
                             Type::Function {
                                 parameter_types: resolved_target.parameter_types.clone(),
@@ -211,8 +340,55 @@ impl TypeChecker<'_> {
                             },
                         );
                         Some(resolved_target)
-                    } else if let Some(info) = self.imported_functions.get(name).cloned() {
-                        self.mark_import_used(name);
+                    } else if let Some(info) = self.builtin_functions.get(name).cloned()
+                        && matches!(
+                            &info.call_target,
+                            TypeAnnotatedCallTarget::BuiltinFunction { function_name }
+                                if function_name == "assert_equals"
+                        )
+                        && type_arguments.is_empty()
+                    {
+                        self.check_builtin_function_is_available(name, *span);
+                        // `assert_equals` compares like `==`, so its type argument
+                        // is the wider of the argument types rather than their
+                        // exact match.
+                        let compared_type = match argument_types.as_slice() {
+                            [left_type, right_type]
+                                if self.is_assignable(right_type, left_type) =>
+                            {
+                                left_type.clone()
+                            }
+                            [left_type, right_type]
+                                if self.is_assignable(left_type, right_type) =>
+                            {
+                                right_type.clone()
+                            }
+                            [_, _] => {
+                                self.error(
+                                    "assert_equals requires arguments of the same type",
+                                    arguments[0].span(),
+                                );
+                                Type::Unknown
+                            }
+                            _ => Type::Unknown,
+                        };
+                        let resolved_target = ResolvedCallTarget {
+                            display_name: name.clone(),
+                            parameter_types: vec![compared_type.clone(), compared_type.clone()],
+                            return_type: info.return_type.clone(),
+                            resolved_type_arguments: vec![compared_type],
+                            call_target: Some(info.call_target),
+                        };
+                        self.expression_annotations.record_resolved_type(
+                            *id,
+                            Type::Function {
+                                parameter_types: resolved_target.parameter_types.clone(),
+                                return_type: Box::new(resolved_target.return_type.clone()),
+                            },
+                        );
+                        Some(resolved_target)
+                    } else if let Some(info) = self.builtin_functions.get(name).cloned() {
+                        self.check_builtin_function_is_available(name, *span);
                         let instantiated = self.instantiate_function_call_signature(
                             name,
                             &info.type_parameters,
@@ -220,6 +396,7 @@ impl TypeChecker<'_> {
                             &info.return_type,
                             type_arguments,
                             &argument_types,
+                            expected_type.as_ref(),
                             span,
                         );
                         let resolved_target = ResolvedCallTarget {
@@ -229,7 +406,7 @@ impl TypeChecker<'_> {
                             resolved_type_arguments: instantiated.resolved_type_arguments,
                             call_target: Some(info.call_target.clone()),
                         };
-                        self.resolved_type_by_expression_id.insert(
+                        self.expression_annotations.record_resolved_type(
                             *id,
                             Type::Function {
                                 parameter_types: resolved_target.parameter_types.clone(),
@@ -241,7 +418,14 @@ impl TypeChecker<'_> {
                         if self.imported_bindings.contains_key(name) {
                             self.mark_import_used(name);
                         }
-                        self.error(format!("unknown function '{name}'"), span.clone());
+                        if name == "sqrt" {
+                            self.error(
+                                "builtin 'sqrt' is not available until float64 is supported",
+                                *span,
+                            );
+                        } else {
+                            self.error(format!("unknown function '{name}'"), *span);
+                        }
                         return Type::Unknown;
                     }
                 } else if let SemanticExpression::FieldAccess {
@@ -251,8 +435,16 @@ impl TypeChecker<'_> {
                     ..
                 } = callee.as_ref()
                 {
+                    // Methods never declare type parameters, so explicit type
+                    // arguments, which parse as for functions, never apply to
+                    // one.
                     if !type_arguments.is_empty() {
-                        self.error("methods do not take type arguments", span.clone());
+                        self.error(
+                            format!(
+                                "method '{field}' does not take type arguments; methods cannot declare type parameters"
+                            ),
+                            *span,
+                        );
                     }
                     let receiver_type = self.check_expression(target);
                     let (receiver_type_id, receiver_type_name, receiver_type_arguments) =
@@ -271,7 +463,7 @@ impl TypeChecker<'_> {
                                             field,
                                             receiver_type.display()
                                         ),
-                                        field_span.clone(),
+                                        *field_span,
                                     );
                                 }
                                 return Type::Unknown;
@@ -314,32 +506,47 @@ impl TypeChecker<'_> {
                                             format!(
                                                 "cannot call mutating method '{receiver_type_name}.{field}' on immutable binding '{name}'"
                                             ),
-                                            field_span.clone(),
+                                            *field_span,
                                         );
                                     }
                                     return Type::Unknown;
                                 }
+                                self.invalidate_field_narrowings(name);
                             } else {
                                 self.error(
                                     format!(
                                         "cannot call mutating method '{receiver_type_name}.{field}' on non-binding receiver"
                                     ),
-                                    field_span.clone(),
+                                    *field_span,
                                 );
                                 return Type::Unknown;
                             }
                         }
+                        let call_target = self
+                            .type_info_by_nominal_type_id(&receiver_type_id)
+                            .map(|info| NominalTypeReference {
+                                package_path: PackagePath::new(&info.package_path),
+                                symbol_name: SymbolName::new(&receiver_type_id.symbol_name),
+                            })
+                            .map(|receiver_type_reference| {
+                                TypeAnnotatedCallTarget::UserDefinedMethod {
+                                    method_reference: TypeAnnotatedMethodReference {
+                                        receiver_type_reference,
+                                        method_name: field.clone(),
+                                    },
+                                }
+                            });
                         Some(ResolvedCallTarget {
                             display_name: field.clone(),
                             parameter_types: method_parameter_types,
                             return_type: method_return_type,
                             resolved_type_arguments: Vec::new(),
-                            call_target: None,
+                            call_target,
                         })
                     } else {
                         self.error(
                             format!("unknown method '{receiver_type_name}.{field}'"),
-                            field_span.clone(),
+                            *field_span,
                         );
                         return Type::Unknown;
                     }
@@ -353,7 +560,7 @@ impl TypeChecker<'_> {
                     if !type_arguments.is_empty() {
                         self.error(
                             "type arguments are only allowed on direct function calls",
-                            span.clone(),
+                            *span,
                         );
                     }
                     let callee_type = self.check_expression(callee);
@@ -379,7 +586,7 @@ impl TypeChecker<'_> {
                     }
                 };
                 if let Some(call_target) = &resolved_target.call_target {
-                    self.call_target_by_expression_id.insert(
+                    self.expression_annotations.record_call_target(
                         super::semantic_expression_id(expression),
                         call_target.clone(),
                     );
@@ -396,10 +603,20 @@ impl TypeChecker<'_> {
                         "assert condition is always false; use abort(\"...\") for unrecoverable failures",
                         arguments
                             .first()
-                            .map_or_else(|| span.clone(), ExpressionSpan::span),
+                            .map_or_else(|| *span, ExpressionSpan::span),
                     );
                     return Type::Unknown;
                 }
+                if let Some(TypeAnnotatedCallTarget::BuiltinFunction { function_name }) =
+                    &resolved_target.call_target
+                    && let Some(assertion_description) =
+                        self.assertion_description(function_name, arguments)
+                {
+                    self.expression_annotations.record_assertion_description(
+                        super::semantic_expression_id(expression),
+                        assertion_description,
+                    );
+                }
                 if !resolved_target.resolved_type_arguments.is_empty() {
                     let resolved_type_arguments = resolved_target
                         .resolved_type_arguments
@@ -407,10 +624,11 @@ impl TypeChecker<'_> {
                         .map(super::type_annotated_resolved_type_argument_from_type)
                         .collect::<Option<Vec<_>>>();
                     if let Some(resolved_type_arguments) = resolved_type_arguments {
-                        self.resolved_type_argument_types_by_expression_id.insert(
-                            super::semantic_expression_id(expression),
-                            resolved_type_arguments,
-                        );
+                        self.expression_annotations
+                            .record_resolved_type_argument_types(
+                                super::semantic_expression_id(expression),
+                                resolved_type_arguments,
+                            );
                     }
                 }
 
@@ -421,7 +639,41 @@ impl TypeChecker<'_> {
                             resolved_target.parameter_types.len(),
                             arguments.len()
                         ),
-                        span.clone(),
+                        *span,
+                    );
+                }
+
+                if let Some(TypeAnnotatedCallTarget::BuiltinFunction { function_name }) =
+                    &resolved_target.call_target
+                    && (function_name == "assert_equals" || function_name == "debug_string")
+                    && let Some(rendered_type) = resolved_target.parameter_types.first()
+                    && type_contains_generic_or_function_type(rendered_type)
+                {
+                    self.error(
+                        format!(
+                            "{function_name} cannot render values of type {}",
+                            rendered_type.display()
+                        ),
+                        arguments
+                            .first()
+                            .map_or_else(|| *span, ExpressionSpan::span),
+                    );
+                }
+
+                if let Some(TypeAnnotatedCallTarget::BuiltinFunction { function_name }) =
+                    &resolved_target.call_target
+                    && (function_name == "min" || function_name == "max")
+                    && let Some(compared_type) = resolved_target.parameter_types.first()
+                    && !is_ordered_type(compared_type)
+                {
+                    self.error(
+                        format!(
+                            "{function_name} requires ordered arguments, got {}",
+                            compared_type.display()
+                        ),
+                        arguments
+                            .first()
+                            .map_or_else(|| *span, ExpressionSpan::span),
                     );
                 }
 
@@ -452,11 +704,26 @@ impl TypeChecker<'_> {
                 operator,
                 left,
                 right,
-                span: _,
+                span,
                 ..
             } => {
                 let left_type = self.check_expression(left);
-                let right_type = self.check_expression(right);
+                let right_type = match operator {
+                    SemanticBinaryOperator::And => self.check_expression_with_condition_narrowing(
+                        right,
+                        left,
+                        NarrowingBranch::WhenTrue,
+                    ),
+                    SemanticBinaryOperator::Or => self.check_expression_with_condition_narrowing(
+                        right,
+                        left,
+                        NarrowingBranch::WhenFalse,
+                    ),
+                    _ => self.check_expression(right),
+                };
+                if left_type == Type::Integer64 && right_type == Type::Integer64 {
+                    self.record_operation_description(expression);
+                }
                 match operator {
                     SemanticBinaryOperator::Add => {
                         if left_type == Type::Unknown || right_type == Type::Unknown {
@@ -487,6 +754,24 @@ impl TypeChecker<'_> {
                         }
                         Type::Integer64
                     }
+                    SemanticBinaryOperator::BitwiseAnd
+                    | SemanticBinaryOperator::BitwiseOr
+                    | SemanticBinaryOperator::BitwiseXor
+                    | SemanticBinaryOperator::ShiftLeft
+                    | SemanticBinaryOperator::ShiftRight => {
+                        self.check_language_feature_is_available(
+                            LanguageFeature::BitwiseOperators,
+                            *span,
+                        );
+                        if left_type == Type::Unknown || right_type == Type::Unknown {
+                            return Type::Unknown;
+                        }
+                        if left_type != Type::Integer64 || right_type != Type::Integer64 {
+                            self.error("bitwise operators require int64 operands", left.span());
+                            return Type::Unknown;
+                        }
+                        Type::Integer64
+                    }
                     SemanticBinaryOperator::EqualEqual | SemanticBinaryOperator::NotEqual => {
                         if !self.are_comparable_for_equality(&left_type, &right_type)
                             && left_type != Type::Unknown
@@ -524,36 +809,61 @@ impl TypeChecker<'_> {
             }
             SemanticExpression::Unary {
                 operator,
-                expression,
+                expression: operand,
                 ..
             } => {
-                let value_type = self.check_expression(expression);
+                let value_type = self.check_expression(operand);
                 match operator {
                     SemanticUnaryOperator::Not => {
                         if value_type != Type::Boolean && value_type != Type::Unknown {
-                            self.error("not operator requires boolean operand", expression.span());
+                            self.error("not operator requires boolean operand", operand.span());
                             return Type::Unknown;
                         }
                         Type::Boolean
                     }
                     SemanticUnaryOperator::Negate => {
+                        if value_type == Type::Integer64 {
+                            self.record_operation_description(expression);
+                        }
                         if value_type != Type::Integer64 && value_type != Type::Unknown {
-                            self.error("unary minus requires int64 operand", expression.span());
+                            self.error("unary minus requires int64 operand", operand.span());
                             return Type::Unknown;
                         }
                         Type::Integer64
                     }
                 }
             }
+            SemanticExpression::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+                ..
+            } => {
+                self.check_language_feature_is_available(
+                    LanguageFeature::IfAndBlockExpressions,
+                    *span,
+                );
+                self.check_if_expression(condition, then_branch, else_branch)
+            }
+            SemanticExpression::Block {
+                block, value, span, ..
+            } => {
+                self.check_language_feature_is_available(
+                    LanguageFeature::IfAndBlockExpressions,
+                    *span,
+                );
+                self.check_block_expression(block, value)
+            }
             SemanticExpression::Match {
                 target, arms, span, ..
             } => self.check_match_expression(target, arms, span),
             SemanticExpression::Matches {
+                id,
                 value,
                 type_name,
-                span: _,
-                ..
-            } => self.check_matches_expression(value, type_name),
+                span,
+            } => self.check_matches_expression(*id, value, type_name, span),
             SemanticExpression::StringInterpolation { parts, .. } => {
                 for part in parts {
                     if let compiler__semantic_program::SemanticStringInterpolationPart::Expression(
@@ -564,11 +874,16 @@ impl TypeChecker<'_> {
                             && let Some((start_byte_offset, end_byte_offset)) =
                                 self.enclosing_interpolation_expression_range(&expression.span())
                         {
-                            self.push_safe_autofix(SafeAutofix::from_text_edit(TextEdit {
-                                start_byte_offset,
-                                end_byte_offset,
-                                replacement_text: escape_string_interpolation_literal_text(value),
-                            }));
+                            self.push_safe_autofix(SafeAutofix::from_text_edit(
+                                "literal_interpolation",
+                                TextEdit {
+                                    start_byte_offset,
+                                    end_byte_offset,
+                                    replacement_text: escape_string_interpolation_literal_text(
+                                        value,
+                                    ),
+                                },
+                            ));
                         }
                         let expression_type = self.check_expression(expression);
                         if expression_type != Type::String && expression_type != Type::Unknown {
@@ -585,24 +900,42 @@ impl TypeChecker<'_> {
                 Type::String
             }
         };
-        self.resolved_type_by_expression_id.insert(
+        self.expression_annotations.record_resolved_type(
             super::semantic_expression_id(expression),
             resolved_type.clone(),
         );
         resolved_type
     }
 
+    fn check_expression_with_condition_narrowing(
+        &mut self,
+        expression: &SemanticExpression,
+        condition: &SemanticExpression,
+        narrowing_branch: NarrowingBranch,
+    ) -> Type {
+        let type_narrowings = self.derive_condition_type_narrowings(condition);
+        let restores = self.apply_branch_narrowings(&type_narrowings, narrowing_branch);
+        let expression_type = self.check_expression(expression);
+        self.restore_variable_types(restores);
+        expression_type
+    }
+
     pub(super) fn check_matches_expression(
         &mut self,
+        expression_id: SemanticExpressionId,
         value: &SemanticExpression,
         type_name: &SemanticTypeName,
+        span: &Span,
     ) -> Type {
         let value_type = self.check_expression(value);
-        let pattern_type = self.resolve_match_pattern_type_name(type_name, &type_name.span);
+        let pattern_type =
+            self.resolve_match_pattern_type_name(type_name, &type_name.span, &value_type);
+        self.matches_pattern_type_by_expression_id
+            .insert(expression_id, pattern_type.clone());
         if pattern_type == Type::Nil {
             self.error(
                 "use '== nil' or '!= nil' instead of 'matches nil'",
-                type_name.span.clone(),
+                type_name.span,
             );
             return Type::Boolean;
         }
@@ -618,19 +951,37 @@ impl TypeChecker<'_> {
                             "matches pattern type '{}' is not in target type",
                             pattern_type.display()
                         ),
-                        type_name.span.clone(),
+                        type_name.span,
                     );
                 }
             }
             _ => {
-                if value_type != pattern_type {
+                if value_type == pattern_type {
+                    self.error(
+                        format!(
+                            "matches check is always true: target is already type {}",
+                            value_type.display()
+                        ),
+                        *span,
+                    );
+                    if Self::narrowing_target(value).is_some() {
+                        self.push_safe_autofix(SafeAutofix::from_text_edit(
+                            "always_true_matches",
+                            TextEdit {
+                                start_byte_offset: span.start,
+                                end_byte_offset: span.end,
+                                replacement_text: "true".to_string(),
+                            },
+                        ));
+                    }
+                } else {
                     self.error(
                         format!(
                             "matches pattern type '{}' does not match target type {}",
                             pattern_type.display(),
                             value_type.display()
                         ),
-                        type_name.span.clone(),
+                        type_name.span,
                     );
                 }
             }
@@ -639,6 +990,169 @@ impl TypeChecker<'_> {
         Type::Boolean
     }
 
+    fn check_if_expression(
+        &mut self,
+        condition: &SemanticExpression,
+        then_branch: &SemanticExpression,
+        else_branch: &SemanticExpression,
+    ) -> Type {
+        let condition_type = self.check_expression(condition);
+        if condition_type != Type::Boolean && condition_type != Type::Unknown {
+            self.error("if condition must be boolean", condition.span());
+        }
+        let then_type = self.check_expression_with_condition_narrowing(
+            then_branch,
+            condition,
+            NarrowingBranch::WhenTrue,
+        );
+        let else_type = self.check_expression_with_condition_narrowing(
+            else_branch,
+            condition,
+            NarrowingBranch::WhenFalse,
+        );
+        // A branch that never produces a value does not widen the result, and
+        // branches of unrelated types join into their union.
+        if then_type == Type::Unknown || else_type == Type::Unknown {
+            Type::Unknown
+        } else if self.is_assignable(&then_type, &else_type) {
+            else_type
+        } else if self.is_assignable(&else_type, &then_type) {
+            then_type
+        } else {
+            normalize_union(vec![then_type, else_type])
+        }
+    }
+
+    // `format` fills each `{}` of a literal template with the next argument, so
+    // the placeholders are counted and the arguments checked here, and the call
+    // is built as string concatenation.
+    fn check_format_call(
+        &mut self,
+        expression_id: SemanticExpressionId,
+        arguments: &[SemanticExpression],
+        argument_types: &[Type],
+        span: Span,
+        call_span: Span,
+    ) {
+        let Some((template, values)) = arguments.split_first() else {
+            self.error("format requires a template string", span);
+            return;
+        };
+        let SemanticExpression::StringLiteral {
+            value: template_text,
+            ..
+        } = template
+        else {
+            self.error("format template must be a string literal", template.span());
+            return;
+        };
+        let template_parts = template_text
+            .split("{}")
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let placeholder_count = template_parts.len() - 1;
+        if placeholder_count != values.len() {
+            self.error(
+                format!(
+                    "format template expects {placeholder_count} arguments, got {}",
+                    values.len()
+                ),
+                span,
+            );
+            return;
+        }
+        let mut convertible = true;
+        for (value, value_type) in values.iter().zip(&argument_types[1..]) {
+            if !matches!(
+                value_type,
+                Type::String | Type::Integer64 | Type::Boolean | Type::Nil | Type::Unknown
+            ) {
+                self.error(
+                    format!("cannot format {} as a string", value_type.display()),
+                    value.span(),
+                );
+                convertible = false;
+            }
+        }
+        if !convertible {
+            return;
+        }
+        // Interpolation only takes strings, so a call that converts nothing is
+        // written as the interpolated string it lowers to.
+        if values
+            .iter()
+            .zip(&argument_types[1..])
+            .all(|(value, value_type)| {
+                *value_type == Type::String
+                    && matches!(value, SemanticExpression::NameReference { .. })
+            })
+        {
+            let mut replacement_text = "\"".to_string();
+            for (index, template_part) in template_parts.iter().enumerate() {
+                replacement_text.push_str(&escape_string_interpolation_literal_text(template_part));
+                if let Some(SemanticExpression::NameReference { name, .. }) = values.get(index) {
+                    replacement_text.push('{');
+                    replacement_text.push_str(name);
+                    replacement_text.push('}');
+                }
+            }
+            replacement_text.push('"');
+            self.push_safe_autofix(SafeAutofix::from_text_edit(
+                "string_format",
+                TextEdit {
+                    start_byte_offset: call_span.start,
+                    end_byte_offset: call_span.end,
+                    replacement_text,
+                },
+            ));
+        }
+        self.expression_annotations
+            .record_format_template_parts(expression_id, template_parts);
+    }
+
+    // The statements may bind names and loop, but control cannot leave the
+    // block other than by reaching its value, so `return` is rejected and
+    // `break`/`continue` only reach loops inside the block.
+    fn check_block_expression(
+        &mut self,
+        block: &SemanticBlock,
+        value: &SemanticExpression,
+    ) -> Type {
+        let enclosing_loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+        self.block_expression_depth += 1;
+        self.scopes.push(HashMap::new());
+        let mut restores = Vec::new();
+        let mut falls_through = true;
+        for statement in &block.statements {
+            if !falls_through {
+                self.error("unreachable code", statement.span());
+                break;
+            }
+            let outcome = self.check_statement(statement);
+            for fallthrough_narrowing in outcome.fallthrough_narrowings {
+                restores.extend(self.apply_narrowing(
+                    &fallthrough_narrowing.target,
+                    fallthrough_narrowing.narrowed_type,
+                ));
+            }
+            falls_through = !outcome.terminates;
+        }
+        let value_type = if falls_through {
+            self.check_expression(value)
+        } else {
+            self.error("unreachable code", value.span());
+            Type::Never
+        };
+        self.restore_variable_types(restores);
+        self.check_unused_in_current_scope();
+        self.autofix_binding_names_in_current_scope();
+        self.collect_inline_candidates_in_current_scope();
+        self.scopes.pop();
+        self.block_expression_depth -= 1;
+        self.loop_depth = enclosing_loop_depth;
+        value_type
+    }
+
     pub(super) fn check_match_expression(
         &mut self,
         target: &SemanticExpression,
@@ -647,7 +1161,7 @@ impl TypeChecker<'_> {
     ) -> Type {
         let target_type = self.check_expression(target);
         if arms.is_empty() {
-            self.error("match must have at least one arm", span.clone());
+            self.error("match must have at least one arm", *span);
             return Type::Unknown;
         }
         let target_variants = match &target_type {
@@ -660,22 +1174,25 @@ impl TypeChecker<'_> {
                 None => target_type != Type::Unknown,
             };
             if should_report_single_arm {
-                self.error("match must have at least two arms", span.clone());
+                self.error("match must have at least two arms", *span);
                 return Type::Unknown;
             }
         }
         if Self::is_boolean_membership_match(arms) {
-            self.error(
-                "use 'matches' for single-pattern boolean checks",
-                span.clone(),
-            );
+            self.error("use 'matches' for single-pattern boolean checks", *span);
         }
 
+        // Only unguarded arms cover their pattern: a guarded arm may decline a
+        // value, which then falls through to the arms after it. Literal arms
+        // cover a single value, so only `true` and `false` together cover a type.
         let mut seen_patterns = std::collections::HashSet::new();
+        let mut guarded_patterns = std::collections::HashSet::new();
+        let mut seen_literals = std::collections::HashSet::new();
+        let mut literal_patterns = std::collections::HashSet::new();
         let mut result_type: Option<Type> = None;
 
         for arm in arms {
-            let pattern_type = self.resolve_match_pattern_type(&arm.pattern);
+            let pattern_type = self.resolve_match_pattern_type(&arm.pattern, &target_type);
             if pattern_type != Type::Unknown && target_type != Type::Unknown {
                 if let Some(variants) = &target_variants {
                     if !variants.contains(&pattern_type) {
@@ -699,13 +1216,41 @@ impl TypeChecker<'_> {
                 }
             }
 
-            if pattern_type != Type::Unknown {
+            if let SemanticMatchPattern::Literal { value, span } = &arm.pattern {
+                self.check_language_feature_is_available(LanguageFeature::LiteralPatterns, *span);
+                let pattern_key = pattern_type.display();
+                let literal_key = Self::match_literal_display(value);
+                if seen_patterns.contains(pattern_key.as_str()) {
+                    self.error(
+                        format!(
+                            "match arm for literal {literal_key} is unreachable after the arm for type '{pattern_key}'"
+                        ),
+                        arm.pattern.span(),
+                    );
+                } else if seen_literals.contains(literal_key.as_str()) {
+                    self.error(
+                        format!("duplicate match arm for literal {literal_key}"),
+                        arm.pattern.span(),
+                    );
+                } else if arm.guard.is_some() {
+                    guarded_patterns.insert(pattern_key);
+                } else {
+                    seen_literals.insert(literal_key);
+                    if seen_literals.contains("true") && seen_literals.contains("false") {
+                        seen_patterns.insert(pattern_key);
+                    } else {
+                        literal_patterns.insert(pattern_key);
+                    }
+                }
+            } else if pattern_type != Type::Unknown {
                 let pattern_key = pattern_type.display();
                 if seen_patterns.contains(pattern_key.as_str()) {
                     self.error(
                         format!("duplicate match arm for type '{pattern_key}'"),
                         arm.pattern.span(),
                     );
+                } else if arm.guard.is_some() {
+                    guarded_patterns.insert(pattern_key);
                 } else {
                     seen_patterns.insert(pattern_key);
                 }
@@ -721,14 +1266,38 @@ impl TypeChecker<'_> {
                     pattern_type.clone(),
                     false,
                     name_span,
-                    name_span.clone(),
+                    *name_span,
                 );
             }
 
-            let arm_type = self.check_expression(&arm.value);
+            let arm_type = match &arm.guard {
+                Some(guard) => {
+                    self.check_language_feature_is_available(
+                        LanguageFeature::MatchGuards,
+                        guard.span(),
+                    );
+                    let guard_type = self.check_expression(guard);
+                    if guard_type != Type::Boolean && guard_type != Type::Unknown {
+                        self.error(
+                            format!("match guard must be boolean, got {}", guard_type.display()),
+                            guard.span(),
+                        );
+                    }
+                    self.check_expression_with_condition_narrowing(
+                        &arm.value,
+                        guard,
+                        NarrowingBranch::WhenTrue,
+                    )
+                }
+                None => self.check_expression(&arm.value),
+            };
             self.check_unused_in_current_scope();
+            self.autofix_binding_names_in_current_scope();
             self.scopes.pop();
 
+            if arm_type == Type::Never {
+                continue;
+            }
             if let Some(expected_type) = &result_type {
                 if *expected_type != Type::Unknown
                     && arm_type != Type::Unknown
@@ -748,31 +1317,115 @@ impl TypeChecker<'_> {
             }
         }
 
-        if let Some(variants) = target_variants {
+        // A target that is not a union is covered by any unguarded type or
+        // binding arm, and arms of another type were reported above.
+        let covered_variants = match target_variants {
+            Some(variants) => Some(variants),
+            None if target_type != Type::Unknown
+                && arms.iter().all(|arm| {
+                    arm.guard.is_some()
+                        || matches!(arm.pattern, SemanticMatchPattern::Literal { .. })
+                }) =>
+            {
+                Some(vec![target_type])
+            }
+            None => None,
+        };
+        if let Some(variants) = covered_variants {
             let missing = variants
                 .iter()
                 .filter(|variant| !seen_patterns.contains(&variant.display()))
                 .map(Type::display)
                 .collect::<Vec<_>>();
             if !missing.is_empty() {
-                self.error(
-                    format!("non-exhaustive match, missing: {}", missing.join(", ")),
-                    span.clone(),
+                let guarded_note = if missing
+                    .iter()
+                    .any(|pattern| guarded_patterns.contains(pattern))
+                {
+                    " (guarded arms do not make a match exhaustive)"
+                } else if missing
+                    .iter()
+                    .any(|pattern| literal_patterns.contains(pattern))
+                {
+                    " (literal arms do not make a match exhaustive)"
+                } else {
+                    ""
+                };
+                let suggested_fix =
+                    self.missing_match_arms_suggested_fix(&arms[arms.len() - 1], &missing);
+                self.error_with_suggested_fix(
+                    format!(
+                        "non-exhaustive match, missing: {}{guarded_note}",
+                        missing.join(", ")
+                    ),
+                    *span,
+                    suggested_fix,
                 );
             }
         }
 
-        result_type.unwrap_or(Type::Unknown)
+        result_type.unwrap_or(Type::Never)
+    }
+
+    // New arms abort at runtime, so they type-check against any match result
+    // type and leave the real handling to the user.
+    fn missing_match_arms_suggested_fix(
+        &self,
+        last_arm: &SemanticMatchArm,
+        missing: &[String],
+    ) -> SuggestedFix {
+        let insertion_offset = last_arm.value.span().end;
+        let last_arm_line_start = self.source_text[..last_arm.span.start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let indentation = self.source_text[last_arm_line_start..]
+            .chars()
+            .take_while(|character| *character == ' ')
+            .collect::<String>();
+        let mut replacement_text = String::new();
+        for pattern in missing {
+            let _ = write!(
+                replacement_text,
+                ",\n{indentation}{pattern} => abort(\"unhandled {pattern}\")"
+            );
+        }
+        SuggestedFix {
+            label: format!(
+                "add missing match {}",
+                if missing.len() == 1 { "arm" } else { "arms" }
+            ),
+            edits: vec![SuggestedEdit {
+                span: Span::new(insertion_offset, insertion_offset),
+                replacement_text,
+            }],
+        }
     }
 
-    pub(super) fn resolve_match_pattern_type(&mut self, pattern: &SemanticMatchPattern) -> Type {
+    pub(super) fn resolve_match_pattern_type(
+        &mut self,
+        pattern: &SemanticMatchPattern,
+        target_type: &Type,
+    ) -> Type {
         match pattern {
             SemanticMatchPattern::Type { type_name, span } => {
-                self.resolve_match_pattern_type_name(type_name, span)
+                self.resolve_match_pattern_type_name(type_name, span, target_type)
             }
             SemanticMatchPattern::Binding {
                 type_name, span, ..
-            } => self.resolve_match_pattern_type_name(type_name, span),
+            } => self.resolve_match_pattern_type_name(type_name, span, target_type),
+            SemanticMatchPattern::Literal { value, .. } => match value {
+                SemanticMatchLiteral::Integer(_) => Type::Integer64,
+                SemanticMatchLiteral::String(_) => Type::String,
+                SemanticMatchLiteral::Boolean(_) => Type::Boolean,
+            },
+        }
+    }
+
+    fn match_literal_display(value: &SemanticMatchLiteral) -> String {
+        match value {
+            SemanticMatchLiteral::Integer(value) => value.to_string(),
+            SemanticMatchLiteral::String(value) => format!("\"{value}\""),
+            SemanticMatchLiteral::Boolean(value) => value.to_string(),
         }
     }
 
@@ -780,30 +1433,90 @@ impl TypeChecker<'_> {
         &mut self,
         type_name: &SemanticTypeName,
         span: &Span,
+        target_type: &Type,
     ) -> Type {
         if type_name.names.len() != 1 {
-            self.error("match patterns must be single types", span.clone());
+            self.error("match patterns must be single types", *span);
             return Type::Unknown;
         }
+        if let Some(instantiated_type) =
+            self.infer_generic_match_pattern_type(type_name, span, target_type)
+        {
+            return instantiated_type;
+        }
         let resolved = self.resolve_type_name(type_name);
         if matches!(resolved, Type::Union(_)) {
-            self.error("match patterns must be concrete types", span.clone());
+            self.error("match patterns must be concrete types", *span);
             return Type::Unknown;
         }
         if matches!(resolved, Type::TypeParameter(_)) {
-            self.error("match patterns must not use type parameters", span.clone());
+            self.error("match patterns must not use type parameters", *span);
             return Type::Unknown;
         }
         if matches!(resolved, Type::Applied { .. }) {
             self.error(
                 "match patterns must not use instantiated generic types",
-                span.clone(),
+                *span,
             );
             return Type::Unknown;
         }
         resolved
     }
 
+    // Patterns name generic structs without type arguments, so the instantiation
+    // is taken from the matching member of the target type. Without one, the bare
+    // nominal type is returned so the usual pattern mismatch is reported.
+    fn infer_generic_match_pattern_type(
+        &mut self,
+        type_name: &SemanticTypeName,
+        span: &Span,
+        target_type: &Type,
+    ) -> Option<Type> {
+        let segment = &type_name.names[0];
+        if !segment.type_arguments.is_empty() {
+            return None;
+        }
+        let info = self.types.get_by_local_name(&segment.name)?;
+        if info.type_parameters.is_empty() || !matches!(info.kind, TypeKind::Struct { .. }) {
+            return None;
+        }
+        let nominal_type_id = info.nominal_type_id.clone();
+        if self.imported_bindings.contains_key(&segment.name) {
+            self.mark_import_used(&segment.name);
+        }
+        if *target_type == Type::Unknown {
+            return Some(Type::Unknown);
+        }
+        let target_members = match target_type {
+            Type::Union(members) => members.as_slice(),
+            _ => std::slice::from_ref(target_type),
+        };
+        let instantiations = target_members
+            .iter()
+            .filter(
+                |member| matches!(member, Type::Applied { base, .. } if base.id == nominal_type_id),
+            )
+            .collect::<Vec<_>>();
+        match instantiations.as_slice() {
+            [instantiation] => Some((*instantiation).clone()),
+            [] => Some(Type::Named(NominalTypeRef {
+                id: nominal_type_id,
+                display_name: segment.name.clone(),
+            })),
+            _ => {
+                self.error(
+                    format!(
+                        "pattern type '{}' matches multiple instantiations in target type {}",
+                        segment.name,
+                        target_type.display()
+                    ),
+                    *span,
+                );
+                Some(Type::Unknown)
+            }
+        }
+    }
+
     pub(super) fn check_struct_literal(
         &mut self,
         expression: &SemanticExpression,
@@ -813,7 +1526,7 @@ impl TypeChecker<'_> {
         if type_name.names.len() != 1 {
             self.error(
                 "struct literal requires a named struct type",
-                type_name.span.clone(),
+                type_name.span,
             );
             for field in fields {
                 self.check_expression(&field.value);
@@ -821,15 +1534,25 @@ impl TypeChecker<'_> {
             return Type::Unknown;
         }
 
-        let struct_type = self.resolve_type_name(type_name);
-        let Some(resolved_struct_fields) = self.resolve_struct_fields(&struct_type) else {
+        let (struct_type, checked_field_value_types) =
+            match self.infer_struct_literal_type(expression, type_name, fields) {
+                Some((struct_type, field_value_types)) => (struct_type, Some(field_value_types)),
+                None => (self.resolve_type_name(type_name), None),
+            };
+        let checked_field_value_types = checked_field_value_types.as_deref();
+        // A literal whose type arguments could not all be inferred is still
+        // checked field by field, but is named and typed without them so that
+        // no further errors mention the missing arguments.
+        let type_arguments_uninferred = checked_field_value_types.is_some()
+            && matches!(&struct_type, Type::Applied { arguments, .. } if arguments.contains(&Type::Unknown));
+        let Some(mut resolved_struct_fields) = self.resolve_struct_fields(&struct_type) else {
             if struct_type != Type::Unknown {
                 self.error(
                     format!(
                         "struct literal requires struct type, found '{}'",
                         struct_type.display()
                     ),
-                    type_name.span.clone(),
+                    type_name.span,
                 );
             }
             for field in fields {
@@ -837,22 +1560,31 @@ impl TypeChecker<'_> {
             }
             return struct_type;
         };
-        self.struct_reference_by_expression_id.insert(
+        self.expression_annotations.record_struct_reference(
             super::semantic_expression_id(expression),
             resolved_struct_fields.struct_reference.clone(),
         );
+        if type_arguments_uninferred {
+            resolved_struct_fields
+                .struct_display_name
+                .clone_from(&type_name.names[0].name);
+        }
 
         let mut seen = std::collections::HashSet::new();
-        for field in fields {
+        for (field_index, field) in fields.iter().enumerate() {
             if !seen.insert(field.name.as_str()) {
                 self.error(
                     format!(
                         "duplicate field '{}' in {} literal",
                         field.name, resolved_struct_fields.struct_display_name
                     ),
-                    field.name_span.clone(),
+                    field.name_span,
+                );
+                self.check_struct_literal_field_value(
+                    field,
+                    field_index,
+                    checked_field_value_types,
                 );
-                self.check_expression(&field.value);
                 continue;
             }
 
@@ -866,13 +1598,21 @@ impl TypeChecker<'_> {
                         "unknown field '{}' on {}",
                         field.name, resolved_struct_fields.struct_display_name
                     ),
-                    field.name_span.clone(),
+                    field.name_span,
+                );
+                self.check_struct_literal_field_value(
+                    field,
+                    field_index,
+                    checked_field_value_types,
                 );
-                self.check_expression(&field.value);
                 continue;
             };
 
-            let value_type = self.check_expression(&field.value);
+            let value_type = self.check_struct_literal_field_value(
+                field,
+                field_index,
+                checked_field_value_types,
+            );
             if *field_type != Type::Unknown
                 && value_type != Type::Unknown
                 && value_type != *field_type
@@ -889,21 +1629,263 @@ impl TypeChecker<'_> {
             }
         }
 
+        let mut suggested_fix = self.missing_struct_literal_fields_suggested_fix(
+            type_name,
+            fields,
+            &resolved_struct_fields,
+        );
         for (field_name, _) in &resolved_struct_fields.fields {
             if !seen.contains(field_name.as_str()) {
-                self.error(
-                    format!(
-                        "missing field '{field_name}' in {} literal",
-                        resolved_struct_fields.struct_display_name
-                    ),
-                    type_name.span.clone(),
+                let message = format!(
+                    "missing field '{field_name}' in {} literal",
+                    resolved_struct_fields.struct_display_name
                 );
+                if let Some(suggested_fix) = suggested_fix.take() {
+                    self.error_with_suggested_fix(message, type_name.span, suggested_fix);
+                } else {
+                    self.error(message, type_name.span);
+                }
             }
         }
 
+        if type_arguments_uninferred {
+            return Type::Unknown;
+        }
         struct_type
     }
 
+    // A literal of a generic struct that names no type arguments, such as
+    // `Box { value: 3 }`, takes them from its field values. The field values
+    // are checked here to infer them, so their types are returned for the rest
+    // of the literal's checking. Type parameters no field value determines are
+    // reported and left unknown.
+    fn infer_struct_literal_type(
+        &mut self,
+        expression: &SemanticExpression,
+        type_name: &SemanticTypeName,
+        fields: &[SemanticStructLiteralField],
+    ) -> Option<(Type, Vec<Type>)> {
+        let segment = &type_name.names[0];
+        if !segment.type_arguments.is_empty()
+            || self.resolve_type_parameter(&segment.name).is_some()
+        {
+            return None;
+        }
+        let name = segment.name.as_str();
+        let info = self.types.get_by_local_name(name)?;
+        let TypeKind::Struct {
+            fields: declared_fields,
+        } = &info.kind
+        else {
+            return None;
+        };
+        if info.type_parameters.is_empty() {
+            return None;
+        }
+        let nominal_type_id = info.nominal_type_id.clone();
+        let type_parameters = info.type_parameters.clone();
+        let declared_fields = declared_fields.clone();
+        self.mark_type_import_used(name);
+        self.record_type_reference(name, segment.span);
+        self.report_deprecated_use(name, segment.span);
+
+        let field_value_types = fields
+            .iter()
+            .map(|field| self.check_expression(&field.value))
+            .collect::<Vec<_>>();
+        let mut inferred_by_type_parameter_name: HashMap<String, Type> = HashMap::new();
+        let mut inconsistent_type_parameter_names = std::collections::BTreeSet::new();
+        let mut has_unknown_field_value = false;
+        let mut seen = std::collections::HashSet::new();
+        for (field, value_type) in fields.iter().zip(&field_value_types) {
+            if !seen.insert(field.name.as_str()) {
+                continue;
+            }
+            let Some((_, declared_field_type)) = declared_fields
+                .iter()
+                .find(|(declared_name, _)| *declared_name == field.name)
+            else {
+                continue;
+            };
+            if *value_type == Type::Unknown {
+                has_unknown_field_value = true;
+                continue;
+            }
+            self.collect_type_parameter_inference_from_argument(
+                declared_field_type,
+                value_type,
+                &mut inferred_by_type_parameter_name,
+                &mut inconsistent_type_parameter_names,
+            );
+        }
+
+        if !inconsistent_type_parameter_names.is_empty() {
+            let inconsistent_names = inconsistent_type_parameter_names
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.error(
+                format!(
+                    "cannot infer consistent type arguments for struct '{name}' (conflicting inferences for: {inconsistent_names})"
+                ),
+                segment.span,
+            );
+            for type_parameter_name in &inconsistent_type_parameter_names {
+                inferred_by_type_parameter_name.insert(type_parameter_name.clone(), Type::Unknown);
+            }
+        }
+        let uninferred_type_parameter_names = type_parameters
+            .iter()
+            .filter(|parameter| !inferred_by_type_parameter_name.contains_key(&parameter.name))
+            .map(|parameter| parameter.name.clone())
+            .collect::<Vec<_>>();
+        if !uninferred_type_parameter_names.is_empty() && !has_unknown_field_value {
+            self.error(
+                format!(
+                    "cannot infer type arguments for struct '{name}' (unconstrained: {}); write them explicitly, as in '{name}[...] {{ ... }}'",
+                    uninferred_type_parameter_names.join(", ")
+                ),
+                segment.span,
+            );
+        }
+
+        let type_arguments = type_parameters
+            .iter()
+            .map(|parameter| {
+                inferred_by_type_parameter_name
+                    .get(&parameter.name)
+                    .cloned()
+                    .unwrap_or(Type::Unknown)
+            })
+            .collect::<Vec<_>>();
+        self.check_type_argument_constraints(
+            name,
+            &type_parameters,
+            &type_arguments,
+            &segment.span,
+        );
+        if let Some(resolved_type_arguments) = type_arguments
+            .iter()
+            .map(super::type_annotated_resolved_type_argument_from_type)
+            .collect::<Option<Vec<_>>>()
+        {
+            self.expression_annotations
+                .record_resolved_type_argument_types(
+                    super::semantic_expression_id(expression),
+                    resolved_type_arguments,
+                );
+        }
+        Some((
+            Type::Applied {
+                base: NominalTypeRef {
+                    id: nominal_type_id,
+                    display_name: name.to_string(),
+                },
+                arguments: type_arguments,
+            },
+            field_value_types,
+        ))
+    }
+
+    fn check_struct_literal_field_value(
+        &mut self,
+        field: &SemanticStructLiteralField,
+        field_index: usize,
+        checked_field_value_types: Option<&[Type]>,
+    ) -> Type {
+        match checked_field_value_types {
+            Some(field_value_types) => field_value_types[field_index].clone(),
+            None => self.check_expression(&field.value),
+        }
+    }
+
+    // Each missing field is inserted after the nearest earlier declared field
+    // the literal already has, so fields stay in declaration order. No fix is
+    // offered unless every missing field has a placeholder of its exact type.
+    fn missing_struct_literal_fields_suggested_fix(
+        &self,
+        type_name: &SemanticTypeName,
+        literal_fields: &[SemanticStructLiteralField],
+        resolved_struct_fields: &ResolvedStructFields,
+    ) -> Option<SuggestedFix> {
+        let mut edits = Vec::new();
+        let mut pending_fields = Vec::new();
+        let mut missing_field_count = 0;
+        let mut preceding_literal_field = None;
+        for (field_name, field_type) in &resolved_struct_fields.fields {
+            if let Some(literal_field) = literal_fields
+                .iter()
+                .find(|literal_field| literal_field.name == *field_name)
+            {
+                edits.extend(self.struct_literal_fields_insertion(
+                    type_name,
+                    literal_fields,
+                    preceding_literal_field,
+                    &pending_fields,
+                ));
+                pending_fields.clear();
+                preceding_literal_field = Some(literal_field);
+            } else {
+                pending_fields.push(format!("{field_name}: {}", placeholder_value(field_type)?));
+                missing_field_count += 1;
+            }
+        }
+        edits.extend(self.struct_literal_fields_insertion(
+            type_name,
+            literal_fields,
+            preceding_literal_field,
+            &pending_fields,
+        ));
+        if edits.is_empty() {
+            return None;
+        }
+        Some(SuggestedFix {
+            label: format!(
+                "add missing {}",
+                if missing_field_count == 1 {
+                    "field"
+                } else {
+                    "fields"
+                }
+            ),
+            edits,
+        })
+    }
+
+    fn struct_literal_fields_insertion(
+        &self,
+        type_name: &SemanticTypeName,
+        literal_fields: &[SemanticStructLiteralField],
+        preceding_literal_field: Option<&SemanticStructLiteralField>,
+        pending_fields: &[String],
+    ) -> Option<SuggestedEdit> {
+        if pending_fields.is_empty() {
+            return None;
+        }
+        let pending_fields_text = pending_fields.join(", ");
+        let (insertion_offset, replacement_text) =
+            match (preceding_literal_field, literal_fields.first()) {
+                (Some(preceding_literal_field), _) => (
+                    preceding_literal_field.value.span().end,
+                    format!(", {pending_fields_text}"),
+                ),
+                (None, Some(first_literal_field)) => (
+                    first_literal_field.name_span.start,
+                    format!("{pending_fields_text}, "),
+                ),
+                (None, None) => {
+                    let opening_brace_offset =
+                        type_name.span.end + self.source_text[type_name.span.end..].find('{')?;
+                    (opening_brace_offset + 1, format!(" {pending_fields_text} "))
+                }
+            };
+        Some(SuggestedEdit {
+            span: Span::new(insertion_offset, insertion_offset),
+            replacement_text,
+        })
+    }
+
     pub(super) fn resolve_field_access_type(
         &mut self,
         target_type: &Type,
@@ -918,7 +1900,7 @@ impl TypeChecker<'_> {
                         field,
                         target_type.display()
                     ),
-                    span.clone(),
+                    *span,
                 );
             }
             return Type::Unknown;
@@ -936,11 +1918,99 @@ impl TypeChecker<'_> {
                 "unknown field '{field}' on {}",
                 resolved_struct_fields.struct_display_name
             ),
-            span.clone(),
+            *span,
         );
         Type::Unknown
     }
 
+    // Failed assertions report the asserted source text and where it starts.
+    fn assertion_description(
+        &self,
+        function_name: &str,
+        arguments: &[SemanticExpression],
+    ) -> Option<String> {
+        let argument_texts = arguments
+            .iter()
+            .map(|argument| {
+                let argument_span = argument.span();
+                self.source_text.get(argument_span.start..argument_span.end)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let asserted_text = match (function_name, argument_texts.as_slice()) {
+            ("assert", [condition]) => (*condition).to_string(),
+            ("assert_equals", [left, right]) => format!("{left} == {right}"),
+            _ => return None,
+        };
+        let (line, column) =
+            LineIndex::new(self.source_text).line_column(arguments.first()?.span().start);
+        Some(format!("{asserted_text} (line {line}, column {column})"))
+    }
+
+    // Int64 operations that can overflow or divide by zero report their source
+    // text and where it starts when they abort.
+    fn record_operation_description(&mut self, expression: &SemanticExpression) {
+        let can_abort = match expression {
+            SemanticExpression::Binary { operator, .. } => matches!(
+                operator,
+                SemanticBinaryOperator::Add
+                    | SemanticBinaryOperator::Subtract
+                    | SemanticBinaryOperator::Multiply
+                    | SemanticBinaryOperator::Divide
+                    | SemanticBinaryOperator::Modulo
+            ),
+            SemanticExpression::Unary { operator, .. } => {
+                *operator == SemanticUnaryOperator::Negate
+            }
+            _ => false,
+        };
+        if !can_abort {
+            return;
+        }
+        let span = expression.span();
+        let Some(operation_text) = self.source_text.get(span.start..span.end) else {
+            return;
+        };
+        let (line, column) = LineIndex::new(self.source_text).line_column(span.start);
+        self.expression_annotations.record_operation_description(
+            super::semantic_expression_id(expression),
+            format!("{operation_text} (line {line}, column {column})"),
+        );
+    }
+
+    // Checks an expression whose context expects `expected_type`, such as the
+    // initializer of an annotated binding. The expected type only lets a
+    // generic call infer type arguments that appear in nothing but its return
+    // type; callers still check that the result is assignable.
+    pub(super) fn check_expression_with_expected_type(
+        &mut self,
+        expression: &SemanticExpression,
+        expected_type: &Type,
+    ) -> Type {
+        if *expected_type != Type::Unknown {
+            self.expected_expression_type = Some(expected_type.clone());
+        }
+        self.check_expression(expression)
+    }
+
+    // Arguments are checked before a call is resolved, so only the parameter
+    // types of a non-generic function are known in time to guide them.
+    fn non_generic_callee_parameter_types(&self, callee: &SemanticExpression) -> Option<Vec<Type>> {
+        let SemanticExpression::NameReference { name, .. } = callee else {
+            return None;
+        };
+        if self.name_reference_resolves_to_value_binding(name) {
+            return None;
+        }
+        let info = self
+            .functions
+            .get(name)
+            .or_else(|| self.imported_functions.get(name))
+            .or_else(|| self.builtin_functions.get(name))?;
+        info.type_parameters
+            .is_empty()
+            .then(|| info.parameter_types.clone())
+    }
+
     fn instantiate_function_call_signature(
         &mut self,
         function_name: &str,
@@ -949,13 +2019,14 @@ impl TypeChecker<'_> {
         return_type: &Type,
         type_arguments: &[SemanticTypeName],
         argument_types: &[Type],
+        expected_return_type: Option<&Type>,
         span: &Span,
     ) -> InstantiatedFunctionSignature {
         if type_parameters.is_empty() {
             if !type_arguments.is_empty() {
                 self.error(
                     format!("function '{function_name}' does not take type arguments"),
-                    span.clone(),
+                    *span,
                 );
             }
             return InstantiatedFunctionSignature {
@@ -969,7 +2040,9 @@ impl TypeChecker<'_> {
                 function_name,
                 type_parameters,
                 parameter_types,
+                return_type,
                 argument_types,
+                expected_return_type,
                 span,
             ) {
                 self.check_type_argument_constraints(
@@ -1007,7 +2080,7 @@ impl TypeChecker<'_> {
                     type_parameters.len(),
                     type_arguments.len()
                 ),
-                span.clone(),
+                *span,
             );
             return InstantiatedFunctionSignature {
                 parameter_types: parameter_types.to_vec(),
@@ -1051,11 +2124,7 @@ impl TypeChecker<'_> {
         return_type: &Type,
         span: &Span,
     ) -> InstantiatedFunctionSignature {
-        let Some(receiver_info) = self
-            .types
-            .values()
-            .find(|info| info.nominal_type_id == *receiver_type_id)
-        else {
+        let Some(receiver_info) = self.type_info_by_nominal_type_id(receiver_type_id) else {
             return InstantiatedFunctionSignature {
                 parameter_types: parameter_types.to_vec(),
                 return_type: return_type.clone(),
@@ -1078,7 +2147,7 @@ impl TypeChecker<'_> {
                     receiver_info.type_parameters.len(),
                     receiver_type_arguments.len()
                 ),
-                span.clone(),
+                *span,
             );
             return InstantiatedFunctionSignature {
                 parameter_types: parameter_types.to_vec(),
@@ -1108,27 +2177,21 @@ impl TypeChecker<'_> {
     fn resolve_struct_fields(&mut self, struct_type: &Type) -> Option<ResolvedStructFields> {
         match struct_type {
             Type::Named(type_name) => {
-                let info = self
-                    .types
-                    .values()
-                    .find(|info| info.nominal_type_id == type_name.id)?;
+                let info = self.type_info_by_nominal_type_id(&type_name.id)?;
                 let TypeKind::Struct { fields } = &info.kind else {
                     return None;
                 };
                 Some(ResolvedStructFields {
                     struct_display_name: type_name.display_name.clone(),
-                    struct_reference: TypeAnnotatedStructReference {
-                        package_path: info.package_path.clone(),
-                        symbol_name: info.nominal_type_id.symbol_name.clone(),
+                    struct_reference: StructReference {
+                        package_path: PackagePath::new(&info.package_path),
+                        symbol_name: SymbolName::new(&info.nominal_type_id.symbol_name),
                     },
                     fields: fields.clone(),
                 })
             }
             Type::Applied { base, arguments } => {
-                let info = self
-                    .types
-                    .values()
-                    .find(|info| info.nominal_type_id == base.id)?;
+                let info = self.type_info_by_nominal_type_id(&base.id)?;
                 let TypeKind::Struct { fields } = &info.kind else {
                     return None;
                 };
@@ -1149,9 +2212,9 @@ impl TypeChecker<'_> {
                     .collect();
                 Some(ResolvedStructFields {
                     struct_display_name: struct_type.display(),
-                    struct_reference: TypeAnnotatedStructReference {
-                        package_path: info.package_path.clone(),
-                        symbol_name: info.nominal_type_id.symbol_name.clone(),
+                    struct_reference: StructReference {
+                        package_path: PackagePath::new(&info.package_path),
+                        symbol_name: SymbolName::new(&info.nominal_type_id.symbol_name),
                     },
                     fields: instantiated_fields,
                 })
@@ -1166,10 +2229,7 @@ impl TypeChecker<'_> {
         span: &Span,
     ) -> Type {
         if elements.is_empty() {
-            self.error(
-                "list literal must include at least one element",
-                span.clone(),
-            );
+            self.error("list literal must include at least one element", *span);
             return Type::Unknown;
         }
 
@@ -1181,16 +2241,131 @@ impl TypeChecker<'_> {
             return Type::Unknown;
         }
 
-        let element_type = Self::normalize_union(element_types);
+        let element_type = normalize_union(element_types);
         Type::List(Box::new(element_type))
     }
 
+    // Variants of the enum `name` names, in declaration order, unless `name`
+    // names some other type or none.
+    pub(super) fn enum_like_union_variants(&self, name: &str) -> Option<Vec<Type>> {
+        let info = self.types.get_by_local_name(name)?;
+        let TypeKind::Union { variants } = &info.kind else {
+            return None;
+        };
+        let enum_like_prefix = format!("{name}.");
+        let is_enum_like = !variants.is_empty()
+            && variants.iter().all(|variant| {
+                matches!(
+                    variant,
+                    Type::Named(named) if named.display_name.starts_with(&enum_like_prefix)
+                )
+            });
+        is_enum_like.then(|| variants.clone())
+    }
+
+    // `Enum.all()`, `Enum.count()`, and `Enum.from_string(name)`, unless the
+    // enum has a variant of that name.
+    fn is_enum_type_call(&self, callee: &SemanticExpression) -> bool {
+        let SemanticExpression::FieldAccess { target, field, .. } = callee else {
+            return false;
+        };
+        let SemanticExpression::NameReference { name, .. } = target.as_ref() else {
+            return false;
+        };
+        let variant_display = format!("{name}.{field}");
+        matches!(field.as_str(), "all" | "count" | "from_string")
+            && self.enum_like_union_variants(name).is_some_and(|variants| {
+                variants
+                    .iter()
+                    .all(|variant| variant.display() != variant_display)
+            })
+    }
+
+    // The variants of an enum are known when it is checked, so these calls are
+    // annotated with them and do not reach later phases as calls.
+    fn check_enum_type_call(
+        &mut self,
+        id: SemanticExpressionId,
+        callee: &SemanticExpression,
+        type_arguments: &[SemanticTypeName],
+        arguments: &[SemanticExpression],
+        span: Span,
+    ) -> Type {
+        let SemanticExpression::FieldAccess { target, field, .. } = callee else {
+            unreachable!("enum type calls are field accesses");
+        };
+        let SemanticExpression::NameReference { name, .. } = target.as_ref() else {
+            unreachable!("enum type calls are on enum names");
+        };
+        let variants = self
+            .enum_like_union_variants(name)
+            .expect("enum type calls are on enums");
+        self.check_language_feature_is_available(LanguageFeature::EnumBuiltins, span);
+        self.mark_type_import_used(name);
+        self.record_type_reference(name, target.span());
+        self.report_deprecated_use(name, target.span());
+        if !type_arguments.is_empty() {
+            self.error(
+                format!("'{name}.{field}' does not take type arguments"),
+                span,
+            );
+        }
+        let argument_types = arguments
+            .iter()
+            .map(|argument| self.check_expression(argument))
+            .collect::<Vec<_>>();
+        let variant_references =
+            enum_variant_references(&variants).expect("enum variants are named after their enum");
+        if field == "from_string" {
+            match (arguments, argument_types.as_slice()) {
+                ([argument], [argument_type]) => {
+                    if !matches!(argument_type, Type::String | Type::Unknown) {
+                        self.error(
+                            format!(
+                                "argument 1 to '{name}.{field}' must be string, got {}",
+                                argument_type.display()
+                            ),
+                            argument.span(),
+                        );
+                    }
+                }
+                _ => self.error(
+                    format!("expected 1 arguments, got {}", arguments.len()),
+                    span,
+                ),
+            }
+            self.expression_annotations
+                .record_enum_call(id, EnumCall::FromString(variant_references));
+            return normalize_union(variants.into_iter().chain([Type::Nil]).collect());
+        }
+        if let Some(first_argument) = arguments.first() {
+            self.error(
+                format!("'{name}.{field}' does not take arguments"),
+                first_argument.span(),
+            );
+        }
+        if field == "all" {
+            self.expression_annotations
+                .record_enum_call(id, EnumCall::All(variant_references));
+            Type::List(Box::new(normalize_union(variants)))
+        } else {
+            self.expression_annotations
+                .record_enum_call(id, EnumCall::Count(variant_references.len()));
+            Type::Integer64
+        }
+    }
+
+    // Type arguments come from the argument types first. Any that no argument
+    // determines may then come from the type the call's context expects of
+    // its result.
     fn infer_function_type_arguments_from_call(
         &mut self,
         function_name: &str,
         type_parameters: &[GenericTypeParameter],
         parameter_types: &[Type],
+        return_type: &Type,
         argument_types: &[Type],
+        expected_return_type: Option<&Type>,
         span: &Span,
     ) -> Option<Vec<Type>> {
         if parameter_types.len() != argument_types.len() {
@@ -1218,11 +2393,29 @@ impl TypeChecker<'_> {
                 format!(
                     "cannot infer consistent type arguments for generic function '{function_name}' (conflicting inferences for: {inconsistent_names})"
                 ),
-                span.clone(),
+                *span,
             );
             return Some(vec![Type::Unknown; type_parameters.len()]);
         }
 
+        if let Some(expected_return_type) = expected_return_type {
+            let mut inferred_from_expected_type: HashMap<String, Type> = HashMap::new();
+            let mut inconsistent_with_expected_type = std::collections::BTreeSet::new();
+            self.collect_type_parameter_inference_from_argument(
+                return_type,
+                expected_return_type,
+                &mut inferred_from_expected_type,
+                &mut inconsistent_with_expected_type,
+            );
+            for (type_parameter_name, inferred_type) in inferred_from_expected_type {
+                if !inconsistent_with_expected_type.contains(&type_parameter_name) {
+                    inferred_by_type_parameter_name
+                        .entry(type_parameter_name)
+                        .or_insert(inferred_type);
+                }
+            }
+        }
+
         let missing_type_parameter_names = type_parameters
             .iter()
             .filter_map(|parameter| {
@@ -1239,7 +2432,7 @@ impl TypeChecker<'_> {
                     "generic function '{function_name}' requires {} explicit type arguments",
                     type_parameters.len()
                 ),
-                span.clone(),
+                *span,
             );
             return None;
         }
@@ -1395,3 +2588,81 @@ fn escape_string_interpolation_literal_text(text: &str) -> String {
     }
     escaped
 }
+
+// Struct literal field values must have exactly the field type, so only types
+// with a literal of that exact type get a placeholder.
+fn placeholder_value(field_type: &Type) -> Option<&'static str> {
+    match field_type {
+        Type::Integer64 => Some("0"),
+        Type::Boolean => Some("false"),
+        Type::String => Some("\"\""),
+        Type::Nil => Some("nil"),
+        Type::Never
+        | Type::List(_)
+        | Type::Named(_)
+        | Type::Applied { .. }
+        | Type::Function { .. }
+        | Type::TypeParameter(_)
+        | Type::Union(_)
+        | Type::Unknown => None,
+    }
+}
+
+// Values rendered by `assert_equals` and `debug_string` need a concrete type
+// to be rendered natively.
+// Enum values have a union of variants of one enum as their type, or a single
+// variant once narrowed.
+fn enum_variant_references_for_type(value_type: &Type) -> Option<Vec<EnumVariantReference>> {
+    match value_type {
+        Type::Union(members) => enum_variant_references(members),
+        Type::Named(_) => enum_variant_references(std::slice::from_ref(value_type)),
+        _ => None,
+    }
+}
+
+fn enum_variant_references(variants: &[Type]) -> Option<Vec<EnumVariantReference>> {
+    let variant_references = variants
+        .iter()
+        .map(|variant| {
+            let Type::Named(named) = variant else {
+                return None;
+            };
+            let (enum_name, variant_name) = named.display_name.rsplit_once('.')?;
+            Some(EnumVariantReference {
+                enum_name: enum_name.to_string(),
+                variant_name: variant_name.to_string(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let enum_name = &variant_references.first()?.enum_name;
+    variant_references
+        .iter()
+        .all(|variant_reference| variant_reference.enum_name == *enum_name)
+        .then_some(variant_references)
+}
+
+fn type_contains_generic_or_function_type(type_reference: &Type) -> bool {
+    match type_reference {
+        Type::TypeParameter(_) | Type::Function { .. } => true,
+        Type::List(element_type) => type_contains_generic_or_function_type(element_type),
+        Type::Applied { arguments, .. } => {
+            arguments.iter().any(type_contains_generic_or_function_type)
+        }
+        Type::Union(members) => members.iter().any(type_contains_generic_or_function_type),
+        Type::Integer64
+        | Type::Boolean
+        | Type::String
+        | Type::Nil
+        | Type::Never
+        | Type::Named(_)
+        | Type::Unknown => false,
+    }
+}
+
+// The types the comparison operators accept, which `min` and `max` share.
+fn is_ordered_type(type_reference: &Type) -> bool {
+    matches!(
+        type_reference,
+        Type::Integer64 | Type::Never | Type::Unknown
+    )
+}
@@ -0,0 +1,158 @@
+use compiler__semantic_program::{
+    SemanticBinaryOperator, SemanticConstantDeclaration, SemanticExpression,
+};
+use compiler__source::Span;
+use compiler__type_annotated_program::TypeAnnotatedInlineCandidate;
+
+use super::{ExpressionSpan, InlineInitializer, TypeChecker};
+
+impl TypeChecker<'_> {
+    pub(super) fn record_variable_inline_initializer(
+        &mut self,
+        name: &str,
+        declaration_span: &Span,
+        initializer: &SemanticExpression,
+    ) {
+        let Some(inline_initializer) = self.inline_initializer(declaration_span, initializer)
+        else {
+            return;
+        };
+        if let Some(info) = self.scopes.last_mut().and_then(|scope| scope.get_mut(name)) {
+            info.inline_initializer = Some(inline_initializer);
+        }
+    }
+
+    pub(super) fn record_constant_inline_initializer(
+        &mut self,
+        constant: &SemanticConstantDeclaration,
+    ) {
+        if let Some(inline_initializer) =
+            self.inline_initializer(&constant.span, &constant.expression)
+        {
+            self.inline_initializer_by_constant_name
+                .insert(constant.name.clone(), inline_initializer);
+        }
+    }
+
+    pub(super) fn collect_inline_candidates_in_current_scope(&mut self) {
+        let Some(scope) = self.scopes.last() else {
+            return;
+        };
+        let inline_candidates = scope
+            .iter()
+            .filter_map(|(name, info)| {
+                self.inline_candidate(
+                    name,
+                    &info.name_span,
+                    info.inline_initializer.as_ref()?,
+                    &info.reference_spans,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.inline_candidates.extend(inline_candidates);
+    }
+
+    pub(super) fn collect_constant_inline_candidates(
+        &mut self,
+        constants: &[&SemanticConstantDeclaration],
+    ) {
+        let inline_candidates = constants
+            .iter()
+            .filter_map(|constant| {
+                self.inline_candidate(
+                    &constant.name,
+                    &constant.name_span,
+                    self.inline_initializer_by_constant_name
+                        .get(&constant.name)?,
+                    self.reference_spans_by_constant_name.get(&constant.name)?,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.inline_candidates.extend(inline_candidates);
+        self.inline_candidates
+            .sort_by_key(|inline_candidate| inline_candidate.name_span.start);
+    }
+
+    // References inside string interpolation are never inlined because the
+    // initializer text may not be valid inside an interpolation.
+    fn inline_candidate(
+        &self,
+        name: &str,
+        name_span: &Span,
+        inline_initializer: &InlineInitializer,
+        reference_spans: &[Span],
+    ) -> Option<TypeAnnotatedInlineCandidate> {
+        let is_inside_interpolation = |span: &Span| {
+            self.enclosing_interpolation_expression_range(span)
+                .is_some()
+        };
+        if reference_spans.is_empty() || reference_spans.iter().any(is_inside_interpolation) {
+            return None;
+        }
+        Some(TypeAnnotatedInlineCandidate {
+            name: name.to_string(),
+            name_span: *name_span,
+            declaration_span: inline_initializer.declaration_span,
+            initializer_span: inline_initializer.initializer_span,
+            initializer_requires_parentheses: inline_initializer.requires_parentheses,
+            reference_spans: reference_spans.to_vec(),
+        })
+    }
+
+    fn inline_initializer(
+        &self,
+        declaration_span: &Span,
+        initializer: &SemanticExpression,
+    ) -> Option<InlineInitializer> {
+        if !self.is_side_effect_free(initializer) {
+            return None;
+        }
+        Some(InlineInitializer {
+            declaration_span: *declaration_span,
+            initializer_span: initializer.span(),
+            requires_parentheses: matches!(
+                initializer,
+                SemanticExpression::Unary { .. } | SemanticExpression::Binary { .. }
+            ),
+        })
+    }
+
+    // Only literals and constants combined by non-trapping operators qualify:
+    // evaluating them anywhere, any number of times, gives the same value.
+    // Names other than constants may be shadowed at a use site.
+    fn is_side_effect_free(&self, expression: &SemanticExpression) -> bool {
+        match expression {
+            SemanticExpression::IntegerLiteral { .. }
+            | SemanticExpression::NilLiteral { .. }
+            | SemanticExpression::BooleanLiteral { .. }
+            | SemanticExpression::StringLiteral { .. } => true,
+            SemanticExpression::NameReference { name, .. } => {
+                self.constants.contains_key(name)
+                    && !self.scopes.iter().any(|scope| scope.contains_key(name))
+            }
+            SemanticExpression::Unary { expression, .. } => self.is_side_effect_free(expression),
+            SemanticExpression::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                !matches!(
+                    operator,
+                    SemanticBinaryOperator::Divide | SemanticBinaryOperator::Modulo
+                ) && self.is_side_effect_free(left)
+                    && self.is_side_effect_free(right)
+            }
+            SemanticExpression::ListLiteral { .. }
+            | SemanticExpression::StructLiteral { .. }
+            | SemanticExpression::FieldAccess { .. }
+            | SemanticExpression::IndexAccess { .. }
+            | SemanticExpression::Call { .. }
+            | SemanticExpression::If { .. }
+            | SemanticExpression::Block { .. }
+            | SemanticExpression::Match { .. }
+            | SemanticExpression::Matches { .. }
+            | SemanticExpression::StringInterpolation { .. } => false,
+        }
+    }
+}
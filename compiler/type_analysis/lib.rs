@@ -1,60 +1,71 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
-use compiler__diagnostics::PhaseDiagnostic;
+use compiler__diagnostics::{PhaseDiagnostic, SuggestedFix};
+use compiler__interned_names::{PackagePath, SymbolName};
 use compiler__packages::PackageId;
 use compiler__phase_results::{PhaseOutput, PhaseStatus};
+use compiler__program_references::{
+    CallableReference, ConstantReference, InterfaceReference, NominalTypeReference, StructReference,
+};
 use compiler__safe_autofix::SafeAutofix;
 use compiler__semantic_program::{
-    SemanticAssignTarget, SemanticBinaryOperator, SemanticConstantDeclaration, SemanticDeclaration,
-    SemanticExpression, SemanticExpressionId, SemanticFile, SemanticFunctionDeclaration,
-    SemanticNameReferenceKind, SemanticStatement, SemanticTypeDeclaration, SemanticTypeName,
-    SemanticUnaryOperator,
+    SemanticAssignTarget, SemanticAttribute, SemanticBinaryOperator, SemanticConstantDeclaration,
+    SemanticDeclaration, SemanticExpression, SemanticExpressionId, SemanticFile,
+    SemanticFunctionDeclaration, SemanticMatchLiteral, SemanticNameReferenceKind,
+    SemanticParameterDeclaration, SemanticStatement, SemanticStaticAssertDeclaration,
+    SemanticTypeDeclaration, SemanticTypeName, SemanticUnaryOperator, deprecation_message,
 };
 use compiler__semantic_types::{
-    GenericTypeParameter, ImportedBinding, ImportedSymbol, ImportedTypeDeclaration, NominalTypeId,
-    NominalTypeRef, Type, type_from_builtin_name,
+    ForbiddenBuiltin, GenericTypeParameter, HostFunctionContract, ImportedBinding, ImportedSymbol,
+    ImportedTypeDeclaration, NominalTypeId, NominalTypeRef, Type, normalize_union,
+    type_from_builtin_name,
 };
-use compiler__source::Span;
+use compiler__source::{LanguageFeature, LanguageVersion, Span};
 use compiler__type_annotated_program::{
-    TypeAnnotatedAssignTarget, TypeAnnotatedBinaryOperator, TypeAnnotatedCallTarget,
-    TypeAnnotatedCallableReference, TypeAnnotatedConstantDeclaration,
-    TypeAnnotatedConstantReference, TypeAnnotatedEnumVariantReference, TypeAnnotatedExpression,
-    TypeAnnotatedFunctionDeclaration, TypeAnnotatedInterfaceDeclaration,
-    TypeAnnotatedInterfaceMethodDeclaration, TypeAnnotatedInterfaceReference,
-    TypeAnnotatedMatchArm, TypeAnnotatedMatchPattern, TypeAnnotatedMethodDeclaration,
-    TypeAnnotatedNameReferenceKind, TypeAnnotatedNominalTypeReference,
-    TypeAnnotatedParameterDeclaration, TypeAnnotatedResolvedTypeArgument, TypeAnnotatedStatement,
+    TypeAnnotatedAssignTarget, TypeAnnotatedAttribute, TypeAnnotatedBinaryOperator,
+    TypeAnnotatedCallTarget, TypeAnnotatedConstantDeclaration, TypeAnnotatedDeclarationSignatures,
+    TypeAnnotatedExpression, TypeAnnotatedFunctionDeclaration, TypeAnnotatedInlineCandidate,
+    TypeAnnotatedInterfaceDeclaration, TypeAnnotatedInterfaceMethodDeclaration,
+    TypeAnnotatedInterfaceSignature, TypeAnnotatedMatchArm, TypeAnnotatedMatchLiteral,
+    TypeAnnotatedMatchPattern, TypeAnnotatedMethodDeclaration, TypeAnnotatedMethodReference,
+    TypeAnnotatedMethodSignature, TypeAnnotatedNameReferenceKind,
+    TypeAnnotatedParameterDeclaration, TypeAnnotatedParameterSignature,
+    TypeAnnotatedResolvedTypeArgument, TypeAnnotatedStatement,
     TypeAnnotatedStringInterpolationPart, TypeAnnotatedStructDeclaration,
     TypeAnnotatedStructFieldDeclaration, TypeAnnotatedStructLiteralField,
-    TypeAnnotatedStructReference, TypeAnnotatedTypeName, TypeAnnotatedTypeNameSegment,
+    TypeAnnotatedStructSignature, TypeAnnotatedTypeName, TypeAnnotatedTypeNameSegment,
     TypeAnnotatedTypeParameter, TypeAnnotatedUnaryOperator, TypeResolvedDeclarations,
 };
 
 mod assignability;
 mod declarations;
+mod expression_annotations;
 mod expressions;
+mod inline_candidates;
 mod naming_rules;
 mod statements;
+mod static_assertions;
 mod type_narrowing;
 mod unused_bindings;
 
+use expression_annotations::{EnumCall, ExpressionAnnotations};
+
 struct TypeAnalysisSummary {
-    resolved_type_by_expression_id: BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    constant_reference_by_expression_id:
-        BTreeMap<SemanticExpressionId, TypeAnnotatedConstantReference>,
-    resolved_type_argument_types_by_expression_id:
-        BTreeMap<SemanticExpressionId, Vec<TypeAnnotatedResolvedTypeArgument>>,
-    struct_reference_by_expression_id: BTreeMap<SemanticExpressionId, TypeAnnotatedStructReference>,
-    enum_variant_reference_by_expression_id:
-        BTreeMap<SemanticExpressionId, TypeAnnotatedEnumVariantReference>,
-    nominal_type_reference_by_local_name: HashMap<String, TypeAnnotatedNominalTypeReference>,
-    implemented_interface_references_by_struct_name:
-        HashMap<String, Vec<TypeAnnotatedInterfaceReference>>,
-    type_declarations_for_annotations: Vec<SemanticTypeDeclaration>,
-    constant_declarations_for_annotations: Vec<SemanticConstantDeclaration>,
-    function_declarations_for_annotations: Vec<SemanticFunctionDeclaration>,
+    expression_annotations: ExpressionAnnotations,
+    nominal_type_reference_by_local_name: HashMap<String, NominalTypeReference>,
+    implemented_interface_references_by_struct_name: HashMap<String, Vec<InterfaceReference>>,
     resolved_declarations: ResolvedDeclarations,
+    inline_candidates: Vec<TypeAnnotatedInlineCandidate>,
+}
+
+// Checking and annotation building both read the semantic file through these
+// borrows instead of copying its declarations.
+struct PackageUnitDeclarations<'a> {
+    types: Vec<&'a SemanticTypeDeclaration>,
+    constants: Vec<&'a SemanticConstantDeclaration>,
+    functions: Vec<&'a SemanticFunctionDeclaration>,
+    static_asserts: Vec<&'a SemanticStaticAssertDeclaration>,
 }
 
 #[derive(Clone)]
@@ -62,10 +73,18 @@ pub enum TypeAnalysisBlockingReason {
     TypeErrorsPresent,
 }
 
+// A file's resolved declarations when it has no type errors, and the
+// signatures of its structs and interfaces either way.
+#[derive(Clone)]
+pub struct TypeCheckedPackageUnit {
+    pub resolved_declarations: Result<TypeResolvedDeclarations, TypeAnalysisBlockingReason>,
+    pub declaration_signatures: TypeAnnotatedDeclarationSignatures,
+}
+
 struct ResolvedDeclarations {
     constants_by_name: HashMap<String, ConstantInfo>,
     functions_by_name: HashMap<String, FunctionInfo>,
-    types_by_name: HashMap<String, TypeInfo>,
+    types: NominalTypeTable,
     methods_by_key: HashMap<MethodKey, MethodInfo>,
 }
 
@@ -76,27 +95,35 @@ pub fn check_package_unit(
     source_text: &str,
     package_unit: &SemanticFile,
     imported_bindings: &[ImportedBinding],
-) -> PhaseOutput<Result<TypeResolvedDeclarations, TypeAnalysisBlockingReason>> {
+    host_function_contracts: &[HostFunctionContract],
+    forbidden_builtins: &[ForbiddenBuiltin],
+    language_version: LanguageVersion,
+) -> PhaseOutput<TypeCheckedPackageUnit> {
     let mut diagnostics = Vec::new();
     let mut safe_autofixes = Vec::new();
+    let declarations = group_package_unit_declarations(package_unit);
     let summary = analyze_package_unit(
         package_id,
         package_path,
         source_text,
-        package_unit,
+        &declarations,
         imported_bindings,
+        host_function_contracts,
+        forbidden_builtins,
+        language_version,
         &mut diagnostics,
         &mut safe_autofixes,
     );
-    let status = if diagnostics.is_empty() {
-        PhaseStatus::Ok
-    } else {
+    let status = if diagnostics.iter().any(PhaseDiagnostic::is_error) {
         PhaseStatus::PreventsDownstreamExecution
+    } else {
+        PhaseStatus::Ok
     };
 
-    let value = if matches!(status, PhaseStatus::Ok) {
+    let resolved_declarations = if matches!(status, PhaseStatus::Ok) {
         Ok(build_resolved_declarations(
             package_path,
+            &declarations,
             &summary,
             &summary.nominal_type_reference_by_local_name,
         ))
@@ -105,7 +132,14 @@ pub fn check_package_unit(
     };
 
     PhaseOutput {
-        value,
+        value: TypeCheckedPackageUnit {
+            resolved_declarations,
+            declaration_signatures: build_declaration_signatures(
+                package_path,
+                &declarations,
+                &summary,
+            ),
+        },
         diagnostics,
         safe_autofixes,
         status,
@@ -114,49 +148,36 @@ pub fn check_package_unit(
 
 fn build_resolved_declarations(
     package_path: &str,
+    declarations: &PackageUnitDeclarations,
     summary: &TypeAnalysisSummary,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) -> TypeResolvedDeclarations {
     let mut resolved_declarations = TypeResolvedDeclarations {
         constant_declarations: build_constant_declaration_annotations(
             package_path,
-            &summary.constant_declarations_for_annotations,
+            &declarations.constants,
             &summary.resolved_declarations,
-            &summary.resolved_type_by_expression_id,
-            &summary.call_target_by_expression_id,
-            &summary.resolved_type_argument_types_by_expression_id,
-            &summary.struct_reference_by_expression_id,
-            &summary.enum_variant_reference_by_expression_id,
-            &summary.constant_reference_by_expression_id,
+            &summary.expression_annotations,
         ),
         interface_declarations: build_interface_declaration_annotations(
             package_path,
-            &summary.type_declarations_for_annotations,
+            &declarations.types,
             &summary.resolved_declarations,
         ),
         struct_declarations: build_struct_declaration_annotations(
             package_path,
-            &summary.type_declarations_for_annotations,
+            &declarations.types,
             &summary.implemented_interface_references_by_struct_name,
             &summary.resolved_declarations,
-            &summary.resolved_type_by_expression_id,
-            &summary.call_target_by_expression_id,
-            &summary.resolved_type_argument_types_by_expression_id,
-            &summary.struct_reference_by_expression_id,
-            &summary.enum_variant_reference_by_expression_id,
-            &summary.constant_reference_by_expression_id,
+            &summary.expression_annotations,
         ),
         function_declarations: build_function_declaration_annotations(
             package_path,
-            &summary.function_declarations_for_annotations,
+            &declarations.functions,
             &summary.resolved_declarations,
-            &summary.resolved_type_by_expression_id,
-            &summary.call_target_by_expression_id,
-            &summary.resolved_type_argument_types_by_expression_id,
-            &summary.struct_reference_by_expression_id,
-            &summary.enum_variant_reference_by_expression_id,
-            &summary.constant_reference_by_expression_id,
+            &summary.expression_annotations,
         ),
+        inline_candidates: summary.inline_candidates.clone(),
     };
     annotate_nominal_type_references(
         &mut resolved_declarations,
@@ -165,28 +186,134 @@ fn build_resolved_declarations(
     resolved_declarations
 }
 
+// Unlike the resolved declarations, these tolerate declarations that failed to
+// check: a method or type that did not resolve is left out or written as
+// `<unknown>`.
+fn build_declaration_signatures(
+    package_path: &str,
+    declarations: &PackageUnitDeclarations,
+    summary: &TypeAnalysisSummary,
+) -> TypeAnnotatedDeclarationSignatures {
+    let resolved_declarations = &summary.resolved_declarations;
+    let mut declaration_signatures = TypeAnnotatedDeclarationSignatures::default();
+    for type_declaration in &declarations.types {
+        let Some(type_info) = resolved_declarations
+            .types
+            .get_by_local_name(&type_declaration.name)
+        else {
+            continue;
+        };
+        match (&type_declaration.kind, &type_info.kind) {
+            (
+                compiler__semantic_program::SemanticTypeDeclarationKind::Interface { methods },
+                TypeKind::Interface {
+                    methods: interface_methods,
+                },
+            ) => {
+                declaration_signatures
+                    .interface_signatures
+                    .push(TypeAnnotatedInterfaceSignature {
+                        interface_reference: InterfaceReference {
+                            package_path: PackagePath::new(package_path),
+                            symbol_name: SymbolName::new(&type_declaration.name),
+                        },
+                        methods: methods
+                            .iter()
+                            .zip(interface_methods)
+                            .map(|(method, resolved_method)| {
+                                method_signature(
+                                    &method.name,
+                                    resolved_method.self_mutable,
+                                    &method.parameters,
+                                    &resolved_method.parameter_types,
+                                    &resolved_method.return_type,
+                                )
+                            })
+                            .collect(),
+                    });
+            }
+            (
+                compiler__semantic_program::SemanticTypeDeclarationKind::Struct { methods, .. },
+                TypeKind::Struct { .. },
+            ) => {
+                declaration_signatures
+                    .struct_signatures
+                    .push(TypeAnnotatedStructSignature {
+                        struct_reference: StructReference {
+                            package_path: PackagePath::new(package_path),
+                            symbol_name: SymbolName::new(&type_declaration.name),
+                        },
+                        implemented_interfaces: summary
+                            .implemented_interface_references_by_struct_name
+                            .get(&type_declaration.name)
+                            .cloned()
+                            .unwrap_or_default(),
+                        methods: methods
+                            .iter()
+                            .filter_map(|method| {
+                                let method_info =
+                                    resolved_declarations.methods_by_key.get(&MethodKey {
+                                        receiver_type_id: type_info.nominal_type_id.clone(),
+                                        method_name: method.name.clone(),
+                                    })?;
+                                Some(method_signature(
+                                    &method.name,
+                                    method_info.self_mutable,
+                                    &method.parameters,
+                                    &method_info.parameter_types,
+                                    &method_info.return_type,
+                                ))
+                            })
+                            .collect(),
+                    });
+            }
+            _ => {}
+        }
+    }
+    declaration_signatures
+}
+
+fn method_signature(
+    name: &str,
+    self_mutable: bool,
+    parameters: &[SemanticParameterDeclaration],
+    parameter_types: &[Type],
+    return_type: &Type,
+) -> TypeAnnotatedMethodSignature {
+    TypeAnnotatedMethodSignature {
+        name: name.to_string(),
+        self_mutable,
+        parameters: parameters
+            .iter()
+            .zip(parameter_types)
+            .map(
+                |(parameter, parameter_type)| TypeAnnotatedParameterSignature {
+                    name: parameter.name.clone(),
+                    mutable: parameter.mutable,
+                    parameter_type: parameter_type.display(),
+                },
+            )
+            .collect(),
+        return_type: return_type.display(),
+    }
+}
+
+fn type_annotated_attributes(attributes: &[SemanticAttribute]) -> Vec<TypeAnnotatedAttribute> {
+    attributes
+        .iter()
+        .map(|attribute| TypeAnnotatedAttribute {
+            kind: attribute.kind,
+            arguments: attribute.arguments.clone(),
+            span: attribute.span,
+        })
+        .collect()
+}
+
 fn build_constant_declaration_annotations(
     package_path: &str,
-    constant_declarations: &[SemanticConstantDeclaration],
+    constant_declarations: &[&SemanticConstantDeclaration],
     resolved_declarations: &ResolvedDeclarations,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> Vec<TypeAnnotatedConstantDeclaration> {
     constant_declarations
         .iter()
@@ -198,22 +325,18 @@ fn build_constant_declaration_annotations(
                 .expect("constant declaration must have resolved type info");
             TypeAnnotatedConstantDeclaration {
                 name: constant_declaration.name.clone(),
-                constant_reference: TypeAnnotatedConstantReference {
-                    package_path: package_path.to_string(),
-                    symbol_name: constant_declaration.name.clone(),
+                constant_reference: ConstantReference {
+                    package_path: PackagePath::new(package_path),
+                    symbol_name: SymbolName::new(&constant_declaration.name),
                 },
                 type_reference: type_annotated_resolved_type_argument_from_type(&resolved_type)
                     .expect("constant type must be fully resolved"),
                 initializer: type_annotated_expression_from_semantic_expression(
                     &constant_declaration.expression,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 ),
-                span: constant_declaration.span.clone(),
+                attributes: type_annotated_attributes(&constant_declaration.attributes),
+                span: constant_declaration.span,
             }
         })
         .collect()
@@ -221,26 +344,9 @@ fn build_constant_declaration_annotations(
 
 fn build_function_declaration_annotations(
     package_path: &str,
-    function_declarations: &[SemanticFunctionDeclaration],
+    function_declarations: &[&SemanticFunctionDeclaration],
     resolved_declarations: &ResolvedDeclarations,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> Vec<TypeAnnotatedFunctionDeclaration> {
     function_declarations
         .iter()
@@ -251,9 +357,9 @@ fn build_function_declaration_annotations(
                 .expect("function declaration must have resolved signature");
             TypeAnnotatedFunctionDeclaration {
                 name: function_declaration.name.clone(),
-                callable_reference: TypeAnnotatedCallableReference {
-                    package_path: package_path.to_string(),
-                    symbol_name: function_declaration.name.clone(),
+                callable_reference: CallableReference {
+                    package_path: PackagePath::new(package_path),
+                    symbol_name: SymbolName::new(&function_declaration.name),
                 },
                 type_parameters: function_declaration
                     .type_parameters
@@ -267,11 +373,11 @@ fn build_function_declaration_annotations(
                                 .as_ref()
                                 .and_then(|constraint| {
                                     type_annotated_interface_reference_from_type(
-                                        &resolved_declarations.types_by_name,
+                                        &resolved_declarations.types,
                                         constraint,
                                     )
                                 }),
-                            span: type_parameter.span.clone(),
+                            span: type_parameter.span,
                         },
                     )
                     .collect(),
@@ -287,7 +393,7 @@ fn build_function_declaration_annotations(
                                 resolved_parameter_type,
                             )
                             .expect("function parameter types must be fully resolved"),
-                            span: parameter.span.clone(),
+                            span: parameter.span,
                         },
                     )
                     .collect(),
@@ -295,7 +401,8 @@ fn build_function_declaration_annotations(
                     &function_info.return_type,
                 )
                 .expect("function return type must be fully resolved"),
-                span: function_declaration.span.clone(),
+                attributes: type_annotated_attributes(&function_declaration.attributes),
+                span: function_declaration.span,
                 statements: function_declaration
                     .body
                     .statements
@@ -303,12 +410,7 @@ fn build_function_declaration_annotations(
                     .map(|statement| {
                         type_annotated_statement_from_semantic_statement(
                             statement,
-                            resolved_type_by_expression_id,
-                            call_target_by_expression_id,
-                            resolved_type_argument_types_by_expression_id,
-                            struct_reference_by_expression_id,
-                            enum_variant_reference_by_expression_id,
-                            constant_reference_by_expression_id,
+                            expression_annotations,
                         )
                     })
                     .collect(),
@@ -319,30 +421,10 @@ fn build_function_declaration_annotations(
 
 fn build_struct_declaration_annotations(
     package_path: &str,
-    type_declarations: &[SemanticTypeDeclaration],
-    implemented_interface_references_by_struct_name: &HashMap<
-        String,
-        Vec<TypeAnnotatedInterfaceReference>,
-    >,
+    type_declarations: &[&SemanticTypeDeclaration],
+    implemented_interface_references_by_struct_name: &HashMap<String, Vec<InterfaceReference>>,
     resolved_declarations: &ResolvedDeclarations,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> Vec<TypeAnnotatedStructDeclaration> {
     type_declarations
         .iter()
@@ -352,17 +434,17 @@ fn build_struct_declaration_annotations(
                 methods,
             } => {
                 let type_info = resolved_declarations
-                    .types_by_name
-                    .get(&type_declaration.name)
+                    .types
+                    .get_by_local_name(&type_declaration.name)
                     .expect("struct declaration must have resolved type info");
                 let TypeKind::Struct { fields } = &type_info.kind else {
                     panic!("resolved struct declaration must have struct kind");
                 };
                 Some(TypeAnnotatedStructDeclaration {
                     name: type_declaration.name.clone(),
-                    struct_reference: TypeAnnotatedStructReference {
-                        package_path: package_path.to_string(),
-                        symbol_name: type_declaration.name.clone(),
+                    struct_reference: StructReference {
+                        package_path: PackagePath::new(package_path),
+                        symbol_name: SymbolName::new(&type_declaration.name),
                     },
                     type_parameters: type_declaration
                         .type_parameters
@@ -376,11 +458,11 @@ fn build_struct_declaration_annotations(
                                     .as_ref()
                                     .and_then(|constraint| {
                                         type_annotated_interface_reference_from_type(
-                                            &resolved_declarations.types_by_name,
+                                            &resolved_declarations.types,
                                             constraint,
                                         )
                                     }),
-                                span: type_parameter.span.clone(),
+                                span: type_parameter.span,
                             }
                         })
                         .collect(),
@@ -398,7 +480,7 @@ fn build_struct_declaration_annotations(
                                     field_type,
                                 )
                                 .expect("struct field types must be fully resolved"),
-                                span: semantic_field.span.clone(),
+                                span: semantic_field.span,
                             }
                         })
                         .collect(),
@@ -431,7 +513,7 @@ fn build_struct_declaration_annotations(
                                                 .expect(
                                                     "method parameter types must be fully resolved",
                                                 ),
-                                            span: parameter.span.clone(),
+                                            span: parameter.span,
                                         }
                                     })
                                     .collect(),
@@ -440,7 +522,7 @@ fn build_struct_declaration_annotations(
                                         &method_info.return_type,
                                     )
                                     .expect("method return type must be fully resolved"),
-                                span: method.span.clone(),
+                                span: method.span,
                                 statements: method
                                     .body
                                     .statements
@@ -448,19 +530,15 @@ fn build_struct_declaration_annotations(
                                     .map(|statement| {
                                         type_annotated_statement_from_semantic_statement(
                                             statement,
-                                            resolved_type_by_expression_id,
-                                            call_target_by_expression_id,
-                                            resolved_type_argument_types_by_expression_id,
-                                            struct_reference_by_expression_id,
-                                            enum_variant_reference_by_expression_id,
-                                            constant_reference_by_expression_id,
+                                            expression_annotations,
                                         )
                                     })
                                     .collect(),
                             }
                         })
                         .collect(),
-                    span: type_declaration.span.clone(),
+                    attributes: type_annotated_attributes(&type_declaration.attributes),
+                    span: type_declaration.span,
                 })
             }
             compiler__semantic_program::SemanticTypeDeclarationKind::Enum { .. }
@@ -472,7 +550,7 @@ fn build_struct_declaration_annotations(
 
 fn build_interface_declaration_annotations(
     package_path: &str,
-    type_declarations: &[SemanticTypeDeclaration],
+    type_declarations: &[&SemanticTypeDeclaration],
     resolved_declarations: &ResolvedDeclarations,
 ) -> Vec<TypeAnnotatedInterfaceDeclaration> {
     type_declarations
@@ -480,8 +558,8 @@ fn build_interface_declaration_annotations(
         .filter_map(|type_declaration| match &type_declaration.kind {
             compiler__semantic_program::SemanticTypeDeclarationKind::Interface { methods } => {
                 let type_info = resolved_declarations
-                    .types_by_name
-                    .get(&type_declaration.name)
+                    .types
+                    .get_by_local_name(&type_declaration.name)
                     .expect("interface declaration must have resolved type info");
                 let TypeKind::Interface {
                     methods: interface_methods,
@@ -491,9 +569,9 @@ fn build_interface_declaration_annotations(
                 };
                 Some(TypeAnnotatedInterfaceDeclaration {
                     name: type_declaration.name.clone(),
-                    interface_reference: TypeAnnotatedInterfaceReference {
-                        package_path: package_path.to_string(),
-                        symbol_name: type_declaration.name.clone(),
+                    interface_reference: InterfaceReference {
+                        package_path: PackagePath::new(package_path),
+                        symbol_name: SymbolName::new(&type_declaration.name),
                     },
                     methods: methods
                         .iter()
@@ -513,7 +591,7 @@ fn build_interface_declaration_annotations(
                                             resolved_parameter_type,
                                         )
                                         .expect("interface method parameter types must be fully resolved"),
-                                        span: parameter.span.clone(),
+                                        span: parameter.span,
                                     }
                                 })
                                 .collect(),
@@ -521,10 +599,11 @@ fn build_interface_declaration_annotations(
                                 &resolved_method.return_type,
                             )
                             .expect("interface method return type must be fully resolved"),
-                            span: method.span.clone(),
+                            span: method.span,
                         })
                         .collect(),
-                    span: type_declaration.span.clone(),
+                    attributes: type_annotated_attributes(&type_declaration.attributes),
+                    span: type_declaration.span,
                 })
             }
             compiler__semantic_program::SemanticTypeDeclarationKind::Struct { .. }
@@ -535,46 +614,27 @@ fn build_interface_declaration_annotations(
 }
 
 fn type_annotated_interface_reference_from_type(
-    types_by_name: &HashMap<String, TypeInfo>,
+    types: &NominalTypeTable,
     value_type: &Type,
-) -> Option<TypeAnnotatedInterfaceReference> {
+) -> Option<InterfaceReference> {
     let nominal_type_id = match value_type {
         Type::Named(named) => Some(named.id.clone()),
         Type::Applied { base, .. } => Some(base.id.clone()),
         _ => None,
     }?;
-    let type_info = types_by_name
-        .values()
-        .find(|info| info.nominal_type_id == nominal_type_id)?;
+    let type_info = types.get_by_nominal_type_id(&nominal_type_id)?;
     if !matches!(type_info.kind, TypeKind::Interface { .. }) {
         return None;
     }
-    Some(TypeAnnotatedInterfaceReference {
-        package_path: type_info.package_path.clone(),
-        symbol_name: nominal_type_id.symbol_name,
+    Some(InterfaceReference {
+        package_path: PackagePath::new(&type_info.package_path),
+        symbol_name: SymbolName::new(&nominal_type_id.symbol_name),
     })
 }
 
 fn type_annotated_statement_from_semantic_statement(
     statement: &SemanticStatement,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> TypeAnnotatedStatement {
     match statement {
         SemanticStatement::Binding {
@@ -588,14 +648,9 @@ fn type_annotated_statement_from_semantic_statement(
             mutable: *mutable,
             initializer: type_annotated_expression_from_semantic_expression(
                 initializer,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             ),
-            span: span.clone(),
+            span: *span,
         },
         SemanticStatement::Assign {
             target,
@@ -604,23 +659,13 @@ fn type_annotated_statement_from_semantic_statement(
         } => TypeAnnotatedStatement::Assign {
             target: type_annotated_assign_target_from_semantic_assign_target(
                 target,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             ),
             value: type_annotated_expression_from_semantic_expression(
                 value,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             ),
-            span: span.clone(),
+            span: *span,
         },
         SemanticStatement::If {
             condition,
@@ -630,12 +675,7 @@ fn type_annotated_statement_from_semantic_statement(
         } => TypeAnnotatedStatement::If {
             condition: type_annotated_expression_from_semantic_expression(
                 condition,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             ),
             then_statements: then_block
                 .statements
@@ -643,12 +683,7 @@ fn type_annotated_statement_from_semantic_statement(
                 .map(|statement| {
                     type_annotated_statement_from_semantic_statement(
                         statement,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
+                        expression_annotations,
                     )
                 })
                 .collect(),
@@ -659,17 +694,37 @@ fn type_annotated_statement_from_semantic_statement(
                     .map(|statement| {
                         type_annotated_statement_from_semantic_statement(
                             statement,
-                            resolved_type_by_expression_id,
-                            call_target_by_expression_id,
-                            resolved_type_argument_types_by_expression_id,
-                            struct_reference_by_expression_id,
-                            enum_variant_reference_by_expression_id,
-                            constant_reference_by_expression_id,
+                            expression_annotations,
                         )
                     })
                     .collect()
             }),
-            span: span.clone(),
+            span: *span,
+        },
+        // Later phases see a guard as an `if` whose only branch is `else`.
+        SemanticStatement::Guard {
+            condition,
+            else_block,
+            span,
+        } => TypeAnnotatedStatement::If {
+            condition: type_annotated_expression_from_semantic_expression(
+                condition,
+                expression_annotations,
+            ),
+            then_statements: Vec::new(),
+            else_statements: Some(
+                else_block
+                    .statements
+                    .iter()
+                    .map(|statement| {
+                        type_annotated_statement_from_semantic_statement(
+                            statement,
+                            expression_annotations,
+                        )
+                    })
+                    .collect(),
+            ),
+            span: *span,
         },
         SemanticStatement::For {
             condition,
@@ -679,12 +734,7 @@ fn type_annotated_statement_from_semantic_statement(
             condition: condition.as_ref().map(|expression| {
                 type_annotated_expression_from_semantic_expression(
                     expression,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )
             }),
             body_statements: body
@@ -693,78 +743,87 @@ fn type_annotated_statement_from_semantic_statement(
                 .map(|statement| {
                     type_annotated_statement_from_semantic_statement(
                         statement,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
+                        expression_annotations,
                     )
                 })
                 .collect(),
-            span: span.clone(),
+            span: *span,
+        },
+        SemanticStatement::ForRange {
+            name,
+            start,
+            end,
+            step,
+            body,
+            span,
+            ..
+        } => TypeAnnotatedStatement::ForRange {
+            name: name.clone(),
+            start: Box::new(type_annotated_expression_from_semantic_expression(
+                start,
+                expression_annotations,
+            )),
+            end: Box::new(type_annotated_expression_from_semantic_expression(
+                end,
+                expression_annotations,
+            )),
+            step: step.as_ref().map(|expression| {
+                Box::new(type_annotated_expression_from_semantic_expression(
+                    expression,
+                    expression_annotations,
+                ))
+            }),
+            body_statements: body
+                .statements
+                .iter()
+                .map(|statement| {
+                    type_annotated_statement_from_semantic_statement(
+                        statement,
+                        expression_annotations,
+                    )
+                })
+                .collect(),
+            span: *span,
+        },
+        SemanticStatement::Break { span } => TypeAnnotatedStatement::Break { span: *span },
+        SemanticStatement::Continue { span } => TypeAnnotatedStatement::Continue { span: *span },
+        SemanticStatement::Defer { value, span } => TypeAnnotatedStatement::Defer {
+            value: type_annotated_expression_from_semantic_expression(
+                value,
+                expression_annotations,
+            ),
+            span: *span,
         },
-        SemanticStatement::Break { span } => TypeAnnotatedStatement::Break { span: span.clone() },
-        SemanticStatement::Continue { span } => {
-            TypeAnnotatedStatement::Continue { span: span.clone() }
-        }
         SemanticStatement::Expression { value, span } => TypeAnnotatedStatement::Expression {
             value: type_annotated_expression_from_semantic_expression(
                 value,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             ),
-            span: span.clone(),
+            span: *span,
         },
         SemanticStatement::Return { value, span } => TypeAnnotatedStatement::Return {
             value: value.as_ref().map_or_else(
-                || TypeAnnotatedExpression::NilLiteral { span: span.clone() },
+                || TypeAnnotatedExpression::NilLiteral { span: *span },
                 |value| {
                     type_annotated_expression_from_semantic_expression(
                         value,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
+                        expression_annotations,
                     )
                 },
             ),
-            span: span.clone(),
+            span: *span,
         },
     }
 }
 
 fn type_annotated_assign_target_from_semantic_assign_target(
     target: &SemanticAssignTarget,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> TypeAnnotatedAssignTarget {
     match target {
         SemanticAssignTarget::Name { name, span, .. } => TypeAnnotatedAssignTarget::Name {
             name: name.clone(),
-            span: span.clone(),
+            span: *span,
         },
         SemanticAssignTarget::Index {
             target,
@@ -773,68 +832,41 @@ fn type_annotated_assign_target_from_semantic_assign_target(
         } => TypeAnnotatedAssignTarget::Index {
             target: Box::new(type_annotated_expression_from_semantic_expression(
                 target,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
             index: Box::new(type_annotated_expression_from_semantic_expression(
                 index,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
-            span: span.clone(),
+            span: *span,
         },
     }
 }
 
 fn type_annotated_expression_from_semantic_expression(
     expression: &SemanticExpression,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> TypeAnnotatedExpression {
     match expression {
         SemanticExpression::IntegerLiteral { value, span, .. } => {
             TypeAnnotatedExpression::IntegerLiteral {
                 value: *value,
-                span: span.clone(),
+                span: *span,
             }
         }
         SemanticExpression::BooleanLiteral { value, span, .. } => {
             TypeAnnotatedExpression::BooleanLiteral {
                 value: *value,
-                span: span.clone(),
+                span: *span,
             }
         }
         SemanticExpression::NilLiteral { span, .. } => {
-            TypeAnnotatedExpression::NilLiteral { span: span.clone() }
+            TypeAnnotatedExpression::NilLiteral { span: *span }
         }
         SemanticExpression::StringLiteral { value, span, .. } => {
             TypeAnnotatedExpression::StringLiteral {
                 value: value.clone(),
-                span: span.clone(),
+                span: *span,
             }
         }
         SemanticExpression::ListLiteral { elements, span, .. } => {
@@ -844,17 +876,12 @@ fn type_annotated_expression_from_semantic_expression(
                     .map(|element| {
                         type_annotated_expression_from_semantic_expression(
                             element,
-                            resolved_type_by_expression_id,
-                            call_target_by_expression_id,
-                            resolved_type_argument_types_by_expression_id,
-                            struct_reference_by_expression_id,
-                            enum_variant_reference_by_expression_id,
-                            constant_reference_by_expression_id,
+                            expression_annotations,
                         )
                     })
                     .collect(),
-                element_type: resolved_type_by_expression_id
-                    .get(&semantic_expression_id(expression))
+                element_type: expression_annotations
+                    .resolved_type(semantic_expression_id(expression))
                     .and_then(|resolved_type| match resolved_type {
                         Type::List(element_type) => {
                             type_annotated_resolved_type_argument_from_type(element_type)
@@ -862,7 +889,7 @@ fn type_annotated_expression_from_semantic_expression(
                         _ => None,
                     })
                     .expect("list literal element types must be fully resolved"),
-                span: span.clone(),
+                span: *span,
             }
         }
         SemanticExpression::NameReference {
@@ -875,33 +902,35 @@ fn type_annotated_expression_from_semantic_expression(
                 }
                 SemanticNameReferenceKind::Builtin => TypeAnnotatedNameReferenceKind::Builtin,
             },
-            constant_reference: constant_reference_by_expression_id
-                .get(&semantic_expression_id(expression))
+            constant_reference: expression_annotations
+                .constant_reference(semantic_expression_id(expression))
                 .cloned(),
-            callable_reference: call_target_by_expression_id
-                .get(&semantic_expression_id(expression))
+            callable_reference: expression_annotations
+                .call_target(semantic_expression_id(expression))
                 .and_then(|call_target| match call_target {
                     TypeAnnotatedCallTarget::UserDefinedFunction { callable_reference } => {
                         Some(callable_reference.clone())
                     }
-                    TypeAnnotatedCallTarget::BuiltinFunction { .. } => None,
+                    TypeAnnotatedCallTarget::BuiltinFunction { .. }
+                    | TypeAnnotatedCallTarget::UserDefinedMethod { .. } => None,
                 }),
-            type_reference: resolved_type_by_expression_id
-                .get(&semantic_expression_id(expression))
+            type_reference: expression_annotations
+                .resolved_type(semantic_expression_id(expression))
                 .and_then(type_annotated_resolved_type_argument_from_type)
                 .expect("name reference types must be fully resolved"),
-            span: span.clone(),
+            span: *span,
         },
         SemanticExpression::FieldAccess { span, .. }
-            if enum_variant_reference_by_expression_id
-                .contains_key(&semantic_expression_id(expression)) =>
+            if expression_annotations
+                .enum_variant_reference(semantic_expression_id(expression))
+                .is_some() =>
         {
             TypeAnnotatedExpression::EnumVariantLiteral {
-                enum_variant_reference: enum_variant_reference_by_expression_id
-                    .get(&semantic_expression_id(expression))
+                enum_variant_reference: expression_annotations
+                    .enum_variant_reference(semantic_expression_id(expression))
                     .cloned()
                     .expect("checked by contains_key"),
-                span: span.clone(),
+                span: *span,
             }
         }
         SemanticExpression::StructLiteral {
@@ -917,20 +946,19 @@ fn type_annotated_expression_from_semantic_expression(
                     name: field.name.clone(),
                     value: type_annotated_expression_from_semantic_expression(
                         &field.value,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
+                        expression_annotations,
                     ),
-                    span: field.span.clone(),
+                    span: field.span,
                 })
                 .collect(),
-            struct_reference: struct_reference_by_expression_id
-                .get(&semantic_expression_id(expression))
+            struct_reference: expression_annotations
+                .struct_reference(semantic_expression_id(expression))
                 .cloned(),
-            span: span.clone(),
+            resolved_type_arguments: expression_annotations
+                .resolved_type_argument_types(semantic_expression_id(expression))
+                .cloned()
+                .unwrap_or_default(),
+            span: *span,
         },
         SemanticExpression::FieldAccess {
             target,
@@ -940,15 +968,13 @@ fn type_annotated_expression_from_semantic_expression(
         } => TypeAnnotatedExpression::FieldAccess {
             target: Box::new(type_annotated_expression_from_semantic_expression(
                 target,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
             field: field.clone(),
-            span: span.clone(),
+            type_reference: expression_annotations
+                .resolved_type(semantic_expression_id(expression))
+                .and_then(type_annotated_resolved_type_argument_from_type),
+            span: *span,
         },
         SemanticExpression::IndexAccess {
             target,
@@ -958,29 +984,19 @@ fn type_annotated_expression_from_semantic_expression(
         } => TypeAnnotatedExpression::IndexAccess {
             target: Box::new(type_annotated_expression_from_semantic_expression(
                 target,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
             index: Box::new(type_annotated_expression_from_semantic_expression(
                 index,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
-            span: span.clone(),
+            span: *span,
         },
         SemanticExpression::Unary {
+            id,
             operator,
             expression,
             span,
-            ..
         } => TypeAnnotatedExpression::Unary {
             operator: match operator {
                 SemanticUnaryOperator::Not => TypeAnnotatedUnaryOperator::Not,
@@ -988,14 +1004,12 @@ fn type_annotated_expression_from_semantic_expression(
             },
             expression: Box::new(type_annotated_expression_from_semantic_expression(
                 expression,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
-            span: span.clone(),
+            operation_description: expression_annotations
+                .operation_description(*id)
+                .map(str::to_string),
+            span: *span,
         },
         SemanticExpression::Binary {
             operator,
@@ -1008,289 +1022,345 @@ fn type_annotated_expression_from_semantic_expression(
                 operator: TypeAnnotatedBinaryOperator::Add,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::Subtract => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::Subtract,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::Multiply => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::Multiply,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::Divide => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::Divide,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::Modulo => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::Modulo,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
+                )),
+                right: Box::new(type_annotated_expression_from_semantic_expression(
+                    right,
+                    expression_annotations,
+                )),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
+            },
+            SemanticBinaryOperator::BitwiseAnd => TypeAnnotatedExpression::Binary {
+                operator: TypeAnnotatedBinaryOperator::BitwiseAnd,
+                left: Box::new(type_annotated_expression_from_semantic_expression(
+                    left,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
+            },
+            SemanticBinaryOperator::BitwiseOr => TypeAnnotatedExpression::Binary {
+                operator: TypeAnnotatedBinaryOperator::BitwiseOr,
+                left: Box::new(type_annotated_expression_from_semantic_expression(
+                    left,
+                    expression_annotations,
+                )),
+                right: Box::new(type_annotated_expression_from_semantic_expression(
+                    right,
+                    expression_annotations,
+                )),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
+            },
+            SemanticBinaryOperator::BitwiseXor => TypeAnnotatedExpression::Binary {
+                operator: TypeAnnotatedBinaryOperator::BitwiseXor,
+                left: Box::new(type_annotated_expression_from_semantic_expression(
+                    left,
+                    expression_annotations,
+                )),
+                right: Box::new(type_annotated_expression_from_semantic_expression(
+                    right,
+                    expression_annotations,
+                )),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
+            },
+            SemanticBinaryOperator::ShiftLeft => TypeAnnotatedExpression::Binary {
+                operator: TypeAnnotatedBinaryOperator::ShiftLeft,
+                left: Box::new(type_annotated_expression_from_semantic_expression(
+                    left,
+                    expression_annotations,
+                )),
+                right: Box::new(type_annotated_expression_from_semantic_expression(
+                    right,
+                    expression_annotations,
+                )),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
+            },
+            SemanticBinaryOperator::ShiftRight => TypeAnnotatedExpression::Binary {
+                operator: TypeAnnotatedBinaryOperator::ShiftRight,
+                left: Box::new(type_annotated_expression_from_semantic_expression(
+                    left,
+                    expression_annotations,
+                )),
+                right: Box::new(type_annotated_expression_from_semantic_expression(
+                    right,
+                    expression_annotations,
+                )),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::EqualEqual => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::EqualEqual,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::NotEqual => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::NotEqual,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::LessThan => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::LessThan,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::LessThanOrEqual => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::LessThanOrEqual,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::GreaterThan => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::GreaterThan,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::GreaterThanOrEqual => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::GreaterThanOrEqual,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::And => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::And,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
             SemanticBinaryOperator::Or => TypeAnnotatedExpression::Binary {
                 operator: TypeAnnotatedBinaryOperator::Or,
                 left: Box::new(type_annotated_expression_from_semantic_expression(
                     left,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
                 right: Box::new(type_annotated_expression_from_semantic_expression(
                     right,
-                    resolved_type_by_expression_id,
-                    call_target_by_expression_id,
-                    resolved_type_argument_types_by_expression_id,
-                    struct_reference_by_expression_id,
-                    enum_variant_reference_by_expression_id,
-                    constant_reference_by_expression_id,
+                    expression_annotations,
                 )),
-                span: span.clone(),
+                operation_description: expression_annotations
+                    .operation_description(semantic_expression_id(expression))
+                    .map(str::to_string),
+                span: *span,
             },
         },
+        SemanticExpression::Call {
+            arguments, span, ..
+        } if expression_annotations
+            .enum_call(semantic_expression_id(expression))
+            .is_some() =>
+        {
+            match expression_annotations
+                .enum_call(semantic_expression_id(expression))
+                .expect("checked by is_some")
+            {
+                EnumCall::All(variant_references) => TypeAnnotatedExpression::ListLiteral {
+                    elements: variant_references
+                        .iter()
+                        .map(
+                            |enum_variant_reference| TypeAnnotatedExpression::EnumVariantLiteral {
+                                enum_variant_reference: enum_variant_reference.clone(),
+                                span: *span,
+                            },
+                        )
+                        .collect(),
+                    element_type: expression_annotations
+                        .resolved_type(semantic_expression_id(expression))
+                        .and_then(|resolved_type| match resolved_type {
+                            Type::List(element_type) => {
+                                type_annotated_resolved_type_argument_from_type(element_type)
+                            }
+                            _ => None,
+                        })
+                        .expect("enum variant types must be fully resolved"),
+                    span: *span,
+                },
+                EnumCall::Count(count) => TypeAnnotatedExpression::IntegerLiteral {
+                    value: i64::try_from(*count).expect("enum variant count should fit in int64"),
+                    span: *span,
+                },
+                EnumCall::VariantName(variant_references) => {
+                    TypeAnnotatedExpression::EnumVariantName {
+                        value: Box::new(type_annotated_expression_from_semantic_expression(
+                            &arguments[0],
+                            expression_annotations,
+                        )),
+                        enum_variant_references: variant_references.clone(),
+                        span: *span,
+                    }
+                }
+                EnumCall::FromString(variant_references) => {
+                    TypeAnnotatedExpression::EnumVariantFromString {
+                        value: Box::new(type_annotated_expression_from_semantic_expression(
+                            &arguments[0],
+                            expression_annotations,
+                        )),
+                        enum_variant_references: variant_references.clone(),
+                        span: *span,
+                    }
+                }
+            }
+        }
+        SemanticExpression::Call {
+            arguments, span, ..
+        } if expression_annotations
+            .format_template_parts(semantic_expression_id(expression))
+            .is_some() =>
+        {
+            type_annotated_format_call_from_semantic_arguments(
+                expression_annotations
+                    .format_template_parts(semantic_expression_id(expression))
+                    .expect("checked by is_some"),
+                &arguments[1..],
+                *span,
+                expression_annotations,
+            )
+        }
         SemanticExpression::Call {
             callee,
             type_arguments,
@@ -1300,67 +1370,99 @@ fn type_annotated_expression_from_semantic_expression(
         } => TypeAnnotatedExpression::Call {
             callee: Box::new(type_annotated_expression_from_semantic_expression(
                 callee,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
-            call_target: call_target_by_expression_id
-                .get(&semantic_expression_id(expression))
+            call_target: expression_annotations
+                .call_target(semantic_expression_id(expression))
                 .cloned(),
             arguments: arguments
                 .iter()
                 .map(|argument| {
                     type_annotated_expression_from_semantic_expression(
                         argument,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
+                        expression_annotations,
                     )
                 })
+                // Failed assertions report which condition failed and where, so
+                // `assert` calls carry a description of the condition.
+                .chain(
+                    expression_annotations
+                        .assertion_description(semantic_expression_id(expression))
+                        .map(
+                            |assertion_description| TypeAnnotatedExpression::StringLiteral {
+                                value: assertion_description.to_string(),
+                                span: *span,
+                            },
+                        ),
+                )
                 .collect(),
             type_arguments: type_arguments
                 .iter()
                 .map(type_annotated_type_name_from_semantic_type_name)
                 .collect(),
-            resolved_type_arguments: resolved_type_argument_types_by_expression_id
-                .get(&semantic_expression_id(expression))
+            resolved_type_arguments: expression_annotations
+                .resolved_type_argument_types(semantic_expression_id(expression))
                 .cloned()
                 .unwrap_or_default(),
-            span: span.clone(),
+            span: *span,
+        },
+        SemanticExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
+            ..
+        } => TypeAnnotatedExpression::If {
+            condition: Box::new(type_annotated_expression_from_semantic_expression(
+                condition,
+                expression_annotations,
+            )),
+            then_branch: Box::new(type_annotated_expression_from_semantic_expression(
+                then_branch,
+                expression_annotations,
+            )),
+            else_branch: Box::new(type_annotated_expression_from_semantic_expression(
+                else_branch,
+                expression_annotations,
+            )),
+            type_reference: expression_annotations
+                .resolved_type(semantic_expression_id(expression))
+                .and_then(type_annotated_resolved_type_argument_from_type),
+            span: *span,
+        },
+        SemanticExpression::Block {
+            block, value, span, ..
+        } => TypeAnnotatedExpression::Block {
+            statements: block
+                .statements
+                .iter()
+                .map(|statement| {
+                    type_annotated_statement_from_semantic_statement(
+                        statement,
+                        expression_annotations,
+                    )
+                })
+                .collect(),
+            value: Box::new(type_annotated_expression_from_semantic_expression(
+                value,
+                expression_annotations,
+            )),
+            span: *span,
         },
         SemanticExpression::Match {
             target, arms, span, ..
         } => TypeAnnotatedExpression::Match {
             target: Box::new(type_annotated_expression_from_semantic_expression(
                 target,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
             arms: arms
                 .iter()
                 .map(|arm| {
-                    type_annotated_match_arm_from_semantic_match_arm(
-                        arm,
-                        resolved_type_by_expression_id,
-                        call_target_by_expression_id,
-                        resolved_type_argument_types_by_expression_id,
-                        struct_reference_by_expression_id,
-                        enum_variant_reference_by_expression_id,
-                        constant_reference_by_expression_id,
-                    )
+                    type_annotated_match_arm_from_semantic_match_arm(arm, expression_annotations)
                 })
                 .collect(),
-            span: span.clone(),
+            span: *span,
         },
         SemanticExpression::Matches {
             value,
@@ -1370,15 +1472,10 @@ fn type_annotated_expression_from_semantic_expression(
         } => TypeAnnotatedExpression::Matches {
             value: Box::new(type_annotated_expression_from_semantic_expression(
                 value,
-                resolved_type_by_expression_id,
-                call_target_by_expression_id,
-                resolved_type_argument_types_by_expression_id,
-                struct_reference_by_expression_id,
-                enum_variant_reference_by_expression_id,
-                constant_reference_by_expression_id,
+                expression_annotations,
             )),
             type_name: type_annotated_type_name_from_semantic_type_name(type_name),
-            span: span.clone(),
+            span: *span,
         },
         SemanticExpression::StringInterpolation { parts, span, .. } => {
             use compiler__semantic_program::SemanticStringInterpolationPart;
@@ -1393,56 +1490,89 @@ fn type_annotated_expression_from_semantic_expression(
                             TypeAnnotatedStringInterpolationPart::Expression(Box::new(
                                 type_annotated_expression_from_semantic_expression(
                                     expression,
-                                    resolved_type_by_expression_id,
-                                    call_target_by_expression_id,
-                                    resolved_type_argument_types_by_expression_id,
-                                    struct_reference_by_expression_id,
-                                    enum_variant_reference_by_expression_id,
-                                    constant_reference_by_expression_id,
+                                    expression_annotations,
                                 ),
                             ))
                         }
                     })
                     .collect(),
-                span: span.clone(),
+                span: *span,
             }
         }
     }
 }
 
+// A `format` call becomes an interpolation of its template parts and
+// arguments, with each non-string argument passed through `string`.
+fn type_annotated_format_call_from_semantic_arguments(
+    template_parts: &[String],
+    values: &[SemanticExpression],
+    span: Span,
+    expression_annotations: &ExpressionAnnotations,
+) -> TypeAnnotatedExpression {
+    let mut parts = Vec::new();
+    for (index, template_part) in template_parts.iter().enumerate() {
+        parts.push(TypeAnnotatedStringInterpolationPart::Literal(
+            template_part.clone(),
+        ));
+        let Some(value) = values.get(index) else {
+            continue;
+        };
+        let value_type = expression_annotations
+            .resolved_type(semantic_expression_id(value))
+            .cloned()
+            .expect("format argument types must be fully resolved");
+        let annotated_value =
+            type_annotated_expression_from_semantic_expression(value, expression_annotations);
+        let string_value = if value_type == Type::String {
+            annotated_value
+        } else {
+            let conversion_type = Type::Function {
+                parameter_types: vec![value_type],
+                return_type: Box::new(Type::String),
+            };
+            TypeAnnotatedExpression::Call {
+                callee: Box::new(TypeAnnotatedExpression::NameReference {
+                    name: "string".to_string(),
+                    kind: TypeAnnotatedNameReferenceKind::UserDefined,
+                    constant_reference: None,
+                    callable_reference: None,
+                    type_reference: type_annotated_resolved_type_argument_from_type(
+                        &conversion_type,
+                    )
+                    .expect("format conversion types must be fully resolved"),
+                    span: value.span(),
+                }),
+                call_target: Some(TypeAnnotatedCallTarget::BuiltinFunction {
+                    function_name: "string".to_string(),
+                }),
+                arguments: vec![annotated_value],
+                type_arguments: Vec::new(),
+                resolved_type_arguments: Vec::new(),
+                span: value.span(),
+            }
+        };
+        parts.push(TypeAnnotatedStringInterpolationPart::Expression(Box::new(
+            string_value,
+        )));
+    }
+    TypeAnnotatedExpression::StringInterpolation { parts, span }
+}
+
 fn type_annotated_match_arm_from_semantic_match_arm(
     arm: &compiler__semantic_program::SemanticMatchArm,
-    resolved_type_by_expression_id: &BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: &BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    resolved_type_argument_types_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        Vec<TypeAnnotatedResolvedTypeArgument>,
-    >,
-    struct_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedStructReference,
-    >,
-    enum_variant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedEnumVariantReference,
-    >,
-    constant_reference_by_expression_id: &BTreeMap<
-        SemanticExpressionId,
-        TypeAnnotatedConstantReference,
-    >,
+    expression_annotations: &ExpressionAnnotations,
 ) -> TypeAnnotatedMatchArm {
     TypeAnnotatedMatchArm {
         pattern: type_annotated_match_pattern_from_semantic_match_pattern(&arm.pattern),
+        guard: arm.guard.as_ref().map(|guard| {
+            type_annotated_expression_from_semantic_expression(guard, expression_annotations)
+        }),
         value: type_annotated_expression_from_semantic_expression(
             &arm.value,
-            resolved_type_by_expression_id,
-            call_target_by_expression_id,
-            resolved_type_argument_types_by_expression_id,
-            struct_reference_by_expression_id,
-            enum_variant_reference_by_expression_id,
-            constant_reference_by_expression_id,
+            expression_annotations,
         ),
-        span: arm.span.clone(),
+        span: arm.span,
     }
 }
 
@@ -1453,7 +1583,7 @@ fn type_annotated_match_pattern_from_semantic_match_pattern(
         compiler__semantic_program::SemanticMatchPattern::Type { type_name, span } => {
             TypeAnnotatedMatchPattern::Type {
                 type_name: type_annotated_type_name_from_semantic_type_name(type_name),
-                span: span.clone(),
+                span: *span,
             }
         }
         compiler__semantic_program::SemanticMatchPattern::Binding {
@@ -1464,8 +1594,24 @@ fn type_annotated_match_pattern_from_semantic_match_pattern(
         } => TypeAnnotatedMatchPattern::Binding {
             name: name.clone(),
             type_name: type_annotated_type_name_from_semantic_type_name(type_name),
-            span: span.clone(),
+            span: *span,
         },
+        compiler__semantic_program::SemanticMatchPattern::Literal { value, span } => {
+            TypeAnnotatedMatchPattern::Literal {
+                value: match value {
+                    SemanticMatchLiteral::Integer(value) => {
+                        TypeAnnotatedMatchLiteral::Integer(*value)
+                    }
+                    SemanticMatchLiteral::String(value) => {
+                        TypeAnnotatedMatchLiteral::String(value.clone())
+                    }
+                    SemanticMatchLiteral::Boolean(value) => {
+                        TypeAnnotatedMatchLiteral::Boolean(*value)
+                    }
+                },
+                span: *span,
+            }
+        }
     }
 }
 
@@ -1483,6 +1629,8 @@ fn semantic_expression_id(expression: &SemanticExpression) -> SemanticExpression
         | SemanticExpression::Call { id, .. }
         | SemanticExpression::Unary { id, .. }
         | SemanticExpression::Binary { id, .. }
+        | SemanticExpression::If { id, .. }
+        | SemanticExpression::Block { id, .. }
         | SemanticExpression::Match { id, .. }
         | SemanticExpression::Matches { id, .. }
         | SemanticExpression::StringInterpolation { id, .. } => *id,
@@ -1504,16 +1652,16 @@ fn type_annotated_type_name_from_semantic_type_name(
                     .iter()
                     .map(type_annotated_type_name_from_semantic_type_name)
                     .collect(),
-                span: name_segment.span.clone(),
+                span: name_segment.span,
             })
             .collect(),
-        span: type_name.span.clone(),
+        span: type_name.span,
     }
 }
 
 fn annotate_nominal_type_references(
     resolved_declarations: &mut TypeResolvedDeclarations,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     for constant_declaration in &mut resolved_declarations.constant_declarations {
         annotate_resolved_type_argument_nominal_references(
@@ -1587,7 +1735,7 @@ fn annotate_nominal_type_references(
 
 fn annotate_statement_nominal_references(
     statement: &mut TypeAnnotatedStatement,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     match statement {
         TypeAnnotatedStatement::Binding { initializer, .. } => {
@@ -1612,7 +1760,8 @@ fn annotate_statement_nominal_references(
             }
             annotate_expression_nominal_references(value, nominal_type_reference_by_local_name);
         }
-        TypeAnnotatedStatement::Expression { value, .. }
+        TypeAnnotatedStatement::Defer { value, .. }
+        | TypeAnnotatedStatement::Expression { value, .. }
         | TypeAnnotatedStatement::Return { value, .. } => {
             annotate_expression_nominal_references(value, nominal_type_reference_by_local_name);
         }
@@ -1656,13 +1805,32 @@ fn annotate_statement_nominal_references(
                 );
             }
         }
+        TypeAnnotatedStatement::ForRange {
+            start,
+            end,
+            step,
+            body_statements,
+            ..
+        } => {
+            annotate_expression_nominal_references(start, nominal_type_reference_by_local_name);
+            annotate_expression_nominal_references(end, nominal_type_reference_by_local_name);
+            if let Some(step) = step {
+                annotate_expression_nominal_references(step, nominal_type_reference_by_local_name);
+            }
+            for statement in body_statements {
+                annotate_statement_nominal_references(
+                    statement,
+                    nominal_type_reference_by_local_name,
+                );
+            }
+        }
         TypeAnnotatedStatement::Break { .. } | TypeAnnotatedStatement::Continue { .. } => {}
     }
 }
 
 fn annotate_expression_nominal_references(
     expression: &mut TypeAnnotatedExpression,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     match expression {
         TypeAnnotatedExpression::IntegerLiteral { .. }
@@ -1685,7 +1853,10 @@ fn annotate_expression_nominal_references(
             }
         }
         TypeAnnotatedExpression::StructLiteral {
-            type_name, fields, ..
+            type_name,
+            fields,
+            resolved_type_arguments,
+            ..
         } => {
             annotate_type_name_nominal_references(type_name, nominal_type_reference_by_local_name);
             for field in fields {
@@ -1694,15 +1865,37 @@ fn annotate_expression_nominal_references(
                     nominal_type_reference_by_local_name,
                 );
             }
+            for resolved_type_argument in resolved_type_arguments {
+                annotate_resolved_type_argument_nominal_references(
+                    resolved_type_argument,
+                    nominal_type_reference_by_local_name,
+                );
+            }
         }
-        TypeAnnotatedExpression::FieldAccess { target, .. } => {
+        TypeAnnotatedExpression::FieldAccess {
+            target,
+            type_reference,
+            ..
+        } => {
             annotate_expression_nominal_references(target, nominal_type_reference_by_local_name);
+            if let Some(type_reference) = type_reference {
+                annotate_resolved_type_argument_nominal_references(
+                    type_reference,
+                    nominal_type_reference_by_local_name,
+                );
+            }
         }
         TypeAnnotatedExpression::IndexAccess { target, index, .. } => {
             annotate_expression_nominal_references(target, nominal_type_reference_by_local_name);
             annotate_expression_nominal_references(index, nominal_type_reference_by_local_name);
         }
-        TypeAnnotatedExpression::Unary { expression, .. } => {
+        TypeAnnotatedExpression::Unary { expression, .. }
+        | TypeAnnotatedExpression::EnumVariantName {
+            value: expression, ..
+        }
+        | TypeAnnotatedExpression::EnumVariantFromString {
+            value: expression, ..
+        } => {
             annotate_expression_nominal_references(
                 expression,
                 nominal_type_reference_by_local_name,
@@ -1739,6 +1932,40 @@ fn annotate_expression_nominal_references(
                 );
             }
         }
+        TypeAnnotatedExpression::If {
+            condition,
+            then_branch,
+            else_branch,
+            type_reference,
+            ..
+        } => {
+            annotate_expression_nominal_references(condition, nominal_type_reference_by_local_name);
+            annotate_expression_nominal_references(
+                then_branch,
+                nominal_type_reference_by_local_name,
+            );
+            annotate_expression_nominal_references(
+                else_branch,
+                nominal_type_reference_by_local_name,
+            );
+            if let Some(type_reference) = type_reference {
+                annotate_resolved_type_argument_nominal_references(
+                    type_reference,
+                    nominal_type_reference_by_local_name,
+                );
+            }
+        }
+        TypeAnnotatedExpression::Block {
+            statements, value, ..
+        } => {
+            for statement in statements {
+                annotate_statement_nominal_references(
+                    statement,
+                    nominal_type_reference_by_local_name,
+                );
+            }
+            annotate_expression_nominal_references(value, nominal_type_reference_by_local_name);
+        }
         TypeAnnotatedExpression::Match { target, arms, .. } => {
             annotate_expression_nominal_references(target, nominal_type_reference_by_local_name);
             for arm in arms {
@@ -1746,6 +1973,12 @@ fn annotate_expression_nominal_references(
                     &mut arm.pattern,
                     nominal_type_reference_by_local_name,
                 );
+                if let Some(guard) = &mut arm.guard {
+                    annotate_expression_nominal_references(
+                        guard,
+                        nominal_type_reference_by_local_name,
+                    );
+                }
                 annotate_expression_nominal_references(
                     &mut arm.value,
                     nominal_type_reference_by_local_name,
@@ -1773,7 +2006,7 @@ fn annotate_expression_nominal_references(
 
 fn annotate_resolved_type_argument_nominal_references(
     resolved_type_argument: &mut TypeAnnotatedResolvedTypeArgument,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     match resolved_type_argument {
         TypeAnnotatedResolvedTypeArgument::Int64
@@ -1836,19 +2069,20 @@ fn annotate_resolved_type_argument_nominal_references(
 
 fn annotate_match_pattern_nominal_references(
     pattern: &mut TypeAnnotatedMatchPattern,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     match pattern {
         TypeAnnotatedMatchPattern::Type { type_name, .. }
         | TypeAnnotatedMatchPattern::Binding { type_name, .. } => {
             annotate_type_name_nominal_references(type_name, nominal_type_reference_by_local_name);
         }
+        TypeAnnotatedMatchPattern::Literal { .. } => {}
     }
 }
 
 fn annotate_type_name_nominal_references(
     type_name: &mut TypeAnnotatedTypeName,
-    nominal_type_reference_by_local_name: &HashMap<String, TypeAnnotatedNominalTypeReference>,
+    nominal_type_reference_by_local_name: &HashMap<String, NominalTypeReference>,
 ) {
     for segment in &mut type_name.names {
         segment.nominal_type_reference = nominal_type_reference_by_local_name
@@ -1920,63 +2154,55 @@ fn analyze_package_unit(
     package_id: PackageId,
     package_path: &str,
     source_text: &str,
-    package_unit: &SemanticFile,
+    declarations: &PackageUnitDeclarations,
     imported_bindings: &[ImportedBinding],
+    host_function_contracts: &[HostFunctionContract],
+    forbidden_builtins: &[ForbiddenBuiltin],
+    language_version: LanguageVersion,
     diagnostics: &mut Vec<PhaseDiagnostic>,
     safe_autofixes: &mut Vec<SafeAutofix>,
 ) -> TypeAnalysisSummary {
-    check_package_unit_declarations(
+    check_declarations(
         package_id,
         package_path,
         source_text,
-        package_unit,
-        imported_bindings,
         diagnostics,
         safe_autofixes,
+        &declarations.types,
+        &declarations.constants,
+        &declarations.functions,
+        &declarations.static_asserts,
+        imported_bindings,
+        host_function_contracts,
+        forbidden_builtins,
+        language_version,
     )
 }
 
-fn check_package_unit_declarations(
-    package_id: PackageId,
-    package_path: &str,
-    source_text: &str,
-    package_unit: &SemanticFile,
-    imported_bindings: &[ImportedBinding],
-    diagnostics: &mut Vec<PhaseDiagnostic>,
-    safe_autofixes: &mut Vec<SafeAutofix>,
-) -> TypeAnalysisSummary {
-    let mut type_declarations = Vec::new();
-    let mut constant_declarations = Vec::new();
-    let mut function_declarations = Vec::new();
+fn group_package_unit_declarations(package_unit: &SemanticFile) -> PackageUnitDeclarations<'_> {
+    let mut declarations = PackageUnitDeclarations {
+        types: Vec::new(),
+        constants: Vec::new(),
+        functions: Vec::new(),
+        static_asserts: Vec::new(),
+    };
     for declaration in &package_unit.declarations {
         match declaration {
             SemanticDeclaration::Type(type_declaration) => {
-                type_declarations.push(type_declaration.clone());
+                declarations.types.push(type_declaration);
             }
             SemanticDeclaration::Constant(constant_declaration) => {
-                constant_declarations.push(constant_declaration.clone());
+                declarations.constants.push(constant_declaration);
             }
             SemanticDeclaration::Function(function_declaration) => {
-                function_declarations.push(function_declaration.clone());
+                declarations.functions.push(function_declaration);
+            }
+            SemanticDeclaration::StaticAssert(static_assert_declaration) => {
+                declarations.static_asserts.push(static_assert_declaration);
             }
         }
     }
-
-    let mut summary = check_declarations(
-        package_id,
-        package_path,
-        source_text,
-        diagnostics,
-        safe_autofixes,
-        &type_declarations,
-        &constant_declarations,
-        &function_declarations,
-        imported_bindings,
-    );
-    summary.type_declarations_for_annotations = type_declarations;
-    summary.constant_declarations_for_annotations = constant_declarations;
-    summary.function_declarations_for_annotations = function_declarations;
-    summary
+    declarations
 }
 
 fn check_declarations(
@@ -1985,19 +2211,36 @@ fn check_declarations(
     source_text: &str,
     diagnostics: &mut Vec<PhaseDiagnostic>,
     safe_autofixes: &mut Vec<SafeAutofix>,
-    type_declarations: &[SemanticTypeDeclaration],
-    constant_declarations: &[SemanticConstantDeclaration],
-    function_declarations: &[SemanticFunctionDeclaration],
+    type_declarations: &[&SemanticTypeDeclaration],
+    constant_declarations: &[&SemanticConstantDeclaration],
+    function_declarations: &[&SemanticFunctionDeclaration],
+    static_assert_declarations: &[&SemanticStaticAssertDeclaration],
     imported_bindings: &[ImportedBinding],
+    host_function_contracts: &[HostFunctionContract],
+    forbidden_builtins: &[ForbiddenBuiltin],
+    language_version: LanguageVersion,
 ) -> TypeAnalysisSummary {
     let mut type_checker = TypeChecker::new(
         package_id,
         package_path,
         source_text,
         imported_bindings,
+        host_function_contracts,
+        forbidden_builtins,
+        language_version,
         diagnostics,
         safe_autofixes,
     );
+    type_checker.check_attributes_are_available(
+        type_declarations,
+        constant_declarations,
+        function_declarations,
+    );
+    type_checker.collect_deprecations(
+        type_declarations,
+        constant_declarations,
+        function_declarations,
+    );
     type_checker.collect_imported_type_declarations();
     type_checker.collect_type_declarations(type_declarations);
     type_checker.collect_imported_function_signatures();
@@ -2006,11 +2249,15 @@ fn check_declarations(
     type_checker.collect_method_signatures(type_declarations);
     type_checker.check_type_interface_conformance(type_declarations);
     type_checker.check_constant_declarations(constant_declarations);
+    type_checker.check_static_assertions(static_assert_declarations, constant_declarations);
     for function in function_declarations {
         type_checker.check_function(function);
     }
     type_checker.check_methods(type_declarations);
     type_checker.check_unused_imports();
+    type_checker.autofix_function_names(function_declarations);
+    type_checker.autofix_type_names(type_declarations);
+    type_checker.collect_constant_inline_candidates(constant_declarations);
     type_checker.build_summary(
         type_declarations,
         function_declarations,
@@ -2020,15 +2267,33 @@ fn check_declarations(
 
 struct VariableInfo {
     value_type: Type,
+    declared_type: Type,
     used: bool,
     mutable: bool,
     name_span: Span,
+    reference_spans: Vec<Span>,
+    narrowed_type_by_field_path: HashMap<Vec<String>, Type>,
+    inline_initializer: Option<InlineInitializer>,
 }
 
 struct ConstantInfo {
     value_type: Type,
 }
 
+struct InlineInitializer {
+    declaration_span: Span,
+    initializer_span: Span,
+    requires_parentheses: bool,
+}
+
+// Where a deprecated type, function, or constant is declared. Imported
+// declarations are in another file, named by `declaration_path`.
+struct DeprecationInfo {
+    message: String,
+    declaration_path: Option<PathBuf>,
+    declaration_span: Span,
+}
+
 struct ImportedBindingInfo {
     symbol: ImportedSymbol,
     span: Span,
@@ -2046,6 +2311,50 @@ struct TypeInfo {
     kind: TypeKind,
 }
 
+#[derive(Default)]
+struct NominalTypeTable {
+    type_info_by_nominal_type_id: HashMap<NominalTypeId, TypeInfo>,
+    nominal_type_id_by_local_name: HashMap<String, NominalTypeId>,
+}
+
+impl NominalTypeTable {
+    fn insert(&mut self, local_name: String, type_info: TypeInfo) {
+        self.nominal_type_id_by_local_name
+            .insert(local_name, type_info.nominal_type_id.clone());
+        self.type_info_by_nominal_type_id
+            .entry(type_info.nominal_type_id.clone())
+            .or_insert(type_info);
+    }
+
+    fn contains_local_name(&self, local_name: &str) -> bool {
+        self.nominal_type_id_by_local_name.contains_key(local_name)
+    }
+
+    fn get_by_local_name(&self, local_name: &str) -> Option<&TypeInfo> {
+        let nominal_type_id = self.nominal_type_id_by_local_name.get(local_name)?;
+        self.type_info_by_nominal_type_id.get(nominal_type_id)
+    }
+
+    fn get_by_local_name_mut(&mut self, local_name: &str) -> Option<&mut TypeInfo> {
+        let nominal_type_id = self.nominal_type_id_by_local_name.get(local_name)?;
+        self.type_info_by_nominal_type_id.get_mut(nominal_type_id)
+    }
+
+    fn get_by_nominal_type_id(&self, nominal_type_id: &NominalTypeId) -> Option<&TypeInfo> {
+        self.type_info_by_nominal_type_id.get(nominal_type_id)
+    }
+
+    fn local_names_and_type_infos(&self) -> impl Iterator<Item = (&String, &TypeInfo)> {
+        self.nominal_type_id_by_local_name
+            .iter()
+            .filter_map(|(local_name, nominal_type_id)| {
+                self.type_info_by_nominal_type_id
+                    .get(nominal_type_id)
+                    .map(|type_info| (local_name, type_info))
+            })
+    }
+}
+
 #[derive(Clone)]
 enum TypeKind {
     Struct {
@@ -2069,6 +2378,7 @@ struct ImplementedInterfaceEntry {
 struct InterfaceMethodSignature {
     name: String,
     self_mutable: bool,
+    parameter_names: Vec<String>,
     parameter_types: Vec<Type>,
     return_type: Type,
 }
@@ -2098,41 +2408,96 @@ struct TypeChecker<'a> {
     package_path: String,
     source_text: &'a str,
     constants: HashMap<String, ConstantInfo>,
-    types: HashMap<String, TypeInfo>,
+    types: NominalTypeTable,
     functions: HashMap<String, FunctionInfo>,
+    // Builtins form the outermost scope: a binding, a package function, or an
+    // imported function with the same name shadows the builtin.
+    builtin_functions: HashMap<String, FunctionInfo>,
+    forbidden_builtins: Vec<ForbiddenBuiltin>,
+    language_version: LanguageVersion,
+    // Uses of features the package's language version lacks that were already
+    // reported, so that a use nested inside one of the same feature is not
+    // reported again.
+    unavailable_feature_uses: Vec<(LanguageFeature, Span)>,
+    reference_spans_by_function_name: HashMap<String, Vec<Span>>,
+    reference_spans_by_type_name: HashMap<String, Vec<Span>>,
+    reference_spans_by_constant_name: HashMap<String, Vec<Span>>,
+    inline_initializer_by_constant_name: HashMap<String, InlineInitializer>,
+    inline_candidates: Vec<TypeAnnotatedInlineCandidate>,
     imported_functions: HashMap<String, FunctionInfo>,
     imported_bindings: HashMap<String, ImportedBindingInfo>,
+    deprecation_by_local_name: HashMap<String, DeprecationInfo>,
+    deprecated_declaration_spans: Vec<Span>,
+    reported_deprecated_use_starts: HashSet<usize>,
     methods: HashMap<MethodKey, MethodInfo>,
     scopes: Vec<HashMap<String, VariableInfo>>,
     type_parameter_scopes: Vec<HashMap<String, Span>>,
     diagnostics: &'a mut Vec<PhaseDiagnostic>,
     safe_autofixes: &'a mut Vec<SafeAutofix>,
     current_return_type: Type,
+    current_return_type_span: Option<Span>,
+    // The type the context of the next checked expression expects; see
+    // `check_expression_with_expected_type`.
+    expected_expression_type: Option<Type>,
     loop_depth: usize,
-    resolved_type_by_expression_id: BTreeMap<SemanticExpressionId, Type>,
-    call_target_by_expression_id: BTreeMap<SemanticExpressionId, TypeAnnotatedCallTarget>,
-    constant_reference_by_expression_id:
-        BTreeMap<SemanticExpressionId, TypeAnnotatedConstantReference>,
-    resolved_type_argument_types_by_expression_id:
-        BTreeMap<SemanticExpressionId, Vec<TypeAnnotatedResolvedTypeArgument>>,
-    struct_reference_by_expression_id: BTreeMap<SemanticExpressionId, TypeAnnotatedStructReference>,
-    enum_variant_reference_by_expression_id:
-        BTreeMap<SemanticExpressionId, TypeAnnotatedEnumVariantReference>,
+    block_expression_depth: usize,
+    block_depth: usize,
+    deferred_local_names: HashSet<String>,
+    expression_annotations: ExpressionAnnotations,
+    matches_pattern_type_by_expression_id: HashMap<SemanticExpressionId, Type>,
+}
+
+#[derive(Clone)]
+struct NarrowingTarget {
+    variable_name: String,
+    field_path: Vec<String>,
 }
 
 struct BranchNarrowing {
-    name: String,
+    target: NarrowingTarget,
     when_true: Type,
     when_false: Type,
 }
 
+#[derive(Clone, Copy)]
+enum NarrowingBranch {
+    WhenTrue,
+    WhenFalse,
+}
+
+struct VariableTypeRestore {
+    scope_index: usize,
+    target: NarrowingTarget,
+    original_type: Option<Type>,
+    narrowed_type: Type,
+}
+
 struct StatementOutcome {
     terminates: bool,
-    fallthrough_narrowing: Option<FallthroughNarrowing>,
+    fallthrough_narrowings: Vec<FallthroughNarrowing>,
+    fallthrough_branch: Option<FallthroughBranch>,
+}
+
+struct BlockOutcome {
+    terminates: bool,
+    fallthrough_branch: Option<FallthroughBranch>,
+}
+
+struct FallthroughBranch {
+    kind: FallthroughBranchKind,
+    span: Span,
+}
+
+#[derive(Clone, Copy)]
+enum FallthroughBranchKind {
+    IfWithoutElse,
+    IfBranch,
+    ElseBranch,
+    LoopExit,
 }
 
 struct FallthroughNarrowing {
-    variable_name: String,
+    target: NarrowingTarget,
     narrowed_type: Type,
 }
 
@@ -2150,16 +2515,30 @@ impl<'a> TypeChecker<'a> {
         package_path: &str,
         source_text: &'a str,
         imported_bindings: &[ImportedBinding],
+        host_function_contracts: &[HostFunctionContract],
+        forbidden_builtins: &[ForbiddenBuiltin],
+        language_version: LanguageVersion,
         diagnostics: &'a mut Vec<PhaseDiagnostic>,
         safe_autofixes: &'a mut Vec<SafeAutofix>,
     ) -> Self {
         let mut imported_binding_map = HashMap::new();
+        let mut deprecation_by_local_name = HashMap::new();
         for imported in imported_bindings {
+            if let Some(deprecation) = &imported.deprecation {
+                deprecation_by_local_name.insert(
+                    imported.local_name.clone(),
+                    DeprecationInfo {
+                        message: deprecation.message.clone(),
+                        declaration_path: Some(deprecation.declaration_path.clone()),
+                        declaration_span: deprecation.declaration_span,
+                    },
+                );
+            }
             imported_binding_map.insert(
                 imported.local_name.clone(),
                 ImportedBindingInfo {
                     symbol: imported.symbol.clone(),
-                    span: imported.span.clone(),
+                    span: imported.span,
                     imported_package_path: imported.imported_package_path.clone(),
                     imported_symbol_name: imported.imported_symbol_name.clone(),
                     used: false,
@@ -2171,68 +2550,71 @@ impl<'a> TypeChecker<'a> {
             package_path: package_path.to_string(),
             source_text,
             constants: HashMap::new(),
-            types: HashMap::new(),
-            functions: builtin_functions(),
+            types: NominalTypeTable::default(),
+            functions: HashMap::new(),
+            builtin_functions: builtin_functions(host_function_contracts),
+            forbidden_builtins: forbidden_builtins.to_vec(),
+            language_version,
+            unavailable_feature_uses: Vec::new(),
+            reference_spans_by_function_name: HashMap::new(),
+            reference_spans_by_type_name: HashMap::new(),
+            reference_spans_by_constant_name: HashMap::new(),
+            inline_initializer_by_constant_name: HashMap::new(),
+            inline_candidates: Vec::new(),
             imported_functions: HashMap::new(),
             imported_bindings: imported_binding_map,
+            deprecation_by_local_name,
+            deprecated_declaration_spans: Vec::new(),
+            reported_deprecated_use_starts: HashSet::new(),
             methods: HashMap::new(),
             scopes: Vec::new(),
             type_parameter_scopes: Vec::new(),
             diagnostics,
             safe_autofixes,
             current_return_type: Type::Unknown,
+            current_return_type_span: None,
+            expected_expression_type: None,
             loop_depth: 0,
-            resolved_type_by_expression_id: BTreeMap::new(),
-            call_target_by_expression_id: BTreeMap::new(),
-            constant_reference_by_expression_id: BTreeMap::new(),
-            resolved_type_argument_types_by_expression_id: BTreeMap::new(),
-            struct_reference_by_expression_id: BTreeMap::new(),
-            enum_variant_reference_by_expression_id: BTreeMap::new(),
+            block_expression_depth: 0,
+            block_depth: 0,
+            deferred_local_names: HashSet::new(),
+            expression_annotations: ExpressionAnnotations::default(),
+            matches_pattern_type_by_expression_id: HashMap::new(),
         }
     }
 
     fn build_summary(
         self,
-        type_declarations: &[SemanticTypeDeclaration],
-        _function_declarations: &[SemanticFunctionDeclaration],
-        _constant_declarations: &[SemanticConstantDeclaration],
+        type_declarations: &[&SemanticTypeDeclaration],
+        _function_declarations: &[&SemanticFunctionDeclaration],
+        _constant_declarations: &[&SemanticConstantDeclaration],
     ) -> TypeAnalysisSummary {
         let nominal_type_reference_by_local_name = self.nominal_type_reference_by_local_name();
         let implemented_interface_references_by_struct_name =
             self.implemented_interface_references_by_struct_name(type_declarations);
 
         TypeAnalysisSummary {
-            resolved_type_by_expression_id: self.resolved_type_by_expression_id,
-            call_target_by_expression_id: self.call_target_by_expression_id,
-            constant_reference_by_expression_id: self.constant_reference_by_expression_id,
-            resolved_type_argument_types_by_expression_id: self
-                .resolved_type_argument_types_by_expression_id,
-            struct_reference_by_expression_id: self.struct_reference_by_expression_id,
-            enum_variant_reference_by_expression_id: self.enum_variant_reference_by_expression_id,
+            expression_annotations: self.expression_annotations,
             nominal_type_reference_by_local_name,
             implemented_interface_references_by_struct_name,
-            type_declarations_for_annotations: Vec::new(),
-            constant_declarations_for_annotations: Vec::new(),
-            function_declarations_for_annotations: Vec::new(),
             resolved_declarations: ResolvedDeclarations {
                 constants_by_name: self.constants,
                 functions_by_name: self.functions,
-                types_by_name: self.types,
+                types: self.types,
                 methods_by_key: self.methods,
             },
+            inline_candidates: self.inline_candidates,
         }
     }
 
-    fn nominal_type_reference_by_local_name(
-        &self,
-    ) -> HashMap<String, TypeAnnotatedNominalTypeReference> {
+    fn nominal_type_reference_by_local_name(&self) -> HashMap<String, NominalTypeReference> {
         let mut nominal_type_reference_by_local_name = HashMap::new();
-        for (local_name, type_info) in &self.types {
+        for (local_name, type_info) in self.types.local_names_and_type_infos() {
             nominal_type_reference_by_local_name.insert(
                 local_name.clone(),
-                TypeAnnotatedNominalTypeReference {
-                    package_path: type_info.package_path.clone(),
-                    symbol_name: type_info.nominal_type_id.symbol_name.clone(),
+                NominalTypeReference {
+                    package_path: PackagePath::new(&type_info.package_path),
+                    symbol_name: SymbolName::new(&type_info.nominal_type_id.symbol_name),
                 },
             );
         }
@@ -2241,8 +2623,8 @@ impl<'a> TypeChecker<'a> {
 
     fn implemented_interface_references_by_struct_name(
         &self,
-        type_declarations: &[SemanticTypeDeclaration],
-    ) -> HashMap<String, Vec<TypeAnnotatedInterfaceReference>> {
+        type_declarations: &[&SemanticTypeDeclaration],
+    ) -> HashMap<String, Vec<InterfaceReference>> {
         let mut implemented_interface_references_by_struct_name = HashMap::new();
         for type_declaration in type_declarations {
             let compiler__semantic_program::SemanticTypeDeclarationKind::Struct { .. } =
@@ -2250,7 +2632,7 @@ impl<'a> TypeChecker<'a> {
             else {
                 continue;
             };
-            let Some(type_info) = self.types.get(&type_declaration.name) else {
+            let Some(type_info) = self.types.get_by_local_name(&type_declaration.name) else {
                 continue;
             };
             let interface_references = type_info
@@ -2269,15 +2651,15 @@ impl<'a> TypeChecker<'a> {
     fn type_annotated_interface_reference_from_type(
         &self,
         value_type: &Type,
-    ) -> Option<TypeAnnotatedInterfaceReference> {
+    ) -> Option<InterfaceReference> {
         let nominal_type_id = Self::nominal_type_id_for_type(value_type)?;
         let type_info = self.type_info_by_nominal_type_id(&nominal_type_id)?;
         if !matches!(type_info.kind, TypeKind::Interface { .. }) {
             return None;
         }
-        Some(TypeAnnotatedInterfaceReference {
-            package_path: type_info.package_path.clone(),
-            symbol_name: nominal_type_id.symbol_name.clone(),
+        Some(InterfaceReference {
+            package_path: PackagePath::new(&type_info.package_path),
+            symbol_name: SymbolName::new(&nominal_type_id.symbol_name),
         })
     }
 
@@ -2290,9 +2672,7 @@ impl<'a> TypeChecker<'a> {
     }
 
     fn type_info_by_nominal_type_id(&self, nominal_type_id: &NominalTypeId) -> Option<&TypeInfo> {
-        self.types
-            .values()
-            .find(|info| info.nominal_type_id == *nominal_type_id)
+        self.types.get_by_nominal_type_id(nominal_type_id)
     }
 
     fn nominal_type_id_for_type(value_type: &Type) -> Option<NominalTypeId> {
@@ -2322,16 +2702,55 @@ impl<'a> TypeChecker<'a> {
             .last()
             .is_some_and(|scope| scope.contains_key(&name));
         if duplicate {
-            self.error(format!("duplicate binding '{name}'"), span.clone());
+            self.error(format!("duplicate binding '{name}'"), *span);
+        } else if value_type != Type::Unknown {
+            let shadowed_type = self
+                .scopes
+                .iter()
+                .rev()
+                .skip(1)
+                .find_map(|scope| scope.get(&name))
+                .map(|info| info.declared_type.clone());
+            if let Some(shadowed_type) = shadowed_type
+                && shadowed_type != Type::Unknown
+                && shadowed_type != value_type
+            {
+                self.error(
+                    format!(
+                        "binding '{name}' shadows an outer binding of type {} with type {}",
+                        shadowed_type.display(),
+                        value_type.display()
+                    ),
+                    name_span,
+                );
+            }
+        }
+        if !duplicate
+            && self.deferred_local_names.contains(&name)
+            && self
+                .scopes
+                .iter()
+                .rev()
+                .skip(1)
+                .any(|scope| scope.contains_key(&name))
+        {
+            self.error(
+                format!("binding '{name}' shadows a name used by a deferred expression"),
+                name_span,
+            );
         }
         if let Some(scope) = self.scopes.last_mut() {
             scope.insert(
                 name,
                 VariableInfo {
+                    declared_type: value_type.clone(),
                     value_type,
                     used: false,
                     mutable,
                     name_span,
+                    reference_spans: Vec::new(),
+                    narrowed_type_by_field_path: HashMap::new(),
+                    inline_initializer: None,
                 },
             );
         }
@@ -2345,6 +2764,94 @@ impl<'a> TypeChecker<'a> {
         kind == SemanticNameReferenceKind::Builtin
             || self.functions.contains_key(name)
             || self.imported_functions.contains_key(name)
+            || (self.builtin_functions.contains_key(name)
+                && !self.name_reference_resolves_to_value_binding(name))
+    }
+
+    // Features newer than the package's language version still parse, so that
+    // they are reported by name rather than as syntax errors.
+    fn check_language_feature_is_available(&mut self, feature: LanguageFeature, span: Span) {
+        let required_version = feature.required_version();
+        if self.language_version >= required_version
+            || self
+                .unavailable_feature_uses
+                .iter()
+                .any(|(reported_feature, reported_span)| {
+                    *reported_feature == feature
+                        && reported_span.start <= span.start
+                        && span.end <= reported_span.end
+                })
+        {
+            return;
+        }
+        self.unavailable_feature_uses.push((feature, span));
+        let message = format!(
+            "{} require language version {}, but this package declares language version {}",
+            feature.name(),
+            required_version.name(),
+            self.language_version.name()
+        );
+        self.error(message, span);
+    }
+
+    fn check_builtin_function_is_available(&mut self, name: &str, span: Span) {
+        if let Some(feature) = builtin_function_language_feature(name) {
+            self.check_language_feature_is_available(feature, span);
+        }
+    }
+
+    fn check_attributes_are_available(
+        &mut self,
+        type_declarations: &[&SemanticTypeDeclaration],
+        constant_declarations: &[&SemanticConstantDeclaration],
+        function_declarations: &[&SemanticFunctionDeclaration],
+    ) {
+        let attributes = type_declarations
+            .iter()
+            .flat_map(|declaration| &declaration.attributes)
+            .chain(
+                constant_declarations
+                    .iter()
+                    .flat_map(|declaration| &declaration.attributes),
+            )
+            .chain(
+                function_declarations
+                    .iter()
+                    .flat_map(|declaration| &declaration.attributes),
+            );
+        for attribute in attributes {
+            self.check_language_feature_is_available(LanguageFeature::Attributes, attribute.span);
+        }
+    }
+
+    // Host functions are called like builtins, so a workspace can forbid them
+    // the same way.
+    fn check_builtin_call_is_allowed(&mut self, name: &str, span: Span) {
+        if self.name_shadows_builtin(name) {
+            return;
+        }
+        let is_builtin = name == "string"
+            || name == "variant_name"
+            || name == "format"
+            || self.builtin_functions.contains_key(name);
+        let Some(forbidden_builtin) = self
+            .forbidden_builtins
+            .iter()
+            .find(|forbidden_builtin| is_builtin && forbidden_builtin.name == name)
+        else {
+            return;
+        };
+        let message = format!(
+            "builtin '{name}' is forbidden in this package by {}",
+            forbidden_builtin.policy_source
+        );
+        self.error(message, span);
+    }
+
+    fn name_shadows_builtin(&self, name: &str) -> bool {
+        self.name_reference_resolves_to_value_binding(name)
+            || self.functions.contains_key(name)
+            || self.imported_functions.contains_key(name)
     }
 
     fn name_reference_resolves_to_value_binding(&self, name: &str) -> bool {
@@ -2362,24 +2869,32 @@ impl<'a> TypeChecker<'a> {
     ) -> Type {
         if self.name_reference_expression_is_callable(name, kind) {
             let function_info = if kind == SemanticNameReferenceKind::Builtin {
-                self.functions.get(name).cloned()
+                self.builtin_functions.get(name).cloned()
             } else if let Some(imported_function_info) = self.imported_functions.get(name).cloned()
             {
                 self.mark_import_used(name);
+                self.report_deprecated_use(name, *span);
                 Some(imported_function_info)
+            } else if let Some(function_info) = self.functions.get(name).cloned() {
+                self.record_function_reference(name, span);
+                self.report_deprecated_use(name, *span);
+                Some(function_info)
             } else {
-                self.functions.get(name).cloned()
+                self.check_builtin_function_is_available(name, *span);
+                self.builtin_functions.get(name).cloned()
             };
             if let Some(function_info) = function_info {
                 if !function_info.type_parameters.is_empty() {
                     self.error(
-                        format!("generic function '{name}' cannot be used as a value"),
-                        span.clone(),
+                        format!(
+                            "generic function '{name}' cannot be used as a value; call it directly instead"
+                        ),
+                        *span,
                     );
                     return Type::Unknown;
                 }
-                self.call_target_by_expression_id
-                    .insert(expression_id, function_info.call_target.clone());
+                self.expression_annotations
+                    .record_call_target(expression_id, function_info.call_target.clone());
                 return Type::Function {
                     parameter_types: function_info.parameter_types,
                     return_type: Box::new(function_info.return_type),
@@ -2398,18 +2913,25 @@ impl<'a> TypeChecker<'a> {
         for scope in self.scopes.iter_mut().rev() {
             if let Some(info) = scope.get_mut(name) {
                 info.used = true;
+                info.reference_spans.push(*span);
                 return info.value_type.clone();
             }
         }
         if let Some(info) = self.constants.get(name) {
-            self.constant_reference_by_expression_id.insert(
+            let value_type = info.value_type.clone();
+            self.reference_spans_by_constant_name
+                .entry(name.to_string())
+                .or_default()
+                .push(*span);
+            self.report_deprecated_use(name, *span);
+            self.expression_annotations.record_constant_reference(
                 expression_id,
-                TypeAnnotatedConstantReference {
-                    package_path: self.package_path.clone(),
-                    symbol_name: name.to_string(),
+                ConstantReference {
+                    package_path: PackagePath::new(&self.package_path),
+                    symbol_name: SymbolName::new(name),
                 },
             );
-            return info.value_type.clone();
+            return value_type;
         }
         if let Some(imported_binding) = self.imported_bindings.get(name) {
             let ImportedSymbol::Constant(value_type) = &imported_binding.symbol else {
@@ -2417,43 +2939,175 @@ impl<'a> TypeChecker<'a> {
                 if self.imported_bindings.contains_key(name) {
                     self.mark_import_used(name);
                 }
-                self.error(format!("unknown name '{name}'"), span.clone());
+                self.report_name_without_value(name, *span);
                 return Type::Unknown;
             };
             let imported_package_path = imported_binding.imported_package_path.clone();
             let imported_symbol_name = imported_binding.imported_symbol_name.clone();
             let value_type = value_type.clone();
-            self.constant_reference_by_expression_id.insert(
+            self.expression_annotations.record_constant_reference(
                 expression_id,
-                TypeAnnotatedConstantReference {
-                    package_path: imported_package_path,
-                    symbol_name: imported_symbol_name,
+                ConstantReference {
+                    package_path: PackagePath::new(&imported_package_path),
+                    symbol_name: SymbolName::new(&imported_symbol_name),
                 },
             );
             self.mark_import_used(name);
+            self.report_deprecated_use(name, *span);
             return value_type;
         }
         if self.imported_bindings.contains_key(name) {
             self.mark_import_used(name);
         }
-        self.error(format!("unknown name '{name}'"), span.clone());
+        self.report_name_without_value(name, *span);
         Type::Unknown
     }
 
+    // A type name in value position is usually a construction with its field
+    // list forgotten, so it is reported as such rather than as an unknown name.
+    fn report_name_without_value(&mut self, name: &str, span: Span) {
+        let message = match self.types.get_by_local_name(name).map(|info| &info.kind) {
+            Some(TypeKind::Struct { .. }) => format!(
+                "'{name}' is a type, not a value; did you mean to construct it with '{name} {{ ... }}'?"
+            ),
+            Some(TypeKind::Interface { .. } | TypeKind::Union { .. }) => {
+                format!("'{name}' is a type, not a value")
+            }
+            None => format!("unknown name '{name}'"),
+        };
+        self.error(message, span);
+    }
+
+    fn record_variable_assignment_reference(&mut self, name: &str, name_span: &Span) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(info) = scope.get_mut(name) {
+                info.reference_spans.push(*name_span);
+                return;
+            }
+        }
+    }
+
+    fn record_function_reference(&mut self, name: &str, span: &Span) {
+        if self.functions.contains_key(name) {
+            self.reference_spans_by_function_name
+                .entry(name.to_string())
+                .or_default()
+                .push(*span);
+        }
+    }
+
+    // `span` may cover type arguments or a variant after the name; only the
+    // name itself is recorded.
+    fn record_type_reference(&mut self, name: &str, span: Span) {
+        self.reference_spans_by_type_name
+            .entry(name.to_string())
+            .or_default()
+            .push(Span::new(span.start, span.start + name.len()));
+    }
+
     fn lookup_variable_for_assignment(&mut self, name: &str) -> Option<(bool, Type)> {
         for scope in self.scopes.iter_mut().rev() {
             if let Some(info) = scope.get_mut(name) {
                 info.used = true;
-                return Some((info.mutable, info.value_type.clone()));
+                return Some((info.mutable, info.declared_type.clone()));
             }
         }
         None
     }
 
+    fn collect_deprecations(
+        &mut self,
+        type_declarations: &[&SemanticTypeDeclaration],
+        constant_declarations: &[&SemanticConstantDeclaration],
+        function_declarations: &[&SemanticFunctionDeclaration],
+    ) {
+        let declarations = type_declarations
+            .iter()
+            .map(|declaration| {
+                (
+                    &declaration.name,
+                    declaration.name_span,
+                    declaration.span,
+                    deprecation_message(&declaration.attributes),
+                )
+            })
+            .chain(constant_declarations.iter().map(|declaration| {
+                (
+                    &declaration.name,
+                    declaration.name_span,
+                    declaration.span,
+                    deprecation_message(&declaration.attributes),
+                )
+            }))
+            .chain(function_declarations.iter().map(|declaration| {
+                (
+                    &declaration.name,
+                    declaration.name_span,
+                    declaration.span,
+                    deprecation_message(&declaration.attributes),
+                )
+            }));
+        for (name, name_span, span, deprecation) in declarations {
+            if let Some(message) = deprecation {
+                self.deprecation_by_local_name.insert(
+                    name.clone(),
+                    DeprecationInfo {
+                        message: message.to_string(),
+                        declaration_path: None,
+                        declaration_span: name_span,
+                    },
+                );
+                self.deprecated_declaration_spans.push(span);
+            }
+        }
+    }
+
+    // Called wherever a type, function, or constant name resolves to its
+    // declaration. A name resolved twice at the same place, as a type in a
+    // signature is, warns once, and uses inside a deprecated declaration do
+    // not warn.
+    fn report_deprecated_use(&mut self, name: &str, span: Span) {
+        let Some(deprecation) = self.deprecation_by_local_name.get(name) else {
+            return;
+        };
+        if self
+            .deprecated_declaration_spans
+            .iter()
+            .any(|declaration_span| {
+                declaration_span.start <= span.start && span.end <= declaration_span.end
+            })
+            || !self.reported_deprecated_use_starts.insert(span.start)
+        {
+            return;
+        }
+        let diagnostic = PhaseDiagnostic::warning(
+            format!("'{name}' is deprecated: {}", deprecation.message),
+            span,
+        );
+        self.diagnostics.push(match &deprecation.declaration_path {
+            Some(declaration_path) => diagnostic.with_related_span_in_file(
+                "declared here",
+                declaration_path.clone(),
+                deprecation.declaration_span,
+            ),
+            None => diagnostic.with_related_span("declared here", deprecation.declaration_span),
+        });
+    }
+
     fn error(&mut self, message: impl Into<String>, span: Span) {
         self.diagnostics.push(PhaseDiagnostic::new(message, span));
     }
 
+    fn error_with_suggested_fix(
+        &mut self,
+        message: impl Into<String>,
+        span: Span,
+        suggested_fix: SuggestedFix,
+    ) {
+        self.diagnostics
+            .push(PhaseDiagnostic::new(message, span).with_suggested_fix(suggested_fix));
+    }
+
     fn push_safe_autofix(&mut self, safe_autofix: SafeAutofix) {
         self.safe_autofixes.push(safe_autofix);
     }
@@ -2494,10 +3148,10 @@ impl<'a> TypeChecker<'a> {
         for (name, span) in names_and_spans {
             self.check_type_name(name, span);
             if scope.contains_key(name) {
-                self.error(format!("duplicate type parameter '{name}'"), span.clone());
+                self.error(format!("duplicate type parameter '{name}'"), *span);
                 continue;
             }
-            scope.insert(name.clone(), span.clone());
+            scope.insert(name.clone(), *span);
         }
         self.type_parameter_scopes.push(scope);
     }
@@ -2536,7 +3190,7 @@ impl<'a> TypeChecker<'a> {
                     .iter()
                     .map(|inner_type| Self::instantiate_type(inner_type, substitutions))
                     .collect();
-                Self::normalize_union(instantiated)
+                normalize_union(instantiated)
             }
             Type::Applied { base, arguments } => Type::Applied {
                 base: base.clone(),
@@ -2572,7 +3226,7 @@ impl<'a> TypeChecker<'a> {
                         type_parameter.name,
                         context_name
                     ),
-                    span.clone(),
+                    *span,
                 );
             }
         }
@@ -2585,10 +3239,7 @@ impl<'a> TypeChecker<'a> {
             let name = segment.name.as_str();
             if name == "function" {
                 if segment.type_arguments.is_empty() {
-                    self.error(
-                        "function type must include a return type",
-                        segment.span.clone(),
-                    );
+                    self.error("function type must include a return type", segment.span);
                     has_unknown = true;
                     continue;
                 }
@@ -2615,7 +3266,7 @@ impl<'a> TypeChecker<'a> {
                 if !segment.type_arguments.is_empty() {
                     self.error(
                         format!("type parameter '{name}' does not take type arguments"),
-                        segment.span.clone(),
+                        segment.span,
                     );
                     has_unknown = true;
                     continue;
@@ -2630,7 +3281,7 @@ impl<'a> TypeChecker<'a> {
                             "built-in type 'List' expects 1 type argument, got {}",
                             segment.type_arguments.len()
                         ),
-                        segment.span.clone(),
+                        segment.span,
                     );
                     has_unknown = true;
                     continue;
@@ -2647,7 +3298,7 @@ impl<'a> TypeChecker<'a> {
                 if !segment.type_arguments.is_empty() {
                     self.error(
                         format!("built-in type '{name}' does not take type arguments"),
-                        segment.span.clone(),
+                        segment.span,
                     );
                     has_unknown = true;
                     continue;
@@ -2655,7 +3306,7 @@ impl<'a> TypeChecker<'a> {
                 resolved.push(builtin);
                 continue;
             }
-            if let Some(info) = self.types.get(name) {
+            if let Some(info) = self.types.get_by_local_name(name) {
                 let nominal_type_id = info.nominal_type_id.clone();
                 let declared_type_parameters = info.type_parameters.clone();
                 let union_variants = match &info.kind {
@@ -2663,15 +3314,9 @@ impl<'a> TypeChecker<'a> {
                     TypeKind::Struct { .. } | TypeKind::Interface { .. } => None,
                 };
                 let type_parameter_count = declared_type_parameters.len();
-                if matches!(
-                    self.imported_bindings.get(name),
-                    Some(ImportedBindingInfo {
-                        symbol: ImportedSymbol::Type(_),
-                        ..
-                    })
-                ) {
-                    self.mark_import_used(name);
-                }
+                self.mark_type_import_used(name);
+                self.record_type_reference(name, segment.span);
+                self.report_deprecated_use(name, segment.span);
                 let resolved_type_arguments = segment
                     .type_arguments
                     .iter()
@@ -2681,7 +3326,7 @@ impl<'a> TypeChecker<'a> {
                     if type_parameter_count == 0 {
                         self.error(
                             format!("type '{name}' does not take type arguments"),
-                            segment.span.clone(),
+                            segment.span,
                         );
                     } else {
                         self.error(
@@ -2689,7 +3334,7 @@ impl<'a> TypeChecker<'a> {
                                 "type '{name}' expects {type_parameter_count} type arguments, got {}",
                                 segment.type_arguments.len()
                             ),
-                            segment.span.clone(),
+                            segment.span,
                         );
                     }
                     has_unknown = true;
@@ -2717,7 +3362,7 @@ impl<'a> TypeChecker<'a> {
                     continue;
                 };
                 if type_parameter_count == 0 {
-                    resolved.push(Self::normalize_union(variants));
+                    resolved.push(normalize_union(variants));
                 } else {
                     let substitutions: HashMap<String, Type> = declared_type_parameters
                         .iter()
@@ -2728,17 +3373,19 @@ impl<'a> TypeChecker<'a> {
                         .iter()
                         .map(|variant| Self::instantiate_type(variant, &substitutions))
                         .collect();
-                    resolved.push(Self::normalize_union(instantiated_variants));
+                    resolved.push(normalize_union(instantiated_variants));
                 }
                 continue;
             }
             if let Some((enum_name, variant_name)) = name.split_once('.')
                 && let Some(variant_type) = self.resolve_enum_variant_type(enum_name, variant_name)
             {
+                self.record_type_reference(enum_name, segment.span);
+                self.report_deprecated_use(enum_name, segment.span);
                 if !segment.type_arguments.is_empty() {
                     self.error(
                         format!("enum variant '{name}' does not take type arguments"),
-                        segment.span.clone(),
+                        segment.span,
                     );
                     has_unknown = true;
                     continue;
@@ -2746,7 +3393,7 @@ impl<'a> TypeChecker<'a> {
                 resolved.push(variant_type);
                 continue;
             }
-            self.error(format!("unknown type '{name}'"), segment.span.clone());
+            self.error(format!("unknown type '{name}'"), segment.span);
             has_unknown = true;
         }
 
@@ -2757,7 +3404,7 @@ impl<'a> TypeChecker<'a> {
         if resolved.len() == 1 {
             return resolved.remove(0);
         }
-        Self::normalize_union(resolved)
+        normalize_union(resolved)
     }
 
     pub(crate) fn resolve_enum_variant_type(
@@ -2765,7 +3412,7 @@ impl<'a> TypeChecker<'a> {
         enum_name: &str,
         variant_name: &str,
     ) -> Option<Type> {
-        let info = self.types.get(enum_name)?;
+        let info = self.types.get_by_local_name(enum_name)?;
         let TypeKind::Union { variants } = &info.kind else {
             return None;
         };
@@ -2774,23 +3421,27 @@ impl<'a> TypeChecker<'a> {
             .iter()
             .find(|variant| variant.display() == variant_display)
             .cloned();
+        self.mark_type_import_used(enum_name);
+        resolved_variant
+    }
+
+    pub(crate) fn mark_type_import_used(&mut self, type_name: &str) {
         if matches!(
-            self.imported_bindings.get(enum_name),
+            self.imported_bindings.get(type_name),
             Some(ImportedBindingInfo {
                 symbol: ImportedSymbol::Type(_),
                 ..
             })
         ) {
-            self.mark_import_used(enum_name);
+            self.mark_import_used(type_name);
         }
-        resolved_variant
     }
 
     fn check_unused_imports(&mut self) {
         let mut unused = Vec::new();
         for (name, binding) in &self.imported_bindings {
             if !binding.used {
-                unused.push((name.clone(), binding.span.clone()));
+                unused.push((name.clone(), binding.span));
             }
         }
         for (name, span) in unused {
@@ -2799,7 +3450,26 @@ impl<'a> TypeChecker<'a> {
     }
 }
 
-fn builtin_functions() -> HashMap<String, FunctionInfo> {
+// Builtins that language version 1 lacks. Their names are not reserved, since
+// bindings and declarations shadow builtins, so only their uses are gated.
+fn builtin_function_language_feature(function_name: &str) -> Option<LanguageFeature> {
+    match function_name {
+        "abs" | "min" | "max" | "pow" => Some(LanguageFeature::MathBuiltins),
+        "checked_add" | "checked_sub" | "checked_mul" | "saturating_add" | "saturating_sub"
+        | "saturating_mul" => Some(LanguageFeature::OverflowArithmeticBuiltins),
+        "now" | "random_int" => Some(LanguageFeature::ClockAndRandomBuiltins),
+        "assert_equals" | "debug_string" => Some(LanguageFeature::ValueRenderingBuiltins),
+        "unreachable" => Some(LanguageFeature::UnreachableBuiltin),
+        _ => None,
+    }
+}
+
+// Host function contracts describe functions an embedding host provides. They
+// are called like builtins but cannot change a builtin's signature, so a
+// contract named after a builtin is ignored.
+fn builtin_functions(
+    host_function_contracts: &[HostFunctionContract],
+) -> HashMap<String, FunctionInfo> {
     let mut functions = HashMap::new();
     functions.insert(
         "abort".to_string(),
@@ -2823,6 +3493,48 @@ fn builtin_functions() -> HashMap<String, FunctionInfo> {
             },
         },
     );
+    functions.insert(
+        "assert_equals".to_string(),
+        FunctionInfo {
+            type_parameters: vec![GenericTypeParameter {
+                name: "T".to_string(),
+                constraint: None,
+            }],
+            parameter_types: vec![
+                Type::TypeParameter("T".to_string()),
+                Type::TypeParameter("T".to_string()),
+            ],
+            return_type: Type::Nil,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "assert_equals".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "debug_string".to_string(),
+        FunctionInfo {
+            type_parameters: vec![GenericTypeParameter {
+                name: "T".to_string(),
+                constraint: None,
+            }],
+            parameter_types: vec![Type::TypeParameter("T".to_string())],
+            return_type: Type::String,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "debug_string".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "unreachable".to_string(),
+        FunctionInfo {
+            type_parameters: Vec::new(),
+            parameter_types: Vec::new(),
+            return_type: Type::Never,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "unreachable".to_string(),
+            },
+        },
+    );
     functions.insert(
         "print".to_string(),
         FunctionInfo {
@@ -2834,6 +3546,122 @@ fn builtin_functions() -> HashMap<String, FunctionInfo> {
             },
         },
     );
+    functions.insert(
+        "now".to_string(),
+        FunctionInfo {
+            type_parameters: Vec::new(),
+            parameter_types: Vec::new(),
+            return_type: Type::Integer64,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "now".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "random_int".to_string(),
+        FunctionInfo {
+            type_parameters: Vec::new(),
+            parameter_types: vec![Type::Integer64, Type::Integer64],
+            return_type: Type::Integer64,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "random_int".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "abs".to_string(),
+        FunctionInfo {
+            type_parameters: Vec::new(),
+            parameter_types: vec![Type::Integer64],
+            return_type: Type::Integer64,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "abs".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "min".to_string(),
+        FunctionInfo {
+            type_parameters: vec![GenericTypeParameter {
+                name: "T".to_string(),
+                constraint: None,
+            }],
+            parameter_types: vec![
+                Type::TypeParameter("T".to_string()),
+                Type::TypeParameter("T".to_string()),
+            ],
+            return_type: Type::TypeParameter("T".to_string()),
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "min".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "max".to_string(),
+        FunctionInfo {
+            type_parameters: vec![GenericTypeParameter {
+                name: "T".to_string(),
+                constraint: None,
+            }],
+            parameter_types: vec![
+                Type::TypeParameter("T".to_string()),
+                Type::TypeParameter("T".to_string()),
+            ],
+            return_type: Type::TypeParameter("T".to_string()),
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "max".to_string(),
+            },
+        },
+    );
+    functions.insert(
+        "pow".to_string(),
+        FunctionInfo {
+            type_parameters: Vec::new(),
+            parameter_types: vec![Type::Integer64, Type::Integer64],
+            return_type: Type::Integer64,
+            call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                function_name: "pow".to_string(),
+            },
+        },
+    );
+    for function_name in ["checked_add", "checked_sub", "checked_mul"] {
+        functions.insert(
+            function_name.to_string(),
+            FunctionInfo {
+                type_parameters: Vec::new(),
+                parameter_types: vec![Type::Integer64, Type::Integer64],
+                return_type: normalize_union(vec![Type::Integer64, Type::Nil]),
+                call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                    function_name: function_name.to_string(),
+                },
+            },
+        );
+    }
+    for function_name in ["saturating_add", "saturating_sub", "saturating_mul"] {
+        functions.insert(
+            function_name.to_string(),
+            FunctionInfo {
+                type_parameters: Vec::new(),
+                parameter_types: vec![Type::Integer64, Type::Integer64],
+                return_type: Type::Integer64,
+                call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                    function_name: function_name.to_string(),
+                },
+            },
+        );
+    }
+    for host_function_contract in host_function_contracts {
+        functions
+            .entry(host_function_contract.name.clone())
+            .or_insert_with(|| FunctionInfo {
+                type_parameters: Vec::new(),
+                parameter_types: host_function_contract.parameter_types.clone(),
+                return_type: host_function_contract.return_type.clone(),
+                call_target: TypeAnnotatedCallTarget::BuiltinFunction {
+                    function_name: host_function_contract.name.clone(),
+                },
+            });
+    }
     functions
 }
 
@@ -2852,9 +3680,11 @@ impl ExpressionSpan for SemanticExpression {
             | SemanticExpression::Call { span, .. }
             | SemanticExpression::Unary { span, .. }
             | SemanticExpression::Binary { span, .. }
+            | SemanticExpression::If { span, .. }
+            | SemanticExpression::Block { span, .. }
             | SemanticExpression::Match { span, .. }
             | SemanticExpression::Matches { span, .. }
-            | SemanticExpression::StringInterpolation { span, .. } => span.clone(),
+            | SemanticExpression::StringInterpolation { span, .. } => *span,
         }
     }
 }
@@ -2866,10 +3696,13 @@ impl StatementSpan for SemanticStatement {
             | SemanticStatement::Assign { span, .. }
             | SemanticStatement::Return { span, .. }
             | SemanticStatement::If { span, .. }
+            | SemanticStatement::Guard { span, .. }
             | SemanticStatement::For { span, .. }
+            | SemanticStatement::ForRange { span, .. }
             | SemanticStatement::Break { span, .. }
             | SemanticStatement::Continue { span, .. }
-            | SemanticStatement::Expression { span, .. } => span.clone(),
+            | SemanticStatement::Defer { span, .. }
+            | SemanticStatement::Expression { span, .. } => *span,
         }
     }
 }
@@ -1,36 +1,242 @@
+use std::collections::BTreeSet;
+
 use super::TypeChecker;
-use compiler__source::Span;
+use compiler__fix_edits::TextEdit;
+use compiler__safe_autofix::SafeAutofix;
+use compiler__semantic_program::{
+    SemanticFunctionDeclaration, SemanticTopLevelVisibility, SemanticTypeDeclaration,
+};
+use compiler__source::{Span, VersionedReservedWord};
 
 impl TypeChecker<'_> {
     pub(super) fn check_type_name(&mut self, name: &str, span: &Span) {
         if !is_pascal_case(name) {
-            self.error("type name must be PascalCase", span.clone());
+            self.error("type name must be PascalCase", *span);
         }
     }
 
     pub(super) fn check_function_name(&mut self, name: &str, span: &Span) {
+        if self.report_reserved_name(name, span) {
+            return;
+        }
         if !is_camel_case(name) {
-            self.error("function name must be camelCase", span.clone());
+            self.error("function name must be camelCase", *span);
         }
     }
 
     pub(super) fn check_constant_name(&mut self, name: &str, span: &Span) {
         if !is_upper_snake_case(name) {
-            self.error("constant name must be UPPER_SNAKE_CASE", span.clone());
+            self.error("constant name must be UPPER_SNAKE_CASE", *span);
         }
     }
 
     pub(super) fn check_variable_name(&mut self, name: &str, span: &Span) {
+        if self.report_reserved_name(name, span) {
+            return;
+        }
         if !is_camel_case_with_optional_leading_underscore(name) {
-            self.error("variable name must be camelCase", span.clone());
+            self.error("variable name must be camelCase", *span);
         }
     }
 
     pub(super) fn check_parameter_name(&mut self, name: &str, span: &Span) {
+        if self.report_reserved_name(name, span) {
+            return;
+        }
         if !is_camel_case_with_optional_leading_underscore(name) {
-            self.error("parameter name must be camelCase", span.clone());
+            self.error("parameter name must be camelCase", *span);
         }
     }
+
+    // Words reserved by a newer language version than the package declares
+    // are still names, which is why the parser does not reject them.
+    fn report_reserved_name(&mut self, name: &str, span: &Span) -> bool {
+        let Some(word) = VersionedReservedWord::from_name(name) else {
+            return false;
+        };
+        let reserved_since = word.reserved_since();
+        if self.language_version < reserved_since {
+            return false;
+        }
+        self.error(
+            format!(
+                "'{name}' is reserved since language version {} and cannot be used as a name",
+                reserved_since.name()
+            ),
+            *span,
+        );
+        true
+    }
+
+    pub(super) fn autofix_binding_names_in_current_scope(&mut self) {
+        let Some(scope) = self.scopes.last() else {
+            return;
+        };
+        let mut renames = Vec::new();
+        for (name, info) in scope {
+            if is_camel_case_with_optional_leading_underscore(name) {
+                continue;
+            }
+            let (prefix, rest) = match name.strip_prefix('_') {
+                Some(rest) => ("_", rest),
+                None => ("", name.as_str()),
+            };
+            let Some(replacement) = camel_case_replacement(rest) else {
+                continue;
+            };
+            let mut spans = info.reference_spans.clone();
+            spans.push(info.name_span);
+            renames.push((name.clone(), format!("{prefix}{replacement}"), spans));
+        }
+        for (name, replacement, spans) in renames {
+            self.push_rename_autofix(&name, &replacement, &spans);
+        }
+    }
+
+    // Only file-private functions are renamed: visible functions may be
+    // referenced from other files, which this phase cannot rewrite.
+    pub(super) fn autofix_function_names(&mut self, functions: &[&SemanticFunctionDeclaration]) {
+        for function in functions {
+            if function.visibility != SemanticTopLevelVisibility::Private
+                || is_camel_case(&function.name)
+            {
+                continue;
+            }
+            let Some(replacement) = camel_case_replacement(&function.name) else {
+                continue;
+            };
+            let mut spans = self
+                .reference_spans_by_function_name
+                .get(&function.name)
+                .cloned()
+                .unwrap_or_default();
+            spans.push(function.name_span);
+            self.push_rename_autofix(&function.name, &replacement, &spans);
+        }
+    }
+
+    // Like functions, only file-private types are renamed. Type names are
+    // resolved in more places than this phase records, so the rename is only
+    // offered when every occurrence of the name in the file was recorded as a
+    // reference; a name also used in a comment or string is left alone.
+    pub(super) fn autofix_type_names(&mut self, types: &[&SemanticTypeDeclaration]) {
+        for type_declaration in types {
+            if type_declaration.visibility != SemanticTopLevelVisibility::Private
+                || is_pascal_case(&type_declaration.name)
+            {
+                continue;
+            }
+            let Some(replacement) = pascal_case_replacement(&type_declaration.name) else {
+                continue;
+            };
+            let mut spans = self
+                .reference_spans_by_type_name
+                .get(&type_declaration.name)
+                .cloned()
+                .unwrap_or_default();
+            spans.push(type_declaration.name_span);
+            let reference_starts = spans.iter().map(|span| span.start).collect::<BTreeSet<_>>();
+            if reference_starts.len()
+                != identifier_occurrence_count(self.source_text, &type_declaration.name)
+            {
+                continue;
+            }
+            self.push_rename_autofix(&type_declaration.name, &replacement, &spans);
+        }
+    }
+
+    fn push_rename_autofix(&mut self, name: &str, replacement: &str, spans: &[Span]) {
+        if source_contains_identifier(self.source_text, replacement) {
+            return;
+        }
+        let mut ranges = BTreeSet::new();
+        for span in spans {
+            if self.source_text.get(span.start..span.end) != Some(name) {
+                return;
+            }
+            ranges.insert((span.start, span.end));
+        }
+        let text_edits = ranges
+            .into_iter()
+            .map(|(start_byte_offset, end_byte_offset)| TextEdit {
+                start_byte_offset,
+                end_byte_offset,
+                replacement_text: replacement.to_string(),
+            })
+            .collect();
+        self.push_safe_autofix(SafeAutofix {
+            rule: "naming_convention",
+            text_edits,
+        });
+    }
+}
+
+// Single-word replacements are never offered: a lowercased single word may be
+// a keyword, and this phase has no access to the lexer's keyword table.
+fn camel_case_replacement(name: &str) -> Option<String> {
+    let words = split_words(name);
+    if words.len() < 2 {
+        return None;
+    }
+    let mut replacement = String::new();
+    for (index, word) in words.iter().enumerate() {
+        let lowercase = word.to_ascii_lowercase();
+        if index == 0 {
+            replacement.push_str(&lowercase);
+            continue;
+        }
+        let mut chars = lowercase.chars();
+        if let Some(first) = chars.next() {
+            replacement.push(first.to_ascii_uppercase());
+            replacement.push_str(chars.as_str());
+        }
+    }
+    is_camel_case(&replacement).then_some(replacement)
+}
+
+// Unlike camelCase, a capitalized single word cannot be a keyword.
+fn pascal_case_replacement(name: &str) -> Option<String> {
+    let mut replacement = String::new();
+    for word in split_words(name) {
+        let lowercase = word.to_ascii_lowercase();
+        let mut chars = lowercase.chars();
+        if let Some(first) = chars.next() {
+            replacement.push(first.to_ascii_uppercase());
+            replacement.push_str(chars.as_str());
+        }
+    }
+    is_pascal_case(&replacement).then_some(replacement)
+}
+
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for segment in name.split('_').filter(|segment| !segment.is_empty()) {
+        let chars = segment.chars().collect::<Vec<_>>();
+        let mut current = String::new();
+        for (index, &ch) in chars.iter().enumerate() {
+            let starts_word = index > 0
+                && ch.is_ascii_uppercase()
+                && (!chars[index - 1].is_ascii_uppercase()
+                    || chars.get(index + 1).is_some_and(char::is_ascii_lowercase));
+            if starts_word {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(ch);
+        }
+        words.push(current);
+    }
+    words
+}
+
+fn source_contains_identifier(source_text: &str, identifier: &str) -> bool {
+    identifier_occurrence_count(source_text, identifier) > 0
+}
+
+fn identifier_occurrence_count(source_text: &str, identifier: &str) -> usize {
+    source_text
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+        .filter(|word| *word == identifier)
+        .count()
 }
 
 fn is_pascal_case(name: &str) -> bool {
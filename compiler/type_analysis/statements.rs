@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use compiler__diagnostics::{PhaseDiagnostic, SuggestedEdit, SuggestedFix};
 use compiler__fix_edits::TextEdit;
 use compiler__safe_autofix::SafeAutofix;
 use compiler__semantic_program::{
@@ -8,15 +9,19 @@ use compiler__semantic_program::{
     SemanticTypeDeclarationKind,
 };
 use compiler__semantic_types::{NominalTypeId, NominalTypeRef, Type};
+use compiler__source::LanguageFeature;
 
-use super::{ExpressionSpan, FallthroughNarrowing, StatementOutcome, StatementSpan, TypeChecker};
+use super::{
+    BlockOutcome, ExpressionSpan, FallthroughBranch, FallthroughBranchKind, FallthroughNarrowing,
+    NarrowingBranch, StatementOutcome, StatementSpan, TypeChecker,
+};
 
 impl TypeChecker<'_> {
     pub(super) fn check_function(&mut self, function: &SemanticFunctionDeclaration) {
         let names_and_spans = function
             .type_parameters
             .iter()
-            .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+            .map(|parameter| (parameter.name.clone(), parameter.span))
             .collect::<Vec<_>>();
         self.push_type_parameters(&names_and_spans);
         self.scopes.push(HashMap::new());
@@ -28,6 +33,8 @@ impl TypeChecker<'_> {
             (Vec::new(), self.resolve_type_name(&function.return_type))
         };
         self.current_return_type = return_type;
+        self.current_return_type_span = Some(function.return_type.span);
+        self.deferred_local_names.clear();
 
         for (index, parameter) in function.parameters.iter().enumerate() {
             self.check_parameter_name(&parameter.name, &parameter.name_span);
@@ -37,25 +44,21 @@ impl TypeChecker<'_> {
                 value_type,
                 parameter.mutable,
                 &parameter.span,
-                parameter.name_span.clone(),
+                parameter.name_span,
             );
         }
 
-        let body_returns = self.check_block(&function.body);
+        let body_outcome = self.check_block(&function.body);
 
         self.check_unused_in_current_scope();
+        self.autofix_binding_names_in_current_scope();
         self.scopes.pop();
         self.pop_type_parameters();
 
-        if !body_returns {
-            self.error(
-                "missing return in function body",
-                function.body.span.clone(),
-            );
-        }
+        self.check_missing_return(&function.body, body_outcome);
     }
 
-    pub(super) fn check_methods(&mut self, types: &[SemanticTypeDeclaration]) {
+    pub(super) fn check_methods(&mut self, types: &[&SemanticTypeDeclaration]) {
         for type_declaration in types {
             let SemanticTypeDeclarationKind::Struct { methods, .. } = &type_declaration.kind else {
                 continue;
@@ -74,7 +77,7 @@ impl TypeChecker<'_> {
         let names_and_spans = type_declaration
             .type_parameters
             .iter()
-            .map(|parameter| (parameter.name.clone(), parameter.span.clone()))
+            .map(|parameter| (parameter.name.clone(), parameter.span))
             .collect::<Vec<_>>();
         self.push_type_parameters(&names_and_spans);
         self.scopes.push(HashMap::new());
@@ -92,6 +95,8 @@ impl TypeChecker<'_> {
             (Vec::new(), self.resolve_type_name(&method.return_type))
         };
         self.current_return_type = return_type;
+        self.current_return_type_span = Some(method.return_type.span);
+        self.deferred_local_names.clear();
 
         let self_type = if type_declaration.type_parameters.is_empty() {
             Type::Named(NominalTypeRef {
@@ -122,7 +127,7 @@ impl TypeChecker<'_> {
             self_type,
             method.self_mutable,
             &method.self_span,
-            method.self_span.clone(),
+            method.self_span,
         );
         if let Some(scope) = self.scopes.last_mut()
             && let Some(self_variable) = scope.get_mut("self")
@@ -138,24 +143,64 @@ impl TypeChecker<'_> {
                 value_type,
                 parameter.mutable,
                 &parameter.span,
-                parameter.name_span.clone(),
+                parameter.name_span,
             );
         }
 
-        let body_returns = self.check_block(&method.body);
+        let body_outcome = self.check_block(&method.body);
 
         self.check_unused_in_current_scope();
+        self.autofix_binding_names_in_current_scope();
         self.scopes.pop();
         self.pop_type_parameters();
 
-        if !body_returns {
-            self.error("missing return in function body", method.body.span.clone());
+        self.check_missing_return(&method.body, body_outcome);
+    }
+
+    fn check_missing_return(&mut self, body: &SemanticBlock, body_outcome: BlockOutcome) {
+        if body_outcome.terminates {
+            return;
         }
+        let fallthrough_branch = body_outcome
+            .fallthrough_branch
+            .filter(|_| !matches!(self.current_return_type, Type::Nil | Type::Unknown));
+        let Some(fallthrough_branch) = fallthrough_branch else {
+            self.error("missing return in function body", body.span);
+            return;
+        };
+        let (message, related_message) = match fallthrough_branch.kind {
+            FallthroughBranchKind::IfWithoutElse => (
+                "'if' without 'else' can reach the end of the function",
+                "this 'if' has no 'else' branch",
+            ),
+            FallthroughBranchKind::IfBranch => (
+                "'if' branch can reach the end of the function",
+                "this branch does not return",
+            ),
+            FallthroughBranchKind::ElseBranch => (
+                "'else' branch can reach the end of the function",
+                "this branch does not return",
+            ),
+            FallthroughBranchKind::LoopExit => (
+                "loop exit can reach the end of the function",
+                "this loop can exit without returning",
+            ),
+        };
+        self.diagnostics.push(
+            PhaseDiagnostic::new(
+                format!("missing return in function body: {message}"),
+                body.span,
+            )
+            .with_related_span(related_message, fallthrough_branch.span),
+        );
     }
 
-    pub(super) fn check_block(&mut self, block: &SemanticBlock) -> bool {
+    pub(super) fn check_block(&mut self, block: &SemanticBlock) -> BlockOutcome {
         self.scopes.push(HashMap::new());
+        self.block_depth += 1;
         let mut falls_through = true;
+        let mut fallthrough_branch = None;
+        let mut restores = Vec::new();
         let mut has_reported_unreachable = false;
         for statement in &block.statements {
             if !falls_through {
@@ -167,19 +212,29 @@ impl TypeChecker<'_> {
             }
 
             let outcome = self.check_statement(statement);
-            if let Some(fallthrough_narrowing) = outcome.fallthrough_narrowing {
-                self.apply_variable_narrowing(
-                    &fallthrough_narrowing.variable_name,
+            for fallthrough_narrowing in outcome.fallthrough_narrowings {
+                restores.extend(self.apply_narrowing(
+                    &fallthrough_narrowing.target,
                     fallthrough_narrowing.narrowed_type,
-                );
+                ));
+            }
+            if outcome.fallthrough_branch.is_some() {
+                fallthrough_branch = outcome.fallthrough_branch;
             }
             if falls_through && outcome.terminates {
                 falls_through = false;
             }
         }
+        self.restore_variable_types(restores);
         self.check_unused_in_current_scope();
+        self.autofix_binding_names_in_current_scope();
+        self.collect_inline_candidates_in_current_scope();
         self.scopes.pop();
-        !falls_through
+        self.block_depth -= 1;
+        BlockOutcome {
+            terminates: !falls_through,
+            fallthrough_branch: fallthrough_branch.filter(|_| falls_through),
+        }
     }
 
     pub(super) fn check_statement(&mut self, statement: &SemanticStatement) -> StatementOutcome {
@@ -194,11 +249,18 @@ impl TypeChecker<'_> {
                 ..
             } => {
                 self.check_variable_name(name, name_span);
-                let value_type = self.check_expression(initializer);
+                let annotated_type = type_name
+                    .as_ref()
+                    .map(|type_name| self.resolve_type_name(type_name));
+                let value_type = match &annotated_type {
+                    Some(annotated_type) => {
+                        self.check_expression_with_expected_type(initializer, annotated_type)
+                    }
+                    None => self.check_expression(initializer),
+                };
                 let mut binding_type = value_type.clone();
                 let mut annotation_mismatch = false;
-                if let Some(type_name) = type_name {
-                    let annotated_type = self.resolve_type_name(type_name);
+                if let Some(annotated_type) = annotated_type {
                     if annotated_type != Type::Unknown
                         && value_type != Type::Unknown
                         && !self.is_assignable(&value_type, &annotated_type)
@@ -217,22 +279,22 @@ impl TypeChecker<'_> {
                         binding_type = annotated_type;
                     }
                 }
-                if binding_type == Type::Never {
+                if value_type == Type::Never {
                     return StatementOutcome {
                         terminates: true,
-                        fallthrough_narrowing: None,
+                        fallthrough_narrowings: Vec::new(),
+                        fallthrough_branch: None,
                     };
                 }
-                self.define_variable(
-                    name.clone(),
-                    binding_type,
-                    *mutable,
-                    span,
-                    name_span.clone(),
-                );
+                let inlinable = !*mutable && binding_type == value_type;
+                self.define_variable(name.clone(), binding_type, *mutable, span, *name_span);
+                if inlinable {
+                    self.record_variable_inline_initializer(name, span, initializer);
+                }
                 StatementOutcome {
                     terminates: false,
-                    fallthrough_narrowing: None,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
                 }
             }
             SemanticStatement::Assign { target, value, .. } => {
@@ -241,13 +303,14 @@ impl TypeChecker<'_> {
                     SemanticAssignTarget::Name {
                         name, name_span, ..
                     } => {
+                        self.record_variable_assignment_reference(name, name_span);
                         if let Some((is_mutable, variable_type)) =
                             self.lookup_variable_for_assignment(name)
                         {
                             if !is_mutable {
                                 self.error(
                                     format!("cannot assign to immutable binding '{name}'"),
-                                    name_span.clone(),
+                                    *name_span,
                                 );
                             } else if variable_type != Type::Unknown
                                 && value_type != Type::Unknown
@@ -262,19 +325,14 @@ impl TypeChecker<'_> {
                                     value.span(),
                                 );
                             }
+                            self.invalidate_variable_narrowing(name);
                         } else if self.constants.contains_key(name) {
-                            self.error(
-                                format!("cannot assign to constant '{name}'"),
-                                name_span.clone(),
-                            );
+                            self.error(format!("cannot assign to constant '{name}'"), *name_span);
                         } else if self.imported_constant_type(name).is_some() {
                             self.mark_import_used(name);
-                            self.error(
-                                format!("cannot assign to constant '{name}'"),
-                                name_span.clone(),
-                            );
+                            self.error(format!("cannot assign to constant '{name}'"), *name_span);
                         } else {
-                            self.error(format!("unknown name '{name}'"), name_span.clone());
+                            self.error(format!("unknown name '{name}'"), *name_span);
                         }
                     }
                     SemanticAssignTarget::Index {
@@ -332,9 +390,21 @@ impl TypeChecker<'_> {
                         }
                     }
                 }
+                StatementOutcome {
+                    terminates: value_type == Type::Never,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
+                }
+            }
+            SemanticStatement::Return { value, span } if self.block_expression_depth > 0 => {
+                self.error("return cannot leave a block expression", *span);
+                if let Some(value) = value {
+                    self.check_expression(value);
+                }
                 StatementOutcome {
                     terminates: false,
-                    fallthrough_narrowing: None,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
                 }
             }
             SemanticStatement::Return { value, span } => {
@@ -342,25 +412,48 @@ impl TypeChecker<'_> {
                     if self.current_return_type == Type::Nil
                         && matches!(value, SemanticExpression::NilLiteral { .. })
                     {
-                        self.push_safe_autofix(SafeAutofix::from_text_edit(TextEdit {
-                            start_byte_offset: span.end,
-                            end_byte_offset: value.span().end,
-                            replacement_text: String::new(),
-                        }));
+                        self.push_safe_autofix(SafeAutofix::from_text_edit(
+                            "redundant_nil_return",
+                            TextEdit {
+                                start_byte_offset: span.end,
+                                end_byte_offset: value.span().end,
+                                replacement_text: String::new(),
+                            },
+                        ));
                     }
-                    let value_type = self.check_expression(value);
+                    let value_type = self.check_expression_with_expected_type(
+                        value,
+                        &self.current_return_type.clone(),
+                    );
                     if self.current_return_type != Type::Unknown
                         && value_type != Type::Unknown
                         && !self.is_assignable(&value_type, &self.current_return_type)
                     {
-                        self.error(
-                            format!(
-                                "return type mismatch: expected {}, got {}",
-                                self.current_return_type.display(),
-                                value_type.display()
-                            ),
-                            value.span(),
+                        let message = format!(
+                            "return type mismatch: expected {}, got {}",
+                            self.current_return_type.display(),
+                            value_type.display()
                         );
+                        if let Some(return_type_span) = self.current_return_type_span
+                            && value_type != Type::Never
+                        {
+                            self.error_with_suggested_fix(
+                                message,
+                                value.span(),
+                                SuggestedFix {
+                                    label: format!(
+                                        "change return type to {}",
+                                        value_type.display()
+                                    ),
+                                    edits: vec![SuggestedEdit {
+                                        span: return_type_span,
+                                        replacement_text: value_type.display(),
+                                    }],
+                                },
+                            );
+                        } else {
+                            self.error(message, value.span());
+                        }
                     }
                 } else if !matches!(self.current_return_type, Type::Nil | Type::Unknown) {
                     self.error(
@@ -368,102 +461,269 @@ impl TypeChecker<'_> {
                             "return statement requires a value of type {}",
                             self.current_return_type.display()
                         ),
-                        span.clone(),
+                        *span,
                     );
                 }
                 StatementOutcome {
                     terminates: true,
-                    fallthrough_narrowing: None,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
                 }
             }
             SemanticStatement::Break { span } => {
                 if self.loop_depth == 0 {
-                    self.error("break can only be used inside a loop", span.clone());
+                    if self.block_expression_depth > 0 {
+                        self.error("break cannot leave a block expression", *span);
+                    } else {
+                        self.error("break can only be used inside a loop", *span);
+                    }
                     StatementOutcome {
                         terminates: false,
-                        fallthrough_narrowing: None,
+                        fallthrough_narrowings: Vec::new(),
+                        fallthrough_branch: None,
                     }
                 } else {
                     StatementOutcome {
                         terminates: true,
-                        fallthrough_narrowing: None,
+                        fallthrough_narrowings: Vec::new(),
+                        fallthrough_branch: None,
                     }
                 }
             }
             SemanticStatement::Continue { span } => {
                 if self.loop_depth == 0 {
-                    self.error("continue can only be used inside a loop", span.clone());
+                    if self.block_expression_depth > 0 {
+                        self.error("continue cannot leave a block expression", *span);
+                    } else {
+                        self.error("continue can only be used inside a loop", *span);
+                    }
                     StatementOutcome {
                         terminates: false,
-                        fallthrough_narrowing: None,
+                        fallthrough_narrowings: Vec::new(),
+                        fallthrough_branch: None,
                     }
                 } else {
                     StatementOutcome {
                         terminates: true,
-                        fallthrough_narrowing: None,
+                        fallthrough_narrowings: Vec::new(),
+                        fallthrough_branch: None,
                     }
                 }
             }
+            SemanticStatement::Defer { value, span } => {
+                self.check_language_feature_is_available(LanguageFeature::DeferStatements, *span);
+                if self.block_depth != 1 || self.block_expression_depth > 0 {
+                    self.error("defer must be at the top level of a function body", *span);
+                }
+                let value_type = self.check_deferred_expression(value);
+                if value_type != Type::Nil && value_type != Type::Unknown {
+                    self.error(
+                        format!(
+                            "deferred expression must have type nil, got {}",
+                            value_type.display()
+                        ),
+                        value.span(),
+                    );
+                }
+                StatementOutcome {
+                    terminates: false,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
+                }
+            }
             SemanticStatement::If {
                 condition,
                 then_block,
                 else_block,
-                ..
+                span,
             } => {
                 let condition_type = self.check_expression(condition);
                 if condition_type != Type::Boolean && condition_type != Type::Unknown {
                     self.error("if condition must be boolean", condition.span());
                 }
-                let condition_type_narrowing = self.derive_condition_type_narrowing(condition);
-                let then_branch_terminates = self.check_block_with_type_narrowing(
+                let condition_type_narrowings = self.derive_condition_type_narrowings(condition);
+                let then_outcome = self.check_block_with_type_narrowing(
                     then_block,
-                    condition_type_narrowing.as_ref(),
-                    true,
+                    &condition_type_narrowings,
+                    NarrowingBranch::WhenTrue,
                 );
-                let else_branch_terminates = else_block.as_ref().is_some_and(|block| {
+                let else_outcome = else_block.as_ref().map(|block| {
                     self.check_block_with_type_narrowing(
                         block,
-                        condition_type_narrowing.as_ref(),
-                        false,
+                        &condition_type_narrowings,
+                        NarrowingBranch::WhenFalse,
                     )
                 });
-                let fallthrough_narrowing = if then_branch_terminates && !else_branch_terminates {
-                    condition_type_narrowing
-                        .as_ref()
+                let then_branch_terminates = then_outcome.terminates;
+                let else_branch_terminates = else_outcome
+                    .as_ref()
+                    .is_some_and(|outcome| outcome.terminates);
+                let fallthrough_narrowings = if then_branch_terminates && !else_branch_terminates {
+                    condition_type_narrowings
+                        .iter()
                         .map(|type_narrowing| FallthroughNarrowing {
-                            variable_name: type_narrowing.name.clone(),
+                            target: type_narrowing.target.clone(),
                             narrowed_type: type_narrowing.when_false.clone(),
                         })
+                        .collect()
                 } else if !then_branch_terminates && else_branch_terminates {
-                    condition_type_narrowing
-                        .as_ref()
+                    condition_type_narrowings
+                        .iter()
                         .map(|type_narrowing| FallthroughNarrowing {
-                            variable_name: type_narrowing.name.clone(),
+                            target: type_narrowing.target.clone(),
                             narrowed_type: type_narrowing.when_true.clone(),
                         })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let fallthrough_branch = if !then_branch_terminates {
+                    Some(
+                        then_outcome
+                            .fallthrough_branch
+                            .unwrap_or(FallthroughBranch {
+                                kind: FallthroughBranchKind::IfBranch,
+                                span: then_block.span,
+                            }),
+                    )
+                } else if let (Some(else_block), Some(else_outcome)) = (else_block, else_outcome) {
+                    (!else_outcome.terminates).then(|| {
+                        else_outcome
+                            .fallthrough_branch
+                            .unwrap_or(FallthroughBranch {
+                                kind: FallthroughBranchKind::ElseBranch,
+                                span: else_block.span,
+                            })
+                    })
                 } else {
-                    None
+                    Some(FallthroughBranch {
+                        kind: FallthroughBranchKind::IfWithoutElse,
+                        span: *span,
+                    })
                 };
                 StatementOutcome {
                     terminates: then_branch_terminates && else_branch_terminates,
-                    fallthrough_narrowing,
+                    fallthrough_narrowings,
+                    fallthrough_branch,
+                }
+            }
+            SemanticStatement::Guard {
+                condition,
+                else_block,
+                span,
+            } => {
+                self.check_language_feature_is_available(LanguageFeature::GuardStatements, *span);
+                let condition_type = self.check_expression(condition);
+                if condition_type != Type::Boolean && condition_type != Type::Unknown {
+                    self.error("guard condition must be boolean", condition.span());
+                }
+                let condition_type_narrowings = self.derive_condition_type_narrowings(condition);
+                let else_outcome = self.check_block_with_type_narrowing(
+                    else_block,
+                    &condition_type_narrowings,
+                    NarrowingBranch::WhenFalse,
+                );
+                if !else_outcome.terminates {
+                    self.error(
+                        "guard else block must exit with return, break, continue, or abort",
+                        else_block.span,
+                    );
+                }
+                StatementOutcome {
+                    terminates: false,
+                    fallthrough_narrowings: condition_type_narrowings
+                        .iter()
+                        .map(|type_narrowing| FallthroughNarrowing {
+                            target: type_narrowing.target.clone(),
+                            narrowed_type: type_narrowing.when_true.clone(),
+                        })
+                        .collect(),
+                    fallthrough_branch: None,
                 }
             }
             SemanticStatement::For {
-                condition, body, ..
+                condition,
+                body,
+                span,
             } => {
+                let mut condition_type_narrowings = Vec::new();
                 if let Some(condition) = condition {
                     let condition_type = self.check_expression(condition);
                     if condition_type != Type::Boolean && condition_type != Type::Unknown {
                         self.error("for condition must be boolean", condition.span());
                     }
+                    condition_type_narrowings = self.derive_condition_type_narrowings(condition);
+                }
+                self.loop_depth += 1;
+                let _ = self.check_block_with_type_narrowing(
+                    body,
+                    &condition_type_narrowings,
+                    NarrowingBranch::WhenTrue,
+                );
+                self.loop_depth = self.loop_depth.saturating_sub(1);
+                StatementOutcome {
+                    terminates: false,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: Some(FallthroughBranch {
+                        kind: FallthroughBranchKind::LoopExit,
+                        span: *span,
+                    }),
+                }
+            }
+            SemanticStatement::ForRange {
+                name,
+                name_span,
+                start,
+                end,
+                step,
+                body,
+                span,
+            } => {
+                self.check_language_feature_is_available(LanguageFeature::RangeForLoops, *span);
+                for bound in [start, end] {
+                    let bound_type = self.check_expression(bound);
+                    if bound_type != Type::Integer64 && bound_type != Type::Unknown {
+                        self.error("range bounds must be int64", bound.span());
+                    }
+                }
+                if let Some(step) = step {
+                    let step_type = self.check_expression(step);
+                    if step_type != Type::Integer64 && step_type != Type::Unknown {
+                        self.error("range step must be int64", step.span());
+                    } else if matches!(**step, SemanticExpression::IntegerLiteral { value: 0, .. })
+                    {
+                        self.error("range step must not be zero", step.span());
+                    }
+                }
+                // The loop variable lives in a scope of its own around the
+                // body, so it is not visible after the loop.
+                self.scopes.push(HashMap::new());
+                if self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .any(|scope| scope.contains_key(name))
+                {
+                    self.error(
+                        format!("loop variable '{name}' shadows an outer binding"),
+                        *name_span,
+                    );
                 }
+                self.define_variable(name.clone(), Type::Integer64, false, span, *name_span);
                 self.loop_depth += 1;
                 let _ = self.check_block(body);
                 self.loop_depth = self.loop_depth.saturating_sub(1);
+                self.check_unused_in_current_scope();
+                self.autofix_binding_names_in_current_scope();
+                self.scopes.pop();
                 StatementOutcome {
                     terminates: false,
-                    fallthrough_narrowing: None,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: Some(FallthroughBranch {
+                        kind: FallthroughBranchKind::LoopExit,
+                        span: *span,
+                    }),
                 }
             }
             SemanticStatement::Expression { value, .. } => {
@@ -474,7 +734,8 @@ impl TypeChecker<'_> {
                 }
                 StatementOutcome {
                     terminates: value_type == Type::Never,
-                    fallthrough_narrowing: None,
+                    fallthrough_narrowings: Vec::new(),
+                    fallthrough_branch: None,
                 }
             }
         }
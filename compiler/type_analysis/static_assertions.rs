@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use compiler__semantic_program::{
+    SemanticBinaryOperator, SemanticConstantDeclaration, SemanticExpression,
+    SemanticNameReferenceKind, SemanticStaticAssertDeclaration, SemanticUnaryOperator,
+};
+
+use compiler__source::LanguageFeature;
+
+use super::{ExpressionSpan, Type, TypeChecker};
+
+#[derive(Clone, PartialEq, Eq)]
+enum ConstantValue {
+    Int64(i64),
+    Boolean(bool),
+    String(String),
+    Nil,
+}
+
+// Evaluates constant expressions the way a run would: int64 arithmetic wraps
+// and shift amounts use their low six bits. Division or remainder by zero has
+// no value, and neither do constants imported from other files, whose
+// initializers are not available here.
+struct ConstantEvaluator<'a> {
+    constant_by_name: HashMap<&'a str, &'a SemanticConstantDeclaration>,
+    constants_in_progress: Vec<&'a str>,
+}
+
+impl TypeChecker<'_> {
+    pub(super) fn check_static_assertions(
+        &mut self,
+        static_asserts: &[&SemanticStaticAssertDeclaration],
+        constants: &[&SemanticConstantDeclaration],
+    ) {
+        let mut evaluator = ConstantEvaluator {
+            constant_by_name: constants
+                .iter()
+                .map(|constant| (constant.name.as_str(), *constant))
+                .collect(),
+            constants_in_progress: Vec::new(),
+        };
+        for static_assert in static_asserts {
+            self.check_language_feature_is_available(
+                LanguageFeature::StaticAssertions,
+                static_assert.span,
+            );
+            let condition_type = self.check_expression(&static_assert.condition);
+            if condition_type == Type::Unknown {
+                continue;
+            }
+            if condition_type != Type::Boolean {
+                self.error(
+                    "static_assert condition must be boolean",
+                    static_assert.condition.span(),
+                );
+                continue;
+            }
+            match evaluator.evaluate(&static_assert.condition) {
+                Some(ConstantValue::Boolean(true)) => {}
+                Some(ConstantValue::Boolean(false)) => self.error(
+                    format!("static assertion failed: {}", static_assert.message),
+                    static_assert.span,
+                ),
+                _ => self.error(
+                    "static_assert condition must be a constant expression",
+                    static_assert.condition.span(),
+                ),
+            }
+        }
+    }
+}
+
+impl<'a> ConstantEvaluator<'a> {
+    fn evaluate(&mut self, expression: &'a SemanticExpression) -> Option<ConstantValue> {
+        match expression {
+            SemanticExpression::IntegerLiteral { value, .. } => Some(ConstantValue::Int64(*value)),
+            SemanticExpression::BooleanLiteral { value, .. } => {
+                Some(ConstantValue::Boolean(*value))
+            }
+            SemanticExpression::StringLiteral { value, .. } => {
+                Some(ConstantValue::String(value.clone()))
+            }
+            SemanticExpression::NilLiteral { .. } => Some(ConstantValue::Nil),
+            SemanticExpression::NameReference {
+                name,
+                kind: SemanticNameReferenceKind::UserDefined,
+                ..
+            } => self.evaluate_constant(name),
+            SemanticExpression::Unary {
+                operator,
+                expression,
+                ..
+            } => match (operator, self.evaluate(expression)?) {
+                (SemanticUnaryOperator::Not, ConstantValue::Boolean(value)) => {
+                    Some(ConstantValue::Boolean(!value))
+                }
+                (SemanticUnaryOperator::Negate, ConstantValue::Int64(value)) => {
+                    Some(ConstantValue::Int64(value.wrapping_neg()))
+                }
+                _ => None,
+            },
+            SemanticExpression::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => self.evaluate_binary(*operator, left, right),
+            _ => None,
+        }
+    }
+
+    fn evaluate_constant(&mut self, name: &str) -> Option<ConstantValue> {
+        let constant = *self.constant_by_name.get(name)?;
+        if self.constants_in_progress.contains(&name) {
+            return None;
+        }
+        self.constants_in_progress.push(&constant.name);
+        let value = self.evaluate(&constant.expression);
+        self.constants_in_progress.pop();
+        value
+    }
+
+    fn evaluate_binary(
+        &mut self,
+        operator: SemanticBinaryOperator,
+        left: &'a SemanticExpression,
+        right: &'a SemanticExpression,
+    ) -> Option<ConstantValue> {
+        let left = self.evaluate(left)?;
+        match (operator, &left) {
+            (SemanticBinaryOperator::And, ConstantValue::Boolean(false))
+            | (SemanticBinaryOperator::Or, ConstantValue::Boolean(true)) => return Some(left),
+            _ => {}
+        }
+        let right = self.evaluate(right)?;
+        Some(match (operator, left, right) {
+            (SemanticBinaryOperator::EqualEqual, left, right) => {
+                ConstantValue::Boolean(left == right)
+            }
+            (SemanticBinaryOperator::NotEqual, left, right) => {
+                ConstantValue::Boolean(left != right)
+            }
+            (
+                SemanticBinaryOperator::And | SemanticBinaryOperator::Or,
+                ConstantValue::Boolean(_),
+                right @ ConstantValue::Boolean(_),
+            ) => right,
+            (
+                SemanticBinaryOperator::Add,
+                ConstantValue::String(left),
+                ConstantValue::String(right),
+            ) => ConstantValue::String(left + &right),
+            (operator, ConstantValue::Int64(left), ConstantValue::Int64(right)) => {
+                evaluate_int64_operator(operator, left, right)?
+            }
+            _ => return None,
+        })
+    }
+}
+
+fn evaluate_int64_operator(
+    operator: SemanticBinaryOperator,
+    left: i64,
+    right: i64,
+) -> Option<ConstantValue> {
+    let shift_amount = u32::try_from(right & 63).ok()?;
+    Some(match operator {
+        SemanticBinaryOperator::Add => ConstantValue::Int64(left.wrapping_add(right)),
+        SemanticBinaryOperator::Subtract => ConstantValue::Int64(left.wrapping_sub(right)),
+        SemanticBinaryOperator::Multiply => ConstantValue::Int64(left.wrapping_mul(right)),
+        SemanticBinaryOperator::Divide | SemanticBinaryOperator::Modulo if right == 0 => {
+            return None;
+        }
+        SemanticBinaryOperator::Divide => ConstantValue::Int64(left.wrapping_div(right)),
+        SemanticBinaryOperator::Modulo => ConstantValue::Int64(left.wrapping_rem(right)),
+        SemanticBinaryOperator::BitwiseAnd => ConstantValue::Int64(left & right),
+        SemanticBinaryOperator::BitwiseOr => ConstantValue::Int64(left | right),
+        SemanticBinaryOperator::BitwiseXor => ConstantValue::Int64(left ^ right),
+        SemanticBinaryOperator::ShiftLeft => ConstantValue::Int64(left.wrapping_shl(shift_amount)),
+        SemanticBinaryOperator::ShiftRight => ConstantValue::Int64(left.wrapping_shr(shift_amount)),
+        SemanticBinaryOperator::LessThan => ConstantValue::Boolean(left < right),
+        SemanticBinaryOperator::LessThanOrEqual => ConstantValue::Boolean(left <= right),
+        SemanticBinaryOperator::GreaterThan => ConstantValue::Boolean(left > right),
+        SemanticBinaryOperator::GreaterThanOrEqual => ConstantValue::Boolean(left >= right),
+        SemanticBinaryOperator::EqualEqual
+        | SemanticBinaryOperator::NotEqual
+        | SemanticBinaryOperator::And
+        | SemanticBinaryOperator::Or => return None,
+    })
+}
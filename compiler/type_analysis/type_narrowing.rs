@@ -1,166 +1,326 @@
+use std::collections::HashSet;
+
 use compiler__semantic_program::{
-    SemanticBinaryOperator, SemanticBlock, SemanticExpression, SemanticMatchArm,
-    SemanticNameReferenceKind,
+    SemanticBinaryOperator, SemanticBlock, SemanticExpression, SemanticExpressionId,
+    SemanticMatchArm, SemanticMatchPattern, SemanticNameReferenceKind, SemanticUnaryOperator,
+    SemanticVisitor, visit_expression_children,
 };
 
-use compiler__semantic_types::Type;
+use compiler__semantic_types::{Type, normalize_union};
 
-use super::{BranchNarrowing, TypeChecker};
+use super::{
+    BlockOutcome, BranchNarrowing, NarrowingBranch, NarrowingTarget, TypeChecker,
+    VariableTypeRestore, semantic_expression_id,
+};
 
 impl TypeChecker<'_> {
     pub(super) fn check_block_with_type_narrowing(
         &mut self,
         block: &SemanticBlock,
-        type_narrowing: Option<&BranchNarrowing>,
-        use_true_branch: bool,
-    ) -> bool {
-        let restore = type_narrowing.and_then(|type_narrowing| {
-            let narrowed_type = if use_true_branch {
-                type_narrowing.when_true.clone()
-            } else {
-                type_narrowing.when_false.clone()
-            };
-            self.apply_variable_narrowing(&type_narrowing.name, narrowed_type)
-        });
-
+        type_narrowings: &[BranchNarrowing],
+        narrowing_branch: NarrowingBranch,
+    ) -> BlockOutcome {
+        let restores = self.apply_branch_narrowings(type_narrowings, narrowing_branch);
         let result = self.check_block(block);
-
-        if let Some((scope_index, name, original_type)) = restore {
-            self.restore_variable_type(scope_index, &name, original_type);
-        }
-
+        self.restore_variable_types(restores);
         result
     }
 
-    pub(super) fn apply_variable_narrowing(
+    pub(super) fn apply_branch_narrowings(
         &mut self,
-        name: &str,
+        type_narrowings: &[BranchNarrowing],
+        narrowing_branch: NarrowingBranch,
+    ) -> Vec<VariableTypeRestore> {
+        type_narrowings
+            .iter()
+            .filter_map(|type_narrowing| {
+                let narrowed_type = match narrowing_branch {
+                    NarrowingBranch::WhenTrue => type_narrowing.when_true.clone(),
+                    NarrowingBranch::WhenFalse => type_narrowing.when_false.clone(),
+                };
+                self.apply_narrowing(&type_narrowing.target, narrowed_type)
+            })
+            .collect()
+    }
+
+    pub(super) fn apply_narrowing(
+        &mut self,
+        target: &NarrowingTarget,
         narrowed_type: Type,
-    ) -> Option<(usize, String, Type)> {
+    ) -> Option<VariableTypeRestore> {
         if narrowed_type == Type::Unknown {
             return None;
         }
         for (scope_index, scope) in self.scopes.iter_mut().enumerate().rev() {
-            if let Some(info) = scope.get_mut(name) {
-                let original_type = info.value_type.clone();
-                info.value_type = narrowed_type;
-                return Some((scope_index, name.to_string(), original_type));
+            if let Some(info) = scope.get_mut(&target.variable_name) {
+                let original_type = if target.field_path.is_empty() {
+                    Some(std::mem::replace(
+                        &mut info.value_type,
+                        narrowed_type.clone(),
+                    ))
+                } else {
+                    info.narrowed_type_by_field_path
+                        .insert(target.field_path.clone(), narrowed_type.clone())
+                };
+                return Some(VariableTypeRestore {
+                    scope_index,
+                    target: target.clone(),
+                    original_type,
+                    narrowed_type,
+                });
             }
         }
         None
     }
 
-    pub(super) fn restore_variable_type(
-        &mut self,
-        scope_index: usize,
-        name: &str,
-        original_type: Type,
-    ) {
-        if let Some(scope) = self.scopes.get_mut(scope_index)
-            && let Some(info) = scope.get_mut(name)
-        {
-            info.value_type = original_type;
+    pub(super) fn restore_variable_types(&mut self, restores: Vec<VariableTypeRestore>) {
+        for restore in restores.into_iter().rev() {
+            let Some(info) = self
+                .scopes
+                .get_mut(restore.scope_index)
+                .and_then(|scope| scope.get_mut(&restore.target.variable_name))
+            else {
+                continue;
+            };
+            // Bindings reassigned after narrowing were already reset to their declared type.
+            if restore.target.field_path.is_empty() {
+                if info.value_type == restore.narrowed_type
+                    && let Some(original_type) = restore.original_type
+                {
+                    info.value_type = original_type;
+                }
+            } else if info
+                .narrowed_type_by_field_path
+                .get(&restore.target.field_path)
+                == Some(&restore.narrowed_type)
+            {
+                match restore.original_type {
+                    Some(original_type) => {
+                        info.narrowed_type_by_field_path
+                            .insert(restore.target.field_path, original_type);
+                    }
+                    None => {
+                        info.narrowed_type_by_field_path
+                            .remove(&restore.target.field_path);
+                    }
+                }
+            }
         }
     }
 
-    pub(super) fn derive_condition_type_narrowing(
-        &mut self,
-        condition: &SemanticExpression,
-    ) -> Option<BranchNarrowing> {
-        if let SemanticExpression::Binary {
-            operator,
-            left,
-            right,
-            ..
-        } = condition
-        {
-            if *operator != SemanticBinaryOperator::EqualEqual
-                && *operator != SemanticBinaryOperator::NotEqual
-            {
-                return None;
+    pub(super) fn invalidate_variable_narrowing(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(info) = scope.get_mut(name) {
+                info.value_type = info.declared_type.clone();
+                info.narrowed_type_by_field_path.clear();
+                return;
             }
+        }
+    }
 
-            let (name, is_nil_test) = if let SemanticExpression::NameReference {
-                name,
-                kind: SemanticNameReferenceKind::UserDefined,
-                ..
-            } = left.as_ref()
-            {
-                (
-                    name,
-                    matches!(right.as_ref(), SemanticExpression::NilLiteral { .. }),
-                )
-            } else if let SemanticExpression::NameReference {
-                name,
-                kind: SemanticNameReferenceKind::UserDefined,
-                ..
-            } = right.as_ref()
-            {
-                (
-                    name,
-                    matches!(left.as_ref(), SemanticExpression::NilLiteral { .. }),
-                )
-            } else {
-                return None;
-            };
-
-            if !is_nil_test {
-                return None;
+    pub(super) fn invalidate_field_narrowings(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(info) = scope.get_mut(name) {
+                info.narrowed_type_by_field_path.clear();
+                return;
             }
+        }
+    }
 
-            let variable_type = self.lookup_variable_type(name)?;
-            let non_nil_type = Self::without_type_member(&variable_type, &Type::Nil);
+    pub(super) fn lookup_field_narrowing(
+        &self,
+        target: &SemanticExpression,
+        field: &str,
+    ) -> Option<Type> {
+        let mut narrowing_target = Self::narrowing_target(target)?;
+        narrowing_target.field_path.push(field.to_string());
+        self.lookup_narrowed_field_type(&narrowing_target)
+    }
 
-            let (when_true, when_false) = match *operator {
-                SemanticBinaryOperator::EqualEqual => (Type::Nil, non_nil_type),
-                SemanticBinaryOperator::NotEqual => (non_nil_type, Type::Nil),
-                _ => return None,
-            };
-            return Some(BranchNarrowing {
-                name: name.clone(),
-                when_true,
-                when_false,
-            });
+    fn lookup_narrowed_field_type(&self, target: &NarrowingTarget) -> Option<Type> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(info) = scope.get(&target.variable_name) {
+                return info
+                    .narrowed_type_by_field_path
+                    .get(&target.field_path)
+                    .cloned();
+            }
         }
+        None
+    }
 
-        if let SemanticExpression::Matches {
-            value,
-            type_name,
-            span: _,
-            ..
-        } = condition
-        {
-            let SemanticExpression::NameReference {
+    pub(super) fn narrowing_target(expression: &SemanticExpression) -> Option<NarrowingTarget> {
+        match expression {
+            SemanticExpression::NameReference {
                 name,
                 kind: SemanticNameReferenceKind::UserDefined,
                 ..
-            } = value.as_ref()
-            else {
-                return None;
-            };
-            let pattern_type = self.resolve_match_pattern_type_name(type_name, &type_name.span);
-            if pattern_type == Type::Unknown {
-                return None;
+            } => Some(NarrowingTarget {
+                variable_name: name.clone(),
+                field_path: Vec::new(),
+            }),
+            SemanticExpression::FieldAccess { target, field, .. } => {
+                let mut narrowing_target = Self::narrowing_target(target)?;
+                narrowing_target.field_path.push(field.clone());
+                Some(narrowing_target)
             }
-            let variable_type = self.lookup_variable_type(name)?;
-            if let Type::Union(variants) = &variable_type
-                && variants.contains(&pattern_type)
-            {
-                return Some(BranchNarrowing {
-                    name: name.clone(),
-                    when_true: pattern_type.clone(),
-                    when_false: Self::without_type_member(&variable_type, &pattern_type),
-                });
-            }
-            if variable_type == pattern_type {
-                return Some(BranchNarrowing {
-                    name: name.clone(),
-                    when_true: pattern_type,
+            _ => None,
+        }
+    }
+
+    fn lookup_narrowing_target_type(
+        &self,
+        target: &NarrowingTarget,
+        expression: &SemanticExpression,
+    ) -> Option<Type> {
+        if target.field_path.is_empty() {
+            return self.lookup_variable_type(&target.variable_name);
+        }
+        self.lookup_variable_type(&target.variable_name)?;
+        self.lookup_narrowed_field_type(target).or_else(|| {
+            self.expression_annotations
+                .resolved_type(semantic_expression_id(expression))
+                .cloned()
+        })
+    }
+
+    pub(super) fn derive_condition_type_narrowings(
+        &mut self,
+        condition: &SemanticExpression,
+    ) -> Vec<BranchNarrowing> {
+        match condition {
+            SemanticExpression::Unary {
+                operator: SemanticUnaryOperator::Not,
+                expression,
+                ..
+            } => self
+                .derive_condition_type_narrowings(expression)
+                .into_iter()
+                .map(|type_narrowing| BranchNarrowing {
+                    target: type_narrowing.target,
+                    when_true: type_narrowing.when_false,
+                    when_false: type_narrowing.when_true,
+                })
+                .collect(),
+            SemanticExpression::Binary {
+                operator: SemanticBinaryOperator::And,
+                left,
+                right,
+                ..
+            } => self
+                .derive_short_circuit_type_narrowings(left, right, NarrowingBranch::WhenTrue)
+                .into_iter()
+                .map(|type_narrowing| BranchNarrowing {
                     when_false: Type::Unknown,
-                });
-            }
+                    ..type_narrowing
+                })
+                .collect(),
+            SemanticExpression::Binary {
+                operator: SemanticBinaryOperator::Or,
+                left,
+                right,
+                ..
+            } => self
+                .derive_short_circuit_type_narrowings(left, right, NarrowingBranch::WhenFalse)
+                .into_iter()
+                .map(|type_narrowing| BranchNarrowing {
+                    when_true: Type::Unknown,
+                    ..type_narrowing
+                })
+                .collect(),
+            SemanticExpression::Binary {
+                operator:
+                    operator @ (SemanticBinaryOperator::EqualEqual | SemanticBinaryOperator::NotEqual),
+                left,
+                right,
+                ..
+            } => self
+                .derive_nil_test_type_narrowing(*operator, left, right)
+                .into_iter()
+                .collect(),
+            SemanticExpression::Matches { id, value, .. } => self
+                .derive_matches_type_narrowing(*id, value)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn derive_short_circuit_type_narrowings(
+        &mut self,
+        left: &SemanticExpression,
+        right: &SemanticExpression,
+        right_evaluation_branch: NarrowingBranch,
+    ) -> Vec<BranchNarrowing> {
+        let mut type_narrowings = self.derive_condition_type_narrowings(left);
+        let restores = self.apply_branch_narrowings(&type_narrowings, right_evaluation_branch);
+        type_narrowings.extend(self.derive_condition_type_narrowings(right));
+        self.restore_variable_types(restores);
+        type_narrowings
+    }
+
+    fn derive_nil_test_type_narrowing(
+        &self,
+        operator: SemanticBinaryOperator,
+        left: &SemanticExpression,
+        right: &SemanticExpression,
+    ) -> Option<BranchNarrowing> {
+        let (value, is_nil_test) = if matches!(right, SemanticExpression::NilLiteral { .. }) {
+            (left, true)
+        } else {
+            (right, matches!(left, SemanticExpression::NilLiteral { .. }))
+        };
+
+        if !is_nil_test {
+            return None;
         }
 
+        let target = Self::narrowing_target(value)?;
+        let value_type = self.lookup_narrowing_target_type(&target, value)?;
+        let non_nil_type = Self::without_type_member(&value_type, &Type::Nil);
+
+        let (when_true, when_false) = match operator {
+            SemanticBinaryOperator::EqualEqual => (Type::Nil, non_nil_type),
+            SemanticBinaryOperator::NotEqual => (non_nil_type, Type::Nil),
+            _ => return None,
+        };
+        Some(BranchNarrowing {
+            target,
+            when_true,
+            when_false,
+        })
+    }
+
+    fn derive_matches_type_narrowing(
+        &self,
+        matches_expression_id: SemanticExpressionId,
+        value: &SemanticExpression,
+    ) -> Option<BranchNarrowing> {
+        let target = Self::narrowing_target(value)?;
+        let pattern_type = self
+            .matches_pattern_type_by_expression_id
+            .get(&matches_expression_id)?
+            .clone();
+        if pattern_type == Type::Unknown {
+            return None;
+        }
+        let value_type = self.lookup_narrowing_target_type(&target, value)?;
+        if let Type::Union(variants) = &value_type
+            && variants.contains(&pattern_type)
+        {
+            return Some(BranchNarrowing {
+                target,
+                when_true: pattern_type.clone(),
+                when_false: Self::without_type_member(&value_type, &pattern_type),
+            });
+        }
+        if value_type == pattern_type {
+            return Some(BranchNarrowing {
+                target,
+                when_true: pattern_type,
+                when_false: Type::Unknown,
+            });
+        }
         None
     }
 
@@ -168,6 +328,9 @@ impl TypeChecker<'_> {
         let mut true_count = 0usize;
         let mut false_count = 0usize;
         for arm in arms {
+            if arm.guard.is_some() || matches!(arm.pattern, SemanticMatchPattern::Literal { .. }) {
+                return false;
+            }
             match &arm.value {
                 SemanticExpression::BooleanLiteral { value: true, .. } => true_count += 1,
                 SemanticExpression::BooleanLiteral { value: false, .. } => false_count += 1,
@@ -177,6 +340,43 @@ impl TypeChecker<'_> {
         true_count == 1 && false_count >= 1
     }
 
+    // A deferred expression runs when the function returns, after any later
+    // assignment, so mutable bindings are checked at their declared types. The
+    // local names it reads are recorded so nested bindings cannot shadow them
+    // at a return that runs the deferred expression.
+    pub(super) fn check_deferred_expression(&mut self, value: &SemanticExpression) -> Type {
+        let mut saved_narrowings = Vec::new();
+        for (scope_index, scope) in self.scopes.iter_mut().enumerate() {
+            for (name, info) in scope.iter_mut().filter(|(_, info)| info.mutable) {
+                saved_narrowings.push((
+                    scope_index,
+                    name.clone(),
+                    std::mem::replace(&mut info.value_type, info.declared_type.clone()),
+                    std::mem::take(&mut info.narrowed_type_by_field_path),
+                ));
+            }
+        }
+        let value_type = self.check_expression(value);
+        for (scope_index, name, value_type, narrowed_type_by_field_path) in saved_narrowings {
+            if let Some(info) = self.scopes[scope_index].get_mut(&name) {
+                info.value_type = value_type;
+                info.narrowed_type_by_field_path = narrowed_type_by_field_path;
+            }
+        }
+
+        let mut collector = LocalNameCollector {
+            names: HashSet::new(),
+        };
+        collector.visit_expression(value);
+        let local_names = collector
+            .names
+            .into_iter()
+            .filter(|name| self.lookup_variable_type(name).is_some())
+            .collect::<Vec<_>>();
+        self.deferred_local_names.extend(local_names);
+        value_type
+    }
+
     pub(super) fn lookup_variable_type(&self, name: &str) -> Option<Type> {
         for scope in self.scopes.iter().rev() {
             if let Some(info) = scope.get(name) {
@@ -197,7 +397,7 @@ impl TypeChecker<'_> {
                 if filtered.is_empty() {
                     Type::Unknown
                 } else {
-                    Self::normalize_union(filtered)
+                    normalize_union(filtered)
                 }
             }
             _ => {
@@ -210,3 +410,21 @@ impl TypeChecker<'_> {
         }
     }
 }
+
+struct LocalNameCollector {
+    names: HashSet<String>,
+}
+
+impl SemanticVisitor for LocalNameCollector {
+    fn visit_expression(&mut self, expression: &SemanticExpression) {
+        if let SemanticExpression::NameReference {
+            name,
+            kind: SemanticNameReferenceKind::UserDefined,
+            ..
+        } = expression
+        {
+            self.names.insert(name.clone());
+        }
+        visit_expression_children(self, expression);
+    }
+}
@@ -0,0 +1,188 @@
+use compiler__interned_names::{PackagePath, SymbolName};
+
+use crate::{TypeAnnotatedDeclarationSignatures, TypeAnnotatedMethodSignature};
+
+// How a struct's methods measure up against an interface's, whether or not the
+// struct declares that it implements the interface. The report is built from
+// the signatures type analysis records even for files with type errors, so it
+// also explains a declared implementation that fails to check. Struct and
+// interface names are written as they are outside their package, such as
+// `Meters` or `units::Meters`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub struct_name: String,
+    pub interface_name: String,
+    pub declares_implementation: bool,
+    pub method_problems: Vec<MethodConformanceProblem>,
+}
+
+impl ConformanceReport {
+    #[must_use]
+    pub fn conforms(&self) -> bool {
+        self.method_problems.is_empty()
+    }
+}
+
+// One interface method the struct lacks or declares differently, in interface
+// method order. `found_signature` is None when the struct has no method of
+// that name, and `differences` then is empty. Signatures are written as
+// "area(self) -> int64".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodConformanceProblem {
+    pub method_name: String,
+    pub expected_signature: String,
+    pub found_signature: Option<String>,
+    pub differences: Vec<String>,
+}
+
+pub fn why_not_conforming<'a>(
+    declaration_signatures: impl IntoIterator<Item = &'a TypeAnnotatedDeclarationSignatures> + Clone,
+    struct_name: &str,
+    interface_name: &str,
+) -> Result<ConformanceReport, String> {
+    let struct_signature = declaration_signatures
+        .clone()
+        .into_iter()
+        .flat_map(|signatures| &signatures.struct_signatures)
+        .find(|struct_signature| {
+            qualified_name(
+                &struct_signature.struct_reference.package_path,
+                &struct_signature.struct_reference.symbol_name,
+            ) == struct_name
+        })
+        .ok_or_else(|| format!("no struct named '{struct_name}' in the target"))?;
+    let interface_signature = declaration_signatures
+        .into_iter()
+        .flat_map(|signatures| &signatures.interface_signatures)
+        .find(|interface_signature| {
+            qualified_name(
+                &interface_signature.interface_reference.package_path,
+                &interface_signature.interface_reference.symbol_name,
+            ) == interface_name
+        })
+        .ok_or_else(|| format!("no interface named '{interface_name}' in the target"))?;
+
+    let method_problems = interface_signature
+        .methods
+        .iter()
+        .filter_map(|interface_method| {
+            let struct_method = struct_signature
+                .methods
+                .iter()
+                .find(|struct_method| struct_method.name == interface_method.name);
+            method_conformance_problem(interface_method, struct_method)
+        })
+        .collect();
+    Ok(ConformanceReport {
+        struct_name: struct_name.to_string(),
+        interface_name: interface_name.to_string(),
+        declares_implementation: struct_signature
+            .implemented_interfaces
+            .contains(&interface_signature.interface_reference),
+        method_problems,
+    })
+}
+
+fn method_conformance_problem(
+    interface_method: &TypeAnnotatedMethodSignature,
+    struct_method: Option<&TypeAnnotatedMethodSignature>,
+) -> Option<MethodConformanceProblem> {
+    let expected_signature = method_signature_text(interface_method);
+    let Some(struct_method) = struct_method else {
+        return Some(MethodConformanceProblem {
+            method_name: interface_method.name.clone(),
+            expected_signature,
+            found_signature: None,
+            differences: Vec::new(),
+        });
+    };
+
+    let mut differences = Vec::new();
+    if struct_method.self_mutable != interface_method.self_mutable {
+        differences.push(format!(
+            "receiver is '{}', expected '{}'",
+            receiver_text(struct_method.self_mutable),
+            receiver_text(interface_method.self_mutable)
+        ));
+    }
+    if struct_method.parameters.len() == interface_method.parameters.len() {
+        for (position, (struct_parameter, interface_parameter)) in struct_method
+            .parameters
+            .iter()
+            .zip(&interface_method.parameters)
+            .enumerate()
+        {
+            push_type_difference(
+                &mut differences,
+                &format!("parameter {} '{}'", position + 1, struct_parameter.name),
+                &struct_parameter.parameter_type,
+                &interface_parameter.parameter_type,
+            );
+        }
+    } else {
+        differences.push(format!(
+            "takes {} parameters, expected {}",
+            struct_method.parameters.len(),
+            interface_method.parameters.len()
+        ));
+    }
+    push_type_difference(
+        &mut differences,
+        "return type",
+        &struct_method.return_type,
+        &interface_method.return_type,
+    );
+    if differences.is_empty() {
+        return None;
+    }
+    Some(MethodConformanceProblem {
+        method_name: interface_method.name.clone(),
+        expected_signature,
+        found_signature: Some(method_signature_text(struct_method)),
+        differences,
+    })
+}
+
+fn push_type_difference(
+    differences: &mut Vec<String>,
+    subject: &str,
+    found_type: &str,
+    expected_type: &str,
+) {
+    if found_type != expected_type {
+        differences.push(format!(
+            "{subject} is {found_type}, expected {expected_type}"
+        ));
+    }
+}
+
+fn qualified_name(package_path: &PackagePath, symbol_name: &SymbolName) -> String {
+    if package_path.is_empty() {
+        symbol_name.to_string()
+    } else {
+        format!("{package_path}::{symbol_name}")
+    }
+}
+
+fn method_signature_text(method: &TypeAnnotatedMethodSignature) -> String {
+    let receiver = receiver_text(method.self_mutable);
+    let parameters = if method.parameters.is_empty() {
+        receiver.to_string()
+    } else {
+        let parameters = method
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let mutable = if parameter.mutable { "mut " } else { "" };
+                format!("{mutable}{}: {}", parameter.name, parameter.parameter_type)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{receiver}, {parameters}")
+    };
+    format!("{}({parameters}) -> {}", method.name, method.return_type)
+}
+
+fn receiver_text(self_mutable: bool) -> &'static str {
+    if self_mutable { "mut self" } else { "self" }
+}
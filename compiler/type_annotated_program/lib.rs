@@ -1,4 +1,12 @@
-use compiler__source::Span;
+mod conformance;
+
+use compiler__program_references::{
+    CallableReference, ConstantReference, EnumVariantReference, InterfaceReference,
+    NominalTypeReference, StructReference,
+};
+use compiler__source::{KnownAttribute, Span};
+
+pub use conformance::{ConformanceReport, MethodConformanceProblem, why_not_conforming};
 
 #[derive(Clone)]
 pub struct TypeResolvedDeclarations {
@@ -6,42 +14,65 @@ pub struct TypeResolvedDeclarations {
     pub interface_declarations: Vec<TypeAnnotatedInterfaceDeclaration>,
     pub struct_declarations: Vec<TypeAnnotatedStructDeclaration>,
     pub function_declarations: Vec<TypeAnnotatedFunctionDeclaration>,
+    pub inline_candidates: Vec<TypeAnnotatedInlineCandidate>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedCallableReference {
-    pub package_path: String,
-    pub symbol_name: String,
+// The method signatures of a file's structs and interfaces. Type analysis
+// records them even for a file with type errors, which has no resolved
+// declarations, so that tools can still explain its declarations. Types are
+// written as diagnostics write them, and a type that failed to resolve as
+// `<unknown>`.
+#[derive(Clone, Default)]
+pub struct TypeAnnotatedDeclarationSignatures {
+    pub interface_signatures: Vec<TypeAnnotatedInterfaceSignature>,
+    pub struct_signatures: Vec<TypeAnnotatedStructSignature>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedStructReference {
-    pub package_path: String,
-    pub symbol_name: String,
+#[derive(Clone)]
+pub struct TypeAnnotatedInterfaceSignature {
+    pub interface_reference: InterfaceReference,
+    pub methods: Vec<TypeAnnotatedMethodSignature>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedInterfaceReference {
-    pub package_path: String,
-    pub symbol_name: String,
+#[derive(Clone)]
+pub struct TypeAnnotatedStructSignature {
+    pub struct_reference: StructReference,
+    pub implemented_interfaces: Vec<InterfaceReference>,
+    pub methods: Vec<TypeAnnotatedMethodSignature>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedNominalTypeReference {
-    pub package_path: String,
-    pub symbol_name: String,
+#[derive(Clone)]
+pub struct TypeAnnotatedMethodSignature {
+    pub name: String,
+    pub self_mutable: bool,
+    pub parameters: Vec<TypeAnnotatedParameterSignature>,
+    pub return_type: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedConstantReference {
-    pub package_path: String,
-    pub symbol_name: String,
+#[derive(Clone)]
+pub struct TypeAnnotatedParameterSignature {
+    pub name: String,
+    pub mutable: bool,
+    pub parameter_type: String,
+}
+
+// An immutable binding or file-private constant whose initializer is side-effect
+// free and already has the declared type, so each reference can be replaced by
+// the initializer text.
+#[derive(Clone, Debug)]
+pub struct TypeAnnotatedInlineCandidate {
+    pub name: String,
+    pub name_span: Span,
+    pub declaration_span: Span,
+    pub initializer_span: Span,
+    pub initializer_requires_parentheses: bool,
+    pub reference_spans: Vec<Span>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TypeAnnotatedEnumVariantReference {
-    pub enum_name: String,
-    pub variant_name: String,
+pub struct TypeAnnotatedMethodReference {
+    pub receiver_type_reference: NominalTypeReference,
+    pub method_name: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -50,34 +81,58 @@ pub enum TypeAnnotatedCallTarget {
         function_name: String,
     },
     UserDefinedFunction {
-        callable_reference: TypeAnnotatedCallableReference,
+        callable_reference: CallableReference,
+    },
+    UserDefinedMethod {
+        method_reference: TypeAnnotatedMethodReference,
     },
 }
 
+#[derive(Clone, Debug)]
+pub struct TypeAnnotatedAttribute {
+    pub kind: KnownAttribute,
+    pub arguments: Vec<String>,
+    pub span: Span,
+}
+
 #[derive(Clone)]
 pub struct TypeAnnotatedConstantDeclaration {
     pub name: String,
-    pub constant_reference: TypeAnnotatedConstantReference,
+    pub constant_reference: ConstantReference,
     pub type_reference: TypeAnnotatedResolvedTypeArgument,
     pub initializer: TypeAnnotatedExpression,
+    pub attributes: Vec<TypeAnnotatedAttribute>,
     pub span: Span,
 }
 
 #[derive(Clone)]
 pub struct TypeAnnotatedFunctionDeclaration {
     pub name: String,
-    pub callable_reference: TypeAnnotatedCallableReference,
+    pub callable_reference: CallableReference,
     pub type_parameters: Vec<TypeAnnotatedTypeParameter>,
     pub parameters: Vec<TypeAnnotatedParameterDeclaration>,
     pub return_type_reference: TypeAnnotatedResolvedTypeArgument,
+    pub attributes: Vec<TypeAnnotatedAttribute>,
     pub span: Span,
     pub statements: Vec<TypeAnnotatedStatement>,
 }
 
+impl TypeAnnotatedFunctionDeclaration {
+    // The `@test` or `@bench` attribute that marks the function for the test
+    // runner, if it has one.
+    #[must_use]
+    pub fn test_attribute(&self) -> Option<KnownAttribute> {
+        self.attributes
+            .iter()
+            .map(|attribute| attribute.kind)
+            .find(|kind| matches!(kind, KnownAttribute::Test | KnownAttribute::Bench))
+    }
+}
+
 #[derive(Clone)]
 pub struct TypeAnnotatedTypeParameter {
     pub name: String,
-    pub constraint_interface_reference: Option<TypeAnnotatedInterfaceReference>,
+    pub constraint_interface_reference: Option<InterfaceReference>,
     pub span: Span,
 }
 
@@ -92,19 +147,21 @@ pub struct TypeAnnotatedParameterDeclaration {
 #[derive(Clone)]
 pub struct TypeAnnotatedStructDeclaration {
     pub name: String,
-    pub struct_reference: TypeAnnotatedStructReference,
+    pub struct_reference: StructReference,
     pub type_parameters: Vec<TypeAnnotatedTypeParameter>,
-    pub implemented_interfaces: Vec<TypeAnnotatedInterfaceReference>,
+    pub implemented_interfaces: Vec<InterfaceReference>,
     pub fields: Vec<TypeAnnotatedStructFieldDeclaration>,
     pub methods: Vec<TypeAnnotatedMethodDeclaration>,
+    pub attributes: Vec<TypeAnnotatedAttribute>,
     pub span: Span,
 }
 
 #[derive(Clone)]
 pub struct TypeAnnotatedInterfaceDeclaration {
     pub name: String,
-    pub interface_reference: TypeAnnotatedInterfaceReference,
+    pub interface_reference: InterfaceReference,
     pub methods: Vec<TypeAnnotatedInterfaceMethodDeclaration>,
+    pub attributes: Vec<TypeAnnotatedAttribute>,
     pub span: Span,
 }
 
@@ -158,12 +215,24 @@ pub enum TypeAnnotatedStatement {
         body_statements: Vec<TypeAnnotatedStatement>,
         span: Span,
     },
+    ForRange {
+        name: String,
+        start: Box<TypeAnnotatedExpression>,
+        end: Box<TypeAnnotatedExpression>,
+        step: Option<Box<TypeAnnotatedExpression>>,
+        body_statements: Vec<TypeAnnotatedStatement>,
+        span: Span,
+    },
     Break {
         span: Span,
     },
     Continue {
         span: Span,
     },
+    Defer {
+        value: TypeAnnotatedExpression,
+        span: Span,
+    },
     Expression {
         value: TypeAnnotatedExpression,
         span: Span,
@@ -218,24 +287,40 @@ pub enum TypeAnnotatedExpression {
     NameReference {
         name: String,
         kind: TypeAnnotatedNameReferenceKind,
-        constant_reference: Option<TypeAnnotatedConstantReference>,
-        callable_reference: Option<TypeAnnotatedCallableReference>,
+        constant_reference: Option<ConstantReference>,
+        callable_reference: Option<CallableReference>,
         type_reference: TypeAnnotatedResolvedTypeArgument,
         span: Span,
     },
     EnumVariantLiteral {
-        enum_variant_reference: TypeAnnotatedEnumVariantReference,
+        enum_variant_reference: EnumVariantReference,
+        span: Span,
+    },
+    // `variant_name(value)` and `Enum.from_string(value)`, which map between
+    // the listed variants of one enum and their names.
+    EnumVariantName {
+        value: Box<TypeAnnotatedExpression>,
+        enum_variant_references: Vec<EnumVariantReference>,
+        span: Span,
+    },
+    EnumVariantFromString {
+        value: Box<TypeAnnotatedExpression>,
+        enum_variant_references: Vec<EnumVariantReference>,
         span: Span,
     },
+    // `resolved_type_arguments` holds the type arguments inferred from the
+    // field values when a generic struct literal does not name them.
     StructLiteral {
         type_name: TypeAnnotatedTypeName,
-        struct_reference: Option<TypeAnnotatedStructReference>,
+        struct_reference: Option<StructReference>,
         fields: Vec<TypeAnnotatedStructLiteralField>,
+        resolved_type_arguments: Vec<TypeAnnotatedResolvedTypeArgument>,
         span: Span,
     },
     FieldAccess {
         target: Box<TypeAnnotatedExpression>,
         field: String,
+        type_reference: Option<TypeAnnotatedResolvedTypeArgument>,
         span: Span,
     },
     IndexAccess {
@@ -243,15 +328,19 @@ pub enum TypeAnnotatedExpression {
         index: Box<TypeAnnotatedExpression>,
         span: Span,
     },
+    // `operation_description` is set on int64 operations that can overflow or
+    // divide by zero, and names the operation when it aborts.
     Unary {
         operator: TypeAnnotatedUnaryOperator,
         expression: Box<TypeAnnotatedExpression>,
+        operation_description: Option<String>,
         span: Span,
     },
     Binary {
         operator: TypeAnnotatedBinaryOperator,
         left: Box<TypeAnnotatedExpression>,
         right: Box<TypeAnnotatedExpression>,
+        operation_description: Option<String>,
         span: Span,
     },
     Call {
@@ -262,6 +351,19 @@ pub enum TypeAnnotatedExpression {
         resolved_type_arguments: Vec<TypeAnnotatedResolvedTypeArgument>,
         span: Span,
     },
+    // `type_reference` joins the types of both branches.
+    If {
+        condition: Box<TypeAnnotatedExpression>,
+        then_branch: Box<TypeAnnotatedExpression>,
+        else_branch: Box<TypeAnnotatedExpression>,
+        type_reference: Option<TypeAnnotatedResolvedTypeArgument>,
+        span: Span,
+    },
+    Block {
+        statements: Vec<TypeAnnotatedStatement>,
+        value: Box<TypeAnnotatedExpression>,
+        span: Span,
+    },
     Match {
         target: Box<TypeAnnotatedExpression>,
         arms: Vec<TypeAnnotatedMatchArm>,
@@ -281,6 +383,7 @@ pub enum TypeAnnotatedExpression {
 #[derive(Clone)]
 pub struct TypeAnnotatedMatchArm {
     pub pattern: TypeAnnotatedMatchPattern,
+    pub guard: Option<TypeAnnotatedExpression>,
     pub value: TypeAnnotatedExpression,
     pub span: Span,
 }
@@ -296,6 +399,17 @@ pub enum TypeAnnotatedMatchPattern {
         type_name: TypeAnnotatedTypeName,
         span: Span,
     },
+    Literal {
+        value: TypeAnnotatedMatchLiteral,
+        span: Span,
+    },
+}
+
+#[derive(Clone)]
+pub enum TypeAnnotatedMatchLiteral {
+    Integer(i64),
+    String(String),
+    Boolean(bool),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -305,6 +419,11 @@ pub enum TypeAnnotatedBinaryOperator {
     Multiply,
     Divide,
     Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
     EqualEqual,
     NotEqual,
     LessThan,
@@ -336,7 +455,7 @@ pub struct TypeAnnotatedTypeName {
 #[derive(Clone)]
 pub struct TypeAnnotatedTypeNameSegment {
     pub name: String,
-    pub nominal_type_reference: Option<TypeAnnotatedNominalTypeReference>,
+    pub nominal_type_reference: Option<NominalTypeReference>,
     pub type_arguments: Vec<TypeAnnotatedTypeName>,
     pub span: Span,
 }
@@ -362,12 +481,12 @@ pub enum TypeAnnotatedResolvedTypeArgument {
         name: String,
     },
     NominalTypeApplication {
-        base_nominal_type_reference: Option<TypeAnnotatedNominalTypeReference>,
+        base_nominal_type_reference: Option<NominalTypeReference>,
         base_name: String,
         arguments: Vec<TypeAnnotatedResolvedTypeArgument>,
     },
     NominalType {
-        nominal_type_reference: Option<TypeAnnotatedNominalTypeReference>,
+        nominal_type_reference: Option<NominalTypeReference>,
         name: String,
     },
 }
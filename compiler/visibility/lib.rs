@@ -1,10 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 use compiler__diagnostics::PhaseDiagnostic;
 use compiler__exports::ExportsByPackage;
 use compiler__source::Span;
-use compiler__symbols::{PackageDiagnostic, PackageFile, SymbolsByPackage};
+use compiler__symbols::{PackageDiagnostic, PackageFile, PackageSymbols, SymbolsByPackage};
 use compiler__syntax::{SyntaxDeclaration, SyntaxImportDeclaration, SyntaxImportMember};
 
 pub struct ResolvedImportBinding {
@@ -71,7 +71,7 @@ fn resolve_import_declaration(
             Err(message) => {
                 diagnostics.push(PackageDiagnostic {
                     path: file.path.to_path_buf(),
-                    diagnostic: PhaseDiagnostic::new(message, import_declaration.span.clone()),
+                    diagnostic: PhaseDiagnostic::new(message, import_declaration.span),
                 });
                 return None;
             }
@@ -81,8 +81,15 @@ fn resolve_import_declaration(
         diagnostics.push(PackageDiagnostic {
             path: file.path.to_path_buf(),
             diagnostic: PhaseDiagnostic::new(
-                format!("unknown package '{}'", import_declaration.package_path),
-                import_declaration.span.clone(),
+                format!(
+                    "unknown package '{}'{}",
+                    import_declaration.package_path,
+                    closest_package_paths_note(
+                        &import_declaration.package_path,
+                        symbols_by_package
+                    )
+                ),
+                import_declaration.span,
             ),
         });
         return None;
@@ -97,10 +104,15 @@ fn resolve_import_declaration(
                 path: file.path.to_path_buf(),
                 diagnostic: PhaseDiagnostic::new(
                     format!(
-                        "imported symbol '{name}' is not declared in package '{}'",
-                        import_declaration.package_path
+                        "imported symbol '{name}' is not declared in package '{}'{}",
+                        import_declaration.package_path,
+                        importable_symbols_note(
+                            target_package_symbols,
+                            exported_symbols,
+                            same_package
+                        )
                     ),
-                    member.span.clone(),
+                    member.span,
                 ),
             });
             continue;
@@ -113,7 +125,7 @@ fn resolve_import_declaration(
                         "imported symbol '{name}' in package '{}' must be declared visible",
                         import_declaration.package_path
                     ),
-                    member.span.clone(),
+                    member.span,
                 ),
             });
             continue;
@@ -126,7 +138,7 @@ fn resolve_import_declaration(
                         "imported symbol '{name}' in package '{}' is not exported",
                         import_declaration.package_path
                     ),
-                    member.span.clone(),
+                    member.span,
                 ),
             });
             continue;
@@ -135,14 +147,14 @@ fn resolve_import_declaration(
         bindings.push(ResolvedImportBinding {
             imported_name: name.clone(),
             local_name: import_local_name(member).to_string(),
-            span: member.alias_span.clone().unwrap_or(member.span.clone()),
+            span: member.alias_span.unwrap_or(member.span),
         });
     }
 
     Some(ResolvedImport {
         source_package_path: file.package_path.to_string(),
         source_path: file.path.to_path_buf(),
-        import_span: import_declaration.span.clone(),
+        import_span: import_declaration.span,
         target_package_path,
         bindings,
     })
@@ -167,11 +179,108 @@ fn resolve_import_package_path(
     Err("import path must start with import origin 'workspace', 'std/', or 'external/'".to_string())
 }
 
+// Suggestions are bounded so that a mistyped import into a large workspace
+// costs no more than a few short string comparisons per package.
+const MAXIMUM_PACKAGE_SUGGESTIONS: usize = 3;
+const MAXIMUM_LISTED_SYMBOLS: usize = 5;
+
+fn closest_package_paths_note(
+    import_package_path: &str,
+    symbols_by_package: &SymbolsByPackage,
+) -> String {
+    let maximum_distance = (import_package_path.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, String)> = symbols_by_package
+        .keys()
+        .map(|package_path| import_form_package_path(package_path))
+        .filter_map(|candidate| {
+            let distance =
+                bounded_edit_distance(import_package_path, &candidate, maximum_distance)?;
+            Some((distance, candidate))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return String::new();
+    }
+    candidates.sort();
+    let suggestions: Vec<String> = candidates
+        .into_iter()
+        .take(MAXIMUM_PACKAGE_SUGGESTIONS)
+        .map(|(_, candidate)| format!("'{candidate}'"))
+        .collect();
+    format!("; did you mean {}?", suggestions.join(" or "))
+}
+
+fn import_form_package_path(package_path: &str) -> String {
+    if package_path.is_empty() {
+        "workspace".to_string()
+    } else if package_path.starts_with("std/") || package_path.starts_with("external/") {
+        package_path.to_string()
+    } else {
+        format!("workspace/{package_path}")
+    }
+}
+
+fn importable_symbols_note(
+    target_package_symbols: &PackageSymbols,
+    exported_symbols: Option<&BTreeSet<String>>,
+    same_package: bool,
+) -> String {
+    let importable_symbols: Vec<&String> = target_package_symbols
+        .package_visible
+        .iter()
+        .filter(|name| same_package || is_exported(name, exported_symbols))
+        .collect();
+    if importable_symbols.is_empty() {
+        return "; the package has no importable symbols".to_string();
+    }
+    let mut listed_symbols: Vec<String> = importable_symbols
+        .iter()
+        .take(MAXIMUM_LISTED_SYMBOLS)
+        .map(|name| (*name).clone())
+        .collect();
+    if importable_symbols.len() > MAXIMUM_LISTED_SYMBOLS {
+        listed_symbols.push(format!(
+            "and {} more",
+            importable_symbols.len() - MAXIMUM_LISTED_SYMBOLS
+        ));
+    }
+    format!("; importable symbols: {}", listed_symbols.join(", "))
+}
+
+// Levenshtein distance over characters, or None once it must exceed
+// `maximum_distance`.
+fn bounded_edit_distance(left: &str, right: &str, maximum_distance: usize) -> Option<usize> {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    if left.len().abs_diff(right.len()) > maximum_distance {
+        return None;
+    }
+    let mut previous_row: Vec<usize> = (0..=right.len()).collect();
+    for (left_index, left_character) in left.iter().enumerate() {
+        let mut current_row = vec![left_index + 1; right.len() + 1];
+        for (right_index, right_character) in right.iter().enumerate() {
+            let substitution_cost = usize::from(left_character != right_character);
+            current_row[right_index + 1] = (previous_row[right_index] + substitution_cost)
+                .min(previous_row[right_index + 1] + 1)
+                .min(current_row[right_index] + 1);
+        }
+        if current_row
+            .iter()
+            .all(|distance| *distance > maximum_distance)
+        {
+            return None;
+        }
+        previous_row = current_row;
+    }
+    let distance = previous_row[right.len()];
+    (distance <= maximum_distance).then_some(distance)
+}
+
 fn import_local_name(member: &SyntaxImportMember) -> &str {
     member.alias.as_deref().unwrap_or(&member.name)
 }
 
-fn is_exported(name: &str, exported_symbols: Option<&std::collections::BTreeSet<String>>) -> bool {
+fn is_exported(name: &str, exported_symbols: Option<&BTreeSet<String>>) -> bool {
     exported_symbols.is_some_and(|symbols| symbols.contains(name))
 }
 
@@ -187,7 +296,7 @@ pub fn resolved_bindings_by_file(
             .extend(import.bindings.iter().map(|binding| ResolvedImportBinding {
                 imported_name: binding.imported_name.clone(),
                 local_name: binding.local_name.clone(),
-                span: binding.span.clone(),
+                span: binding.span,
             }));
     }
     bindings_by_file
@@ -0,0 +1,241 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use compiler__file_system::FileSystem;
+
+use crate::types::{DependencyRule, DiscoveryError, ForbiddenBuiltinRule, WorkspaceSettings};
+
+const WORKSPACE_MARKER_FILENAME: &str = "COPPICE_WORKSPACE";
+
+pub(crate) struct WorkspaceConfiguration {
+    pub(crate) dependency_rules: Vec<DependencyRule>,
+    pub(crate) settings: WorkspaceSettings,
+}
+
+// The marker holds one entry per line: `allow` dependency rules, `include`
+// and `exclude` package patterns, `ignore` path patterns, `forbid` builtin
+// rules, and `<setting> = <value>` assignments. An empty or missing marker leaves every
+// setting at its default.
+pub(crate) fn read_workspace_configuration(
+    file_system: &dyn FileSystem,
+    root_directory: &Path,
+) -> Result<WorkspaceConfiguration, Vec<DiscoveryError>> {
+    let marker_path = root_directory.join(WORKSPACE_MARKER_FILENAME);
+    let contents = match file_system.read_to_string(&marker_path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            return Ok(WorkspaceConfiguration {
+                dependency_rules: Vec::new(),
+                settings: WorkspaceSettings::default(),
+            });
+        }
+        Err(error) => {
+            return Err(vec![marker_error(format!(
+                "failed to read workspace marker: {error}"
+            ))]);
+        }
+    };
+
+    let mut rules = Vec::new();
+    let mut settings = WorkspaceSettings::default();
+    let mut assigned_setting_names = Vec::new();
+    let mut errors = Vec::new();
+    for (line_index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        let line_number = line_index + 1;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("allow ") {
+            match parse_dependency_rule(line) {
+                Some(rule) => rules.push(rule),
+                None => errors.push(marker_error(format!(
+                    "invalid dependency rule on line {line_number}: expected 'allow <package pattern> -> <package pattern>'"
+                ))),
+            }
+        } else if let Some(pattern) = line.strip_prefix("include ") {
+            match parse_package_pattern(pattern, line_number) {
+                Ok(pattern) => settings.package_include_patterns.push(pattern),
+                Err(error) => errors.push(error),
+            }
+        } else if let Some(pattern) = line.strip_prefix("exclude ") {
+            match parse_package_pattern(pattern, line_number) {
+                Ok(pattern) => settings.package_exclude_patterns.push(pattern),
+                Err(error) => errors.push(error),
+            }
+        } else if let Some(pattern) = line.strip_prefix("ignore ") {
+            let pattern = pattern.trim();
+            if is_valid_ignore_pattern(pattern) {
+                settings.ignore_patterns.push(pattern.to_string());
+            } else {
+                errors.push(marker_error(format!(
+                    "invalid ignore pattern '{pattern}' on line {line_number}: expected a workspace-relative path whose segments may use '*' and '**'"
+                )));
+            }
+        } else if let Some(rule) = line.strip_prefix("forbid ") {
+            match parse_forbidden_builtin_rule(rule, line_number) {
+                Some(rule) => settings.forbidden_builtin_rules.push(rule),
+                None => errors.push(marker_error(format!(
+                    "invalid builtin rule on line {line_number}: expected 'forbid <builtin> in <package pattern>'"
+                ))),
+            }
+        } else if let Some((name, value)) = line.split_once('=') {
+            let name = name.trim();
+            if assigned_setting_names.contains(&name) {
+                errors.push(marker_error(format!(
+                    "duplicate workspace setting '{name}' on line {line_number}"
+                )));
+                continue;
+            }
+            assigned_setting_names.push(name);
+            if let Err(error) = apply_setting(&mut settings, name, value.trim(), line_number) {
+                errors.push(error);
+            }
+        } else {
+            errors.push(marker_error(format!(
+                "invalid workspace configuration on line {line_number}: expected a dependency rule, an 'include' or 'exclude' package pattern, an 'ignore' path pattern, a 'forbid' builtin rule, or '<setting> = <value>'"
+            )));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(WorkspaceConfiguration {
+            dependency_rules: rules,
+            settings,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+fn apply_setting(
+    settings: &mut WorkspaceSettings,
+    name: &str,
+    value: &str,
+    line_number: usize,
+) -> Result<(), DiscoveryError> {
+    match name {
+        "build.output_directory" => {
+            settings.build_output_directory =
+                parse_workspace_relative_path_setting(name, value, line_number)?;
+        }
+        "lint.baseline" => {
+            settings.diagnostic_baseline = Some(parse_workspace_relative_path_setting(
+                name,
+                value,
+                line_number,
+            )?);
+        }
+        "lint.strict" => {
+            settings.strict = parse_boolean_setting(name, value, line_number)?;
+        }
+        _ => {
+            return Err(marker_error(format!(
+                "unknown workspace setting '{name}' on line {line_number}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn parse_boolean_setting(
+    name: &str,
+    value: &str,
+    line_number: usize,
+) -> Result<bool, DiscoveryError> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(marker_error(format!(
+            "invalid value '{value}' for workspace setting '{name}' on line {line_number}: expected 'true' or 'false'"
+        ))),
+    }
+}
+
+fn parse_workspace_relative_path_setting(
+    name: &str,
+    value: &str,
+    line_number: usize,
+) -> Result<PathBuf, DiscoveryError> {
+    let path = PathBuf::from(value);
+    let is_inside_workspace = !value.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if is_inside_workspace {
+        Ok(path)
+    } else {
+        Err(marker_error(format!(
+            "invalid value '{value}' for workspace setting '{name}' on line {line_number}: expected a relative path inside the workspace"
+        )))
+    }
+}
+
+fn parse_package_pattern(pattern: &str, line_number: usize) -> Result<String, DiscoveryError> {
+    let pattern = pattern.trim();
+    if is_valid_package_pattern(pattern) {
+        Ok(pattern.to_string())
+    } else {
+        Err(marker_error(format!(
+            "invalid package pattern '{pattern}' on line {line_number}: expected 'workspace' or a package path beneath it, optionally ending in '/*'"
+        )))
+    }
+}
+
+fn is_valid_ignore_pattern(pattern: &str) -> bool {
+    !pattern.is_empty()
+        && pattern
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
+fn parse_dependency_rule(line: &str) -> Option<DependencyRule> {
+    let rule = line.strip_prefix("allow ")?;
+    let (source_package_pattern, target_package_pattern) = rule.split_once("->")?;
+    let source_package_pattern = source_package_pattern.trim();
+    let target_package_pattern = target_package_pattern.trim();
+    if !is_valid_package_pattern(source_package_pattern)
+        || !is_valid_package_pattern(target_package_pattern)
+    {
+        return None;
+    }
+    Some(DependencyRule {
+        source_package_pattern: source_package_pattern.to_string(),
+        target_package_pattern: target_package_pattern.to_string(),
+    })
+}
+
+fn parse_forbidden_builtin_rule(rule: &str, line_number: usize) -> Option<ForbiddenBuiltinRule> {
+    let (builtin_name, package_pattern) = rule.split_once(" in ")?;
+    let builtin_name = builtin_name.trim();
+    let package_pattern = package_pattern.trim();
+    let is_valid_builtin_name = !builtin_name.is_empty()
+        && builtin_name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_');
+    if !is_valid_builtin_name || !is_valid_package_pattern(package_pattern) {
+        return None;
+    }
+    Some(ForbiddenBuiltinRule {
+        builtin_name: builtin_name.to_string(),
+        package_pattern: package_pattern.to_string(),
+        line_number,
+    })
+}
+
+fn is_valid_package_pattern(pattern: &str) -> bool {
+    let package_path = pattern.strip_suffix("/*").unwrap_or(pattern);
+    if package_path != "workspace" && !package_path.starts_with("workspace/") {
+        return false;
+    }
+    package_path.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_')
+    })
+}
+
+fn marker_error(message: String) -> DiscoveryError {
+    DiscoveryError::new(message, Some(PathBuf::from(WORKSPACE_MARKER_FILENAME)))
+}
@@ -1,26 +1,41 @@
 use std::collections::{BTreeMap, BTreeSet};
-use std::fs;
 use std::io;
-use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
+use compiler__file_system::{FileSystem, FileSystemEntryKind};
 use compiler__packages::PackageId;
 use compiler__source::{FileId, FileRole, SourceFile, compare_paths};
 
-use crate::types::{DiscoveredPackage, DiscoveryError, Workspace};
+use crate::configuration::read_workspace_configuration;
+use crate::types::{
+    DiscoveredPackage, DiscoveryError, PackageMetadata, Workspace, WorkspaceSettings,
+};
 
-pub fn discover_workspace(root_directory: &Path) -> Result<Workspace, Vec<DiscoveryError>> {
+pub fn discover_workspace(
+    file_system: &dyn FileSystem,
+    root_directory: &Path,
+) -> Result<Workspace, Vec<DiscoveryError>> {
+    let configuration = read_workspace_configuration(file_system, root_directory)?;
     let mut package_roots = BTreeSet::new();
     let mut source_paths = Vec::new();
     let mut errors = Vec::new();
 
-    if let Err(error) = collect_workspace_entries(
-        root_directory,
-        Path::new(""),
-        &mut package_roots,
-        &mut source_paths,
-        &mut errors,
-    ) {
+    let walk_result = file_system
+        .canonicalize(root_directory)
+        .and_then(|canonical_root| {
+            let mut walk = WorkspaceWalk {
+                file_system,
+                workspace_root: root_directory,
+                visited_directories: BTreeSet::from([canonical_root.clone()]),
+                canonical_root,
+                settings: &configuration.settings,
+                package_roots: &mut package_roots,
+                source_paths: &mut source_paths,
+                errors: &mut errors,
+            };
+            walk.collect_entries(Path::new(""))
+        });
+    if let Err(error) = walk_result {
         errors.push(DiscoveryError::new(
             format!("failed to walk workspace: {error}"),
             None,
@@ -31,6 +46,18 @@ pub fn discover_workspace(root_directory: &Path) -> Result<Workspace, Vec<Discov
         return Err(errors);
     }
 
+    // Files of an excluded package are left out with it rather than being
+    // assigned to an enclosing package.
+    let included_package_roots = package_roots
+        .iter()
+        .filter(|package_root| {
+            configuration
+                .settings
+                .includes_package(&package_path_from_root(package_root))
+        })
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
     source_paths.sort_by(|left, right| compare_paths(left, right));
 
     let mut source_paths_by_package_root: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
@@ -40,7 +67,9 @@ pub fn discover_workspace(root_directory: &Path) -> Result<Workspace, Vec<Discov
             continue;
         }
         let source_directory = source_path.parent().unwrap_or(Path::new(""));
-        if let Some(package_root) = nearest_package_root(source_directory, &package_roots) {
+        if let Some(package_root) = nearest_package_root(source_directory, &package_roots)
+            && included_package_roots.contains(&package_root)
+        {
             source_paths_by_package_root
                 .entry(package_root)
                 .or_default()
@@ -54,7 +83,7 @@ pub fn discover_workspace(root_directory: &Path) -> Result<Workspace, Vec<Discov
 
     let mut file_id_counter = 0usize;
     let mut packages = Vec::new();
-    for (package_index, package_root) in package_roots.iter().enumerate() {
+    for (package_index, package_root) in included_package_roots.iter().enumerate() {
         let mut source_files = Vec::new();
         if let Some(paths) = source_paths_by_package_root.get(package_root) {
             for source_path in paths {
@@ -76,82 +105,89 @@ pub fn discover_workspace(root_directory: &Path) -> Result<Workspace, Vec<Discov
             root_directory: package_root.clone(),
             manifest_path,
             source_files,
+            metadata: PackageMetadata::default(),
         });
     }
 
-    Workspace::new(root_directory.to_path_buf(), packages).map_err(|error| vec![error])
+    Workspace::new(
+        root_directory.to_path_buf(),
+        packages,
+        configuration.dependency_rules,
+        configuration.settings,
+    )
+    .map_err(|error| vec![error])
 }
 
-fn collect_workspace_entries(
-    workspace_root: &Path,
-    relative_directory: &Path,
-    package_roots: &mut BTreeSet<PathBuf>,
-    source_paths: &mut Vec<PathBuf>,
-    errors: &mut Vec<DiscoveryError>,
-) -> io::Result<()> {
-    let absolute_directory = workspace_root.join(relative_directory);
-    let mut entries = Vec::new();
-    for entry in fs::read_dir(absolute_directory)? {
-        entries.push(entry?);
-    }
-    entries.sort_by(|left, right| compare_paths(&left.path(), &right.path()));
-
-    for entry in entries {
-        let file_type = entry.file_type()?;
-        let mut is_file = file_type.is_file();
-        let mut is_directory = file_type.is_dir();
-        if file_type.is_symlink() {
-            let metadata = match fs::metadata(entry.path()) {
-                Ok(metadata) => metadata,
-                Err(error) if error.kind() == ErrorKind::NotFound => {
-                    // Ignore dangling symlinks while walking the workspace tree.
+struct WorkspaceWalk<'a> {
+    file_system: &'a dyn FileSystem,
+    workspace_root: &'a Path,
+    canonical_root: PathBuf,
+    visited_directories: BTreeSet<PathBuf>,
+    settings: &'a WorkspaceSettings,
+    package_roots: &'a mut BTreeSet<PathBuf>,
+    source_paths: &'a mut Vec<PathBuf>,
+    errors: &'a mut Vec<DiscoveryError>,
+}
+
+impl WorkspaceWalk<'_> {
+    // Symlinked directories are followed, with each canonical directory
+    // visited at most once so that link cycles terminate. A link whose target
+    // lies inside the workspace is skipped instead, because the target is
+    // discovered under its own path and following the link would discover
+    // its packages and files a second time.
+    fn collect_entries(&mut self, relative_directory: &Path) -> io::Result<()> {
+        let absolute_directory = self.workspace_root.join(relative_directory);
+        let mut entries = self.file_system.read_directory(&absolute_directory)?;
+        entries.sort_by(|left, right| compare_paths(&left.path, &right.path));
+
+        for entry in entries {
+            let Some(file_name) = entry.path.file_name() else {
+                continue;
+            };
+            let child_relative_path = relative_directory.join(file_name);
+            if self.settings.ignores_path(&child_relative_path) {
+                continue;
+            }
+
+            if entry.kind == FileSystemEntryKind::Directory {
+                let canonical_directory = self.file_system.canonicalize(&entry.path)?;
+                if self.is_link_within_workspace(&canonical_directory, &child_relative_path)
+                    || !self.visited_directories.insert(canonical_directory)
+                {
                     continue;
                 }
-                Err(error) => {
-                    return Err(error);
-                }
-            };
-            if metadata.file_type().is_dir() {
-                // Do not recurse into symlinked directories to keep workspace
-                // discovery deterministic and avoid traversing generated trees.
+                self.collect_entries(&child_relative_path)?;
                 continue;
             }
-            is_file = metadata.file_type().is_file();
-            is_directory = false;
-        }
 
-        let file_name = entry.file_name();
-        let child_relative_path = relative_directory.join(file_name);
-
-        if is_directory {
-            collect_workspace_entries(
-                workspace_root,
-                &child_relative_path,
-                package_roots,
-                source_paths,
-                errors,
-            )?;
-            continue;
-        }
+            if entry.kind != FileSystemEntryKind::File {
+                continue;
+            }
 
-        if !is_file {
-            continue;
+            let Some(role) = FileRole::from_path(&child_relative_path) else {
+                continue;
+            };
+            let canonical_file = self.file_system.canonicalize(&entry.path)?;
+            if self.is_link_within_workspace(&canonical_file, &child_relative_path) {
+                continue;
+            }
+            self.source_paths.push(child_relative_path.clone());
+            if role == FileRole::PackageManifest
+                && !self.package_roots.insert(relative_directory.to_path_buf())
+            {
+                self.errors.push(DiscoveryError::new(
+                    "duplicate PACKAGE.copp in package root",
+                    Some(child_relative_path),
+                ));
+            }
         }
+        Ok(())
+    }
 
-        let Some(role) = FileRole::from_path(&child_relative_path) else {
-            continue;
-        };
-        source_paths.push(child_relative_path.clone());
-        if role == FileRole::PackageManifest
-            && !package_roots.insert(relative_directory.to_path_buf())
-        {
-            errors.push(DiscoveryError::new(
-                "duplicate PACKAGE.copp in package root",
-                Some(child_relative_path),
-            ));
-        }
+    fn is_link_within_workspace(&self, canonical_path: &Path, relative_path: &Path) -> bool {
+        canonical_path.starts_with(&self.canonical_root)
+            && *canonical_path != self.canonical_root.join(relative_path)
     }
-    Ok(())
 }
 
 fn nearest_package_root(directory: &Path, package_roots: &BTreeSet<PathBuf>) -> Option<PathBuf> {
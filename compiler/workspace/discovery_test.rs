@@ -2,8 +2,9 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use compiler__source::FileRole;
-use compiler__workspace::discover_workspace;
+use compiler__file_system::{InMemoryFileSystem, RealFileSystem};
+use compiler__source::{FileRole, LanguageVersion};
+use compiler__workspace::{DependencyRule, PackageMetadata, WorkspaceSettings, discover_workspace};
 
 #[test]
 fn assigns_files_to_nearest_manifest_package() {
@@ -15,7 +16,8 @@ fn assigns_files_to_nearest_manifest_package() {
         "platform/auth/oauth/google.copp",
     ]);
 
-    let workspace = discover_workspace(workspace.path()).expect("discovery should succeed");
+    let workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
 
     let auth_package = workspace
         .package_by_path("platform/auth")
@@ -52,7 +54,7 @@ fn ignores_orphan_source_file() {
     let workspace = TestWorkspace::new(&["orphans/lost.copp"]);
 
     let discovered_workspace =
-        discover_workspace(workspace.path()).expect("discovery should succeed");
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
     assert!(discovered_workspace.packages().is_empty());
 }
 
@@ -65,7 +67,8 @@ fn classifies_file_roles_by_suffix() {
         "pkg/tool.test.copp",
     ]);
 
-    let model = discover_workspace(workspace.path()).expect("discovery should succeed");
+    let model =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
     let package = model
         .packages()
         .first()
@@ -103,8 +106,10 @@ fn discovery_order_is_deterministic() {
         "alpha/b.copp",
     ]);
 
-    let first = discover_workspace(workspace.path()).expect("first discovery should succeed");
-    let second = discover_workspace(workspace.path()).expect("second discovery should succeed");
+    let first = discover_workspace(&RealFileSystem, workspace.path())
+        .expect("first discovery should succeed");
+    let second = discover_workspace(&RealFileSystem, workspace.path())
+        .expect("second discovery should succeed");
 
     let first_paths: Vec<String> = first
         .packages()
@@ -133,6 +138,301 @@ fn discovery_order_is_deterministic() {
     assert_eq!(first_source_paths, second_source_paths);
 }
 
+#[test]
+fn reads_dependency_rules_from_workspace_marker() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp"]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "# layering\nallow workspace/app/* -> workspace/lib/*\n\nallow workspace -> workspace/app\n",
+    );
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    assert_eq!(
+        discovered_workspace.dependency_rules(),
+        &[
+            DependencyRule {
+                source_package_pattern: "workspace/app/*".to_string(),
+                target_package_pattern: "workspace/lib/*".to_string(),
+            },
+            DependencyRule {
+                source_package_pattern: "workspace".to_string(),
+                target_package_pattern: "workspace/app".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn rejects_malformed_dependency_rule() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp"]);
+    workspace.write_file("COPPICE_WORKSPACE", "allow app/* -> lib/*\n");
+
+    let errors =
+        discover_workspace(&RealFileSystem, workspace.path()).expect_err("discovery should fail");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].path.as_deref(),
+        Some(Path::new("COPPICE_WORKSPACE"))
+    );
+}
+
+#[test]
+fn defaults_settings_when_workspace_marker_is_empty() {
+    let workspace = TestWorkspace::new(&["COPPICE_WORKSPACE", "PACKAGE.copp"]);
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    assert_eq!(
+        discovered_workspace.settings(),
+        &WorkspaceSettings::default()
+    );
+    assert_eq!(
+        discovered_workspace.settings().build_output_directory,
+        Path::new(".coppice/build")
+    );
+}
+
+#[test]
+fn reads_settings_from_workspace_marker() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp"]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "build.output_directory = out/bin\nlint.strict = true\nlint.baseline = lint/baseline.json\n",
+    );
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    let settings = discovered_workspace.settings();
+    assert_eq!(settings.build_output_directory, Path::new("out/bin"));
+    assert!(settings.strict);
+    assert_eq!(
+        settings.diagnostic_baseline.as_deref(),
+        Some(Path::new("lint/baseline.json"))
+    );
+}
+
+#[test]
+fn leaves_out_packages_excluded_by_workspace_settings() {
+    let workspace = TestWorkspace::new(&[
+        "PACKAGE.copp",
+        "main.bin.copp",
+        "app/PACKAGE.copp",
+        "app/lib.copp",
+        "generated/PACKAGE.copp",
+        "generated/lib.copp",
+        "generated/nested/PACKAGE.copp",
+        "generated/nested/lib.copp",
+        "tools/PACKAGE.copp",
+    ]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "include workspace\ninclude workspace/app\ninclude workspace/generated/*\nexclude workspace/generated\n",
+    );
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    let package_paths: Vec<&str> = discovered_workspace
+        .packages()
+        .iter()
+        .map(|package| package.package_path.as_str())
+        .collect();
+    assert_eq!(package_paths, vec!["", "app", "generated/nested"]);
+    let root_file_paths: Vec<String> = discovered_workspace
+        .package_by_path("")
+        .expect("root package should exist")
+        .source_files
+        .iter()
+        .map(|file| compiler__source::path_to_key(&file.workspace_relative_path))
+        .collect();
+    assert_eq!(root_file_paths, vec!["main.bin.copp".to_string()]);
+}
+
+#[test]
+fn skips_ignored_and_hidden_paths() {
+    let workspace = TestWorkspace::new(&[
+        "PACKAGE.copp",
+        "lib.copp",
+        "draft.scratch.copp",
+        ".cache/PACKAGE.copp",
+        "out/PACKAGE.copp",
+        "vendor/PACKAGE.copp",
+        "app/PACKAGE.copp",
+        "app/lib.copp",
+        "app/generated/PACKAGE.copp",
+        "app/generated/lib.copp",
+    ]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "build.output_directory = out\nignore vendor\nignore **/generated\nignore *.scratch.copp\n",
+    );
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    let package_paths: Vec<&str> = discovered_workspace
+        .packages()
+        .iter()
+        .map(|package| package.package_path.as_str())
+        .collect();
+    assert_eq!(package_paths, vec!["", "app"]);
+    let source_paths: Vec<String> = discovered_workspace
+        .packages()
+        .iter()
+        .flat_map(|package| package.source_files.iter())
+        .map(|file| compiler__source::path_to_key(&file.workspace_relative_path))
+        .collect();
+    assert_eq!(
+        source_paths,
+        vec!["lib.copp".to_string(), "app/lib.copp".to_string()]
+    );
+}
+
+#[test]
+fn matches_forbidden_builtin_rules_to_packages() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp", "reports/PACKAGE.copp"]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "forbid print in workspace/reports/*\nforbid now in workspace\nforbid read_line\n",
+    );
+
+    let errors =
+        discover_workspace(&RealFileSystem, workspace.path()).expect_err("discovery should fail");
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["invalid builtin rule on line 3: expected 'forbid <builtin> in <package pattern>'"]
+    );
+
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "forbid print in workspace/reports/*\nforbid now in workspace\n",
+    );
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    let forbidden_builtin_names = |package_path: &str| {
+        discovered_workspace
+            .settings()
+            .forbidden_builtin_rules_for_package(package_path)
+            .into_iter()
+            .map(|rule| (rule.builtin_name.clone(), rule.line_number))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        forbidden_builtin_names("reports"),
+        vec![("print".to_string(), 1)]
+    );
+    assert_eq!(forbidden_builtin_names(""), vec![("now".to_string(), 2)]);
+}
+
+#[test]
+fn rejects_invalid_workspace_settings() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp"]);
+    workspace.write_file(
+        "COPPICE_WORKSPACE",
+        "lint.strict = yes\nlint.strict = true\nbuild.output_directory = ../out\nformat.indent = 4\nexclude generated\nbuild\nignore ../vendor\n",
+    );
+
+    let errors =
+        discover_workspace(&RealFileSystem, workspace.path()).expect_err("discovery should fail");
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "invalid value 'yes' for workspace setting 'lint.strict' on line 1: expected 'true' or 'false'",
+            "duplicate workspace setting 'lint.strict' on line 2",
+            "invalid value '../out' for workspace setting 'build.output_directory' on line 3: expected a relative path inside the workspace",
+            "unknown workspace setting 'format.indent' on line 4",
+            "invalid package pattern 'generated' on line 5: expected 'workspace' or a package path beneath it, optionally ending in '/*'",
+            "invalid workspace configuration on line 6: expected a dependency rule, an 'include' or 'exclude' package pattern, an 'ignore' path pattern, a 'forbid' builtin rule, or '<setting> = <value>'",
+            "invalid ignore pattern '../vendor' on line 7: expected a workspace-relative path whose segments may use '*' and '**'",
+        ]
+    );
+    assert!(
+        errors
+            .iter()
+            .all(|error| error.path.as_deref() == Some(Path::new("COPPICE_WORKSPACE")))
+    );
+}
+
+#[test]
+fn records_package_metadata_on_discovered_packages() {
+    let workspace = TestWorkspace::new(&["PACKAGE.copp", "tools/PACKAGE.copp"]);
+
+    let mut discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    assert!(
+        discovered_workspace
+            .packages()
+            .iter()
+            .all(|package| package.metadata == PackageMetadata::default())
+    );
+
+    let metadata = PackageMetadata {
+        name: Some("tools".to_string()),
+        version: Some("1.0.0".to_string()),
+        description: None,
+        entrypoints: Some(vec!["main.bin.copp".to_string()]),
+        language_version: Some(LanguageVersion::Version1),
+    };
+    discovered_workspace.set_package_metadata("tools", metadata.clone());
+    let tools_package = discovered_workspace
+        .package_by_path("tools")
+        .expect("tools package should exist");
+    assert_eq!(tools_package.metadata, metadata);
+}
+
+#[test]
+fn discovers_workspace_from_in_memory_file_system() {
+    let mut file_system = InMemoryFileSystem::new();
+    file_system.insert_file(
+        "/workspace/COPPICE_WORKSPACE",
+        "allow workspace -> workspace/app\n",
+    );
+    file_system.insert_file("/workspace/PACKAGE.copp", "");
+    file_system.insert_file("/workspace/main.bin.copp", "");
+    file_system.insert_file("/workspace/app/PACKAGE.copp", "");
+    file_system.insert_file("/workspace/app/lib.copp", "");
+
+    let workspace = discover_workspace(&file_system, Path::new("/workspace"))
+        .expect("discovery should succeed");
+    let package_paths: Vec<&str> = workspace
+        .packages()
+        .iter()
+        .map(|package| package.package_path.as_str())
+        .collect();
+    assert_eq!(package_paths, vec!["", "app"]);
+    assert_eq!(workspace.dependency_rules().len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn follows_symlinked_directories_once_and_skips_links_within_the_workspace() {
+    let workspace = TestWorkspace::new(&["app/PACKAGE.copp", "app/lib.copp"]);
+    let shared = TestWorkspace::new(&["PACKAGE.copp", "strings.copp"]);
+    std::os::unix::fs::symlink(workspace.path().join("app"), workspace.path().join("alias"))
+        .expect("symlink should be created");
+    std::os::unix::fs::symlink(shared.path(), workspace.path().join("shared"))
+        .expect("symlink should be created");
+    std::os::unix::fs::symlink(shared.path(), shared.path().join("cycle"))
+        .expect("symlink should be created");
+
+    let discovered_workspace =
+        discover_workspace(&RealFileSystem, workspace.path()).expect("discovery should succeed");
+    let package_paths: Vec<&str> = discovered_workspace
+        .packages()
+        .iter()
+        .map(|package| package.package_path.as_str())
+        .collect();
+    assert_eq!(package_paths, vec!["app", "shared"]);
+    let source_paths: Vec<String> = discovered_workspace
+        .packages()
+        .iter()
+        .flat_map(|package| package.source_files.iter())
+        .map(|file| compiler__source::path_to_key(&file.workspace_relative_path))
+        .collect();
+    assert_eq!(source_paths, vec!["app/lib.copp", "shared/strings.copp"]);
+}
+
 struct TestWorkspace {
     root: PathBuf,
 }
@@ -160,6 +460,10 @@ impl TestWorkspace {
     fn path(&self) -> &Path {
         &self.root
     }
+
+    fn write_file(&self, relative_file: &str, contents: &str) {
+        fs::write(self.root.join(relative_file), contents).expect("test file should be written");
+    }
 }
 
 impl Drop for TestWorkspace {
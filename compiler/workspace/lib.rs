@@ -1,5 +1,9 @@
+mod configuration;
 mod discovery;
 mod types;
 
 pub use discovery::discover_workspace;
-pub use types::{DiscoveredPackage, DiscoveryError, Workspace};
+pub use types::{
+    DependencyRule, DiscoveredPackage, DiscoveryError, ForbiddenBuiltinRule, PackageMetadata,
+    Workspace, WorkspaceSettings,
+};
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use compiler__packages::PackageId;
-use compiler__source::SourceFile;
+use compiler__source::{LanguageVersion, SourceFile};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DiscoveredPackage {
@@ -11,6 +11,50 @@ pub struct DiscoveredPackage {
     pub root_directory: PathBuf,
     pub manifest_path: PathBuf,
     pub source_files: Vec<SourceFile>,
+    pub metadata: PackageMetadata,
+}
+
+// Metadata declared by the `package` declaration in a package's manifest.
+// Discovery does not parse manifests, so every field is unset until analysis
+// records the parsed metadata with `Workspace::set_package_metadata`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackageMetadata {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub entrypoints: Option<Vec<String>>,
+    pub language_version: Option<LanguageVersion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyRule {
+    pub source_package_pattern: String,
+    pub target_package_pattern: String,
+}
+
+// Forbids the packages a pattern matches from calling a builtin, as declared
+// by a `forbid` entry on `line_number` of the workspace marker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenBuiltinRule {
+    pub builtin_name: String,
+    pub package_pattern: String,
+    pub line_number: usize,
+}
+
+// Settings read from the workspace marker. Package patterns use the syntax of
+// dependency rules; when any include pattern is given, only the packages it
+// matches are part of the workspace. Ignore patterns match workspace-relative
+// paths, where `*` matches within one segment and `**` matches any number of
+// segments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkspaceSettings {
+    pub package_include_patterns: Vec<String>,
+    pub package_exclude_patterns: Vec<String>,
+    pub ignore_patterns: Vec<String>,
+    pub forbidden_builtin_rules: Vec<ForbiddenBuiltinRule>,
+    pub build_output_directory: PathBuf,
+    pub strict: bool,
+    pub diagnostic_baseline: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -18,6 +62,8 @@ pub struct Workspace {
     root_directory: PathBuf,
     packages: Vec<DiscoveredPackage>,
     package_id_by_path: BTreeMap<String, PackageId>,
+    dependency_rules: Vec<DependencyRule>,
+    settings: WorkspaceSettings,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -36,10 +82,122 @@ impl DiscoveryError {
     }
 }
 
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            package_include_patterns: Vec::new(),
+            package_exclude_patterns: Vec::new(),
+            ignore_patterns: Vec::new(),
+            forbidden_builtin_rules: Vec::new(),
+            build_output_directory: PathBuf::from(".coppice").join("build"),
+            strict: false,
+            diagnostic_baseline: None,
+        }
+    }
+}
+
+impl WorkspaceSettings {
+    #[must_use]
+    pub fn includes_package(&self, package_path: &str) -> bool {
+        let package = workspace_package_name(package_path);
+        (self.package_include_patterns.is_empty()
+            || self
+                .package_include_patterns
+                .iter()
+                .any(|pattern| package_pattern_matches(pattern, &package)))
+            && !self
+                .package_exclude_patterns
+                .iter()
+                .any(|pattern| package_pattern_matches(pattern, &package))
+    }
+
+    #[must_use]
+    pub fn forbidden_builtin_rules_for_package(
+        &self,
+        package_path: &str,
+    ) -> Vec<&ForbiddenBuiltinRule> {
+        let package = workspace_package_name(package_path);
+        self.forbidden_builtin_rules
+            .iter()
+            .filter(|rule| package_pattern_matches(&rule.package_pattern, &package))
+            .collect()
+    }
+
+    // A path is ignored when it or one of its ancestors is. Hidden entries and
+    // the build output directory are always ignored, so that build artifacts
+    // and tool state are never discovered as sources.
+    #[must_use]
+    pub fn ignores_path(&self, relative_path: &Path) -> bool {
+        if relative_path.starts_with(&self.build_output_directory) {
+            return true;
+        }
+        let key = compiler__source::path_to_key(relative_path);
+        let segments = key.split('/').collect::<Vec<_>>();
+        segments.iter().any(|segment| segment.starts_with('.'))
+            || self.ignore_patterns.iter().any(|pattern| {
+                let pattern_segments = pattern.split('/').collect::<Vec<_>>();
+                (1..=segments.len())
+                    .any(|length| path_pattern_matches(&pattern_segments, &segments[..length]))
+            })
+    }
+}
+
+fn workspace_package_name(package_path: &str) -> String {
+    if package_path.is_empty() {
+        "workspace".to_string()
+    } else {
+        format!("workspace/{package_path}")
+    }
+}
+
+fn path_pattern_matches(pattern_segments: &[&str], path_segments: &[&str]) -> bool {
+    match pattern_segments.split_first() {
+        None => path_segments.is_empty(),
+        Some((&"**", rest)) => (0..=path_segments.len())
+            .any(|skipped| path_pattern_matches(rest, &path_segments[skipped..])),
+        Some((pattern_segment, rest)) => {
+            path_segments
+                .split_first()
+                .is_some_and(|(path_segment, path_rest)| {
+                    segment_pattern_matches(pattern_segment, path_segment)
+                        && path_pattern_matches(rest, path_rest)
+                })
+        }
+    }
+}
+
+fn segment_pattern_matches(pattern: &str, segment: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == segment,
+        Some((prefix, rest)) => segment.strip_prefix(prefix).is_some_and(|remaining| {
+            (0..=remaining.len()).any(|skipped| {
+                remaining.is_char_boundary(skipped)
+                    && segment_pattern_matches(rest, &remaining[skipped..])
+            })
+        }),
+    }
+}
+
+// A trailing `/*` matches the named package and every package nested beneath
+// it; any other pattern matches exactly one package.
+fn package_pattern_matches(pattern: &str, package: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => {
+            package == prefix
+                || package
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+        None => package == pattern,
+    }
+}
+
 impl Workspace {
     pub(crate) fn new(
         root_directory: PathBuf,
         packages: Vec<DiscoveredPackage>,
+        dependency_rules: Vec<DependencyRule>,
+        settings: WorkspaceSettings,
     ) -> Result<Self, DiscoveryError> {
         let mut package_id_by_path = BTreeMap::new();
         for package in &packages {
@@ -57,6 +215,8 @@ impl Workspace {
             root_directory,
             packages,
             package_id_by_path,
+            dependency_rules,
+            settings,
         })
     }
 
@@ -70,9 +230,27 @@ impl Workspace {
         &self.packages
     }
 
+    #[must_use]
+    pub fn dependency_rules(&self) -> &[DependencyRule] {
+        &self.dependency_rules
+    }
+
+    #[must_use]
+    pub fn settings(&self) -> &WorkspaceSettings {
+        &self.settings
+    }
+
     #[must_use]
     pub fn package_by_path(&self, package_path: &str) -> Option<&DiscoveredPackage> {
         let package_id = self.package_id_by_path.get(package_path)?;
         self.packages.get(package_id.0)
     }
+
+    pub fn set_package_metadata(&mut self, package_path: &str, metadata: PackageMetadata) {
+        if let Some(package_id) = self.package_id_by_path.get(package_path)
+            && let Some(package) = self.packages.get_mut(package_id.0)
+        {
+            package.metadata = metadata;
+        }
+    }
 }
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use compiler__reports::{
+    RenderedDiagnostic, RenderedDiagnosticSeverity, RenderedRelatedSpan, RenderedSpan,
+    RenderedSuggestedFix, RenderedTextEdit,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompilerFailure {
+    pub kind: CompilerFailureKind,
+    pub message: String,
+    pub path: Option<String>,
+    pub details: Vec<CompilerFailureDetail>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompilerFailureKind {
+    CheckFailed,
+    ReadSource,
+    WriteSource,
+    InvalidWorkspaceRoot,
+    WorkspaceRootNotDirectory,
+    WorkspaceRootMissingManifest,
+    InvalidAnalysisTarget,
+    TargetOutsideWorkspace,
+    PackageNotFound,
+    WorkspaceDiscoveryFailed,
+    BuildFailed,
+    RunFailed,
+    Cancelled,
+    DiagnosticBaselineNotConfigured,
+    DeclarationNotFound,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompilerFailureDetail {
+    pub message: String,
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Diagnostic {
+    pub phase: DiagnosticPhase,
+    pub severity: DiagnosticSeverity,
+    pub path: String,
+    pub message: String,
+    pub span: DiagnosticSpan,
+    pub related_spans: Vec<DiagnosticRelatedSpan>,
+    pub suggested_fixes: Vec<SuggestedFix>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum DiagnosticPhase {
+    Parsing,
+    SyntaxRules,
+    FileRoleRules,
+    Resolution,
+    SemanticLowering,
+    MaintainabilityRules,
+    TypeAnalysis,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+// `start` and `end` are byte offsets; `line` and `column` locate the start,
+// or the operator of an operator expression, counting columns in characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DiagnosticSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+// `path` is only set when the related span is in another file than the
+// diagnostic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DiagnosticRelatedSpan {
+    pub message: String,
+    pub path: Option<String>,
+    pub span: DiagnosticSpan,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SuggestedFix {
+    pub label: String,
+    pub text_edits: Vec<SuggestedFixEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SuggestedFixEdit {
+    pub span: DiagnosticSpan,
+    pub replacement_text: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DiagnosticTextOptions {
+    pub color: bool,
+    pub context_line_count: usize,
+}
+
+// Renders diagnostics as the command line does, reading the annotated lines
+// from `source_by_path`.
+#[must_use]
+pub fn render_diagnostics_text(
+    diagnostics: &[Diagnostic],
+    source_by_path: &BTreeMap<String, String>,
+    options: DiagnosticTextOptions,
+) -> String {
+    let rendered_diagnostics = diagnostics
+        .iter()
+        .map(rendered_diagnostic)
+        .collect::<Vec<_>>();
+    compiler__reports::render_diagnostics_text(
+        &rendered_diagnostics,
+        source_by_path,
+        compiler__reports::DiagnosticTextOptions {
+            color: options.color,
+            context_line_count: options.context_line_count,
+        },
+    )
+}
+
+impl From<compiler__reports::CompilerFailure> for CompilerFailure {
+    fn from(failure: compiler__reports::CompilerFailure) -> Self {
+        Self {
+            kind: failure.kind.into(),
+            message: failure.message,
+            path: failure.path,
+            details: failure
+                .details
+                .into_iter()
+                .map(|detail| CompilerFailureDetail {
+                    message: detail.message,
+                    path: detail.path,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<compiler__reports::CompilerFailureKind> for CompilerFailureKind {
+    fn from(kind: compiler__reports::CompilerFailureKind) -> Self {
+        use compiler__reports::CompilerFailureKind as Internal;
+        match kind {
+            Internal::CheckFailed => Self::CheckFailed,
+            Internal::ReadSource => Self::ReadSource,
+            Internal::WriteSource => Self::WriteSource,
+            Internal::InvalidWorkspaceRoot => Self::InvalidWorkspaceRoot,
+            Internal::WorkspaceRootNotDirectory => Self::WorkspaceRootNotDirectory,
+            Internal::WorkspaceRootMissingManifest => Self::WorkspaceRootMissingManifest,
+            Internal::InvalidAnalysisTarget => Self::InvalidAnalysisTarget,
+            Internal::TargetOutsideWorkspace => Self::TargetOutsideWorkspace,
+            Internal::PackageNotFound => Self::PackageNotFound,
+            Internal::WorkspaceDiscoveryFailed => Self::WorkspaceDiscoveryFailed,
+            Internal::BuildFailed => Self::BuildFailed,
+            Internal::RunFailed => Self::RunFailed,
+            Internal::Cancelled => Self::Cancelled,
+            Internal::DiagnosticBaselineNotConfigured => Self::DiagnosticBaselineNotConfigured,
+            Internal::DeclarationNotFound => Self::DeclarationNotFound,
+        }
+    }
+}
+
+impl From<RenderedDiagnostic> for Diagnostic {
+    fn from(diagnostic: RenderedDiagnostic) -> Self {
+        use compiler__reports::DiagnosticPhase as InternalPhase;
+        Self {
+            phase: match diagnostic.phase {
+                InternalPhase::Parsing => DiagnosticPhase::Parsing,
+                InternalPhase::SyntaxRules => DiagnosticPhase::SyntaxRules,
+                InternalPhase::FileRoleRules => DiagnosticPhase::FileRoleRules,
+                InternalPhase::Resolution => DiagnosticPhase::Resolution,
+                InternalPhase::SemanticLowering => DiagnosticPhase::SemanticLowering,
+                InternalPhase::MaintainabilityRules => DiagnosticPhase::MaintainabilityRules,
+                InternalPhase::TypeAnalysis => DiagnosticPhase::TypeAnalysis,
+            },
+            severity: match diagnostic.severity {
+                RenderedDiagnosticSeverity::Error => DiagnosticSeverity::Error,
+                RenderedDiagnosticSeverity::Warning => DiagnosticSeverity::Warning,
+            },
+            path: diagnostic.path,
+            message: diagnostic.message,
+            span: diagnostic_span(diagnostic.span),
+            related_spans: diagnostic
+                .related_spans
+                .into_iter()
+                .map(|related_span| DiagnosticRelatedSpan {
+                    message: related_span.message,
+                    path: related_span.path,
+                    span: diagnostic_span(related_span.span),
+                })
+                .collect(),
+            suggested_fixes: diagnostic
+                .suggested_fixes
+                .into_iter()
+                .map(|suggested_fix| SuggestedFix {
+                    label: suggested_fix.label,
+                    text_edits: suggested_fix
+                        .text_edits
+                        .into_iter()
+                        .map(|text_edit| SuggestedFixEdit {
+                            span: diagnostic_span(text_edit.span),
+                            replacement_text: text_edit.replacement_text,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn diagnostic_span(span: RenderedSpan) -> DiagnosticSpan {
+    DiagnosticSpan {
+        start: span.start,
+        end: span.end,
+        line: span.line,
+        column: span.column,
+    }
+}
+
+fn rendered_diagnostic(diagnostic: &Diagnostic) -> RenderedDiagnostic {
+    use compiler__reports::DiagnosticPhase as InternalPhase;
+    RenderedDiagnostic {
+        phase: match diagnostic.phase {
+            DiagnosticPhase::Parsing => InternalPhase::Parsing,
+            DiagnosticPhase::SyntaxRules => InternalPhase::SyntaxRules,
+            DiagnosticPhase::FileRoleRules => InternalPhase::FileRoleRules,
+            DiagnosticPhase::Resolution => InternalPhase::Resolution,
+            DiagnosticPhase::SemanticLowering => InternalPhase::SemanticLowering,
+            DiagnosticPhase::MaintainabilityRules => InternalPhase::MaintainabilityRules,
+            DiagnosticPhase::TypeAnalysis => InternalPhase::TypeAnalysis,
+        },
+        severity: match diagnostic.severity {
+            DiagnosticSeverity::Error => RenderedDiagnosticSeverity::Error,
+            DiagnosticSeverity::Warning => RenderedDiagnosticSeverity::Warning,
+        },
+        path: diagnostic.path.clone(),
+        message: diagnostic.message.clone(),
+        span: rendered_span(diagnostic.span),
+        related_spans: diagnostic
+            .related_spans
+            .iter()
+            .map(|related_span| RenderedRelatedSpan {
+                message: related_span.message.clone(),
+                path: related_span.path.clone(),
+                span: rendered_span(related_span.span),
+            })
+            .collect(),
+        suggested_fixes: diagnostic
+            .suggested_fixes
+            .iter()
+            .map(|suggested_fix| RenderedSuggestedFix {
+                label: suggested_fix.label.clone(),
+                text_edits: suggested_fix
+                    .text_edits
+                    .iter()
+                    .map(|text_edit| RenderedTextEdit {
+                        span: rendered_span(text_edit.span),
+                        replacement_text: text_edit.replacement_text.clone(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn rendered_span(span: DiagnosticSpan) -> RenderedSpan {
+    RenderedSpan {
+        start: span.start,
+        end: span.end,
+        line: span.line,
+        column: span.column,
+    }
+}
@@ -0,0 +1,467 @@
+// The supported way to embed the compiler. The `compiler__*` crates are
+// internal and change freely; this crate is versioned with semver instead. New
+// functions, fields, and enum variants are minor releases, and every other
+// change to an existing item is a major release. Every public type is defined
+// here rather than re-exported from an internal crate, so that internal changes
+// never leak into this crate's API. Structs and enums are `#[non_exhaustive]`
+// so that fields and variants can be added: start options from `default()` and
+// set the fields you need.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use compiler__analysis_pipeline::analyze_target_summary_with_workspace_root;
+use compiler__driver::{
+    ReproducibilityCheck, build_target_with_workspace_root, load_target_with_workspace_root,
+    run_target_with_workspace_root, why_not_conforming_with_workspace_root,
+};
+use compiler__executable_program::diff_programs;
+use compiler__interpreter::Interpreter;
+use compiler__runtime_interface::ExecutionContext;
+use compiler__semantic_types::{HostFunctionContract, Type, normalize_union};
+use compiler__source_formatting::canonicalize_source_text;
+
+mod diagnostics;
+mod programs;
+mod runtime;
+
+pub use diagnostics::{
+    CompilerFailure, CompilerFailureDetail, CompilerFailureKind, Diagnostic, DiagnosticPhase,
+    DiagnosticRelatedSpan, DiagnosticSeverity, DiagnosticSpan, DiagnosticTextOptions, SuggestedFix,
+    SuggestedFixEdit, render_diagnostics_text,
+};
+pub use programs::{
+    ChangeKind, ConformanceReport, FunctionChange, MethodConformanceProblem, ProgramDiff,
+    TypeChange,
+};
+pub use runtime::{
+    CapturedOutput, ExecutionPolicy, HostFunctionSignature, OutputSink, OverflowMode,
+    RuntimeCapability, RuntimeType, RuntimeValue, SharedOutputSink,
+};
+
+// The semver version of this crate, set on the library target.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Paths are resolved against the current directory, and the workspace root is
+// discovered from the target path unless it is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AnalyzeOptions {
+    pub workspace_root: Option<String>,
+}
+
+// Strict checks also fail when safe autofixes are pending.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CheckOptions {
+    pub workspace_root: Option<String>,
+    pub strict: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BuildOptions {
+    pub workspace_root: Option<String>,
+    pub output_directory: Option<String>,
+    pub strict: bool,
+    pub check_reproducible: bool,
+}
+
+// A program that calls a builtin needing a capability the execution policy
+// denies aborts, naming the capability on stderr. `random_int` is seeded with
+// `random_seed` and `now` returns `fixed_now_unix_seconds` when they are set,
+// so that runs repeat exactly; otherwise both come from the system clock. Int64
+// arithmetic wraps on overflow unless the overflow mode aborts, naming the
+// operation and where it is. With an output sink the program's stdout and
+// stderr are captured into it instead of being inherited.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RunOptions {
+    pub workspace_root: Option<String>,
+    pub output_directory: Option<String>,
+    pub strict: bool,
+    pub execution_policy: ExecutionPolicy,
+    pub random_seed: Option<i64>,
+    pub fixed_now_unix_seconds: Option<i64>,
+    pub overflow_mode: OverflowMode,
+    pub output_sink: Option<SharedOutputSink>,
+}
+
+// The program may call the host functions declared here, and calls are type
+// checked against their signatures when it loads. The execution policy
+// restricts runtime builtins but not host functions. The random seed, fixed
+// time, overflow mode, and output sink behave as they do in `RunOptions`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LoadOptions {
+    pub workspace_root: Option<String>,
+    pub strict: bool,
+    pub host_function_signature_by_name: BTreeMap<String, HostFunctionSignature>,
+    pub execution_policy: ExecutionPolicy,
+    pub random_seed: Option<i64>,
+    pub fixed_now_unix_seconds: Option<i64>,
+    pub overflow_mode: OverflowMode,
+    pub output_sink: Option<SharedOutputSink>,
+}
+
+// Sources are keyed by the paths diagnostics refer to, ready for
+// `render_diagnostics_text`.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct AnalysisOutput {
+    pub diagnostics: Vec<Diagnostic>,
+    pub source_by_path: BTreeMap<String, String>,
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+}
+
+// Only binary entrypoints produce an executable; other targets are analyzed
+// and checked for diagnostics.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct BuildOutput {
+    pub executable_path: Option<PathBuf>,
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+}
+
+// Each workspace root applies to its own side of the diff, and both sides are
+// analyzed with the same strictness.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DiffOptions {
+    pub old_workspace_root: Option<String>,
+    pub new_workspace_root: Option<String>,
+    pub strict: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConformanceOptions {
+    pub workspace_root: Option<String>,
+}
+
+// An aborted program exits with a nonzero code and reports its abort message.
+// The standard output byte count is only known when an output sink captured it.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RunOutput {
+    pub exit_code: i32,
+    pub abort_message: Option<String>,
+    pub standard_output_byte_count: Option<usize>,
+    pub standard_error_byte_count: usize,
+    pub elapsed: Duration,
+    pub safe_autofix_edit_count_by_workspace_relative_path: BTreeMap<String, usize>,
+}
+
+// Diagnostics are returned rather than treated as a failure; errors are
+// reserved for targets that could not be analyzed at all.
+pub fn analyze(path: &str, options: &AnalyzeOptions) -> Result<AnalysisOutput, CompilerFailure> {
+    let analyzed_target =
+        analyze_target_summary_with_workspace_root(path, options.workspace_root.as_deref())?;
+    Ok(AnalysisOutput {
+        diagnostics: analyzed_target
+            .diagnostics
+            .into_iter()
+            .map(Diagnostic::from)
+            .collect(),
+        source_by_path: analyzed_target.source_by_path,
+        safe_autofix_edit_count_by_workspace_relative_path: analyzed_target
+            .safe_autofix_edit_count_by_workspace_relative_path,
+    })
+}
+
+pub fn check(path: &str, options: &CheckOptions) -> Result<(), CompilerFailure> {
+    let analysis_output = analyze(
+        path,
+        &AnalyzeOptions {
+            workspace_root: options.workspace_root.clone(),
+        },
+    )?;
+    if analysis_output.diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(failure_from_diagnostics(
+            CompilerFailureKind::CheckFailed,
+            "check failed due to diagnostics",
+            &analysis_output.diagnostics,
+        ));
+    }
+    if options.strict
+        && !analysis_output
+            .safe_autofix_edit_count_by_workspace_relative_path
+            .is_empty()
+    {
+        return Err(CompilerFailure {
+            kind: CompilerFailureKind::CheckFailed,
+            message: "check failed due to pending safe autofixes".to_string(),
+            path: None,
+            details: analysis_output
+                .safe_autofix_edit_count_by_workspace_relative_path
+                .iter()
+                .map(
+                    |(workspace_relative_path, text_edit_count)| CompilerFailureDetail {
+                        message: format!("{text_edit_count} pending safe autofix edits"),
+                        path: Some(workspace_relative_path.clone()),
+                    },
+                )
+                .collect(),
+        });
+    }
+    Ok(())
+}
+
+pub fn build(path: &str, options: &BuildOptions) -> Result<BuildOutput, CompilerFailure> {
+    let build_result = build_target_with_workspace_root(
+        path,
+        options.workspace_root.as_deref(),
+        options.output_directory.as_deref(),
+        options.strict,
+        if options.check_reproducible {
+            ReproducibilityCheck::BuildTwiceAndCompare
+        } else {
+            ReproducibilityCheck::Disabled
+        },
+    );
+    build_result.build?;
+    if let Some(analysis_result) = &build_result.analysis_result {
+        let diagnostics = analysis_result
+            .diagnostics
+            .iter()
+            .cloned()
+            .map(Diagnostic::from)
+            .collect::<Vec<_>>();
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return Err(failure_from_diagnostics(
+                CompilerFailureKind::BuildFailed,
+                "build failed due to diagnostics",
+                &diagnostics,
+            ));
+        }
+    }
+    Ok(BuildOutput {
+        executable_path: build_result.executable_path.map(PathBuf::from),
+        safe_autofix_edit_count_by_workspace_relative_path: build_result
+            .safe_autofix_edit_count_by_workspace_relative_path,
+    })
+}
+
+// The program inherits the caller's standard streams unless an output sink is
+// set.
+pub fn run(path: &str, options: &RunOptions) -> Result<RunOutput, CompilerFailure> {
+    let run_result = run_target_with_workspace_root(
+        path,
+        options.workspace_root.as_deref(),
+        options.output_directory.as_deref(),
+        options.strict,
+        &ExecutionContext {
+            execution_policy: options.execution_policy.clone().into(),
+            random_seed: options.random_seed,
+            fixed_now_unix_seconds: options.fixed_now_unix_seconds,
+            overflow_mode: options.overflow_mode.into(),
+            output_sink: options.output_sink.clone().map(Into::into),
+        },
+    );
+    let run_outcome = run_result.run?;
+    Ok(RunOutput {
+        exit_code: run_outcome.exit_code,
+        abort_message: run_outcome.abort_message,
+        standard_output_byte_count: run_outcome.standard_output_byte_count,
+        standard_error_byte_count: run_outcome.standard_error_byte_count,
+        elapsed: run_outcome.elapsed,
+        safe_autofix_edit_count_by_workspace_relative_path: run_result
+            .safe_autofix_edit_count_by_workspace_relative_path,
+    })
+}
+
+// Compares the programs that builds of two binary entrypoints would compile,
+// such as one entrypoint at two revisions, without emitting either executable.
+pub fn diff(
+    old_path: &str,
+    new_path: &str,
+    options: &DiffOptions,
+) -> Result<ProgramDiff, CompilerFailure> {
+    let old_program = load_target_with_workspace_root(
+        old_path,
+        options.old_workspace_root.as_deref(),
+        options.strict,
+        &[],
+    )
+    .program?;
+    let new_program = load_target_with_workspace_root(
+        new_path,
+        options.new_workspace_root.as_deref(),
+        options.strict,
+        &[],
+    )
+    .program?;
+    Ok(diff_programs(&old_program, &new_program).into())
+}
+
+// Explains which of an interface's methods a struct in the analyzed target is
+// missing or declares differently, for tools that would otherwise piece it
+// together from separate diagnostics. The target may have errors. Names are
+// qualified outside the root package, as in `units::Meters`.
+pub fn why_not_conforming(
+    path: &str,
+    struct_name: &str,
+    interface_name: &str,
+    options: &ConformanceOptions,
+) -> Result<ConformanceReport, CompilerFailure> {
+    why_not_conforming_with_workspace_root(
+        path,
+        options.workspace_root.as_deref(),
+        struct_name,
+        interface_name,
+    )
+    .map(ConformanceReport::from)
+    .map_err(CompilerFailure::from)
+}
+
+#[must_use]
+pub fn format(source_text: &str) -> String {
+    canonicalize_source_text(source_text)
+}
+
+// Runs a binary entrypoint in-process so that coppice can serve as an embedded
+// scripting language. Loading analyzes and lowers the target like a build but
+// emits no executable. Failures while running, including `abort` calls and
+// errors returned by host functions, are `RunFailed` errors.
+pub struct CoppiceEngine {
+    interpreter: Interpreter,
+    host_function_signature_by_name: BTreeMap<String, HostFunctionSignature>,
+}
+
+impl CoppiceEngine {
+    pub fn load(path: &str, options: &LoadOptions) -> Result<Self, CompilerFailure> {
+        let host_function_contracts = options
+            .host_function_signature_by_name
+            .iter()
+            .map(|(name, signature)| HostFunctionContract {
+                name: name.clone(),
+                parameter_types: signature
+                    .parameter_types
+                    .iter()
+                    .copied()
+                    .map(type_from_runtime_type)
+                    .collect(),
+                return_type: type_from_runtime_type(signature.return_type),
+            })
+            .collect::<Vec<_>>();
+        let load_result = load_target_with_workspace_root(
+            path,
+            options.workspace_root.as_deref(),
+            options.strict,
+            &host_function_contracts,
+        );
+        let mut interpreter = Interpreter::new(load_result.program?)?;
+        interpreter.set_execution_context(ExecutionContext {
+            execution_policy: options.execution_policy.clone().into(),
+            random_seed: options.random_seed,
+            fixed_now_unix_seconds: options.fixed_now_unix_seconds,
+            overflow_mode: options.overflow_mode.into(),
+            output_sink: options.output_sink.clone().map(Into::into),
+        });
+        Ok(Self {
+            interpreter,
+            host_function_signature_by_name: options.host_function_signature_by_name.clone(),
+        })
+    }
+
+    // Makes `function` callable from coppice code as the builtin `name`.
+    // Registering a builtin's own name, such as `print`, replaces it; the
+    // signature must then match the builtin's. Functions declared in
+    // `LoadOptions` must be registered with the signature they were declared
+    // with.
+    pub fn register_host_function<F>(
+        &mut self,
+        name: &str,
+        signature: HostFunctionSignature,
+        mut function: F,
+    ) -> Result<(), CompilerFailure>
+    where
+        F: FnMut(&[RuntimeValue]) -> Result<RuntimeValue, String> + 'static,
+    {
+        if let Some(declared_signature) = self.host_function_signature_by_name.get(name)
+            && *declared_signature != signature
+        {
+            return Err(CompilerFailure {
+                kind: CompilerFailureKind::RunFailed,
+                message: format!(
+                    "host function '{name}' must match the signature it was declared with"
+                ),
+                path: None,
+                details: Vec::new(),
+            });
+        }
+        self.interpreter
+            .register_host_function(
+                name,
+                signature.into(),
+                Box::new(move |arguments: &[compiler__interpreter::RuntimeValue]| {
+                    let arguments = arguments
+                        .iter()
+                        .cloned()
+                        .map(RuntimeValue::from)
+                        .collect::<Vec<_>>();
+                    function(&arguments).map(Into::into)
+                }),
+            )
+            .map_err(Into::into)
+    }
+
+    // Calls a top-level function declared alongside the entrypoint.
+    pub fn call(
+        &mut self,
+        function_name: &str,
+        arguments: &[RuntimeValue],
+    ) -> Result<RuntimeValue, CompilerFailure> {
+        let arguments = arguments
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect::<Vec<_>>();
+        self.interpreter
+            .call_function(function_name, &arguments)
+            .map(RuntimeValue::from)
+            .map_err(Into::into)
+    }
+
+    pub fn run_main(&mut self) -> Result<(), CompilerFailure> {
+        self.interpreter.run_entrypoint().map_err(Into::into)
+    }
+}
+
+fn type_from_runtime_type(runtime_type: RuntimeType) -> Type {
+    match runtime_type {
+        RuntimeType::Boolean => Type::Boolean,
+        RuntimeType::Int64 => Type::Integer64,
+        RuntimeType::Int64OrNil => normalize_union(vec![Type::Integer64, Type::Nil]),
+        RuntimeType::Nil => Type::Nil,
+        RuntimeType::Never => Type::Never,
+        RuntimeType::String => Type::String,
+    }
+}
+
+fn failure_from_diagnostics(
+    kind: CompilerFailureKind,
+    message: &str,
+    diagnostics: &[Diagnostic],
+) -> CompilerFailure {
+    CompilerFailure {
+        kind,
+        message: message.to_string(),
+        path: None,
+        details: diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(|diagnostic| CompilerFailureDetail {
+                message: format!(
+                    "{} ({}:{}:{})",
+                    diagnostic.message,
+                    diagnostic.path,
+                    diagnostic.span.line,
+                    diagnostic.span.column
+                ),
+                path: Some(diagnostic.path.clone()),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,482 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use coppice::{
+    AnalyzeOptions, BuildOptions, CapturedOutput, ChangeKind, CheckOptions, CompilerFailureKind,
+    ConformanceOptions, CoppiceEngine, DiffOptions, ExecutionPolicy, HostFunctionSignature,
+    LoadOptions, OverflowMode, RuntimeCapability, RuntimeType, RuntimeValue, SharedOutputSink,
+    analyze, build, check, diff, format, why_not_conforming,
+};
+use tests__test_workspace::TestWorkspace;
+
+fn main_workspace(main_source: &str) -> TestWorkspace {
+    TestWorkspace::new(
+        "coppice_api_test",
+        &[("PACKAGE.copp", ""), ("main.bin.copp", main_source)],
+    )
+}
+
+fn main_path(workspace: &TestWorkspace) -> String {
+    workspace.file_path("main.bin.copp")
+}
+
+fn check_options(workspace: &TestWorkspace) -> CheckOptions {
+    let mut options = CheckOptions::default();
+    options.workspace_root = Some(workspace.path().display().to_string());
+    options
+}
+
+fn diff_options(old_workspace: &TestWorkspace, new_workspace: &TestWorkspace) -> DiffOptions {
+    let mut options = DiffOptions::default();
+    options.old_workspace_root = Some(old_workspace.path().display().to_string());
+    options.new_workspace_root = Some(new_workspace.path().display().to_string());
+    options
+}
+
+#[test]
+fn version_is_the_library_target_version() {
+    assert_eq!(coppice::VERSION, "0.1.0");
+}
+
+#[test]
+fn check_passes_for_valid_program() {
+    let workspace = main_workspace("function main() -> nil {\n    return\n}\n");
+    check(&main_path(&workspace), &check_options(&workspace)).expect("check should pass");
+}
+
+#[test]
+fn analyze_returns_diagnostics_that_check_reports_as_failure() {
+    let workspace = main_workspace("function main() -> nil {\n    return 1\n}\n");
+    let mut analyze_options = AnalyzeOptions::default();
+    analyze_options.workspace_root = Some(workspace.path().display().to_string());
+
+    let analysis_output =
+        analyze(&main_path(&workspace), &analyze_options).expect("analysis should run");
+    assert!(!analysis_output.diagnostics.is_empty());
+    assert!(
+        analysis_output
+            .source_by_path
+            .contains_key(&analysis_output.diagnostics[0].path)
+    );
+
+    let failure =
+        check(&main_path(&workspace), &check_options(&workspace)).expect_err("check should fail");
+    assert!(matches!(failure.kind, CompilerFailureKind::CheckFailed));
+    assert_eq!(failure.details.len(), analysis_output.diagnostics.len());
+}
+
+#[test]
+fn check_and_build_pass_when_diagnostics_are_only_warnings() {
+    let workspace = main_workspace(
+        "@deprecated(\"use greet\")\nfunction hello() -> nil {\n    print(\"hi\")\n    return\n}\n\nfunction main() -> nil {\n    hello()\n    return\n}\n",
+    );
+    let mut analyze_options = AnalyzeOptions::default();
+    analyze_options.workspace_root = Some(workspace.path().display().to_string());
+    let analysis_output =
+        analyze(&main_path(&workspace), &analyze_options).expect("analysis should run");
+    assert!(!analysis_output.diagnostics.is_empty());
+    assert!(
+        analysis_output
+            .diagnostics
+            .iter()
+            .all(|diagnostic| !diagnostic.is_error())
+    );
+
+    check(&main_path(&workspace), &check_options(&workspace)).expect("check should pass");
+    let mut build_options = BuildOptions::default();
+    build_options.workspace_root = Some(workspace.path().display().to_string());
+    build_options.output_directory = Some(workspace.file_path("out"));
+    let build_output = build(&main_path(&workspace), &build_options).expect("build should pass");
+    assert!(build_output.executable_path.is_some());
+}
+
+#[test]
+fn diff_reports_function_and_type_shape_changes_between_programs() {
+    let old_workspace = main_workspace(
+        "type Point :: struct {\n    x: int64,\n    y: int64,\n}\n\n\
+         function unused() -> int64 {\n    return 1\n}\n\n\
+         function origin() -> Point {\n    return Point { x: 0, y: 0 }\n}\n\n\
+         function main() -> nil {\n    print(string(origin().x))\n    return\n}\n",
+    );
+    let new_workspace = main_workspace(
+        "type Point :: struct {\n    x: int64,\n    label: string,\n}\n\n\
+         function origin() -> Point {\n    return Point { x: 0, label: \"origin\" }\n}\n\n\
+         function main() -> nil {\n    print(string(origin().x))\n    return\n}\n",
+    );
+    let program_diff = diff(
+        &main_path(&old_workspace),
+        &main_path(&new_workspace),
+        &diff_options(&old_workspace, &new_workspace),
+    )
+    .expect("diff should load both programs");
+    assert_eq!(
+        program_diff
+            .function_changes
+            .iter()
+            .map(|function_change| (function_change.name.as_str(), function_change.kind))
+            .collect::<Vec<_>>(),
+        [
+            ("origin", ChangeKind::Changed),
+            ("unused", ChangeKind::Removed)
+        ]
+    );
+    let [point_change] = program_diff.type_changes.as_slice() else {
+        panic!("only Point should change");
+    };
+    assert_eq!(point_change.name, "Point");
+    assert_eq!(point_change.kind, ChangeKind::Changed);
+    assert_eq!(
+        point_change.details,
+        ["field 'label' added", "field 'y' removed"]
+    );
+
+    let unchanged_diff = diff(
+        &main_path(&old_workspace),
+        &main_path(&old_workspace),
+        &diff_options(&old_workspace, &old_workspace),
+    )
+    .expect("diff should load both programs");
+    assert!(unchanged_diff.is_empty());
+}
+
+#[test]
+fn why_not_conforming_explains_missing_and_mismatched_methods() {
+    let workspace = main_workspace(
+        "type Named :: interface {\n    function name(self) -> string,\n    \
+         function rename(mut self, name: string) -> nil,\n}\n\n\
+         type User :: struct {\n    id: int64,\n    \
+         function name(self) -> int64 {\n        return self.id\n    },\n}\n\n\
+         function main() -> nil {\n    print(string(User { id: 1 }.name()))\n    return\n}\n",
+    );
+    let mut options = ConformanceOptions::default();
+    options.workspace_root = Some(workspace.path().display().to_string());
+
+    let report = why_not_conforming(&main_path(&workspace), "User", "Named", &options)
+        .expect("conformance should load the program");
+    assert!(!report.conforms());
+    assert!(!report.declares_implementation);
+    let [name_problem, rename_problem] = report.method_problems.as_slice() else {
+        panic!("name and rename should both be reported");
+    };
+    assert_eq!(name_problem.method_name, "name");
+    assert_eq!(name_problem.expected_signature, "name(self) -> string");
+    assert_eq!(
+        name_problem.found_signature.as_deref(),
+        Some("name(self) -> int64")
+    );
+    assert_eq!(
+        name_problem.differences,
+        ["return type is int64, expected string"]
+    );
+    assert_eq!(rename_problem.method_name, "rename");
+    assert_eq!(
+        rename_problem.expected_signature,
+        "rename(mut self, name: string) -> nil"
+    );
+    assert_eq!(rename_problem.found_signature, None);
+    assert!(rename_problem.differences.is_empty());
+
+    let failure = why_not_conforming(&main_path(&workspace), "Admin", "Named", &options)
+        .expect_err("an unknown struct should fail");
+    assert!(matches!(
+        failure.kind,
+        CompilerFailureKind::DeclarationNotFound
+    ));
+}
+
+#[test]
+fn format_canonicalizes_line_endings_and_trailing_newlines() {
+    assert_eq!(
+        format("function main() -> nil {\r\n    return\r\n}\n\n"),
+        "function main() -> nil {\n    return\n}\n"
+    );
+}
+
+#[test]
+fn engine_calls_functions_and_routes_print_to_the_host() {
+    let workspace = main_workspace(
+        "type Point :: struct {\n    x: int64,\n    y: int64,\n}\n\n\
+         function main() -> nil {\n    print(\"started\")\n    return\n}\n\n\
+         function translate(point: Point, offset: int64) -> Point {\n    \
+         print(\"translating\")\n    \
+         return Point { x: point.x + offset, y: point.y + offset }\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    let printed_lines = Rc::new(RefCell::new(Vec::new()));
+    let host_printed_lines = Rc::clone(&printed_lines);
+    engine
+        .register_host_function(
+            "print",
+            HostFunctionSignature::new(vec![RuntimeType::String], RuntimeType::Nil),
+            move |arguments| {
+                host_printed_lines.borrow_mut().push(arguments[0].clone());
+                Ok(RuntimeValue::Nil)
+            },
+        )
+        .expect("print should be replaceable");
+
+    engine.run_main().expect("main should run");
+    let point = |x, y| RuntimeValue::Struct {
+        type_name: "Point".to_string(),
+        fields: vec![
+            ("x".to_string(), RuntimeValue::Int64(x)),
+            ("y".to_string(), RuntimeValue::Int64(y)),
+        ],
+    };
+    let translated = engine
+        .call("translate", &[point(1, 2), RuntimeValue::Int64(10)])
+        .expect("call should succeed");
+    assert_eq!(translated, point(11, 12));
+    assert_eq!(
+        *printed_lines.borrow(),
+        [
+            RuntimeValue::String("started".to_string()),
+            RuntimeValue::String("translating".to_string()),
+        ]
+    );
+
+    let failure = engine
+        .call("missing", &[])
+        .expect_err("unknown functions should fail");
+    assert!(matches!(failure.kind, CompilerFailureKind::RunFailed));
+}
+
+#[test]
+fn engine_type_checks_calls_to_declared_host_functions() {
+    let scale_signature = HostFunctionSignature::new(vec![RuntimeType::Int64], RuntimeType::Int64);
+    let mut load_options = LoadOptions::default();
+    load_options
+        .host_function_signature_by_name
+        .insert("host_scale".to_string(), scale_signature.clone());
+
+    let workspace = main_workspace(
+        "function main() -> nil {\n    return\n}\n\n\
+         function scaled(value: int64) -> int64 {\n    return host_scale(value) + 1\n}\n",
+    );
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+    assert!(
+        engine
+            .register_host_function(
+                "host_scale",
+                HostFunctionSignature::new(Vec::new(), RuntimeType::Int64),
+                |_| Ok(RuntimeValue::Int64(0)),
+            )
+            .is_err()
+    );
+    engine
+        .register_host_function("host_scale", scale_signature, |arguments| match arguments {
+            [RuntimeValue::Int64(value)] => Ok(RuntimeValue::Int64(value * 3)),
+            _ => Err("host_scale expects an int64".to_string()),
+        })
+        .expect("declared host function should register");
+    assert_eq!(
+        engine
+            .call("scaled", &[RuntimeValue::Int64(4)])
+            .expect("call should succeed"),
+        RuntimeValue::Int64(13)
+    );
+
+    let mismatched_workspace = main_workspace(
+        "function main() -> nil {\n    return\n}\n\n\
+         function scaled() -> int64 {\n    return host_scale(\"four\")\n}\n",
+    );
+    load_options.workspace_root = Some(mismatched_workspace.path().display().to_string());
+    let Err(failure) = CoppiceEngine::load(&main_path(&mismatched_workspace), &load_options) else {
+        panic!("mismatched host function call should not load");
+    };
+    assert!(matches!(failure.kind, CompilerFailureKind::BuildFailed));
+    assert_eq!(failure.details.len(), 1);
+}
+
+#[test]
+fn engine_aborts_builtins_denied_by_the_execution_policy() {
+    let workspace =
+        main_workspace("function main() -> nil {\n    print(\"started\")\n    return\n}\n");
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    load_options.execution_policy = ExecutionPolicy::sandboxed();
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    let failure = engine
+        .run_main()
+        .expect_err("print should be denied by the sandbox");
+    assert!(matches!(failure.kind, CompilerFailureKind::RunFailed));
+    assert_eq!(
+        failure.message,
+        RuntimeCapability::StandardOutput.denial_message()
+    );
+}
+
+#[test]
+fn engine_repeats_native_random_ints_and_time_for_a_fixed_seed_and_time() {
+    let workspace = main_workspace(
+        "function draw() -> int64 {\n    return random_int(1, 6)\n}\n\nfunction clock() -> int64 {\n    return now()\n}\n\nfunction main() -> nil {\n    return\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    load_options.random_seed = Some(42);
+    load_options.fixed_now_unix_seconds = Some(1_700_000_000);
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    for expected in [2, 2] {
+        assert_eq!(
+            engine.call("draw", &[]).expect("draw should succeed"),
+            RuntimeValue::Int64(expected)
+        );
+    }
+    assert_eq!(
+        engine.call("clock", &[]).expect("clock should succeed"),
+        RuntimeValue::Int64(1_700_000_000)
+    );
+}
+
+#[test]
+fn engine_captures_printed_output_into_the_output_sink() {
+    let workspace = main_workspace(
+        "function main() -> nil {\n    print(\"first\")\n    print(\"second\")\n    return\n}\n",
+    );
+    let captured_output = Arc::new(Mutex::new(CapturedOutput::default()));
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    load_options.output_sink = Some(SharedOutputSink::new(captured_output.clone()));
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    engine.run_main().expect("main should run");
+    let captured_output = captured_output.lock().expect("sink should not be poisoned");
+    assert_eq!(captured_output.standard_output, b"first\nsecond\n");
+    assert!(captured_output.standard_error.is_empty());
+}
+
+#[test]
+fn engine_reports_the_failed_assertion_condition_and_position() {
+    let workspace = main_workspace(
+        "function check(count: int64) -> nil {\n    assert(count < 3)\n    return\n}\n\nfunction main() -> nil {\n    return\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    engine
+        .call("check", &[RuntimeValue::Int64(2)])
+        .expect("assertion should hold");
+    let failure = engine
+        .call("check", &[RuntimeValue::Int64(3)])
+        .expect_err("assertion should fail");
+    assert_eq!(
+        failure.message,
+        "assertion failed: count < 3 (line 2, column 12)"
+    );
+}
+
+#[test]
+fn engine_keeps_struct_fields_in_declaration_order() {
+    let workspace = main_workspace(
+        "type Place :: struct {\n    zone: int64,\n    alpha: string,\n}\n\nfunction place() -> Place {\n    return Place { alpha: \"a\", zone: 3 }\n}\n\nfunction describe() -> string {\n    return debug_string(place())\n}\n\nfunction main() -> nil {\n    return\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    assert_eq!(
+        engine.call("place", &[]).expect("call should succeed"),
+        RuntimeValue::Struct {
+            type_name: "Place".to_string(),
+            fields: vec![
+                ("zone".to_string(), RuntimeValue::Int64(3)),
+                ("alpha".to_string(), RuntimeValue::String("a".to_string())),
+            ],
+        }
+    );
+    assert_eq!(
+        engine.call("describe", &[]).expect("call should succeed"),
+        RuntimeValue::String("Place { zone: 3, alpha: \"a\" }".to_string())
+    );
+}
+
+#[test]
+fn engine_converts_enum_values_to_and_from_variant_names() {
+    let workspace = main_workspace(
+        "type Direction :: enum {\n    North,\n    South,\n}\n\nfunction parse(name: string) -> Direction | nil {\n    return Direction.from_string(name)\n}\n\nfunction nameOf(direction: Direction) -> string {\n    return variant_name(direction)\n}\n\nfunction main() -> nil {\n    return\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    let south = RuntimeValue::EnumVariant {
+        enum_name: "Direction".to_string(),
+        variant_name: "South".to_string(),
+    };
+    assert_eq!(
+        engine
+            .call("parse", &[RuntimeValue::String("South".to_string())])
+            .expect("call should succeed"),
+        south
+    );
+    assert_eq!(
+        engine
+            .call("parse", &[RuntimeValue::String("south".to_string())])
+            .expect("call should succeed"),
+        RuntimeValue::Nil
+    );
+    assert_eq!(
+        engine
+            .call("nameOf", &[south])
+            .expect("call should succeed"),
+        RuntimeValue::String("South".to_string())
+    );
+}
+
+#[test]
+fn engine_aborts_on_overflow_only_when_the_overflow_mode_asks() {
+    let workspace = main_workspace(
+        "function negate(value: int64) -> int64 {\n    return -value\n}\n\nfunction quotient(left: int64, right: int64) -> int64 {\n    return left / right\n}\n\nfunction main() -> nil {\n    return\n}\n",
+    );
+    let mut load_options = LoadOptions::default();
+    load_options.workspace_root = Some(workspace.path().display().to_string());
+    let mut wrapping_engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+    load_options.overflow_mode = OverflowMode::Abort;
+    let mut aborting_engine =
+        CoppiceEngine::load(&main_path(&workspace), &load_options).expect("program should load");
+
+    let int64 = RuntimeValue::Int64;
+    assert_eq!(
+        wrapping_engine
+            .call("negate", &[int64(i64::MIN)])
+            .expect("overflow should wrap"),
+        int64(i64::MIN)
+    );
+    assert_eq!(
+        wrapping_engine
+            .call("quotient", &[int64(i64::MIN), int64(-1)])
+            .expect("overflow should wrap"),
+        int64(i64::MIN)
+    );
+    let failure = aborting_engine
+        .call("quotient", &[int64(i64::MIN), int64(-1)])
+        .expect_err("overflow should abort");
+    assert_eq!(
+        failure.message,
+        "arithmetic overflow: left / right (line 6, column 12)"
+    );
+    let failure = wrapping_engine
+        .call("quotient", &[int64(1), int64(0)])
+        .expect_err("division by zero should abort");
+    assert_eq!(
+        failure.message,
+        "integer division by zero: left / right (line 6, column 12)"
+    );
+}
@@ -0,0 +1,124 @@
+// What changed between two programs. Functions and types are named as they
+// are written outside their package, such as `units::Meters`, and methods as
+// `Type.method`. Type changes describe how a struct or interface changed shape;
+// changes to method bodies are listed with the functions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProgramDiff {
+    pub function_changes: Vec<FunctionChange>,
+    pub type_changes: Vec<TypeChange>,
+}
+
+impl ProgramDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.function_changes.is_empty() && self.type_changes.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FunctionChange {
+    pub name: String,
+    pub kind: ChangeKind,
+}
+
+// `details` describes each change to a changed type's shape, such as
+// "field 'x' changed from int64 to string", and is empty otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TypeChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub details: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConformanceReport {
+    pub struct_name: String,
+    pub interface_name: String,
+    pub declares_implementation: bool,
+    pub method_problems: Vec<MethodConformanceProblem>,
+}
+
+impl ConformanceReport {
+    #[must_use]
+    pub fn conforms(&self) -> bool {
+        self.method_problems.is_empty()
+    }
+}
+
+// One interface method the struct lacks or declares differently.
+// `found_signature` is None when the struct has no method of that name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MethodConformanceProblem {
+    pub method_name: String,
+    pub expected_signature: String,
+    pub found_signature: Option<String>,
+    pub differences: Vec<String>,
+}
+
+impl From<compiler__executable_program::ProgramDiff> for ProgramDiff {
+    fn from(program_diff: compiler__executable_program::ProgramDiff) -> Self {
+        Self {
+            function_changes: program_diff
+                .function_changes
+                .into_iter()
+                .map(|function_change| FunctionChange {
+                    name: function_change.name,
+                    kind: function_change.kind.into(),
+                })
+                .collect(),
+            type_changes: program_diff
+                .type_changes
+                .into_iter()
+                .map(|type_change| TypeChange {
+                    name: type_change.name,
+                    kind: type_change.kind.into(),
+                    details: type_change.details,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<compiler__executable_program::ChangeKind> for ChangeKind {
+    fn from(kind: compiler__executable_program::ChangeKind) -> Self {
+        use compiler__executable_program::ChangeKind as Internal;
+        match kind {
+            Internal::Added => Self::Added,
+            Internal::Removed => Self::Removed,
+            Internal::Changed => Self::Changed,
+        }
+    }
+}
+
+impl From<compiler__driver::ConformanceReport> for ConformanceReport {
+    fn from(report: compiler__driver::ConformanceReport) -> Self {
+        Self {
+            struct_name: report.struct_name,
+            interface_name: report.interface_name,
+            declares_implementation: report.declares_implementation,
+            method_problems: report
+                .method_problems
+                .into_iter()
+                .map(|method_problem| MethodConformanceProblem {
+                    method_name: method_problem.method_name,
+                    expected_signature: method_problem.expected_signature,
+                    found_signature: method_problem.found_signature,
+                    differences: method_problem.differences,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+// A value crossing the boundary between a host and an interpreted program.
+// Structs carry their fields in declaration order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RuntimeValue {
+    Int64(i64),
+    Boolean(bool),
+    String(String),
+    Nil,
+    List(Vec<RuntimeValue>),
+    Struct {
+        type_name: String,
+        fields: Vec<(String, RuntimeValue)>,
+    },
+    EnumVariant {
+        enum_name: String,
+        variant_name: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RuntimeType {
+    Boolean,
+    Int64,
+    Int64OrNil,
+    Nil,
+    Never,
+    String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HostFunctionSignature {
+    pub parameter_types: Vec<RuntimeType>,
+    pub return_type: RuntimeType,
+}
+
+impl HostFunctionSignature {
+    #[must_use]
+    pub fn new(parameter_types: Vec<RuntimeType>, return_type: RuntimeType) -> Self {
+        Self {
+            parameter_types,
+            return_type,
+        }
+    }
+}
+
+// A process resource that builtins reach outside the program itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum RuntimeCapability {
+    StandardOutput,
+}
+
+impl RuntimeCapability {
+    pub const ALL: &[RuntimeCapability] = &[RuntimeCapability::StandardOutput];
+
+    // Written to stderr when a denied builtin is called, and the message of
+    // the failure when the program runs in-process.
+    #[must_use]
+    pub fn denial_message(self) -> String {
+        compiler__runtime_interface::RuntimeCapability::from(self).denial_message()
+    }
+}
+
+// The default policy permits every capability; the sandboxed profile denies
+// them all.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExecutionPolicy {
+    pub denied_capabilities: BTreeSet<RuntimeCapability>,
+}
+
+impl ExecutionPolicy {
+    #[must_use]
+    pub fn sandboxed() -> Self {
+        Self {
+            denied_capabilities: RuntimeCapability::ALL.iter().copied().collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn permits(&self, capability: RuntimeCapability) -> bool {
+        !self.denied_capabilities.contains(&capability)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverflowMode {
+    #[default]
+    Wrap,
+    Abort,
+}
+
+// Receives what a program writes to its standard streams, so that the output
+// of a run can be captured instead of inheriting the host's.
+pub trait OutputSink: Send {
+    fn write_standard_output(&mut self, bytes: &[u8]);
+    fn write_standard_error(&mut self, bytes: &[u8]);
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CapturedOutput {
+    pub standard_output: Vec<u8>,
+    pub standard_error: Vec<u8>,
+}
+
+impl OutputSink for CapturedOutput {
+    fn write_standard_output(&mut self, bytes: &[u8]) {
+        self.standard_output.extend_from_slice(bytes);
+    }
+
+    fn write_standard_error(&mut self, bytes: &[u8]) {
+        self.standard_error.extend_from_slice(bytes);
+    }
+}
+
+// A sink shared between the caller, who reads it back after the run, and the
+// run writing to it. Handles are equal when they share the same sink.
+#[derive(Clone)]
+pub struct SharedOutputSink(Arc<Mutex<dyn OutputSink>>);
+
+impl SharedOutputSink {
+    #[must_use]
+    pub fn new(output_sink: Arc<Mutex<dyn OutputSink>>) -> Self {
+        Self(output_sink)
+    }
+}
+
+impl fmt::Debug for SharedOutputSink {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("SharedOutputSink")
+    }
+}
+
+impl PartialEq for SharedOutputSink {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedOutputSink {}
+
+// Forwards what the runtime writes to the caller's sink.
+struct ForwardingOutputSink(SharedOutputSink);
+
+impl compiler__runtime_interface::OutputSink for ForwardingOutputSink {
+    fn write_standard_output(&mut self, bytes: &[u8]) {
+        (self.0)
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_standard_output(bytes);
+    }
+
+    fn write_standard_error(&mut self, bytes: &[u8]) {
+        (self.0)
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_standard_error(bytes);
+    }
+}
+
+impl From<SharedOutputSink> for compiler__runtime_interface::SharedOutputSink {
+    fn from(output_sink: SharedOutputSink) -> Self {
+        Self::new(Arc::new(Mutex::new(ForwardingOutputSink(output_sink))))
+    }
+}
+
+impl From<compiler__interpreter::RuntimeValue> for RuntimeValue {
+    fn from(value: compiler__interpreter::RuntimeValue) -> Self {
+        use compiler__interpreter::RuntimeValue as Internal;
+        match value {
+            Internal::Int64(value) => Self::Int64(value),
+            Internal::Boolean(value) => Self::Boolean(value),
+            Internal::String(value) => Self::String(value),
+            Internal::Nil => Self::Nil,
+            Internal::List(elements) => Self::List(elements.into_iter().map(Self::from).collect()),
+            Internal::Struct { type_name, fields } => Self::Struct {
+                type_name,
+                fields: fields
+                    .into_iter()
+                    .map(|(name, value)| (name, Self::from(value)))
+                    .collect(),
+            },
+            Internal::EnumVariant {
+                enum_name,
+                variant_name,
+            } => Self::EnumVariant {
+                enum_name,
+                variant_name,
+            },
+        }
+    }
+}
+
+impl From<RuntimeValue> for compiler__interpreter::RuntimeValue {
+    fn from(value: RuntimeValue) -> Self {
+        match value {
+            RuntimeValue::Int64(value) => Self::Int64(value),
+            RuntimeValue::Boolean(value) => Self::Boolean(value),
+            RuntimeValue::String(value) => Self::String(value),
+            RuntimeValue::Nil => Self::Nil,
+            RuntimeValue::List(elements) => {
+                Self::List(elements.into_iter().map(Self::from).collect())
+            }
+            RuntimeValue::Struct { type_name, fields } => Self::Struct {
+                type_name,
+                fields: fields
+                    .into_iter()
+                    .map(|(name, value)| (name, Self::from(value)))
+                    .collect(),
+            },
+            RuntimeValue::EnumVariant {
+                enum_name,
+                variant_name,
+            } => Self::EnumVariant {
+                enum_name,
+                variant_name,
+            },
+        }
+    }
+}
+
+impl From<RuntimeType> for compiler__runtime_interface::RuntimeType {
+    fn from(runtime_type: RuntimeType) -> Self {
+        match runtime_type {
+            RuntimeType::Boolean => Self::Boolean,
+            RuntimeType::Int64 => Self::Int64,
+            RuntimeType::Int64OrNil => Self::Int64OrNil,
+            RuntimeType::Nil => Self::Nil,
+            RuntimeType::Never => Self::Never,
+            RuntimeType::String => Self::String,
+        }
+    }
+}
+
+impl From<HostFunctionSignature> for compiler__interpreter::HostFunctionSignature {
+    fn from(signature: HostFunctionSignature) -> Self {
+        Self {
+            parameter_types: signature
+                .parameter_types
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            return_type: signature.return_type.into(),
+        }
+    }
+}
+
+impl From<RuntimeCapability> for compiler__runtime_interface::RuntimeCapability {
+    fn from(capability: RuntimeCapability) -> Self {
+        match capability {
+            RuntimeCapability::StandardOutput => Self::StandardOutput,
+        }
+    }
+}
+
+impl From<ExecutionPolicy> for compiler__runtime_interface::ExecutionPolicy {
+    fn from(execution_policy: ExecutionPolicy) -> Self {
+        Self {
+            denied_capabilities: execution_policy
+                .denied_capabilities
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<OverflowMode> for compiler__runtime_interface::OverflowMode {
+    fn from(overflow_mode: OverflowMode) -> Self {
+        match overflow_mode {
+            OverflowMode::Wrap => Self::Wrap,
+            OverflowMode::Abort => Self::Abort,
+        }
+    }
+}
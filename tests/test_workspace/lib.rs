@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static NEXT_WORKSPACE_NUMBER: AtomicUsize = AtomicUsize::new(0);
+
+// A workspace written to a fresh temporary directory, with its workspace
+// marker, and removed when dropped. Directory names combine the process id, a
+// per-process counter, and the time, so that tests running in parallel threads
+// or processes never share a directory.
+pub struct TestWorkspace {
+    root: PathBuf,
+}
+
+impl TestWorkspace {
+    #[must_use]
+    pub fn new(name_prefix: &str, files: &[(&str, &str)]) -> Self {
+        let unique_suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after unix epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!(
+            "{name_prefix}_{}_{}_{unique_suffix}",
+            process::id(),
+            NEXT_WORKSPACE_NUMBER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).expect("workspace root should be created");
+        let workspace = Self { root };
+        workspace.write_file("COPPICE_WORKSPACE", "");
+        for (relative_path, contents) in files {
+            workspace.write_file(relative_path, contents);
+        }
+        workspace
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    #[must_use]
+    pub fn file_path(&self, relative_path: &str) -> String {
+        self.root.join(relative_path).display().to_string()
+    }
+
+    pub fn write_file(&self, relative_path: &str, contents: &str) {
+        let path = self.root.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("parent directory should be created");
+        }
+        fs::write(path, contents).expect("file should be written");
+    }
+}
+
+impl Drop for TestWorkspace {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,244 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use coppice::{
+    CapturedOutput, CoppiceEngine, ExecutionPolicy, LoadOptions, OverflowMode, RunOptions,
+    SharedOutputSink,
+};
+use tests__snapshot_fixture_helpers::collect_snapshot_fixture_case_paths;
+
+// Every `run` fixture is also loaded into the interpreter. Both paths must
+// agree on whether the program is accepted, on its standard output, and on how
+// it finishes, so that native codegen cannot drift from the reference
+// semantics unnoticed.
+#[test]
+fn native_runs_match_interpreted_runs() {
+    let runfiles_directory = runfiles::find_runfiles_dir().unwrap().join("_main");
+
+    let mut case_paths = Vec::new();
+    collect_snapshot_fixture_case_paths(
+        &runfiles_directory.join("unified_tests"),
+        &runfiles_directory,
+        "case.test",
+        &mut case_paths,
+    );
+    case_paths.sort();
+
+    let mut compared_case_count = 0;
+    for case_path in &case_paths {
+        let script_path = runfiles_directory.join(case_path).join("case.test");
+        let script_contents = fs::read_to_string(&script_path)
+            .unwrap_or_else(|error| panic!("failed to read {}: {error}", script_path.display()));
+        for run_arguments in run_command_arguments(&script_contents) {
+            compare_case(&runfiles_directory, case_path, &run_arguments);
+            compared_case_count += 1;
+        }
+    }
+    assert!(compared_case_count > 0, "no run fixture cases found");
+}
+
+#[derive(Debug, Default)]
+struct DifferentialRunConfiguration {
+    target: String,
+    strict: bool,
+    execution_policy: ExecutionPolicy,
+    random_seed: Option<i64>,
+    fixed_now_unix_seconds: Option<i64>,
+    overflow_mode: OverflowMode,
+}
+
+// Failures to build or load are compared by kind only; the build and load
+// paths word their failures differently.
+#[derive(Debug, PartialEq, Eq)]
+enum ProgramOutcome {
+    Rejected,
+    Completed,
+    Aborted(String),
+    Exited(i32),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProgramResult {
+    outcome: ProgramOutcome,
+    standard_output: String,
+}
+
+fn run_command_arguments(script_contents: &str) -> Vec<Vec<String>> {
+    script_contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.strip_prefix('[') {
+            Some(after_open_bracket) => after_open_bracket
+                .split_once(']')
+                .map_or(line, |(_, command_line)| command_line.trim()),
+            None => line,
+        })
+        .filter_map(|command_line| {
+            let mut words = command_line.split_whitespace();
+            (words.next() == Some("run")).then(|| words.map(str::to_string).collect())
+        })
+        .collect()
+}
+
+fn parse_run_configuration(
+    run_arguments: &[String],
+    case_path: &Path,
+) -> DifferentialRunConfiguration {
+    let mut configuration = DifferentialRunConfiguration::default();
+    let mut arguments = run_arguments.iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--strict" => configuration.strict = true,
+            "--sandbox" => configuration.execution_policy = ExecutionPolicy::sandboxed(),
+            "--abort-on-overflow" => configuration.overflow_mode = OverflowMode::Abort,
+            "--random-seed" => {
+                configuration.random_seed = Some(parse_integer_argument(
+                    arguments.next(),
+                    argument,
+                    case_path,
+                ));
+            }
+            "--fixed-now" => {
+                configuration.fixed_now_unix_seconds = Some(parse_integer_argument(
+                    arguments.next(),
+                    argument,
+                    case_path,
+                ));
+            }
+            flag if flag.starts_with("--") => panic!(
+                "run flag '{flag}' in case {} has no differential equivalent",
+                case_path.display()
+            ),
+            target => configuration.target = target.to_string(),
+        }
+    }
+    assert!(
+        !configuration.target.is_empty(),
+        "run command without a target in case {}",
+        case_path.display()
+    );
+    configuration
+}
+
+fn parse_integer_argument(value: Option<&String>, flag: &str, case_path: &Path) -> i64 {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("invalid {flag} value in case {}", case_path.display()))
+}
+
+fn compare_case(runfiles_directory: &Path, case_path: &Path, run_arguments: &[String]) {
+    let configuration = parse_run_configuration(run_arguments, case_path);
+    let working_directory = env::temp_dir().join(format!(
+        "coppice_differential_case_{}_{}",
+        case_path.to_string_lossy().replace(['/', '\\'], "__"),
+        std::process::id()
+    ));
+    if working_directory.exists() {
+        fs::remove_dir_all(&working_directory).unwrap();
+    }
+    let input_directory = working_directory.join("input");
+    copy_directory_tree(
+        &runfiles_directory.join(case_path).join("input"),
+        &input_directory,
+    );
+    let output_directory = working_directory.join("output");
+    fs::create_dir_all(&output_directory).unwrap();
+
+    let target_path = input_directory.join(&configuration.target);
+    let target_path = target_path.to_string_lossy();
+    let workspace_root = input_directory.to_string_lossy().into_owned();
+    let native_result = run_native(
+        &target_path,
+        &workspace_root,
+        &output_directory,
+        &configuration,
+    );
+    let interpreted_result = run_interpreted(&target_path, &workspace_root, &configuration);
+    fs::remove_dir_all(&working_directory).unwrap();
+
+    assert_eq!(
+        native_result,
+        interpreted_result,
+        "native and interpreted runs disagree for case {} (left: native, right: interpreted)",
+        case_path.display()
+    );
+}
+
+fn run_native(
+    target_path: &str,
+    workspace_root: &str,
+    output_directory: &Path,
+    configuration: &DifferentialRunConfiguration,
+) -> ProgramResult {
+    let captured_output = Arc::new(Mutex::new(CapturedOutput::default()));
+    let mut options = RunOptions::default();
+    options.workspace_root = Some(workspace_root.to_string());
+    options.output_directory = Some(output_directory.to_string_lossy().into_owned());
+    options.strict = configuration.strict;
+    options.execution_policy = configuration.execution_policy.clone();
+    options.random_seed = configuration.random_seed;
+    options.fixed_now_unix_seconds = configuration.fixed_now_unix_seconds;
+    options.overflow_mode = configuration.overflow_mode;
+    options.output_sink = Some(SharedOutputSink::new(captured_output.clone()));
+
+    let outcome = match coppice::run(target_path, &options) {
+        Err(_) => ProgramOutcome::Rejected,
+        Ok(output) => match output.abort_message {
+            Some(abort_message) => ProgramOutcome::Aborted(abort_message),
+            None if output.exit_code == 0 => ProgramOutcome::Completed,
+            None => ProgramOutcome::Exited(output.exit_code),
+        },
+    };
+    ProgramResult {
+        outcome,
+        standard_output: captured_standard_output(&captured_output),
+    }
+}
+
+fn run_interpreted(
+    target_path: &str,
+    workspace_root: &str,
+    configuration: &DifferentialRunConfiguration,
+) -> ProgramResult {
+    let captured_output = Arc::new(Mutex::new(CapturedOutput::default()));
+    let mut options = LoadOptions::default();
+    options.workspace_root = Some(workspace_root.to_string());
+    options.strict = configuration.strict;
+    options.execution_policy = configuration.execution_policy.clone();
+    options.random_seed = configuration.random_seed;
+    options.fixed_now_unix_seconds = configuration.fixed_now_unix_seconds;
+    options.overflow_mode = configuration.overflow_mode;
+    options.output_sink = Some(SharedOutputSink::new(captured_output.clone()));
+
+    let outcome = match CoppiceEngine::load(target_path, &options) {
+        Err(_) => ProgramOutcome::Rejected,
+        Ok(mut engine) => match engine.run_main() {
+            Ok(()) => ProgramOutcome::Completed,
+            Err(failure) => ProgramOutcome::Aborted(failure.message),
+        },
+    };
+    ProgramResult {
+        outcome,
+        standard_output: captured_standard_output(&captured_output),
+    }
+}
+
+fn captured_standard_output(captured_output: &Mutex<CapturedOutput>) -> String {
+    String::from_utf8_lossy(&captured_output.lock().unwrap().standard_output).into_owned()
+}
+
+fn copy_directory_tree(source_directory: &Path, destination_directory: &Path) {
+    fs::create_dir_all(destination_directory).unwrap();
+    for entry in fs::read_dir(source_directory).unwrap().flatten() {
+        let source_path = entry.path();
+        let destination_path = destination_directory.join(entry.file_name());
+        if source_path.is_dir() {
+            copy_directory_tree(&source_path, &destination_path);
+        } else if source_path.is_file() {
+            fs::copy(&source_path, &destination_path).unwrap();
+        }
+    }
+}
@@ -311,6 +311,10 @@ enum RunCommand {
     Build,
     Run,
     Fix,
+    Graph,
+    Test,
+    Disassemble,
+    Conformance,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -350,8 +354,12 @@ fn parse_run_command(command_name: &str, case_path: &Path, run_number: usize) ->
         "build" => RunCommand::Build,
         "run" => RunCommand::Run,
         "fix" => RunCommand::Fix,
+        "graph" => RunCommand::Graph,
+        "test" => RunCommand::Test,
+        "disassemble" => RunCommand::Disassemble,
+        "conformance" => RunCommand::Conformance,
         _ => panic!(
-            "unsupported command '{}' in run {} for {}; expected one of: build, run, fix",
+            "unsupported command '{}' in run {} for {}; expected one of: build, run, fix, graph, test, disassemble, conformance",
             command_name,
             run_number,
             case_path.display()
@@ -609,6 +617,23 @@ fn output_keys_for_check(run_command: RunCommand) -> Vec<OutputKey> {
                 format: OutputFormat::None,
             },
         ],
+        RunCommand::Graph
+        | RunCommand::Test
+        | RunCommand::Disassemble
+        | RunCommand::Conformance => vec![
+            OutputKey {
+                kind: OutputKind::Exit,
+                format: OutputFormat::None,
+            },
+            OutputKey {
+                kind: OutputKind::Stdout,
+                format: OutputFormat::None,
+            },
+            OutputKey {
+                kind: OutputKind::Stderr,
+                format: OutputFormat::None,
+            },
+        ],
     }
 }
 
@@ -676,6 +701,23 @@ fn output_keys_for_update(run_command: RunCommand) -> Vec<OutputKey> {
                 format: OutputFormat::None,
             },
         ],
+        RunCommand::Graph
+        | RunCommand::Test
+        | RunCommand::Disassemble
+        | RunCommand::Conformance => vec![
+            OutputKey {
+                kind: OutputKind::Exit,
+                format: OutputFormat::None,
+            },
+            OutputKey {
+                kind: OutputKind::Stdout,
+                format: OutputFormat::None,
+            },
+            OutputKey {
+                kind: OutputKind::Stderr,
+                format: OutputFormat::None,
+            },
+        ],
     }
 }
 